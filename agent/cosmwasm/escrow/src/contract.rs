@@ -0,0 +1,560 @@
+//! Contract entry points.
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    to_json_binary, Api, Attribute, BankMsg, Binary, Deps, DepsMut, Env, IbcMsg, IbcTimeout,
+    MessageInfo, Order, Response, StdResult, Storage,
+};
+use cw_storage_plus::Bound;
+use sha2::{Digest, Sha256};
+
+use crate::ibc::{DEFAULT_PACKET_TIMEOUT_SECS, MAX_PACKET_TIMEOUT_SECS};
+use crate::msg::{
+    EscrowResponse, EscrowsResponse, ExecuteMsg, InstantiateMsg, QueryMsg, SettlePacket,
+};
+use crate::state::{Escrow, EscrowState, IbcSettler, BY_PARTY, ESCROWS, NEXT_ID};
+use crate::{ContractError, MEMO_LEN};
+
+const CONTRACT_NAME: &str = env!("CARGO_PKG_NAME");
+const CONTRACT_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Escrows returned by [`QueryMsg::EscrowsByParty`] unless limited.
+pub const DEFAULT_LIMIT: u32 = 30;
+
+/// Most escrows returned by one [`QueryMsg::EscrowsByParty`].
+pub const MAX_LIMIT: u32 = 100;
+
+/// Domain separator of chain-agnostic escrow IDs.
+const ESCROW_ID_DOMAIN: &[u8] = b"zescrow/escrow-id/v1";
+
+/// Initializes the escrow ID counter.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn instantiate(
+    deps: DepsMut,
+    _env: Env,
+    _info: MessageInfo,
+    _msg: InstantiateMsg,
+) -> Result<Response, ContractError> {
+    cw2::set_contract_version(deps.storage, CONTRACT_NAME, CONTRACT_VERSION)?;
+    NEXT_ID.save(deps.storage, &0)?;
+    Ok(Response::new().add_attribute("action", "instantiate"))
+}
+
+/// Dispatches escrow operations.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn execute(
+    deps: DepsMut,
+    env: Env,
+    info: MessageInfo,
+    msg: ExecuteMsg,
+) -> Result<Response, ContractError> {
+    match msg {
+        ExecuteMsg::Create {
+            recipient,
+            finish_after,
+            cancel_after,
+            has_conditions,
+            memo,
+            settler,
+        } => create(
+            deps,
+            &env,
+            info,
+            recipient,
+            finish_after,
+            cancel_after,
+            has_conditions,
+            memo,
+            settler,
+        ),
+        ExecuteMsg::Finish { id } => finish(deps, env, info, id),
+        ExecuteMsg::Cancel { id } => cancel(deps, env, info, id),
+        ExecuteMsg::SendSettlement {
+            channel,
+            id,
+            proof,
+            timeout_secs,
+        } => send_settlement(env, info, channel, id, proof, timeout_secs),
+    }
+}
+
+/// Answers escrow queries.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn query(deps: Deps, env: Env, msg: QueryMsg) -> StdResult<Binary> {
+    match msg {
+        QueryMsg::Escrow { id } => to_json_binary(&escrow_response(
+            deps.api,
+            &env,
+            id,
+            ESCROWS.load(deps.storage, id)?,
+        )?),
+        QueryMsg::EscrowsByParty {
+            party,
+            start_after,
+            limit,
+        } => {
+            let party = deps.api.addr_validate(&party)?;
+            let limit = limit.unwrap_or(DEFAULT_LIMIT).min(MAX_LIMIT) as usize;
+            let escrows = BY_PARTY
+                .prefix(&party)
+                .keys(
+                    deps.storage,
+                    start_after.map(Bound::exclusive),
+                    None,
+                    Order::Ascending,
+                )
+                .take(limit)
+                .map(|id| {
+                    let id = id?;
+                    escrow_response(deps.api, &env, id, ESCROWS.load(deps.storage, id)?)
+                })
+                .collect::<StdResult<_>>()?;
+            to_json_binary(&EscrowsResponse { escrows })
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn create(
+    deps: DepsMut,
+    env: &Env,
+    info: MessageInfo,
+    recipient: String,
+    finish_after: Option<u64>,
+    cancel_after: Option<u64>,
+    has_conditions: bool,
+    memo: Option<String>,
+    settler: Option<IbcSettler>,
+) -> Result<Response, ContractError> {
+    // Must have at least one resolution path
+    if finish_after.is_none() && cancel_after.is_none() {
+        return Err(ContractError::MustSpecifyPath);
+    }
+    // If both set, enforce ordering
+    if let (Some(finish), Some(cancel)) = (finish_after, cancel_after) {
+        if finish >= cancel {
+            return Err(ContractError::InvalidTimeOrder);
+        }
+    }
+    let funds = match info.funds.as_slice() {
+        [coin] if !coin.amount.is_zero() => coin.clone(),
+        _ => return Err(ContractError::InvalidFunds),
+    };
+    if let Some(memo) = &memo {
+        if memo.len() > MEMO_LEN {
+            return Err(ContractError::MemoTooLong(memo.len()));
+        }
+    }
+    let recipient = deps.api.addr_validate(&recipient)?;
+
+    let id = NEXT_ID.load(deps.storage)?;
+    NEXT_ID.save(deps.storage, &(id + 1))?;
+    let escrow = Escrow {
+        sender: info.sender,
+        recipient,
+        funds,
+        finish_after,
+        cancel_after,
+        has_conditions,
+        memo,
+        settler,
+        state: EscrowState::Funded,
+    };
+    ESCROWS.save(deps.storage, id, &escrow)?;
+    BY_PARTY.save(deps.storage, (&escrow.sender, id), &())?;
+    BY_PARTY.save(deps.storage, (&escrow.recipient, id), &())?;
+
+    event(deps.api, env, "create_escrow", id, &escrow)
+}
+
+/// Releases an escrow:
+/// - If `finish_after` is `Some(t)`, require current height >= t.
+/// - If `finish_after` is `None`, allow immediate release.
+/// - Only callable by `recipient`.
+fn finish(deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let escrow = funded_escrow(deps.as_ref(), id)?;
+    if info.sender != escrow.recipient {
+        return Err(ContractError::Unauthorized);
+    }
+    let (escrow, transfer) = release(deps.storage, &env, id, escrow)?;
+
+    Ok(event(deps.api, &env, "finish_escrow", id, &escrow)?.add_message(transfer))
+}
+
+/// Marks funded escrow `id` finished once `finish_after` is reached,
+/// returning it with the transfer to its recipient.
+pub(crate) fn release(
+    storage: &mut dyn Storage,
+    env: &Env,
+    id: u64,
+    mut escrow: Escrow,
+) -> Result<(Escrow, BankMsg), ContractError> {
+    if let Some(t) = escrow.finish_after {
+        if env.block.height < t {
+            return Err(ContractError::NotReady(t));
+        }
+    }
+    escrow.state = EscrowState::Finished;
+    ESCROWS.save(storage, id, &escrow)?;
+    let transfer = BankMsg::Send {
+        to_address: escrow.recipient.to_string(),
+        amount: vec![escrow.funds.clone()],
+    };
+    Ok((escrow, transfer))
+}
+
+/// Sends a settlement packet for escrow `id` on the other end of
+/// `channel`, from the message's sender.
+fn send_settlement(
+    env: Env,
+    info: MessageInfo,
+    channel: String,
+    id: u64,
+    proof: Option<String>,
+    timeout_secs: Option<u64>,
+) -> Result<Response, ContractError> {
+    let timeout_secs = timeout_secs
+        .unwrap_or(DEFAULT_PACKET_TIMEOUT_SECS)
+        .min(MAX_PACKET_TIMEOUT_SECS);
+    let packet = SettlePacket {
+        id,
+        sender: info.sender.to_string(),
+        proof,
+    };
+
+    Ok(Response::new()
+        .add_attribute("action", "send_settlement")
+        .add_attribute("channel", &channel)
+        .add_attribute("escrow_id", id.to_string())
+        .add_attribute("sender", &info.sender)
+        .add_message(IbcMsg::SendPacket {
+            channel_id: channel,
+            data: to_json_binary(&packet)?,
+            timeout: IbcTimeout::with_timestamp(env.block.time.plus_seconds(timeout_secs)),
+        }))
+}
+
+/// Cancels an escrow:
+/// - Requires `cancel_after` to be `Some(t)`.
+/// - Current height >= t.
+/// - Only callable by the original `sender`.
+fn cancel(deps: DepsMut, env: Env, info: MessageInfo, id: u64) -> Result<Response, ContractError> {
+    let mut escrow = funded_escrow(deps.as_ref(), id)?;
+    if info.sender != escrow.sender {
+        return Err(ContractError::Unauthorized);
+    }
+    let t = escrow.cancel_after.ok_or(ContractError::CancelNotAllowed)?;
+    if env.block.height < t {
+        return Err(ContractError::NotExpired(t));
+    }
+    escrow.state = EscrowState::Cancelled;
+    ESCROWS.save(deps.storage, id, &escrow)?;
+
+    Ok(
+        event(deps.api, &env, "cancel_escrow", id, &escrow)?.add_message(BankMsg::Send {
+            to_address: escrow.sender.to_string(),
+            amount: vec![escrow.funds.clone()],
+        }),
+    )
+}
+
+/// Loads escrow `id`, which must still be funded.
+pub(crate) fn funded_escrow(deps: Deps, id: u64) -> Result<Escrow, ContractError> {
+    let escrow = ESCROWS.load(deps.storage, id)?;
+    match escrow.state {
+        EscrowState::Funded => Ok(escrow),
+        state => Err(ContractError::NotFunded(state)),
+    }
+}
+
+/// Response emitting the `wasm` event clients track escrows by.
+fn event(api: &dyn Api, env: &Env, action: &str, id: u64, escrow: &Escrow) -> StdResult<Response> {
+    Ok(Response::new().add_attributes(attributes(api, env, action, id, escrow)?))
+}
+
+/// Attributes of the `wasm` event clients track escrows by.
+pub(crate) fn attributes(
+    api: &dyn Api,
+    env: &Env,
+    action: &str,
+    id: u64,
+    escrow: &Escrow,
+) -> StdResult<Vec<Attribute>> {
+    Ok(vec![
+        Attribute::new("action", action),
+        Attribute::new("escrow_id", id.to_string()),
+        Attribute::new("uid", hex::encode(escrow_uid(api, env, id, escrow)?)),
+        Attribute::new("sender", &escrow.sender),
+        Attribute::new("recipient", &escrow.recipient),
+        Attribute::new("amount", escrow.funds.amount.to_string()),
+        Attribute::new("denom", &escrow.funds.denom),
+    ])
+}
+
+/// Chain-agnostic ID of escrow `id`, as computed by
+/// `zescrow_core::EscrowId`: the SHA-256 digest of the domain separator and
+/// the digests of the CAIP-2 chain ID (`cosmos:<chain ID>`), the canonical
+/// addresses of this contract and the parties, the escrow ID, and the
+/// denomination, then the amount. The escrow ID and amount are 32-byte
+/// big-endian words.
+pub fn escrow_uid(api: &dyn Api, env: &Env, id: u64, escrow: &Escrow) -> StdResult<[u8; 32]> {
+    let chain = format!("cosmos:{}", env.block.chain_id);
+    let contract = api.addr_canonicalize(env.contract.address.as_str())?;
+    let sender = api.addr_canonicalize(escrow.sender.as_str())?;
+    let recipient = api.addr_canonicalize(escrow.recipient.as_str())?;
+    let mut nonce = [0; 32];
+    nonce[24..].copy_from_slice(&id.to_be_bytes());
+    let mut amount = [0; 32];
+    amount[16..].copy_from_slice(&escrow.funds.amount.u128().to_be_bytes());
+
+    let mut hasher = Sha256::new();
+    hasher.update(ESCROW_ID_DOMAIN);
+    for part in [
+        chain.as_bytes(),
+        contract.as_slice(),
+        sender.as_slice(),
+        recipient.as_slice(),
+        &nonce,
+        escrow.funds.denom.as_bytes(),
+    ] {
+        hasher.update(Sha256::digest(part));
+    }
+    hasher.update(amount);
+    Ok(hasher.finalize().into())
+}
+
+fn escrow_response(api: &dyn Api, env: &Env, id: u64, escrow: Escrow) -> StdResult<EscrowResponse> {
+    Ok(EscrowResponse {
+        id,
+        uid: hex::encode(escrow_uid(api, env, id, &escrow)?),
+        sender: escrow.sender,
+        recipient: escrow.recipient,
+        funds: escrow.funds,
+        finish_after: escrow.finish_after,
+        cancel_after: escrow.cancel_after,
+        has_conditions: escrow.has_conditions,
+        memo: escrow.memo,
+        settler: escrow.settler,
+        state: escrow.state,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{message_info, mock_dependencies, mock_env, MockApi};
+    use cosmwasm_std::{coins, from_json, Addr, CosmosMsg};
+
+    use super::*;
+
+    fn create_msg(
+        recipient: &Addr,
+        finish_after: Option<u64>,
+        cancel_after: Option<u64>,
+    ) -> ExecuteMsg {
+        ExecuteMsg::Create {
+            recipient: recipient.to_string(),
+            finish_after,
+            cancel_after,
+            has_conditions: false,
+            memo: Some("invoice-42".into()),
+            settler: None,
+        }
+    }
+
+    #[test]
+    fn escrow_lifecycle() {
+        let mut deps = mock_dependencies();
+        let api = MockApi::default();
+        let (sender, recipient) = (api.addr_make("sender"), api.addr_make("recipient"));
+        let mut env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&sender, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+
+        let finish_after = env.block.height + 10;
+        let response = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&sender, &coins(500, "uosmo")),
+            create_msg(&recipient, Some(finish_after), None),
+        )
+        .unwrap();
+        assert!(response
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "escrow_id" && attr.value == "0"));
+        let uid = hex::encode(
+            escrow_uid(&api, &env, 0, &ESCROWS.load(&deps.storage, 0).unwrap()).unwrap(),
+        );
+        assert!(response
+            .attributes
+            .iter()
+            .any(|attr| attr.key == "uid" && attr.value == uid));
+
+        let finish = ExecuteMsg::Finish { id: 0 };
+        let early = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&recipient, &[]),
+            finish.clone(),
+        );
+        assert!(matches!(early, Err(ContractError::NotReady(h)) if h == finish_after));
+
+        env.block.height = finish_after;
+        let unauthorized = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&sender, &[]),
+            finish.clone(),
+        );
+        assert!(matches!(unauthorized, Err(ContractError::Unauthorized)));
+        let response = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&recipient, &[]),
+            finish.clone(),
+        )
+        .unwrap();
+        assert_eq!(
+            response.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: coins(500, "uosmo"),
+            })
+        );
+        let again = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&recipient, &[]),
+            finish,
+        );
+        assert!(matches!(
+            again,
+            Err(ContractError::NotFunded(EscrowState::Finished))
+        ));
+
+        let escrows: EscrowsResponse = from_json(
+            query(
+                deps.as_ref(),
+                env,
+                QueryMsg::EscrowsByParty {
+                    party: recipient.to_string(),
+                    start_after: None,
+                    limit: None,
+                },
+            )
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(escrows.escrows.len(), 1);
+        assert_eq!(escrows.escrows[0].state, EscrowState::Finished);
+        assert_eq!(escrows.escrows[0].uid, uid);
+    }
+
+    #[test]
+    fn escrow_uid_vector() {
+        let api = MockApi::default();
+        let mut env = mock_env();
+        env.block.chain_id = "osmosis-1".into();
+        env.contract.address = api.addr_make("contract");
+        let escrow = Escrow {
+            sender: api.addr_make("sender"),
+            recipient: api.addr_make("recipient"),
+            funds: coins(500, "uosmo").remove(0),
+            finish_after: None,
+            cancel_after: Some(10),
+            has_conditions: false,
+            memo: None,
+            settler: None,
+            state: EscrowState::Funded,
+        };
+        // Computed as `zescrow_core::EscrowId` does, over the addresses'
+        // canonical (SHA-256 digest) forms.
+        assert_eq!(
+            hex::encode(escrow_uid(&api, &env, 0, &escrow).unwrap()),
+            "66c47ef1163f51da39d93f8afebc936e46d5fd1d09e58fd08169e4f9ce14b3c3"
+        );
+        env.block.chain_id = "osmo-test-5".into();
+        assert_ne!(
+            hex::encode(escrow_uid(&api, &env, 0, &escrow).unwrap()),
+            "66c47ef1163f51da39d93f8afebc936e46d5fd1d09e58fd08169e4f9ce14b3c3"
+        );
+    }
+
+    #[test]
+    fn cancel_requires_expiry() {
+        let mut deps = mock_dependencies();
+        let api = MockApi::default();
+        let (sender, recipient) = (api.addr_make("sender"), api.addr_make("recipient"));
+        let mut env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&sender, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        let cancel_after = env.block.height + 5;
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&sender, &coins(7, "uosmo")),
+            create_msg(&recipient, None, Some(cancel_after)),
+        )
+        .unwrap();
+
+        let cancel = ExecuteMsg::Cancel { id: 0 };
+        let early = execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&sender, &[]),
+            cancel.clone(),
+        );
+        assert!(matches!(early, Err(ContractError::NotExpired(h)) if h == cancel_after));
+        env.block.height = cancel_after;
+        execute(deps.as_mut(), env, message_info(&sender, &[]), cancel).unwrap();
+    }
+
+    #[test]
+    fn create_guards() {
+        let mut deps = mock_dependencies();
+        let api = MockApi::default();
+        let (sender, recipient) = (api.addr_make("sender"), api.addr_make("recipient"));
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&sender, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        let funded = message_info(&sender, &coins(1, "uosmo"));
+
+        let no_path = execute(
+            deps.as_mut(),
+            env.clone(),
+            funded.clone(),
+            create_msg(&recipient, None, None),
+        );
+        assert!(matches!(no_path, Err(ContractError::MustSpecifyPath)));
+        let order = execute(
+            deps.as_mut(),
+            env.clone(),
+            funded,
+            create_msg(&recipient, Some(9), Some(9)),
+        );
+        assert!(matches!(order, Err(ContractError::InvalidTimeOrder)));
+        let unfunded = execute(
+            deps.as_mut(),
+            env,
+            message_info(&sender, &[]),
+            create_msg(&recipient, Some(1), None),
+        );
+        assert!(matches!(unfunded, Err(ContractError::InvalidFunds)));
+    }
+}
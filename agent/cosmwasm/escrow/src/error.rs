@@ -0,0 +1,59 @@
+//! Contract errors.
+
+use cosmwasm_std::StdError;
+use thiserror::Error;
+
+use crate::state::EscrowState;
+
+/// Errors the escrow contract fails with.
+#[derive(Error, Debug)]
+pub enum ContractError {
+    /// Storage, serialization, or address validation error.
+    #[error("{0}")]
+    Std(#[from] StdError),
+
+    /// Neither `finish_after` nor `cancel_after` is set.
+    #[error("must specify at least one of finish_after or cancel_after")]
+    MustSpecifyPath,
+
+    /// `finish_after` is not before `cancel_after`.
+    #[error("finish_after must be before cancel_after")]
+    InvalidTimeOrder,
+
+    /// Not exactly one non-zero coin was sent with `Create`.
+    #[error("send exactly one non-zero coin to escrow")]
+    InvalidFunds,
+
+    /// The memo is longer than [`MEMO_LEN`](crate::MEMO_LEN) bytes.
+    #[error("memo is {0} bytes, at most {max} allowed", max = crate::MEMO_LEN)]
+    MemoTooLong(usize),
+
+    /// The message was not sent by the party allowed to send it.
+    #[error("unauthorized")]
+    Unauthorized,
+
+    /// The escrow was already finished or cancelled.
+    #[error("escrow is {0:?}, not funded")]
+    NotFunded(EscrowState),
+
+    /// `finish_after` has not been reached.
+    #[error("escrow cannot be finished before block {0}")]
+    NotReady(u64),
+
+    /// The escrow has no `cancel_after`.
+    #[error("escrow cannot be cancelled")]
+    CancelNotAllowed,
+
+    /// `cancel_after` has not been reached.
+    #[error("escrow cannot be cancelled before block {0}")]
+    NotExpired(u64),
+
+    /// An IBC channel was opened with an ordering other than unordered.
+    #[error("only unordered IBC channels are supported")]
+    InvalidIbcOrder,
+
+    /// An IBC channel was opened with another version than
+    /// [`IBC_VERSION`](crate::ibc::IBC_VERSION).
+    #[error("IBC channel version must be {expected}, got {0}", expected = crate::ibc::IBC_VERSION)]
+    InvalidIbcVersion(String),
+}
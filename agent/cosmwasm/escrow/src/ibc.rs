@@ -0,0 +1,274 @@
+//! IBC entry points: escrow contracts on two chains open a channel to each
+//! other, over which one sends [`SettlePacket`]s finishing escrows on the
+//! other.
+//!
+//! A packet finishes escrow `id` on the receiving chain only if the escrow
+//! was created with an [`IbcSettler`](crate::state::IbcSettler) naming the
+//! channel it arrived on and the account that sent it. The outcome is
+//! acknowledged with a [`SettleAck`], which never aborts the packet, so a
+//! refused settlement is still acknowledged to the sender.
+
+#[cfg(not(feature = "library"))]
+use cosmwasm_std::entry_point;
+use cosmwasm_std::{
+    from_json, to_json_binary, Binary, DepsMut, Env, Ibc3ChannelOpenResponse, IbcBasicResponse,
+    IbcChannel, IbcChannelCloseMsg, IbcChannelConnectMsg, IbcChannelOpenMsg,
+    IbcChannelOpenResponse, IbcOrder, IbcPacket, IbcPacketAckMsg, IbcPacketReceiveMsg,
+    IbcPacketTimeoutMsg, IbcReceiveResponse, Never,
+};
+
+use crate::contract::{attributes, funded_escrow, release};
+use crate::msg::{SettleAck, SettlePacket};
+use crate::ContractError;
+
+/// Version of the channels between escrow contracts.
+pub const IBC_VERSION: &str = "zescrow-settle-1";
+
+/// Seconds until a settlement packet times out unless configured.
+pub const DEFAULT_PACKET_TIMEOUT_SECS: u64 = 600;
+
+/// Most seconds a settlement packet may stay pending before it times out.
+pub const MAX_PACKET_TIMEOUT_SECS: u64 = 24 * 60 * 60;
+
+/// Accepts unordered channels of [`IBC_VERSION`].
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_open(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelOpenMsg,
+) -> Result<IbcChannelOpenResponse, ContractError> {
+    check_channel(msg.channel(), msg.counterparty_version())?;
+    Ok(Some(Ibc3ChannelOpenResponse {
+        version: IBC_VERSION.into(),
+    }))
+}
+
+/// Records the opened channel in the response.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_connect(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelConnectMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let channel = msg.channel();
+    check_channel(channel, msg.counterparty_version())?;
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_connect")
+        .add_attribute("channel", &channel.endpoint.channel_id))
+}
+
+/// Lets channels close; escrows naming them can still be finished by their
+/// recipients.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_channel_close(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcChannelCloseMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "ibc_close")
+        .add_attribute("channel", &msg.channel().endpoint.channel_id))
+}
+
+/// Finishes the escrow a [`SettlePacket`] names, acknowledging the outcome.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_receive(
+    deps: DepsMut,
+    env: Env,
+    msg: IbcPacketReceiveMsg,
+) -> Result<IbcReceiveResponse, Never> {
+    Ok(settle(deps, &env, &msg.packet).unwrap_or_else(|e| {
+        IbcReceiveResponse::new(ack(&SettleAck::Error(e.to_string())))
+            .add_attribute("action", "receive_settlement")
+            .add_attribute("error", e.to_string())
+    }))
+}
+
+/// Records the acknowledgement of a settlement this contract sent.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_ack(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketAckMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let packet: SettlePacket = from_json(&msg.original_packet.data)?;
+    let response = IbcBasicResponse::new()
+        .add_attribute("action", "settlement_ack")
+        .add_attribute("escrow_id", packet.id.to_string());
+    Ok(match from_json(&msg.acknowledgement.data)? {
+        SettleAck::Result(_) => response.add_attribute("success", "true"),
+        SettleAck::Error(error) => response
+            .add_attribute("success", "false")
+            .add_attribute("error", error),
+    })
+}
+
+/// Records that a settlement this contract sent timed out.
+#[cfg_attr(not(feature = "library"), entry_point)]
+pub fn ibc_packet_timeout(
+    _deps: DepsMut,
+    _env: Env,
+    msg: IbcPacketTimeoutMsg,
+) -> Result<IbcBasicResponse, ContractError> {
+    let packet: SettlePacket = from_json(&msg.packet.data)?;
+    Ok(IbcBasicResponse::new()
+        .add_attribute("action", "settlement_timeout")
+        .add_attribute("escrow_id", packet.id.to_string()))
+}
+
+/// Checks that `channel` is unordered and of [`IBC_VERSION`] on both ends.
+fn check_channel(
+    channel: &IbcChannel,
+    counterparty_version: Option<&str>,
+) -> Result<(), ContractError> {
+    if channel.order != IbcOrder::Unordered {
+        return Err(ContractError::InvalidIbcOrder);
+    }
+    // The version is left empty when a channel is opened, for the contract
+    // to choose.
+    let version = Some(channel.version.as_str()).filter(|version| !version.is_empty());
+    for version in version.into_iter().chain(counterparty_version) {
+        if version != IBC_VERSION {
+            return Err(ContractError::InvalidIbcVersion(version.to_owned()));
+        }
+    }
+    Ok(())
+}
+
+/// Finishes the escrow `packet` names, if its settler sent the packet over
+/// the settler's channel.
+fn settle(
+    deps: DepsMut,
+    env: &Env,
+    packet: &IbcPacket,
+) -> Result<IbcReceiveResponse, ContractError> {
+    let SettlePacket { id, sender, proof } = from_json(&packet.data)?;
+    let escrow = funded_escrow(deps.as_ref(), id)?;
+    let authorized = escrow.settler.as_ref().is_some_and(|settler| {
+        settler.channel == packet.dest.channel_id && settler.sender == sender
+    });
+    if !authorized {
+        return Err(ContractError::Unauthorized);
+    }
+    let (escrow, transfer) = release(deps.storage, env, id, escrow)?;
+
+    let response = IbcReceiveResponse::new(ack(&SettleAck::Result(Binary::default())))
+        .add_attributes(attributes(deps.api, env, "finish_escrow", id, &escrow)?)
+        .add_attribute("channel", &packet.dest.channel_id)
+        .add_message(transfer);
+    Ok(match proof {
+        Some(proof) => response.add_attribute("proof", proof),
+        None => response,
+    })
+}
+
+/// Encodes an acknowledgement.
+fn ack(ack: &SettleAck) -> Binary {
+    to_json_binary(ack).expect("acknowledgements serialize")
+}
+
+#[cfg(test)]
+mod tests {
+    use cosmwasm_std::testing::{
+        message_info, mock_dependencies, mock_env, mock_ibc_channel_open_init,
+        mock_ibc_packet_recv, MockApi,
+    };
+    use cosmwasm_std::{coins, BankMsg, CosmosMsg};
+
+    use super::*;
+    use crate::contract::{execute, instantiate, query};
+    use crate::msg::{EscrowResponse, ExecuteMsg, InstantiateMsg, QueryMsg};
+    use crate::state::{EscrowState, IbcSettler};
+
+    const CHANNEL: &str = "channel-7";
+    const SETTLER: &str = "cosmos1settler";
+
+    fn receive(deps: DepsMut, channel: &str, sender: &str) -> SettleAck {
+        let packet = SettlePacket {
+            id: 0,
+            sender: sender.into(),
+            proof: Some("proof-digest".into()),
+        };
+        let msg = mock_ibc_packet_recv(channel, &packet).unwrap();
+        let response = ibc_packet_receive(deps, mock_env(), msg).unwrap();
+        from_json(response.acknowledgement.unwrap()).unwrap()
+    }
+
+    #[test]
+    fn settlement_packet_finishes_escrow() {
+        let mut deps = mock_dependencies();
+        let api = MockApi::default();
+        let (sender, recipient) = (api.addr_make("sender"), api.addr_make("recipient"));
+        let env = mock_env();
+        instantiate(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&sender, &[]),
+            InstantiateMsg {},
+        )
+        .unwrap();
+        execute(
+            deps.as_mut(),
+            env.clone(),
+            message_info(&sender, &coins(500, "uosmo")),
+            ExecuteMsg::Create {
+                recipient: recipient.to_string(),
+                finish_after: None,
+                cancel_after: Some(env.block.height + 10),
+                has_conditions: true,
+                memo: None,
+                settler: Some(IbcSettler {
+                    channel: CHANNEL.into(),
+                    sender: SETTLER.into(),
+                }),
+            },
+        )
+        .unwrap();
+
+        // Refused settlements are acknowledged with an error.
+        let ack = receive(deps.as_mut(), CHANNEL, "cosmos1mallory");
+        assert!(matches!(ack, SettleAck::Error(_)));
+        let ack = receive(deps.as_mut(), "channel-8", SETTLER);
+        assert!(matches!(ack, SettleAck::Error(_)));
+
+        let packet = SettlePacket {
+            id: 0,
+            sender: SETTLER.into(),
+            proof: None,
+        };
+        let msg = mock_ibc_packet_recv(CHANNEL, &packet).unwrap();
+        let response = ibc_packet_receive(deps.as_mut(), env.clone(), msg).unwrap();
+        let ack: SettleAck = from_json(response.acknowledgement.unwrap()).unwrap();
+        assert!(matches!(ack, SettleAck::Result(_)));
+        assert_eq!(
+            response.messages[0].msg,
+            CosmosMsg::Bank(BankMsg::Send {
+                to_address: recipient.to_string(),
+                amount: coins(500, "uosmo"),
+            })
+        );
+        let escrow: EscrowResponse =
+            from_json(query(deps.as_ref(), env, QueryMsg::Escrow { id: 0 }).unwrap()).unwrap();
+        assert_eq!(escrow.state, EscrowState::Finished);
+
+        let ack = receive(deps.as_mut(), CHANNEL, SETTLER);
+        assert!(matches!(ack, SettleAck::Error(e) if e.contains("Finished")));
+    }
+
+    #[test]
+    fn channel_handshake_checks() {
+        let mut deps = mock_dependencies();
+        let open = mock_ibc_channel_open_init(CHANNEL, IbcOrder::Unordered, IBC_VERSION);
+        assert!(ibc_channel_open(deps.as_mut(), mock_env(), open).is_ok());
+        let ordered = mock_ibc_channel_open_init(CHANNEL, IbcOrder::Ordered, IBC_VERSION);
+        assert!(matches!(
+            ibc_channel_open(deps.as_mut(), mock_env(), ordered),
+            Err(ContractError::InvalidIbcOrder)
+        ));
+        let other = mock_ibc_channel_open_init(CHANNEL, IbcOrder::Unordered, "ics20-1");
+        assert!(matches!(
+            ibc_channel_open(deps.as_mut(), mock_env(), other),
+            Err(ContractError::InvalidIbcVersion(_))
+        ));
+    }
+}
@@ -0,0 +1,33 @@
+//! Escrow contract for CosmWasm chains with XRPL-style time-lock semantics.
+//!
+//! Mirrors the Solana escrow program: a sender locks one coin for a
+//! recipient, who can finish the escrow once `finish_after` is reached,
+//! while the sender can cancel it once `cancel_after` is. Timelocks are
+//! block heights. Escrows are assigned sequential IDs and kept after they
+//! are settled, with their state recorded. Events and queries also carry
+//! each escrow's chain-agnostic ID (see [`contract::escrow_uid`]).
+//!
+//! Conditions are proven off-chain: the client checks the proof before the
+//! recipient finishes, as on Solana.
+//!
+//! Escrows can also be finished by a settlement packet from another IBC
+//! chain: an escrow created with a [`IbcSettler`](state::IbcSettler) is
+//! released when the escrow contract on the other end of the settler's
+//! channel relays a [`SettlePacket`](msg::SettlePacket) sent by the
+//! settler, e.g. once the escrow's conditions were proven there (see
+//! [`ibc`]).
+//!
+//! Build with the `library` feature to use the message types without the
+//! contract entry points, as the Zescrow client does.
+
+pub mod contract;
+pub mod error;
+pub mod ibc;
+pub mod msg;
+pub mod state;
+
+pub use error::ContractError;
+
+/// Maximum length in bytes of the optional escrow memo (e.g., an invoice ID
+/// or URI hash).
+pub const MEMO_LEN: usize = 64;
@@ -0,0 +1,147 @@
+//! Messages the escrow contract is instantiated, executed, and queried
+//! with.
+
+use cosmwasm_schema::{cw_serde, QueryResponses};
+use cosmwasm_std::{Addr, Binary, Coin};
+
+use crate::state::{EscrowState, IbcSettler};
+
+/// Instantiates the contract; it takes no configuration.
+#[cw_serde]
+pub struct InstantiateMsg {}
+
+/// Escrow operations.
+#[cw_serde]
+pub enum ExecuteMsg {
+    /// Locks the single coin sent with the message for `recipient`,
+    /// enforcing XRPL-style guards:
+    /// - At least one of `finish_after` or `cancel_after` must be set.
+    /// - If both are set, `finish_after < cancel_after`.
+    Create {
+        /// Beneficiary of the escrowed funds.
+        recipient: String,
+        /// Optional block height after which the recipient can finish.
+        finish_after: Option<u64>,
+        /// Optional block height after which the sender can cancel.
+        cancel_after: Option<u64>,
+        /// Whether finishing requires proof that the escrow's conditions
+        /// are met, which is verified off-chain by the client.
+        #[serde(default)]
+        has_conditions: bool,
+        /// Optional memo correlating the escrow with off-chain records, at
+        /// most [`MEMO_LEN`](crate::MEMO_LEN) bytes.
+        #[serde(default)]
+        memo: Option<String>,
+        /// Optional account on another IBC chain whose settlement packets
+        /// may finish the escrow, as the recipient can.
+        #[serde(default)]
+        settler: Option<IbcSettler>,
+    },
+    /// Releases escrow `id` to its recipient, who must send the message.
+    Finish {
+        /// Escrow ID assigned at creation.
+        id: u64,
+    },
+    /// Sends a [`SettlePacket`] over IBC `channel`, asking the escrow
+    /// contract on the other end to finish its escrow `id`, for which the
+    /// message's sender is the [`IbcSettler`].
+    SendSettlement {
+        /// Channel on this chain to the other escrow contract.
+        channel: String,
+        /// ID of the escrow on the other chain.
+        id: u64,
+        /// Reference to the fulfillment or proof the settlement rests on,
+        /// recorded with the settlement.
+        #[serde(default)]
+        proof: Option<String>,
+        /// Seconds until the packet times out, at most
+        /// [`MAX_PACKET_TIMEOUT_SECS`](crate::ibc::MAX_PACKET_TIMEOUT_SECS)
+        /// [default:
+        /// [`DEFAULT_PACKET_TIMEOUT_SECS`](crate::ibc::DEFAULT_PACKET_TIMEOUT_SECS)].
+        #[serde(default)]
+        timeout_secs: Option<u64>,
+    },
+    /// Refunds escrow `id` to its sender, who must send the message.
+    Cancel {
+        /// Escrow ID assigned at creation.
+        id: u64,
+    },
+}
+
+/// Escrow queries.
+#[cw_serde]
+#[derive(QueryResponses)]
+pub enum QueryMsg {
+    /// Returns escrow `id`.
+    #[returns(EscrowResponse)]
+    Escrow {
+        /// Escrow ID assigned at creation.
+        id: u64,
+    },
+    /// Returns the escrows `party` sends or receives, in ID order.
+    #[returns(EscrowsResponse)]
+    EscrowsByParty {
+        /// Sender or recipient address.
+        party: String,
+        /// Only return escrows with IDs above this one, to page through.
+        start_after: Option<u64>,
+        /// Maximum number of escrows to return, at most
+        /// [`MAX_LIMIT`](crate::contract::MAX_LIMIT).
+        limit: Option<u32>,
+    },
+}
+
+/// An escrow and its lifecycle state.
+#[cw_serde]
+pub struct EscrowResponse {
+    /// Escrow ID assigned at creation.
+    pub id: u64,
+    /// Chain-agnostic escrow ID, as hex.
+    pub uid: String,
+    /// Account that funded the escrow.
+    pub sender: Addr,
+    /// Beneficiary of the escrowed funds.
+    pub recipient: Addr,
+    /// Escrowed coin.
+    pub funds: Coin,
+    /// Optional block height after which the recipient can finish.
+    pub finish_after: Option<u64>,
+    /// Optional block height after which the sender can cancel.
+    pub cancel_after: Option<u64>,
+    /// Whether finishing requires proof of the escrow's conditions.
+    pub has_conditions: bool,
+    /// Optional memo.
+    pub memo: Option<String>,
+    /// Optional IBC settler that may finish the escrow.
+    pub settler: Option<IbcSettler>,
+    /// Whether the escrow is funded, finished, or cancelled.
+    pub state: EscrowState,
+}
+
+/// Escrows returned by [`QueryMsg::EscrowsByParty`].
+#[cw_serde]
+pub struct EscrowsResponse {
+    /// Escrows in ID order.
+    pub escrows: Vec<EscrowResponse>,
+}
+
+/// Data of an IBC packet asking the receiving escrow contract to finish
+/// escrow `id`.
+#[cw_serde]
+pub struct SettlePacket {
+    /// Escrow ID on the receiving chain.
+    pub id: u64,
+    /// Account that sent the settlement on the sending chain.
+    pub sender: String,
+    /// Reference to the fulfillment or proof the settlement rests on.
+    pub proof: Option<String>,
+}
+
+/// Acknowledgement of a [`SettlePacket`], in the standard ICS-4 envelope.
+#[cw_serde]
+pub enum SettleAck {
+    /// The escrow was finished.
+    Result(Binary),
+    /// The escrow could not be finished, and why.
+    Error(String),
+}
@@ -0,0 +1,62 @@
+//! Contract storage.
+
+use cosmwasm_schema::cw_serde;
+use cosmwasm_std::{Addr, Coin};
+use cw_storage_plus::{Item, Map};
+
+/// Lifecycle state of an escrow. Settled escrows are kept, so they can
+/// still be queried.
+#[cw_serde]
+#[derive(Copy, Eq)]
+pub enum EscrowState {
+    /// Funds are locked.
+    Funded,
+    /// Funds were released to the recipient.
+    Finished,
+    /// Funds were refunded to the sender.
+    Cancelled,
+}
+
+/// Account on the counterparty chain of an IBC channel whose settlement
+/// packets may finish an escrow in place of its recipient.
+#[cw_serde]
+pub struct IbcSettler {
+    /// Channel on this chain the packets arrive on, which must connect to
+    /// an escrow contract trusted to report the packets' sender.
+    pub channel: String,
+    /// Address of the account that sends the packets on the counterparty
+    /// chain.
+    pub sender: String,
+}
+
+/// Escrow data, keyed by escrow ID.
+#[cw_serde]
+pub struct Escrow {
+    /// Account that funded the escrow.
+    pub sender: Addr,
+    /// Beneficiary of the escrowed funds.
+    pub recipient: Addr,
+    /// Escrowed coin.
+    pub funds: Coin,
+    /// Optional block height after which funds can be released.
+    pub finish_after: Option<u64>,
+    /// Optional block height after which the sender can reclaim funds.
+    pub cancel_after: Option<u64>,
+    /// Whether finishing requires proof of the escrow's conditions.
+    pub has_conditions: bool,
+    /// Optional memo for correlating the escrow with off-chain records.
+    pub memo: Option<String>,
+    /// Optional IBC settler that may finish the escrow.
+    pub settler: Option<IbcSettler>,
+    /// Lifecycle state.
+    pub state: EscrowState,
+}
+
+/// ID the next escrow is assigned.
+pub const NEXT_ID: Item<u64> = Item::new("next_id");
+
+/// Escrows by ID.
+pub const ESCROWS: Map<u64, Escrow> = Map::new("escrows");
+
+/// IDs of the escrows each address sends or receives.
+pub const BY_PARTY: Map<(&Addr, u64), ()> = Map::new("by_party");
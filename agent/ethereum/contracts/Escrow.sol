@@ -1,11 +1,15 @@
 // SPDX-License-Identifier: MIT
 pragma solidity ^0.8.28;
 
+import "@openzeppelin/contracts/utils/Multicall.sol";
 import "@openzeppelin/contracts/utils/ReentrancyGuard.sol";
+import "@openzeppelin/contracts/utils/Strings.sol";
+import "./IRiscZeroVerifier.sol";
 
 /// @title Zescrow Escrow Manager
 /// @notice Holds funds until a time-lock expires or explicit cancellation
-contract Escrow is ReentrancyGuard {
+/// @dev `multicall` batches finish/cancel calls while preserving `msg.sender`
+contract Escrow is ReentrancyGuard, Multicall {
     /// @dev Represents a single escrow's state
     struct EscrowDB {
         address sender; // depositor
@@ -14,8 +18,41 @@ contract Escrow is ReentrancyGuard {
         uint256 finishAfter; // unlock block (0 = immediate)
         uint256 cancelAfter; // refund block (0 = disabled)
         bool settled; // prevents reuse
+        bool hasConditions; // finish requires a RISC Zero proof
+        bytes32 fingerprint; // escrow fingerprint proofs must commit to
+        bytes32 conditionFingerprint; // condition proofs must fulfil
     }
 
+    /// @dev Bincode encoding of `ExecutionResult::Ok(ExecutionState::ConditionsMet)`,
+    /// the result committed by the guest when all conditions are fulfilled
+    bytes2 private constant CONDITIONS_MET = hex"0002";
+
+    /// @dev Length of the freshness prefixed to seals: a 32-byte nonce and
+    /// an 8-byte big-endian expiry block
+    uint256 private constant FRESHNESS_LEN = 40;
+
+    /// @dev Length of the chain state attested by the host: an 8-byte
+    /// big-endian block number and the block hash, or zeros if none
+    uint256 private constant CHAIN_STATE_LEN = 40;
+
+    /// @dev Offset of the 32-byte fingerprint of the condition proven in
+    /// seals, after the freshness and the chain state
+    uint256 private constant CONDITION_OFFSET = FRESHNESS_LEN + CHAIN_STATE_LEN;
+
+    /// @dev Length of the prefix of seals: the freshness, the chain state,
+    /// then the condition fingerprint
+    uint256 private constant SEAL_PREFIX_LEN = CONDITION_OFFSET + 32;
+
+    /// @dev Domain separator of chain-agnostic escrow IDs
+    bytes private constant ESCROW_ID_DOMAIN = "zescrow/escrow-id/v1";
+
+    /// @notice RISC Zero verifier (router) checking condition proofs;
+    /// zero disables conditional escrows
+    IRiscZeroVerifier public immutable verifier;
+
+    /// @notice Image ID of the Zescrow guest program
+    bytes32 public immutable imageId;
+
     /// @dev Auto-incrementing escrow ID; we start at 1 on creation
     uint256 private _nextEscrowId = 0;
 
@@ -34,6 +71,13 @@ contract Escrow is ReentrancyGuard {
     error TooEarlyToCancel(); // block.number < cancelAfter
     error CancelDisabled(); // cancelAfter == 0
     error TransferFailed(); // low-level payable call (transfer) returned false
+    error ConditionsUnsupported(); // no verifier configured
+    error ProofRequired(); // conditional escrow finished without a proof
+    error NoConditions(); // proof supplied for an unconditional escrow
+    error MalformedSeal(); // seal shorter than its prefix
+    error ProofExpired(); // block.number > proof expiry
+    error StaleChainState(); // attested block is not among the last 256 or its hash differs
+    error ConditionMismatch(); // proof is for another condition than the escrow's
 
     event EscrowCreated(
         uint256 indexed escrowId,
@@ -41,7 +85,8 @@ contract Escrow is ReentrancyGuard {
         address indexed recipient,
         uint256 amount,
         uint256 finishAfter,
-        uint256 cancelAfter
+        uint256 cancelAfter,
+        bytes32 uid
     );
     event EscrowFinished(
         uint256 indexed escrowId,
@@ -54,6 +99,14 @@ contract Escrow is ReentrancyGuard {
         uint256 amount
     );
 
+    /// @param verifier_ RISC Zero verifier (router) address, or zero to
+    /// disable conditional escrows
+    /// @param imageId_ Image ID of the Zescrow guest program
+    constructor(IRiscZeroVerifier verifier_, bytes32 imageId_) {
+        verifier = verifier_;
+        imageId = imageId_;
+    }
+
     /// @notice Create a new escrow
     /// - Must set at least one of `finishAfter` or `cancelAfter`
     /// - If both set, `finishAfter < cancelAfter`
@@ -66,6 +119,49 @@ contract Escrow is ReentrancyGuard {
         uint256 finishAfter,
         uint256 cancelAfter
     ) external payable returns (uint256 escrowId) {
+        return _createEscrow(recipient, finishAfter, cancelAfter, false, 0, 0);
+    }
+
+    /// @notice Create a new escrow that can only be finished with a RISC Zero
+    /// proof that its cryptographic conditions are fulfilled
+    /// @dev Same requirements as `createEscrow`. The escrow fingerprint covers
+    /// the escrow ID, so it is computed for `nextEscrowId()`; an escrow created
+    /// with another ID cannot be finished, only cancelled.
+    /// @param recipient The address to receive funds upon release
+    /// @param finishAfter Absolute block number after which finish/release is allowed
+    /// @param cancelAfter Absolute block number after which cancel/refund is allowed
+    /// @param fingerprint Fingerprint of the escrow (`EscrowMetadata::fingerprint`)
+    /// proofs must commit to
+    /// @param conditionFingerprint Fingerprint of the condition
+    /// (`Condition::fingerprint`) proofs must show fulfilled
+    /// @return escrowId A unique identifier for the new escrow
+    function createConditionalEscrow(
+        address recipient,
+        uint256 finishAfter,
+        uint256 cancelAfter,
+        bytes32 fingerprint,
+        bytes32 conditionFingerprint
+    ) external payable returns (uint256 escrowId) {
+        if (address(verifier) == address(0)) revert ConditionsUnsupported();
+        return
+            _createEscrow(
+                recipient,
+                finishAfter,
+                cancelAfter,
+                true,
+                fingerprint,
+                conditionFingerprint
+            );
+    }
+
+    function _createEscrow(
+        address recipient,
+        uint256 finishAfter,
+        uint256 cancelAfter,
+        bool hasConditions,
+        bytes32 fingerprint,
+        bytes32 conditionFingerprint
+    ) private returns (uint256 escrowId) {
         if (recipient == address(0)) revert InvalidRecipient();
         if (msg.value == 0) revert InsufficientValue();
         if (finishAfter == 0 && cancelAfter == 0) revert TimeLockUnset();
@@ -80,7 +176,10 @@ contract Escrow is ReentrancyGuard {
             amount: msg.value,
             finishAfter: finishAfter,
             cancelAfter: cancelAfter,
-            settled: false
+            settled: false,
+            hasConditions: hasConditions,
+            fingerprint: fingerprint,
+            conditionFingerprint: conditionFingerprint
         });
 
         emit EscrowCreated(
@@ -89,13 +188,114 @@ contract Escrow is ReentrancyGuard {
             recipient,
             msg.value,
             finishAfter,
-            cancelAfter
+            cancelAfter,
+            escrowUid(escrowId, msg.sender, recipient, msg.value)
+        );
+    }
+
+    /// @notice Chain-agnostic ID of an escrow of this contract, as computed by
+    /// `zescrow_core::EscrowId`: the SHA-256 digest of the domain separator and
+    /// the digests of the CAIP-2 chain ID (`eip155:<chain ID>`), this contract,
+    /// the parties, the escrow ID, and the (empty) native asset, then the amount
+    /// @param escrowId The ID of the escrow
+    /// @param sender The depositor
+    /// @param recipient The beneficiary
+    /// @param amount The escrowed amount
+    function escrowUid(
+        uint256 escrowId,
+        address sender,
+        address recipient,
+        uint256 amount
+    ) public view returns (bytes32) {
+        bytes memory chain = abi.encodePacked("eip155:", Strings.toString(block.chainid));
+        return sha256(
+            abi.encodePacked(
+                ESCROW_ID_DOMAIN,
+                sha256(chain),
+                sha256(abi.encodePacked(address(this))),
+                sha256(abi.encodePacked(sender)),
+                sha256(abi.encodePacked(recipient)),
+                sha256(abi.encodePacked(escrowId)),
+                sha256(""),
+                amount
+            )
         );
     }
 
     /// @notice Release an existing escrow (callable only by recipient)
     /// @param escrowId The ID of the escrow to finish/complete
     function finishEscrow(uint256 escrowId) external nonReentrant {
+        if (_escrows[escrowId].hasConditions) revert ProofRequired();
+        _finishEscrow(escrowId);
+    }
+
+    /// @notice Release a conditional escrow (callable only by recipient)
+    /// @param escrowId The ID of the escrow to finish/complete
+    /// @param seal Nonce, expiry block, attested chain state, and condition
+    /// fingerprint committed by the guest, followed by the Groth16 seal
+    /// proving the guest committed `ConditionsMet` for this escrow
+    function finishEscrowWithProof(
+        uint256 escrowId,
+        bytes calldata seal
+    ) external nonReentrant {
+        EscrowDB storage escrow = _escrows[escrowId];
+        if (escrow.sender == address(0)) revert EscrowNotExists();
+        if (!escrow.hasConditions) revert NoConditions();
+        if (seal.length < SEAL_PREFIX_LEN) revert MalformedSeal();
+        uint64 expiresAt = uint64(bytes8(seal[32:FRESHNESS_LEN]));
+        if (block.number > expiresAt) revert ProofExpired();
+        _checkChainState(seal[FRESHNESS_LEN:FRESHNESS_LEN + CHAIN_STATE_LEN]);
+        verifier.verify(
+            seal[SEAL_PREFIX_LEN:],
+            imageId,
+            journalDigest(escrowId, seal[:SEAL_PREFIX_LEN])
+        );
+        _finishEscrow(escrowId);
+    }
+
+    /// @notice Digest of the journal a proof finishing `escrowId` must commit:
+    /// the `ConditionsMet` result bound to the escrow and condition fingerprints
+    /// stored at creation, this escrow instance, and the freshness and chain
+    /// state of the seal prefix, so a proof for one escrow or condition cannot
+    /// finish another, nor be reused after it expires
+    /// @param escrowId The ID of the escrow proven
+    /// @param prefix Nonce, 8-byte expiry block, attested chain state (8-byte
+    /// block number and block hash), and condition fingerprint committed by
+    /// the guest, as prefixed to seals
+    function journalDigest(
+        uint256 escrowId,
+        bytes calldata prefix
+    ) public view returns (bytes32) {
+        EscrowDB storage escrow = _escrows[escrowId];
+        if (escrow.sender == address(0)) revert EscrowNotExists();
+        if (prefix.length != SEAL_PREFIX_LEN) revert MalformedSeal();
+        if (bytes32(prefix[CONDITION_OFFSET:]) != escrow.conditionFingerprint)
+            revert ConditionMismatch();
+        bytes32 instance = sha256(abi.encodePacked(address(this), escrowId));
+        return
+            sha256(
+                abi.encodePacked(
+                    CONDITIONS_MET,
+                    escrow.fingerprint,
+                    instance,
+                    prefix[:CONDITION_OFFSET],
+                    escrow.conditionFingerprint
+                )
+            );
+    }
+
+    /// @dev Reverts unless `state` is empty or names one of the last 256
+    /// blocks with its hash, so conditions proven against it used recent,
+    /// canonical chain state
+    function _checkChainState(bytes calldata state) private view {
+        uint64 height = uint64(bytes8(state[:8]));
+        bytes32 hash = bytes32(state[8:]);
+        if (height == 0 && hash == bytes32(0)) return;
+        // `blockhash` is zero for the current block and those older than 256.
+        if (hash == bytes32(0) || blockhash(height) != hash) revert StaleChainState();
+    }
+
+    function _finishEscrow(uint256 escrowId) private {
         EscrowDB storage escrow = _escrows[escrowId];
         if (escrow.sender == address(0)) revert EscrowNotExists();
         if (msg.sender != escrow.recipient) revert OnlyRecipient();
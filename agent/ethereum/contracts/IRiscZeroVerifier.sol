@@ -0,0 +1,18 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.28;
+
+/// @title RISC Zero verifier interface
+/// @notice Subset of `IRiscZeroVerifier` from risc0-ethereum, implemented by
+/// the deployed `RiscZeroVerifierRouter` and `RiscZeroGroth16Verifier`
+interface IRiscZeroVerifier {
+    /// @notice Verify that `seal` proves the guest `imageId` committed a
+    /// journal hashing to `journalDigest`; reverts otherwise
+    /// @param seal Selector-prefixed Groth16 seal
+    /// @param imageId Guest program image ID
+    /// @param journalDigest SHA-256 digest of the journal
+    function verify(
+        bytes calldata seal,
+        bytes32 imageId,
+        bytes32 journalDigest
+    ) external view;
+}
@@ -0,0 +1,17 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.28;
+
+import "../IRiscZeroVerifier.sol";
+
+/// @notice Test verifier accepting the seals the prover produces for fake
+/// (dev-mode) receipts: a zero selector followed by the SHA-256 digest of
+/// the image ID and journal digest. Proves nothing; local networks only
+contract DevModeVerifier is IRiscZeroVerifier {
+    error InvalidSeal();
+
+    function verify(bytes calldata seal, bytes32 imageId, bytes32 journalDigest) external pure {
+        if (seal.length != 36 || bytes4(seal[:4]) != bytes4(0)) revert InvalidSeal();
+        if (bytes32(seal[4:]) != sha256(abi.encodePacked(imageId, journalDigest)))
+            revert InvalidSeal();
+    }
+}
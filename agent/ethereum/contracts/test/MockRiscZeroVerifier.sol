@@ -0,0 +1,28 @@
+// SPDX-License-Identifier: MIT
+pragma solidity ^0.8.28;
+
+import "../IRiscZeroVerifier.sol";
+
+/// @notice Test verifier accepting exactly one seal, over the expected
+/// journal digest once one is set
+contract MockRiscZeroVerifier is IRiscZeroVerifier {
+    error InvalidSeal();
+    error InvalidJournal();
+
+    bytes32 private immutable _validSealHash;
+    bytes32 private _journalDigest;
+
+    constructor(bytes memory validSeal) {
+        _validSealHash = keccak256(validSeal);
+    }
+
+    function expectJournalDigest(bytes32 journalDigest) external {
+        _journalDigest = journalDigest;
+    }
+
+    function verify(bytes calldata seal, bytes32, bytes32 journalDigest) external view {
+        if (keccak256(seal) != _validSealHash) revert InvalidSeal();
+        if (_journalDigest != bytes32(0) && journalDigest != _journalDigest)
+            revert InvalidJournal();
+    }
+}
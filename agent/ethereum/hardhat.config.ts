@@ -33,6 +33,21 @@ const config: HardhatUserConfig = {
             accounts: PRIVATE_KEY ? [PRIVATE_KEY] : [],
             chainId: 11155111,
         },
+        arbitrumSepolia: {
+            url: process.env.ETHEREUM_RPC_URL || "https://sepolia-rollup.arbitrum.io/rpc",
+            accounts: PRIVATE_KEY ? [PRIVATE_KEY] : [],
+            chainId: 421614,
+        },
+        optimismSepolia: {
+            url: process.env.ETHEREUM_RPC_URL || "https://sepolia.optimism.io",
+            accounts: PRIVATE_KEY ? [PRIVATE_KEY] : [],
+            chainId: 11155420,
+        },
+        baseSepolia: {
+            url: process.env.ETHEREUM_RPC_URL || "https://sepolia.base.org",
+            accounts: PRIVATE_KEY ? [PRIVATE_KEY] : [],
+            chainId: 84532,
+        },
     },
     etherscan: {
         apiKey: ETHERSCAN_API_KEY,
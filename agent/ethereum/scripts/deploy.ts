@@ -1,8 +1,26 @@
-import { ethers } from "hardhat";
+import { ethers, network } from "hardhat";
+
+const LOCAL_NETWORKS = ["hardhat", "localhost"];
 
 async function main() {
+    // Conditional escrows stay disabled unless a RISC Zero verifier is given.
+    let verifier = process.env.ZESCROW_VERIFIER_ADDRESS ?? ethers.ZeroAddress;
+    const imageId = process.env.ZESCROW_IMAGE_ID ?? ethers.ZeroHash;
+
+    // Dev mode accepts fake proofs, so it is refused outside local networks.
+    if (process.env.ZESCROW_DEV_MODE === "1") {
+        if (!LOCAL_NETWORKS.includes(network.name)) {
+            throw new Error(`ZESCROW_DEV_MODE is only allowed on local networks, not ${network.name}`);
+        }
+        const DevModeVerifier = await ethers.getContractFactory("DevModeVerifier");
+        const devVerifier = await DevModeVerifier.deploy();
+        await devVerifier.waitForDeployment();
+        verifier = await devVerifier.getAddress();
+        console.log("DevModeVerifier deployed to:", verifier);
+    }
+
     const Escrow = await ethers.getContractFactory("Escrow");
-    const escrow = await Escrow.deploy();
+    const escrow = await Escrow.deploy(verifier, imageId);
     await escrow.waitForDeployment();
     const escrow_addr = await escrow.getAddress();
     console.log("Escrow deployed to:", escrow_addr);
@@ -13,4 +31,4 @@ main()
     .catch((error) => {
         console.error(error);
         process.exit(1);
-    });
\ No newline at end of file
+    });
@@ -4,10 +4,40 @@ import type { SignerWithAddress } from "@nomicfoundation/hardhat-ethers/signers"
 import type { Signer } from "ethers";
 
 import {
+    DevModeVerifier__factory,
     type Escrow,
     Escrow__factory,
+    type MockRiscZeroVerifier,
+    MockRiscZeroVerifier__factory,
 } from "../typechain-types";
 
+const IMAGE_ID = ethers.id("zescrow-guest");
+const VALID_SEAL = "0x73c457ba0123";
+const FINGERPRINT = ethers.id("escrow-fingerprint");
+const NONCE = ethers.id("nonce");
+const NEVER = 2n ** 64n - 1n;
+const CONDITION = ethers.id("condition");
+const NO_CHAIN_STATE = ethers.concat([ethers.toBeHex(0, 8), ethers.ZeroHash]);
+
+/// The nonce, expiry block, chain state, and condition fingerprint a seal
+/// commits to.
+function sealPrefix(
+    expiresAt: bigint = NEVER,
+    chainState: string = NO_CHAIN_STATE,
+    condition: string = CONDITION
+): string {
+    return ethers.concat([NONCE, ethers.toBeHex(expiresAt, 8), chainState, condition]);
+}
+
+/// Prefixes the mock seal with what it commits to.
+function freshSeal(
+    expiresAt: bigint = NEVER,
+    chainState: string = NO_CHAIN_STATE,
+    condition: string = CONDITION
+): string {
+    return ethers.concat([sealPrefix(expiresAt, chainState, condition), VALID_SEAL]);
+}
+
 describe("Escrow", () => {
     let deployer: SignerWithAddress;
     let recipient: SignerWithAddress;
@@ -15,7 +45,10 @@ describe("Escrow", () => {
 
     beforeEach(async () => {
         [deployer, recipient] = await ethers.getSigners();
-        escrow = await new Escrow__factory(deployer as unknown as Signer).deploy();
+        escrow = await new Escrow__factory(deployer as unknown as Signer).deploy(
+            ethers.ZeroAddress,
+            ethers.ZeroHash
+        );
         await escrow.waitForDeployment();
     });
 
@@ -40,6 +73,7 @@ describe("Escrow", () => {
             recipient.address,
             undefined,
             undefined,
+            undefined,
             undefined
         );
         const events = await escrow.queryFilter(filter, receipt.blockNumber);
@@ -80,6 +114,7 @@ describe("Escrow", () => {
             recipient.address,
             undefined,
             undefined,
+            undefined,
             undefined
         );
         const events = await escrow.queryFilter(filter, receipt.blockNumber);
@@ -100,5 +135,313 @@ describe("Escrow", () => {
         const balAfter = await ethers.provider.getBalance(deployer.address);
         expect(balAfter).to.be.gt(balBefore);
     });
-});
 
+    it("emits the chain-agnostic escrow ID", async () => {
+        const value = ethers.parseEther("1");
+        const startBlock = await ethers.provider.getBlockNumber();
+        await (await escrow.createEscrow(recipient.address, startBlock + 3, 0, { value })).wait();
+        const [created] = await escrow.queryFilter(escrow.filters.EscrowCreated());
+
+        // Computed as `zescrow_core::EscrowId` does.
+        const { chainId } = await ethers.provider.getNetwork();
+        const digest = (types: string[], values: unknown[]) =>
+            ethers.sha256(ethers.solidityPacked(types, values));
+        const uid = ethers.sha256(
+            ethers.concat([
+                ethers.toUtf8Bytes("zescrow/escrow-id/v1"),
+                ethers.sha256(ethers.toUtf8Bytes(`eip155:${chainId}`)),
+                digest(["address"], [await escrow.getAddress()]),
+                digest(["address"], [deployer.address]),
+                digest(["address"], [recipient.address]),
+                digest(["uint256"], [1]),
+                ethers.sha256("0x"),
+                ethers.toBeHex(value, 32),
+            ])
+        );
+        expect(created.args.uid).to.equal(uid);
+        expect(await escrow.escrowUid(1, deployer.address, recipient.address, value)).to.equal(
+            uid
+        );
+    });
+
+    it("finishes several escrows in one multicall", async () => {
+        const startBlock = await ethers.provider.getBlockNumber();
+        const value = ethers.parseEther("1");
+        for (let i = 0; i < 2; i++) {
+            await (
+                await escrow.createEscrow(recipient.address, startBlock + 3, 0, { value })
+            ).wait();
+        }
+        await network.provider.send("evm_mine");
+
+        const calls = [1, 2].map((id) =>
+            escrow.interface.encodeFunctionData("finishEscrow", [id])
+        );
+        // Any call failing reverts the whole batch
+        await expect(
+            escrow.connect(recipient).multicall([...calls, calls[0]])
+        ).to.be.revertedWithCustomError(escrow, "AlreadySettled");
+
+        const balBefore = await ethers.provider.getBalance(recipient.address);
+        await (await escrow.connect(recipient).multicall(calls)).wait();
+        const balAfter = await ethers.provider.getBalance(recipient.address);
+        expect(balAfter - balBefore).to.be.gt(value);
+    });
+
+    describe("with conditions", () => {
+        let conditional: Escrow;
+        let verifier: MockRiscZeroVerifier;
+
+        beforeEach(async () => {
+            verifier = await new MockRiscZeroVerifier__factory(
+                deployer as unknown as Signer
+            ).deploy(VALID_SEAL);
+            await verifier.waitForDeployment();
+            conditional = await new Escrow__factory(deployer as unknown as Signer).deploy(
+                await verifier.getAddress(),
+                IMAGE_ID
+            );
+            await conditional.waitForDeployment();
+        });
+
+        async function createConditional(fingerprint: string = FINGERPRINT): Promise<bigint> {
+            const startBlock = await ethers.provider.getBlockNumber();
+            const tx = await conditional.createConditionalEscrow(
+                recipient.address,
+                startBlock + 1,
+                startBlock + 10,
+                fingerprint,
+                CONDITION,
+                { value: ethers.parseEther("1") }
+            );
+            const receipt = await tx.wait();
+            if (!receipt) throw new Error("Transaction failed to be mined");
+
+            const events = await conditional.queryFilter(
+                conditional.filters.EscrowCreated(),
+                receipt.blockNumber
+            );
+            if (events.length === 0) throw new Error("EscrowCreated event not found");
+            return events[0].args.escrowId;
+        }
+
+        it("rejects conditional escrows without a verifier", async () => {
+            const startBlock = await ethers.provider.getBlockNumber();
+            await expect(
+                escrow.createConditionalEscrow(
+                    recipient.address,
+                    startBlock + 1,
+                    0,
+                    FINGERPRINT,
+                    CONDITION,
+                    { value: ethers.parseEther("1") }
+                )
+            ).to.be.revertedWithCustomError(escrow, "ConditionsUnsupported");
+        });
+
+        it("requires a valid proof to finish", async () => {
+            const escrowId = await createConditional();
+            const asRecipient = conditional.connect(recipient);
+
+            await expect(asRecipient.finishEscrow(escrowId)).to.be.revertedWithCustomError(
+                conditional,
+                "ProofRequired"
+            );
+            await expect(
+                asRecipient.finishEscrowWithProof(escrowId, "0xdead")
+            ).to.be.revertedWithCustomError(conditional, "MalformedSeal");
+            await expect(
+                asRecipient.finishEscrowWithProof(
+                    escrowId,
+                    ethers.concat([sealPrefix(), "0xdead"])
+                )
+            ).to.be.reverted;
+
+            const balBefore = await ethers.provider.getBalance(recipient.address);
+            await (await asRecipient.finishEscrowWithProof(escrowId, freshSeal())).wait();
+            const balAfter = await ethers.provider.getBalance(recipient.address);
+            expect(balAfter).to.be.gt(balBefore);
+        });
+
+        it("rejects proofs for unconditional escrows", async () => {
+            const startBlock = await ethers.provider.getBlockNumber();
+            await (
+                await conditional.createEscrow(recipient.address, startBlock + 1, 0, {
+                    value: ethers.parseEther("1"),
+                })
+            ).wait();
+
+            await expect(
+                conditional.connect(recipient).finishEscrowWithProof(1, freshSeal())
+            ).to.be.revertedWithCustomError(conditional, "NoConditions");
+        });
+
+        it("binds the journal to the escrow instance", async () => {
+            const escrowId = await createConditional();
+            const other = await createConditional();
+            const instance = ethers.sha256(
+                ethers.solidityPacked(
+                    ["address", "uint256"],
+                    [await conditional.getAddress(), escrowId]
+                )
+            );
+            const digest = ethers.sha256(
+                ethers.concat([
+                    "0x0002",
+                    FINGERPRINT,
+                    instance,
+                    NONCE,
+                    ethers.toBeHex(NEVER, 8),
+                    NO_CHAIN_STATE,
+                    CONDITION,
+                ])
+            );
+            expect(await conditional.journalDigest(escrowId, sealPrefix())).to.equal(digest);
+            expect(await conditional.journalDigest(other, sealPrefix())).to.not.equal(digest);
+            await expect(
+                conditional.journalDigest(escrowId, NONCE)
+            ).to.be.revertedWithCustomError(conditional, "MalformedSeal");
+
+            await (await verifier.expectJournalDigest(digest)).wait();
+            const asRecipient = conditional.connect(recipient);
+            await expect(
+                asRecipient.finishEscrowWithProof(other, freshSeal())
+            ).to.be.revertedWithCustomError(verifier, "InvalidJournal");
+            await (await asRecipient.finishEscrowWithProof(escrowId, freshSeal())).wait();
+        });
+
+        it("rejects proofs of another condition", async () => {
+            const escrowId = await createConditional();
+            const other = ethers.id("other-condition");
+            const asRecipient = conditional.connect(recipient);
+
+            await expect(
+                conditional.journalDigest(escrowId, sealPrefix(NEVER, NO_CHAIN_STATE, other))
+            ).to.be.revertedWithCustomError(conditional, "ConditionMismatch");
+            await expect(
+                asRecipient.finishEscrowWithProof(escrowId, freshSeal(NEVER, NO_CHAIN_STATE, other))
+            ).to.be.revertedWithCustomError(conditional, "ConditionMismatch");
+            await (await asRecipient.finishEscrowWithProof(escrowId, freshSeal())).wait();
+        });
+
+        it("binds the journal to the stored escrow fingerprint", async () => {
+            const stored = ethers.id("other-escrow");
+            const escrowId = await createConditional(stored);
+            const instance = ethers.sha256(
+                ethers.solidityPacked(
+                    ["address", "uint256"],
+                    [await conditional.getAddress(), escrowId]
+                )
+            );
+            const digest = (fingerprint: string) =>
+                ethers.sha256(
+                    ethers.concat([
+                        "0x0002",
+                        fingerprint,
+                        instance,
+                        NONCE,
+                        ethers.toBeHex(NEVER, 8),
+                        NO_CHAIN_STATE,
+                        CONDITION,
+                    ])
+                );
+            expect(await conditional.journalDigest(escrowId, sealPrefix())).to.equal(
+                digest(stored)
+            );
+
+            // A proof committing to another escrow's fingerprint is rejected.
+            await (await verifier.expectJournalDigest(digest(FINGERPRINT))).wait();
+            await expect(
+                conditional.connect(recipient).finishEscrowWithProof(escrowId, freshSeal())
+            ).to.be.revertedWithCustomError(verifier, "InvalidJournal");
+        });
+
+        it("rejects expired proofs", async () => {
+            const escrowId = await createConditional();
+            await network.provider.send("evm_mine");
+            await network.provider.send("evm_mine");
+            const current = BigInt(await ethers.provider.getBlockNumber());
+            const asRecipient = conditional.connect(recipient);
+
+            // The finish transaction is mined in the next block.
+            await expect(
+                asRecipient.finishEscrowWithProof(escrowId, freshSeal(current))
+            ).to.be.revertedWithCustomError(conditional, "ProofExpired");
+            await (
+                await asRecipient.finishEscrowWithProof(escrowId, freshSeal(current + 5n))
+            ).wait();
+        });
+
+        it("checks attested chain state against recent block hashes", async () => {
+            const escrowId = await createConditional();
+            const asRecipient = conditional.connect(recipient);
+            const attest = async (number: number, hash?: string) =>
+                ethers.concat([
+                    ethers.toBeHex(number, 8),
+                    hash ?? (await ethers.provider.getBlock(number))!.hash!,
+                ]);
+
+            const recent = await ethers.provider.getBlockNumber();
+            await expect(
+                asRecipient.finishEscrowWithProof(
+                    escrowId,
+                    freshSeal(NEVER, await attest(recent, ethers.id("forged")))
+                )
+            ).to.be.revertedWithCustomError(conditional, "StaleChainState");
+
+            // `blockhash` only covers the last 256 blocks.
+            await network.provider.send("hardhat_mine", ["0x100"]);
+            await expect(
+                asRecipient.finishEscrowWithProof(
+                    escrowId,
+                    freshSeal(NEVER, await attest(recent))
+                )
+            ).to.be.revertedWithCustomError(conditional, "StaleChainState");
+
+            const latest = await ethers.provider.getBlockNumber();
+            await (
+                await asRecipient.finishEscrowWithProof(
+                    escrowId,
+                    freshSeal(NEVER, await attest(latest))
+                )
+            ).wait();
+        });
+    });
+
+    it("finishes with dev-mode seals through the dev-mode verifier", async () => {
+        const verifier = await new DevModeVerifier__factory(
+            deployer as unknown as Signer
+        ).deploy();
+        await verifier.waitForDeployment();
+        const devEscrow = await new Escrow__factory(deployer as unknown as Signer).deploy(
+            await verifier.getAddress(),
+            IMAGE_ID
+        );
+        await devEscrow.waitForDeployment();
+
+        const startBlock = await ethers.provider.getBlockNumber();
+        await (
+            await devEscrow.createConditionalEscrow(
+                recipient.address,
+                startBlock + 1,
+                startBlock + 10,
+                FINGERPRINT,
+                CONDITION,
+                { value: ethers.parseEther("1") }
+            )
+        ).wait();
+        const [created] = await devEscrow.queryFilter(devEscrow.filters.EscrowCreated());
+        const escrowId = created.args.escrowId;
+
+        const digest = await devEscrow.journalDigest(escrowId, sealPrefix());
+        const claim = ethers.sha256(ethers.concat([IMAGE_ID, digest]));
+        const seal = (body: string) =>
+            ethers.concat([sealPrefix(), "0x00000000", body]);
+        const asRecipient = devEscrow.connect(recipient);
+
+        await expect(
+            asRecipient.finishEscrowWithProof(escrowId, seal(ethers.ZeroHash))
+        ).to.be.revertedWithCustomError(verifier, "InvalidSeal");
+        await (await asRecipient.finishEscrowWithProof(escrowId, seal(claim))).wait();
+    });
+});
@@ -0,0 +1,560 @@
+//! Escrow contract for Substrate chains running `pallet-contracts`, with
+//! XRPL-style time-lock semantics.
+//!
+//! Mirrors the Solana escrow program: a sender locks the native token for a
+//! recipient, who can finish the escrow once `finish_after` is reached,
+//! while the sender can cancel it once `cancel_after` is. Timelocks are
+//! block numbers. Escrows are assigned sequential IDs and kept after they
+//! are settled, with their state recorded, along with their chain-agnostic
+//! ID (see `zescrow_core::EscrowId`). Contracts cannot read the chain's
+//! CAIP-2 ID, so `create` takes its SHA-256 digest.
+//!
+//! Conditions are proven off-chain: the client checks the proof before the
+//! recipient finishes, as on Solana.
+//!
+//! Build with the `ink-as-dependency` feature to use the message types,
+//! events, and [`selectors`] without the contract entry points, as the
+//! Zescrow client does.
+
+#![cfg_attr(not(feature = "std"), no_std, no_main)]
+
+/// Maximum length in bytes of the optional escrow memo (e.g., an invoice ID
+/// or URI hash).
+pub const MEMO_LEN: usize = 64;
+
+/// Domain separator of chain-agnostic escrow IDs.
+pub const ESCROW_ID_DOMAIN: &[u8] = b"zescrow/escrow-id/v1";
+
+/// Escrows returned by one [`escrows_by_party`](escrow::Zescrow::escrows_by_party)
+/// call at most.
+pub const MAX_LIMIT: u32 = 100;
+
+/// Selectors of the contract's messages, for clients encoding calls.
+///
+/// ink! derives a message's selector from its name.
+pub mod selectors {
+    /// Selector of [`create`](crate::escrow::Zescrow::create).
+    pub const CREATE: [u8; 4] = ink::selector_bytes!("create");
+    /// Selector of [`finish`](crate::escrow::Zescrow::finish).
+    pub const FINISH: [u8; 4] = ink::selector_bytes!("finish");
+    /// Selector of [`cancel`](crate::escrow::Zescrow::cancel).
+    pub const CANCEL: [u8; 4] = ink::selector_bytes!("cancel");
+    /// Selector of [`escrow`](crate::escrow::Zescrow::escrow).
+    pub const ESCROW: [u8; 4] = ink::selector_bytes!("escrow");
+    /// Selector of [`escrows_by_party`](crate::escrow::Zescrow::escrows_by_party).
+    pub const ESCROWS_BY_PARTY: [u8; 4] = ink::selector_bytes!("escrows_by_party");
+}
+
+#[ink::contract]
+pub mod escrow {
+    use ink::env::hash::Sha2x256;
+    use ink::prelude::string::String;
+    use ink::prelude::vec::Vec;
+    use ink::storage::Mapping;
+
+    use crate::{ESCROW_ID_DOMAIN, MAX_LIMIT, MEMO_LEN};
+
+    /// Lifecycle state of an escrow. Settled escrows are kept, so they can
+    /// still be queried.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub enum EscrowState {
+        /// Funds are locked.
+        Funded,
+        /// Funds were released to the recipient.
+        Finished,
+        /// Funds were refunded to the sender.
+        Cancelled,
+    }
+
+    /// An escrow and its lifecycle state.
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    #[cfg_attr(feature = "std", derive(ink::storage::traits::StorageLayout))]
+    pub struct Escrow {
+        /// Escrow ID assigned at creation.
+        pub id: u64,
+        /// Chain-agnostic escrow ID.
+        pub uid: [u8; 32],
+        /// Account that funded the escrow.
+        pub sender: AccountId,
+        /// Beneficiary of the escrowed funds.
+        pub recipient: AccountId,
+        /// Escrowed amount of the native token.
+        pub amount: Balance,
+        /// Optional block number after which the recipient can finish.
+        pub finish_after: Option<u64>,
+        /// Optional block number after which the sender can cancel.
+        pub cancel_after: Option<u64>,
+        /// Whether finishing requires proof of the escrow's conditions.
+        pub has_conditions: bool,
+        /// Optional memo for correlating the escrow with off-chain records.
+        pub memo: Option<String>,
+        /// Lifecycle state.
+        pub state: EscrowState,
+    }
+
+    /// What happened to an escrow.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Action {
+        /// The escrow was created and funded.
+        Created,
+        /// The escrow was released to its recipient.
+        Finished,
+        /// The escrow was refunded to its sender.
+        Cancelled,
+    }
+
+    /// Errors the escrow contract fails with.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    #[ink::scale_derive(Encode, Decode, TypeInfo)]
+    pub enum Error {
+        /// Neither `finish_after` nor `cancel_after` is set.
+        MustSpecifyPath,
+        /// `finish_after` is not before `cancel_after`.
+        InvalidTimeOrder,
+        /// No value was transferred with `create`.
+        InvalidFunds,
+        /// The memo is longer than [`MEMO_LEN`] bytes.
+        MemoTooLong,
+        /// The message was not sent by the party allowed to send it.
+        Unauthorized,
+        /// There is no escrow with the ID.
+        NotFound,
+        /// The escrow was already finished or cancelled.
+        NotFunded(EscrowState),
+        /// `finish_after` has not been reached.
+        NotReady(u64),
+        /// The escrow has no `cancel_after`.
+        CancelNotAllowed,
+        /// `cancel_after` has not been reached.
+        NotExpired(u64),
+        /// Paying out the escrowed funds failed.
+        TransferFailed,
+    }
+
+    /// Result of the contract's messages.
+    pub type Result<T> = core::result::Result<T, Error>;
+
+    /// Emitted when an escrow is created, finished, or cancelled; clients
+    /// track escrows by it.
+    #[ink(event)]
+    pub struct EscrowUpdated {
+        /// Escrow ID assigned at creation.
+        #[ink(topic)]
+        pub id: u64,
+        /// What happened to the escrow.
+        pub action: Action,
+        /// Account that funded the escrow.
+        #[ink(topic)]
+        pub sender: AccountId,
+        /// Beneficiary of the escrowed funds.
+        #[ink(topic)]
+        pub recipient: AccountId,
+        /// Escrowed amount.
+        pub amount: Balance,
+        /// Chain-agnostic escrow ID.
+        pub uid: [u8; 32],
+    }
+
+    /// Escrows by ID, indexed by party.
+    #[ink(storage)]
+    #[derive(Default)]
+    pub struct Zescrow {
+        /// ID the next escrow is assigned.
+        next_id: u64,
+        /// Escrows by ID.
+        escrows: Mapping<u64, Escrow>,
+        /// IDs of the escrows each account sends or receives, by position.
+        party_escrows: Mapping<(AccountId, u32), u64>,
+        /// Number of escrows each account sends or receives.
+        party_counts: Mapping<AccountId, u32>,
+    }
+
+    impl Zescrow {
+        /// Creates the contract with no escrows.
+        #[ink(constructor)]
+        pub fn new() -> Self {
+            Self::default()
+        }
+
+        /// Locks the value transferred with the message for `recipient`,
+        /// returning the escrow ID, and enforcing XRPL-style guards:
+        /// - At least one of `finish_after` or `cancel_after` must be set.
+        /// - If both are set, `finish_after < cancel_after`.
+        ///
+        /// `chain` is the SHA-256 digest of the chain's CAIP-2 ID, hashed
+        /// into the escrow's chain-agnostic ID.
+        #[ink(message, payable)]
+        pub fn create(
+            &mut self,
+            recipient: AccountId,
+            finish_after: Option<u64>,
+            cancel_after: Option<u64>,
+            has_conditions: bool,
+            memo: Option<String>,
+            chain: [u8; 32],
+        ) -> Result<u64> {
+            // Must have at least one resolution path
+            if finish_after.is_none() && cancel_after.is_none() {
+                return Err(Error::MustSpecifyPath);
+            }
+            // If both set, enforce ordering
+            if let (Some(finish), Some(cancel)) = (finish_after, cancel_after) {
+                if finish >= cancel {
+                    return Err(Error::InvalidTimeOrder);
+                }
+            }
+            let amount = self.env().transferred_value();
+            if amount == 0 {
+                return Err(Error::InvalidFunds);
+            }
+            if memo.as_ref().is_some_and(|memo| memo.len() > MEMO_LEN) {
+                return Err(Error::MemoTooLong);
+            }
+
+            let id = self.next_id;
+            self.next_id += 1;
+            let sender = self.env().caller();
+            let escrow = Escrow {
+                id,
+                uid: self.uid(&chain, id, sender, recipient, amount),
+                sender,
+                recipient,
+                amount,
+                finish_after,
+                cancel_after,
+                has_conditions,
+                memo,
+                state: EscrowState::Funded,
+            };
+            self.index(escrow.sender, id);
+            if escrow.recipient != escrow.sender {
+                self.index(escrow.recipient, id);
+            }
+            self.escrows.insert(id, &escrow);
+            self.emit(&escrow, Action::Created);
+            Ok(id)
+        }
+
+        /// Releases escrow `id` to its recipient:
+        /// - If `finish_after` is `Some(t)`, require current block >= t.
+        /// - If `finish_after` is `None`, allow immediate release.
+        /// - Only callable by `recipient`.
+        #[ink(message)]
+        pub fn finish(&mut self, id: u64) -> Result<()> {
+            let mut escrow = self.funded_escrow(id)?;
+            if self.env().caller() != escrow.recipient {
+                return Err(Error::Unauthorized);
+            }
+            if let Some(t) = escrow.finish_after {
+                if self.block_number() < t {
+                    return Err(Error::NotReady(t));
+                }
+            }
+            escrow.state = EscrowState::Finished;
+            self.escrows.insert(id, &escrow);
+            self.env()
+                .transfer(escrow.recipient, escrow.amount)
+                .map_err(|_| Error::TransferFailed)?;
+            self.emit(&escrow, Action::Finished);
+            Ok(())
+        }
+
+        /// Refunds escrow `id` to its sender:
+        /// - Requires `cancel_after` to be `Some(t)`.
+        /// - Current block >= t.
+        /// - Only callable by the original `sender`.
+        #[ink(message)]
+        pub fn cancel(&mut self, id: u64) -> Result<()> {
+            let mut escrow = self.funded_escrow(id)?;
+            if self.env().caller() != escrow.sender {
+                return Err(Error::Unauthorized);
+            }
+            let t = escrow.cancel_after.ok_or(Error::CancelNotAllowed)?;
+            if self.block_number() < t {
+                return Err(Error::NotExpired(t));
+            }
+            escrow.state = EscrowState::Cancelled;
+            self.escrows.insert(id, &escrow);
+            self.env()
+                .transfer(escrow.sender, escrow.amount)
+                .map_err(|_| Error::TransferFailed)?;
+            self.emit(&escrow, Action::Cancelled);
+            Ok(())
+        }
+
+        /// Returns escrow `id`, if it exists.
+        #[ink(message)]
+        pub fn escrow(&self, id: u64) -> Option<Escrow> {
+            self.escrows.get(id)
+        }
+
+        /// Returns up to `limit` (at most [`MAX_LIMIT`]) of the escrows
+        /// `party` sends or receives, in ID order, skipping the first
+        /// `offset`.
+        #[ink(message)]
+        pub fn escrows_by_party(&self, party: AccountId, offset: u32, limit: u32) -> Vec<Escrow> {
+            let count = self.party_counts.get(party).unwrap_or_default();
+            let end = count.min(offset.saturating_add(limit.min(MAX_LIMIT)));
+            (offset..end)
+                .filter_map(|position| self.party_escrows.get((party, position)))
+                .filter_map(|id| self.escrows.get(id))
+                .collect()
+        }
+
+        /// Loads escrow `id`, which must still be funded.
+        fn funded_escrow(&self, id: u64) -> Result<Escrow> {
+            let escrow = self.escrows.get(id).ok_or(Error::NotFound)?;
+            match escrow.state {
+                EscrowState::Funded => Ok(escrow),
+                state => Err(Error::NotFunded(state)),
+            }
+        }
+
+        /// Records escrow `id` among the escrows of `party`.
+        fn index(&mut self, party: AccountId, id: u64) {
+            let count = self.party_counts.get(party).unwrap_or_default();
+            self.party_escrows.insert((party, count), &id);
+            self.party_counts.insert(party, &(count + 1));
+        }
+
+        /// Chain-agnostic ID of escrow `id`, as computed by
+        /// `zescrow_core::EscrowId`, given the digest of the chain's CAIP-2
+        /// ID.
+        fn uid(
+            &self,
+            chain: &[u8; 32],
+            id: u64,
+            sender: AccountId,
+            recipient: AccountId,
+            amount: Balance,
+        ) -> [u8; 32] {
+            let word = |value: u128| {
+                let mut word = [0; 32];
+                word[16..].copy_from_slice(&value.to_be_bytes());
+                word
+            };
+            let contract = self.env().account_id();
+            let mut input = Vec::with_capacity(ESCROW_ID_DOMAIN.len() + 7 * 32);
+            input.extend_from_slice(ESCROW_ID_DOMAIN);
+            input.extend_from_slice(chain);
+            for part in [
+                contract.as_ref(),
+                sender.as_ref(),
+                recipient.as_ref(),
+                &word(id.into()),
+                &[],
+            ] {
+                input.extend_from_slice(&self.env().hash_bytes::<Sha2x256>(part));
+            }
+            input.extend_from_slice(&word(amount));
+            self.env().hash_bytes::<Sha2x256>(&input)
+        }
+
+        fn block_number(&self) -> u64 {
+            u64::from(self.env().block_number())
+        }
+
+        fn emit(&self, escrow: &Escrow, action: Action) {
+            self.env().emit_event(EscrowUpdated {
+                id: escrow.id,
+                action,
+                sender: escrow.sender,
+                recipient: escrow.recipient,
+                amount: escrow.amount,
+                uid: escrow.uid,
+            });
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use ink::env::test;
+        use ink::env::DefaultEnvironment;
+
+        use super::*;
+
+        /// SHA-256 digest of Polkadot's CAIP-2 ID,
+        /// `polkadot:91b171bb158e2d3848fa23a9f1c25182`.
+        const CHAIN: [u8; 32] = [
+            0x6a, 0x26, 0xe5, 0x5b, 0x02, 0x18, 0xf9, 0x11, 0x80, 0x02, 0x16, 0x89, 0x6a, 0x62,
+            0x8d, 0x3d, 0x4b, 0x8b, 0x57, 0x46, 0x5a, 0xfe, 0x0e, 0xca, 0x57, 0xe6, 0xd8, 0x5c,
+            0x4c, 0x0e, 0x2b, 0x3f,
+        ];
+
+        fn accounts() -> test::DefaultAccounts<DefaultEnvironment> {
+            test::default_accounts::<DefaultEnvironment>()
+        }
+
+        /// Creates an escrow of `amount` from `sender`, funding the
+        /// contract's balance as the transfer would.
+        fn create(
+            contract: &mut Zescrow,
+            sender: AccountId,
+            recipient: AccountId,
+            amount: Balance,
+            finish_after: Option<u64>,
+            cancel_after: Option<u64>,
+        ) -> Result<u64> {
+            test::set_caller::<DefaultEnvironment>(sender);
+            test::set_value_transferred::<DefaultEnvironment>(amount);
+            let callee = test::callee::<DefaultEnvironment>();
+            let balance = test::get_account_balance::<DefaultEnvironment>(callee).unwrap_or(0);
+            test::set_account_balance::<DefaultEnvironment>(callee, balance + amount);
+            let id = contract.create(
+                recipient,
+                finish_after,
+                cancel_after,
+                false,
+                Some("invoice-42".into()),
+                CHAIN,
+            );
+            test::set_value_transferred::<DefaultEnvironment>(0);
+            id
+        }
+
+        fn advance_to(block: u64) {
+            while u64::from(ink::env::block_number::<DefaultEnvironment>()) < block {
+                test::advance_block::<DefaultEnvironment>();
+            }
+        }
+
+        #[ink::test]
+        fn escrow_lifecycle() {
+            let accounts = accounts();
+            let mut contract = Zescrow::new();
+            let id = create(
+                &mut contract,
+                accounts.alice,
+                accounts.bob,
+                500,
+                Some(10),
+                None,
+            )
+            .unwrap();
+            assert_eq!(id, 0);
+            assert_eq!(test::recorded_events().count(), 1);
+
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            assert_eq!(contract.finish(id), Err(Error::NotReady(10)));
+
+            advance_to(10);
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.finish(id), Err(Error::Unauthorized));
+            test::set_caller::<DefaultEnvironment>(accounts.bob);
+            let before = test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap();
+            contract.finish(id).unwrap();
+            assert_eq!(
+                test::get_account_balance::<DefaultEnvironment>(accounts.bob).unwrap(),
+                before + 500
+            );
+            assert_eq!(
+                contract.finish(id),
+                Err(Error::NotFunded(EscrowState::Finished))
+            );
+
+            let escrows = contract.escrows_by_party(accounts.bob, 0, MAX_LIMIT);
+            assert_eq!(escrows.len(), 1);
+            assert_eq!(escrows[0].state, EscrowState::Finished);
+            assert_eq!(escrows[0].memo.as_deref(), Some("invoice-42"));
+        }
+
+        #[ink::test]
+        fn cancel_requires_expiry() {
+            let accounts = accounts();
+            let mut contract = Zescrow::new();
+            let id = create(
+                &mut contract,
+                accounts.alice,
+                accounts.bob,
+                7,
+                None,
+                Some(5),
+            )
+            .unwrap();
+
+            test::set_caller::<DefaultEnvironment>(accounts.alice);
+            assert_eq!(contract.cancel(id), Err(Error::NotExpired(5)));
+            advance_to(5);
+            contract.cancel(id).unwrap();
+            assert_eq!(
+                contract.escrow(id).map(|escrow| escrow.state),
+                Some(EscrowState::Cancelled)
+            );
+        }
+
+        #[ink::test]
+        fn create_guards() {
+            let accounts = accounts();
+            let mut contract = Zescrow::new();
+            let (alice, bob) = (accounts.alice, accounts.bob);
+
+            assert_eq!(
+                create(&mut contract, alice, bob, 1, None, None),
+                Err(Error::MustSpecifyPath)
+            );
+            assert_eq!(
+                create(&mut contract, alice, bob, 1, Some(9), Some(9)),
+                Err(Error::InvalidTimeOrder)
+            );
+            assert_eq!(
+                create(&mut contract, alice, bob, 0, Some(1), None),
+                Err(Error::InvalidFunds)
+            );
+        }
+
+        #[ink::test]
+        fn escrows_by_party_pages() {
+            let accounts = accounts();
+            let mut contract = Zescrow::new();
+            for _ in 0..3 {
+                create(
+                    &mut contract,
+                    accounts.alice,
+                    accounts.bob,
+                    1,
+                    Some(1),
+                    None,
+                )
+                .unwrap();
+            }
+
+            let page = contract.escrows_by_party(accounts.alice, 1, 1);
+            assert_eq!(page.iter().map(|e| e.id).collect::<Vec<_>>(), [1]);
+            assert_eq!(contract.escrows_by_party(accounts.bob, 2, 10).len(), 1);
+            assert!(contract
+                .escrows_by_party(accounts.charlie, 0, 10)
+                .is_empty());
+        }
+
+        #[ink::test]
+        fn escrow_uid_vector() {
+            let accounts = accounts();
+            test::set_callee::<DefaultEnvironment>(AccountId::from([0x07; 32]));
+            let mut contract = Zescrow::new();
+            let id = create(
+                &mut contract,
+                accounts.alice,
+                accounts.bob,
+                500,
+                Some(10),
+                None,
+            )
+            .unwrap();
+
+            // Computed as `zescrow_core::EscrowId` does.
+            let escrow = contract.escrow(id).unwrap();
+            assert_eq!(
+                escrow.uid,
+                [
+                    0x09, 0x01, 0x14, 0x34, 0x9b, 0xc7, 0xb8, 0xe9, 0x06, 0xc2, 0xf8, 0xdf, 0xda,
+                    0xa5, 0xe7, 0x02, 0x72, 0x91, 0xda, 0xc4, 0x64, 0x37, 0xf0, 0xbd, 0xd9, 0x92,
+                    0xb1, 0x72, 0xe0, 0xd2, 0x52, 0x88,
+                ]
+            );
+        }
+    }
+}
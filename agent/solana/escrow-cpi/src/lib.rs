@@ -0,0 +1,348 @@
+//! CPI interface for the Zescrow Solana escrow program.
+//!
+//! Other on-chain programs can depend on this crate to create, finish, and
+//! cancel escrows via cross-program invocation, without copy-pasting the
+//! Anchor account definitions. Off-chain callers can use the [`instruction`]
+//! builders and [`pda`] helpers to construct transactions directly.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use anchor_lang::prelude::*;
+//! use anchor_lang::solana_program::hash::hash;
+//! use escrow_cpi::{cpi, CreateEscrowArgs};
+//!
+//! let cpi_ctx = CpiContext::new(
+//!     ctx.accounts.escrow_program.to_account_info(),
+//!     cpi::accounts::CreateEscrow {
+//!         sender: ctx.accounts.payer.to_account_info(),
+//!         recipient: ctx.accounts.recipient.to_account_info(),
+//!         escrow_account: ctx.accounts.escrow_account.to_account_info(),
+//!         compliance: ctx.accounts.compliance.to_account_info(),
+//!         system_program: ctx.accounts.system_program.to_account_info(),
+//!     },
+//! );
+//! let args = CreateEscrowArgs {
+//!     amount,
+//!     finish_after,
+//!     cancel_after,
+//!     memo: None,
+//!     rent_destination: None,
+//!     // SHA-256 digest of the cluster's CAIP-2 ID, here mainnet-beta's.
+//!     chain: hash(b"solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp").to_bytes(),
+//!     bridge_trigger: None,
+//! };
+//! cpi::create_escrow(cpi_ctx, args)?;
+//! ```
+
+#![forbid(unsafe_code)]
+#![warn(missing_docs)]
+
+pub use escrow::program::Escrow as EscrowProgram;
+pub use escrow::{
+    cpi, BridgeTrigger, ComplianceEvent, ComplianceList, ComplianceMode, CreateEscrowArgs,
+    CreateStakeEscrowArgs, Escrow as EscrowAccount, EscrowError, EscrowEvent, EscrowState,
+    PostedVaa, StakeEscrow as StakeEscrowAccount, StakeEscrowEvent, UpdateComplianceArgs,
+    COMPLIANCE, ESCROW, ID, STAKE_ESCROW, WORMHOLE_CORE_BRIDGE,
+};
+
+/// Program-derived address helpers.
+pub mod pda {
+    use anchor_lang::prelude::Pubkey;
+
+    use super::{COMPLIANCE, ESCROW, ID, STAKE_ESCROW};
+
+    /// Derives the escrow PDA and bump for the canonical program ID.
+    pub fn find_escrow_address(sender: &Pubkey, recipient: &Pubkey) -> (Pubkey, u8) {
+        find_escrow_address_with_program_id(sender, recipient, &ID)
+    }
+
+    /// Derives the escrow PDA and bump for a program deployed at `program_id`.
+    ///
+    /// Useful for local validators and devnet deployments where the program
+    /// ID differs from the one declared in the program crate.
+    pub fn find_escrow_address_with_program_id(
+        sender: &Pubkey,
+        recipient: &Pubkey,
+        program_id: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[ESCROW, sender.as_ref(), recipient.as_ref()], program_id)
+    }
+
+    /// Derives the compliance list PDA and bump for a program deployed at `program_id`.
+    pub fn find_compliance_address_with_program_id(program_id: &Pubkey) -> (Pubkey, u8) {
+        Pubkey::find_program_address(&[COMPLIANCE], program_id)
+    }
+
+    /// Derives the stake escrow PDA and bump for a program deployed at `program_id`.
+    pub fn find_stake_escrow_address_with_program_id(
+        sender: &Pubkey,
+        recipient: &Pubkey,
+        stake_account: &Pubkey,
+        program_id: &Pubkey,
+    ) -> (Pubkey, u8) {
+        Pubkey::find_program_address(
+            &[
+                STAKE_ESCROW,
+                sender.as_ref(),
+                recipient.as_ref(),
+                stake_account.as_ref(),
+            ],
+            program_id,
+        )
+    }
+}
+
+/// Instruction builders for transactions that invoke the escrow program directly.
+pub mod instruction {
+    use anchor_lang::prelude::Pubkey;
+    use anchor_lang::solana_program::instruction::Instruction;
+    use anchor_lang::solana_program::{bpf_loader_upgradeable, sysvar};
+    use anchor_lang::{system_program, InstructionData, ToAccountMetas};
+    use escrow::{accounts, instruction as ix};
+
+    use super::pda::find_compliance_address_with_program_id;
+    use super::{ComplianceMode, CreateEscrowArgs, CreateStakeEscrowArgs, UpdateComplianceArgs};
+
+    fn compliance(program_id: &Pubkey) -> Pubkey {
+        find_compliance_address_with_program_id(program_id).0
+    }
+
+    /// Builds a `create_escrow` instruction.
+    ///
+    /// `sender` must sign the transaction and fund the escrow.
+    pub fn create_escrow(
+        program_id: Pubkey,
+        sender: Pubkey,
+        recipient: Pubkey,
+        escrow_account: Pubkey,
+        args: CreateEscrowArgs,
+    ) -> Instruction {
+        Instruction {
+            program_id,
+            accounts: accounts::CreateEscrow {
+                sender,
+                recipient,
+                escrow_account,
+                compliance: compliance(&program_id),
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: ix::CreateEscrow { args }.data(),
+        }
+    }
+
+    /// Builds a `finish_escrow` instruction.
+    ///
+    /// `recipient` must sign the transaction. `rent_destination` must be the
+    /// account recorded on the escrow at creation, if one was set.
+    pub fn finish_escrow(
+        program_id: Pubkey,
+        recipient: Pubkey,
+        escrow_account: Pubkey,
+        rent_destination: Option<Pubkey>,
+    ) -> Instruction {
+        Instruction {
+            program_id,
+            accounts: accounts::FinishEscrow {
+                recipient,
+                escrow_account,
+                compliance: compliance(&program_id),
+                rent_destination,
+            }
+            .to_account_metas(None),
+            data: ix::FinishEscrow {}.data(),
+        }
+    }
+
+    /// Builds a `finish_escrow_with_vaa` instruction.
+    ///
+    /// `payer` must sign the transaction; anyone may. `posted_vaa` must be a
+    /// VAA posted by the Wormhole core bridge that matches the escrow's
+    /// bridge trigger, and `rent_destination` the account recorded on the
+    /// escrow at creation, if one was set.
+    pub fn finish_escrow_with_vaa(
+        program_id: Pubkey,
+        payer: Pubkey,
+        recipient: Pubkey,
+        escrow_account: Pubkey,
+        posted_vaa: Pubkey,
+        rent_destination: Option<Pubkey>,
+    ) -> Instruction {
+        Instruction {
+            program_id,
+            accounts: accounts::FinishEscrowWithVaa {
+                payer,
+                recipient,
+                escrow_account,
+                posted_vaa,
+                compliance: compliance(&program_id),
+                rent_destination,
+            }
+            .to_account_metas(None),
+            data: ix::FinishEscrowWithVaa {}.data(),
+        }
+    }
+
+    /// Builds a `cancel_escrow` instruction.
+    ///
+    /// `sender` must sign the transaction. `rent_destination` must be the
+    /// account recorded on the escrow at creation, if one was set.
+    pub fn cancel_escrow(
+        program_id: Pubkey,
+        sender: Pubkey,
+        escrow_account: Pubkey,
+        rent_destination: Option<Pubkey>,
+    ) -> Instruction {
+        Instruction {
+            program_id,
+            accounts: accounts::CancelEscrow {
+                sender,
+                escrow_account,
+                rent_destination,
+            }
+            .to_account_metas(None),
+            data: ix::CancelEscrow {}.data(),
+        }
+    }
+
+    /// Builds a `create_stake_escrow` instruction.
+    ///
+    /// `sender` must sign the transaction and be both the staker and
+    /// withdrawer authority of `stake_account`.
+    pub fn create_stake_escrow(
+        program_id: Pubkey,
+        sender: Pubkey,
+        recipient: Pubkey,
+        stake_account: Pubkey,
+        escrow_account: Pubkey,
+        args: CreateStakeEscrowArgs,
+    ) -> Instruction {
+        Instruction {
+            program_id,
+            accounts: accounts::CreateStakeEscrow {
+                sender,
+                recipient,
+                stake_account,
+                escrow_account,
+                compliance: compliance(&program_id),
+                stake_program: solana_stake_interface::program::ID,
+                clock: sysvar::clock::ID,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: ix::CreateStakeEscrow { args }.data(),
+        }
+    }
+
+    /// Builds a `finish_stake_escrow` instruction.
+    ///
+    /// `recipient` must sign the transaction.
+    pub fn finish_stake_escrow(
+        program_id: Pubkey,
+        recipient: Pubkey,
+        stake_account: Pubkey,
+        escrow_account: Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id,
+            accounts: accounts::FinishStakeEscrow {
+                recipient,
+                escrow_account,
+                stake_account,
+                compliance: compliance(&program_id),
+                stake_program: solana_stake_interface::program::ID,
+                clock: sysvar::clock::ID,
+            }
+            .to_account_metas(None),
+            data: ix::FinishStakeEscrow {}.data(),
+        }
+    }
+
+    /// Builds a `cancel_stake_escrow` instruction.
+    ///
+    /// `sender` must sign the transaction.
+    pub fn cancel_stake_escrow(
+        program_id: Pubkey,
+        sender: Pubkey,
+        stake_account: Pubkey,
+        escrow_account: Pubkey,
+    ) -> Instruction {
+        Instruction {
+            program_id,
+            accounts: accounts::CancelStakeEscrow {
+                sender,
+                escrow_account,
+                stake_account,
+                stake_program: solana_stake_interface::program::ID,
+                clock: sysvar::clock::ID,
+            }
+            .to_account_metas(None),
+            data: ix::CancelStakeEscrow {}.data(),
+        }
+    }
+
+    /// Builds an `initialize_compliance` instruction.
+    ///
+    /// `payer` must sign the transaction and be the program's upgrade authority.
+    pub fn initialize_compliance(
+        program_id: Pubkey,
+        payer: Pubkey,
+        authority: Pubkey,
+        mode: ComplianceMode,
+    ) -> Instruction {
+        let (program_data, _) =
+            Pubkey::find_program_address(&[program_id.as_ref()], &bpf_loader_upgradeable::ID);
+        Instruction {
+            program_id,
+            accounts: accounts::InitializeCompliance {
+                payer,
+                compliance: compliance(&program_id),
+                program: program_id,
+                program_data,
+                system_program: system_program::ID,
+            }
+            .to_account_metas(None),
+            data: ix::InitializeCompliance { authority, mode }.data(),
+        }
+    }
+
+    /// Builds a `set_compliance_entry` instruction.
+    ///
+    /// `authority` must sign the transaction.
+    pub fn set_compliance_entry(
+        program_id: Pubkey,
+        authority: Pubkey,
+        address: Pubkey,
+        listed: bool,
+    ) -> Instruction {
+        Instruction {
+            program_id,
+            accounts: accounts::UpdateCompliance {
+                authority,
+                compliance: compliance(&program_id),
+            }
+            .to_account_metas(None),
+            data: ix::SetComplianceEntry { address, listed }.data(),
+        }
+    }
+
+    /// Builds an `update_compliance` instruction.
+    ///
+    /// `authority` must sign the transaction.
+    pub fn update_compliance(
+        program_id: Pubkey,
+        authority: Pubkey,
+        args: UpdateComplianceArgs,
+    ) -> Instruction {
+        Instruction {
+            program_id,
+            accounts: accounts::UpdateCompliance {
+                authority,
+                compliance: compliance(&program_id),
+            }
+            .to_account_metas(None),
+            data: ix::UpdateCompliance { args }.data(),
+        }
+    }
+}
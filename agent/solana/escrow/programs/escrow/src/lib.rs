@@ -4,16 +4,87 @@
 //! for releasing or canceling escrowed funds. It follows XRPL-style semantics
 //! where at least one resolution path (`finish_after` or `cancel_after`) must
 //! be specified.
+//!
+//! # Compute budget
+//!
+//! `finish_*` and `cancel_*` are kept lean so they can share a transaction
+//! with token transfers or proof verification without exceeding the default
+//! 200k compute-unit limit:
+//! - Caller authorization is enforced by the PDA seeds plus `has_one`
+//!   constraints, so handlers do not re-check keys.
+//! - The `Clock` sysvar is only read when the relevant timelock is set; stake
+//!   escrows reuse the `clock` account already required by the stake program.
+//! - Lamport escrows are paid out with direct lamport arithmetic on the
+//!   program-owned PDA rather than a system-program CPI.
+//! - Stored bumps are used for PDA validation (`create_program_address`)
+//!   instead of re-running `find_program_address`.
+//!   The compliance PDA is the exception, since it may not exist yet.
+//!
+//! # Compliance
+//!
+//! Deployments can initialize a single compliance PDA (seed [`COMPLIANCE`])
+//! holding an allow or deny list administered by a configurable authority.
+//! `create_*` and `finish_*` consult it for both counterparties; if it has
+//! never been initialized, every address is permitted.
+//!
+//! Measure actual usage with `anchor test` or `solana logs`, which report
+//! `consumed N of M compute units` per instruction.
+//!
+//! # Escrow IDs
+//!
+//! `create_escrow` emits the escrow's chain-agnostic ID (see
+//! `zescrow_core::EscrowId`), whose nonce is the creation slot. Programs
+//! cannot read the cluster's CAIP-2 ID, so the caller passes its SHA-256
+//! digest.
+//!
+//! # Bridge triggers
+//!
+//! An escrow created with a [`BridgeTrigger`] can also be finished by
+//! anyone, e.g. a relayer, with `finish_escrow_with_vaa`, once a Wormhole
+//! message from the trigger's emitter carrying its payload was verified and
+//! posted by Wormhole's core bridge ([`WORMHOLE_CORE_BRIDGE`], or its devnet
+//! deployment with the `devnet` feature). The guardian signatures are
+//! checked by the core bridge, so finishing only reads the posted VAA.
 
 use anchor_lang::prelude::*;
 use anchor_lang::solana_program::clock::Clock;
+use anchor_lang::solana_program::program::invoke_signed;
 use anchor_lang::system_program;
+use solana_sha256_hasher::{hash, hashv};
+use solana_stake_interface::instruction as stake_instruction;
+use solana_stake_interface::program::ID as STAKE_PROGRAM_ID;
+use solana_stake_interface::state::StakeAuthorize;
 
 declare_id!("J4SfUoLAAsvmAWMQGa8dJHw8vsSvRfUUMXGTxcmSeS8s");
 
 /// Seed prefix for PDA derivation.
 pub const ESCROW: &[u8] = b"escrow";
 
+/// Seed prefix for stake escrow PDA derivation.
+pub const STAKE_ESCROW: &[u8] = b"stake_escrow";
+
+/// Seed for the compliance list PDA.
+pub const COMPLIANCE: &[u8] = b"compliance";
+
+/// Maximum number of addresses a compliance list can hold.
+pub const MAX_COMPLIANCE_ENTRIES: usize = 64;
+
+/// Length in bytes of the optional escrow memo (e.g., an invoice ID or URI hash).
+pub const MEMO_LEN: usize = 64;
+
+/// Domain separator of chain-agnostic escrow IDs.
+pub const ESCROW_ID_DOMAIN: &[u8] = b"zescrow/escrow-id/v1";
+
+/// Wormhole core bridge program, which owns the posted VAAs that finish
+/// escrows with a bridge trigger.
+#[cfg(not(feature = "devnet"))]
+pub const WORMHOLE_CORE_BRIDGE: Pubkey = pubkey!("worm2ZoG2kUd4vFXhvjh93UUH596ayRfgQ2MgjNMTth");
+
+/// Wormhole core bridge program, which owns the posted VAAs that finish
+/// escrows with a bridge trigger.
+#[cfg(feature = "devnet")]
+pub const WORMHOLE_CORE_BRIDGE: Pubkey = pubkey!("3u8hJUVTA4jH1wYAyUur7FFZVQ8H635K3tSHHF4ssjQ5");
+
 #[program]
 pub mod escrow {
     use super::*;
@@ -33,6 +104,10 @@ pub mod escrow {
         }
         // Amount cannot be zero
         require!(args.amount > 0, EscrowError::InvalidAmount);
+        ensure_permitted(
+            &ctx.accounts.compliance,
+            &[ctx.accounts.sender.key(), ctx.accounts.recipient.key()],
+        )?;
 
         // Transfer lamports into the PDA
         let cpi_ctx = CpiContext::new(
@@ -51,13 +126,25 @@ pub mod escrow {
         escrow.amount = args.amount;
         escrow.finish_after = args.finish_after;
         escrow.cancel_after = args.cancel_after;
+        escrow.memo = args.memo;
+        escrow.rent_destination = args.rent_destination;
+        escrow.bridge_trigger = args.bridge_trigger;
         escrow.bump = ctx.bumps.escrow_account;
 
+        let uid = escrow_uid(
+            &args.chain,
+            &escrow.sender,
+            &escrow.recipient,
+            Clock::get()?.slot,
+            escrow.amount,
+        );
         emit!(EscrowEvent {
             sender: escrow.sender,
             recipient: escrow.recipient,
             amount: escrow.amount,
-            action: EscrowState::Created
+            memo: escrow.memo,
+            action: EscrowState::Created,
+            uid: Some(uid),
         });
 
         Ok(())
@@ -67,24 +154,72 @@ pub mod escrow {
     /// - If `finish_after` is `Some(t)`, require current slot >= t.  
     /// - If `finish_after` is `None`, allow immediate release.  
     /// - Only callable by `recipient`.
+    /// - Escrowed lamports go to `recipient`; rent goes to the configured
+    ///   `rent_destination`, or to `recipient` if none was set.
     pub fn finish_escrow(ctx: Context<FinishEscrow>) -> Result<()> {
         let escrow = &ctx.accounts.escrow_account;
-        let current_slot = Clock::get()?.slot;
 
-        require!(
-            ctx.accounts.recipient.key() == escrow.recipient,
-            EscrowError::Unauthorized
-        );
+        if let Some(t) = escrow.finish_after {
+            require!(Clock::get()?.slot >= t, EscrowError::NotReady);
+        }
+        ensure_permitted(&ctx.accounts.compliance, &[escrow.sender, escrow.recipient])?;
+
+        let rent_destination = resolve_rent_destination(
+            escrow.rent_destination,
+            ctx.accounts.rent_destination.as_ref(),
+            ctx.accounts.recipient.to_account_info(),
+        )?;
+        escrow.sub_lamports(escrow.amount)?;
+        ctx.accounts.recipient.add_lamports(escrow.amount)?;
+        escrow.close(rent_destination)?;
+
+        emit!(EscrowEvent {
+            sender: escrow.sender,
+            recipient: escrow.recipient,
+            amount: escrow.amount,
+            memo: escrow.memo,
+            action: EscrowState::Finished,
+            uid: None,
+        });
+
+        Ok(())
+    }
 
+    /// Releases an escrow created with a bridge trigger on a Wormhole
+    /// message matching it:
+    /// - `posted_vaa` must be a VAA posted by the Wormhole core bridge, from
+    ///   the trigger's emitter and with its payload.
+    /// - If `finish_after` is `Some(t)`, require current slot >= t.
+    /// - Callable by anyone; the `payer` only pays the transaction fees.
+    /// - Escrowed lamports go to `recipient`; rent goes to the configured
+    ///   `rent_destination`, or to `recipient` if none was set.
+    pub fn finish_escrow_with_vaa(ctx: Context<FinishEscrowWithVaa>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+
+        let trigger = escrow.bridge_trigger.ok_or(EscrowError::NoBridgeTrigger)?;
         if let Some(t) = escrow.finish_after {
-            require!(current_slot >= t, EscrowError::NotReady);
+            require!(Clock::get()?.slot >= t, EscrowError::NotReady);
         }
+        let vaa = PostedVaa::try_from_account(&ctx.accounts.posted_vaa)?;
+        require!(trigger.matches(&vaa), EscrowError::BridgeMessageMismatch);
+        ensure_permitted(&ctx.accounts.compliance, &[escrow.sender, escrow.recipient])?;
+
+        let rent_destination = resolve_rent_destination(
+            escrow.rent_destination,
+            ctx.accounts.rent_destination.as_ref(),
+            ctx.accounts.recipient.to_account_info(),
+        )?;
+        escrow.sub_lamports(escrow.amount)?;
+        ctx.accounts.recipient.add_lamports(escrow.amount)?;
+        escrow.close(rent_destination)?;
 
         emit!(EscrowEvent {
             sender: escrow.sender,
             recipient: escrow.recipient,
             amount: escrow.amount,
-            action: EscrowState::Finished
+            memo: escrow.memo,
+            action: EscrowState::Finished,
+            uid: None,
         });
 
         Ok(())
@@ -94,28 +229,314 @@ pub mod escrow {
     /// - Requires `cancel_after` to be `Some(t)`.  
     /// - Current slot >= t.  
     /// - Only callable by the original `sender`.
+    /// - Escrowed lamports go back to `sender`; rent goes to the configured
+    ///   `rent_destination`, or to `sender` if none was set.
     pub fn cancel_escrow(ctx: Context<CancelEscrow>) -> Result<()> {
         let escrow = &ctx.accounts.escrow_account;
-        let current_slot = Clock::get()?.slot;
 
-        require!(
-            ctx.accounts.sender.key() == escrow.sender,
-            EscrowError::Unauthorized
-        );
         // Must have set a `cancel_after`
-        require!(escrow.cancel_after.is_some(), EscrowError::CancelNotAllowed);
-        let t = escrow.cancel_after.unwrap();
-        require!(current_slot >= t, EscrowError::NotExpired);
+        let t = escrow.cancel_after.ok_or(EscrowError::CancelNotAllowed)?;
+        require!(Clock::get()?.slot >= t, EscrowError::NotExpired);
+
+        let rent_destination = resolve_rent_destination(
+            escrow.rent_destination,
+            ctx.accounts.rent_destination.as_ref(),
+            ctx.accounts.sender.to_account_info(),
+        )?;
+        escrow.sub_lamports(escrow.amount)?;
+        ctx.accounts.sender.add_lamports(escrow.amount)?;
+        escrow.close(rent_destination)?;
 
         emit!(EscrowEvent {
             sender: escrow.sender,
             recipient: escrow.recipient,
             amount: escrow.amount,
+            memo: escrow.memo,
+            action: EscrowState::Cancelled,
+            uid: None,
+        });
+
+        Ok(())
+    }
+
+    /// Creates a stake escrow by transferring the staker and withdrawer
+    /// authorities of `stake_account` from `sender` to the escrow PDA.
+    ///
+    /// Enforces the same timelock guards as `create_escrow`. The stake keeps
+    /// earning rewards while escrowed; the delegation itself is untouched.
+    pub fn create_stake_escrow(
+        ctx: Context<CreateStakeEscrow>,
+        args: CreateStakeEscrowArgs,
+    ) -> Result<()> {
+        require!(
+            args.finish_after.is_some() || args.cancel_after.is_some(),
+            EscrowError::MustSpecifyPath
+        );
+        if let (Some(finish), Some(cancel)) = (args.finish_after, args.cancel_after) {
+            require!(finish < cancel, EscrowError::InvalidTimeOrder);
+        }
+        ensure_permitted(
+            &ctx.accounts.compliance,
+            &[ctx.accounts.sender.key(), ctx.accounts.recipient.key()],
+        )?;
+
+        transfer_stake_authority(
+            ctx.accounts.stake_program.to_account_info(),
+            ctx.accounts.stake_account.to_account_info(),
+            ctx.accounts.sender.to_account_info(),
+            ctx.accounts.escrow_account.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            &[],
+        )?;
+
+        let stake_lamports = ctx.accounts.stake_account.to_account_info().lamports();
+        let escrow = &mut ctx.accounts.escrow_account;
+
+        escrow.sender = ctx.accounts.sender.key();
+        escrow.recipient = ctx.accounts.recipient.key();
+        escrow.stake_account = ctx.accounts.stake_account.key();
+        escrow.finish_after = args.finish_after;
+        escrow.cancel_after = args.cancel_after;
+        escrow.memo = args.memo;
+        escrow.bump = ctx.bumps.escrow_account;
+
+        emit!(StakeEscrowEvent {
+            sender: escrow.sender,
+            recipient: escrow.recipient,
+            stake_account: escrow.stake_account,
+            lamports: stake_lamports,
+            memo: escrow.memo,
+            action: EscrowState::Created
+        });
+
+        Ok(())
+    }
+
+    /// Releases a stake escrow by handing both stake authorities to `recipient`.
+    /// - If `finish_after` is `Some(t)`, require current slot >= t.
+    /// - Only callable by `recipient`.
+    pub fn finish_stake_escrow(ctx: Context<FinishStakeEscrow>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+
+        if let Some(t) = escrow.finish_after {
+            require!(ctx.accounts.clock.slot >= t, EscrowError::NotReady);
+        }
+        ensure_permitted(&ctx.accounts.compliance, &[escrow.sender, escrow.recipient])?;
+
+        transfer_stake_authority(
+            ctx.accounts.stake_program.to_account_info(),
+            ctx.accounts.stake_account.to_account_info(),
+            ctx.accounts.escrow_account.to_account_info(),
+            ctx.accounts.recipient.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            &[&escrow.signer_seeds()[..]],
+        )?;
+
+        emit!(StakeEscrowEvent {
+            sender: escrow.sender,
+            recipient: escrow.recipient,
+            stake_account: escrow.stake_account,
+            lamports: ctx.accounts.stake_account.to_account_info().lamports(),
+            memo: escrow.memo,
+            action: EscrowState::Finished
+        });
+
+        Ok(())
+    }
+
+    /// Cancels a stake escrow by handing both stake authorities back to `sender`.
+    /// - Requires `cancel_after` to be `Some(t)` and current slot >= t.
+    /// - Only callable by the original `sender`.
+    pub fn cancel_stake_escrow(ctx: Context<CancelStakeEscrow>) -> Result<()> {
+        let escrow = &ctx.accounts.escrow_account;
+
+        let t = escrow.cancel_after.ok_or(EscrowError::CancelNotAllowed)?;
+        require!(ctx.accounts.clock.slot >= t, EscrowError::NotExpired);
+
+        transfer_stake_authority(
+            ctx.accounts.stake_program.to_account_info(),
+            ctx.accounts.stake_account.to_account_info(),
+            ctx.accounts.escrow_account.to_account_info(),
+            ctx.accounts.sender.to_account_info(),
+            ctx.accounts.clock.to_account_info(),
+            &[&escrow.signer_seeds()[..]],
+        )?;
+
+        emit!(StakeEscrowEvent {
+            sender: escrow.sender,
+            recipient: escrow.recipient,
+            stake_account: escrow.stake_account,
+            lamports: ctx.accounts.stake_account.to_account_info().lamports(),
+            memo: escrow.memo,
             action: EscrowState::Cancelled
         });
 
         Ok(())
     }
+
+    /// Initializes the compliance list PDA.
+    ///
+    /// Only callable by the program's upgrade authority, which hands
+    /// administration of the list to `authority`.
+    pub fn initialize_compliance(
+        ctx: Context<InitializeCompliance>,
+        authority: Pubkey,
+        mode: ComplianceMode,
+    ) -> Result<()> {
+        let compliance = &mut ctx.accounts.compliance;
+        compliance.authority = authority;
+        compliance.mode = mode;
+        compliance.entries = Vec::new();
+        compliance.bump = ctx.bumps.compliance;
+        Ok(())
+    }
+
+    /// Adds `address` to (`listed = true`) or removes it from the compliance list.
+    /// - Only callable by the compliance authority.
+    pub fn set_compliance_entry(
+        ctx: Context<UpdateCompliance>,
+        address: Pubkey,
+        listed: bool,
+    ) -> Result<()> {
+        let compliance = &mut ctx.accounts.compliance;
+        let position = compliance.entries.iter().position(|a| *a == address);
+
+        match (listed, position) {
+            (true, None) => {
+                require!(
+                    compliance.entries.len() < MAX_COMPLIANCE_ENTRIES,
+                    EscrowError::ComplianceListFull
+                );
+                compliance.entries.push(address);
+            }
+            (false, Some(i)) => {
+                compliance.entries.swap_remove(i);
+            }
+            _ => {}
+        }
+
+        emit!(ComplianceEvent {
+            address,
+            listed,
+            mode: compliance.mode,
+        });
+
+        Ok(())
+    }
+
+    /// Changes the list mode and/or hands administration to a new authority.
+    /// - Only callable by the compliance authority.
+    pub fn update_compliance(
+        ctx: Context<UpdateCompliance>,
+        args: UpdateComplianceArgs,
+    ) -> Result<()> {
+        let compliance = &mut ctx.accounts.compliance;
+        if let Some(mode) = args.mode {
+            compliance.mode = mode;
+        }
+        if let Some(authority) = args.authority {
+            compliance.authority = authority;
+        }
+        Ok(())
+    }
+}
+
+/// Fails with [`EscrowError::AddressBlocked`] if the compliance list rejects
+/// any of `parties`.
+///
+/// An uninitialized compliance PDA (not owned by this program) permits everyone.
+fn ensure_permitted(compliance: &UncheckedAccount, parties: &[Pubkey]) -> Result<()> {
+    if compliance.owner != &crate::ID {
+        return Ok(());
+    }
+    let list = ComplianceList::try_deserialize(&mut &compliance.try_borrow_data()?[..])?;
+    require!(
+        parties.iter().all(|party| list.permits(party)),
+        EscrowError::AddressBlocked
+    );
+    Ok(())
+}
+
+/// Picks the account credited with the escrow PDA's rent on close.
+///
+/// Falls back to `closer` when the escrow has no configured destination;
+/// otherwise the matching account must have been supplied (the key itself
+/// is checked by the account constraint).
+fn resolve_rent_destination<'info>(
+    configured: Option<Pubkey>,
+    supplied: Option<&UncheckedAccount<'info>>,
+    closer: AccountInfo<'info>,
+) -> Result<AccountInfo<'info>> {
+    match (configured, supplied) {
+        (None, _) => Ok(closer),
+        (Some(_), Some(destination)) => Ok(destination.to_account_info()),
+        (Some(_), None) => err!(EscrowError::MissingRentDestination),
+    }
+}
+
+/// Reassigns both the staker and withdrawer authorities of `stake` from
+/// `authorized` to `new_authorized`, signing with `signer_seeds` when the
+/// current authority is the escrow PDA.
+///
+/// Stake accounts with an active lockup require the custodian to sign the
+/// withdrawer change and are therefore not supported.
+fn transfer_stake_authority<'info>(
+    stake_program: AccountInfo<'info>,
+    stake: AccountInfo<'info>,
+    authorized: AccountInfo<'info>,
+    new_authorized: AccountInfo<'info>,
+    clock: AccountInfo<'info>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    for stake_authorize in [StakeAuthorize::Staker, StakeAuthorize::Withdrawer] {
+        let ix = stake_instruction::authorize(
+            stake.key,
+            authorized.key,
+            new_authorized.key,
+            stake_authorize,
+            None,
+        );
+        invoke_signed(
+            &ix,
+            &[
+                stake.clone(),
+                clock.clone(),
+                authorized.clone(),
+                stake_program.clone(),
+            ],
+            signer_seeds,
+        )?;
+    }
+    Ok(())
+}
+
+/// Chain-agnostic ID of a lamport escrow created at `slot`, as computed by
+/// `zescrow_core::EscrowId`, given the digest of the cluster's CAIP-2 ID.
+pub fn escrow_uid(
+    chain: &[u8; 32],
+    sender: &Pubkey,
+    recipient: &Pubkey,
+    slot: u64,
+    amount: u64,
+) -> [u8; 32] {
+    let word = |value: u64| {
+        let mut word = [0; 32];
+        word[24..].copy_from_slice(&value.to_be_bytes());
+        word
+    };
+    let parts = [
+        *chain,
+        hash(crate::ID.as_ref()).to_bytes(),
+        hash(sender.as_ref()).to_bytes(),
+        hash(recipient.as_ref()).to_bytes(),
+        hash(&word(slot)).to_bytes(),
+        hash(&[]).to_bytes(),
+    ];
+    let mut data: Vec<&[u8]> = vec![ESCROW_ID_DOMAIN];
+    data.extend(parts.iter().map(|part| part.as_slice()));
+    let amount = word(amount);
+    data.push(&amount);
+    hashv(&data).to_bytes()
 }
 
 /// Escrow account data, stored in a PDA.
@@ -131,10 +552,150 @@ pub struct Escrow {
     pub finish_after: Option<u64>,
     /// Optional slot after which sender can reclaim funds
     pub cancel_after: Option<u64>,
+    /// Optional memo for correlating the escrow with off-chain records
+    pub memo: Option<[u8; 64]>,
+    /// Optional account credited with the PDA rent on close
+    /// (defaults to whoever closes the escrow)
+    pub rent_destination: Option<Pubkey>,
+    /// Optional Wormhole message that lets anyone finish the escrow
+    pub bridge_trigger: Option<BridgeTrigger>,
     /// PDA bump seed for address validation.
     pub bump: u8,
 }
 
+/// Wormhole message that lets anyone finish an escrow with
+/// `finish_escrow_with_vaa`.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub struct BridgeTrigger {
+    /// Wormhole chain ID of the emitter's chain.
+    pub emitter_chain: u16,
+    /// Address of the emitter, left-padded to 32 bytes.
+    pub emitter: [u8; 32],
+    /// SHA-256 digest of the message payload.
+    pub payload_hash: [u8; 32],
+}
+
+impl BridgeTrigger {
+    /// Returns `true` if `vaa` is from the emitter and carries the payload.
+    pub fn matches(&self, vaa: &PostedVaa) -> bool {
+        vaa.emitter_chain == self.emitter_chain
+            && vaa.emitter_address == self.emitter
+            && hash(&vaa.payload).to_bytes() == self.payload_hash
+    }
+}
+
+/// Message of a VAA posted by the Wormhole core bridge, stored after the
+/// [`PostedVaa::PREFIX`] of its account data.
+#[derive(AnchorDeserialize)]
+pub struct PostedVaa {
+    /// Version of the VAA.
+    pub vaa_version: u8,
+    /// Finality the guardians waited for before signing.
+    pub consistency_level: u8,
+    /// Unix time of the block the message was sent in.
+    pub vaa_time: u32,
+    /// Account holding the verified guardian signatures.
+    pub vaa_signature_account: Pubkey,
+    /// Unix time the VAA was posted.
+    pub submission_time: u32,
+    /// Nonce chosen by the emitter.
+    pub nonce: u32,
+    /// Sequence number of the message among the emitter's.
+    pub sequence: u64,
+    /// Wormhole chain ID of the emitter's chain.
+    pub emitter_chain: u16,
+    /// Address of the emitter, left-padded to 32 bytes.
+    pub emitter_address: [u8; 32],
+    /// The message payload.
+    pub payload: Vec<u8>,
+}
+
+impl PostedVaa {
+    /// Prefix of posted VAA accounts, unlike those of messages the core
+    /// bridge has not verified.
+    pub const PREFIX: &'static [u8] = b"vaa";
+
+    /// Reads the posted VAA in `account`, whose owner is checked by the
+    /// account constraint.
+    fn try_from_account(account: &UncheckedAccount) -> Result<Self> {
+        let data = account.try_borrow_data()?;
+        let message = data
+            .strip_prefix(Self::PREFIX)
+            .ok_or(EscrowError::InvalidVaa)?;
+        AnchorDeserialize::deserialize(&mut &message[..])
+            .map_err(|_| error!(EscrowError::InvalidVaa))
+    }
+}
+
+/// Stake escrow account data, stored in a PDA that holds the stake
+/// account's staker and withdrawer authorities while escrowed.
+#[account]
+pub struct StakeEscrow {
+    /// Original stake authority that initialized the escrow
+    pub sender: Pubkey,
+    /// Intended beneficiary of the stake account
+    pub recipient: Pubkey,
+    /// Escrowed stake account
+    pub stake_account: Pubkey,
+    /// Optional slot after which the stake can be released
+    pub finish_after: Option<u64>,
+    /// Optional slot after which sender can reclaim the stake
+    pub cancel_after: Option<u64>,
+    /// Optional memo for correlating the escrow with off-chain records
+    pub memo: Option<[u8; 64]>,
+    /// PDA bump seed for address validation.
+    pub bump: u8,
+}
+
+/// Compliance list consulted when creating and finishing escrows.
+#[account]
+pub struct ComplianceList {
+    /// Account allowed to edit the list
+    pub authority: Pubkey,
+    /// Whether `entries` is an allow list or a deny list
+    pub mode: ComplianceMode,
+    /// Listed addresses, at most [`MAX_COMPLIANCE_ENTRIES`]
+    pub entries: Vec<Pubkey>,
+    /// PDA bump seed for address validation.
+    pub bump: u8,
+}
+
+impl ComplianceList {
+    /// Account size, including the discriminator and a full `entries` vector.
+    pub const SPACE: usize = 8 + 32 + 1 + (4 + 32 * MAX_COMPLIANCE_ENTRIES) + 1;
+
+    /// Returns `true` if `address` may take part in an escrow.
+    pub fn permits(&self, address: &Pubkey) -> bool {
+        let listed = self.entries.contains(address);
+        match self.mode {
+            ComplianceMode::Allow => listed,
+            ComplianceMode::Deny => !listed,
+        }
+    }
+}
+
+/// How the compliance list's entries are interpreted.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum ComplianceMode {
+    /// Only listed addresses may take part in escrows.
+    Allow,
+    /// Listed addresses are blocked; everyone else may take part.
+    Deny,
+}
+
+impl StakeEscrow {
+    /// PDA signer seeds used when the escrow hands the stake authorities back out.
+    fn signer_seeds(&self) -> [&[u8]; 5] {
+        [
+            STAKE_ESCROW,
+            self.sender.as_ref(),
+            self.recipient.as_ref(),
+            self.stake_account.as_ref(),
+            std::slice::from_ref(&self.bump),
+        ]
+    }
+}
+
 /// Context for `create_escrow` transaction.
 #[derive(Accounts)]
 #[instruction(args: CreateEscrowArgs)]
@@ -162,6 +723,12 @@ pub struct CreateEscrow<'info> {
     )]
     pub escrow_account: Account<'info, Escrow>,
 
+    /// Compliance list PDA; may be uninitialized.
+    ///
+    /// CHECK: address enforced via seeds; only deserialized if owned by this program.
+    #[account(seeds = [COMPLIANCE], bump)]
+    pub compliance: UncheckedAccount<'info>,
+
     /// System program for lamport transfers
     pub system_program: Program<'info, System>,
 }
@@ -177,6 +744,17 @@ pub struct CreateEscrowArgs {
     /// Optional slot after which "cancel" is allowed.
     /// Must be `None` or greater than `finish_after` if both are set.
     pub cancel_after: Option<u64>,
+    /// Optional memo (e.g., invoice ID or URI hash), zero-padded to [`MEMO_LEN`] bytes.
+    pub memo: Option<[u8; 64]>,
+    /// Optional account (sender, recipient, or a third party) credited with
+    /// the PDA rent on close. If `None`, rent goes to whoever closes the escrow.
+    pub rent_destination: Option<Pubkey>,
+    /// SHA-256 digest of the cluster's CAIP-2 ID, hashed into the escrow's
+    /// chain-agnostic ID.
+    pub chain: [u8; 32],
+    /// Optional Wormhole message that lets anyone finish the escrow with
+    /// `finish_escrow_with_vaa`.
+    pub bridge_trigger: Option<BridgeTrigger>,
 }
 
 /// Context for `finish_escrow`.
@@ -186,14 +764,74 @@ pub struct FinishEscrow<'info> {
     #[account(mut)]
     pub recipient: Signer<'info>,
 
-    /// PDA holding the escrow, closed to recipient on success
+    /// PDA holding the escrow, closed on success
     #[account(
         mut,
         seeds = [ESCROW, escrow_account.sender.as_ref(), recipient.key().as_ref()],
         bump = escrow_account.bump,
-        close = recipient
+        has_one = recipient @ EscrowError::Unauthorized
+    )]
+    pub escrow_account: Account<'info, Escrow>,
+
+    /// Compliance list PDA; may be uninitialized.
+    ///
+    /// CHECK: address enforced via seeds; only deserialized if owned by this program.
+    #[account(seeds = [COMPLIANCE], bump)]
+    pub compliance: UncheckedAccount<'info>,
+
+    /// Account credited with the PDA rent, required if the escrow set one.
+    ///
+    /// CHECK: must match `escrow_account.rent_destination`.
+    #[account(
+        mut,
+        constraint = Some(rent_destination.key()) == escrow_account.rent_destination
+            @ EscrowError::InvalidRentDestination
+    )]
+    pub rent_destination: Option<UncheckedAccount<'info>>,
+}
+
+/// Context for `finish_escrow_with_vaa`.
+#[derive(Accounts)]
+pub struct FinishEscrowWithVaa<'info> {
+    /// Account paying the transaction fees, e.g. a relayer
+    pub payer: Signer<'info>,
+
+    /// Recipient credited with the funds
+    ///
+    /// CHECK: must match `escrow_account.recipient`.
+    #[account(mut)]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// PDA holding the escrow, closed on success
+    #[account(
+        mut,
+        seeds = [ESCROW, escrow_account.sender.as_ref(), recipient.key().as_ref()],
+        bump = escrow_account.bump,
+        has_one = recipient @ EscrowError::Unauthorized
     )]
     pub escrow_account: Account<'info, Escrow>,
+
+    /// VAA verified and posted by the Wormhole core bridge
+    ///
+    /// CHECK: owner checked here; contents checked against the bridge trigger.
+    #[account(owner = WORMHOLE_CORE_BRIDGE @ EscrowError::InvalidVaa)]
+    pub posted_vaa: UncheckedAccount<'info>,
+
+    /// Compliance list PDA; may be uninitialized.
+    ///
+    /// CHECK: address enforced via seeds; only deserialized if owned by this program.
+    #[account(seeds = [COMPLIANCE], bump)]
+    pub compliance: UncheckedAccount<'info>,
+
+    /// Account credited with the PDA rent, required if the escrow set one.
+    ///
+    /// CHECK: must match `escrow_account.rent_destination`.
+    #[account(
+        mut,
+        constraint = Some(rent_destination.key()) == escrow_account.rent_destination
+            @ EscrowError::InvalidRentDestination
+    )]
+    pub rent_destination: Option<UncheckedAccount<'info>>,
 }
 
 /// Context for `cancel_escrow` transaction.
@@ -203,14 +841,240 @@ pub struct CancelEscrow<'info> {
     #[account(mut)]
     pub sender: Signer<'info>,
 
-    /// PDA holding the escrow, closed back to sender on success.
+    /// PDA holding the escrow, closed on success.
     #[account(
         mut,
         seeds = [ESCROW, sender.key().as_ref(), escrow_account.recipient.as_ref()],
         bump = escrow_account.bump,
-        close = sender
+        has_one = sender @ EscrowError::Unauthorized
     )]
     pub escrow_account: Account<'info, Escrow>,
+
+    /// Account credited with the PDA rent, required if the escrow set one.
+    ///
+    /// CHECK: must match `escrow_account.rent_destination`.
+    #[account(
+        mut,
+        constraint = Some(rent_destination.key()) == escrow_account.rent_destination
+            @ EscrowError::InvalidRentDestination
+    )]
+    pub rent_destination: Option<UncheckedAccount<'info>>,
+}
+
+/// Context for `create_stake_escrow` transaction.
+#[derive(Accounts)]
+#[instruction(args: CreateStakeEscrowArgs)]
+pub struct CreateStakeEscrow<'info> {
+    /// Current staker and withdrawer authority of `stake_account`
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    /// Recipient of the stake account; must differ from sender.
+    ///
+    /// CHECK: we enforce correctness via PDA seeds.
+    #[account(
+        constraint = recipient.key() != sender.key() @ EscrowError::InvalidRecipient
+    )]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// Stake account being escrowed.
+    ///
+    /// CHECK: owner enforced; the stake program validates its state and authorities.
+    #[account(mut, owner = STAKE_PROGRAM_ID @ EscrowError::InvalidStakeAccount)]
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// PDA that takes over the stake authorities.
+    #[account(
+        init,
+        payer = sender,
+        space = 8 + std::mem::size_of::<StakeEscrow>(),
+        seeds = [
+            STAKE_ESCROW,
+            sender.key().as_ref(),
+            recipient.key().as_ref(),
+            stake_account.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_account: Account<'info, StakeEscrow>,
+
+    /// Compliance list PDA; may be uninitialized.
+    ///
+    /// CHECK: address enforced via seeds; only deserialized if owned by this program.
+    #[account(seeds = [COMPLIANCE], bump)]
+    pub compliance: UncheckedAccount<'info>,
+
+    /// Native stake program
+    ///
+    /// CHECK: address enforced.
+    #[account(address = STAKE_PROGRAM_ID @ EscrowError::InvalidStakeProgram)]
+    pub stake_program: UncheckedAccount<'info>,
+
+    /// Clock sysvar required by the stake program's `Authorize` instruction
+    pub clock: Sysvar<'info, Clock>,
+
+    /// System program for PDA allocation
+    pub system_program: Program<'info, System>,
+}
+
+/// Arguments for `create_stake_escrow` transaction.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct CreateStakeEscrowArgs {
+    /// Optional slot after which "release" is allowed.
+    /// Must be `None` or less than `cancel_after` if both are set.
+    pub finish_after: Option<u64>,
+    /// Optional slot after which "cancel" is allowed.
+    /// Must be `None` or greater than `finish_after` if both are set.
+    pub cancel_after: Option<u64>,
+    /// Optional memo (e.g., invoice ID or URI hash), zero-padded to [`MEMO_LEN`] bytes.
+    pub memo: Option<[u8; 64]>,
+}
+
+/// Context for `finish_stake_escrow` transaction.
+#[derive(Accounts)]
+pub struct FinishStakeEscrow<'info> {
+    /// Recipient taking over the stake authorities
+    #[account(mut)]
+    pub recipient: Signer<'info>,
+
+    /// PDA holding the stake authorities, closed to recipient on success
+    #[account(
+        mut,
+        seeds = [
+            STAKE_ESCROW,
+            escrow_account.sender.as_ref(),
+            recipient.key().as_ref(),
+            escrow_account.stake_account.as_ref()
+        ],
+        bump = escrow_account.bump,
+        has_one = recipient @ EscrowError::Unauthorized,
+        close = recipient
+    )]
+    pub escrow_account: Account<'info, StakeEscrow>,
+
+    /// Escrowed stake account
+    ///
+    /// CHECK: address enforced against the escrow; the stake program validates the rest.
+    #[account(
+        mut,
+        address = escrow_account.stake_account,
+        owner = STAKE_PROGRAM_ID @ EscrowError::InvalidStakeAccount
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// Compliance list PDA; may be uninitialized.
+    ///
+    /// CHECK: address enforced via seeds; only deserialized if owned by this program.
+    #[account(seeds = [COMPLIANCE], bump)]
+    pub compliance: UncheckedAccount<'info>,
+
+    /// Native stake program
+    ///
+    /// CHECK: address enforced.
+    #[account(address = STAKE_PROGRAM_ID @ EscrowError::InvalidStakeProgram)]
+    pub stake_program: UncheckedAccount<'info>,
+
+    /// Clock sysvar required by the stake program's `Authorize` instruction
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Context for `cancel_stake_escrow` transaction.
+#[derive(Accounts)]
+pub struct CancelStakeEscrow<'info> {
+    /// Original stake authority reclaiming the stake account
+    #[account(mut)]
+    pub sender: Signer<'info>,
+
+    /// PDA holding the stake authorities, closed back to sender on success.
+    #[account(
+        mut,
+        seeds = [
+            STAKE_ESCROW,
+            sender.key().as_ref(),
+            escrow_account.recipient.as_ref(),
+            escrow_account.stake_account.as_ref()
+        ],
+        bump = escrow_account.bump,
+        has_one = sender @ EscrowError::Unauthorized,
+        close = sender
+    )]
+    pub escrow_account: Account<'info, StakeEscrow>,
+
+    /// Escrowed stake account
+    ///
+    /// CHECK: address enforced against the escrow; the stake program validates the rest.
+    #[account(
+        mut,
+        address = escrow_account.stake_account,
+        owner = STAKE_PROGRAM_ID @ EscrowError::InvalidStakeAccount
+    )]
+    pub stake_account: UncheckedAccount<'info>,
+
+    /// Native stake program
+    ///
+    /// CHECK: address enforced.
+    #[account(address = STAKE_PROGRAM_ID @ EscrowError::InvalidStakeProgram)]
+    pub stake_program: UncheckedAccount<'info>,
+
+    /// Clock sysvar required by the stake program's `Authorize` instruction
+    pub clock: Sysvar<'info, Clock>,
+}
+
+/// Context for `initialize_compliance` transaction.
+#[derive(Accounts)]
+pub struct InitializeCompliance<'info> {
+    /// Program upgrade authority, paying for the PDA
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// Compliance list PDA.
+    #[account(
+        init,
+        payer = payer,
+        space = ComplianceList::SPACE,
+        seeds = [COMPLIANCE],
+        bump
+    )]
+    pub compliance: Account<'info, ComplianceList>,
+
+    /// This program, used to locate its program data account
+    #[account(constraint = program.programdata_address()? == Some(program_data.key()))]
+    pub program: Program<'info, crate::program::Escrow>,
+
+    /// Program data account holding the upgrade authority
+    #[account(
+        constraint = program_data.upgrade_authority_address == Some(payer.key())
+            @ EscrowError::Unauthorized
+    )]
+    pub program_data: Account<'info, ProgramData>,
+
+    /// System program for PDA allocation
+    pub system_program: Program<'info, System>,
+}
+
+/// Context for `set_compliance_entry` and `update_compliance` transactions.
+#[derive(Accounts)]
+pub struct UpdateCompliance<'info> {
+    /// Current compliance authority
+    pub authority: Signer<'info>,
+
+    /// Compliance list PDA.
+    #[account(
+        mut,
+        seeds = [COMPLIANCE],
+        bump = compliance.bump,
+        has_one = authority @ EscrowError::Unauthorized
+    )]
+    pub compliance: Account<'info, ComplianceList>,
+}
+
+/// Arguments for `update_compliance` transaction.
+#[derive(AnchorSerialize, AnchorDeserialize)]
+pub struct UpdateComplianceArgs {
+    /// New list mode, if changing.
+    pub mode: Option<ComplianceMode>,
+    /// New compliance authority, if changing.
+    pub authority: Option<Pubkey>,
 }
 
 /// Events emitted by the escrow program.
@@ -222,8 +1086,40 @@ pub struct EscrowEvent {
     pub recipient: Pubkey,
     /// Escrow amount; must be nonzero
     pub amount: u64,
+    /// Optional memo supplied at creation
+    pub memo: Option<[u8; 64]>,
     /// What stage of the escrow lifecycle was just executed
     pub action: EscrowState,
+    /// Chain-agnostic escrow ID, emitted on creation
+    pub uid: Option<[u8; 32]>,
+}
+
+/// Events emitted by the escrow program for stake escrows.
+#[event]
+pub struct StakeEscrowEvent {
+    /// Original stake authority
+    pub sender: Pubkey,
+    /// Intended beneficiary
+    pub recipient: Pubkey,
+    /// Escrowed stake account
+    pub stake_account: Pubkey,
+    /// Stake account balance at the time of the event
+    pub lamports: u64,
+    /// Optional memo supplied at creation
+    pub memo: Option<[u8; 64]>,
+    /// What stage of the escrow lifecycle was just executed
+    pub action: EscrowState,
+}
+
+/// Emitted when an address is added to or removed from the compliance list.
+#[event]
+pub struct ComplianceEvent {
+    /// Address whose entry changed
+    pub address: Pubkey,
+    /// Whether the address is now listed
+    pub listed: bool,
+    /// Current list mode
+    pub mode: ComplianceMode,
 }
 
 /// Escrow lifecycle actions.
@@ -271,4 +1167,40 @@ pub enum EscrowError {
     /// `cancel_after` not yet reached.
     #[msg("Too early to cancel.")]
     NotExpired,
+
+    /// The escrow has a rent destination but none was supplied.
+    #[msg("Rent destination account is required for this escrow.")]
+    MissingRentDestination,
+
+    /// The supplied rent destination does not match the escrow.
+    #[msg("Rent destination does not match the escrow.")]
+    InvalidRentDestination,
+
+    /// A counterparty is rejected by the compliance list.
+    #[msg("Address is blocked by the compliance list.")]
+    AddressBlocked,
+
+    /// The compliance list has no room for another entry.
+    #[msg("Compliance list is full.")]
+    ComplianceListFull,
+
+    /// The escrow was created without a bridge trigger.
+    #[msg("Escrow has no bridge trigger.")]
+    NoBridgeTrigger,
+
+    /// The account is not a VAA posted by the Wormhole core bridge.
+    #[msg("Account is not a posted Wormhole VAA.")]
+    InvalidVaa,
+
+    /// The VAA is from another emitter or carries another payload.
+    #[msg("VAA does not match the escrow's bridge trigger.")]
+    BridgeMessageMismatch,
+
+    /// The stake account is not owned by the native stake program.
+    #[msg("Account is not a stake account.")]
+    InvalidStakeAccount,
+
+    /// The supplied program is not the native stake program.
+    #[msg("Program is not the native stake program.")]
+    InvalidStakeProgram,
 }
@@ -4,6 +4,7 @@ import type { Escrow } from "../target/types/escrow.ts";
 import { Keypair, PublicKey, SystemProgram, LAMPORTS_PER_SOL } from "@solana/web3.js";
 import { assert } from "chai";
 import { BN } from "bn.js";
+import { createHash } from "crypto";
 
 describe("escrow", () => {
     const provider = anchor.AnchorProvider.env();
@@ -12,6 +13,20 @@ describe("escrow", () => {
 
     const PREFIX = Buffer.from("escrow");
     const AMOUNT = new BN(LAMPORTS_PER_SOL);
+    const CHAIN = Array.from(sha256(Buffer.from("solana:localnet")));
+
+    const [compliancePda] = PublicKey.findProgramAddressSync(
+        [Buffer.from("compliance")],
+        program.programId
+    );
+
+    function sha256(data: Buffer): Buffer {
+        return createHash("sha256").update(data).digest();
+    }
+
+    function word(value: BN): Buffer {
+        return value.toArrayLike(Buffer, "be", 32);
+    }
 
     function derivePda(sender: PublicKey, recipient: PublicKey): [PublicKey, number] {
         return PublicKey.findProgramAddressSync(
@@ -37,11 +52,16 @@ describe("escrow", () => {
                 amount: AMOUNT,
                 finishAfter: null,
                 cancelAfter: new BN(0),
+                memo: null,
+                rentDestination: null,
+                chain: CHAIN,
+                bridgeTrigger: null,
             })
             .accounts({
                 sender: sender.publicKey,
                 recipient: recipient.publicKey,
                 escrowAccount: escrowPda,
+                compliance: compliancePda,
                 systemProgram: SystemProgram.programId,
             })
             .signers([sender])
@@ -53,6 +73,8 @@ describe("escrow", () => {
             .accounts({
                 recipient: recipient.publicKey,
                 escrowAccount: escrowPda,
+                compliance: compliancePda,
+                rentDestination: null,
             })
             .signers([recipient])
             .rpc();
@@ -79,11 +101,16 @@ describe("escrow", () => {
                 amount: AMOUNT,
                 finishAfter: new BN(0),
                 cancelAfter: null,
+                memo: null,
+                rentDestination: null,
+                chain: CHAIN,
+                bridgeTrigger: null,
             })
             .accounts({
                 sender: sender.publicKey,
                 recipient: recipient.publicKey,
                 escrowAccount: escrowPda,
+                compliance: compliancePda,
                 systemProgram: SystemProgram.programId,
             })
             .signers([sender])
@@ -95,6 +122,8 @@ describe("escrow", () => {
             .accounts({
                 recipient: recipient.publicKey,
                 escrowAccount: escrowPda,
+                compliance: compliancePda,
+                rentDestination: null,
             })
             .signers([recipient])
             .rpc();
@@ -119,11 +148,16 @@ describe("escrow", () => {
                 amount: AMOUNT,
                 finishAfter: null,
                 cancelAfter: new BN(0),
+                memo: null,
+                rentDestination: null,
+                chain: CHAIN,
+                bridgeTrigger: null,
             })
             .accounts({
                 sender: sender.publicKey,
                 recipient: recipient.publicKey,
                 escrowAccount: escrowPda,
+                compliance: compliancePda,
                 systemProgram: SystemProgram.programId,
             })
             .signers([sender])
@@ -135,6 +169,7 @@ describe("escrow", () => {
             .accounts({
                 sender: sender.publicKey,
                 escrowAccount: escrowPda,
+                rentDestination: null,
             })
             .signers([sender])
             .rpc();
@@ -153,6 +188,240 @@ describe("escrow", () => {
         }
     });
 
+    it("should store the memo on the escrow account", async () => {
+        const memo = Buffer.alloc(64);
+        memo.write("INV-2026-0042");
+
+        await program.methods
+            .createEscrow({
+                amount: AMOUNT,
+                finishAfter: null,
+                cancelAfter: new BN(0),
+                memo: Array.from(memo),
+                rentDestination: null,
+                chain: CHAIN,
+                bridgeTrigger: null,
+            })
+            .accounts({
+                sender: sender.publicKey,
+                recipient: recipient.publicKey,
+                escrowAccount: escrowPda,
+                compliance: compliancePda,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([sender])
+            .rpc();
+
+        const escrow = await program.account.escrow.fetch(escrowPda);
+        assert.deepEqual(Buffer.from(escrow.memo as number[]), memo, "memo stored");
+    });
+
+    it("should emit the chain-agnostic escrow ID", async () => {
+        const signature = await program.methods
+            .createEscrow({
+                amount: AMOUNT,
+                finishAfter: null,
+                cancelAfter: new BN(0),
+                memo: null,
+                rentDestination: null,
+                chain: CHAIN,
+                bridgeTrigger: null,
+            })
+            .accounts({
+                sender: sender.publicKey,
+                recipient: recipient.publicKey,
+                escrowAccount: escrowPda,
+                compliance: compliancePda,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([sender])
+            .rpc({ commitment: "confirmed" });
+
+        const tx = await provider.connection.getTransaction(signature, {
+            commitment: "confirmed",
+            maxSupportedTransactionVersion: 0,
+        });
+        const parser = new anchor.EventParser(program.programId, program.coder);
+        const [event] = [...parser.parseLogs(tx!.meta!.logMessages!)];
+        const expected = sha256(
+            Buffer.concat([
+                Buffer.from("zescrow/escrow-id/v1"),
+                Buffer.from(CHAIN),
+                sha256(program.programId.toBuffer()),
+                sha256(sender.publicKey.toBuffer()),
+                sha256(recipient.publicKey.toBuffer()),
+                sha256(word(new BN(tx!.slot))),
+                sha256(Buffer.alloc(0)),
+                word(AMOUNT),
+            ])
+        );
+        assert.deepEqual(Buffer.from(event.data.uid as number[]), expected, "uid emitted");
+    });
+
+    it("should send rent to the configured `rentDestination` on finish", async () => {
+        const treasury = Keypair.generate();
+
+        await program.methods
+            .createEscrow({
+                amount: AMOUNT,
+                finishAfter: null,
+                cancelAfter: new BN(0),
+                memo: null,
+                rentDestination: treasury.publicKey,
+                chain: CHAIN,
+                bridgeTrigger: null,
+            })
+            .accounts({
+                sender: sender.publicKey,
+                recipient: recipient.publicKey,
+                escrowAccount: escrowPda,
+                compliance: compliancePda,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([sender])
+            .rpc();
+
+        const rent = await provider.connection.getBalance(escrowPda) - AMOUNT.toNumber();
+
+        // Omitting the configured destination must fail
+        try {
+            await program.methods
+                .finishEscrow()
+                .accounts({
+                    recipient: recipient.publicKey,
+                    escrowAccount: escrowPda,
+                    compliance: compliancePda,
+                    rentDestination: null,
+                })
+                .signers([recipient])
+                .rpc();
+            assert.fail("Expected finish without rent destination to fail");
+        } catch (err: unknown) {
+            assert.match(String(err), /MissingRentDestination/);
+        }
+
+        const before = await provider.connection.getBalance(recipient.publicKey);
+        await program.methods
+            .finishEscrow()
+            .accounts({
+                recipient: recipient.publicKey,
+                escrowAccount: escrowPda,
+                compliance: compliancePda,
+                rentDestination: treasury.publicKey,
+            })
+            .signers([recipient])
+            .rpc();
+        const after = await provider.connection.getBalance(recipient.publicKey);
+        assert.equal(after - before, AMOUNT.toNumber(), "recipient got only the escrowed amount");
+        assert.equal(
+            await provider.connection.getBalance(treasury.publicKey),
+            rent,
+            "rent went to the configured destination"
+        );
+    });
+
+    it("should block listed addresses once compliance is initialized", async () => {
+        const authority = provider.wallet.publicKey;
+        const [programData] = PublicKey.findProgramAddressSync(
+            [program.programId.toBuffer()],
+            new PublicKey("BPFLoaderUpgradeab1e11111111111111111111111")
+        );
+
+        await program.methods
+            .initializeCompliance(authority, { deny: {} })
+            .accounts({
+                payer: authority,
+                compliance: compliancePda,
+                program: program.programId,
+                programData,
+                systemProgram: SystemProgram.programId,
+            })
+            .rpc();
+        await program.methods
+            .setComplianceEntry(recipient.publicKey, true)
+            .accounts({ authority, compliance: compliancePda })
+            .rpc();
+
+        try {
+            await program.methods
+                .createEscrow({
+                    amount: AMOUNT,
+                    finishAfter: null,
+                    cancelAfter: new BN(0),
+                    memo: null,
+                    rentDestination: null,
+                    chain: CHAIN,
+                    bridgeTrigger: null,
+                })
+                .accounts({
+                    sender: sender.publicKey,
+                    recipient: recipient.publicKey,
+                    escrowAccount: escrowPda,
+                    compliance: compliancePda,
+                    systemProgram: SystemProgram.programId,
+                })
+                .signers([sender])
+                .rpc();
+            assert.fail("Expected create to a blocked recipient to fail");
+        } catch (err: unknown) {
+            assert.match(String(err), /AddressBlocked/);
+        }
+
+        await program.methods
+            .setComplianceEntry(recipient.publicKey, false)
+            .accounts({ authority, compliance: compliancePda })
+            .rpc();
+    });
+
+    it("should refuse VAAs not posted by the Wormhole core bridge", async () => {
+        await program.methods
+            .createEscrow({
+                amount: AMOUNT,
+                finishAfter: null,
+                cancelAfter: new BN(0),
+                memo: null,
+                rentDestination: null,
+                chain: CHAIN,
+                bridgeTrigger: {
+                    emitterChain: 2,
+                    emitter: Array(32).fill(9),
+                    payloadHash: Array.from(sha256(Buffer.from("delivered"))),
+                },
+            })
+            .accounts({
+                sender: sender.publicKey,
+                recipient: recipient.publicKey,
+                escrowAccount: escrowPda,
+                compliance: compliancePda,
+                systemProgram: SystemProgram.programId,
+            })
+            .signers([sender])
+            .rpc();
+
+        // An account anyone could have written, rather than a posted VAA.
+        const forged = Keypair.generate();
+        await airdrop(forged.publicKey, LAMPORTS_PER_SOL);
+        try {
+            await program.methods
+                .finishEscrowWithVaa()
+                .accounts({
+                    payer: provider.wallet.publicKey,
+                    recipient: recipient.publicKey,
+                    escrowAccount: escrowPda,
+                    postedVaa: forged.publicKey,
+                    compliance: compliancePda,
+                    rentDestination: null,
+                })
+                .rpc();
+            assert.fail("Expected finishing with a forged VAA to fail");
+        } catch (err: unknown) {
+            assert.match(String(err), /InvalidVaa/);
+        }
+
+        const escrow = await program.account.escrow.fetch(escrowPda);
+        assert.equal(escrow.bridgeTrigger?.emitterChain, 2);
+    });
+
     async function airdrop(pubkey: PublicKey, lamports: number): Promise<void> {
         const sig = await provider.connection.requestAirdrop(pubkey, lamports);
         await confirmTransaction(sig);
@@ -0,0 +1,419 @@
+//! `export-archive` and `import-archive` commands, bundling everything known
+//! about an escrow into a single `.tar.gz` so it can be handed off between
+//! machines or parties mid-lifecycle.
+//!
+//! An archive holds:
+//!
+//! - `manifest.json`: format version, export time, and contents
+//! - `escrow_metadata.json`: the metadata, without local connection
+//!   settings (see [`share`](crate::share))
+//! - `escrow_conditions.json`, or `escrow_conditions.enc.json` when
+//!   encrypted under a password
+//! - `proof_data.json` and `receipt.bin`, if the escrow was proven
+//! - `history.json`: the state transitions recorded in the store
+
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use anyhow::{anyhow, bail, Context};
+use clap::{value_parser, Args};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use zescrow_client::Encrypted;
+use zescrow_core::interface::{
+    load_escrow_data, save_escrow_data, ProofArtifact, ESCROW_CONDITIONS_PATH,
+    ESCROW_METADATA_PATH, PROOF_DATA_PATH,
+};
+use zescrow_core::{Chain, Condition, EscrowMetadata, ExecutionState};
+
+use crate::output::{print_json, OutputFormat};
+use crate::{load_escrow, share, ClientOpts};
+
+/// Default path of escrow archives.
+pub const ARCHIVE_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../deploy/escrow_archive.tar.gz"
+);
+
+/// Default path of receipts imported from archives.
+const RECEIPT_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/receipt.bin");
+
+/// Environment variable holding the password of encrypted conditions.
+pub const PASSWORD_VAR: &str = "ZESCROW_ARCHIVE_PASSWORD";
+
+/// Archive format version.
+const VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = "manifest.json";
+const METADATA_FILE: &str = "escrow_metadata.json";
+const CONDITIONS_FILE: &str = "escrow_conditions.json";
+const ENCRYPTED_CONDITIONS_FILE: &str = "escrow_conditions.enc.json";
+const PROOF_FILE: &str = "proof_data.json";
+const RECEIPT_FILE: &str = "receipt.bin";
+const HISTORY_FILE: &str = "history.json";
+
+#[derive(Args, Debug)]
+pub struct ExportArgs {
+    /// Local ID of the stored escrow (with `--store`; defaults to the most
+    /// recently created)
+    #[arg(long, value_name = "ID")]
+    escrow: Option<i64>,
+
+    /// Fulfillment of the escrow's conditions to include [default:
+    /// templates/escrow_conditions.json, if present]
+    #[arg(long, value_name = "FILE", value_parser = value_parser!(PathBuf))]
+    conditions: Option<PathBuf>,
+
+    /// Encrypt the conditions under a password, read from
+    /// `ZESCROW_ARCHIVE_PASSWORD` or prompted for
+    #[arg(long)]
+    encrypt: bool,
+
+    /// Proof data from `prove` to include [default:
+    /// deploy/proof_data.json if it is for this escrow, or the proof
+    /// recorded in the store]
+    #[arg(long, value_name = "FILE", value_parser = value_parser!(PathBuf))]
+    proof: Option<PathBuf>,
+
+    /// Full receipt from `prove --receipt` to include
+    #[arg(long, value_name = "FILE", value_parser = value_parser!(PathBuf))]
+    receipt: Option<PathBuf>,
+
+    /// Output path for the archive
+    #[arg(
+        long,
+        value_name = "FILE",
+        default_value = ARCHIVE_PATH,
+        value_parser = value_parser!(PathBuf)
+    )]
+    out: PathBuf,
+}
+
+#[derive(Args, Debug)]
+pub struct ImportArgs {
+    /// Archive from `export-archive`
+    #[arg(default_value = ARCHIVE_PATH, value_parser = value_parser!(PathBuf))]
+    file: PathBuf,
+
+    /// Extract into this directory, e.g. one watched by `daemon`, instead
+    /// of `templates/` and `deploy/`
+    #[arg(long, value_name = "DIR", value_parser = value_parser!(PathBuf))]
+    dir: Option<PathBuf>,
+}
+
+/// Summary of an archive's contents.
+#[derive(Debug, Serialize, Deserialize)]
+struct Manifest {
+    version: u32,
+    /// When the archive was written, in seconds since the Unix epoch.
+    exported_at: u64,
+    chain: Chain,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    escrow_id: Option<u64>,
+    state: ExecutionState,
+    /// Files in the archive besides the manifest.
+    files: Vec<String>,
+}
+
+/// A recorded state transition of the escrow.
+#[derive(Debug, Serialize, Deserialize)]
+struct HistoryEntry {
+    state: ExecutionState,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tx: Option<String>,
+    at: u64,
+}
+
+/// Writes the archive of the selected escrow.
+pub fn export(opts: &ClientOpts, args: ExportArgs) -> anyhow::Result<()> {
+    let (id, metadata) = load_escrow(opts, args.escrow)?;
+    let mut files: Vec<(&str, Vec<u8>)> = Vec::new();
+    files.push((
+        METADATA_FILE,
+        serde_json::to_vec_pretty(&share::without_local_settings(&metadata))?,
+    ));
+
+    let conditions = args.conditions.or_else(|| {
+        let default = PathBuf::from(ESCROW_CONDITIONS_PATH);
+        (metadata.params.has_conditions && default.exists()).then_some(default)
+    });
+    if let Some(path) = conditions {
+        info!("Loading conditions from {}", path.display());
+        let condition: Condition = load_escrow_data(&path)?;
+        let json = serde_json::to_vec_pretty(&condition)?;
+        if args.encrypt {
+            let encrypted = Encrypted::encrypt(&json, &password(true)?)?;
+            files.push((
+                ENCRYPTED_CONDITIONS_FILE,
+                serde_json::to_vec_pretty(&encrypted)?,
+            ));
+        } else {
+            warn!("Conditions are archived unencrypted; pass --encrypt to protect them");
+            files.push((CONDITIONS_FILE, json));
+        }
+    } else if args.encrypt {
+        bail!("--encrypt given but there are no conditions to encrypt");
+    }
+
+    if let Some(proof) = proof_data(opts, id, &metadata, args.proof.as_deref())? {
+        files.push((PROOF_FILE, serde_json::to_vec_pretty(&proof)?));
+    }
+    if let Some(path) = &args.receipt {
+        let receipt = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+        files.push((RECEIPT_FILE, receipt));
+    }
+
+    if let (Some(store), Some(id)) = (&opts.store, id) {
+        let history: Vec<HistoryEntry> = store
+            .transitions(id)?
+            .into_iter()
+            .map(|transition| HistoryEntry {
+                state: transition.state,
+                tx: transition.tx,
+                at: transition.at,
+            })
+            .collect();
+        files.push((HISTORY_FILE, serde_json::to_vec_pretty(&history)?));
+    }
+
+    let manifest = Manifest {
+        version: VERSION,
+        exported_at: now(),
+        chain: metadata.params.chain_config.chain,
+        escrow_id: metadata.escrow_id,
+        state: metadata.state,
+        files: files.iter().map(|(name, _)| (*name).to_owned()).collect(),
+    };
+    write_archive(&args.out, &manifest, &files)?;
+    info!("Saved archive to {}", args.out.display());
+    match opts.output {
+        OutputFormat::Text => {
+            println!("Archive written to {}", args.out.display());
+            println!("Contents: {}", manifest.files.join(", "));
+        }
+        OutputFormat::Json => print_json(&manifest)?,
+    }
+    Ok(())
+}
+
+/// Extracts an archive, connecting the escrow with the local chain
+/// configuration and recording it in the store.
+pub fn import(opts: &ClientOpts, args: ImportArgs) -> anyhow::Result<()> {
+    let mut files = read_archive(&args.file)?;
+    let mut take = |name: &str| files.remove(name);
+
+    let manifest: Manifest = serde_json::from_slice(
+        &take(MANIFEST_FILE).ok_or_else(|| anyhow!("archive has no {MANIFEST_FILE}"))?,
+    )
+    .context("parsing the archive manifest")?;
+    if manifest.version > VERSION {
+        bail!(
+            "archive format version {} is newer than supported ({VERSION}); upgrade the client",
+            manifest.version
+        );
+    }
+    let mut metadata: EscrowMetadata = serde_json::from_slice(
+        &take(METADATA_FILE).ok_or_else(|| anyhow!("archive has no {METADATA_FILE}"))?,
+    )
+    .context("parsing escrow metadata")?;
+    share::connect(opts, &mut metadata)?;
+
+    let path = |file: &str, default: &str| match &args.dir {
+        Some(dir) => dir.join(file),
+        None => PathBuf::from(default),
+    };
+    if let Some(dir) = &args.dir {
+        std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+
+    let metadata_path = path(METADATA_FILE, ESCROW_METADATA_PATH);
+    info!("Saving metadata to {}", metadata_path.display());
+    save_escrow_data(&metadata_path, &metadata)?;
+
+    let conditions = match (take(CONDITIONS_FILE), take(ENCRYPTED_CONDITIONS_FILE)) {
+        (Some(json), _) => Some(json),
+        (None, Some(encrypted)) => {
+            let encrypted: Encrypted =
+                serde_json::from_slice(&encrypted).context("parsing encrypted conditions")?;
+            Some(encrypted.decrypt(&password(false)?)?)
+        }
+        (None, None) => None,
+    };
+    if let Some(json) = conditions {
+        let condition: Condition =
+            serde_json::from_slice(&json).context("parsing escrow conditions")?;
+        let conditions_path = path(CONDITIONS_FILE, ESCROW_CONDITIONS_PATH);
+        info!("Saving conditions to {}", conditions_path.display());
+        save_escrow_data(&conditions_path, &condition)?;
+    }
+
+    let proof = take(PROOF_FILE)
+        .map(|json| serde_json::from_slice::<ProofArtifact>(&json))
+        .transpose()
+        .context("parsing proof data")?;
+    let seal = proof
+        .as_ref()
+        .map(|proof| proof.seal_for(&metadata))
+        .transpose()?
+        .flatten();
+    if let Some(proof) = &proof {
+        let proof_path = path(PROOF_FILE, PROOF_DATA_PATH);
+        info!("Saving proof data to {}", proof_path.display());
+        save_escrow_data(&proof_path, proof)?;
+    }
+    if let Some(receipt) = take(RECEIPT_FILE) {
+        let receipt_path = path(RECEIPT_FILE, RECEIPT_PATH);
+        info!("Saving receipt to {}", receipt_path.display());
+        std::fs::write(&receipt_path, receipt)
+            .with_context(|| format!("writing {}", receipt_path.display()))?;
+    }
+
+    let history: Vec<HistoryEntry> = take(HISTORY_FILE)
+        .map(|json| serde_json::from_slice(&json))
+        .transpose()
+        .context("parsing escrow history")?
+        .unwrap_or_default();
+
+    let id = match &opts.store {
+        Some(store) => {
+            let id = store.insert(&metadata, None)?;
+            if let Some(seal) = &seal {
+                store.record_proof(id, seal)?;
+            }
+            Some(id)
+        }
+        None => None,
+    };
+
+    match opts.output {
+        OutputFormat::Text => {
+            println!(
+                "Imported {} escrow ({:?}) into {}",
+                manifest.chain.as_ref(),
+                metadata.state,
+                metadata_path.display()
+            );
+            if let Some(id) = id {
+                println!("Recorded as escrow {id} in the store");
+            }
+            for entry in &history {
+                println!(
+                    "  {:>10}  {:?}  {}",
+                    entry.at,
+                    entry.state,
+                    entry.tx.as_deref().unwrap_or("-")
+                );
+            }
+        }
+        OutputFormat::Json => print_json(&metadata)?,
+    }
+    Ok(())
+}
+
+/// Returns the proof data to archive: that in `path`, or the default proof
+/// data file if it is for this escrow, or the proof recorded in the store.
+fn proof_data(
+    opts: &ClientOpts,
+    id: Option<i64>,
+    metadata: &EscrowMetadata,
+    path: Option<&Path>,
+) -> anyhow::Result<Option<ProofArtifact>> {
+    if let Some(path) = path {
+        let proof = ProofArtifact::load(path)?;
+        proof.seal_for(metadata)?;
+        return Ok(Some(proof));
+    }
+    if Path::new(PROOF_DATA_PATH).exists() {
+        let proof = ProofArtifact::load(PROOF_DATA_PATH)?;
+        if proof.seal_for(metadata).is_ok() {
+            return Ok(Some(proof));
+        }
+    }
+    let seal = match (&opts.store, id) {
+        (Some(store), Some(id)) => store.latest_proof(id)?,
+        _ => None,
+    };
+    Ok(seal.map(|seal| ProofArtifact::new(metadata, Some(&seal))))
+}
+
+/// Writes the manifest and `files` to a gzipped tarball at `path`.
+fn write_archive(
+    path: &Path,
+    manifest: &Manifest,
+    files: &[(&str, Vec<u8>)],
+) -> anyhow::Result<()> {
+    let file = File::create(path).with_context(|| format!("creating {}", path.display()))?;
+    let mut tar = tar::Builder::new(GzEncoder::new(file, Compression::default()));
+    let json = serde_json::to_vec_pretty(manifest)?;
+    let entries = std::iter::once((MANIFEST_FILE, &json))
+        .chain(files.iter().map(|(name, data)| (*name, data)));
+    for (name, data) in entries {
+        let mut header = tar::Header::new_gnu();
+        header.set_size(data.len() as u64);
+        header.set_mode(0o600);
+        header.set_mtime(manifest.exported_at);
+        header.set_cksum();
+        tar.append_data(&mut header, name, data.as_slice())
+            .with_context(|| format!("adding {name} to the archive"))?;
+    }
+    tar.into_inner()?.finish()?;
+    Ok(())
+}
+
+/// Reads the files of an archive by name, rejecting unexpected entries.
+fn read_archive(path: &Path) -> anyhow::Result<HashMap<String, Vec<u8>>> {
+    const KNOWN: [&str; 7] = [
+        MANIFEST_FILE,
+        METADATA_FILE,
+        CONDITIONS_FILE,
+        ENCRYPTED_CONDITIONS_FILE,
+        PROOF_FILE,
+        RECEIPT_FILE,
+        HISTORY_FILE,
+    ];
+    let file = File::open(path).with_context(|| format!("opening {}", path.display()))?;
+    let mut archive = tar::Archive::new(GzDecoder::new(file));
+    let mut files = HashMap::new();
+    for entry in archive.entries().context("reading the archive")? {
+        let mut entry = entry.context("reading the archive")?;
+        let name = entry.path()?.to_string_lossy().into_owned();
+        if !KNOWN.contains(&name.as_str()) {
+            bail!("unexpected file `{name}` in the archive");
+        }
+        let mut data = Vec::new();
+        entry
+            .read_to_end(&mut data)
+            .with_context(|| format!("reading {name} from the archive"))?;
+        files.insert(name, data);
+    }
+    Ok(files)
+}
+
+/// Returns the archive password from [`PASSWORD_VAR`], or prompts for it,
+/// twice if `confirm`.
+fn password(confirm: bool) -> anyhow::Result<String> {
+    if let Ok(password) = std::env::var(PASSWORD_VAR) {
+        return Ok(password);
+    }
+    if !std::io::IsTerminal::is_terminal(&std::io::stdin()) {
+        bail!("archive password required; set {PASSWORD_VAR}");
+    }
+    let mut prompt = dialoguer::Password::new().with_prompt("Archive password");
+    if confirm {
+        prompt = prompt.with_confirmation("Repeat password", "Passwords do not match");
+    }
+    Ok(prompt.interact()?)
+}
+
+/// Returns the current time in seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
@@ -0,0 +1,286 @@
+//! Batch escrow creation for `create --batch`.
+//!
+//! Each row of a CSV or JSON file becomes one escrow, created from the
+//! chain configuration, sender, and asset kind in `escrow_params.json`.
+//! Results are written to a report after every row, so an interrupted or
+//! partly failed run can be resumed: rows already created are skipped and
+//! failed rows are retried.
+
+use std::fs;
+use std::path::Path;
+
+use anyhow::{bail, Context};
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use zescrow_client::{EscrowStore, Operation, Simulation, ZescrowClient};
+use zescrow_core::interface::{load_escrow_data, save_escrow_data};
+use zescrow_core::{BigNumber, EscrowMetadata, EscrowParams, Party};
+
+use crate::output::{self, OutputFormat};
+
+/// Default path of the combined batch report.
+pub const BATCH_METADATA_PATH: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../deploy/escrow_batch_metadata.json"
+);
+
+/// One escrow to create.
+#[derive(Debug, Deserialize)]
+struct BatchRow {
+    /// Recipient address or public key.
+    recipient: String,
+    /// Amount in the asset's smallest unit.
+    amount: u128,
+    /// Overrides `finish_after` from the template.
+    #[serde(default)]
+    finish_after: Option<u64>,
+    /// Overrides `cancel_after` from the template.
+    #[serde(default)]
+    cancel_after: Option<u64>,
+    /// Overrides `memo` from the template.
+    #[serde(default)]
+    memo: Option<String>,
+}
+
+/// Outcome of one row, as recorded in the batch report.
+#[derive(Debug, Serialize, Deserialize)]
+struct BatchEntry {
+    /// Zero-based row index in the batch file.
+    row: usize,
+    /// Recipient, to detect a batch file edited between runs.
+    recipient: String,
+    /// Metadata of the created escrow.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    metadata: Option<EscrowMetadata>,
+    /// Error from the last failed attempt.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Outcome of simulating one row, as printed by `--dry-run --output json`.
+#[derive(Debug, Serialize)]
+struct RowSimulation {
+    /// Zero-based row index in the batch file.
+    row: usize,
+    recipient: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    simulation: Option<Simulation>,
+    /// Error building or simulating the transaction.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Creates one escrow per row of `batch`, using `template` for everything
+/// a row does not specify, and records the results in `report_path` (and
+/// each created escrow in `store`, if given).
+///
+/// Prints one line per row, or the whole report at the end as JSON.
+///
+/// # Errors
+///
+/// Returns an error if the batch file or report cannot be read or written,
+/// the report does not match the batch file, or any row failed.
+pub async fn create_escrows(
+    client: &ZescrowClient,
+    template: &EscrowParams,
+    batch: &Path,
+    report_path: &Path,
+    store: Option<&EscrowStore>,
+    output: OutputFormat,
+) -> anyhow::Result<()> {
+    let rows = read_rows(batch)?;
+    let mut report: Vec<BatchEntry> = if report_path.exists() {
+        info!(?report_path, "Resuming batch");
+        load_escrow_data(report_path)?
+    } else {
+        Vec::new()
+    };
+    for entry in &report {
+        match rows.get(entry.row) {
+            Some(row) if row.recipient == entry.recipient => {}
+            _ => bail!(
+                "{report_path:?} does not match {batch:?} at row {}; \
+                 use a new --batch-output to start over",
+                entry.row
+            ),
+        }
+    }
+
+    let (mut created, mut skipped, mut failed) = (0, 0, 0);
+    for (index, row) in rows.iter().enumerate() {
+        let position = report.iter().position(|entry| entry.row == index);
+        if position.is_some_and(|i| report[i].metadata.is_some()) {
+            skipped += 1;
+            continue;
+        }
+
+        let result = match row_params(template, row) {
+            Ok(params) => client.create_escrow(&params).await.map_err(Into::into),
+            Err(e) => Err(e),
+        };
+        let entry = BatchEntry {
+            row: index,
+            recipient: row.recipient.clone(),
+            metadata: None,
+            error: None,
+        };
+        let entry = match result {
+            Ok(metadata) => {
+                // The escrow exists on-chain either way, so keep going and
+                // let the report record it.
+                if let Some(Err(e)) = store.map(|store| store.insert(&metadata, None)) {
+                    warn!(%e, row = index, "Failed to record escrow in the store");
+                }
+                if output == OutputFormat::Text {
+                    match metadata.escrow_id {
+                        Some(id) => {
+                            println!("row {index}: created escrow {id} for {}", row.recipient)
+                        }
+                        None => println!("row {index}: created escrow for {}", row.recipient),
+                    }
+                }
+                created += 1;
+                BatchEntry {
+                    metadata: Some(metadata),
+                    ..entry
+                }
+            }
+            Err(e) => {
+                if output == OutputFormat::Text {
+                    println!("row {index}: failed for {}: {e:#}", row.recipient);
+                }
+                failed += 1;
+                BatchEntry {
+                    error: Some(format!("{e:#}")),
+                    ..entry
+                }
+            }
+        };
+        match position {
+            Some(i) => report[i] = entry,
+            None => report.push(entry),
+        }
+        // Persist after every row so an interrupted run can resume.
+        save_escrow_data(report_path, &report)?;
+    }
+
+    match output {
+        OutputFormat::Text => println!(
+            "{created} created, {skipped} already created, {failed} failed; report in {}",
+            report_path.display()
+        ),
+        OutputFormat::Json => output::print_json(&report)?,
+    }
+    if failed > 0 {
+        warn!(failed, "Some escrows were not created");
+        bail!("{failed} row(s) failed; rerun the same command to retry them");
+    }
+    Ok(())
+}
+
+/// Simulates creating one escrow per row of `batch` without broadcasting
+/// anything or writing a report, printing one result per row.
+///
+/// # Errors
+///
+/// Returns an error if the batch file cannot be read or any row would fail.
+pub async fn simulate_escrows(
+    client: &ZescrowClient,
+    template: &EscrowParams,
+    batch: &Path,
+    output: OutputFormat,
+) -> anyhow::Result<()> {
+    let mut results = Vec::new();
+    for (index, row) in read_rows(batch)?.iter().enumerate() {
+        let result = match row_params(template, row) {
+            Ok(params) => client
+                .simulate(Operation::Create(&params))
+                .await
+                .map_err(Into::into),
+            Err(e) => Err(e),
+        };
+        let entry = RowSimulation {
+            row: index,
+            recipient: row.recipient.clone(),
+            simulation: None,
+            error: None,
+        };
+        let entry = match result {
+            Ok(simulation) => {
+                if output == OutputFormat::Text {
+                    println!("row {index}: {simulation}");
+                }
+                RowSimulation {
+                    simulation: Some(simulation),
+                    ..entry
+                }
+            }
+            Err(e) => {
+                if output == OutputFormat::Text {
+                    println!("row {index}: failed for {}: {e:#}", row.recipient);
+                }
+                RowSimulation {
+                    error: Some(format!("{e:#}")),
+                    ..entry
+                }
+            }
+        };
+        results.push(entry);
+    }
+
+    if output == OutputFormat::Json {
+        output::print_json(&results)?;
+    }
+    let failed = results
+        .iter()
+        .filter(|result| {
+            !result
+                .simulation
+                .as_ref()
+                .is_some_and(Simulation::succeeded)
+        })
+        .count();
+    if failed > 0 {
+        bail!("dry run: {failed} row(s) would fail");
+    }
+    Ok(())
+}
+
+/// Reads batch rows from a JSON array (`.json`) or a CSV file with a header
+/// row (anything else).
+fn read_rows(path: &Path) -> anyhow::Result<Vec<BatchRow>> {
+    if path.extension().is_some_and(|ext| ext == "json") {
+        return load_escrow_data(path);
+    }
+    let content = fs::read_to_string(path).with_context(|| format!("reading {path:?}"))?;
+    csv::ReaderBuilder::new()
+        .trim(csv::Trim::All)
+        .from_reader(content.as_bytes())
+        .deserialize()
+        .enumerate()
+        .map(|(index, row)| row.with_context(|| format!("parsing row {index} of {path:?}")))
+        .collect()
+}
+
+/// Applies a row's overrides to the template parameters.
+fn row_params(template: &EscrowParams, row: &BatchRow) -> anyhow::Result<EscrowParams> {
+    let mut params = template.clone();
+    params.recipient = Party::new(&row.recipient)?;
+    params.recipient_contact = None;
+    params.asset.amount = BigNumber::from(row.amount);
+    if row.finish_after.is_some() {
+        params.finish_after = row.finish_after;
+    }
+    if row.cancel_after.is_some() {
+        params.cancel_after = row.cancel_after;
+    }
+    if row.memo.is_some() {
+        params.memo = row.memo.clone();
+    }
+    if let (Some(finish), Some(cancel)) = (params.finish_after, params.cancel_after) {
+        if cancel != 0 && finish >= cancel {
+            bail!("finish_after ({finish}) must be less than cancel_after ({cancel})");
+        }
+    }
+    Ok(params)
+}
@@ -0,0 +1,268 @@
+//! Bulk settlement for `finish --all-ready` and `cancel --all-expired`.
+//!
+//! Scans the local store for funded escrows, checks each one's live state,
+//! and settles those whose timelocks allow it in batches: one `multicall`
+//! transaction per batch on Ethereum, one transaction per escrow on Solana.
+//! Eligibility is re-checked on-chain every run, so rerunning after a
+//! partial failure only retries the escrows that are still unsettled.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use anyhow::bail;
+use serde::Serialize;
+use tracing::{info, warn};
+use zescrow_client::{EscrowStore, Operation, Recipient, Simulation, StoredEscrow};
+use zescrow_core::{EscrowMetadata, ExecutionState};
+
+use crate::output::{self, OutputFormat};
+use crate::ClientOpts;
+
+/// Settlement applied to every eligible escrow.
+#[derive(Debug, Clone, Copy)]
+pub enum Action {
+    /// Finish escrows whose `finish_after` has passed.
+    Finish,
+    /// Cancel escrows whose `cancel_after` has passed.
+    Cancel,
+}
+
+/// Outcome of one escrow in the summary report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Outcome {
+    Settled,
+    /// Simulated with `--dry-run`.
+    Simulated,
+    /// Timelocks do not allow the action yet.
+    NotReady,
+    /// Already settled on-chain, though the store still records it as
+    /// funded.
+    AlreadySettled,
+    /// Escrows with conditions need a proof each; finish them one at a time.
+    Skipped,
+    Failed,
+}
+
+/// One escrow in the summary report.
+#[derive(Debug, Serialize)]
+struct Entry {
+    /// Local identifier in the store.
+    id: i64,
+    chain: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    escrow_id: Option<u64>,
+    outcome: Outcome,
+    /// Simulated transaction, with `--dry-run`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    simulation: Option<Simulation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl Entry {
+    fn new(escrow: &StoredEscrow, outcome: Outcome) -> Self {
+        Self {
+            id: escrow.id,
+            chain: escrow
+                .metadata
+                .params
+                .chain_config
+                .chain
+                .as_ref()
+                .to_owned(),
+            escrow_id: escrow.metadata.escrow_id,
+            outcome,
+            simulation: None,
+            error: None,
+        }
+    }
+}
+
+impl fmt::Display for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "escrow {} ({}): ", self.id, self.chain)?;
+        match (self.outcome, &self.simulation, &self.error) {
+            (_, _, Some(error)) => write!(f, "failed: {error}"),
+            (_, Some(simulation), None) => write!(f, "{simulation}"),
+            (Outcome::Settled, ..) => f.write_str("settled"),
+            (Outcome::NotReady, ..) => f.write_str("not ready"),
+            (Outcome::AlreadySettled, ..) => f.write_str("already settled"),
+            (Outcome::Skipped, ..) => f.write_str("skipped: has conditions"),
+            (Outcome::Simulated | Outcome::Failed, ..) => f.write_str("failed"),
+        }
+    }
+}
+
+/// Settles every funded escrow in `store` that `action` applies to, in
+/// batches of at most `batch_size`, and prints a summary report.
+///
+/// `recipient` is the key finishing escrows; it is ignored when
+/// cancelling.
+///
+/// # Errors
+///
+/// Returns an error if the store cannot be read, or any escrow failed.
+pub async fn settle_all(
+    opts: &ClientOpts,
+    store: &EscrowStore,
+    action: Action,
+    recipient: Option<Recipient>,
+    batch_size: usize,
+) -> anyhow::Result<()> {
+    // Escrows of one chain deployment share a client and can share a batch.
+    let mut groups: BTreeMap<String, Vec<StoredEscrow>> = BTreeMap::new();
+    for escrow in store.list(None)? {
+        if !matches!(
+            escrow.metadata.state,
+            ExecutionState::Funded | ExecutionState::PendingCreate | ExecutionState::ConditionsMet
+        ) {
+            continue;
+        }
+        let key = serde_json::to_string(&escrow.metadata.params.chain_config)?;
+        groups.entry(key).or_default().push(escrow);
+    }
+    info!(
+        escrows = groups.values().map(Vec::len).sum::<usize>(),
+        "Scanning stored escrows"
+    );
+
+    let mut report = Vec::new();
+    for escrows in groups.into_values() {
+        let config = &escrows[0].metadata.params.chain_config;
+        let mut builder = opts.builder(config);
+        if let (Action::Finish, Some(recipient)) = (action, &recipient) {
+            builder = builder.recipient(recipient.clone());
+        }
+        let client = match builder.build().await {
+            Ok(client) => client,
+            Err(e) => {
+                report.extend(escrows.iter().map(|escrow| Entry {
+                    error: Some(e.to_string()),
+                    ..Entry::new(escrow, Outcome::Failed)
+                }));
+                continue;
+            }
+        };
+
+        let mut ready = Vec::new();
+        for escrow in escrows {
+            if matches!(action, Action::Finish) && escrow.metadata.params.has_conditions {
+                report.push(Entry::new(&escrow, Outcome::Skipped));
+                continue;
+            }
+            let status = match client.get_escrow_state(&escrow.metadata).await {
+                Ok(status) => status,
+                Err(e) => {
+                    report.push(Entry {
+                        error: Some(e.to_string()),
+                        ..Entry::new(&escrow, Outcome::Failed)
+                    });
+                    continue;
+                }
+            };
+            let eligible = match action {
+                Action::Finish => status.can_finish(),
+                Action::Cancel => status.can_cancel(),
+            };
+            if status.settled {
+                report.push(Entry::new(&escrow, Outcome::AlreadySettled));
+            } else if eligible {
+                ready.push(escrow);
+            } else {
+                report.push(Entry::new(&escrow, Outcome::NotReady));
+            }
+        }
+
+        if opts.dry_run {
+            for escrow in &ready {
+                let operation = match action {
+                    Action::Finish => Operation::Finish(&escrow.metadata),
+                    Action::Cancel => Operation::Cancel(&escrow.metadata),
+                };
+                report.push(match client.simulate(operation).await {
+                    Ok(simulation) => Entry {
+                        simulation: Some(simulation),
+                        ..Entry::new(escrow, Outcome::Simulated)
+                    },
+                    Err(e) => Entry {
+                        error: Some(e.to_string()),
+                        ..Entry::new(escrow, Outcome::Failed)
+                    },
+                });
+            }
+            continue;
+        }
+
+        for batch in ready.chunks(batch_size.max(1)) {
+            let metadata: Vec<EscrowMetadata> =
+                batch.iter().map(|escrow| escrow.metadata.clone()).collect();
+            info!(count = batch.len(), ?action, "Settling batch");
+            let result = match action {
+                Action::Finish => client.finish_escrows(&metadata).await,
+                Action::Cancel => client.cancel_escrows(&metadata).await,
+            };
+            match result {
+                Ok(()) => {
+                    let state = match action {
+                        Action::Finish => ExecutionState::Released,
+                        Action::Cancel => ExecutionState::Refunded,
+                    };
+                    for escrow in batch {
+                        // The escrow is settled on-chain either way, so keep
+                        // going and let the report record it.
+                        if let Err(e) = store.update_state(escrow.id, state, None) {
+                            warn!(%e, id = escrow.id, "Failed to record settlement in the store");
+                        }
+                        report.push(Entry::new(escrow, Outcome::Settled));
+                    }
+                }
+                Err(e) => report.extend(batch.iter().map(|escrow| Entry {
+                    error: Some(e.to_string()),
+                    ..Entry::new(escrow, Outcome::Failed)
+                })),
+            }
+        }
+    }
+
+    report.sort_by_key(|entry| entry.id);
+    print_report(&report, action, opts.output)
+}
+
+/// Prints the per-escrow report and a summary line, failing if any escrow
+/// failed.
+fn print_report(report: &[Entry], action: Action, output: OutputFormat) -> anyhow::Result<()> {
+    let count = |outcome| report.iter().filter(|e| e.outcome == outcome).count();
+    let failed = count(Outcome::Failed);
+    match output {
+        OutputFormat::Text => {
+            for entry in report {
+                println!("{entry}");
+            }
+            let verb = match action {
+                Action::Finish => "finished",
+                Action::Cancel => "cancelled",
+            };
+            println!(
+                "{} {verb}, {} simulated, {} not ready, {} already settled, {} skipped, {failed} failed",
+                count(Outcome::Settled),
+                count(Outcome::Simulated),
+                count(Outcome::NotReady),
+                count(Outcome::AlreadySettled),
+                count(Outcome::Skipped),
+            );
+        }
+        OutputFormat::Json => output::print_json(&report)?,
+    }
+    if failed > 0 {
+        bail!("{failed} escrow(s) failed; rerun the same command to retry them");
+    }
+    let would_fail = report
+        .iter()
+        .filter(|e| e.simulation.as_ref().is_some_and(|s| !s.succeeded()))
+        .count();
+    if would_fail > 0 {
+        bail!("dry run: {would_fail} escrow(s) would fail");
+    }
+    Ok(())
+}
@@ -0,0 +1,1593 @@
+//! Cardano blockchain agent implementation.
+//!
+//! Provides [`CardanoAgent`] for interacting with the Zescrow Plutus escrow
+//! validator on Cardano. Each escrow is a UTxO locked at the validator's
+//! script address, with the parties and timelocks in its inline datum.
+//! Transactions are built and signed locally, and submitted through a
+//! Blockfrost-compatible API, which also evaluates the execution budget of
+//! the validator before escrows are spent.
+//!
+//! Spending an escrow references the validator from the UTxO configured in
+//! [`CardanoConfig::reference_script`], and puts up one of the signer's
+//! outputs as collateral, which is only taken if the validator fails.
+//! Slot timelocks are converted to the POSIX times the validator checks
+//! with the network's slot length.
+//!
+//! The validator does not verify proofs: as on Solana, conditions are
+//! checked off-chain before the recipient finishes an escrow.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use ciborium::value::Value;
+use futures::channel::mpsc;
+use futures::StreamExt;
+use num_traits::ToPrimitive;
+use pallas_addresses::{
+    Address, Network, ShelleyAddress, ShelleyDelegationPart, ShelleyPaymentPart,
+};
+use pallas_crypto::hash::{Hash, Hasher};
+use pallas_crypto::key::ed25519::SecretKey;
+use pallas_txbuilder::{
+    BuildConway, BuiltTransaction, ExUnits, Input, Output, ScriptKind, StagingTransaction,
+};
+use serde::Deserialize;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+use url::Url;
+use zescrow_core::interface::{CardanoConfig, CardanoNetwork, ChainConfig};
+use zescrow_core::{
+    AssetKind, BigNumber, Chain, ChainMetadata, EscrowId, EscrowMetadata, EscrowParams,
+    ExecutionState, ObservedBlock, Party,
+};
+
+use super::Agent;
+use crate::error::ClientError;
+use crate::ethereum::EthereumSigner;
+use crate::pending::{PendingTransaction, WaitConfig};
+use crate::rpc::cardano::{BlockfrostClient, BlockfrostError};
+use crate::rpc::RpcPolicy;
+use crate::simulation::{Operation, Simulation};
+use crate::watch::{EscrowUpdate, UpdateKind, UpdateStream};
+use crate::{EscrowStatus, EscrowSummary, Result};
+
+// Validator actions for logging.
+const CREATE_ESCROW: &str = "create";
+const FINISH_ESCROW: &str = "finish";
+const CANCEL_ESCROW: &str = "cancel";
+const GET_ESCROW: &str = "escrow";
+const LIST_ESCROWS: &str = "escrows_by_party";
+const WATCH: &str = "watch";
+const WAIT_FOR_TRANSACTION: &str = "wait_for_transaction";
+const GET_BLOCK: &str = "block";
+
+/// Blocks after which a block can no longer be rolled back: the security
+/// parameter `k` of the Ouroboros protocol.
+const SECURITY_PARAM: u64 = 2160;
+
+/// Interval between confirmation and event polls unless configured: about
+/// a block.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(20);
+
+/// Slots a transaction stays valid for after the latest block.
+const TTL_SLOTS: u64 = 900;
+
+/// Plutus data of the `Finish` redeemer: constructor 0 without fields.
+const FINISH_REDEEMER: &[u8] = &[0xd8, 0x79, 0x80];
+
+/// Plutus data of the `Cancel` redeemer: constructor 1 without fields.
+const CANCEL_REDEEMER: &[u8] = &[0xd8, 0x7a, 0x80];
+
+/// Lovelace set aside for the fee and change when selecting the outputs
+/// funding an escrow.
+const FEE_MARGIN: u64 = 2_000_000;
+
+/// Smallest output accepted as collateral, in lovelace.
+const MIN_COLLATERAL: u64 = 5_000_000;
+
+/// Rounds of fee estimation before giving up on the fee converging.
+const FEE_ROUNDS: usize = 4;
+
+/// Largest page of results Blockfrost returns.
+const PAGE_SIZE: usize = 100;
+
+/// Shared handle to an ed25519 key signing Cardano transactions.
+#[derive(Clone)]
+pub struct CardanoSigner(Arc<SecretKey>);
+
+impl CardanoSigner {
+    /// Creates a signer from a raw 32-byte ed25519 signing key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not 32 bytes.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        <[u8; 32]>::try_from(bytes)
+            .map(|key| Self(Arc::new(SecretKey::from(key))))
+            .map_err(|_| ClientError::Keypair("ed25519 signing keys are 32 bytes".into()))
+    }
+
+    /// Loads a signer from a `sender_private_id`-style string: a hex
+    /// signing key, with or without `0x` or the `5820` CBOR header of
+    /// `cardano-cli` key files, a Bech32 `ed25519_sk1...` key, or
+    /// `keystore:<name>` for a key in the encrypted
+    /// [`Keystore`](crate::keystore::Keystore).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key cannot be parsed or decrypted.
+    pub fn from_id(id: &str) -> Result<Self> {
+        if let Some(signer) = crate::keystore::cardano_signer(id) {
+            return signer;
+        }
+        let id = id.trim();
+        if id.starts_with("ed25519_sk1") {
+            let (_, bytes) = bech32::decode(id)
+                .map_err(|e| ClientError::Keypair(format!("invalid Bech32 key: {e}")))?;
+            return Self::from_bytes(&bytes);
+        }
+        let hex = id.trim_start_matches("0x");
+        let hex = match hex.len() {
+            68 => hex.strip_prefix("5820").unwrap_or(hex),
+            _ => hex,
+        };
+        let bytes =
+            hex::decode(hex).map_err(|e| ClientError::Keypair(format!("invalid hex key: {e}")))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Creates a signer whose signing key is the secret of a local Ethereum
+    /// key, so hex keys given for any chain can sign Cardano transactions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is held by a KMS.
+    pub fn from_ethereum(signer: &EthereumSigner) -> Result<Self> {
+        match signer {
+            EthereumSigner::Local(signer) => Self::from_bytes(&signer.credential().to_bytes()),
+            #[cfg(any(feature = "aws-kms", feature = "gcp-kms"))]
+            _ => Err(ClientError::Keypair(
+                "KMS-held keys cannot sign Cardano transactions".into(),
+            )),
+        }
+    }
+
+    /// Returns the payment key hash of the signer, which the validator
+    /// identifies parties by.
+    pub fn key_hash(&self) -> Hash<28> {
+        Hasher::<224>::hash(self.0.public_key().as_ref())
+    }
+
+    /// Returns the Bech32 enterprise address (without a stake part) of the
+    /// signer on `network`, which holds the funds the agent spends.
+    pub fn address(&self, network: CardanoNetwork) -> String {
+        enterprise_address(self.key_hash(), network)
+    }
+
+    /// Signs the built transaction `tx`, adding the signer's witness.
+    fn sign(&self, tx: BuiltTransaction, operation: &'static str) -> Result<BuiltTransaction> {
+        let signature = self.0.sign(tx.tx_hash.0);
+        let signature = <[u8; 64]>::try_from(signature.as_ref())
+            .map_err(|_| ClientError::cardano(operation, "ed25519 signatures are 64 bytes"))?;
+        tx.add_signature(self.0.public_key(), signature)
+            .map_err(|e| ClientError::cardano(operation, e))
+    }
+}
+
+impl fmt::Debug for CardanoSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CardanoSigner")
+            .field(&self.key_hash().to_string())
+            .finish()
+    }
+}
+
+/// Cardano blockchain agent for escrow operations.
+///
+/// Manages interactions with the Zescrow Plutus validator, including
+/// transaction building, fee and execution budget estimation, signing, and
+/// submission.
+pub struct CardanoAgent {
+    /// Blockfrost client for the network.
+    api: Arc<BlockfrostClient>,
+    /// Script address of the escrow validator.
+    script: Address,
+    /// Bech32 script address of the escrow validator.
+    script_address: String,
+    /// Network, reference script UTxO, and API project ID.
+    config: CardanoConfig,
+    /// UTxO holding the validator as a reference script.
+    reference: Input,
+    /// Signer of the escrow creator (sender).
+    sender: CardanoSigner,
+    /// Optional signer of the escrow beneficiary (recipient).
+    recipient: Option<CardanoSigner>,
+    /// Timeout and polling interval of confirmation waits.
+    wait: WaitConfig,
+}
+
+impl CardanoAgent {
+    /// Creates a new Cardano agent from chain configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Chain configuration with the Blockfrost URL, sender
+    ///   key, validator script address, and [`CardanoConfig`]
+    /// * `sender` - Optional signer overriding `sender_private_id`
+    /// * `recipient` - Optional recipient signer for finish operations
+    /// * `rpc` - Retry settings and metrics for API requests, which fail
+    ///   over to `fallback_rpc_urls` when `rpc_url` is unavailable
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The configuration has no `cardano` settings
+    /// - A key cannot be loaded
+    /// - The API URLs, script address, or reference script UTxO are
+    ///   malformed, or the script address is of another network
+    pub fn new(
+        config: &ChainConfig,
+        sender: Option<CardanoSigner>,
+        recipient: Option<CardanoSigner>,
+        rpc: RpcPolicy,
+    ) -> Result<Self> {
+        let ChainConfig {
+            rpc_url,
+            fallback_rpc_urls,
+            sender_private_id,
+            agent_id,
+            cardano,
+            ..
+        } = config;
+
+        let cardano = cardano.clone().ok_or_else(|| {
+            ClientError::cardano("config", "`cardano` settings are required on Cardano")
+        })?;
+        let network = cardano.network;
+
+        let sender = match sender {
+            Some(sender) => sender,
+            None => CardanoSigner::from_id(sender_private_id)?,
+        };
+        debug!(sender = %sender.address(network), "Loaded sender signer");
+        if let Some(recipient) = &recipient {
+            debug!(recipient = %recipient.address(network), "Loaded recipient signer");
+        }
+
+        let script =
+            Address::from_bech32(agent_id).map_err(|e| ClientError::cardano("parse_script", e))?;
+        match &script {
+            Address::Shelley(address)
+                if matches!(address.payment(), ShelleyPaymentPart::Script(_))
+                    && address.network().value() == network.network_id() => {}
+            _ => {
+                return Err(ClientError::cardano(
+                    "parse_script",
+                    format!("{agent_id} is not a script address on {network:?}"),
+                ))
+            }
+        }
+        info!(script = %agent_id, "Using escrow validator");
+
+        let (hash, index) = utxo_ref(&cardano.reference_script)?;
+        let reference = input(hash, index.into(), "parse_reference_script")?;
+
+        let primary = Url::parse(rpc_url)?;
+        let fallbacks = fallback_rpc_urls
+            .iter()
+            .map(|url| Url::parse(url))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            api: Arc::new(BlockfrostClient::new(
+                &primary,
+                &fallbacks,
+                cardano.project_id.clone(),
+                rpc,
+            )),
+            script,
+            script_address: agent_id.to_ascii_lowercase(),
+            config: cardano,
+            reference,
+            sender,
+            recipient,
+            wait: WaitConfig::default(),
+        })
+    }
+
+    /// Stops waiting for confirmation after [`WaitConfig::timeout`],
+    /// failing with [`ClientError::Pending`], and polls at
+    /// [`WaitConfig::poll_interval`].
+    pub fn with_wait(mut self, wait: WaitConfig) -> Self {
+        self.wait = wait;
+        self
+    }
+
+    fn poll_interval(&self) -> Duration {
+        self.wait.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL)
+    }
+
+    fn network(&self) -> CardanoNetwork {
+        self.config.network
+    }
+
+    /// Returns the chain-agnostic ID of the escrow locking `lovelace` from
+    /// `sender` to `recipient` in output `index` of transaction `tx_hash`,
+    /// the output reference being its nonce.
+    fn escrow_uid(
+        &self,
+        sender: Hash<28>,
+        recipient: Hash<28>,
+        tx_hash: &str,
+        index: u64,
+        lovelace: u64,
+    ) -> Option<EscrowId> {
+        let network = self.network();
+        let contract = Address::from_bech32(&self.script_address).ok()?.to_vec();
+        let mut nonce = Hash::<32>::from_str(tx_hash).ok()?.to_vec();
+        nonce.extend_from_slice(&EscrowId::word(index));
+        Some(EscrowId::new(
+            network.caip2_id(),
+            &contract,
+            &enterprise(sender, network).to_vec(),
+            &enterprise(recipient, network).to_vec(),
+            &nonce,
+            &[],
+            lovelace.into(),
+        ))
+    }
+
+    fn recipient_signer(&self) -> Result<&CardanoSigner> {
+        self.recipient
+            .as_ref()
+            .ok_or_else(|| ClientError::cardano(FINISH_ESCROW, "recipient key not configured"))
+    }
+
+    /// Verifies that a signer's payment key matches the escrow party's, so
+    /// parties may be given with any address of their key.
+    fn validate_signer(signer: &CardanoSigner, expected: &Party, role: &str) -> Result<()> {
+        (party_key_hash(expected)? == signer.key_hash())
+            .then_some(())
+            .ok_or_else(|| {
+                ClientError::Keypair(format!(
+                    "{role} key mismatch: expected {expected}, got key hash {}",
+                    signer.key_hash()
+                ))
+            })
+    }
+
+    /// Returns the reference of the escrow UTxO in `metadata`.
+    fn escrow_ref(metadata: &EscrowMetadata) -> Result<(&str, u32)> {
+        match &metadata.chain_metadata {
+            Some(ChainMetadata::Cardano {
+                tx_hash,
+                output_index,
+                ..
+            }) => Ok((tx_hash, *output_index)),
+            _ => Err(ClientError::InvalidChainOperation(
+                "escrow UTxO is required on Cardano".into(),
+            )),
+        }
+    }
+
+    /// Returns the latest block.
+    async fn tip(&self, operation: &'static str) -> Result<Tip> {
+        let block: LatestBlock = self
+            .api
+            .get(&["blocks", "latest"], &[])
+            .await
+            .map_err(|e| ClientError::cardano(operation, e))?;
+        Ok(Tip {
+            slot: block.slot.unwrap_or_default(),
+            height: block.height.unwrap_or_default(),
+        })
+    }
+
+    /// Reads the block at `path` under `blocks`, if any, with its slot and
+    /// hash.
+    async fn block(&self, path: &[&str]) -> Result<Option<ObservedBlock>> {
+        let path = [&["blocks"], path].concat();
+        let block: LatestBlock = match self.api.get(&path, &[]).await {
+            Ok(block) => block,
+            Err(e) if e.is_not_found() => return Ok(None),
+            Err(e) => return Err(ClientError::cardano(GET_BLOCK, e)),
+        };
+        let mut hash = [0; 32];
+        hex::decode_to_slice(block.hash.unwrap_or_default(), &mut hash)
+            .map_err(|e| ClientError::cardano(GET_BLOCK, format!("invalid block hash: {e}")))?;
+        Ok(Some(ObservedBlock {
+            height: block.slot.unwrap_or_default(),
+            hash,
+        }))
+    }
+
+    async fn protocol_params(&self, operation: &'static str) -> Result<ProtocolParams> {
+        self.api
+            .get(&["epochs", "latest", "parameters"], &[])
+            .await
+            .map_err(|e| ClientError::cardano(operation, e))
+    }
+
+    /// Returns the outputs of transaction `hash`.
+    async fn tx_utxos(&self, hash: &str, operation: &'static str) -> Result<TxUtxos> {
+        self.api
+            .get(&["txs", hash, "utxos"], &[])
+            .await
+            .map_err(|e| ClientError::cardano(operation, e))
+    }
+
+    /// Returns output `index` of transaction `hash`.
+    async fn output(&self, hash: &str, index: u32, operation: &'static str) -> Result<TxOutput> {
+        self.tx_utxos(hash, operation)
+            .await?
+            .outputs
+            .into_iter()
+            .find(|output| output.output_index == u64::from(index))
+            .ok_or_else(|| ClientError::cardano(operation, format!("no output {hash}#{index}")))
+    }
+
+    /// Returns the unspent outputs of the signer holding only lovelace,
+    /// largest first.
+    async fn wallet(&self, signer: &CardanoSigner, operation: &'static str) -> Result<Vec<Utxo>> {
+        let address = signer.address(self.network());
+        let mut utxos = Vec::new();
+        for page in 1.. {
+            let page = page.to_string();
+            let batch: Vec<Utxo> = match self
+                .api
+                .get(
+                    &["addresses", &address, "utxos"],
+                    &[("count", "100"), ("page", &page)],
+                )
+                .await
+            {
+                Ok(batch) => batch,
+                // Addresses that never received funds are unknown.
+                Err(e) if e.is_not_found() => Vec::new(),
+                Err(e) => return Err(ClientError::cardano(operation, e)),
+            };
+            let full = batch.len() == PAGE_SIZE;
+            utxos.extend(batch.into_iter().filter(|utxo| utxo.lovelace_only()));
+            if !full {
+                break;
+            }
+        }
+        utxos.sort_by_key(|utxo| std::cmp::Reverse(utxo.lovelace()));
+        Ok(utxos)
+    }
+
+    /// Selects outputs of the signer holding at least `target` lovelace.
+    async fn select(
+        &self,
+        signer: &CardanoSigner,
+        target: u64,
+        operation: &'static str,
+    ) -> Result<Vec<Utxo>> {
+        let mut selected = Vec::new();
+        let mut total = 0;
+        for utxo in self.wallet(signer, operation).await? {
+            if total >= target {
+                break;
+            }
+            total += utxo.lovelace();
+            selected.push(utxo);
+        }
+        match total >= target {
+            true => Ok(selected),
+            false => Err(ClientError::cardano(
+                operation,
+                format!(
+                    "insufficient funds at {}: {total} lovelace available, {target} needed",
+                    signer.address(self.network())
+                ),
+            )),
+        }
+    }
+
+    /// Lovelace locked by the escrow of `params`.
+    fn amount(params: &EscrowParams) -> Result<u64> {
+        if params.asset.kind != AssetKind::Native {
+            return Err(ClientError::InvalidChainOperation(format!(
+                "{:?} assets are not supported on Cardano",
+                params.asset.kind
+            )));
+        }
+        params
+            .asset
+            .amount
+            .0
+            .to_u64()
+            .ok_or(ClientError::AssetOverflow)
+    }
+
+    /// Plans the transaction performing `operation`.
+    async fn plan<'a>(&'a self, operation: Operation<'_>) -> Result<Plan<'a>> {
+        let name = match operation {
+            Operation::Create(_) => CREATE_ESCROW,
+            Operation::Finish(_) => FINISH_ESCROW,
+            Operation::Cancel(_) => CANCEL_ESCROW,
+        };
+        let tip = self.tip(name).await?;
+        let ttl = tip.slot + TTL_SLOTS;
+        match operation {
+            Operation::Create(params) => {
+                Self::validate_signer(&self.sender, &params.sender, "sender")?;
+                params.memo_bytes()?;
+                let amount = Self::amount(params)?;
+                let network = self.network();
+                let datum = EscrowDatum {
+                    sender: self.sender.key_hash(),
+                    recipient: party_key_hash(&params.recipient)?,
+                    finish_after: params
+                        .finish_after
+                        .map(|slot| network.slot_to_posix_ms(slot)),
+                    cancel_after: params
+                        .cancel_after
+                        .map(|slot| network.slot_to_posix_ms(slot)),
+                    has_conditions: params.has_conditions,
+                    memo: params.memo.as_ref().map(|memo| memo.as_bytes().to_vec()),
+                };
+                let inputs = self
+                    .select(&self.sender, amount + FEE_MARGIN, CREATE_ESCROW)
+                    .await?;
+                Ok(Plan {
+                    name: CREATE_ESCROW,
+                    signer: &self.sender,
+                    inputs,
+                    lock: Some((amount, datum.to_cbor()?)),
+                    spend: None,
+                    valid_from: None,
+                    ttl,
+                })
+            }
+            Operation::Finish(metadata) => {
+                let signer = self.recipient_signer()?;
+                Self::validate_signer(signer, &metadata.params.recipient, "recipient")?;
+                self.spend(metadata, signer, FINISH_ESCROW, tip, ttl).await
+            }
+            Operation::Cancel(metadata) => {
+                Self::validate_signer(&self.sender, &metadata.params.sender, "sender")?;
+                self.spend(metadata, &self.sender, CANCEL_ESCROW, tip, ttl)
+                    .await
+            }
+        }
+    }
+
+    /// Plans a transaction of `signer` spending the escrow in `metadata`
+    /// to finish or cancel it, valid from its timelock.
+    async fn spend<'a>(
+        &self,
+        metadata: &EscrowMetadata,
+        signer: &'a CardanoSigner,
+        name: &'static str,
+        tip: Tip,
+        ttl: u64,
+    ) -> Result<Plan<'a>> {
+        let (hash, index) = Self::escrow_ref(metadata)?;
+        let output = self.output(hash, index, name).await?;
+        if let Some(tx) = &output.consumed_by_tx {
+            return Err(ClientError::cardano(
+                name,
+                format!("escrow {hash}#{index} was settled in transaction {tx}"),
+            ));
+        }
+        let datum = output
+            .datum()
+            .ok_or_else(|| ClientError::cardano(name, "escrow output has no valid datum"))?;
+
+        let (deadline, redeemer) = match name {
+            FINISH_ESCROW => (datum.finish_after, FINISH_REDEEMER),
+            _ => (
+                Some(datum.cancel_after.ok_or_else(|| {
+                    ClientError::InvalidChainOperation("escrow cannot be cancelled".into())
+                })?),
+                CANCEL_REDEEMER,
+            ),
+        };
+        // The first slot starting at or after the deadline.
+        let valid_from = deadline.map(|ms| self.network().posix_ms_to_slot(ms + 999));
+        if let Some(slot) = valid_from.filter(|slot| *slot > tip.slot) {
+            return Err(ClientError::cardano(
+                name,
+                format!(
+                    "escrow cannot be spent before slot {slot}, now at {}",
+                    tip.slot
+                ),
+            ));
+        }
+
+        let collateral = self
+            .wallet(signer, name)
+            .await?
+            .into_iter()
+            .next()
+            .filter(|utxo| utxo.lovelace() >= MIN_COLLATERAL)
+            .ok_or_else(|| {
+                ClientError::cardano(
+                    name,
+                    format!(
+                        "{} needs an output of at least {MIN_COLLATERAL} lovelace for collateral",
+                        signer.address(self.network())
+                    ),
+                )
+            })?;
+        Ok(Plan {
+            name,
+            signer,
+            inputs: vec![collateral],
+            lock: None,
+            spend: Some(Spend {
+                input: input(hash, index.into(), name)?,
+                lovelace: output.lovelace(),
+                redeemer,
+            }),
+            valid_from,
+            ttl,
+        })
+    }
+
+    /// Stages the transaction of `plan` paying `fee`, with the execution
+    /// `budget` of the validator if it spends an escrow.
+    fn stage(
+        &self,
+        plan: &Plan<'_>,
+        params: &ProtocolParams,
+        fee: u64,
+        budget: Budget,
+    ) -> Result<StagingTransaction> {
+        let name = plan.name;
+        let change_address = Address::from_bech32(&plan.signer.address(self.network()))
+            .map_err(|e| ClientError::cardano(name, e))?;
+        let funds: u64 = plan.inputs.iter().map(Utxo::lovelace).sum();
+        let spent = plan.spend.as_ref().map_or(0, |spend| spend.lovelace);
+        let locked = plan.lock.as_ref().map_or(0, |(amount, _)| *amount);
+        let change = (funds + spent)
+            .checked_sub(locked + fee)
+            .ok_or_else(|| ClientError::cardano(name, "insufficient funds for the fee"))?;
+
+        let mut tx = StagingTransaction::new()
+            .network_id(self.network().network_id())
+            .invalid_from_slot(plan.ttl)
+            .fee(fee);
+        for utxo in &plan.inputs {
+            tx = tx.input(utxo.input(name)?);
+        }
+        // The escrow is the first output, as `ChainMetadata` records.
+        if let Some((amount, datum)) = &plan.lock {
+            tx = tx
+                .output(Output::new(self.script.clone(), *amount).set_inline_datum(datum.clone()));
+        }
+        tx = tx.output(Output::new(change_address.clone(), change));
+
+        if let Some(spend) = &plan.spend {
+            let collateral = &plan.inputs[0];
+            let taken = (fee * params.collateral_percent.unwrap_or(150)).div_ceil(100);
+            let returned = collateral
+                .lovelace()
+                .checked_sub(taken)
+                .ok_or_else(|| ClientError::cardano(name, "collateral does not cover the fee"))?;
+            tx = tx
+                .input(spend.input.clone())
+                .reference_input(self.reference.clone())
+                .collateral_input(collateral.input(name)?)
+                .collateral_output(Output::new(change_address, returned))
+                .disclosed_signer(plan.signer.key_hash())
+                .add_spend_redeemer(
+                    spend.input.clone(),
+                    spend.redeemer.to_vec(),
+                    Some(ExUnits {
+                        mem: budget.mem,
+                        steps: budget.steps,
+                    }),
+                )
+                .language_view(ScriptKind::PlutusV3, params.cost_model(name)?);
+        }
+        if let Some(slot) = plan.valid_from {
+            tx = tx.valid_from_slot(slot);
+        }
+        Ok(tx)
+    }
+
+    fn sign(&self, plan: &Plan<'_>, tx: StagingTransaction) -> Result<BuiltTransaction> {
+        let built = tx
+            .build_conway_raw()
+            .map_err(|e| ClientError::cardano(plan.name, e))?;
+        plan.signer.sign(built, plan.name)
+    }
+
+    /// Evaluates the execution budget of the scripts a signed transaction
+    /// runs, or why they would fail.
+    async fn evaluate(
+        &self,
+        tx: &[u8],
+        operation: &'static str,
+    ) -> Result<std::result::Result<Budget, String>> {
+        let response: serde_json::Value = match self
+            .api
+            .post_cbor(&["utils", "txs", "evaluate"], hex::encode(tx).as_bytes())
+            .await
+        {
+            Ok(response) => response,
+            Err(BlockfrostError::Status { message, .. }) => return Ok(Err(message)),
+            Err(e) => return Err(ClientError::cardano(operation, e)),
+        };
+        let result = &response["result"];
+        let Some(budgets) = result["EvaluationResult"].as_object() else {
+            let failure = match &result["EvaluationFailure"] {
+                serde_json::Value::Null => &response["fault"],
+                failure => failure,
+            };
+            return Ok(Err(failure.to_string()));
+        };
+        Ok(Ok(budgets.values().fold(
+            Budget::default(),
+            |budget, units| Budget {
+                mem: budget.mem + units["memory"].as_u64().unwrap_or_default(),
+                steps: budget.steps + units["steps"].as_u64().unwrap_or_default(),
+            },
+        )))
+    }
+
+    /// Returns the size of the validator held as reference script.
+    async fn reference_script_size(&self, operation: &'static str) -> Result<u64> {
+        let (hash, index) = utxo_ref(&self.config.reference_script)?;
+        let script_hash = self
+            .output(hash, index, operation)
+            .await?
+            .reference_script_hash
+            .ok_or_else(|| {
+                ClientError::cardano(
+                    operation,
+                    format!("{hash}#{index} holds no reference script"),
+                )
+            })?;
+        let script: ScriptInfo = self
+            .api
+            .get(&["scripts", &script_hash], &[])
+            .await
+            .map_err(|e| ClientError::cardano(operation, e))?;
+        script
+            .serialised_size
+            .ok_or_else(|| ClientError::cardano(operation, "reference script has no size"))
+    }
+
+    /// Builds and signs the transaction of `plan`, evaluating the execution
+    /// budget of the validator if it spends an escrow, and raising the fee
+    /// until it covers the transaction's size, budget, and reference
+    /// script. Returns why the validator would fail instead, if it would.
+    async fn build(&self, plan: &Plan<'_>) -> Result<std::result::Result<Built, String>> {
+        let name = plan.name;
+        let params = self.protocol_params(name).await?;
+
+        let mut budget = Budget::default();
+        let mut script_fee = 0;
+        if plan.spend.is_some() {
+            // Evaluate with the largest budget and fee a transaction may
+            // have, so the draft is well-formed.
+            let limit = params.max_budget();
+            let max_fee = params.min_fee_a * params.max_tx_size + params.min_fee_b;
+            let draft = self.sign(plan, self.stage(plan, &params, max_fee, limit)?)?;
+            budget = match self.evaluate(&draft.tx_bytes.0, name).await? {
+                Ok(budget) => budget,
+                Err(error) => return Ok(Err(error)),
+            };
+            let size = self.reference_script_size(name).await?;
+            script_fee = params.execution_fee(budget) + params.reference_script_fee(size);
+        }
+
+        let mut fee = 0;
+        for _ in 0..FEE_ROUNDS {
+            let tx = self.sign(plan, self.stage(plan, &params, fee, budget)?)?;
+            let needed =
+                params.min_fee_a * tx.tx_bytes.0.len() as u64 + params.min_fee_b + script_fee;
+            if needed <= fee {
+                debug!(
+                    fee,
+                    mem = budget.mem,
+                    steps = budget.steps,
+                    "Built {} transaction",
+                    name
+                );
+                return Ok(Ok(Built { tx, fee, budget }));
+            }
+            fee = needed;
+        }
+        Err(ClientError::cardano(
+            name,
+            "transaction fee did not converge",
+        ))
+    }
+
+    /// Builds, signs, and submits the transaction performing `operation`,
+    /// and waits for it to be included.
+    async fn execute(&self, operation: Operation<'_>) -> Result<TxInfo> {
+        let plan = self.plan(operation).await?;
+        let built = self
+            .build(&plan)
+            .await?
+            .map_err(|e| ClientError::cardano(plan.name, e))?;
+        let hash: String = self
+            .api
+            .post_cbor(&["tx", "submit"], &built.tx.tx_bytes.0)
+            .await
+            .map_err(|e| ClientError::cardano(plan.name, e))?;
+        info!(tx_hash = %hash, fee = built.fee, "{} transaction sent", plan.name);
+
+        self.await_tx(&hash, plan.ttl, plan.name).await
+    }
+
+    /// Polls for transaction `hash` until it is included in a block, it
+    /// expires after slot `ttl`, or the wait times out.
+    async fn await_tx(&self, hash: &str, ttl: u64, operation: &'static str) -> Result<TxInfo> {
+        let deadline = self.wait.timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            match self.api.get::<TxInfo>(&["txs", hash], &[]).await {
+                Ok(tx) => return check(tx, operation),
+                Err(e) if e.is_not_found() => {}
+                Err(e) => return Err(ClientError::cardano(operation, e)),
+            }
+            let slot = self.tip(operation).await?.slot;
+            if slot > ttl {
+                return Err(ClientError::tx_dropped(format!(
+                    "transaction {hash} was not included before it expired at slot {ttl}"
+                )));
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(pending(operation, hash, ttl));
+            }
+            tokio::time::sleep(self.poll_interval()).await;
+        }
+    }
+
+    /// Payment key hashes of the configured sender and recipient.
+    fn parties(&self) -> Vec<Hash<28>> {
+        std::iter::once(&self.sender)
+            .chain(self.recipient.as_ref())
+            .map(CardanoSigner::key_hash)
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl Agent for CardanoAgent {
+    async fn create_escrow(&self, params: &EscrowParams) -> Result<EscrowMetadata> {
+        let tx = self.execute(Operation::Create(params)).await?;
+        info!("{} transaction confirmed", CREATE_ESCROW);
+        debug!(escrow = %format!("{}#0", tx.hash), "Escrow created");
+        let uid = Self::amount(params).ok().and_then(|lovelace| {
+            let recipient = party_key_hash(&params.recipient).ok()?;
+            self.escrow_uid(self.sender.key_hash(), recipient, &tx.hash, 0, lovelace)
+        });
+
+        Ok(EscrowMetadata {
+            params: params.clone(),
+            state: ExecutionState::Funded,
+            escrow_id: None,
+            uid,
+            chain_metadata: Some(ChainMetadata::Cardano {
+                tx_hash: tx.hash,
+                output_index: 0,
+            }),
+            guest: None,
+            observed: None,
+        })
+    }
+
+    async fn finish_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
+        self.execute(Operation::Finish(metadata)).await?;
+        info!("{} transaction confirmed", FINISH_ESCROW);
+        Ok(())
+    }
+
+    async fn cancel_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
+        self.execute(Operation::Cancel(metadata)).await?;
+        info!("{} transaction confirmed", CANCEL_ESCROW);
+        Ok(())
+    }
+
+    /// Builds the transaction, evaluating the validator if it spends an
+    /// escrow; transactions locking escrows run no scripts, so are only
+    /// priced.
+    async fn simulate(&self, operation: Operation<'_>) -> Result<Simulation> {
+        let plan = self.plan(operation).await?;
+        Ok(match self.build(&plan).await? {
+            Ok(built) => Simulation {
+                operation: plan.name,
+                error: None,
+                logs: Vec::new(),
+                units: plan.spend.is_some().then_some(built.budget.steps),
+                fee: Some(BigNumber::from(built.fee)),
+            },
+            Err(error) => Simulation {
+                operation: plan.name,
+                error: Some(error),
+                logs: Vec::new(),
+                units: None,
+                fee: None,
+            },
+        })
+    }
+
+    async fn get_escrow_state(&self, metadata: &EscrowMetadata) -> Result<EscrowStatus> {
+        let (hash, index) = Self::escrow_ref(metadata)?;
+        let current_height = self.tip(GET_ESCROW).await?.slot;
+        let output = self.output(hash, index, GET_ESCROW).await?;
+        let datum = output
+            .datum()
+            .ok_or_else(|| ClientError::cardano(GET_ESCROW, "escrow output has no valid datum"))?;
+        Ok(status(
+            &datum,
+            output.lovelace(),
+            output.consumed_by_tx.is_some(),
+            self.network(),
+            current_height,
+        ))
+    }
+
+    async fn current_height(&self) -> Result<u64> {
+        Ok(self.tip(GET_ESCROW).await?.slot)
+    }
+
+    async fn latest_block(&self) -> Result<ObservedBlock> {
+        self.block(&["latest"])
+            .await?
+            .ok_or_else(|| ClientError::cardano(GET_BLOCK, "latest block not found"))
+    }
+
+    /// Reads the hash of the block in slot `height`.
+    async fn block_hash(&self, height: u64) -> Result<Option<[u8; 32]>> {
+        let block = self.block(&["slot", &height.to_string()]).await?;
+        Ok(block.map(|block| block.hash))
+    }
+
+    /// Reads the slot of the block [`SECURITY_PARAM`] blocks below the
+    /// latest, which can no longer be rolled back.
+    async fn finalized_height(&self) -> Result<u64> {
+        let tip = self.tip(GET_BLOCK).await?;
+        let number = tip.height.saturating_sub(SECURITY_PARAM);
+        Ok(self
+            .block(&[&number.to_string()])
+            .await?
+            .map_or(0, |block| block.height))
+    }
+
+    /// Pages through the UTxOs at the validator's address. Spent escrows
+    /// leave no UTxO, so only unsettled ones are included.
+    async fn list_escrows(&self) -> Result<Vec<EscrowSummary>> {
+        let current_height = self.tip(LIST_ESCROWS).await?.slot;
+        let parties = self.parties();
+        let network = self.network();
+        let mut escrows = Vec::new();
+        for page in 1.. {
+            let page = page.to_string();
+            let batch: Vec<Utxo> = match self
+                .api
+                .get(
+                    &["addresses", &self.script_address, "utxos"],
+                    &[("count", "100"), ("page", &page)],
+                )
+                .await
+            {
+                Ok(batch) => batch,
+                Err(e) if e.is_not_found() => Vec::new(),
+                Err(e) => return Err(ClientError::cardano(LIST_ESCROWS, e)),
+            };
+            let full = batch.len() == PAGE_SIZE;
+            escrows.extend(batch.iter().filter_map(|utxo| {
+                let datum = EscrowDatum::from_hex(utxo.inline_datum.as_deref()?)?;
+                datum.involves(&parties).then(|| EscrowSummary {
+                    id: format!("{}#{}", utxo.tx_hash, utxo.output_index),
+                    uid: self.escrow_uid(
+                        datum.sender,
+                        datum.recipient,
+                        &utxo.tx_hash,
+                        utxo.output_index,
+                        utxo.lovelace(),
+                    ),
+                    sender: enterprise_address(datum.sender, network),
+                    recipient: enterprise_address(datum.recipient, network),
+                    status: status(&datum, utxo.lovelace(), false, network, current_height),
+                })
+            }));
+            if !full {
+                break;
+            }
+        }
+
+        escrows.sort_by(|a, b| a.id.cmp(&b.id));
+        info!(count = escrows.len(), "Found escrows");
+        Ok(escrows)
+    }
+
+    /// Polls for transactions at the validator's address in new blocks,
+    /// reporting escrows they lock and spend.
+    async fn watch_escrows(&self, metadata: Option<&EscrowMetadata>) -> Result<UpdateStream> {
+        let escrow = metadata
+            .map(Self::escrow_ref)
+            .transpose()?
+            .map(|(hash, index)| format!("{hash}#{index}"));
+        let parties = self.parties();
+        let script = self.script_address.clone();
+        let network = self.network();
+        let api = self.api.clone();
+        let interval = self.poll_interval();
+        let mut from = self.tip(WATCH).await?.height + 1;
+        info!(url = %api.url(), %script, "Watching escrow validator transactions");
+
+        let (updates, stream) = mpsc::unbounded();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let start = from.to_string();
+                let txs: std::result::Result<Vec<AddressTx>, _> = api
+                    .get(
+                        &["addresses", &script, "transactions"],
+                        &[("order", "asc"), ("count", "100"), ("from", &start)],
+                    )
+                    .await;
+                let txs = match txs {
+                    Ok(txs) => txs,
+                    Err(e) if e.is_not_found() => continue,
+                    Err(e) => {
+                        warn!(error = %e, "Failed to list escrow transactions");
+                        if updates
+                            .unbounded_send(Err(ClientError::cardano(WATCH, e)))
+                            .is_err()
+                        {
+                            return;
+                        }
+                        continue;
+                    }
+                };
+                for tx in &txs {
+                    let found = match tx_updates(&api, &script, network, &tx.tx_hash).await {
+                        Ok(found) => found,
+                        Err(e) => {
+                            warn!(
+                                error = %e,
+                                tx_hash = %tx.tx_hash,
+                                "Failed to decode escrow transaction"
+                            );
+                            if updates
+                                .unbounded_send(Err(ClientError::cardano(WATCH, e)))
+                                .is_err()
+                            {
+                                return;
+                            }
+                            break;
+                        }
+                    };
+                    from = from.max(tx.block_height + 1);
+                    for (update, datum) in found {
+                        let wanted = match &escrow {
+                            Some(escrow) => update.escrow == *escrow,
+                            None => datum.involves(&parties),
+                        };
+                        if wanted && updates.unbounded_send(Ok(update)).is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+        Ok(stream.boxed())
+    }
+
+    async fn wait_for_transaction(&self, pending: &PendingTransaction) -> Result<()> {
+        if !matches!(pending.chain, Chain::Cardano) {
+            return Err(ClientError::ConfigMismatch {
+                expected: Chain::Cardano.as_ref().to_owned(),
+                actual: pending.chain.as_ref().to_owned(),
+            });
+        }
+        let operation = [CREATE_ESCROW, FINISH_ESCROW, CANCEL_ESCROW]
+            .into_iter()
+            .find(|operation| *operation == pending.operation)
+            .unwrap_or(WAIT_FOR_TRANSACTION);
+        let ttl = pending
+            .blockhash
+            .as_deref()
+            .and_then(|ttl| ttl.parse().ok())
+            .ok_or_else(|| {
+                ClientError::cardano(operation, "pending transaction has no expiry slot")
+            })?;
+
+        self.await_tx(&pending.id, ttl, operation).await?;
+        info!(tx_hash = %pending.id, "{} transaction confirmed", operation);
+        Ok(())
+    }
+}
+
+/// Decodes the escrows transaction `hash` locks at and spends from the
+/// `script` address, with their datums.
+async fn tx_updates(
+    api: &BlockfrostClient,
+    script: &str,
+    network: CardanoNetwork,
+    hash: &str,
+) -> std::result::Result<Vec<(EscrowUpdate, EscrowDatum)>, BlockfrostError> {
+    let info: TxInfo = api.get(&["txs", hash], &[]).await?;
+    // The validator failed, so only the collateral was taken.
+    if !info.valid_contract {
+        return Ok(Vec::new());
+    }
+    let utxos: TxUtxos = api.get(&["txs", hash, "utxos"], &[]).await?;
+    let update = |kind, output: &TxOutput, escrow: String| {
+        let datum = output.datum()?;
+        let update = EscrowUpdate {
+            escrow,
+            kind,
+            sender: Some(enterprise_address(datum.sender, network)),
+            recipient: Some(enterprise_address(datum.recipient, network)),
+            amount: BigNumber::from(output.lovelace()),
+            tx: Some(hash.to_owned()),
+        };
+        Some((update, datum))
+    };
+
+    let mut found: Vec<_> = utxos
+        .outputs
+        .iter()
+        .filter(|output| output.address == script)
+        .filter_map(|output| {
+            update(
+                UpdateKind::Created,
+                output,
+                format!("{hash}#{}", output.output_index),
+            )
+        })
+        .collect();
+
+    // Redeemers refer to spent inputs by their position in sorted order.
+    let mut spent: Vec<&TxOutput> = utxos
+        .inputs
+        .iter()
+        .filter(|input| !input.collateral && !input.reference)
+        .collect();
+    spent.sort_by(|a, b| (&a.tx_hash, a.output_index).cmp(&(&b.tx_hash, b.output_index)));
+    if spent.iter().any(|input| input.address == script) {
+        let redeemers: Vec<RedeemerInfo> = api.get(&["txs", hash, "redeemers"], &[]).await?;
+        for redeemer in redeemers
+            .iter()
+            .filter(|redeemer| redeemer.purpose == "spend")
+        {
+            let Some(input) = spent
+                .get(redeemer.tx_index as usize)
+                .copied()
+                .filter(|input| input.address == script)
+            else {
+                continue;
+            };
+            let data: DatumCbor = api
+                .get(
+                    &["scripts", "datum", &redeemer.redeemer_data_hash, "cbor"],
+                    &[],
+                )
+                .await?;
+            let kind = match hex::decode(&data.cbor).ok().as_deref() {
+                Some(FINISH_REDEEMER) => UpdateKind::Finished,
+                Some(CANCEL_REDEEMER) => UpdateKind::Cancelled,
+                _ => continue,
+            };
+            let escrow = format!("{}#{}", input.tx_hash, input.output_index);
+            found.extend(update(kind, input, escrow));
+        }
+    }
+    Ok(found)
+}
+
+/// Parses a `<tx hash>#<output index>` UTxO reference.
+fn utxo_ref(reference: &str) -> Result<(&str, u32)> {
+    reference
+        .split_once('#')
+        .and_then(|(hash, index)| Some((hash, index.parse().ok()?)))
+        .ok_or_else(|| {
+            ClientError::InvalidChainOperation(format!(
+                "invalid UTxO reference {reference}, expected <tx hash>#<index>"
+            ))
+        })
+}
+
+fn input(hash: &str, index: u64, operation: &'static str) -> Result<Input> {
+    Hash::<32>::from_str(hash)
+        .map(|hash| Input::new(hash, index))
+        .map_err(|_| ClientError::cardano(operation, format!("invalid transaction hash {hash}")))
+}
+
+/// Returns the payment key hash of an escrow party's address.
+fn party_key_hash(party: &Party) -> Result<Hash<28>> {
+    match Address::from_bech32(&party.to_string()) {
+        Ok(Address::Shelley(address)) => match address.payment() {
+            ShelleyPaymentPart::Key(hash) => Ok(*hash),
+            ShelleyPaymentPart::Script(_) => Err(ClientError::InvalidChainOperation(format!(
+                "{party} is a script address, not a key's"
+            ))),
+        },
+        _ => Err(ClientError::InvalidChainOperation(format!(
+            "{party} is not a Cardano payment address"
+        ))),
+    }
+}
+
+/// Returns the enterprise address of payment key `hash`.
+fn enterprise(hash: Hash<28>, network: CardanoNetwork) -> Address {
+    Address::Shelley(ShelleyAddress::new(
+        Network::from(network.network_id()),
+        ShelleyPaymentPart::key_hash(hash),
+        ShelleyDelegationPart::Null,
+    ))
+}
+
+/// Returns the Bech32 enterprise address of payment key `hash`.
+fn enterprise_address(hash: Hash<28>, network: CardanoNetwork) -> String {
+    enterprise(hash, network)
+        .to_bech32()
+        .expect("Shelley addresses encode to Bech32")
+}
+
+/// Builds the error for a transaction still pending when its wait timed
+/// out, with the slot it expires after.
+fn pending(operation: &str, tx_hash: &str, ttl: u64) -> ClientError {
+    ClientError::Pending(PendingTransaction {
+        chain: Chain::Cardano,
+        operation: operation.to_owned(),
+        id: tx_hash.to_owned(),
+        blockhash: Some(ttl.to_string()),
+    })
+}
+
+/// Fails if the validator of the transaction in `tx` failed, and its
+/// collateral was taken instead.
+fn check(tx: TxInfo, operation: &'static str) -> Result<TxInfo> {
+    match tx.valid_contract {
+        true => Ok(tx),
+        false => Err(ClientError::cardano(
+            operation,
+            format!(
+                "transaction {} failed script validation; its collateral was taken",
+                tx.hash
+            ),
+        )),
+    }
+}
+
+/// Converts the datum of an escrow UTxO holding `lovelace` into its live
+/// status, with the timelocks in slots.
+fn status(
+    datum: &EscrowDatum,
+    lovelace: u64,
+    settled: bool,
+    network: CardanoNetwork,
+    current_height: u64,
+) -> EscrowStatus {
+    EscrowStatus {
+        settled,
+        amount: BigNumber::from(lovelace),
+        finish_after: datum.finish_after.map(|ms| network.posix_ms_to_slot(ms)),
+        cancel_after: datum.cancel_after.map(|ms| network.posix_ms_to_slot(ms)),
+        current_height,
+        has_conditions: datum.has_conditions,
+    }
+}
+
+/// A transaction of a signer, before its fee and execution budget are set.
+struct Plan<'a> {
+    name: &'static str,
+    signer: &'a CardanoSigner,
+    /// Outputs of the signer holding only lovelace, funding the escrow or
+    /// the fee; the first one is also the collateral when spending.
+    inputs: Vec<Utxo>,
+    /// Escrow locked by the transaction: its lovelace and inline datum.
+    lock: Option<(u64, Vec<u8>)>,
+    /// Escrow spent by the transaction.
+    spend: Option<Spend>,
+    /// First slot the transaction is valid in, if limited.
+    valid_from: Option<u64>,
+    /// Slot the transaction expires after.
+    ttl: u64,
+}
+
+/// An escrow UTxO spent through the validator.
+struct Spend {
+    input: Input,
+    lovelace: u64,
+    redeemer: &'static [u8],
+}
+
+/// A signed transaction with its fee and execution budget.
+struct Built {
+    tx: BuiltTransaction,
+    fee: u64,
+    budget: Budget,
+}
+
+/// Memory and CPU steps the validator may use.
+#[derive(Debug, Clone, Copy, Default)]
+struct Budget {
+    mem: u64,
+    steps: u64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Tip {
+    slot: u64,
+    height: u64,
+}
+
+/// Inline datum of an escrow UTxO, as the validator's `EscrowDatum`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct EscrowDatum {
+    sender: Hash<28>,
+    recipient: Hash<28>,
+    /// POSIX time in milliseconds.
+    finish_after: Option<u64>,
+    /// POSIX time in milliseconds.
+    cancel_after: Option<u64>,
+    has_conditions: bool,
+    memo: Option<Vec<u8>>,
+}
+
+impl EscrowDatum {
+    /// Encodes the datum as Plutus data.
+    fn to_cbor(&self) -> Result<Vec<u8>> {
+        let int = |value: u64| Value::Integer(value.into());
+        let option = |value: Option<Value>| match value {
+            Some(value) => constr(0, vec![value]),
+            None => constr(1, Vec::new()),
+        };
+        let datum = constr(
+            0,
+            vec![
+                Value::Bytes(self.sender.to_vec()),
+                Value::Bytes(self.recipient.to_vec()),
+                option(self.finish_after.map(int)),
+                option(self.cancel_after.map(int)),
+                constr(u64::from(self.has_conditions), Vec::new()),
+                option(self.memo.clone().map(Value::Bytes)),
+            ],
+        );
+        let mut bytes = Vec::new();
+        ciborium::into_writer(&datum, &mut bytes)
+            .map_err(|e| ClientError::Serialization(e.to_string()))?;
+        Ok(bytes)
+    }
+
+    /// Decodes a hex-encoded inline datum, if it is an escrow's.
+    fn from_hex(datum: &str) -> Option<Self> {
+        let bytes = hex::decode(datum).ok()?;
+        let value: Value = ciborium::from_reader(bytes.as_slice()).ok()?;
+        let [sender, recipient, finish_after, cancel_after, has_conditions, memo] =
+            <[Value; 6]>::try_from(fields(value, 0)?).ok()?;
+        let key_hash = |value: Value| -> Option<Hash<28>> {
+            <[u8; 28]>::try_from(value.into_bytes().ok()?)
+                .ok()
+                .map(Hash::new)
+        };
+        let option = |value: Value| -> Option<Option<Value>> {
+            match tag(&value)? {
+                0 => fields(value, 0)?.into_iter().next().map(Some),
+                1 => Some(None),
+                _ => None,
+            }
+        };
+        let time = |value: Value| -> Option<Option<u64>> {
+            match option(value)? {
+                Some(value) => Some(Some(u64::try_from(value.into_integer().ok()?).ok()?)),
+                None => Some(None),
+            }
+        };
+        Some(Self {
+            sender: key_hash(sender)?,
+            recipient: key_hash(recipient)?,
+            finish_after: time(finish_after)?,
+            cancel_after: time(cancel_after)?,
+            has_conditions: tag(&has_conditions)? == 1,
+            memo: match option(memo)? {
+                Some(memo) => Some(memo.into_bytes().ok()?),
+                None => None,
+            },
+        })
+    }
+
+    /// Whether any of the payment key hashes `parties` is a party.
+    fn involves(&self, parties: &[Hash<28>]) -> bool {
+        parties.contains(&self.sender) || parties.contains(&self.recipient)
+    }
+}
+
+/// Plutus data of constructor `index` with `fields`.
+fn constr(index: u64, fields: Vec<Value>) -> Value {
+    Value::Tag(121 + index, Box::new(Value::Array(fields)))
+}
+
+/// Constructor index of Plutus data, if it is a constructor.
+fn tag(value: &Value) -> Option<u64> {
+    match value {
+        Value::Tag(tag @ 121..=127, _) => Some(tag - 121),
+        _ => None,
+    }
+}
+
+/// Fields of Plutus data of constructor `index`.
+fn fields(value: Value, index: u64) -> Option<Vec<Value>> {
+    match value {
+        Value::Tag(tag, fields) if tag == 121 + index => fields.into_array().ok(),
+        _ => None,
+    }
+}
+
+#[derive(Deserialize)]
+struct LatestBlock {
+    hash: Option<String>,
+    slot: Option<u64>,
+    height: Option<u64>,
+}
+
+/// Protocol parameters of the current epoch.
+#[derive(Deserialize)]
+struct ProtocolParams {
+    min_fee_a: u64,
+    min_fee_b: u64,
+    max_tx_size: u64,
+    price_mem: Option<f64>,
+    price_step: Option<f64>,
+    max_tx_ex_mem: Option<String>,
+    max_tx_ex_steps: Option<String>,
+    collateral_percent: Option<u64>,
+    min_fee_ref_script_cost_per_byte: Option<f64>,
+    #[serde(default)]
+    cost_models_raw: Option<HashMap<String, Vec<i64>>>,
+}
+
+impl ProtocolParams {
+    /// Largest execution budget of a transaction.
+    fn max_budget(&self) -> Budget {
+        let parse = |limit: &Option<String>| {
+            limit
+                .as_deref()
+                .and_then(|limit| limit.parse().ok())
+                .unwrap_or_default()
+        };
+        Budget {
+            mem: parse(&self.max_tx_ex_mem),
+            steps: parse(&self.max_tx_ex_steps),
+        }
+    }
+
+    /// Fee of the execution `budget`.
+    fn execution_fee(&self, budget: Budget) -> u64 {
+        let fee = budget.mem as f64 * self.price_mem.unwrap_or_default()
+            + budget.steps as f64 * self.price_step.unwrap_or_default();
+        fee.ceil() as u64
+    }
+
+    /// Fee of referencing a script of `size` bytes, as charged below the
+    /// first 25 KiB tier.
+    fn reference_script_fee(&self, size: u64) -> u64 {
+        (size as f64 * self.min_fee_ref_script_cost_per_byte.unwrap_or_default()).ceil() as u64
+    }
+
+    /// Plutus V3 cost model, for the script integrity hash.
+    fn cost_model(&self, operation: &'static str) -> Result<Vec<i64>> {
+        self.cost_models_raw
+            .as_ref()
+            .and_then(|models| models.get("PlutusV3"))
+            .cloned()
+            .ok_or_else(|| ClientError::cardano(operation, "network has no Plutus V3 cost model"))
+    }
+}
+
+/// An unspent output at an address.
+#[derive(Debug, Deserialize)]
+struct Utxo {
+    tx_hash: String,
+    output_index: u64,
+    amount: Vec<Amount>,
+    #[serde(default)]
+    inline_datum: Option<String>,
+}
+
+impl Utxo {
+    fn lovelace(&self) -> u64 {
+        lovelace(&self.amount)
+    }
+
+    fn lovelace_only(&self) -> bool {
+        self.amount.iter().all(|amount| amount.unit == "lovelace")
+    }
+
+    fn input(&self, operation: &'static str) -> Result<Input> {
+        input(&self.tx_hash, self.output_index, operation)
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct Amount {
+    unit: String,
+    quantity: String,
+}
+
+fn lovelace(amount: &[Amount]) -> u64 {
+    amount
+        .iter()
+        .filter(|amount| amount.unit == "lovelace")
+        .filter_map(|amount| amount.quantity.parse::<u64>().ok())
+        .sum()
+}
+
+/// Inputs and outputs of a transaction.
+#[derive(Deserialize)]
+struct TxUtxos {
+    inputs: Vec<TxOutput>,
+    outputs: Vec<TxOutput>,
+}
+
+/// An input or output of a transaction; inputs are the outputs they spend.
+#[derive(Deserialize)]
+struct TxOutput {
+    address: String,
+    amount: Vec<Amount>,
+    /// Transaction of the spent output (inputs only).
+    #[serde(default)]
+    tx_hash: String,
+    output_index: u64,
+    #[serde(default)]
+    inline_datum: Option<String>,
+    #[serde(default)]
+    reference_script_hash: Option<String>,
+    #[serde(default)]
+    collateral: bool,
+    #[serde(default)]
+    reference: bool,
+    /// Transaction spending the output (outputs only).
+    #[serde(default)]
+    consumed_by_tx: Option<String>,
+}
+
+impl TxOutput {
+    fn lovelace(&self) -> u64 {
+        lovelace(&self.amount)
+    }
+
+    fn datum(&self) -> Option<EscrowDatum> {
+        EscrowDatum::from_hex(self.inline_datum.as_deref()?)
+    }
+}
+
+/// Outcome of an included transaction.
+#[derive(Deserialize)]
+struct TxInfo {
+    hash: String,
+    #[serde(default = "valid")]
+    valid_contract: bool,
+}
+
+fn valid() -> bool {
+    true
+}
+
+#[derive(Deserialize)]
+struct ScriptInfo {
+    serialised_size: Option<u64>,
+}
+
+#[derive(Deserialize)]
+struct AddressTx {
+    tx_hash: String,
+    block_height: u64,
+}
+
+#[derive(Deserialize)]
+struct RedeemerInfo {
+    tx_index: u64,
+    purpose: String,
+    redeemer_data_hash: String,
+}
+
+#[derive(Deserialize)]
+struct DatumCbor {
+    cbor: String,
+}
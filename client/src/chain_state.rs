@@ -0,0 +1,88 @@
+//! Recent chain state attested to in proofs.
+//!
+//! The host fetches a recent block header (Ethereum) or slot hash (Solana),
+//! checks it lightly, and passes it to the guest in the proof's
+//! [`EscrowBinding`](zescrow_core::interface::EscrowBinding), which commits
+//! it to the journal. Verifiers then check that the block or slot is
+//! recent and has the committed hash: the Ethereum escrow contract does so
+//! with `blockhash`.
+
+use alloy::eips::BlockNumberOrTag;
+use alloy::providers::{DynProvider, Provider};
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::slot_hashes::{self, SlotHashes};
+use solana_sdk::{account, sysvar};
+use zescrow_core::interface::ChainState;
+
+use crate::error::ClientError;
+use crate::Result;
+
+const CHAIN_STATE: &str = "chainState";
+
+/// Blocks the Ethereum escrow contract can look up with `blockhash`, so the
+/// most an attested block may lag behind the finish transaction.
+pub const ETHEREUM_BLOCKHASH_WINDOW: u64 = 256;
+
+/// Fetches the header of the block `depth` blocks below the latest, so a
+/// shallow reorg cannot invalidate it, and checks that it hashes to the
+/// hash the node reports.
+///
+/// # Errors
+///
+/// Returns an error if the block cannot be fetched, or its header does not
+/// match its hash.
+pub async fn ethereum(provider: &DynProvider, depth: u64) -> Result<ChainState> {
+    let err = |e| ClientError::ethereum(CHAIN_STATE, e);
+    let latest = provider.get_block_number().await.map_err(err)?;
+    let number = latest.saturating_sub(depth);
+    let block = provider
+        .get_block_by_number(BlockNumberOrTag::Number(number))
+        .await
+        .map_err(err)?
+        .ok_or_else(|| ClientError::ethereum(CHAIN_STATE, format!("block {number} not found")))?;
+    let header = &block.header;
+    if header.inner.number != number || header.inner.hash_slow() != header.hash {
+        return Err(ClientError::ethereum(
+            CHAIN_STATE,
+            format!("header of block {number} does not match its hash"),
+        ));
+    }
+    Ok(ChainState {
+        height: number,
+        hash: header.hash.0,
+    })
+}
+
+/// Reads the most recent slot hash from the `SlotHashes` sysvar, and checks
+/// that the account is the sysvar and the slot is recent.
+///
+/// # Errors
+///
+/// Returns an error if the sysvar cannot be read or decoded, or its latest
+/// slot is not recent.
+pub fn solana(client: &RpcClient) -> Result<ChainState> {
+    let err = |e| ClientError::solana(CHAIN_STATE, e);
+    let account = client.get_account(&sysvar::slot_hashes::ID).map_err(err)?;
+    if account.owner != sysvar::ID {
+        return Err(ClientError::solana(
+            CHAIN_STATE,
+            "SlotHashes account is not owned by the sysvar program",
+        ));
+    }
+    let slot_hashes: SlotHashes = account::from_account(&account)
+        .ok_or_else(|| ClientError::solana(CHAIN_STATE, "failed to decode SlotHashes"))?;
+    let &(slot, hash) = slot_hashes
+        .first()
+        .ok_or_else(|| ClientError::solana(CHAIN_STATE, "SlotHashes is empty"))?;
+    let current = client.get_slot().map_err(err)?;
+    if slot > current || current - slot > slot_hashes::MAX_ENTRIES as u64 {
+        return Err(ClientError::solana(
+            CHAIN_STATE,
+            format!("latest slot hash is for slot {slot}, not recent at slot {current}"),
+        ));
+    }
+    Ok(ChainState {
+        height: slot,
+        hash: hash.to_bytes(),
+    })
+}
@@ -0,0 +1,296 @@
+//! Address book of named parties, managed with the `contacts` commands and
+//! referred to as `"recipient": "@<name>"` in the escrow parameters.
+//!
+//! Each contact holds one address per chain:
+//!
+//! ```json
+//! {
+//!   "alice": {
+//!     "solana": "9xQeWvG816bUx9EPjHmaT23yvVM2ZWbrrpZb9PusVFin",
+//!     "ethereum": "0x70997970C51812dc3A010C7d01b50e0d17dc79C8"
+//!   }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::Path;
+use std::str::FromStr;
+
+use anyhow::{anyhow, bail, Context};
+use clap::Subcommand;
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use zescrow_core::interface::{load_escrow_data, save_escrow_data};
+use zescrow_core::{Chain, Party, ID};
+
+use crate::output::{print_json, OutputFormat};
+
+/// Default path of the contacts file.
+pub const CONTACTS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/contacts.json");
+
+/// Prefix marking a contact name in place of a party.
+pub const CONTACT_PREFIX: &str = "@";
+
+/// Chains a contact can have an address on, in listing order.
+const CHAINS: [Chain; 7] = [
+    Chain::Solana,
+    Chain::Ethereum,
+    Chain::Cosmos,
+    Chain::Substrate,
+    Chain::Cardano,
+    Chain::Tron,
+    Chain::Hedera,
+];
+
+#[derive(Subcommand, Debug)]
+pub enum ContactsCmd {
+    /// Add a contact, or set its address on another chain
+    Add {
+        /// Name to refer to the contact by, as `@<NAME>`
+        name: String,
+
+        /// Chain of the address (`solana`, `ethereum`, `cosmos`,
+        /// `substrate`, `cardano`, or `tron`)
+        #[arg(long)]
+        chain: Chain,
+
+        /// Base58 public key (Solana), 0x-prefixed address (Ethereum),
+        /// Bech32 address (Cosmos and Cardano), SS58 address (Substrate),
+        /// base58check address (Tron), or account ID or 0x-prefixed EVM
+        /// address (Hedera)
+        #[arg(long)]
+        address: String,
+    },
+
+    /// List contacts with their addresses
+    List,
+
+    /// Remove a contact, or only its address on one chain
+    Remove {
+        /// Name of the contact
+        name: String,
+
+        /// Remove only the address on this chain
+        #[arg(long)]
+        chain: Option<Chain>,
+    },
+}
+
+/// Addresses of one contact.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Contact {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    solana: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    ethereum: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cosmos: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    substrate: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    cardano: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    tron: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    hedera: Option<String>,
+}
+
+impl Contact {
+    fn address(&self, chain: Chain) -> Option<&str> {
+        match chain {
+            Chain::Solana => self.solana.as_deref(),
+            Chain::Ethereum => self.ethereum.as_deref(),
+            Chain::Cosmos => self.cosmos.as_deref(),
+            Chain::Substrate => self.substrate.as_deref(),
+            Chain::Cardano => self.cardano.as_deref(),
+            Chain::Tron => self.tron.as_deref(),
+            Chain::Hedera => self.hedera.as_deref(),
+        }
+    }
+
+    fn address_mut(&mut self, chain: Chain) -> &mut Option<String> {
+        match chain {
+            Chain::Solana => &mut self.solana,
+            Chain::Ethereum => &mut self.ethereum,
+            Chain::Cosmos => &mut self.cosmos,
+            Chain::Substrate => &mut self.substrate,
+            Chain::Cardano => &mut self.cardano,
+            Chain::Tron => &mut self.tron,
+            Chain::Hedera => &mut self.hedera,
+        }
+    }
+
+    /// Whether the contact has no address on any chain.
+    fn is_empty(&self) -> bool {
+        CHAINS.iter().all(|chain| self.address(*chain).is_none())
+    }
+}
+
+/// Contacts by name.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Contacts(BTreeMap<String, Contact>);
+
+impl Contacts {
+    /// Loads the contacts file at `path`, which may not exist yet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file exists but cannot be read or parsed.
+    pub fn load(path: &Path) -> anyhow::Result<Self> {
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+        load_escrow_data(path)
+    }
+
+    /// Returns the party of contact `name` on `chain`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such contact, or it has no address
+    /// on `chain`.
+    pub fn party(&self, name: &str, chain: Chain) -> anyhow::Result<Party> {
+        let contact = self.0.get(name).ok_or_else(|| {
+            let names = self.0.keys().cloned().collect::<Vec<_>>().join(", ");
+            anyhow!("no contact `{name}` (available: {names})")
+        })?;
+        let address = contact
+            .address(chain)
+            .ok_or_else(|| anyhow!("contact `{name}` has no {} address", chain.as_ref()))?;
+        parse_party(chain, address)
+    }
+}
+
+/// Parses an address on `chain` into a party in that chain's encoding.
+fn parse_party(chain: Chain, address: &str) -> anyhow::Result<Party> {
+    let id = match chain {
+        Chain::Solana => {
+            let pubkey = Pubkey::from_str(address)
+                .map_err(|e| anyhow!("invalid Solana address {address}: {e}"))?;
+            ID::Base58(pubkey.to_string())
+        }
+        Chain::Ethereum => {
+            let address = alloy::primitives::Address::from_str(address)
+                .map_err(|e| anyhow!("invalid Ethereum address {address}: {e}"))?;
+            ID::Hex(hex::encode(address))
+        }
+        Chain::Cosmos => {
+            bech32::decode(address)
+                .map_err(|e| anyhow!("invalid Cosmos address {address}: {e}"))?;
+            ID::Bech32(address.to_lowercase())
+        }
+        Chain::Substrate => match ID::from_str(address)? {
+            id @ ID::Ss58(_) => id,
+            _ => bail!("invalid Substrate address {address}: not an SS58 address"),
+        },
+        // Shelley addresses have header types 0 to 7.
+        Chain::Cardano => match bech32::decode(address) {
+            Ok((_, bytes)) if bytes.first().is_some_and(|header| header >> 4 < 8) => {
+                ID::Bech32(address.to_owned())
+            }
+            _ => bail!("invalid Cardano address {address}: not a Shelley address"),
+        },
+        Chain::Tron => {
+            let id = ID::Base58(address.to_owned());
+            if id.tron_account().is_none() {
+                bail!("invalid Tron address {address}: not a base58check address");
+            }
+            id
+        }
+        // Account IDs are kept as entered, for the agent to resolve.
+        Chain::Hedera => match ID::from_str(address) {
+            Ok(id @ ID::Hedera(_)) => id,
+            _ => {
+                let evm = alloy::primitives::Address::from_str(address)
+                    .map_err(|e| anyhow!("invalid Hedera address {address}: {e}"))?;
+                ID::Hex(hex::encode(evm))
+            }
+        },
+    };
+    Ok(Party::from(id))
+}
+
+/// Runs a `contacts` command against the contacts file at `path`.
+pub fn run(command: ContactsCmd, path: &Path, output: OutputFormat) -> anyhow::Result<()> {
+    let mut contacts = Contacts::load(path)?;
+    match command {
+        ContactsCmd::Add {
+            name,
+            chain,
+            address,
+        } => {
+            if name.is_empty()
+                || name.starts_with(CONTACT_PREFIX)
+                || name.contains(char::is_whitespace)
+            {
+                bail!(
+                    "invalid contact name `{name}`: must be non-empty, without whitespace \
+                     or a leading `{CONTACT_PREFIX}`"
+                );
+            }
+            parse_party(chain, &address)?;
+            *contacts
+                .0
+                .entry(name.clone())
+                .or_default()
+                .address_mut(chain) = Some(address);
+            save(&contacts, path)?;
+            eprintln!("Saved {} address of `{name}`", chain.as_ref());
+        }
+
+        ContactsCmd::List => match output {
+            OutputFormat::Text => print_contacts(&contacts, path),
+            OutputFormat::Json => print_json(&contacts)?,
+        },
+
+        ContactsCmd::Remove { name, chain } => {
+            let contact = contacts
+                .0
+                .get_mut(&name)
+                .ok_or_else(|| anyhow!("no contact `{name}`"))?;
+            match chain {
+                Some(chain) => {
+                    contact.address_mut(chain).take().ok_or_else(|| {
+                        anyhow!("contact `{name}` has no {} address", chain.as_ref())
+                    })?;
+                    if contact.is_empty() {
+                        contacts.0.remove(&name);
+                    }
+                }
+                None => {
+                    contacts.0.remove(&name);
+                }
+            }
+            save(&contacts, path)?;
+            eprintln!("Removed `{name}`");
+        }
+    }
+    Ok(())
+}
+
+/// Writes the contacts file, creating its directory.
+fn save(contacts: &Contacts, path: &Path) -> anyhow::Result<()> {
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+    save_escrow_data(path, contacts)
+}
+
+/// Prints one row per contact address.
+fn print_contacts(contacts: &Contacts, path: &Path) {
+    if contacts.0.is_empty() {
+        println!("No contacts in {}", path.display());
+        return;
+    }
+    let width = contacts.0.keys().map(String::len).max().unwrap_or(0).max(4);
+    println!("{:<width$}  {:<9}  ADDRESS", "NAME", "CHAIN");
+    for (name, contact) in &contacts.0 {
+        for chain in CHAINS {
+            if let Some(address) = contact.address(chain) {
+                println!("{name:<width$}  {:<9}  {address}", chain.as_ref());
+            }
+        }
+    }
+}
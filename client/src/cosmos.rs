@@ -0,0 +1,913 @@
+//! Cosmos blockchain agent implementation.
+//!
+//! Provides [`CosmosAgent`] for interacting with the Zescrow CosmWasm
+//! escrow contract on Cosmos SDK chains (e.g., Osmosis, Neutron).
+//! Transactions are simulated, signed, and broadcast through the chain's
+//! LCD (REST) endpoint, which must run Cosmos SDK v0.50 or later.
+//!
+//! The contract does not verify proofs: as on Solana, conditions are
+//! checked off-chain before the recipient finishes an escrow. Escrows can
+//! also be finished from another IBC chain (see [`ibc`]).
+
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
+
+use base64::prelude::*;
+use cosmrs::cosmwasm::MsgExecuteContract;
+use cosmrs::crypto::secp256k1::SigningKey;
+use cosmrs::tendermint::chain;
+use cosmrs::tx::{Body, Fee, Msg, SignDoc, SignerInfo};
+use cosmrs::{AccountId, Coin};
+use futures::channel::mpsc;
+use futures::StreamExt;
+use num_traits::ToPrimitive;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::json;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+use url::Url;
+use zescrow_core::interface::{ChainConfig, CosmosConfig};
+use zescrow_core::{
+    AssetKind, BigNumber, Chain, ChainMetadata, EscrowMetadata, EscrowParams, ExecutionState, Party,
+};
+use zescrow_cosmwasm::contract::MAX_LIMIT;
+use zescrow_cosmwasm::msg::{EscrowResponse, EscrowsResponse, ExecuteMsg, QueryMsg};
+use zescrow_cosmwasm::state::{EscrowState, IbcSettler};
+
+use super::Agent;
+use crate::error::ClientError;
+use crate::ethereum::EthereumSigner;
+use crate::pending::{PendingTransaction, WaitConfig};
+use crate::rpc::cosmos::{LcdClient, LcdError};
+use crate::rpc::RpcPolicy;
+use crate::simulation::{Operation, Simulation};
+use crate::watch::{EscrowUpdate, UpdateKind, UpdateStream};
+use crate::{EscrowStatus, EscrowSummary, Result};
+
+pub mod ibc;
+
+// Contract messages for logging.
+const CREATE_ESCROW: &str = "create";
+const FINISH_ESCROW: &str = "finish";
+const CANCEL_ESCROW: &str = "cancel";
+const GET_ESCROW: &str = "escrow";
+const LIST_ESCROWS: &str = "escrows_by_party";
+const WATCH: &str = "watch";
+const WAIT_FOR_TRANSACTION: &str = "wait_for_transaction";
+
+/// Interval between confirmation and event polls unless configured.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(2);
+
+/// Shared handle to a secp256k1 key signing Cosmos transactions.
+#[derive(Clone)]
+pub struct CosmosSigner(Arc<SigningKey>);
+
+impl CosmosSigner {
+    /// Creates a signer from a raw 32-byte secp256k1 private key.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not a valid private key.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        SigningKey::from_slice(bytes)
+            .map(|key| Self(Arc::new(key)))
+            .map_err(|e| ClientError::Keypair(format!("invalid secp256k1 key: {e}")))
+    }
+
+    /// Loads a signer from a `sender_private_id`-style string: a hex
+    /// private key, with or without `0x`, or `keystore:<name>` for a key
+    /// in the encrypted [`Keystore`](crate::keystore::Keystore).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key cannot be parsed or decrypted.
+    pub fn from_id(id: &str) -> Result<Self> {
+        if let Some(signer) = crate::keystore::cosmos_signer(id) {
+            return signer;
+        }
+        let bytes = hex::decode(id.trim().trim_start_matches("0x"))
+            .map_err(|e| ClientError::Keypair(format!("invalid hex key: {e}")))?;
+        Self::from_bytes(&bytes)
+    }
+
+    /// Uses the secp256k1 key of a local Ethereum signer, which controls
+    /// an account on Cosmos chains too.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is held in a KMS, which cannot sign
+    /// Cosmos transactions.
+    pub fn from_ethereum(signer: &EthereumSigner) -> Result<Self> {
+        match signer {
+            EthereumSigner::Local(signer) => Self::from_bytes(&signer.credential().to_bytes()),
+            #[cfg(any(feature = "aws-kms", feature = "gcp-kms"))]
+            _ => Err(ClientError::Keypair(
+                "KMS-held keys cannot sign Cosmos transactions".into(),
+            )),
+        }
+    }
+
+    /// Returns the hex-encoded compressed public key of the signer.
+    pub fn public_key(&self) -> String {
+        hex::encode(self.0.public_key().to_bytes())
+    }
+
+    /// Returns the account address of the signer on a chain with the Bech32
+    /// `prefix`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `prefix` is not a valid Bech32 prefix.
+    pub fn account_id(&self, prefix: &str) -> Result<AccountId> {
+        self.0
+            .public_key()
+            .account_id(prefix)
+            .map_err(|e| ClientError::Keypair(format!("invalid address prefix {prefix}: {e}")))
+    }
+}
+
+impl fmt::Debug for CosmosSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("CosmosSigner")
+            .field(&self.public_key())
+            .finish()
+    }
+}
+
+/// A signer with its account address on the configured chain.
+struct Account {
+    signer: CosmosSigner,
+    address: AccountId,
+}
+
+/// Cosmos blockchain agent for escrow operations.
+///
+/// Manages interactions with the Zescrow CosmWasm contract, including
+/// transaction building, signing, and submission.
+pub struct CosmosAgent {
+    /// LCD client for the chain.
+    lcd: Arc<LcdClient>,
+    /// Address of the deployed escrow contract.
+    contract: AccountId,
+    /// Chain ID, denomination, address prefix, and gas pricing.
+    config: CosmosConfig,
+    /// Chain ID transactions are signed for.
+    chain_id: chain::Id,
+    /// Signer of the escrow creator (sender), with its address.
+    sender: Account,
+    /// Optional signer of the escrow beneficiary (recipient), with its
+    /// address.
+    recipient: Option<Account>,
+    /// Timeout and polling interval of confirmation waits.
+    wait: WaitConfig,
+}
+
+impl CosmosAgent {
+    /// Creates a new Cosmos agent from chain configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Chain configuration with the LCD URL, sender key, and
+    ///   [`CosmosConfig`]
+    /// * `sender` - Optional signer overriding `sender_private_id`
+    /// * `recipient` - Optional recipient signer for finish operations
+    /// * `rpc` - Retry settings and metrics for LCD requests, which fail
+    ///   over to `fallback_rpc_urls` when `rpc_url` is unavailable
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - The configuration has no `cosmos` settings
+    /// - A key cannot be loaded
+    /// - The LCD URLs, contract address, or chain ID are malformed
+    pub fn new(
+        config: &ChainConfig,
+        sender: Option<CosmosSigner>,
+        recipient: Option<CosmosSigner>,
+        rpc: RpcPolicy,
+    ) -> Result<Self> {
+        let ChainConfig {
+            rpc_url,
+            fallback_rpc_urls,
+            sender_private_id,
+            agent_id,
+            cosmos,
+            ..
+        } = config;
+
+        let cosmos = cosmos.clone().ok_or_else(|| {
+            ClientError::cosmos("config", "`cosmos` settings are required on Cosmos")
+        })?;
+        let chain_id = cosmos
+            .chain_id
+            .parse()
+            .map_err(|e| ClientError::cosmos("config", format!("invalid chain ID: {e}")))?;
+
+        let sender = match sender {
+            Some(sender) => sender,
+            None => CosmosSigner::from_id(sender_private_id)?,
+        };
+        let sender_address = sender.account_id(&cosmos.prefix)?;
+        debug!(sender = %sender_address, "Loaded sender signer");
+
+        let recipient = recipient
+            .map(|signer| {
+                let address = signer.account_id(&cosmos.prefix)?;
+                debug!(recipient = %address, "Loaded recipient signer");
+                Ok::<_, ClientError>(Account { signer, address })
+            })
+            .transpose()?;
+
+        let contract = agent_id
+            .parse()
+            .map_err(|e| ClientError::cosmos("parse_contract", e))?;
+        info!(%contract, "Using escrow contract");
+
+        let primary = Url::parse(rpc_url)?;
+        let fallbacks = fallback_rpc_urls
+            .iter()
+            .map(|url| Url::parse(url))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(Self {
+            lcd: Arc::new(LcdClient::new(&primary, &fallbacks, rpc)),
+            contract,
+            config: cosmos,
+            chain_id,
+            sender: Account {
+                signer: sender,
+                address: sender_address,
+            },
+            recipient,
+            wait: WaitConfig::default(),
+        })
+    }
+
+    /// Stops waiting for confirmation after [`WaitConfig::timeout`],
+    /// failing with [`ClientError::Pending`], and polls at
+    /// [`WaitConfig::poll_interval`].
+    pub fn with_wait(mut self, wait: WaitConfig) -> Self {
+        self.wait = wait;
+        self
+    }
+
+    fn poll_interval(&self) -> Duration {
+        self.wait.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL)
+    }
+
+    fn recipient_signer(&self) -> Result<&Account> {
+        self.recipient
+            .as_ref()
+            .ok_or_else(|| ClientError::cosmos(FINISH_ESCROW, "recipient key not configured"))
+    }
+
+    /// Verifies that a signer's address matches the escrow party.
+    fn validate_signer(address: &AccountId, expected: &Party, role: &str) -> Result<()> {
+        (address.as_ref().eq_ignore_ascii_case(&expected.to_string()))
+            .then_some(())
+            .ok_or_else(|| {
+                ClientError::Keypair(format!(
+                    "{role} key mismatch: expected {expected}, got {address}"
+                ))
+            })
+    }
+
+    /// Returns the contract-assigned ID of the escrow in `metadata`.
+    fn escrow_id(metadata: &EscrowMetadata) -> Result<u64> {
+        metadata.escrow_id.ok_or_else(|| {
+            ClientError::InvalidChainOperation("escrow ID is required on Cosmos".into())
+        })
+    }
+
+    /// Builds the message creating the escrow for `params`, with the coin
+    /// it locks.
+    fn create_msg(&self, params: &EscrowParams) -> Result<(ExecuteMsg, Coin)> {
+        Self::validate_signer(&self.sender.address, &params.sender, "sender")?;
+        let denom = match params.asset.kind {
+            AssetKind::Native => self.config.denom.clone(),
+            AssetKind::Token => params
+                .asset
+                .id
+                .as_ref()
+                .ok_or_else(|| {
+                    ClientError::InvalidChainOperation("token escrows need the denom as ID".into())
+                })?
+                .to_string(),
+            _ => {
+                return Err(ClientError::InvalidChainOperation(format!(
+                    "{:?} assets are not supported on Cosmos",
+                    params.asset.kind
+                )))
+            }
+        };
+        let amount = params.asset.amount.0.to_u128().ok_or_else(|| {
+            ClientError::cosmos(CREATE_ESCROW, "amount exceeds the range of a coin")
+        })?;
+        let funds = Coin::new(amount, &denom).map_err(|e| ClientError::cosmos(CREATE_ESCROW, e))?;
+
+        let msg = ExecuteMsg::Create {
+            recipient: params.recipient.to_string(),
+            finish_after: params.finish_after,
+            cancel_after: params.cancel_after,
+            has_conditions: params.has_conditions,
+            memo: params.memo.clone(),
+            settler: self.config.ibc_settler.as_ref().map(|settler| IbcSettler {
+                channel: settler.channel.clone(),
+                sender: settler.sender.clone(),
+            }),
+        };
+        Ok((msg, funds))
+    }
+
+    /// Builds the contract call for `operation`, with the signer sending it
+    /// and the name of the call.
+    fn operation_msg(
+        &self,
+        operation: Operation<'_>,
+    ) -> Result<(ExecuteMsg, Vec<Coin>, &Account, &'static str)> {
+        Ok(match operation {
+            Operation::Create(params) => {
+                let (msg, funds) = self.create_msg(params)?;
+                (msg, vec![funds], &self.sender, CREATE_ESCROW)
+            }
+            Operation::Finish(metadata) => {
+                let signer = self.recipient_signer()?;
+                Self::validate_signer(&signer.address, &metadata.params.recipient, "recipient")?;
+                let id = Self::escrow_id(metadata)?;
+                (ExecuteMsg::Finish { id }, Vec::new(), signer, FINISH_ESCROW)
+            }
+            Operation::Cancel(metadata) => {
+                Self::validate_signer(&self.sender.address, &metadata.params.sender, "sender")?;
+                let id = Self::escrow_id(metadata)?;
+                (
+                    ExecuteMsg::Cancel { id },
+                    Vec::new(),
+                    &self.sender,
+                    CANCEL_ESCROW,
+                )
+            }
+        })
+    }
+
+    /// Fetches the account number and sequence of `address`.
+    async fn account(&self, address: &AccountId, operation: &'static str) -> Result<(u64, u64)> {
+        let response: AccountResponse = self
+            .lcd
+            .get(
+                &["cosmos", "auth", "v1beta1", "accounts", address.as_ref()],
+                &[],
+            )
+            .await
+            .map_err(|e| match e.is_not_found() {
+                true => ClientError::cosmos(
+                    operation,
+                    format!("account {address} not found; fund it first"),
+                ),
+                false => ClientError::cosmos(operation, e),
+            })?;
+        let account = response.account;
+        Ok((
+            parse_number(&account.account_number, operation)?,
+            parse_number(&account.sequence, operation)?,
+        ))
+    }
+
+    /// Returns the current block height.
+    async fn height(&self, operation: &'static str) -> Result<u64> {
+        let block: LatestBlock = self
+            .lcd
+            .get(
+                &[
+                    "cosmos",
+                    "base",
+                    "tendermint",
+                    "v1beta1",
+                    "blocks",
+                    "latest",
+                ],
+                &[],
+            )
+            .await
+            .map_err(|e| ClientError::cosmos(operation, e))?;
+        parse_number(&block.block.header.height, operation)
+    }
+
+    /// Runs a smart query against the escrow contract.
+    async fn query<T: DeserializeOwned>(
+        &self,
+        msg: &QueryMsg,
+        operation: &'static str,
+    ) -> Result<T> {
+        let query =
+            serde_json::to_vec(msg).map_err(|e| ClientError::Serialization(e.to_string()))?;
+        let encoded = BASE64_STANDARD.encode(query);
+        let response: SmartQuery<T> = self
+            .lcd
+            .get(
+                &[
+                    "cosmwasm",
+                    "wasm",
+                    "v1",
+                    "contract",
+                    self.contract.as_ref(),
+                    "smart",
+                    &encoded,
+                ],
+                &[],
+            )
+            .await
+            .map_err(|e| ClientError::cosmos(operation, e))?;
+        Ok(response.data)
+    }
+
+    /// Signs a transaction from `from` calling the contract with `msg` and
+    /// `funds`, with a limit of `gas` priced at the configured gas price,
+    /// returning its encoded bytes.
+    fn sign(
+        &self,
+        from: &Account,
+        msg: &ExecuteMsg,
+        funds: Vec<Coin>,
+        (account_number, sequence): (u64, u64),
+        gas: u64,
+        operation: &'static str,
+    ) -> Result<Vec<u8>> {
+        let key = &from.signer.0;
+        let error = |e: cosmrs::ErrorReport| ClientError::cosmos(operation, e);
+        let msg = MsgExecuteContract {
+            sender: from.address.clone(),
+            contract: self.contract.clone(),
+            msg: serde_json::to_vec(msg).map_err(|e| ClientError::Serialization(e.to_string()))?,
+            funds,
+        }
+        .to_any()
+        .map_err(error)?;
+        let body = Body::new(vec![msg], "", 0u32);
+        let fee = Fee::from_amount_and_gas(self.fee(gas, operation)?, gas);
+        let auth_info = SignerInfo::single_direct(Some(key.public_key()), sequence).auth_info(fee);
+        SignDoc::new(&body, &auth_info, &self.chain_id, account_number)
+            .and_then(|doc| doc.sign(key))
+            .and_then(|raw| raw.to_bytes())
+            .map_err(error)
+    }
+
+    /// Returns the fee of `gas` units at the configured gas price.
+    fn fee(&self, gas: u64, operation: &'static str) -> Result<Coin> {
+        let amount = (gas as f64 * self.config.gas_price).ceil() as u128;
+        Coin::new(amount, &self.config.denom).map_err(|e| ClientError::cosmos(operation, e))
+    }
+
+    /// Simulates a signed transaction, returning the gas it uses, or why it
+    /// would fail.
+    async fn simulate_tx(
+        &self,
+        tx_bytes: &[u8],
+        operation: &'static str,
+    ) -> Result<std::result::Result<u64, String>> {
+        let body = json!({ "tx_bytes": BASE64_STANDARD.encode(tx_bytes) });
+        match self
+            .lcd
+            .post::<SimulateResponse>(&["cosmos", "tx", "v1beta1", "simulate"], &body)
+            .await
+        {
+            Ok(response) => parse_number(&response.gas_info.gas_used, operation).map(Ok),
+            Err(LcdError::Status { message, .. }) => Ok(Err(message)),
+            Err(e) => Err(ClientError::cosmos(operation, e)),
+        }
+    }
+
+    /// Simulates the call to price it, then signs, broadcasts, and waits
+    /// for the transaction to be included.
+    async fn execute(&self, operation: Operation<'_>) -> Result<TxResponse> {
+        let (msg, funds, signer, name) = self.operation_msg(operation)?;
+        self.execute_msg(&msg, funds, signer, name).await
+    }
+
+    /// Calls the contract with `msg` and `funds` from `signer`, as
+    /// [`execute`](Self::execute) does.
+    async fn execute_msg(
+        &self,
+        msg: &ExecuteMsg,
+        funds: Vec<Coin>,
+        signer: &Account,
+        name: &'static str,
+    ) -> Result<TxResponse> {
+        let account = self.account(&signer.address, name).await?;
+
+        let unpriced = self.sign(signer, msg, funds.clone(), account, 0, name)?;
+        let used = self
+            .simulate_tx(&unpriced, name)
+            .await?
+            .map_err(|e| ClientError::cosmos(name, e))?;
+        let gas = (used as f64 * self.config.gas_adjustment).ceil() as u64;
+        debug!(used, gas, "Simulated {} transaction", name);
+
+        let tx_bytes = self.sign(signer, msg, funds, account, gas, name)?;
+        let body = json!({
+            "tx_bytes": BASE64_STANDARD.encode(tx_bytes),
+            "mode": "BROADCAST_MODE_SYNC",
+        });
+        let response: TxEnvelope = self
+            .lcd
+            .post(&["cosmos", "tx", "v1beta1", "txs"], &body)
+            .await
+            .map_err(|e| ClientError::cosmos(name, e))?;
+        let tx = check(response.tx_response, name)?;
+        info!(tx_hash = %tx.txhash, "{} transaction sent", name);
+
+        self.await_tx(&tx.txhash, name).await
+    }
+
+    /// Polls for transaction `hash` until it is included in a block, or the
+    /// wait times out.
+    async fn await_tx(&self, hash: &str, operation: &'static str) -> Result<TxResponse> {
+        let deadline = self.wait.timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            match self
+                .lcd
+                .get::<TxEnvelope>(&["cosmos", "tx", "v1beta1", "txs", hash], &[])
+                .await
+            {
+                Ok(response) => return check(response.tx_response, operation),
+                Err(e) if e.is_not_found() => {}
+                Err(e) => return Err(ClientError::cosmos(operation, e)),
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(pending(operation, hash));
+            }
+            tokio::time::sleep(self.poll_interval()).await;
+        }
+    }
+
+    /// Addresses of the configured sender and recipient.
+    fn parties(&self) -> Vec<String> {
+        std::iter::once(&self.sender.address)
+            .chain(self.recipient.as_ref().map(|account| &account.address))
+            .map(ToString::to_string)
+            .collect()
+    }
+}
+
+#[async_trait::async_trait]
+impl Agent for CosmosAgent {
+    async fn create_escrow(&self, params: &EscrowParams) -> Result<EscrowMetadata> {
+        let tx = self.execute(Operation::Create(params)).await?;
+        info!("{} transaction confirmed", CREATE_ESCROW);
+
+        let contract = self.contract.to_string();
+        let (escrow_id, uid) = tx
+            .events
+            .iter()
+            .find_map(|event| {
+                let update = event.update(&contract, Some(&tx.txhash))?;
+                (update.kind == UpdateKind::Created).then_some((update, event))
+            })
+            .and_then(|(update, event)| {
+                let uid = event.attribute("uid").and_then(|uid| uid.parse().ok());
+                Some((update.escrow.parse::<u64>().ok()?, uid))
+            })
+            .ok_or_else(|| ClientError::MissingEvent("create_escrow wasm event".into()))?;
+        let height = parse_number(&tx.height, CREATE_ESCROW)?;
+        debug!(escrow_id, "Escrow created");
+
+        Ok(EscrowMetadata {
+            params: params.clone(),
+            state: ExecutionState::Funded,
+            escrow_id: Some(escrow_id),
+            uid,
+            chain_metadata: Some(ChainMetadata::Cosmos {
+                tx_hash: tx.txhash,
+                height,
+            }),
+            guest: None,
+            observed: None,
+        })
+    }
+
+    async fn finish_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
+        self.execute(Operation::Finish(metadata)).await?;
+        info!("{} transaction confirmed", FINISH_ESCROW);
+        Ok(())
+    }
+
+    async fn cancel_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
+        self.execute(Operation::Cancel(metadata)).await?;
+        info!("{} transaction confirmed", CANCEL_ESCROW);
+        Ok(())
+    }
+
+    async fn simulate(&self, operation: Operation<'_>) -> Result<Simulation> {
+        let (msg, funds, signer, name) = self.operation_msg(operation)?;
+        let account = self.account(&signer.address, name).await?;
+        let tx_bytes = self.sign(signer, &msg, funds, account, 0, name)?;
+
+        Ok(match self.simulate_tx(&tx_bytes, name).await? {
+            Ok(used) => {
+                let gas = (used as f64 * self.config.gas_adjustment).ceil() as u64;
+                Simulation {
+                    operation: name,
+                    error: None,
+                    logs: Vec::new(),
+                    units: Some(used),
+                    fee: Some(BigNumber::from(self.fee(gas, name)?.amount)),
+                }
+            }
+            Err(error) => Simulation {
+                operation: name,
+                error: Some(error),
+                logs: Vec::new(),
+                units: None,
+                fee: None,
+            },
+        })
+    }
+
+    async fn get_escrow_state(&self, metadata: &EscrowMetadata) -> Result<EscrowStatus> {
+        let id = Self::escrow_id(metadata)?;
+        let current_height = self.height(GET_ESCROW).await?;
+        let escrow: EscrowResponse = self.query(&QueryMsg::Escrow { id }, GET_ESCROW).await?;
+        Ok(status(&escrow, current_height))
+    }
+
+    async fn current_height(&self) -> Result<u64> {
+        self.height(GET_ESCROW).await
+    }
+
+    /// Pages through the escrows of each configured party. The contract
+    /// keeps settled escrows, so they are included.
+    async fn list_escrows(&self) -> Result<Vec<EscrowSummary>> {
+        let current_height = self.height(LIST_ESCROWS).await?;
+        let mut escrows = Vec::new();
+        for party in self.parties() {
+            let mut start_after = None;
+            loop {
+                let msg = QueryMsg::EscrowsByParty {
+                    party: party.clone(),
+                    start_after,
+                    limit: Some(MAX_LIMIT),
+                };
+                let page: EscrowsResponse = self.query(&msg, LIST_ESCROWS).await?;
+                let full = page.escrows.len() == MAX_LIMIT as usize;
+                start_after = page.escrows.last().map(|escrow| escrow.id);
+                escrows.extend(page.escrows.iter().map(|escrow| EscrowSummary {
+                    id: escrow.id.to_string(),
+                    uid: escrow.uid.parse().ok(),
+                    sender: escrow.sender.to_string(),
+                    recipient: escrow.recipient.to_string(),
+                    status: status(escrow, current_height),
+                }));
+                if !full {
+                    break;
+                }
+            }
+        }
+
+        // An escrow between two configured parties matches more than once.
+        escrows.sort_by_key(|escrow| escrow.id.parse::<u64>().unwrap_or_default());
+        escrows.dedup_by(|a, b| a.id == b.id);
+        info!(count = escrows.len(), "Found escrows");
+        Ok(escrows)
+    }
+
+    /// Polls for transactions calling the escrow contract in new blocks and
+    /// decodes the `wasm` events it emits.
+    async fn watch_escrows(&self, metadata: Option<&EscrowMetadata>) -> Result<UpdateStream> {
+        let escrow = metadata
+            .map(Self::escrow_id)
+            .transpose()?
+            .map(|id| id.to_string());
+        let parties = self.parties();
+        let contract = self.contract.to_string();
+        let lcd = self.lcd.clone();
+        let interval = self.poll_interval();
+        let mut from = self.height(WATCH).await? + 1;
+        info!(url = %lcd.url(), %contract, "Watching escrow contract events");
+
+        let (updates, stream) = mpsc::unbounded();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval).await;
+                let query = format!("wasm._contract_address='{contract}' AND tx.height>={from}");
+                let search: std::result::Result<TxSearch, _> = lcd
+                    .get(
+                        &["cosmos", "tx", "v1beta1", "txs"],
+                        &[
+                            ("query", query.as_str()),
+                            ("order_by", "ORDER_BY_ASC"),
+                            ("limit", "100"),
+                        ],
+                    )
+                    .await;
+                let txs = match search {
+                    Ok(search) => search.tx_responses,
+                    Err(e) => {
+                        warn!(error = %e, "Failed to search escrow transactions");
+                        if updates
+                            .unbounded_send(Err(ClientError::cosmos(WATCH, e)))
+                            .is_err()
+                        {
+                            return;
+                        }
+                        continue;
+                    }
+                };
+                for tx in txs.iter().filter(|tx| tx.code == 0) {
+                    if let Ok(height) = tx.height.parse::<u64>() {
+                        from = from.max(height + 1);
+                    }
+                    for update in tx
+                        .events
+                        .iter()
+                        .filter_map(|event| event.update(&contract, Some(&tx.txhash)))
+                    {
+                        let wanted = match &escrow {
+                            Some(escrow) => update.escrow == *escrow,
+                            None => [&update.sender, &update.recipient]
+                                .into_iter()
+                                .flatten()
+                                .any(|party| parties.contains(party)),
+                        };
+                        if wanted && updates.unbounded_send(Ok(update)).is_err() {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+        Ok(stream.boxed())
+    }
+
+    async fn wait_for_transaction(&self, pending: &PendingTransaction) -> Result<()> {
+        if !matches!(pending.chain, Chain::Cosmos) {
+            return Err(ClientError::ConfigMismatch {
+                expected: Chain::Cosmos.as_ref().to_owned(),
+                actual: pending.chain.as_ref().to_owned(),
+            });
+        }
+        let operation = [CREATE_ESCROW, FINISH_ESCROW, CANCEL_ESCROW]
+            .into_iter()
+            .find(|operation| *operation == pending.operation)
+            .unwrap_or(WAIT_FOR_TRANSACTION);
+
+        self.await_tx(&pending.id, operation).await?;
+        info!(tx_hash = %pending.id, "{} transaction confirmed", operation);
+        Ok(())
+    }
+}
+
+/// Builds the error for a transaction still pending when its wait timed out.
+fn pending(operation: &str, tx_hash: &str) -> ClientError {
+    ClientError::Pending(PendingTransaction {
+        chain: Chain::Cosmos,
+        operation: operation.to_owned(),
+        id: tx_hash.to_owned(),
+        blockhash: None,
+    })
+}
+
+/// Fails if the transaction in `tx` was rejected or reverted.
+fn check(tx: TxResponse, operation: &'static str) -> Result<TxResponse> {
+    match tx.code {
+        0 => Ok(tx),
+        code => Err(ClientError::cosmos(
+            operation,
+            format!(
+                "transaction {} failed with code {code}: {}",
+                tx.txhash, tx.raw_log
+            ),
+        )),
+    }
+}
+
+/// Parses a number the LCD encodes as a string.
+fn parse_number(value: &str, operation: &'static str) -> Result<u64> {
+    value
+        .parse()
+        .map_err(|_| ClientError::cosmos(operation, format!("invalid number: {value}")))
+}
+
+/// Converts the contract's view of an escrow into its live status.
+fn status(escrow: &EscrowResponse, current_height: u64) -> EscrowStatus {
+    EscrowStatus {
+        settled: escrow.state != EscrowState::Funded,
+        amount: BigNumber::from(escrow.funds.amount.u128()),
+        finish_after: escrow.finish_after,
+        cancel_after: escrow.cancel_after,
+        current_height,
+        has_conditions: escrow.has_conditions,
+    }
+}
+
+#[derive(Deserialize)]
+struct AccountResponse {
+    account: BaseAccount,
+}
+
+#[derive(Deserialize)]
+struct BaseAccount {
+    account_number: String,
+    sequence: String,
+}
+
+#[derive(Deserialize)]
+struct LatestBlock {
+    block: Block,
+}
+
+#[derive(Deserialize)]
+struct Block {
+    header: Header,
+}
+
+#[derive(Deserialize)]
+struct Header {
+    height: String,
+}
+
+#[derive(Deserialize)]
+struct SmartQuery<T> {
+    data: T,
+}
+
+#[derive(Deserialize)]
+struct SimulateResponse {
+    gas_info: GasInfo,
+}
+
+#[derive(Deserialize)]
+struct GasInfo {
+    gas_used: String,
+}
+
+#[derive(Deserialize)]
+struct TxEnvelope {
+    tx_response: TxResponse,
+}
+
+#[derive(Deserialize)]
+struct TxSearch {
+    #[serde(default)]
+    tx_responses: Vec<TxResponse>,
+}
+
+/// Outcome of a transaction, as reported by the LCD.
+#[derive(Deserialize)]
+struct TxResponse {
+    txhash: String,
+    #[serde(default)]
+    height: String,
+    #[serde(default)]
+    code: u32,
+    #[serde(default)]
+    raw_log: String,
+    #[serde(default)]
+    events: Vec<Event>,
+}
+
+#[derive(Deserialize)]
+struct Event {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    attributes: Vec<Attribute>,
+}
+
+#[derive(Deserialize)]
+struct Attribute {
+    key: String,
+    #[serde(default)]
+    value: String,
+}
+
+impl Event {
+    fn attribute(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    /// Decodes a `wasm` event emitted by `contract` into an escrow update.
+    fn update(&self, contract: &str, tx: Option<&str>) -> Option<EscrowUpdate> {
+        if self.kind != "wasm" || self.attribute("_contract_address") != Some(contract) {
+            return None;
+        }
+        let kind = match self.attribute("action")? {
+            "create_escrow" => UpdateKind::Created,
+            "finish_escrow" => UpdateKind::Finished,
+            "cancel_escrow" => UpdateKind::Cancelled,
+            _ => return None,
+        };
+        let amount = self.attribute("amount")?.parse::<u128>().ok()?;
+        Some(EscrowUpdate {
+            escrow: self.attribute("escrow_id")?.to_owned(),
+            kind,
+            sender: self.attribute("sender").map(str::to_owned),
+            recipient: self.attribute("recipient").map(str::to_owned),
+            amount: BigNumber::from(amount),
+            tx: tx.map(str::to_owned),
+        })
+    }
+}
@@ -0,0 +1,215 @@
+//! Escrow settlement driven by another IBC chain.
+//!
+//! An escrow created with an [`IbcSettler`](zescrow_core::IbcSettler) in
+//! its chain's [`CosmosConfig`](zescrow_core::interface::CosmosConfig) is
+//! finished by a settlement packet its escrow contract receives over the
+//! settler's channel, sent by the settler account through the escrow
+//! contract of the counterparty chain. Settling takes three steps on the
+//! counterparty chain's [`CosmosAgent`], whose sender is the settler:
+//!
+//! 1. [`send_settlement`](CosmosAgent::send_settlement) has the contract
+//!    send the packet, naming the escrow and a reference to the fulfillment
+//!    (e.g. the digest of the proof) recorded with the settlement.
+//! 2. [`relay_settlement`](CosmosAgent::relay_settlement) relays it with
+//!    [Hermes], unless a relayer already serves the channel.
+//! 3. [`await_settlement`](CosmosAgent::await_settlement) waits for the
+//!    acknowledgement of the escrow being finished.
+//!
+//! [Hermes]: https://hermes.informal.systems
+
+use std::path::PathBuf;
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::process::Command;
+use tokio::time::Instant;
+use tracing::info;
+use zescrow_cosmwasm::msg::ExecuteMsg;
+
+use super::{pending, CosmosAgent, TxResponse, TxSearch};
+use crate::error::ClientError;
+use crate::Result;
+
+const SEND_SETTLEMENT: &str = "send_settlement";
+const RELAY_SETTLEMENT: &str = "relay_settlement";
+const AWAIT_SETTLEMENT: &str = "await_settlement";
+
+/// A settlement packet sent by the escrow contract.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct SettlementPacket {
+    /// Channel the packet was sent on, on the sending chain.
+    pub channel: String,
+    /// Sequence number of the packet on the channel.
+    pub sequence: u64,
+    /// Hash of the transaction that sent the packet.
+    pub tx_hash: String,
+}
+
+/// The [Hermes](https://hermes.informal.systems) relayer, configured for
+/// both chains of a settlement channel.
+#[derive(Debug, Clone)]
+pub struct Hermes {
+    /// Path of the `hermes` binary.
+    pub binary: PathBuf,
+    /// Configuration file, unless Hermes's default.
+    pub config: Option<PathBuf>,
+}
+
+impl Default for Hermes {
+    fn default() -> Self {
+        Self {
+            binary: "hermes".into(),
+            config: None,
+        }
+    }
+}
+
+impl CosmosAgent {
+    /// Has the escrow contract send a packet over `channel` finishing escrow
+    /// `id` on the other chain, as the settler the escrow names.
+    ///
+    /// # Arguments
+    ///
+    /// * `channel` - Channel of this chain's end of the settlement channel
+    /// * `id` - ID of the escrow on the other chain
+    /// * `proof` - Reference to the fulfillment, recorded with the settlement
+    /// * `timeout` - Time the packet may wait to be relayed, 10 minutes
+    ///   unless set
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction fails or emits no packet.
+    pub async fn send_settlement(
+        &self,
+        channel: &str,
+        id: u64,
+        proof: Option<String>,
+        timeout: Option<Duration>,
+    ) -> Result<SettlementPacket> {
+        let msg = ExecuteMsg::SendSettlement {
+            channel: channel.to_owned(),
+            id,
+            proof,
+            timeout_secs: timeout.map(|timeout| timeout.as_secs()),
+        };
+        let tx = self
+            .execute_msg(&msg, Vec::new(), &self.sender, SEND_SETTLEMENT)
+            .await?;
+        let sequence = packet_sequence(&tx, "send_packet", channel)
+            .ok_or_else(|| ClientError::MissingEvent("send_packet event".into()))?;
+        info!(channel, sequence, tx_hash = %tx.txhash, "Sent settlement packet");
+        Ok(SettlementPacket {
+            channel: channel.to_owned(),
+            sequence,
+            tx_hash: tx.txhash,
+        })
+    }
+
+    /// Relays the pending packets of the settlement channel, including
+    /// `packet`, and their acknowledgements with `hermes`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if Hermes cannot be run or fails.
+    pub async fn relay_settlement(&self, packet: &SettlementPacket, hermes: &Hermes) -> Result<()> {
+        let mut command = Command::new(&hermes.binary);
+        if let Some(config) = &hermes.config {
+            command.arg("--config").arg(config);
+        }
+        let port = format!("wasm.{}", self.contract);
+        let output = command
+            .args(["clear", "packets", "--chain", &self.config.chain_id])
+            .args(["--port", &port, "--channel", &packet.channel])
+            .output()
+            .await
+            .map_err(|e| {
+                ClientError::cosmos(RELAY_SETTLEMENT, format!("cannot run Hermes: {e}"))
+            })?;
+        if !output.status.success() {
+            return Err(ClientError::cosmos(
+                RELAY_SETTLEMENT,
+                format!(
+                    "Hermes failed with {}: {}",
+                    output.status,
+                    String::from_utf8_lossy(&output.stderr).trim()
+                ),
+            ));
+        }
+        info!(channel = %packet.channel, sequence = packet.sequence, "Relayed settlement");
+        Ok(())
+    }
+
+    /// Polls for the acknowledgement of `packet` until the escrow is
+    /// finished, or the wait times out.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::Pending`] if the wait times out, and an error
+    /// if the packet timed out or the other chain refused the settlement.
+    pub async fn await_settlement(&self, packet: &SettlementPacket) -> Result<()> {
+        let contract = self.contract.to_string();
+        let deadline = self.wait.timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            if let Some(tx) = self.packet_tx("acknowledge_packet", packet).await? {
+                let ack = tx
+                    .events
+                    .iter()
+                    .find(|event| {
+                        event.kind == "wasm"
+                            && event.attribute("_contract_address") == Some(&contract)
+                            && event.attribute("action") == Some("settlement_ack")
+                    })
+                    .ok_or_else(|| ClientError::MissingEvent("settlement_ack wasm event".into()))?;
+                if ack.attribute("success") != Some("true") {
+                    let error = ack.attribute("error").unwrap_or("unknown error");
+                    return Err(ClientError::cosmos(
+                        AWAIT_SETTLEMENT,
+                        format!("settlement refused: {error}"),
+                    ));
+                }
+                info!(sequence = packet.sequence, tx_hash = %tx.txhash, "Settlement acknowledged");
+                return Ok(());
+            }
+            if self.packet_tx("timeout_packet", packet).await?.is_some() {
+                return Err(ClientError::cosmos(
+                    AWAIT_SETTLEMENT,
+                    format!("settlement packet {} timed out", packet.sequence),
+                ));
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(pending(SEND_SETTLEMENT, &packet.tx_hash));
+            }
+            tokio::time::sleep(self.poll_interval()).await;
+        }
+    }
+
+    /// Searches for the transaction emitting a `kind` event for `packet`.
+    async fn packet_tx(&self, kind: &str, packet: &SettlementPacket) -> Result<Option<TxResponse>> {
+        let query = format!(
+            "{kind}.packet_src_channel='{}' AND {kind}.packet_sequence='{}'",
+            packet.channel, packet.sequence
+        );
+        let search: TxSearch = self
+            .lcd
+            .get(
+                &["cosmos", "tx", "v1beta1", "txs"],
+                &[("query", query.as_str()), ("limit", "1")],
+            )
+            .await
+            .map_err(|e| ClientError::cosmos(AWAIT_SETTLEMENT, e))?;
+        Ok(search.tx_responses.into_iter().find(|tx| {
+            tx.code == 0 && packet_sequence(tx, kind, &packet.channel) == Some(packet.sequence)
+        }))
+    }
+}
+
+/// Sequence of the packet on `channel` that `tx` emitted a `kind` event for.
+fn packet_sequence(tx: &TxResponse, kind: &str, channel: &str) -> Option<u64> {
+    tx.events
+        .iter()
+        .filter(|event| event.kind == kind)
+        .find(|event| event.attribute("packet_src_channel") == Some(channel))?
+        .attribute("packet_sequence")?
+        .parse()
+        .ok()
+}
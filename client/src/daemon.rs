@@ -0,0 +1,345 @@
+//! `daemon` mode: settles escrows automatically.
+//!
+//! Watches a directory with one subdirectory per escrow:
+//!
+//! ```text
+//! escrows/
+//! └── invoice-42/
+//!     ├── escrow_metadata.json    # as written by `create`
+//!     ├── escrow_conditions.json  # condition fulfillment, once available
+//!     └── policy.json             # optional, see `Policy`
+//! ```
+//!
+//! On every pass, each funded escrow is finished as soon as its timelock and
+//! conditions allow (proving the conditions once a fulfillment appears), or
+//! cancelled once it has expired, as its policy permits. Settled escrows are
+//! recorded in their metadata and skipped from then on.
+//!
+//! Configured webhooks are notified of proofs and settlements, and once
+//! per run of escrows nearing their cancellation height.
+
+use std::collections::HashSet;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+use tokio::time::MissedTickBehavior;
+use tracing::{debug, error, info, warn};
+use zescrow_client::{ClientError, Operation, Recipient, Simulation};
+use zescrow_core::interface::{expand_env_vars, load_escrow_data, save_escrow_data};
+use zescrow_core::{Condition, EscrowMetadata, ExecutionState};
+
+use crate::output::OutputFormat;
+use crate::webhook::{Event, Webhooks};
+use crate::{prove_escrow, ClientOpts};
+
+/// Default directory of escrows managed by the daemon.
+pub const ESCROWS_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/escrows");
+
+/// Escrow metadata file in each escrow directory.
+const METADATA_FILE: &str = "escrow_metadata.json";
+
+/// Condition fulfillment file in each escrow directory.
+const CONDITIONS_FILE: &str = "escrow_conditions.json";
+
+/// Settlement policy file in each escrow directory.
+const POLICY_FILE: &str = "policy.json";
+
+/// Per-escrow settlement policy, read from `policy.json`.
+#[derive(Debug, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct Policy {
+    /// Finish the escrow once it can be finished.
+    finish: bool,
+    /// Cancel the escrow once it has expired.
+    cancel: bool,
+    /// Recipient key used to finish, overriding `--recipient`: a Solana
+    /// keypair file or a 0x-prefixed Ethereum private key. `${VAR}`
+    /// references are expanded, so keys need not be stored in the file.
+    recipient: Option<String>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self {
+            finish: true,
+            cancel: true,
+            recipient: None,
+        }
+    }
+}
+
+/// Settlement action taken on an escrow.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Action {
+    Finish,
+    Cancel,
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Finish => "finish",
+            Self::Cancel => "cancel",
+        })
+    }
+}
+
+/// Outcome of one settlement attempt.
+#[derive(Debug, Serialize)]
+struct Settlement {
+    /// Name of the escrow's directory.
+    escrow: String,
+    action: Action,
+    /// Simulated transaction, with `--dry-run`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    simulation: Option<Simulation>,
+    /// Why the attempt failed; it is retried on the next pass.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl fmt::Display for Settlement {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match (&self.simulation, &self.error) {
+            (_, Some(error)) => write!(f, "{}: {} failed: {error}", self.escrow, self.action),
+            (Some(simulation), None) => write!(f, "{}: {simulation}", self.escrow),
+            (None, None) => write!(f, "{}: {} succeeded", self.escrow, self.action),
+        }
+    }
+}
+
+/// Webhook notifications sent while settling.
+pub struct Notifier<'a> {
+    webhooks: &'a Webhooks,
+    /// Notify once an escrow is this many blocks/slots from expiring.
+    expiry_warning: Option<u64>,
+    /// Escrows already reported as nearing expiry.
+    warned: HashSet<PathBuf>,
+}
+
+impl<'a> Notifier<'a> {
+    /// Notifies `webhooks`, warning of escrows within `expiry_warning`
+    /// blocks/slots of expiring.
+    pub fn new(webhooks: &'a Webhooks, expiry_warning: Option<u64>) -> Self {
+        Self {
+            webhooks,
+            expiry_warning,
+            warned: HashSet::new(),
+        }
+    }
+}
+
+/// Settles the escrows in `dir` every `interval` until interrupted, or once
+/// if `once` or `--dry-run` is set.
+///
+/// `recipient` is the key used to finish escrows whose policy names none.
+///
+/// # Errors
+///
+/// Returns an error if a single pass cannot read `dir`. While looping,
+/// errors are logged and retried on the next pass.
+pub async fn run(
+    opts: &ClientOpts,
+    dir: &Path,
+    recipient: Option<Recipient>,
+    interval: Duration,
+    once: bool,
+    mut notifier: Notifier<'_>,
+) -> anyhow::Result<()> {
+    if once || opts.dry_run {
+        return pass(opts, dir, recipient.as_ref(), &mut notifier).await;
+    }
+
+    info!(?dir, ?interval, "Starting escrow daemon");
+    let mut ticker = tokio::time::interval(interval);
+    ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            _ = ticker.tick() => {}
+            _ = tokio::signal::ctrl_c() => break,
+        }
+        if let Err(e) = pass(opts, dir, recipient.as_ref(), &mut notifier).await {
+            warn!(error = %format!("{e:#}"), "Daemon pass failed");
+        }
+    }
+    info!("Escrow daemon stopped");
+    Ok(())
+}
+
+/// Attempts to settle every escrow in `dir` once.
+async fn pass(
+    opts: &ClientOpts,
+    dir: &Path,
+    recipient: Option<&Recipient>,
+    notifier: &mut Notifier<'_>,
+) -> anyhow::Result<()> {
+    let entries = dir
+        .read_dir()
+        .with_context(|| format!("reading escrow directory {dir:?}"))?;
+    for entry in entries {
+        let path = entry?.path();
+        if !path.join(METADATA_FILE).is_file() {
+            continue;
+        }
+        match settle(opts, &path, recipient, notifier).await {
+            Ok(Some(settlement)) => match opts.output {
+                OutputFormat::Text => println!("{settlement}"),
+                OutputFormat::Json => println!("{}", serde_json::to_string(&settlement)?),
+            },
+            Ok(None) => {}
+            // Errors that will not clear up by themselves, such as unmet
+            // conditions, are reported as needing attention.
+            Err(e)
+                if e.downcast_ref::<ClientError>()
+                    .is_some_and(|e| !e.is_retryable()) =>
+            {
+                error!(?path, error = %format!("{e:#}"), "Escrow needs attention")
+            }
+            Err(e) => warn!(?path, error = %format!("{e:#}"), "Skipping escrow"),
+        }
+    }
+    Ok(())
+}
+
+/// Finishes or cancels the escrow in `path` if its state and policy allow.
+///
+/// Returns `None` if there is nothing to do yet.
+async fn settle(
+    opts: &ClientOpts,
+    path: &Path,
+    recipient: Option<&Recipient>,
+    notifier: &mut Notifier<'_>,
+) -> anyhow::Result<Option<Settlement>> {
+    let metadata_path = path.join(METADATA_FILE);
+    let mut metadata: EscrowMetadata = load_escrow_data(&metadata_path)?;
+    if !matches!(
+        metadata.state,
+        ExecutionState::Funded | ExecutionState::PendingCreate | ExecutionState::ConditionsMet
+    ) {
+        return Ok(None);
+    }
+
+    let policy_path = path.join(POLICY_FILE);
+    let policy: Policy = if policy_path.exists() {
+        load_escrow_data(&policy_path)?
+    } else {
+        Policy::default()
+    };
+    let recipient = match &policy.recipient {
+        Some(key) => Some(expand_env_vars(key).parse::<Recipient>()?),
+        None => recipient.cloned(),
+    };
+
+    let config = &metadata.params.chain_config;
+    let status = opts
+        .builder(config)
+        .build()
+        .await?
+        .get_escrow_state(&metadata)
+        .await?;
+    if status.settled {
+        debug!(?path, "Escrow already settled");
+        return Ok(None);
+    }
+
+    let conditions_path = path.join(CONDITIONS_FILE);
+    let provable = !metadata.params.has_conditions || conditions_path.is_file();
+    let action = match recipient {
+        Some(recipient) if policy.finish && status.can_finish() && provable => {
+            Some((Action::Finish, Some(recipient)))
+        }
+        _ if policy.cancel && status.can_cancel() => Some((Action::Cancel, None)),
+        _ => None,
+    };
+    let name = path.file_name().map_or_else(
+        || path.display().to_string(),
+        |name| name.to_string_lossy().into(),
+    );
+    let Some((action, recipient)) = action else {
+        let expiring = match (status.cancel_after, notifier.expiry_warning) {
+            (Some(cancel_after), Some(warning)) => {
+                cancel_after > status.current_height
+                    && cancel_after - status.current_height <= warning
+            }
+            _ => false,
+        };
+        if expiring && !opts.dry_run && notifier.warned.insert(path.to_path_buf()) {
+            notifier
+                .webhooks
+                .notify(Event::Expiring, Some(&name), &metadata)
+                .await;
+        }
+        return Ok(None);
+    };
+
+    let mut settlement = Settlement {
+        escrow: name.clone(),
+        action,
+        simulation: None,
+        error: None,
+    };
+    let result = match action {
+        Action::Finish => {
+            let mut builder = opts.builder(config);
+            if let Some(recipient) = recipient {
+                builder = builder.recipient(recipient);
+            }
+            if metadata.params.has_conditions {
+                let condition: Condition = load_escrow_data(&conditions_path)?;
+                let seal = prove_escrow(opts, &metadata, condition).await?;
+                notifier
+                    .webhooks
+                    .notify(Event::Proven, Some(&name), &metadata)
+                    .await;
+                if let Some(seal) = seal {
+                    builder = builder.proof_seal(seal);
+                }
+            }
+            let client = builder.build().await?;
+            if opts.dry_run {
+                client
+                    .simulate(Operation::Finish(&metadata))
+                    .await
+                    .map(Some)
+            } else {
+                client.finish_escrow(&metadata).await.map(|()| None)
+            }
+        }
+        Action::Cancel => {
+            let client = opts.builder(config).build().await?;
+            if opts.dry_run {
+                client
+                    .simulate(Operation::Cancel(&metadata))
+                    .await
+                    .map(Some)
+            } else {
+                client.cancel_escrow(&metadata).await.map(|()| None)
+            }
+        }
+    };
+
+    match result {
+        Ok(Some(simulation)) => settlement.simulation = Some(simulation),
+        Ok(None) => {
+            metadata.state = match action {
+                Action::Finish => ExecutionState::Released,
+                Action::Cancel => ExecutionState::Refunded,
+            };
+            save_escrow_data(&metadata_path, &metadata)?;
+            let event = match action {
+                Action::Finish => Event::Finished,
+                Action::Cancel => Event::Cancelled,
+            };
+            notifier
+                .webhooks
+                .notify(event, Some(&name), &metadata)
+                .await;
+        }
+        Err(e) => settlement.error = Some(e.to_string()),
+    }
+    Ok(Some(settlement))
+}
@@ -0,0 +1,1227 @@
+//! Configuration diagnostics for `doctor`.
+//!
+//! Checks the active configuration the way `create` and `finish` would use
+//! it: that the JSON templates parse, the RPC endpoints answer, the escrow
+//! program or contract is deployed at `agent_id`, the sender key loads,
+//! matches `sender`, and holds funds, and that the prover can run. Every
+//! check is reported with a suggested fix, rather than stopping at the
+//! first failure.
+
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+use alloy::primitives::{Address, U256};
+use alloy::providers::{Provider, ProviderBuilder};
+use alloy::signers::Signer;
+use anyhow::bail;
+use serde::Serialize;
+use serde_json::json;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::pubkey::Pubkey;
+#[cfg(feature = "substrate")]
+use subxt::dynamic::Value;
+#[cfg(feature = "substrate")]
+use subxt::ext::scale_value::At;
+#[cfg(feature = "substrate")]
+use subxt::{OnlineClient, PolkadotConfig};
+#[cfg(feature = "cardano")]
+use zescrow_client::CardanoSigner;
+#[cfg(feature = "cosmos")]
+use zescrow_client::CosmosSigner;
+#[cfg(feature = "substrate")]
+use zescrow_client::SubstrateSigner;
+use zescrow_client::{secrets, EthereumSigner, SolanaSigner};
+use zescrow_core::interface::{
+    load_escrow_data, ChainConfig, ESCROW_CONDITIONS_PATH, ESCROW_METADATA_PATH,
+};
+use zescrow_core::{AssetKind, Chain, Condition, EscrowMetadata, EscrowParams, ID};
+
+use crate::output::{self, OutputFormat};
+use crate::ClientOpts;
+
+/// Outcome of one check.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum Status {
+    Ok,
+    /// Works, but likely not as intended.
+    Warn,
+    Fail,
+}
+
+/// One check in the report.
+#[derive(Debug, Serialize)]
+struct Check {
+    name: &'static str,
+    status: Status,
+    detail: String,
+    /// What to do about a warning or failure.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fix: Option<String>,
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let status = match self.status {
+            Status::Ok => "ok  ",
+            Status::Warn => "warn",
+            Status::Fail => "FAIL",
+        };
+        write!(f, "[{status}] {}: {}", self.name, self.detail)?;
+        if let Some(fix) = &self.fix {
+            write!(f, "\n       fix: {fix}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks collected so far.
+#[derive(Debug, Default)]
+struct Report(Vec<Check>);
+
+impl Report {
+    fn ok(&mut self, name: &'static str, detail: impl Into<String>) {
+        self.push(name, Status::Ok, detail, None::<String>);
+    }
+
+    fn warn(&mut self, name: &'static str, detail: impl Into<String>, fix: impl Into<String>) {
+        self.push(name, Status::Warn, detail, Some(fix));
+    }
+
+    fn fail(&mut self, name: &'static str, detail: impl Into<String>, fix: impl Into<String>) {
+        self.push(name, Status::Fail, detail, Some(fix));
+    }
+
+    fn push(
+        &mut self,
+        name: &'static str,
+        status: Status,
+        detail: impl Into<String>,
+        fix: Option<impl Into<String>>,
+    ) {
+        self.0.push(Check {
+            name,
+            status,
+            detail: detail.into(),
+            fix: fix.map(Into::into),
+        });
+    }
+}
+
+/// Runs every check and prints the report.
+///
+/// # Errors
+///
+/// Returns an error if any check failed.
+pub async fn run(opts: &ClientOpts) -> anyhow::Result<()> {
+    let mut report = Report::default();
+
+    let params = match opts.load_params().await {
+        Ok(params) => {
+            report.ok("escrow params", "parsed");
+            Some(params)
+        }
+        Err(e) => {
+            report.fail(
+                "escrow params",
+                format!("{e:#}"),
+                "copy deploy/solana/escrow_params.json or deploy/ethereum/escrow_params.json \
+                 to deploy/, run `init`, or select a profile with --profile",
+            );
+            None
+        }
+    };
+    check_optional_file::<EscrowMetadata>(&mut report, "escrow metadata", ESCROW_METADATA_PATH);
+    if let Some(condition) =
+        check_optional_file::<Condition>(&mut report, "escrow conditions", ESCROW_CONDITIONS_PATH)
+    {
+        let verification = condition.verification();
+        match verification.error {
+            None => report.ok("fulfillment", "conditions hold"),
+            Some(error) => report.fail(
+                "fulfillment",
+                error,
+                "run `verify-condition` to see which subcondition fails, and regenerate it \
+                 with `generate`",
+            ),
+        }
+    }
+
+    if let Some(params) = &params {
+        check_params(&mut report, params);
+        let config = &params.chain_config;
+        if let Some(chain) = &config.custom_chain {
+            report.warn(
+                "chain",
+                format!("custom chain `{chain}`; RPC, program, and key checks skipped"),
+                "check the endpoint and keys with the agent's own tooling",
+            );
+        } else {
+            match config.chain {
+                Chain::Ethereum => check_ethereum(&mut report, params).await,
+                Chain::Solana => check_solana(&mut report, params).await,
+                Chain::Cosmos => check_cosmos(&mut report, params).await,
+                Chain::Substrate => check_substrate(&mut report, params).await,
+                Chain::Cardano => check_cardano(&mut report, params).await,
+                Chain::Tron => check_tron(&mut report, params).await,
+                Chain::Hedera => check_hedera(&mut report, params).await,
+            }
+            for url in &config.fallback_rpc_urls {
+                if let Err(e) = rpc_reachable(config, url).await {
+                    report.warn(
+                        "fallback RPC",
+                        format!("{url}: {e}"),
+                        "remove or replace the endpoint in fallback_rpc_urls",
+                    );
+                }
+            }
+        }
+    }
+    check_prover(&mut report, params.as_ref());
+
+    print_report(&report, opts.output)
+}
+
+/// Parses `path` as `T` if it exists; it is only written by later steps.
+fn check_optional_file<T: serde::de::DeserializeOwned>(
+    report: &mut Report,
+    name: &'static str,
+    path: &str,
+) -> Option<T> {
+    if !Path::new(path).exists() {
+        report.ok(name, format!("{path} not created yet"));
+        return None;
+    }
+    match load_escrow_data(path) {
+        Ok(value) => {
+            report.ok(name, "parsed");
+            Some(value)
+        }
+        Err(e) => {
+            report.fail(name, format!("{e:#}"), format!("fix or delete {path}"));
+            None
+        }
+    }
+}
+
+/// Checks the parameters the chains would reject.
+fn check_params(report: &mut Report, params: &EscrowParams) {
+    let timelocks = match (params.finish_after, params.cancel_after) {
+        (None, None) => Err("neither finish_after nor cancel_after is set"),
+        (Some(finish), Some(cancel)) if finish >= cancel => {
+            Err("finish_after is not before cancel_after")
+        }
+        _ => Ok(()),
+    };
+    match timelocks {
+        Ok(()) => report.ok("timelocks", "valid"),
+        Err(e) => report.fail(
+            "timelocks",
+            e,
+            "set finish_after below cancel_after, in blocks (Ethereum) or slots (Solana)",
+        ),
+    }
+    if let Err(e) = params.memo_bytes() {
+        report.fail("memo", e.to_string(), "shorten the memo");
+    }
+}
+
+async fn check_ethereum(report: &mut Report, params: &EscrowParams) {
+    let config = &params.chain_config;
+    if let Some(Err(e)) = config.network.map(|network| network.validate()) {
+        report.fail(
+            "network",
+            e.to_string(),
+            "set chain_id, block_time_secs, and confirmations of `network.custom`, \
+             or use the matching preset",
+        );
+        return;
+    }
+    let provider = match config.rpc_url.parse() {
+        Ok(url) => ProviderBuilder::new().connect_http(url).erased(),
+        Err(e) => {
+            report.fail(
+                "RPC",
+                format!("invalid rpc_url: {e}"),
+                "set rpc_url to an http(s) URL",
+            );
+            return;
+        }
+    };
+    match provider.get_chain_id().await {
+        Ok(chain_id) => match config.network {
+            Some(network) if network.chain_id() != chain_id => report.fail(
+                "RPC",
+                format!(
+                    "chain ID {chain_id} does not match network preset {}",
+                    network.chain_id()
+                ),
+                "point rpc_url at the preset's network, or change `network`",
+            ),
+            _ => report.ok("RPC", format!("{} (chain ID {chain_id})", config.rpc_url)),
+        },
+        Err(e) => {
+            report.fail(
+                "RPC",
+                format!("{}: {e}", config.rpc_url),
+                "check rpc_url (ETHEREUM_RPC_URL in .env), and that the node is running \
+                 (`npx hardhat node` for localnet)",
+            );
+            return;
+        }
+    }
+
+    match config.agent_id.parse::<Address>() {
+        Ok(address) => match provider.get_code_at(address).await {
+            Ok(code) if !code.is_empty() => report.ok("contract", format!("deployed at {address}")),
+            Ok(_) => report.fail(
+                "contract",
+                format!("no contract at {address}"),
+                "deploy it with deploy/ethereum/run.sh and set agent_id to its address",
+            ),
+            Err(e) => report.fail(
+                "contract",
+                e.to_string(),
+                "retry, or check the RPC endpoint",
+            ),
+        },
+        Err(e) => report.fail(
+            "contract",
+            format!("invalid agent_id: {e}"),
+            "set agent_id to the escrow contract's 0x-prefixed address",
+        ),
+    }
+
+    let signer = match sender_id(config).await {
+        Ok(id) => EthereumSigner::from_id(&id)
+            .await
+            .map_err(anyhow::Error::from),
+        Err(e) => Err(e),
+    };
+    let address = match signer {
+        Ok(signer) => signer.address(),
+        Err(e) => {
+            report.fail(
+                "sender key",
+                format!("{e:#}"),
+                "set sender_private_id to a 0x-prefixed private key (SENDER_PRIVATE_KEY in \
+                 .env), a keystore:, secret, or KMS reference",
+            );
+            return;
+        }
+    };
+    if !params
+        .sender
+        .to_string()
+        .eq_ignore_ascii_case(&address.to_string())
+    {
+        report.fail(
+            "sender key",
+            format!("key is for {address}, but sender is {}", params.sender),
+            "set sender to the key's address",
+        );
+    } else {
+        report.ok("sender key", address.to_string());
+    }
+
+    match provider.get_balance(address).await {
+        Ok(balance) => {
+            let needed = matches!(params.asset.kind, AssetKind::Native)
+                .then(|| U256::from_str(&params.asset.amount().to_string()).ok())
+                .flatten();
+            check_balance(
+                report,
+                balance.to_string(),
+                balance.is_zero(),
+                needed.is_some_and(|n| balance <= n),
+                "wei",
+            );
+        }
+        Err(e) => report.fail("balance", e.to_string(), "retry, or check the RPC endpoint"),
+    }
+}
+
+async fn check_solana(report: &mut Report, params: &EscrowParams) {
+    let config = &params.chain_config;
+    let client = RpcClient::new(config.rpc_url.clone());
+    match client.get_version().await {
+        Ok(version) => report.ok(
+            "RPC",
+            format!("{} (solana-core {})", config.rpc_url, version.solana_core),
+        ),
+        Err(e) => {
+            report.fail(
+                "RPC",
+                format!("{}: {e}", config.rpc_url),
+                "check rpc_url (SOLANA_RPC_URL in .env), and that the validator is running \
+                 (`solana-test-validator` for localnet)",
+            );
+            return;
+        }
+    }
+
+    match config.agent_id.parse::<Pubkey>() {
+        Ok(program) => match client.get_account(&program).await {
+            Ok(account) if account.executable => {
+                report.ok("program", format!("deployed at {program}"))
+            }
+            Ok(_) => report.fail(
+                "program",
+                format!("{program} is not a program"),
+                "set agent_id to the escrow program ID",
+            ),
+            Err(_) => report.fail(
+                "program",
+                format!("no program at {program}"),
+                "deploy it with deploy/solana/run.sh and set agent_id to its program ID",
+            ),
+        },
+        Err(e) => report.fail(
+            "program",
+            format!("invalid agent_id: {e}"),
+            "set agent_id to the escrow program ID",
+        ),
+    }
+
+    let signer = match sender_id(config).await {
+        Ok(id) => SolanaSigner::from_id(&id).map_err(anyhow::Error::from),
+        Err(e) => Err(e),
+    };
+    let pubkey = match signer {
+        Ok(signer) => signer.pubkey(),
+        Err(e) => {
+            report.fail(
+                "sender key",
+                format!("{e:#}"),
+                "set sender_private_id to a keypair file (e.g. ~/.config/solana/id.json, \
+                 create one with `solana-keygen new`), a keystore:, or secret reference",
+            );
+            return;
+        }
+    };
+    if params.sender.to_string() != pubkey.to_string() {
+        report.fail(
+            "sender key",
+            format!("keypair is for {pubkey}, but sender is {}", params.sender),
+            "set sender to the keypair's public key",
+        );
+    } else {
+        report.ok("sender key", pubkey.to_string());
+    }
+
+    match client.get_balance(&pubkey).await {
+        Ok(balance) => {
+            let low = matches!(params.asset.kind, AssetKind::Native)
+                && params.asset.amount.0 >= balance.into();
+            check_balance(report, balance.to_string(), balance == 0, low, "lamports");
+        }
+        Err(e) => report.fail("balance", e.to_string(), "retry, or check the RPC endpoint"),
+    }
+}
+
+#[cfg(feature = "cosmos")]
+async fn check_cosmos(report: &mut Report, params: &EscrowParams) {
+    let config = &params.chain_config;
+    let Some(cosmos) = &config.cosmos else {
+        report.fail(
+            "cosmos",
+            "no `cosmos` settings",
+            "set cosmos.chain_id, cosmos.denom, cosmos.prefix, and cosmos.gas_price",
+        );
+        return;
+    };
+    match lcd_chain_id(&config.rpc_url).await {
+        Ok(chain_id) if chain_id != cosmos.chain_id => report.fail(
+            "RPC",
+            format!(
+                "chain ID {chain_id} does not match cosmos.chain_id {}",
+                cosmos.chain_id
+            ),
+            "point rpc_url at the chain's LCD endpoint, or change cosmos.chain_id",
+        ),
+        Ok(chain_id) => report.ok("RPC", format!("{} (chain ID {chain_id})", config.rpc_url)),
+        Err(e) => {
+            report.fail(
+                "RPC",
+                format!("{}: {e:#}", config.rpc_url),
+                "set rpc_url to the chain's LCD (REST) endpoint, e.g. http://localhost:1317",
+            );
+            return;
+        }
+    }
+
+    let contract = lcd_get(
+        &config.rpc_url,
+        &format!("cosmwasm/wasm/v1/contract/{}", config.agent_id),
+    )
+    .await;
+    match contract {
+        Ok(_) => report.ok("contract", format!("deployed at {}", config.agent_id)),
+        Err(e) => report.fail(
+            "contract",
+            format!("no contract at {}: {e:#}", config.agent_id),
+            "instantiate the CosmWasm escrow contract and set agent_id to its address",
+        ),
+    }
+
+    let address = match sender_id(config).await.and_then(|id| {
+        Ok(CosmosSigner::from_id(&id)?
+            .account_id(&cosmos.prefix)?
+            .to_string())
+    }) {
+        Ok(address) => address,
+        Err(e) => {
+            report.fail(
+                "sender key",
+                format!("{e:#}"),
+                "set sender_private_id to a hex secp256k1 private key (create one with \
+                 `keygen --chain cosmos`), a keystore:, or secret reference",
+            );
+            return;
+        }
+    };
+    if !params.sender.to_string().eq_ignore_ascii_case(&address) {
+        report.fail(
+            "sender key",
+            format!("key is for {address}, but sender is {}", params.sender),
+            "set sender to the key's address",
+        );
+    } else {
+        report.ok("sender key", address.clone());
+    }
+
+    let balance = lcd_get(
+        &config.rpc_url,
+        &format!(
+            "cosmos/bank/v1beta1/balances/{address}/by_denom?denom={}",
+            cosmos.denom
+        ),
+    )
+    .await
+    .and_then(|response| {
+        response["balance"]["amount"]
+            .as_str()
+            .unwrap_or("0")
+            .parse::<u128>()
+            .map_err(Into::into)
+    });
+    match balance {
+        Ok(balance) => {
+            let low = matches!(params.asset.kind, AssetKind::Native)
+                && params.asset.amount.0 >= balance.into();
+            check_balance(
+                report,
+                balance.to_string(),
+                balance == 0,
+                low,
+                &cosmos.denom,
+            );
+        }
+        Err(e) => report.fail(
+            "balance",
+            format!("{e:#}"),
+            "retry, or check the LCD endpoint",
+        ),
+    }
+}
+
+#[cfg(not(feature = "cosmos"))]
+async fn check_cosmos(report: &mut Report, _params: &EscrowParams) {
+    report.fail(
+        "agent",
+        "the Cosmos agent is not built in",
+        "rebuild the client with `--features cosmos`",
+    );
+}
+
+#[cfg(feature = "substrate")]
+async fn check_substrate(report: &mut Report, params: &EscrowParams) {
+    let config = &params.chain_config;
+    let api = match OnlineClient::<PolkadotConfig>::from_insecure_url(&config.rpc_url).await {
+        Ok(api) => api,
+        Err(e) => {
+            report.fail(
+                "RPC",
+                format!("{}: {e:#}", config.rpc_url),
+                "set rpc_url to the node's WebSocket endpoint, e.g. ws://localhost:9944",
+            );
+            return;
+        }
+    };
+    let spec_version = api.runtime_version().spec_version;
+    if api.metadata().pallet_by_name("Contracts").is_none() {
+        report.fail(
+            "RPC",
+            format!(
+                "{} (runtime {spec_version}) has no Contracts pallet",
+                config.rpc_url
+            ),
+            "point rpc_url at a chain running pallet-contracts",
+        );
+        return;
+    }
+    report.ok(
+        "RPC",
+        format!("{} (runtime {spec_version})", config.rpc_url),
+    );
+
+    let contract = ID::Ss58(config.agent_id.clone());
+    let Some(prefix) = contract.ss58_prefix() else {
+        report.fail(
+            "contract",
+            format!("agent_id {} is not an SS58 address", config.agent_id),
+            "set agent_id to the SS58 address of the ink! escrow contract",
+        );
+        return;
+    };
+    let info = match contract.to_bytes() {
+        Ok(account) => substrate_storage(&api, "Contracts", "ContractInfoOf", account).await,
+        Err(e) => Err(e.into()),
+    };
+    match info {
+        Ok(Some(_)) => report.ok("contract", format!("deployed at {}", config.agent_id)),
+        Ok(None) => report.fail(
+            "contract",
+            format!("no contract at {}", config.agent_id),
+            "instantiate the ink! escrow contract and set agent_id to its address",
+        ),
+        Err(e) => report.fail(
+            "contract",
+            format!("{e:#}"),
+            "retry, or check the node's WebSocket endpoint",
+        ),
+    }
+
+    let signer = match sender_id(config)
+        .await
+        .and_then(|id| Ok(SubstrateSigner::from_id(&id)?))
+    {
+        Ok(signer) => signer,
+        Err(e) => {
+            report.fail(
+                "sender key",
+                format!("{e:#}"),
+                "set sender_private_id to a hex sr25519 seed (create one with \
+                 `keygen --chain substrate`), a secret URI, a keystore:, or secret reference",
+            );
+            return;
+        }
+    };
+    let address = signer.address(prefix);
+    if params.sender.to_string() != address {
+        report.fail(
+            "sender key",
+            format!("key is for {address}, but sender is {}", params.sender),
+            "set sender to the key's address",
+        );
+    } else {
+        report.ok("sender key", address);
+    }
+
+    let account = signer.account_id();
+    let balance = substrate_storage(&api, "System", "Account", account.0.to_vec())
+        .await
+        .map(|account| {
+            account
+                .as_ref()
+                .and_then(|account| account.at("data")?.at("free")?.as_u128())
+                .unwrap_or_default()
+        });
+    match balance {
+        Ok(balance) => {
+            let low = matches!(params.asset.kind, AssetKind::Native)
+                && params.asset.amount.0 >= balance.into();
+            check_balance(report, balance.to_string(), balance == 0, low, "planck");
+        }
+        Err(e) => report.fail(
+            "balance",
+            format!("{e:#}"),
+            "retry, or check the node's WebSocket endpoint",
+        ),
+    }
+}
+
+#[cfg(not(feature = "substrate"))]
+async fn check_substrate(report: &mut Report, _params: &EscrowParams) {
+    report.fail(
+        "agent",
+        "the Substrate agent is not built in",
+        "rebuild the client with `--features substrate`",
+    );
+}
+
+#[cfg(feature = "cardano")]
+async fn check_cardano(report: &mut Report, params: &EscrowParams) {
+    let config = &params.chain_config;
+    let Some(cardano) = &config.cardano else {
+        report.fail(
+            "cardano",
+            "no `cardano` settings",
+            "set cardano.network and cardano.reference_script",
+        );
+        return;
+    };
+    let project_id = cardano.project_id.as_deref();
+    match blockfrost_get(&config.rpc_url, project_id, "blocks/latest").await {
+        Ok(block) => report.ok(
+            "RPC",
+            format!(
+                "{} (slot {})",
+                config.rpc_url,
+                block["slot"].as_u64().unwrap_or(0)
+            ),
+        ),
+        Err(e) => {
+            report.fail(
+                "RPC",
+                format!("{}: {e:#}", config.rpc_url),
+                "set rpc_url to a Blockfrost API endpoint and cardano.project_id to its key",
+            );
+            return;
+        }
+    }
+
+    let script = cardano
+        .reference_script
+        .split_once('#')
+        .and_then(|(hash, index)| Some((hash, index.parse::<usize>().ok()?)));
+    let reference = match script {
+        Some((hash, index)) => {
+            blockfrost_get(&config.rpc_url, project_id, &format!("txs/{hash}/utxos"))
+                .await
+                .map(|utxos| !utxos["outputs"][index]["reference_script_hash"].is_null())
+        }
+        None => Err(anyhow::anyhow!(
+            "reference_script {} is not a `<tx hash>#<index>` UTxO reference",
+            cardano.reference_script
+        )),
+    };
+    match reference {
+        Ok(true) => report.ok(
+            "contract",
+            format!(
+                "validator at {} (script {})",
+                config.agent_id, cardano.reference_script
+            ),
+        ),
+        Ok(false) => report.fail(
+            "contract",
+            format!("no reference script at {}", cardano.reference_script),
+            "deploy the escrow validator as a reference script and set \
+             cardano.reference_script to its UTxO",
+        ),
+        Err(e) => report.fail(
+            "contract",
+            format!("{e:#}"),
+            "deploy the escrow validator as a reference script and set \
+             cardano.reference_script to its UTxO",
+        ),
+    }
+
+    let address = match sender_id(config)
+        .await
+        .and_then(|id| Ok(CardanoSigner::from_id(&id)?.address(cardano.network)))
+    {
+        Ok(address) => address,
+        Err(e) => {
+            report.fail(
+                "sender key",
+                format!("{e:#}"),
+                "set sender_private_id to a hex ed25519 signing key (create one with \
+                 `keygen --chain cardano`), a keystore:, or secret reference",
+            );
+            return;
+        }
+    };
+    if params.sender.to_string() != address {
+        report.fail(
+            "sender key",
+            format!("key is for {address}, but sender is {}", params.sender),
+            "set sender to the key's address",
+        );
+    } else {
+        report.ok("sender key", address.clone());
+    }
+
+    let path = format!("addresses/{address}");
+    let balance = match blockfrost_get(&config.rpc_url, project_id, &path).await {
+        Ok(account) => account["amount"]
+            .as_array()
+            .into_iter()
+            .flatten()
+            .find(|amount| amount["unit"] == "lovelace")
+            .and_then(|amount| amount["quantity"].as_str())
+            .unwrap_or("0")
+            .parse::<u128>()
+            .map_err(Into::into),
+        // Addresses that never received funds are unknown to the indexer.
+        Err(e)
+            if e.downcast_ref::<reqwest::Error>()
+                .and_then(reqwest::Error::status)
+                == Some(reqwest::StatusCode::NOT_FOUND) =>
+        {
+            Ok(0)
+        }
+        Err(e) => Err(e),
+    };
+    match balance {
+        Ok(balance) => {
+            let low = matches!(params.asset.kind, AssetKind::Native)
+                && params.asset.amount.0 >= balance.into();
+            check_balance(report, balance.to_string(), balance == 0, low, "lovelace");
+        }
+        Err(e) => report.fail(
+            "balance",
+            format!("{e:#}"),
+            "retry, or check the Blockfrost endpoint",
+        ),
+    }
+}
+
+#[cfg(not(feature = "cardano"))]
+async fn check_cardano(report: &mut Report, _params: &EscrowParams) {
+    report.fail(
+        "agent",
+        "the Cardano agent is not built in",
+        "rebuild the client with `--features cardano`",
+    );
+}
+
+async fn check_tron(report: &mut Report, params: &EscrowParams) {
+    let config = &params.chain_config;
+    let api_key = config.tron.as_ref().and_then(|t| t.api_key.as_deref());
+    match tron_post(&config.rpc_url, api_key, "wallet/getnowblock", json!({})).await {
+        Ok(block) => report.ok(
+            "RPC",
+            format!(
+                "{} (block {})",
+                config.rpc_url,
+                block["block_header"]["raw_data"]["number"]
+                    .as_u64()
+                    .unwrap_or(0)
+            ),
+        ),
+        Err(e) => {
+            report.fail(
+                "RPC",
+                format!("{}: {e:#}", config.rpc_url),
+                "set rpc_url to a full node's HTTP API (e.g. https://api.shasta.trongrid.io) \
+                 and tron.api_key to a TronGrid API key",
+            );
+            return;
+        }
+    }
+
+    match ID::Base58(config.agent_id.clone()).tron_account() {
+        Some(account) => {
+            let body = json!({ "value": tron_hex(account), "visible": false });
+            match tron_post(&config.rpc_url, api_key, "wallet/getcontract", body).await {
+                Ok(contract) if contract["bytecode"].is_string() => {
+                    report.ok("contract", format!("deployed at {}", config.agent_id))
+                }
+                Ok(_) => report.fail(
+                    "contract",
+                    format!("no contract at {}", config.agent_id),
+                    "deploy the Ethereum escrow contract to Tron and set agent_id to its address",
+                ),
+                Err(e) => report.fail(
+                    "contract",
+                    format!("{e:#}"),
+                    "retry, or check the RPC endpoint",
+                ),
+            }
+        }
+        None => report.fail(
+            "contract",
+            format!("invalid agent_id {}", config.agent_id),
+            "set agent_id to the escrow contract's base58check address",
+        ),
+    }
+
+    let signer = match sender_id(config).await {
+        Ok(id) => EthereumSigner::from_id(&id)
+            .await
+            .map_err(anyhow::Error::from),
+        Err(e) => Err(e),
+    };
+    let account = match signer {
+        Ok(signer) => <[u8; 20]>::from(signer.address()),
+        Err(e) => {
+            report.fail(
+                "sender key",
+                format!("{e:#}"),
+                "set sender_private_id to a 0x-prefixed private key (create one with \
+                 `keygen --chain tron`), a keystore:, secret, or KMS reference",
+            );
+            return;
+        }
+    };
+    let address = ID::tron(account).to_string();
+    if params.sender.to_string() != address {
+        report.fail(
+            "sender key",
+            format!("key is for {address}, but sender is {}", params.sender),
+            "set sender to the key's address",
+        );
+    } else {
+        report.ok("sender key", address);
+    }
+
+    // Accounts that never received TRX are empty objects.
+    let body = json!({ "address": tron_hex(account), "visible": false });
+    match tron_post(&config.rpc_url, api_key, "wallet/getaccount", body).await {
+        Ok(account) => {
+            let balance = account["balance"].as_u64().unwrap_or(0);
+            let low = matches!(params.asset.kind, AssetKind::Native)
+                && params.asset.amount.0 >= balance.into();
+            check_balance(report, balance.to_string(), balance == 0, low, "sun");
+        }
+        Err(e) => report.fail(
+            "balance",
+            format!("{e:#}"),
+            "retry, or check the RPC endpoint",
+        ),
+    }
+}
+
+async fn check_hedera(report: &mut Report, params: &EscrowParams) {
+    let config = &params.chain_config;
+    let provider = match config.rpc_url.parse() {
+        Ok(url) => ProviderBuilder::new().connect_http(url).erased(),
+        Err(e) => {
+            report.fail(
+                "RPC",
+                format!("invalid rpc_url: {e}"),
+                "set rpc_url to an http(s) URL",
+            );
+            return;
+        }
+    };
+    match provider.get_chain_id().await {
+        Ok(chain_id) => report.ok("RPC", format!("{} (chain ID {chain_id})", config.rpc_url)),
+        Err(e) => {
+            report.fail(
+                "RPC",
+                format!("{}: {e}", config.rpc_url),
+                "set rpc_url to a JSON-RPC relay (e.g. https://testnet.hashio.io/api)",
+            );
+            return;
+        }
+    }
+
+    let Some(mirror_url) = config.hedera.as_ref().map(|h| h.mirror_url.as_str()) else {
+        report.fail(
+            "mirror node",
+            "no `hedera` settings",
+            "set hedera.mirror_url (e.g. https://testnet.mirrornode.hedera.com)",
+        );
+        return;
+    };
+    match mirror_get(mirror_url, "blocks?limit=1").await {
+        Ok(_) => report.ok("mirror node", mirror_url.to_owned()),
+        Err(e) => {
+            report.fail(
+                "mirror node",
+                format!("{mirror_url}: {e:#}"),
+                "check hedera.mirror_url, and that the mirror node serves /api/v1",
+            );
+            return;
+        }
+    }
+
+    match config.agent_id.parse::<Address>() {
+        Ok(address) => match provider.get_code_at(address).await {
+            Ok(code) if !code.is_empty() => report.ok("contract", format!("deployed at {address}")),
+            Ok(_) => report.fail(
+                "contract",
+                format!("no contract at {address}"),
+                "deploy the Ethereum escrow contract to Hedera and set agent_id to its EVM address",
+            ),
+            Err(e) => report.fail(
+                "contract",
+                e.to_string(),
+                "retry, or check the RPC endpoint",
+            ),
+        },
+        Err(e) => report.fail(
+            "contract",
+            format!("invalid agent_id: {e}"),
+            "set agent_id to the escrow contract's 0x-prefixed EVM address",
+        ),
+    }
+
+    let signer = match sender_id(config).await {
+        Ok(id) => EthereumSigner::from_id(&id)
+            .await
+            .map_err(anyhow::Error::from),
+        Err(e) => Err(e),
+    };
+    let address = match signer {
+        Ok(signer) => signer.address(),
+        Err(e) => {
+            report.fail(
+                "sender key",
+                format!("{e:#}"),
+                "set sender_private_id to a 0x-prefixed ECDSA private key (create one with \
+                 `keygen --chain hedera`), a keystore:, secret, or KMS reference",
+            );
+            return;
+        }
+    };
+    // The sender may be an account ID, which transactions come from the
+    // EVM address alias of.
+    let sender = match ID::from_str(&params.sender.to_string()) {
+        Ok(ID::Hedera(account)) => {
+            match mirror_get(mirror_url, &format!("accounts/{account}")).await {
+                Ok(resolved) => resolved["evm_address"]
+                    .as_str()
+                    .unwrap_or_default()
+                    .to_owned(),
+                Err(e) => {
+                    report.fail(
+                        "sender key",
+                        format!("account {account}: {e:#}"),
+                        "check that the sender account exists on the network",
+                    );
+                    return;
+                }
+            }
+        }
+        _ => params.sender.to_string(),
+    };
+    if !sender
+        .trim_start_matches("0x")
+        .eq_ignore_ascii_case(&hex::encode(address))
+    {
+        report.fail(
+            "sender key",
+            format!("key is for {address}, but sender is {}", params.sender),
+            "set sender to the key's EVM address, or the account ID it is the alias of",
+        );
+    } else {
+        report.ok("sender key", address.to_string());
+    }
+
+    // The relay reports balances in weibars, and amounts are in tinybars.
+    match provider.get_balance(address).await {
+        Ok(balance) => {
+            let tinybars = balance / U256::from(10_000_000_000u64);
+            let needed = matches!(params.asset.kind, AssetKind::Native)
+                .then(|| U256::from_str(&params.asset.amount().to_string()).ok())
+                .flatten();
+            check_balance(
+                report,
+                tinybars.to_string(),
+                tinybars.is_zero(),
+                needed.is_some_and(|n| tinybars <= n),
+                "tinybars",
+            );
+        }
+        Err(e) => report.fail("balance", e.to_string(), "retry, or check the RPC endpoint"),
+    }
+}
+
+/// Fetches the value of the storage map `pallet::entry` at `key`, an
+/// account ID, in the latest block.
+#[cfg(feature = "substrate")]
+async fn substrate_storage(
+    api: &OnlineClient<PolkadotConfig>,
+    pallet: &str,
+    entry: &str,
+    key: Vec<u8>,
+) -> anyhow::Result<Option<Value<u32>>> {
+    let address = subxt::dynamic::storage(pallet, entry, vec![Value::from_bytes(key)]);
+    let value = api.storage().at_latest().await?.fetch(&address).await?;
+    Ok(value.map(|value| value.to_value()).transpose()?)
+}
+
+/// Sends a GET request for `path` to the LCD endpoint at `url`.
+async fn lcd_get(url: &str, path: &str) -> anyhow::Result<serde_json::Value> {
+    let url = format!("{}/{path}", url.trim_end_matches('/'));
+    Ok(reqwest::get(url).await?.error_for_status()?.json().await?)
+}
+
+/// Sends a GET request for `path` to the Hedera mirror node REST API at
+/// `url`.
+async fn mirror_get(url: &str, path: &str) -> anyhow::Result<serde_json::Value> {
+    let url = format!("{}/api/v1/{path}", url.trim_end_matches('/'));
+    Ok(reqwest::get(url).await?.error_for_status()?.json().await?)
+}
+
+/// Sends a GET request for `path` to the Blockfrost API at `url`.
+async fn blockfrost_get(
+    url: &str,
+    project_id: Option<&str>,
+    path: &str,
+) -> anyhow::Result<serde_json::Value> {
+    let url = format!("{}/{path}", url.trim_end_matches('/'));
+    let mut request = reqwest::Client::new().get(url);
+    if let Some(project_id) = project_id {
+        request = request.header("project_id", project_id);
+    }
+    Ok(request.send().await?.error_for_status()?.json().await?)
+}
+
+/// Sends a POST request with a JSON `body` to `path` of the Tron HTTP API
+/// at `url`.
+async fn tron_post(
+    url: &str,
+    api_key: Option<&str>,
+    path: &str,
+    body: serde_json::Value,
+) -> anyhow::Result<serde_json::Value> {
+    let url = format!("{}/{path}", url.trim_end_matches('/'));
+    let mut request = reqwest::Client::new().post(url).json(&body);
+    if let Some(api_key) = api_key {
+        request = request.header("TRON-PRO-API-KEY", api_key);
+    }
+    let response: serde_json::Value = request.send().await?.error_for_status()?.json().await?;
+    match response.get("Error").and_then(serde_json::Value::as_str) {
+        Some(error) => bail!("{error}"),
+        None => Ok(response),
+    }
+}
+
+/// Returns a Tron account in the hex form the HTTP API takes.
+fn tron_hex(account: [u8; 20]) -> String {
+    format!("41{}", hex::encode(account))
+}
+
+/// Returns the chain ID of the node behind the LCD endpoint at `url`.
+async fn lcd_chain_id(url: &str) -> anyhow::Result<String> {
+    let info = lcd_get(url, "cosmos/base/tendermint/v1beta1/node_info").await?;
+    info["default_node_info"]["network"]
+        .as_str()
+        .map(str::to_owned)
+        .ok_or_else(|| anyhow::anyhow!("node info has no network"))
+}
+
+/// Reports the sender's balance: failing if empty, warning if it does not
+/// cover the escrowed amount and fees.
+fn check_balance(report: &mut Report, balance: String, empty: bool, low: bool, unit: &str) {
+    let detail = format!("{balance} {unit}");
+    let fix = "fund the sender (e.g. `solana airdrop` on devnet, or a Sepolia faucet)";
+    if empty {
+        report.fail("balance", detail, fix);
+    } else if low {
+        report.warn(
+            "balance",
+            format!("{detail}, not above the escrowed amount"),
+            fix,
+        );
+    } else {
+        report.ok("balance", detail);
+    }
+}
+
+/// Returns the sender key, resolving a secret reference.
+async fn sender_id(config: &ChainConfig) -> anyhow::Result<String> {
+    Ok(secrets::resolve(&config.sender_private_id)
+        .await?
+        .unwrap_or_else(|| config.sender_private_id.clone()))
+}
+
+/// Checks that escrows with conditions can be proven by this build.
+fn check_prover(report: &mut Report, params: Option<&EscrowParams>) {
+    let has_conditions = params.is_some_and(|params| params.has_conditions);
+    if !cfg!(feature = "prover") {
+        if has_conditions {
+            report.fail(
+                "prover",
+                "escrow has conditions, but this build cannot prove them",
+                "rebuild with `--features prover` (in release mode), or prove elsewhere and \
+                 use `finish --proof`",
+            );
+        } else {
+            report.ok(
+                "prover",
+                "not built in; only needed for escrows with conditions",
+            );
+        }
+        return;
+    }
+    report.ok("prover", "built in");
+
+    // Groth16 compression for the Ethereum verifier runs in a container.
+    let groth16 = params.is_some_and(|params| {
+        params.has_conditions && matches!(params.chain_config.chain, Chain::Ethereum)
+    });
+    if groth16 {
+        let docker = std::process::Command::new("docker")
+            .arg("info")
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if docker {
+            report.ok("docker", "running");
+        } else {
+            report.fail(
+                "docker",
+                "not running; Groth16 proofs for Ethereum cannot be compressed",
+                "install and start Docker, or prove on another machine with `prove`",
+            );
+        }
+    }
+}
+
+/// Prints the report, failing if any check failed.
+fn print_report(report: &Report, output: OutputFormat) -> anyhow::Result<()> {
+    let count = |status| report.0.iter().filter(|c| c.status == status).count();
+    match output {
+        OutputFormat::Text => {
+            for check in &report.0 {
+                println!("{check}");
+            }
+            println!(
+                "{} ok, {} warnings, {} failed",
+                count(Status::Ok),
+                count(Status::Warn),
+                count(Status::Fail)
+            );
+        }
+        OutputFormat::Json => output::print_json(&report.0)?,
+    }
+    let failed = count(Status::Fail);
+    if failed > 0 {
+        bail!("{failed} check(s) failed");
+    }
+    Ok(())
+}
+
+/// Checks that `url` answers as an RPC endpoint of the configured chain.
+async fn rpc_reachable(config: &ChainConfig, url: &str) -> anyhow::Result<()> {
+    match config.chain {
+        Chain::Ethereum => {
+            let provider = ProviderBuilder::new().connect_http(url.parse()?).erased();
+            provider.get_chain_id().await?;
+        }
+        Chain::Solana => {
+            RpcClient::new(url.to_owned()).get_version().await?;
+        }
+        Chain::Cosmos => {
+            lcd_chain_id(url).await?;
+        }
+        #[cfg(feature = "substrate")]
+        Chain::Substrate => {
+            OnlineClient::<PolkadotConfig>::from_insecure_url(url).await?;
+        }
+        #[cfg(not(feature = "substrate"))]
+        Chain::Substrate => bail!("Substrate requires the `substrate` feature"),
+        Chain::Cardano => {
+            let project_id = config
+                .cardano
+                .as_ref()
+                .and_then(|c| c.project_id.as_deref());
+            blockfrost_get(url, project_id, "blocks/latest").await?;
+        }
+        Chain::Tron => {
+            let api_key = config.tron.as_ref().and_then(|t| t.api_key.as_deref());
+            tron_post(url, api_key, "wallet/getnowblock", json!({})).await?;
+        }
+        Chain::Hedera => {
+            let provider = ProviderBuilder::new().connect_http(url.parse()?).erased();
+            provider.get_chain_id().await?;
+        }
+    }
+    Ok(())
+}
@@ -3,7 +3,13 @@
 //! Provides [`ClientError`] for all client-side operations including
 //! blockchain interactions, key management, and ZK proof generation.
 
+use std::time::Duration;
+
 use thiserror::Error;
+#[cfg(feature = "prover")]
+use zescrow_core::Condition;
+
+use crate::pending::PendingTransaction;
 
 /// Errors arising from client operations.
 #[derive(Error, Debug)]
@@ -48,6 +54,49 @@ pub enum ClientError {
         message: String,
     },
 
+    /// Cosmos-specific agent error.
+    #[error("cosmos agent: {context} - {message}")]
+    Cosmos {
+        /// Operation context (e.g., "create", "finish").
+        context: &'static str,
+        /// Underlying error message.
+        message: String,
+    },
+
+    /// Substrate-specific agent error.
+    #[error("substrate agent: {context} - {message}")]
+    Substrate {
+        /// Operation context (e.g., "create", "finish").
+        context: &'static str,
+        /// Underlying error message.
+        message: String,
+    },
+
+    /// Cardano-specific agent error.
+    #[error("cardano agent: {context} - {message}")]
+    Cardano {
+        /// Operation context (e.g., "create", "finish").
+        context: &'static str,
+        /// Underlying error message.
+        message: String,
+    },
+    /// Tron-specific agent error.
+    #[error("tron agent: {context} - {message}")]
+    Tron {
+        /// Operation context (e.g., "create", "finish").
+        context: &'static str,
+        /// Underlying error message.
+        message: String,
+    },
+    /// Hedera-specific agent error.
+    #[error("hedera agent: {context} - {message}")]
+    Hedera {
+        /// Operation context (e.g., "create", "finish").
+        context: &'static str,
+        /// Underlying error message.
+        message: String,
+    },
+
     /// Error serializing or deserializing data.
     #[error("serialization error: {0}")]
     Serialization(String),
@@ -58,7 +107,7 @@ pub enum ClientError {
 
     /// Error parsing a hex address.
     #[error("address parse error: {0}")]
-    AddressParse(#[from] rustc_hex::FromHexError),
+    AddressParse(#[from] alloy::hex::FromHexError),
 
     /// Invalid operation for the current chain context.
     #[error("invalid chain operation: {0}")]
@@ -76,6 +125,86 @@ pub enum ClientError {
     #[error("transaction dropped: {0}")]
     TransactionDropped(String),
 
+    /// Resolved `maxFeePerGas` exceeds the configured cap.
+    #[error("max fee per gas of {max_fee_per_gas} wei exceeds the cap of {cap} wei")]
+    GasPriceCapExceeded {
+        /// Resolved max fee per gas, in wei.
+        max_fee_per_gas: u128,
+        /// Configured cap, in wei.
+        cap: u128,
+    },
+
+    /// A condition proof is stale and would be rejected on-chain.
+    #[error("proof expired at block/slot {expires_at}, now at {height}")]
+    ProofExpired {
+        /// Last block or slot the proof could be used at.
+        expires_at: u64,
+        /// Current block or slot.
+        height: u64,
+    },
+
+    /// A proof is from another guest program than the one pinned in the
+    /// escrow's metadata at creation.
+    #[error(
+        "guest image ID {image_id} differs from {pinned} pinned at escrow creation (v{version})"
+    )]
+    GuestMismatch {
+        /// Pinned image ID.
+        pinned: String,
+        /// Version of the prover that built the pinned guest.
+        version: String,
+        /// Image ID of the guest proving or proven with.
+        image_id: String,
+    },
+
+    /// Proof data is unsigned, or signed by a prover other than those
+    /// trusted with `--trusted-prover`.
+    #[error("proof data is {}, not by a trusted prover", signed_by(.signer))]
+    UntrustedProver {
+        /// Hex-encoded public key of the prover that signed the proof data,
+        /// if any.
+        signer: Option<String>,
+    },
+
+    /// The zkVM guest input, journal, or a receipt could not be decoded,
+    /// e.g. because it was produced by another version.
+    #[error("guest decode failure: {0}")]
+    GuestDecode(String),
+
+    /// The escrow's conditions are not fulfilled.
+    #[error("escrow conditions not met{}: {reason}", subcondition(.path))]
+    ConditionUnmet {
+        /// Why verification failed, as reported by the guest.
+        reason: String,
+        /// Subcondition indices leading to the first failing condition, if
+        /// known (see [`Verification::failing_path`]).
+        ///
+        /// [`Verification::failing_path`]: zescrow_core::Verification::failing_path
+        path: Option<Vec<usize>>,
+    },
+
+    /// A proof does not verify, or is bound to another escrow or guest.
+    #[error("proof verification failed: {0}")]
+    ProofVerification(String),
+
+    /// The prover cannot produce a proof.
+    #[error("prover unavailable: {reason}")]
+    ProverUnavailable {
+        /// Why proving failed.
+        reason: String,
+        /// Whether proving again may succeed, e.g. after a remote proving
+        /// service outage, rather than needing a configuration change.
+        retryable: bool,
+    },
+
+    /// The prover did not produce a proof in time.
+    #[error("proving timed out after {0:?}")]
+    ProverTimeout(Duration),
+
+    /// A counterparty is rejected by the on-chain compliance list.
+    #[error("address blocked by compliance list: {0}")]
+    AddressBlocked(String),
+
     /// Asset amount exceeds representable range.
     #[error("asset amount overflow: value exceeds u64 range")]
     AssetOverflow,
@@ -87,6 +216,35 @@ pub enum ClientError {
     /// Error from zescrow-core library.
     #[error("core library error: {0}")]
     Core(String),
+
+    /// Local escrow store error.
+    #[error("escrow store error: {0}")]
+    Store(String),
+
+    /// Operation journal error, e.g. an unreconciled pending operation.
+    #[error("operation journal error: {0}")]
+    Journal(String),
+
+    /// An atomic swap step was refused, e.g. as its timelocks no longer
+    /// leave the required safety margin.
+    #[error("atomic swap error: {0}")]
+    Swap(String),
+
+    /// A fan-out step was refused, e.g. as its legs are not all locked.
+    #[error("fan-out escrow error: {0}")]
+    FanOut(String),
+
+    /// A lifecycle cost could not be quoted, e.g. as a price feed failed.
+    #[error("quote error: {0}")]
+    Quote(String),
+
+    /// A key reference could not be resolved from its secret manager.
+    #[error("secret error: {0}")]
+    Secret(String),
+
+    /// A transaction was sent but not confirmed before the wait timed out.
+    #[error("{0}")]
+    Pending(PendingTransaction),
 }
 
 impl ClientError {
@@ -106,10 +264,118 @@ impl ClientError {
         }
     }
 
+    /// Creates a Cosmos agent error with context.
+    pub fn cosmos(context: &'static str, msg: impl ToString) -> Self {
+        Self::Cosmos {
+            context,
+            message: msg.to_string(),
+        }
+    }
+
+    /// Creates a Substrate agent error with context.
+    pub fn substrate(context: &'static str, msg: impl ToString) -> Self {
+        Self::Substrate {
+            context,
+            message: msg.to_string(),
+        }
+    }
+
+    /// Creates a Cardano agent error with context.
+    pub fn cardano(context: &'static str, msg: impl ToString) -> Self {
+        Self::Cardano {
+            context,
+            message: msg.to_string(),
+        }
+    }
+
+    /// Creates a Tron agent error with context.
+    pub fn tron(context: &'static str, msg: impl ToString) -> Self {
+        Self::Tron {
+            context,
+            message: msg.to_string(),
+        }
+    }
+
+    /// Creates a Hedera agent error with context.
+    pub fn hedera(context: &'static str, msg: impl ToString) -> Self {
+        Self::Hedera {
+            context,
+            message: msg.to_string(),
+        }
+    }
+
     /// Creates a transaction dropped error with details.
     pub fn tx_dropped(details: impl ToString) -> Self {
         Self::TransactionDropped(details.to_string())
     }
+
+    /// Whether the operation may succeed if retried unchanged, as opposed
+    /// to needing attention, e.g. a fulfillment that does not meet the
+    /// escrow's conditions.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::ProverUnavailable { retryable, .. } => *retryable,
+            Self::ProverTimeout(_) | Self::TransactionDropped(_) | Self::Pending(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Classifies an error from proving `condition` with the prover crate,
+    /// locating the failing subcondition if the conditions are not met.
+    #[cfg(feature = "prover")]
+    pub fn from_proving(error: anyhow::Error, condition: &Condition) -> Self {
+        use zescrow_prover::ProverError;
+
+        let Some(prover) = error.downcast_ref::<ProverError>() else {
+            return Self::ProverUnavailable {
+                reason: format!("{error:#}"),
+                retryable: false,
+            };
+        };
+        match prover {
+            ProverError::ConditionFailed(reason) => Self::ConditionUnmet {
+                reason: reason.clone(),
+                path: condition.verification().failing_path(),
+            },
+            ProverError::UnexpectedState(state) => Self::ConditionUnmet {
+                reason: format!("escrow ended in state {state:?}"),
+                path: None,
+            },
+            ProverError::Decode(reason) => Self::GuestDecode(reason.clone()),
+            ProverError::VerifyReceipt(_)
+            | ProverError::BindingMismatch
+            | ProverError::ImageIdMismatch { .. }
+            | ProverError::NotGroth16
+            | ProverError::MalformedSeal { .. } => Self::ProofVerification(prover.to_string()),
+            ProverError::RemoteTimeout(timeout) => Self::ProverTimeout(*timeout),
+            ProverError::Remote(_) | ProverError::Proving(_) => Self::ProverUnavailable {
+                reason: prover.to_string(),
+                retryable: true,
+            },
+            _ => Self::ProverUnavailable {
+                reason: prover.to_string(),
+                retryable: false,
+            },
+        }
+    }
+}
+
+/// Formats a subcondition path for [`ClientError::ConditionUnmet`].
+fn subcondition(path: &Option<Vec<usize>>) -> String {
+    match path.as_deref() {
+        Some([]) | None => String::new(),
+        Some(path) => {
+            let path: Vec<String> = path.iter().map(usize::to_string).collect();
+            format!(" at subcondition {}", path.join("."))
+        }
+    }
+}
+
+fn signed_by(signer: &Option<String>) -> String {
+    match signer {
+        Some(signer) => format!("signed by {signer}"),
+        None => "unsigned".into(),
+    }
 }
 
 impl From<solana_client::client_error::ClientError> for ClientError {
@@ -118,14 +384,14 @@ impl From<solana_client::client_error::ClientError> for ClientError {
     }
 }
 
-impl From<ethers::providers::ProviderError> for ClientError {
-    fn from(value: ethers::providers::ProviderError) -> Self {
+impl From<alloy::transports::TransportError> for ClientError {
+    fn from(value: alloy::transports::TransportError) -> Self {
         Self::Blockchain(value.to_string())
     }
 }
 
-impl From<ethers::signers::WalletError> for ClientError {
-    fn from(value: ethers::signers::WalletError) -> Self {
+impl From<alloy::signers::local::LocalSignerError> for ClientError {
+    fn from(value: alloy::signers::local::LocalSignerError) -> Self {
         Self::Keypair(value.to_string())
     }
 }
@@ -141,3 +407,10 @@ impl From<zescrow_core::error::EscrowError> for ClientError {
         Self::Core(value.to_string())
     }
 }
+
+#[cfg(feature = "sqlite")]
+impl From<rusqlite::Error> for ClientError {
+    fn from(value: rusqlite::Error) -> Self {
+        Self::Store(value.to_string())
+    }
+}
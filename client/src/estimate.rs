@@ -0,0 +1,94 @@
+//! Cost estimates for the whole escrow lifecycle.
+
+use std::fmt;
+
+use serde::Serialize;
+use zescrow_core::BigNumber;
+
+use crate::simulation::Simulation;
+
+/// Estimated cost of one escrow transaction.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Cost {
+    /// Contract method or program instruction estimated.
+    pub operation: &'static str,
+    /// Gas (Ethereum) or compute units (Solana) the transaction would use,
+    /// if known.
+    pub units: Option<u64>,
+    /// Upper bound on the fee, in wei or lamports, if known.
+    pub fee: Option<BigNumber>,
+    /// Rent locked in the escrow account by the transaction, in lamports
+    /// (Solana only). Returned when the escrow is settled.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposit: Option<BigNumber>,
+    /// Whether `units` comes from simulating the transaction, rather than
+    /// reference figures for a typical escrow.
+    pub simulated: bool,
+}
+
+impl Cost {
+    /// A cost whose units and fee are unknown.
+    pub fn unknown(operation: &'static str) -> Self {
+        Self {
+            operation,
+            units: None,
+            fee: None,
+            deposit: None,
+            simulated: false,
+        }
+    }
+}
+
+impl From<Simulation> for Cost {
+    /// Takes the units of a successful simulation, and the fee either way.
+    fn from(simulation: Simulation) -> Self {
+        let units = simulation.units.filter(|_| simulation.succeeded());
+        Self {
+            operation: simulation.operation,
+            units,
+            fee: simulation.fee,
+            deposit: None,
+            simulated: units.is_some(),
+        }
+    }
+}
+
+impl fmt::Display for Cost {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:<22}", self.operation)?;
+        match self.units {
+            Some(units) if self.simulated => write!(f, " units {units:>9}")?,
+            Some(units) => write!(f, " units ~{units:>8}")?,
+            None => write!(f, " units {:>9}", "?")?,
+        }
+        match &self.fee {
+            Some(fee) => write!(f, "  max fee {fee}")?,
+            None => write!(f, "  max fee ?")?,
+        }
+        if let Some(deposit) = &self.deposit {
+            write!(f, "  deposit {deposit} (refunded)")?;
+        }
+        Ok(())
+    }
+}
+
+/// Estimated costs of creating an escrow and of either settling it.
+///
+/// Finish and cancel are estimated before the escrow exists, so they
+/// usually rely on reference figures rather than simulation.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct LifecycleEstimate {
+    /// Creating and funding the escrow.
+    pub create: Cost,
+    /// Releasing the escrow to the recipient, including on-chain proof
+    /// verification for escrows with conditions (Ethereum).
+    pub finish: Cost,
+    /// Refunding the escrow to the sender.
+    pub cancel: Cost,
+}
+
+impl fmt::Display for LifecycleEstimate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}\n{}\n{}", self.create, self.finish, self.cancel)
+    }
+}
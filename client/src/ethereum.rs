@@ -4,47 +4,472 @@
 //! smart contract. Supports creating, finishing, and canceling escrows
 //! on Ethereum and EVM-compatible chains.
 
-use std::convert::TryFrom;
+use std::collections::HashSet;
 use std::str::FromStr;
-use std::sync::Arc;
-
-use ethers::abi::Abi;
-use ethers::contract::{Contract, EthEvent};
-use ethers::middleware::SignerMiddleware;
-use ethers::providers::{Http, Middleware, Provider};
-use ethers::signers::{LocalWallet, Signer};
-use ethers::types::{Address, H256, U256};
-use serde_json::Value;
-use tracing::{debug, info};
-use zescrow_core::{ChainConfig, EscrowMetadata, EscrowParams, ExecutionState};
+use std::time::{Duration, Instant};
+
+use alloy::consensus::TxEip1559;
+use alloy::contract::CallBuilder;
+use alloy::eips::BlockNumberOrTag;
+use alloy::network::EthereumWallet;
+use alloy::primitives::{Address, Bytes, TxKind, B256, U256};
+use alloy::providers::{
+    DynProvider, PendingTransactionBuilder, PendingTransactionError, Provider, ProviderBuilder,
+    WatchTxError,
+};
+use alloy::rpc::types::{Filter, Log, TransactionReceipt, TransactionRequest};
+use alloy::signers::Signer;
+use alloy::sol;
+use alloy::sol_types::{SolCall, SolEvent, SolEventInterface};
+use futures::{future, Stream, StreamExt};
+use sha2::{Digest, Sha256};
+use tracing::{debug, info, warn};
+use url::Url;
+use zescrow_core::condition::cross_chain::{Observation, RemoteClaim};
+use zescrow_core::interface::{ChainState, EscrowBinding, Freshness, Journal};
+use zescrow_core::{
+    BigNumber, Chain, ChainConfig, EscrowId, EscrowMetadata, EscrowParams, EvmNetwork,
+    ExecutionState, ObservedBlock,
+};
 
 use crate::error::ClientError;
-use crate::{Agent, Result};
+use crate::estimate::{Cost, LifecycleEstimate};
+use crate::offline::{Broadcast, OfflineTransaction};
+use crate::pending::{PendingTransaction, WaitConfig};
+use crate::rpc::ethereum::FailoverTransport;
+use crate::rpc::RpcPolicy;
+use crate::simulation::{Operation, Simulation};
+use crate::watch::{EscrowUpdate, UpdateKind, UpdateStream};
+use crate::{Agent, EscrowStatus, EscrowSummary, Result};
+
+pub mod indexer;
+pub mod l2;
+pub mod permit;
+pub mod safe;
+pub mod signer;
+pub mod smart_account;
+pub mod zksync;
+
+use indexer::{EscrowIndexer, IndexedEvent};
+use safe::{SafeConfig, SafeSender, SafeSubmission};
+pub use signer::EthereumSigner;
+use smart_account::SmartAccountSender;
+use zksync::PaymasterSender;
 
-/// Escrow contract ABI embedded at compile time.
-const ESCROW_JSON: &str = include_str!("../abi/Escrow.json");
+// Typed bindings generated from the Hardhat artifact copied in by
+// `agent/ethereum`'s `postcompile` script.
+sol!(
+    #[sol(rpc)]
+    Escrow,
+    "abi/Escrow.json"
+);
 
 // Contract method names.
 const CREATE_ESCROW: &str = "createEscrow";
 const FINISH_ESCROW: &str = "finishEscrow";
 const CANCEL_ESCROW: &str = "cancelEscrow";
+const MULTICALL: &str = "multicall";
+const GET_ESCROW: &str = "getEscrow";
+const LIST_ESCROWS: &str = "listEscrows";
+const SUBSCRIBE: &str = "subscribe";
+const WATCH: &str = "watch";
+const WAIT_FOR_TRANSACTION: &str = "waitForTransaction";
+const BUILD_TRANSACTION: &str = "buildTransaction";
+const BROADCAST_TRANSACTION: &str = "broadcastTransaction";
+const OBSERVE: &str = "observe";
+const GET_BLOCK: &str = "getBlock";
+
+/// Typical gas of finishing an escrow, for estimates made before it
+/// exists. ERC-20 escrows use somewhat more.
+const FINISH_GAS: u64 = 60_000;
+
+/// Typical gas of cancelling an escrow, for estimates made before it exists.
+const CANCEL_GAS: u64 = 55_000;
+
+/// Typical extra gas of verifying a Groth16 seal through the RISC Zero
+/// verifier router when finishing an escrow with conditions.
+const PROOF_VERIFICATION_GAS: u64 = 300_000;
+
+/// Length of an encoded Groth16 seal: the journal's
+/// [seal prefix](Journal::seal_prefix), the 4-byte verifier selector, and three curve points.
+const SEAL_LEN: usize = Journal::SEAL_PREFIX_LEN + 260;
+
+/// Flashbots Protect RPC endpoint for private transaction submission.
+pub const FLASHBOTS_PROTECT_RPC: &str = "https://rpc.flashbots.net/fast";
+
+/// Interval between receipt polls unless configured otherwise.
+const RECEIPT_POLL_INTERVAL: Duration = Duration::from_secs(4);
+
+/// Times a transaction is resent after a reorg drops it before giving up.
+const MAX_REORG_RETRIES: u32 = 3;
+
+/// Timelocks further out than this are almost certainly in the wrong units.
+const MAX_TIMELOCK_SECS: u64 = 365 * 24 * 60 * 60;
+
+/// EIP-1559 fee settings applied to every Ethereum transaction.
+///
+/// Unset fees are estimated from recent blocks via `eth_feeHistory`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct FeeConfig {
+    /// Override for `maxFeePerGas`, in wei.
+    pub max_fee_per_gas: Option<u128>,
+    /// Override for `maxPriorityFeePerGas`, in wei.
+    pub max_priority_fee_per_gas: Option<u128>,
+    /// Refuse to send if the resolved `maxFeePerGas` exceeds this, in wei.
+    pub gas_price_cap: Option<u128>,
+    /// Replace transactions that stay unmined with higher-fee copies.
+    pub bump: Option<FeeBump>,
+}
+
+/// Policy for replacing a stuck transaction with a higher-fee copy at the
+/// same nonce.
+#[derive(Debug, Clone, Copy)]
+pub struct FeeBump {
+    /// How long to wait for inclusion before replacing the transaction.
+    pub timeout: Duration,
+    /// Fee increase per replacement, in percent. Most nodes reject
+    /// replacements below 10%.
+    pub percent: u64,
+    /// Replacements to send before waiting on the last one indefinitely.
+    pub max_bumps: u32,
+}
+
+impl Default for FeeBump {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(180),
+            percent: 15,
+            max_bumps: 5,
+        }
+    }
+}
+
+impl FeeBump {
+    /// Raises `fee` by the configured percentage, by at least one wei.
+    fn apply(&self, fee: u128) -> u128 {
+        let increase = (fee * u128::from(self.percent)).div_ceil(100).max(1);
+        fee.saturating_add(increase)
+    }
+}
+
+/// Expected gas usage and fees for an Ethereum transaction.
+#[derive(Debug, Clone, Copy)]
+pub struct GasEstimate {
+    /// Estimated gas limit.
+    pub gas_limit: u64,
+    /// `maxFeePerGas` the transaction would be sent with, in wei.
+    pub max_fee_per_gas: u128,
+    /// `maxPriorityFeePerGas` the transaction would be sent with, in wei.
+    pub max_priority_fee_per_gas: u128,
+    /// L1 data fee charged on top of execution gas, in wei (L2 presets only).
+    pub l1_data_fee: Option<U256>,
+}
+
+impl GasEstimate {
+    /// Upper bound on the transaction cost, in wei.
+    pub fn max_cost(&self) -> U256 {
+        U256::from(self.gas_limit) * U256::from(self.max_fee_per_gas)
+            + self.l1_data_fee.unwrap_or_default()
+    }
+}
+
+/// Fees resolved for a single transaction.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Fees {
+    pub(crate) max_fee_per_gas: u128,
+    pub(crate) max_priority_fee_per_gas: u128,
+}
+
+/// Lifecycle event emitted by the Escrow contract.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum EscrowEvent {
+    /// A new escrow was funded.
+    Created {
+        /// Contract-assigned escrow ID.
+        escrow_id: u64,
+        /// Depositor.
+        sender: Address,
+        /// Beneficiary.
+        recipient: Address,
+        /// Locked amount, in wei.
+        amount: U256,
+        /// Chain-agnostic escrow ID.
+        uid: EscrowId,
+    },
+    /// An escrow was released to its recipient.
+    Finished {
+        /// Contract-assigned escrow ID.
+        escrow_id: u64,
+        /// Beneficiary.
+        recipient: Address,
+        /// Released amount, in wei.
+        amount: U256,
+    },
+    /// An escrow was refunded to its sender.
+    Cancelled {
+        /// Contract-assigned escrow ID.
+        escrow_id: u64,
+        /// Depositor.
+        sender: Address,
+        /// Refunded amount, in wei.
+        amount: U256,
+    },
+}
+
+impl EscrowEvent {
+    /// Decodes an Escrow contract log.
+    fn decode(log: &Log) -> Result<Self> {
+        let decoded = Escrow::EscrowEvents::decode_log(&log.inner)
+            .map_err(|e| ClientError::ethereum(SUBSCRIBE, e))?;
+
+        Ok(match decoded.data {
+            Escrow::EscrowEvents::EscrowCreated(e) => Self::Created {
+                escrow_id: to_escrow_id(e.escrowId, SUBSCRIBE)?,
+                sender: e.sender,
+                recipient: e.recipient,
+                amount: e.amount,
+                uid: EscrowId(e.uid.0),
+            },
+            Escrow::EscrowEvents::EscrowFinished(e) => Self::Finished {
+                escrow_id: to_escrow_id(e.escrowId, SUBSCRIBE)?,
+                recipient: e.recipient,
+                amount: e.amount,
+            },
+            Escrow::EscrowEvents::EscrowCancelled(e) => Self::Cancelled {
+                escrow_id: to_escrow_id(e.escrowId, SUBSCRIBE)?,
+                sender: e.sender,
+                amount: e.amount,
+            },
+        })
+    }
+}
+
+/// Contract instances that submit through a private transaction relay
+/// instead of the public mempool.
+struct PrivateRelay {
+    as_sender: Escrow::EscrowInstance<DynProvider>,
+    as_recipient: Option<Escrow::EscrowInstance<DynProvider>>,
+}
+
+/// Returns the logs of a mined `receipt`.
+fn receipt_logs(receipt: TransactionReceipt) -> Vec<Log> {
+    info!(tx_hash = ?receipt.transaction_hash, "Transaction mined");
+    receipt.inner.logs().to_vec()
+}
+
+/// Builds the error for an `operation` transaction still pending at its
+/// deadline.
+fn pending(operation: &str, tx_hash: B256) -> ClientError {
+    ClientError::Pending(PendingTransaction {
+        chain: Chain::Ethereum,
+        operation: operation.to_owned(),
+        id: tx_hash.to_string(),
+        blockhash: None,
+    })
+}
+
+/// Converts an indexed contract event into a chain-agnostic update, with
+/// the parties' addresses in the chain's `format`.
+pub(crate) fn to_update(
+    indexed: IndexedEvent,
+    format: fn(Address) -> String,
+) -> Result<EscrowUpdate> {
+    let (kind, escrow_id, sender, recipient, amount) = match indexed.event {
+        EscrowEvent::Created {
+            escrow_id,
+            sender,
+            recipient,
+            amount,
+            ..
+        } => (
+            UpdateKind::Created,
+            escrow_id,
+            Some(sender),
+            Some(recipient),
+            amount,
+        ),
+        EscrowEvent::Finished {
+            escrow_id,
+            recipient,
+            amount,
+        } => (
+            UpdateKind::Finished,
+            escrow_id,
+            None,
+            Some(recipient),
+            amount,
+        ),
+        EscrowEvent::Cancelled {
+            escrow_id,
+            sender,
+            amount,
+        } => (UpdateKind::Cancelled, escrow_id, Some(sender), None, amount),
+    };
+    Ok(EscrowUpdate {
+        escrow: escrow_id.to_string(),
+        kind,
+        sender: sender.map(format),
+        recipient: recipient.map(format),
+        amount: u128::try_from(amount)
+            .map(BigNumber::from)
+            .map_err(|_| ClientError::AssetOverflow)?,
+        tx: indexed.tx_hash.map(|hash| hash.to_string()),
+    })
+}
 
-/// The `EscrowCreated` event emitted when a new escrow is created.
-#[derive(Clone, Debug, EthEvent)]
-#[ethevent(
-    name = "EscrowCreated",
-    abi = "EscrowCreated(uint256,address,address,uint256,uint256,uint256)"
-)]
-struct EscrowCreatedEvent {
-    #[ethevent(indexed)]
-    escrow_id: U256,
-    #[ethevent(indexed)]
-    sender: Address,
-    #[ethevent(indexed)]
+/// Encodes the `createEscrow` call for `params`, or
+/// `createConditionalEscrow` storing the escrow and condition `fingerprints`
+/// when the escrow has conditions, paying `recipient`, and returns it with
+/// the escrowed amount. The contract has no memo field, so escrows with a
+/// memo are rejected.
+pub(crate) fn create_calldata(
     recipient: Address,
-    amount: U256,
-    finish_after: U256,
-    cancel_after: U256,
+    params: &EscrowParams,
+    fingerprints: Option<(B256, B256)>,
+) -> Result<(Bytes, U256)> {
+    crate::reject_memo(params)?;
+    let finish_after = U256::from(params.finish_after.unwrap_or_default());
+    let cancel_after = U256::from(params.cancel_after.unwrap_or_default());
+    let amount = U256::from_str(&params.asset.amount().to_string())
+        .map_err(|_| ClientError::AssetOverflow)?;
+    let calldata = if params.has_conditions {
+        let (fingerprint, condition) = fingerprints.ok_or_else(|| {
+            ClientError::ethereum(CREATE_ESCROW, "escrow has conditions but no condition")
+        })?;
+        Escrow::createConditionalEscrowCall {
+            recipient,
+            finishAfter: finish_after,
+            cancelAfter: cancel_after,
+            fingerprint,
+            conditionFingerprint: condition,
+        }
+        .abi_encode()
+    } else {
+        Escrow::createEscrowCall {
+            recipient,
+            finishAfter: finish_after,
+            cancelAfter: cancel_after,
+        }
+        .abi_encode()
+    };
+    Ok((calldata.into(), amount))
+}
+
+/// Returns the fingerprints a conditional escrow of `params` is created
+/// with: that of the escrow as `escrow`'s next escrow ID, and `condition`.
+/// Returns `None` if `params` has no conditions.
+///
+/// The escrow fingerprint covers the escrow ID, so an escrow created first
+/// by someone else leaves this one unfinishable; it can still be cancelled.
+pub(crate) async fn create_fingerprints(
+    escrow: &Escrow::EscrowInstance<DynProvider>,
+    params: &EscrowParams,
+    condition: Option<B256>,
+) -> Result<Option<(B256, B256)>> {
+    if !params.has_conditions {
+        return Ok(None);
+    }
+    let condition = condition.ok_or_else(|| {
+        ClientError::ethereum(CREATE_ESCROW, "escrow has conditions but no condition")
+    })?;
+    let next_id = escrow
+        .nextEscrowId()
+        .call()
+        .await
+        .map_err(|e| ClientError::ethereum(CREATE_ESCROW, e))?;
+    let metadata = EscrowMetadata {
+        params: params.clone(),
+        state: ExecutionState::Funded,
+        escrow_id: Some(to_escrow_id(next_id, CREATE_ESCROW)?),
+        uid: None,
+        chain_metadata: None,
+        guest: None,
+        observed: None,
+    };
+    Ok(Some((metadata.fingerprint().into(), condition)))
+}
+
+/// Encodes the `finishEscrow` call for escrow `id`, or
+/// `finishEscrowWithProof` with `seal` when the escrow has conditions,
+/// after checking that the seal is bound to that escrow.
+pub(crate) fn finish_calldata(
+    metadata: &EscrowMetadata,
+    id: u64,
+    seal: Option<&Bytes>,
+) -> Result<Bytes> {
+    let escrow_id = U256::from(id);
+    let calldata = if metadata.params.has_conditions {
+        let seal = seal.cloned().ok_or_else(|| {
+            ClientError::ethereum(FINISH_ESCROW, "escrow has conditions but no proof seal")
+        })?;
+        let binding = metadata
+            .binding()
+            .map_err(|e| ClientError::ethereum(FINISH_ESCROW, e))?;
+        check_proof_binding(&seal, binding)?;
+        Escrow::finishEscrowWithProofCall {
+            escrowId: escrow_id,
+            seal,
+        }
+        .abi_encode()
+    } else {
+        Escrow::finishEscrowCall {
+            escrowId: escrow_id,
+        }
+        .abi_encode()
+    };
+    Ok(calldata.into())
+}
+
+/// Describes why a call reverted, naming the contract error when it is
+/// one of the Escrow contract's custom errors.
+fn revert_reason(error: &alloy::contract::Error) -> String {
+    error
+        .as_decoded_interface_error::<Escrow::EscrowErrors>()
+        .map(|decoded| format!("{decoded:?}"))
+        .unwrap_or_else(|| error.to_string())
+}
+
+/// Converts an on-chain escrow ID to `u64`.
+fn to_escrow_id(id: U256, operation: &'static str) -> Result<u64> {
+    u64::try_from(id).map_err(|e| ClientError::ethereum(operation, e))
+}
+
+/// Converts an on-chain block number to `u64`.
+fn to_height(block: U256, operation: &'static str) -> Result<u64> {
+    u64::try_from(block).map_err(|e| ClientError::ethereum(operation, e))
+}
+
+/// Collects the escrow IDs of a batch, failing if any is missing.
+fn batch_ids(metadata: &[EscrowMetadata], operation: &'static str) -> Result<Vec<u64>> {
+    metadata
+        .iter()
+        .map(|m| {
+            m.escrow_id
+                .ok_or_else(|| ClientError::ethereum(operation, "missing escrow_id"))
+        })
+        .collect()
+}
+
+/// Checks that `seal` proves the guest committed `ConditionsMet` for the
+/// escrow `binding` identifies, with the freshness, chain state, and
+/// condition fingerprint the seal is prefixed with, so a proof for another
+/// escrow fails here rather than reverting on-chain.
+#[cfg(feature = "prover")]
+fn check_proof_binding(seal: &[u8], binding: EscrowBinding) -> Result<()> {
+    let (journal, _) = Journal::from_seal_prefix(seal, binding)
+        .ok_or_else(|| ClientError::ethereum(FINISH_ESCROW, "proof seal is too short"))?;
+    crate::prover::verify_ethereum_seal(seal, &journal.encode()).map_err(|e| {
+        ClientError::ethereum(
+            FINISH_ESCROW,
+            format!("proof seal is not for this escrow: {e:#}"),
+        )
+    })
+}
+
+/// Without the prover, the escrow contract checks the binding on-chain.
+#[cfg(not(feature = "prover"))]
+fn check_proof_binding(_seal: &[u8], _binding: EscrowBinding) -> Result<()> {
+    Ok(())
 }
 
 /// Ethereum blockchain agent for escrow operations.
@@ -52,12 +477,44 @@ struct EscrowCreatedEvent {
 /// Manages interactions with the Zescrow Ethereum smart contract,
 /// including transaction signing and event parsing.
 pub struct EthereumAgent {
-    /// Ethereum JSON-RPC provider.
-    pub provider: Provider<Http>,
+    /// Ethereum JSON-RPC provider, signing as the sender.
+    pub provider: DynProvider,
+    /// Sender key; signs Safe transactions when the sender is a Safe.
+    sender: EthereumSigner,
     /// Contract instance signed by the sender.
-    escrow_as_sender: Contract<SignerMiddleware<Provider<Http>, LocalWallet>>,
+    escrow_as_sender: Escrow::EscrowInstance<DynProvider>,
+    /// Recipient key (optional, for finish operations).
+    recipient: Option<EthereumSigner>,
     /// Contract instance signed by the recipient (optional, for finish operations).
-    escrow_as_recipient: Option<Contract<SignerMiddleware<Provider<Http>, LocalWallet>>>,
+    escrow_as_recipient: Option<Escrow::EscrowInstance<DynProvider>>,
+    /// EIP-1559 fee settings.
+    fees: FeeConfig,
+    /// Optional WS/IPC provider for event subscriptions.
+    pubsub: Option<DynProvider>,
+    /// Set when the escrow sender is a Safe rather than the sender key itself.
+    safe: Option<SafeSender>,
+    /// Network preset, if configured.
+    network: Option<EvmNetwork>,
+    /// Set when finish/cancel transactions go through a private relay.
+    relay: Option<PrivateRelay>,
+    /// Set when the escrow sender is an ERC-4337 smart account.
+    sender_account: Option<SmartAccountSender>,
+    /// Set when the escrow recipient is an ERC-4337 smart account.
+    recipient_account: Option<SmartAccountSender>,
+    /// Set when a zkSync paymaster sponsors escrow calls.
+    paymaster: Option<PaymasterSender>,
+    /// Blocks a transaction must be buried under before it is final.
+    confirmations: u64,
+    /// Groth16 seal submitted when finishing an escrow with conditions.
+    proof_seal: Option<Bytes>,
+    /// Fingerprint of the condition escrows are created with.
+    condition: Option<B256>,
+    /// Block from which escrow events are scanned when listing escrows.
+    from_block: u64,
+    /// Retry settings and metrics for the agent's RPC connections.
+    rpc: RpcPolicy,
+    /// Timeout and polling interval of confirmation waits.
+    wait: WaitConfig,
 }
 
 impl EthereumAgent {
@@ -66,178 +523,1105 @@ impl EthereumAgent {
     /// # Arguments
     ///
     /// * `config` - Chain configuration containing RPC URL and sender key
-    /// * `recipient` - Optional recipient wallet for finish operations
+    /// * `sender` - Optional signer overriding `sender_private_id`
+    /// * `recipient` - Optional recipient signer for finish operations
+    /// * `fees` - EIP-1559 fee overrides and cap
+    /// * `rpc` - Retry settings and metrics for RPC requests, which fail
+    ///   over to `fallback_rpc_urls` when `rpc_url` is unavailable
     ///
     /// # Errors
     ///
     /// Returns an error if:
     /// - RPC connection fails
-    /// - Contract ABI parsing fails
-    /// - Wallet parsing fails
-    pub async fn new(config: &ChainConfig, recipient: Option<LocalWallet>) -> Result<Self> {
+    /// - Contract address parsing fails
+    /// - Signer parsing fails
+    /// - The settings of a custom network are invalid
+    /// - The endpoint's chain ID does not match the configured network preset
+    pub async fn new(
+        config: &ChainConfig,
+        sender: Option<EthereumSigner>,
+        recipient: Option<EthereumSigner>,
+        fees: FeeConfig,
+        rpc: RpcPolicy,
+    ) -> Result<Self> {
         let ChainConfig {
             rpc_url,
+            fallback_rpc_urls,
             sender_private_id,
             agent_id,
+            pubsub_url,
+            network,
+            smart_account,
+            zksync,
             ..
         } = config;
 
-        let provider = Provider::<Http>::try_from(rpc_url)?;
+        if let Some(network) = network {
+            network.validate()?;
+        }
+        let fallback_rpc_urls = fallback_rpc_urls
+            .iter()
+            .map(|url| Url::parse(url))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+        let transport =
+            FailoverTransport::new(&Url::parse(rpc_url)?, &fallback_rpc_urls, rpc.clone());
+        let sender = match sender {
+            Some(sender) => sender,
+            None => EthereumSigner::from_id(sender_private_id).await?,
+        };
+        debug!(sender = %sender.address(), "Loaded sender signer");
+        let provider = Self::connect(&transport, sender.clone());
+
         let chain_id = provider
-            .get_chainid()
+            .get_chain_id()
             .await
-            .map_err(|e| ClientError::ethereum("get_chainid", e))?
-            .as_u64();
+            .map_err(|e| ClientError::ethereum("get_chain_id", e))?;
         debug!(%chain_id, "Connected to Ethereum");
 
-        let abi = Self::load_contract_abi()?;
-        let escrow_addr = Address::from_str(agent_id)?;
+        if let Some(network) = network {
+            (network.chain_id() == chain_id)
+                .then_some(())
+                .ok_or_else(|| ClientError::ConfigMismatch {
+                    expected: format!("{} (chain ID {})", network.as_ref(), network.chain_id()),
+                    actual: format!("chain ID {chain_id}"),
+                })?;
+        }
 
-        let escrow_as_sender = Self::create_contract_instance(
-            &provider,
-            escrow_addr,
-            abi.clone(),
-            sender_private_id,
-            chain_id,
-        )?;
+        let pubsub = match pubsub_url {
+            Some(url) => Some(
+                ProviderBuilder::new()
+                    .connect(url)
+                    .await
+                    .map_err(|e| ClientError::ethereum(SUBSCRIBE, e))?
+                    .erased(),
+            ),
+            None => None,
+        };
 
-        let escrow_as_recipient = recipient.map(|wallet| {
-            let signer = Arc::new(SignerMiddleware::new(
-                provider.clone(),
-                wallet.with_chain_id(chain_id),
-            ));
-            Contract::new(escrow_addr, abi, signer)
-        });
+        let escrow_addr = Address::from_str(agent_id)?;
+        let escrow_as_sender = Escrow::new(escrow_addr, provider.clone());
+        let escrow_as_recipient = recipient
+            .clone()
+            .map(|signer| Escrow::new(escrow_addr, Self::connect(&transport, signer)));
+
+        let (sender_account, recipient_account) = match smart_account {
+            Some(config) => {
+                let account = |address: &Option<String>| {
+                    address
+                        .as_deref()
+                        .map(|address| SmartAccountSender::new(config, address, provider.clone()))
+                        .transpose()
+                };
+                (
+                    account(&config.sender_account)?,
+                    account(&config.recipient_account)?,
+                )
+            }
+            None => (None, None),
+        };
+        let paymaster = zksync
+            .as_ref()
+            .map(|config| PaymasterSender::new(config, provider.clone()))
+            .transpose()?;
 
         Ok(Self {
             provider,
+            sender,
             escrow_as_sender,
+            recipient,
             escrow_as_recipient,
+            fees,
+            pubsub,
+            safe: None,
+            network: *network,
+            relay: None,
+            sender_account,
+            recipient_account,
+            paymaster,
+            confirmations: network.map_or(1, |network| network.default_confirmations()),
+            proof_seal: None,
+            condition: None,
+            from_block: 0,
+            rpc,
+            wait: WaitConfig::default(),
         })
     }
 
-    /// Loads and parses the contract ABI from embedded JSON.
-    fn load_contract_abi() -> Result<Abi> {
-        let artifact: Value = serde_json::from_str(ESCROW_JSON)
-            .map_err(|e| ClientError::ethereum("parse_artifact", e))?;
+    /// Routes sender-only calls (`createEscrow`, `cancelEscrow`) through the
+    /// Safe at `config.address`, with the configured sender key acting as
+    /// one of its owners.
+    pub fn with_safe(mut self, config: SafeConfig) -> Self {
+        self.safe = Some(SafeSender::new(config, self.provider.clone()));
+        self
+    }
+
+    /// Sends `finishEscrow` and `cancelEscrow` transactions through the
+    /// private relay at `url` (e.g., [`FLASHBOTS_PROTECT_RPC`]) so they are
+    /// not exposed in the public mempool before inclusion.
+    ///
+    /// `createEscrow` still uses the public endpoint, as do cancellations
+    /// routed through a Safe.
+    pub fn with_private_relay(mut self, url: &Url) -> Self {
+        let address = *self.escrow_as_sender.address();
+        let transport = FailoverTransport::new(url, &[], self.rpc.clone());
+        let relay = PrivateRelay {
+            as_sender: Escrow::new(address, Self::connect(&transport, self.sender.clone())),
+            as_recipient: self
+                .recipient
+                .clone()
+                .map(|signer| Escrow::new(address, Self::connect(&transport, signer))),
+        };
+        self.relay = Some(relay);
+        self.apply_poll_interval();
+        info!(%url, "Submitting finish/cancel transactions privately");
+        self
+    }
+
+    /// Stops waiting for transactions to become final after
+    /// [`WaitConfig::timeout`], failing with [`ClientError::Pending`], and
+    /// polls for receipts at [`WaitConfig::poll_interval`].
+    ///
+    /// Applies to transactions sent directly, not to Safe transactions or
+    /// user operations awaiting execution.
+    pub fn with_wait(mut self, wait: WaitConfig) -> Self {
+        self.wait = wait;
+        self.apply_poll_interval();
+        self
+    }
 
-        let abi_json = artifact
-            .get("abi")
-            .ok_or_else(|| ClientError::ethereum("load_abi", "missing ABI field in artifact"))?
-            .to_string();
+    /// Sets the configured poll interval on every signing provider.
+    fn apply_poll_interval(&self) {
+        let Some(interval) = self.wait.poll_interval else {
+            return;
+        };
+        let relay = self.relay.as_ref();
+        std::iter::once(&self.escrow_as_sender)
+            .chain(self.escrow_as_recipient.as_ref())
+            .chain(relay.map(|relay| &relay.as_sender))
+            .chain(relay.and_then(|relay| relay.as_recipient.as_ref()))
+            .for_each(|contract| contract.provider().client().set_poll_interval(interval));
+    }
 
-        serde_json::from_str::<Abi>(&abi_json).map_err(|e| ClientError::ethereum("parse_abi", e))
+    /// Returns when waits for transactions sent now should give up.
+    fn deadline(&self) -> Option<Instant> {
+        self.wait.timeout.map(|timeout| Instant::now() + timeout)
     }
 
-    /// Creates a contract instance with a signing middleware.
-    fn create_contract_instance(
-        provider: &Provider<Http>,
-        address: Address,
-        abi: Abi,
-        private_key: &str,
-        chain_id: u64,
-    ) -> Result<Contract<SignerMiddleware<Provider<Http>, LocalWallet>>> {
-        let wallet = private_key.parse::<LocalWallet>()?.with_chain_id(chain_id);
-        let signer = Arc::new(SignerMiddleware::new(provider.clone(), wallet));
-        Ok(Contract::new(address, abi, signer))
+    /// Waits until transactions are `confirmations` blocks deep (at least
+    /// one) before treating them as final. Defaults to the network preset's
+    /// [`default_confirmations`](EvmNetwork::default_confirmations), or
+    /// one without a preset.
+    pub fn with_confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations.max(1);
+        self
     }
 
-    /// Extracts the escrow ID from transaction events.
-    async fn extract_escrow_id(&self, block_hash: H256) -> Result<u64> {
-        let events = self
-            .escrow_as_sender
-            .event::<EscrowCreatedEvent>()
-            .at_block_hash(block_hash)
-            .query()
+    /// Supplies the Groth16 seal proving an escrow's conditions are met, as
+    /// produced by `zescrow_prover::ethereum_seal`.
+    ///
+    /// Escrows created with conditions can only be finished with a seal that
+    /// the contract's RISC Zero verifier accepts.
+    pub fn with_proof_seal(mut self, seal: impl Into<Bytes>) -> Self {
+        self.proof_seal = Some(seal.into());
+        self
+    }
+
+    /// Supplies the fingerprint of the condition (`Condition::fingerprint`)
+    /// escrows with conditions are created with.
+    ///
+    /// The contract stores it, and only finishes the escrow with a proof
+    /// that this condition is fulfilled.
+    pub fn with_condition_fingerprint(mut self, fingerprint: [u8; 32]) -> Self {
+        self.condition = Some(fingerprint.into());
+        self
+    }
+
+    /// Scans escrow events from `block` (e.g., the contract's deployment
+    /// block) when listing escrows. Defaults to zero.
+    pub fn with_from_block(mut self, block: u64) -> Self {
+        self.from_block = block;
+        self
+    }
+
+    /// Estimates gas and fees for creating an escrow from `params`.
+    ///
+    /// Estimates are for a direct call from the sender key, even when the
+    /// sender is a Safe.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the call would revert or fee estimation fails.
+    pub async fn estimate_create(&self, params: &EscrowParams) -> Result<GasEstimate> {
+        let (call, _) = self.create_call(params, params).await?;
+        let gas_limit = call
+            .estimate_gas()
             .await
-            .map_err(|e| ClientError::ethereum("query_events", e))?;
+            .map_err(|e| ClientError::ethereum(CREATE_ESCROW, e))?;
+        self.gas_estimate(gas_limit, call.calldata().clone(), CREATE_ESCROW)
+            .await
+    }
 
-        let event = events
-            .into_iter()
-            .next()
-            .ok_or_else(|| ClientError::MissingEvent("EscrowCreated event not found".into()))?;
+    /// Estimates gas and fees for finishing the escrow in `metadata`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no recipient is configured, the call would revert
+    /// (e.g., before `finish_after`), or fee estimation fails.
+    pub async fn estimate_finish(&self, metadata: &EscrowMetadata) -> Result<GasEstimate> {
+        let id = metadata
+            .escrow_id
+            .ok_or_else(|| ClientError::ethereum(FINISH_ESCROW, "missing escrow_id"))?;
+        let call = self.finish_call(self.recipient_contract()?, metadata, id)?;
+        let gas_limit = call
+            .estimate_gas()
+            .await
+            .map_err(|e| ClientError::ethereum(FINISH_ESCROW, e))?;
+        self.gas_estimate(gas_limit, call.calldata().clone(), FINISH_ESCROW)
+            .await
+    }
 
-        let escrow_id = event.escrow_id;
-        if escrow_id.is_zero() {
-            return Err(ClientError::MissingEvent("escrow_id is zero".into()));
+    /// Estimates gas and fees for cancelling the escrow in `metadata`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the call would revert (e.g., before
+    /// `cancel_after`) or fee estimation fails.
+    pub async fn estimate_cancel(&self, metadata: &EscrowMetadata) -> Result<GasEstimate> {
+        let id = metadata
+            .escrow_id
+            .ok_or_else(|| ClientError::ethereum(CANCEL_ESCROW, "missing escrow_id"))?;
+        let call = self.escrow_as_sender.cancelEscrow(U256::from(id));
+        let gas_limit = call
+            .estimate_gas()
+            .await
+            .map_err(|e| ClientError::ethereum(CANCEL_ESCROW, e))?;
+        self.gas_estimate(gas_limit, call.calldata().clone(), CANCEL_ESCROW)
+            .await
+    }
+
+    /// Combines a gas limit with current fees and, on L2 presets, the L1
+    /// data fee for `calldata`.
+    async fn gas_estimate(
+        &self,
+        gas_limit: u64,
+        calldata: Bytes,
+        operation: &'static str,
+    ) -> Result<GasEstimate> {
+        let fees = self.estimate_fees(operation).await?;
+        let l1_data_fee = match self.network.and_then(|network| network.rollup()) {
+            Some(rollup) => Some(
+                l2::l1_data_fee(
+                    &self.provider,
+                    rollup,
+                    *self.escrow_as_sender.address(),
+                    calldata,
+                    operation,
+                )
+                .await?,
+            ),
+            None => None,
+        };
+
+        Ok(GasEstimate {
+            gas_limit,
+            max_fee_per_gas: fees.max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+            l1_data_fee,
+        })
+    }
+
+    /// Subscribes to `EscrowCreated`, `EscrowFinished`, and `EscrowCancelled`
+    /// events via `eth_subscribe`.
+    ///
+    /// Requires `pubsub_url` in the chain configuration. The stream ends when
+    /// the subscription is dropped by the node; logs that fail to decode are
+    /// yielded as errors rather than ending the stream.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no pubsub endpoint is configured or the
+    /// subscription request fails.
+    pub async fn subscribe(&self) -> Result<impl Stream<Item = Result<EscrowEvent>> + Send> {
+        let pubsub = self
+            .pubsub
+            .as_ref()
+            .ok_or_else(|| ClientError::ethereum(SUBSCRIBE, "pubsub_url not configured"))?;
+
+        let filter = Filter::new()
+            .address(*self.escrow_as_sender.address())
+            .event_signature(vec![
+                Escrow::EscrowCreated::SIGNATURE_HASH,
+                Escrow::EscrowFinished::SIGNATURE_HASH,
+                Escrow::EscrowCancelled::SIGNATURE_HASH,
+            ]);
+
+        let subscription = pubsub
+            .subscribe_logs(&filter)
+            .await
+            .map_err(|e| ClientError::ethereum(SUBSCRIBE, e))?;
+        info!("Subscribed to escrow events");
+
+        Ok(subscription.into_stream().map(|log| {
+            EscrowEvent::decode(&log).inspect_err(|e| warn!(%e, "Failed to decode escrow log"))
+        }))
+    }
+
+    /// Returns an indexer for this agent's escrow contract, starting at
+    /// `from_block` and honoring the configured confirmation depth.
+    pub fn indexer(&self, from_block: u64) -> EscrowIndexer {
+        EscrowIndexer::new(
+            self.provider.clone(),
+            *self.escrow_as_sender.address(),
+            from_block,
+        )
+        .with_confirmations(self.confirmations)
+    }
+
+    /// Builds an HTTP provider over `transport` that fills nonce, gas, and
+    /// chain ID and signs with `signer`.
+    fn connect(transport: &FailoverTransport, signer: EthereumSigner) -> DynProvider {
+        ProviderBuilder::new()
+            .wallet(EthereumWallet::from(signer))
+            .connect_client(transport.clone().into_client())
+            .erased()
+    }
+
+    /// Resolves the fees for the next transaction and checks them against
+    /// the configured cap.
+    async fn resolve_fees(&self, operation: &'static str) -> Result<Fees> {
+        let fees = self.estimate_fees(operation).await?;
+        self.check_gas_price_cap(fees.max_fee_per_gas)?;
+        Ok(fees)
+    }
+
+    /// Returns an error if `max_fee_per_gas` exceeds the configured cap.
+    fn check_gas_price_cap(&self, max_fee_per_gas: u128) -> Result<()> {
+        match self.fees.gas_price_cap {
+            Some(cap) if max_fee_per_gas > cap => Err(ClientError::GasPriceCapExceeded {
+                max_fee_per_gas,
+                cap,
+            }),
+            _ => Ok(()),
         }
+    }
+
+    /// Estimates the fees for the next transaction.
+    ///
+    /// Configured overrides take precedence; any missing value is estimated.
+    /// The priority fee is clamped to the max fee.
+    async fn estimate_fees(&self, operation: &'static str) -> Result<Fees> {
+        let FeeConfig {
+            max_fee_per_gas,
+            max_priority_fee_per_gas,
+            ..
+        } = self.fees;
 
-        Ok(escrow_id.as_u64())
+        let (max_fee_per_gas, max_priority_fee_per_gas) =
+            match (max_fee_per_gas, max_priority_fee_per_gas) {
+                (Some(max_fee), Some(priority_fee)) => (max_fee, priority_fee),
+                (max_fee, priority_fee) => {
+                    let estimate = self
+                        .provider
+                        .estimate_eip1559_fees()
+                        .await
+                        .map_err(|e| ClientError::ethereum(operation, e))?;
+                    (
+                        max_fee.unwrap_or(estimate.max_fee_per_gas),
+                        priority_fee.unwrap_or(estimate.max_priority_fee_per_gas),
+                    )
+                }
+            };
+
+        let fees = Fees {
+            max_fee_per_gas,
+            max_priority_fee_per_gas: max_priority_fee_per_gas.min(max_fee_per_gas),
+        };
+        debug!(?fees, "Resolved EIP-1559 fees");
+        Ok(fees)
     }
 
-    /// Returns the recipient contract instance, or an error if not configured.
-    fn recipient_contract(
+    /// Warns about `finish_after`/`cancel_after` values that look like they
+    /// were set in the wrong block units for the configured network.
+    ///
+    /// Timelocks are compared against `block.number` as the contract sees
+    /// it: L1 blocks on Ethereum and Arbitrum, L2 blocks on OP Stack chains.
+    async fn check_timelocks(&self, params: &EscrowParams) -> Result<()> {
+        let Some(network) = self.network else {
+            return Ok(());
+        };
+        let height = l2::timelock_height(&self.provider, network, CREATE_ESCROW).await?;
+        let horizon = height.saturating_add(network.timelock_blocks_for(MAX_TIMELOCK_SECS));
+
+        let timelocks = [
+            ("finish_after", params.finish_after),
+            ("cancel_after", params.cancel_after),
+        ];
+        for (name, block) in timelocks {
+            match block {
+                Some(block) if block != 0 && block <= height => warn!(
+                    network = network.as_ref(),
+                    %height,
+                    "{name} ({block}) has already passed"
+                ),
+                Some(block) if block > horizon => warn!(
+                    network = network.as_ref(),
+                    %height,
+                    "{name} ({block}) is over a year away; timelocks on {} count ~{}s blocks",
+                    network.as_ref(),
+                    network.timelock_block_time_secs()
+                ),
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+
+    /// Sends `tx` through `provider` and waits for it to become final.
+    ///
+    /// If a reorg drops the transaction from both the chain and the mempool,
+    /// it is resent (with a fresh nonce) up to [`MAX_REORG_RETRIES`] times.
+    async fn send_and_confirm(
+        &self,
+        provider: &DynProvider,
+        from: Address,
+        tx: TransactionRequest,
+        operation: &'static str,
+    ) -> Result<TransactionReceipt> {
+        let deadline = self.deadline();
+        for attempt in 0..=MAX_REORG_RETRIES {
+            let tx_hash = self
+                .send_until_mined(provider, from, tx.clone(), operation, deadline)
+                .await?;
+            debug!(%tx_hash, attempt, "Sent {} transaction", operation);
+
+            if let Some(receipt) = self
+                .await_final(provider, tx_hash, operation, deadline)
+                .await?
+            {
+                return Ok(receipt);
+            }
+            warn!(%tx_hash, "{} transaction dropped by a reorg; resending", operation);
+        }
+        Err(ClientError::tx_dropped(format!(
+            "{operation} dropped by reorgs {} times",
+            MAX_REORG_RETRIES + 1
+        )))
+    }
+
+    /// Sends `tx` and returns the hash of the copy to await.
+    ///
+    /// With a [`FeeBump`] policy, waits until a copy is mined: a transaction
+    /// still unmined after the timeout is replaced by a copy at the same
+    /// nonce with raised fees, until the bump limit or the gas price cap is
+    /// reached, or until `deadline`.
+    async fn send_until_mined(
+        &self,
+        provider: &DynProvider,
+        from: Address,
+        mut tx: TransactionRequest,
+        operation: &'static str,
+        deadline: Option<Instant>,
+    ) -> Result<B256> {
+        let err = |e| ClientError::ethereum(operation, e);
+
+        let Some(bump) = self.fees.bump else {
+            let pending = provider.send_transaction(tx).await.map_err(err)?;
+            return Ok(*pending.tx_hash());
+        };
+
+        let nonce = provider
+            .get_transaction_count(from)
+            .pending()
+            .await
+            .map_err(err)?;
+        tx.from = Some(from);
+        tx.nonce = Some(nonce);
+
+        let mut sent = vec![*provider
+            .send_transaction(tx.clone())
+            .await
+            .map_err(err)?
+            .tx_hash()];
+        let poll_interval = self.wait.poll_interval.unwrap_or(RECEIPT_POLL_INTERVAL);
+        let mut bumps = 0;
+        loop {
+            let bump_at = (bumps < bump.max_bumps).then(|| Instant::now() + bump.timeout);
+            loop {
+                for tx_hash in &sent {
+                    if provider
+                        .get_transaction_receipt(*tx_hash)
+                        .await
+                        .map_err(err)?
+                        .is_some()
+                    {
+                        return Ok(*tx_hash);
+                    }
+                }
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    let latest = sent.last().copied().unwrap_or_default();
+                    return Err(pending(operation, latest));
+                }
+                if bump_at.is_some_and(|bump_at| Instant::now() >= bump_at) {
+                    break;
+                }
+                tokio::time::sleep(poll_interval).await;
+            }
+
+            bumps += 1;
+            let max_fee_per_gas = bump.apply(tx.max_fee_per_gas.unwrap_or_default());
+            if let Err(e) = self.check_gas_price_cap(max_fee_per_gas) {
+                warn!(%e, "Not bumping {} transaction further", operation);
+                bumps = bump.max_bumps;
+                continue;
+            }
+            tx.max_fee_per_gas = Some(max_fee_per_gas);
+            tx.max_priority_fee_per_gas = tx
+                .max_priority_fee_per_gas
+                .map(|fee| bump.apply(fee).min(max_fee_per_gas));
+
+            // A failed replacement usually means an earlier copy was just
+            // mined; the next poll picks it up.
+            match provider.send_transaction(tx.clone()).await {
+                Ok(pending) => {
+                    let tx_hash = *pending.tx_hash();
+                    warn!(%tx_hash, %nonce, max_fee_per_gas, bumps, "Replaced stuck {} transaction", operation);
+                    sent.push(tx_hash);
+                }
+                Err(e) => warn!(%e, "Failed to replace {} transaction", operation),
+            }
+        }
+    }
+
+    /// Waits for `tx_hash` to reach the configured confirmation depth and
+    /// checks its block is still canonical.
+    ///
+    /// A transaction reorged out but still in the mempool is awaited again.
+    /// Returns `None` once it has been dropped entirely, and
+    /// [`ClientError::Pending`] if it is not final by `deadline`.
+    async fn await_final(
         &self,
-    ) -> Result<&Contract<SignerMiddleware<Provider<Http>, LocalWallet>>> {
-        self.escrow_as_recipient
+        provider: &DynProvider,
+        tx_hash: B256,
+        operation: &'static str,
+        deadline: Option<Instant>,
+    ) -> Result<Option<TransactionReceipt>> {
+        let err = |e| ClientError::ethereum(operation, e);
+
+        for _ in 0..=MAX_REORG_RETRIES {
+            let timeout =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            let receipt = PendingTransactionBuilder::new(provider.root().clone(), tx_hash)
+                .with_required_confirmations(self.confirmations)
+                .with_timeout(timeout)
+                .get_receipt()
+                .await
+                .map_err(|e| match e {
+                    PendingTransactionError::TxWatcher(WatchTxError::Timeout) => {
+                        pending(operation, tx_hash)
+                    }
+                    e => ClientError::ethereum(operation, e),
+                })?;
+
+            let canonical = match receipt.block_number {
+                Some(number) => provider
+                    .get_block_by_number(number.into())
+                    .await
+                    .map_err(err)?
+                    .is_some_and(|block| Some(block.header.hash) == receipt.block_hash),
+                None => false,
+            };
+            if canonical {
+                debug!(%tx_hash, confirmations = self.confirmations, "Transaction final");
+                return Ok(Some(receipt));
+            }
+
+            warn!(%tx_hash, block_hash = ?receipt.block_hash, "Reorg detected");
+            if provider
+                .get_transaction_by_hash(tx_hash)
+                .await
+                .map_err(err)?
+                .is_none()
+            {
+                return Ok(None);
+            }
+        }
+        Err(ClientError::tx_dropped(format!(
+            "{operation} transaction {tx_hash} kept being reorged out"
+        )))
+    }
+
+    /// Logs the L1 data fee a rollup will charge on top of execution gas.
+    ///
+    /// Estimation failures are logged and otherwise ignored.
+    async fn log_l1_data_fee(&self, to: Address, calldata: Bytes, operation: &'static str) {
+        let Some(rollup) = self.network.and_then(|network| network.rollup()) else {
+            return;
+        };
+        match l2::l1_data_fee(&self.provider, rollup, to, calldata, operation).await {
+            Ok(fee) => {
+                info!(?rollup, l1_data_fee = %fee, "Estimated L1 data fee for {}", operation)
+            }
+            Err(e) => warn!(?rollup, %e, "Failed to estimate L1 data fee"),
+        }
+    }
+
+    /// Builds the `createEscrow` call for `params`, or `createConditionalEscrow`
+    /// when the escrow has conditions, fingerprinting the escrow by `recorded`,
+    /// the parameters its metadata records. Returns the call and its value.
+    async fn create_call(
+        &self,
+        params: &EscrowParams,
+        recorded: &EscrowParams,
+    ) -> Result<(CallBuilder<&DynProvider, ()>, U256)> {
+        let recipient = Address::from_str(&params.recipient.to_string())?;
+        let fingerprints =
+            create_fingerprints(&self.escrow_as_sender, recorded, self.condition).await?;
+        let (calldata, amount) = create_calldata(recipient, params, fingerprints)?;
+        let call = CallBuilder::new_raw(self.escrow_as_sender.provider(), calldata)
+            .to(*self.escrow_as_sender.address())
+            .value(amount);
+        Ok((call, amount))
+    }
+
+    /// Builds the `finishEscrow` call for escrow `id`, or
+    /// `finishEscrowWithProof` when the escrow has conditions, after checking
+    /// that the proof seal is bound to that escrow.
+    fn finish_call<'a>(
+        &self,
+        contract: &'a Escrow::EscrowInstance<DynProvider>,
+        metadata: &EscrowMetadata,
+        id: u64,
+    ) -> Result<CallBuilder<&'a DynProvider, ()>> {
+        let calldata = finish_calldata(metadata, id, self.proof_seal.as_ref())?;
+        Ok(CallBuilder::new_raw(contract.provider(), calldata).to(*contract.address()))
+    }
+
+    /// Sends a sender-only call to the escrow contract, through the smart
+    /// account or Safe when one is configured, or else sponsored by the
+    /// paymaster if its policy covers the sender.
+    ///
+    /// Returns `false` if the call was only proposed to the Safe.
+    async fn send_as_sender(&self, calldata: Bytes, operation: &'static str) -> Result<bool> {
+        let fees = self.resolve_fees(operation).await?;
+
+        if let Some(account) = &self.sender_account {
+            self.submit_user_op(account, &self.sender, U256::ZERO, calldata, fees, operation)
+                .await?;
+        } else if let Some(safe) = &self.safe {
+            let submission = safe
+                .submit(
+                    &self.sender,
+                    *self.escrow_as_sender.address(),
+                    U256::ZERO,
+                    calldata,
+                    fees,
+                    operation,
+                )
+                .await?;
+            if let SafeSubmission::Proposed { safe_tx_hash, .. } = submission {
+                info!(%safe_tx_hash, "{} awaiting Safe confirmations", operation);
+                return Ok(false);
+            }
+        } else if let Some(paymaster) = self.sender_paymaster() {
+            self.submit_sponsored(
+                paymaster,
+                &self.sender,
+                U256::ZERO,
+                calldata,
+                fees,
+                operation,
+            )
+            .await?;
+        } else {
+            let contract = self.cancel_contract();
+            let tx = CallBuilder::new_raw(contract.provider(), calldata)
+                .to(*contract.address())
+                .max_fee_per_gas(fees.max_fee_per_gas)
+                .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+                .into_transaction_request();
+            self.send_and_confirm(contract.provider(), self.sender.address(), tx, operation)
+                .await?;
+        }
+        Ok(true)
+    }
+
+    /// Sends a recipient-only call to the escrow contract, through the
+    /// recipient's smart account when one is configured, or else sponsored
+    /// by the paymaster if one is configured.
+    async fn send_as_recipient(&self, calldata: Bytes, operation: &'static str) -> Result<()> {
+        let contract = self.recipient_contract()?;
+        let fees = self.resolve_fees(operation).await?;
+        let recipient = || {
+            self.recipient
+                .as_ref()
+                .ok_or_else(|| ClientError::ethereum(operation, "recipient wallet not configured"))
+        };
+
+        match (&self.recipient_account, &self.paymaster) {
+            (Some(account), _) => {
+                self.submit_user_op(account, recipient()?, U256::ZERO, calldata, fees, operation)
+                    .await?;
+            }
+            (None, Some(paymaster)) => {
+                self.submit_sponsored(
+                    paymaster,
+                    recipient()?,
+                    U256::ZERO,
+                    calldata,
+                    fees,
+                    operation,
+                )
+                .await?;
+            }
+            (None, None) => {
+                let tx = CallBuilder::new_raw(contract.provider(), calldata)
+                    .to(*contract.address())
+                    .max_fee_per_gas(fees.max_fee_per_gas)
+                    .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+                    .into_transaction_request();
+                self.send_and_confirm(
+                    contract.provider(),
+                    self.recipient_address()?,
+                    tx,
+                    operation,
+                )
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Submits a call from a smart account as a UserOperation signed by
+    /// `owner`, then waits for the bundle transaction to be final.
+    async fn submit_user_op(
+        &self,
+        account: &SmartAccountSender,
+        owner: &EthereumSigner,
+        value: U256,
+        calldata: Bytes,
+        fees: Fees,
+        operation: &'static str,
+    ) -> Result<TransactionReceipt> {
+        let receipt = account
+            .submit(
+                owner,
+                *self.escrow_as_sender.address(),
+                value,
+                calldata,
+                fees,
+                operation,
+            )
+            .await?;
+        self.await_final(
+            &self.provider,
+            receipt.transaction_hash,
+            operation,
+            self.deadline(),
+        )
+        .await?
+        .ok_or_else(|| {
+            ClientError::tx_dropped(format!(
+                "{operation} bundle transaction {} was reorged out",
+                receipt.transaction_hash
+            ))
+        })
+    }
+
+    /// Returns the account acting as escrow sender: the smart account or
+    /// Safe if configured, else the sender key.
+    fn sender_party(&self) -> Address {
+        match (&self.sender_account, &self.safe) {
+            (Some(account), _) => account.account(),
+            (None, Some(safe)) => safe.address(),
+            (None, None) => self.sender.address(),
+        }
+    }
+
+    /// Returns the paymaster sponsoring the sender's calls, if any.
+    fn sender_paymaster(&self) -> Option<&PaymasterSender> {
+        self.paymaster
+            .as_ref()
+            .filter(|paymaster| paymaster.sponsors_sender())
+    }
+
+    /// Returns the account acting as escrow recipient, if configured.
+    fn recipient_party(&self) -> Option<Address> {
+        match &self.recipient_account {
+            Some(account) => Some(account.account()),
+            None => self.recipient.as_ref().map(Signer::address),
+        }
+    }
+
+    /// Fails if the proof seal has expired, or attests a block too old for
+    /// the contract to look up, rather than letting the contract revert.
+    async fn check_proof_fresh(&self) -> Result<()> {
+        let Some((freshness, rest)) = self.proof_seal.as_deref().and_then(Freshness::split_prefix)
+        else {
+            return Ok(());
+        };
+        let height = self.current_height(FINISH_ESCROW).await?;
+        if freshness.is_expired(height) {
+            return Err(ClientError::ProofExpired {
+                expires_at: freshness.expires_at,
+                height,
+            });
+        }
+        if let Some((state, _)) = ChainState::split_prefix(rest).filter(|(s, _)| !s.is_none()) {
+            let latest = self
+                .provider
+                .get_block_number()
+                .await
+                .map_err(|e| ClientError::ethereum(FINISH_ESCROW, e))?;
+            // The finish transaction lands in a later block than `latest`.
+            if latest.saturating_sub(state.height) >= crate::chain_state::ETHEREUM_BLOCKHASH_WINDOW
+            {
+                return Err(ClientError::ethereum(
+                    FINISH_ESCROW,
+                    format!(
+                        "proof attests block {}, too old for the contract at block {latest}; \
+                         re-run prove",
+                        state.height
+                    ),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the current height in the units timelocks are measured in.
+    async fn current_height(&self, operation: &'static str) -> Result<u64> {
+        match self.network {
+            Some(network) => l2::timelock_height(&self.provider, network, operation).await,
+            None => self
+                .provider
+                .get_block_number()
+                .await
+                .map_err(|e| ClientError::ethereum(operation, e)),
+        }
+    }
+
+    /// Returns the CAIP-2 ID of the connected chain, `eip155:<chain ID>`.
+    async fn caip2_id(&self) -> Result<String> {
+        let chain_id = self
+            .provider
+            .get_chain_id()
+            .await
+            .map_err(|e| ClientError::ethereum(OBSERVE, e))?;
+        Ok(format!("eip155:{chain_id}"))
+    }
+
+    /// Reads the number and hash of block `number`, if it exists.
+    async fn block(&self, number: BlockNumberOrTag) -> Result<Option<ObservedBlock>> {
+        let block = self
+            .provider
+            .get_block_by_number(number)
+            .await
+            .map_err(|e| ClientError::ethereum(GET_BLOCK, e))?;
+        Ok(block.map(|block| ObservedBlock {
+            height: block.header.inner.number,
+            hash: block.header.hash.0,
+        }))
+    }
+
+    /// Reads escrow `id` from the contract.
+    async fn escrow_status(
+        &self,
+        id: u64,
+        current_height: u64,
+        operation: &'static str,
+    ) -> Result<EscrowStatus> {
+        let escrow = self
+            .escrow_as_sender
+            .getEscrow(U256::from(id))
+            .call()
+            .await
+            .map_err(|e| ClientError::ethereum(operation, e))?;
+        // Zero means the timelock is unset.
+        let timelock = |block: U256| {
+            (!block.is_zero())
+                .then(|| to_height(block, operation))
+                .transpose()
+        };
+
+        Ok(EscrowStatus {
+            settled: escrow.settled,
+            amount: u128::try_from(escrow.amount)
+                .map(BigNumber::from)
+                .map_err(|_| ClientError::AssetOverflow)?,
+            finish_after: timelock(escrow.finishAfter)?,
+            cancel_after: timelock(escrow.cancelAfter)?,
+            current_height,
+            has_conditions: escrow.hasConditions,
+        })
+    }
+
+    /// Returns the recipient contract instance, or an error if not configured.
+    ///
+    /// Prefers the private relay when one is configured.
+    fn recipient_contract(&self) -> Result<&Escrow::EscrowInstance<DynProvider>> {
+        match &self.relay {
+            Some(relay) => relay.as_recipient.as_ref(),
+            None => self.escrow_as_recipient.as_ref(),
+        }
+        .ok_or_else(|| ClientError::ethereum(FINISH_ESCROW, "recipient wallet not configured"))
+    }
+
+    /// Returns the recipient's address, or an error if not configured.
+    fn recipient_address(&self) -> Result<Address> {
+        self.recipient
             .as_ref()
+            .map(Signer::address)
             .ok_or_else(|| ClientError::ethereum(FINISH_ESCROW, "recipient wallet not configured"))
     }
-}
 
-#[async_trait::async_trait]
-impl Agent for EthereumAgent {
-    async fn create_escrow(&self, params: &EscrowParams) -> Result<EscrowMetadata> {
-        let recipient = Address::from_str(&params.recipient.to_string())?;
-        let finish_after = params.finish_after.unwrap_or_default();
-        let cancel_after = params.cancel_after.unwrap_or_default();
-        let amount = U256::from_dec_str(&params.asset.amount().to_string())
-            .map_err(|_| ClientError::AssetOverflow)?;
+    /// Returns the sender contract instance for `cancelEscrow` and batched
+    /// cancellations, preferring the private relay when one is configured.
+    fn cancel_contract(&self) -> &Escrow::EscrowInstance<DynProvider> {
+        self.relay
+            .as_ref()
+            .map_or(&self.escrow_as_sender, |relay| &relay.as_sender)
+    }
+
+    /// Creates an escrow from `params`, fingerprinting it as `recorded`, the
+    /// parameters its metadata is recorded with, if it has conditions.
+    pub(crate) async fn create_escrow_as(
+        &self,
+        params: &EscrowParams,
+        recorded: &EscrowParams,
+    ) -> Result<EscrowMetadata> {
+        let (call, amount) = self.create_call(params, recorded).await?;
 
         info!(
             "Sending {} transaction with amount {}",
             CREATE_ESCROW, amount
         );
 
-        let call = self
-            .escrow_as_sender
-            .method::<_, H256>(CREATE_ESCROW, (recipient, finish_after, cancel_after))
-            .map_err(|e| ClientError::ethereum(CREATE_ESCROW, e))?
-            .value(amount);
+        self.check_timelocks(params).await?;
 
-        let pending_tx = call
-            .send()
-            .await
-            .map_err(|e| ClientError::ethereum(CREATE_ESCROW, e))?;
+        let fees = self.resolve_fees(CREATE_ESCROW).await?;
+        self.log_l1_data_fee(
+            *self.escrow_as_sender.address(),
+            call.calldata().clone(),
+            CREATE_ESCROW,
+        )
+        .await;
 
-        let receipt = pending_tx
-            .await
-            .map_err(|e| ClientError::ethereum(CREATE_ESCROW, e))?
-            .ok_or_else(|| ClientError::tx_dropped("createEscrow transaction not confirmed"))?;
+        let logs = match (&self.sender_account, &self.safe, self.sender_paymaster()) {
+            (Some(account), _, _) => receipt_logs(
+                self.submit_user_op(
+                    account,
+                    &self.sender,
+                    amount,
+                    call.calldata().clone(),
+                    fees,
+                    CREATE_ESCROW,
+                )
+                .await?,
+            ),
+            (None, Some(safe), _) => {
+                let submission = safe
+                    .submit(
+                        &self.sender,
+                        *self.escrow_as_sender.address(),
+                        amount,
+                        call.calldata().clone(),
+                        fees,
+                        CREATE_ESCROW,
+                    )
+                    .await?;
+                match submission {
+                    SafeSubmission::Executed(receipt) => receipt_logs(*receipt),
+                    SafeSubmission::Proposed { safe_tx_hash, .. } => {
+                        info!(%safe_tx_hash, "{} awaiting Safe confirmations", CREATE_ESCROW);
+                        return Ok(EscrowMetadata {
+                            params: params.clone(),
+                            state: ExecutionState::Initialized,
+                            escrow_id: None,
+                            uid: None,
+                            chain_metadata: None,
+                            guest: None,
+                            observed: None,
+                        });
+                    }
+                }
+            }
+            (None, None, Some(paymaster)) => {
+                self.submit_sponsored(
+                    paymaster,
+                    &self.sender,
+                    amount,
+                    call.calldata().clone(),
+                    fees,
+                    CREATE_ESCROW,
+                )
+                .await?
+            }
+            (None, None, None) => {
+                let tx = call
+                    .max_fee_per_gas(fees.max_fee_per_gas)
+                    .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+                    .into_transaction_request();
+                receipt_logs(
+                    self.send_and_confirm(&self.provider, self.sender.address(), tx, CREATE_ESCROW)
+                        .await?,
+                )
+            }
+        };
 
-        info!(tx_hash = ?receipt.transaction_hash, "Transaction mined");
+        // Escrow IDs are assigned in inclusion order, so read the ID from the
+        // final receipt rather than one that may have been reorged out.
 
-        let block_hash = receipt
-            .block_hash
-            .ok_or_else(|| ClientError::MissingEvent("no block hash in receipt".into()))?;
+        // Bundles may carry other parties' escrows, so match on the sender.
+        let party = self.sender_party();
+        let event = logs
+            .iter()
+            .filter(|log| log.address() == *self.escrow_as_sender.address())
+            .filter_map(|log| log.log_decode::<Escrow::EscrowCreated>().ok())
+            .find(|log| log.inner.data.sender == party)
+            .ok_or_else(|| ClientError::MissingEvent("EscrowCreated event not found".into()))?;
 
-        let escrow_id = self.extract_escrow_id(block_hash).await?;
-        info!("{} confirmed for escrow ID {}", CREATE_ESCROW, escrow_id);
+        let escrow_id = event.inner.data.escrowId;
+        if escrow_id.is_zero() {
+            return Err(ClientError::MissingEvent("escrow_id is zero".into()));
+        }
+        let escrow_id = to_escrow_id(escrow_id, CREATE_ESCROW)?;
+        let uid = EscrowId(event.inner.data.uid.0);
+        info!(%uid, "{} confirmed for escrow ID {}", CREATE_ESCROW, escrow_id);
 
         Ok(EscrowMetadata {
             params: params.clone(),
             state: ExecutionState::Funded,
             escrow_id: Some(escrow_id),
+            uid: Some(uid),
+            chain_metadata: None,
+            guest: None,
+            observed: None,
         })
     }
+}
+
+#[async_trait::async_trait]
+impl Agent for EthereumAgent {
+    async fn create_escrow(&self, params: &EscrowParams) -> Result<EscrowMetadata> {
+        self.create_escrow_as(params, params).await
+    }
 
     async fn finish_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
         let id = metadata
             .escrow_id
             .ok_or_else(|| ClientError::ethereum(FINISH_ESCROW, "missing escrow_id"))?;
+        if metadata.params.has_conditions {
+            self.check_proof_fresh().await?;
+        }
 
-        let contract = self.recipient_contract()?;
+        let calldata = self
+            .finish_call(self.recipient_contract()?, metadata, id)?
+            .calldata()
+            .clone();
 
         info!("Sending {} transaction for escrow ID {}", FINISH_ESCROW, id);
 
-        contract
-            .method::<_, ()>(FINISH_ESCROW, U256::from(id))
-            .map_err(|e| ClientError::ethereum(FINISH_ESCROW, e))?
-            .send()
-            .await
-            .map_err(|e| ClientError::ethereum(FINISH_ESCROW, e))?
-            .await
-            .map_err(|e| ClientError::ethereum(FINISH_ESCROW, e))?;
+        self.send_as_recipient(calldata, FINISH_ESCROW).await?;
 
         info!("{} confirmed for escrow ID {}", FINISH_ESCROW, id);
         Ok(())
@@ -250,16 +1634,590 @@ impl Agent for EthereumAgent {
 
         info!("Sending {} transaction for escrow ID {}", CANCEL_ESCROW, id);
 
-        self.escrow_as_sender
-            .method::<_, ()>(CANCEL_ESCROW, U256::from(id))
-            .map_err(|e| ClientError::ethereum(CANCEL_ESCROW, e))?
-            .send()
+        let calldata = Escrow::cancelEscrowCall {
+            escrowId: U256::from(id),
+        }
+        .abi_encode();
+        if self.send_as_sender(calldata.into(), CANCEL_ESCROW).await? {
+            info!("{} confirmed for escrow ID {}", CANCEL_ESCROW, id);
+        }
+        Ok(())
+    }
+
+    /// Simulates with `eth_call` and `eth_estimateGas` from the sender or
+    /// recipient key directly, even when the party is a Safe or smart
+    /// account.
+    async fn estimate(&self, params: &EscrowParams) -> Result<LifecycleEstimate> {
+        let wei = |estimate: GasEstimate| {
+            u128::try_from(estimate.max_cost())
+                .map(BigNumber::from)
+                .map_err(|_| ClientError::AssetOverflow)
+        };
+        let create = self.estimate_create(params).await?;
+
+        // The escrow does not exist yet, so settlement is priced from
+        // reference gas at current fees.
+        let escrow_id = U256::ZERO;
+        let (finish_gas, finish_calldata) = if params.has_conditions {
+            let call = Escrow::finishEscrowWithProofCall {
+                escrowId: escrow_id,
+                seal: vec![0; SEAL_LEN].into(),
+            };
+            (FINISH_GAS + PROOF_VERIFICATION_GAS, call.abi_encode())
+        } else {
+            let call = Escrow::finishEscrowCall {
+                escrowId: escrow_id,
+            };
+            (FINISH_GAS, call.abi_encode())
+        };
+        let cancel_calldata = Escrow::cancelEscrowCall {
+            escrowId: escrow_id,
+        }
+        .abi_encode();
+        let finish = self
+            .gas_estimate(finish_gas, finish_calldata.into(), FINISH_ESCROW)
+            .await?;
+        let cancel = self
+            .gas_estimate(CANCEL_GAS, cancel_calldata.into(), CANCEL_ESCROW)
+            .await?;
+
+        Ok(LifecycleEstimate {
+            create: Cost {
+                operation: CREATE_ESCROW,
+                units: Some(create.gas_limit),
+                fee: Some(wei(create)?),
+                deposit: None,
+                simulated: true,
+            },
+            finish: Cost {
+                operation: FINISH_ESCROW,
+                units: Some(finish_gas),
+                fee: Some(wei(finish)?),
+                deposit: None,
+                simulated: false,
+            },
+            cancel: Cost {
+                operation: CANCEL_ESCROW,
+                units: Some(CANCEL_GAS),
+                fee: Some(wei(cancel)?),
+                deposit: None,
+                simulated: false,
+            },
+        })
+    }
+
+    async fn simulate(&self, operation: Operation<'_>) -> Result<Simulation> {
+        let escrow_id = |metadata: &EscrowMetadata, name| {
+            metadata
+                .escrow_id
+                .ok_or_else(|| ClientError::ethereum(name, "missing escrow_id"))
+        };
+        let (call, name) = match operation {
+            Operation::Create(params) => (self.create_call(params, params).await?.0, CREATE_ESCROW),
+            Operation::Finish(metadata) => {
+                let contract = self.escrow_as_recipient.as_ref().ok_or_else(|| {
+                    ClientError::ethereum(FINISH_ESCROW, "recipient wallet not configured")
+                })?;
+                let id = escrow_id(metadata, FINISH_ESCROW)?;
+                (self.finish_call(contract, metadata, id)?, FINISH_ESCROW)
+            }
+            Operation::Cancel(metadata) => {
+                let calldata = Escrow::cancelEscrowCall {
+                    escrowId: U256::from(escrow_id(metadata, CANCEL_ESCROW)?),
+                }
+                .abi_encode();
+                let call = CallBuilder::new_raw(self.escrow_as_sender.provider(), calldata.into())
+                    .to(*self.escrow_as_sender.address());
+                (call, CANCEL_ESCROW)
+            }
+        };
+
+        if let Err(e) = call.call_raw().await {
+            return Ok(Simulation {
+                operation: name,
+                error: Some(revert_reason(&e)),
+                logs: Vec::new(),
+                units: None,
+                fee: None,
+            });
+        }
+        let gas_limit = call
+            .estimate_gas()
+            .await
+            .map_err(|e| ClientError::ethereum(name, e))?;
+        let estimate = self
+            .gas_estimate(gas_limit, call.calldata().clone(), name)
+            .await?;
+
+        Ok(Simulation {
+            operation: name,
+            error: None,
+            logs: Vec::new(),
+            units: Some(gas_limit),
+            fee: Some(
+                u128::try_from(estimate.max_cost())
+                    .map(BigNumber::from)
+                    .map_err(|_| ClientError::AssetOverflow)?,
+            ),
+        })
+    }
+
+    async fn build_transaction(&self, operation: Operation<'_>) -> Result<OfflineTransaction> {
+        if self.safe.is_some() || self.sender_account.is_some() || self.recipient_account.is_some()
+        {
+            return Err(ClientError::ethereum(
+                BUILD_TRANSACTION,
+                "offline transactions are signed by a key, not a Safe or smart account",
+            ));
+        }
+        let escrow_id = |metadata: &EscrowMetadata, name| {
+            metadata
+                .escrow_id
+                .ok_or_else(|| ClientError::ethereum(name, "missing escrow_id"))
+        };
+        // The transaction is signed elsewhere, so only the parties'
+        // addresses are needed here.
+        let (from, calldata, value, name) = match operation {
+            Operation::Create(params) => {
+                self.check_timelocks(params).await?;
+                let (call, amount) = self.create_call(params, params).await?;
+                (
+                    Address::from_str(&params.sender.to_string())?,
+                    call.calldata().clone(),
+                    amount,
+                    CREATE_ESCROW,
+                )
+            }
+            Operation::Finish(metadata) => {
+                let id = escrow_id(metadata, FINISH_ESCROW)?;
+                let call = self.finish_call(&self.escrow_as_sender, metadata, id)?;
+                (
+                    Address::from_str(&metadata.params.recipient.to_string())?,
+                    call.calldata().clone(),
+                    U256::ZERO,
+                    FINISH_ESCROW,
+                )
+            }
+            Operation::Cancel(metadata) => {
+                let calldata = Escrow::cancelEscrowCall {
+                    escrowId: U256::from(escrow_id(metadata, CANCEL_ESCROW)?),
+                }
+                .abi_encode();
+                (
+                    Address::from_str(&metadata.params.sender.to_string())?,
+                    calldata.into(),
+                    U256::ZERO,
+                    CANCEL_ESCROW,
+                )
+            }
+        };
+
+        let err = |e| ClientError::ethereum(name, e);
+        let to = *self.escrow_as_sender.address();
+        let request = TransactionRequest::default()
+            .from(from)
+            .to(to)
+            .value(value)
+            .input(calldata.clone().into());
+        let gas_limit = self.provider.estimate_gas(request).await.map_err(err)?;
+        let nonce = self
+            .provider
+            .get_transaction_count(from)
+            .pending()
             .await
-            .map_err(|e| ClientError::ethereum(CANCEL_ESCROW, e))?
+            .map_err(err)?;
+        let chain_id = self.provider.get_chain_id().await.map_err(err)?;
+        let fees = self.resolve_fees(name).await?;
+
+        let tx = TxEip1559 {
+            chain_id,
+            nonce,
+            gas_limit,
+            max_fee_per_gas: fees.max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+            to: TxKind::Call(to),
+            value,
+            access_list: Default::default(),
+            input: calldata,
+        };
+        debug!(?tx, "Built {} transaction", name);
+        Ok(OfflineTransaction::ethereum(&tx, from, name))
+    }
+
+    async fn broadcast_transaction(&self, tx: &OfflineTransaction) -> Result<Broadcast> {
+        if !tx.is_signed() {
+            return Err(ClientError::ethereum(
+                BROADCAST_TRANSACTION,
+                format!("missing signature of {}", tx.signers.join(", ")),
+            ));
+        }
+        let raw = tx.ethereum_bytes()?;
+        let tx_hash = *self
+            .provider
+            .send_raw_transaction(&raw)
             .await
-            .map_err(|e| ClientError::ethereum(CANCEL_ESCROW, e))?;
+            .map_err(|e| ClientError::ethereum(BROADCAST_TRANSACTION, e))?
+            .tx_hash();
+        debug!(%tx_hash, "Sent {} transaction", tx.operation);
+
+        let receipt = match self
+            .await_final(
+                &self.provider,
+                tx_hash,
+                BROADCAST_TRANSACTION,
+                self.deadline(),
+            )
+            .await?
+        {
+            Some(receipt) if receipt.status() => receipt,
+            Some(_) => {
+                return Err(ClientError::ethereum(
+                    BROADCAST_TRANSACTION,
+                    format!("transaction {tx_hash} reverted"),
+                ))
+            }
+            None => {
+                return Err(ClientError::tx_dropped(format!(
+                    "{} transaction {tx_hash} dropped by a reorg",
+                    tx.operation
+                )))
+            }
+        };
+        info!(%tx_hash, "{} transaction mined", tx.operation);
+
+        let escrow_id = receipt
+            .inner
+            .logs()
+            .iter()
+            .filter(|log| log.address() == *self.escrow_as_sender.address())
+            .find_map(|log| log.log_decode::<Escrow::EscrowCreated>().ok())
+            .map(|log| to_escrow_id(log.inner.data.escrowId, BROADCAST_TRANSACTION))
+            .transpose()?;
+        Ok(Broadcast {
+            transaction: tx_hash.to_string(),
+            escrow_id,
+            chain_metadata: None,
+        })
+    }
+
+    async fn get_escrow_state(&self, metadata: &EscrowMetadata) -> Result<EscrowStatus> {
+        let id = metadata
+            .escrow_id
+            .ok_or_else(|| ClientError::ethereum(GET_ESCROW, "missing escrow_id"))?;
+
+        let current_height = self.current_height(GET_ESCROW).await?;
+        self.escrow_status(id, current_height, GET_ESCROW).await
+    }
+
+    async fn current_height(&self) -> Result<u64> {
+        self.current_height(GET_ESCROW).await
+    }
+
+    /// Attests the block as deep as transactions are awaited, so a reorg
+    /// the agent tolerates cannot invalidate it.
+    async fn chain_state(&self) -> Result<ChainState> {
+        crate::chain_state::ethereum(&self.provider, self.confirmations).await
+    }
+
+    /// Observes the escrow's `EscrowFinished` or `EscrowCancelled` event,
+    /// scanning from the configured start block, once it is as deep as
+    /// transactions are awaited.
+    async fn observe_escrow(&self, metadata: &EscrowMetadata) -> Result<Observation> {
+        let err = |e| ClientError::ethereum(OBSERVE, e);
+        let id = metadata
+            .escrow_id
+            .ok_or_else(|| ClientError::ethereum(OBSERVE, "missing escrow_id"))?;
+        let instance = metadata.binding()?.instance;
+
+        let filter = Filter::new()
+            .address(*self.escrow_as_sender.address())
+            .event_signature(vec![
+                Escrow::EscrowFinished::SIGNATURE_HASH,
+                Escrow::EscrowCancelled::SIGNATURE_HASH,
+            ])
+            .topic1(U256::from(id))
+            .from_block(self.from_block);
+        let log = self
+            .provider
+            .get_logs(&filter)
+            .await
+            .map_err(err)?
+            .into_iter()
+            .find(|log| !log.removed)
+            .ok_or_else(|| ClientError::ethereum(OBSERVE, format!("escrow {id} is not settled")))?;
+        let state = match EscrowEvent::decode(&log)? {
+            EscrowEvent::Finished { .. } => ExecutionState::Released,
+            _ => ExecutionState::Refunded,
+        };
+        let (Some(height), Some(block_hash)) = (log.block_number, log.block_hash) else {
+            return Err(ClientError::ethereum(OBSERVE, "settlement log is pending"));
+        };
+        let latest = self.provider.get_block_number().await.map_err(err)?;
+        if latest + 1 < height + self.confirmations {
+            return Err(ClientError::ethereum(
+                OBSERVE,
+                format!(
+                    "settlement of escrow {id} at block {height} is not yet {} blocks deep",
+                    self.confirmations
+                ),
+            ));
+        }
+
+        Ok(Observation {
+            chain: self.caip2_id().await?,
+            claim: RemoteClaim::Escrow { instance, state },
+            height,
+            block_hash: block_hash.0,
+        })
+    }
+
+    /// Observes the storage slot at `key`, the contract address followed by
+    /// the slot, in the block as deep as transactions are awaited; the
+    /// value is hashed as its 32 big-endian bytes.
+    async fn observe_state(&self, key: &[u8]) -> Result<Observation> {
+        let err = |e| ClientError::ethereum(OBSERVE, e);
+        if key.len() != 52 {
+            return Err(ClientError::ethereum(
+                OBSERVE,
+                "state key must be a 20-byte address followed by a 32-byte slot",
+            ));
+        }
+        let (address, slot) = key.split_at(20);
+        let block = crate::chain_state::ethereum(&self.provider, self.confirmations).await?;
+        let value = self
+            .provider
+            .get_storage_at(Address::from_slice(address), U256::from_be_slice(slot))
+            .number(block.height)
+            .await
+            .map_err(err)?;
 
-        info!("{} confirmed for escrow ID {}", CANCEL_ESCROW, id);
+        Ok(Observation {
+            chain: self.caip2_id().await?,
+            claim: RemoteClaim::State {
+                key: key.to_vec(),
+                value_hash: Sha256::digest(value.to_be_bytes::<32>()).into(),
+            },
+            height: block.height,
+            block_hash: block.hash,
+        })
+    }
+
+    async fn latest_block(&self) -> Result<ObservedBlock> {
+        self.block(BlockNumberOrTag::Latest)
+            .await?
+            .ok_or_else(|| ClientError::ethereum(GET_BLOCK, "latest block not found"))
+    }
+
+    async fn block_hash(&self, height: u64) -> Result<Option<[u8; 32]>> {
+        let block = self.block(BlockNumberOrTag::Number(height)).await?;
+        Ok(block.map(|block| block.hash))
+    }
+
+    /// Reads the block the consensus layer finalized, about two epochs
+    /// behind the latest.
+    async fn finalized_height(&self) -> Result<u64> {
+        self.block(BlockNumberOrTag::Finalized)
+            .await?
+            .map(|block| block.height)
+            .ok_or_else(|| ClientError::ethereum(GET_BLOCK, "finalized block not found"))
+    }
+
+    /// Scans `EscrowCreated` events from the configured start block for
+    /// escrows in which the sender or recipient is a party, then reads each
+    /// one's live state.
+    async fn list_escrows(&self) -> Result<Vec<EscrowSummary>> {
+        let parties: Vec<Address> = std::iter::once(self.sender_party())
+            .chain(self.recipient_party())
+            .collect();
+        let events = self.indexer(self.from_block).backfill().await?;
+        let current_height = self.current_height(LIST_ESCROWS).await?;
+
+        let mut escrows = Vec::new();
+        for indexed in events {
+            let EscrowEvent::Created {
+                escrow_id,
+                sender,
+                recipient,
+                uid,
+                ..
+            } = indexed.event
+            else {
+                continue;
+            };
+            if !parties.contains(&sender) && !parties.contains(&recipient) {
+                continue;
+            }
+            escrows.push(EscrowSummary {
+                id: escrow_id.to_string(),
+                uid: Some(uid),
+                sender: sender.to_string(),
+                recipient: recipient.to_string(),
+                status: self
+                    .escrow_status(escrow_id, current_height, LIST_ESCROWS)
+                    .await?,
+            });
+        }
+        info!(count = escrows.len(), "Found escrows");
+        Ok(escrows)
+    }
+
+    /// Polls contract events through an [`EscrowIndexer`], honoring the
+    /// configured confirmation depth.
+    ///
+    /// To recognize settlements of escrows created before the watch began,
+    /// watching all escrows first scans `EscrowCreated` events from the
+    /// configured start block.
+    async fn watch_escrows(&self, metadata: Option<&EscrowMetadata>) -> Result<UpdateStream> {
+        let parties: Vec<Address> = std::iter::once(self.sender_party())
+            .chain(self.recipient_party())
+            .collect();
+        let is_party = move |event: &EscrowEvent| match *event {
+            EscrowEvent::Created {
+                sender, recipient, ..
+            } => parties.contains(&sender) || parties.contains(&recipient),
+            _ => false,
+        };
+
+        let (indexer, mut ids) = match metadata {
+            Some(metadata) => {
+                let id = metadata
+                    .escrow_id
+                    .ok_or_else(|| ClientError::ethereum(WATCH, "missing escrow_id"))?;
+                let head = self
+                    .provider
+                    .get_block_number()
+                    .await
+                    .map_err(|e| ClientError::ethereum(WATCH, e))?;
+                // Start at the first block not yet confirmed.
+                let start = (head + 2).saturating_sub(self.confirmations);
+                (self.indexer(start), HashSet::from([id]))
+            }
+            None => {
+                let mut indexer = self.indexer(self.from_block);
+                let ids = indexer
+                    .backfill()
+                    .await?
+                    .into_iter()
+                    .filter(|indexed| is_party(&indexed.event))
+                    .map(|indexed| indexed.event.escrow_id())
+                    .collect();
+                (indexer, ids)
+            }
+        };
+        let watch_all = metadata.is_none();
+        info!(
+            next_block = indexer.next_block(),
+            escrows = ids.len(),
+            "Watching escrow events"
+        );
+
+        let updates = indexer.follow().filter_map(move |item| {
+            let update = match item {
+                Ok(indexed) => {
+                    let id = indexed.event.escrow_id();
+                    if watch_all && is_party(&indexed.event) {
+                        ids.insert(id);
+                    }
+                    ids.contains(&id)
+                        .then(|| to_update(indexed, |a| a.to_string()))
+                }
+                Err(e) => Some(Err(e)),
+            };
+            future::ready(update)
+        });
+        Ok(updates.boxed())
+    }
+
+    /// Finishes all escrows in `metadata` in a single `multicall` transaction.
+    ///
+    /// The batch is atomic: if any escrow cannot be finished, none are.
+    async fn finish_escrows(&self, metadata: &[EscrowMetadata]) -> Result<()> {
+        if let [single] = metadata {
+            return self.finish_escrow(single).await;
+        }
+        if metadata.is_empty() {
+            return Ok(());
+        }
+
+        let contract = self.recipient_contract()?;
+        let ids = batch_ids(metadata, FINISH_ESCROW)?;
+        if metadata.iter().any(|m| m.params.has_conditions) {
+            self.check_proof_fresh().await?;
+        }
+        let calls = metadata
+            .iter()
+            .zip(&ids)
+            .map(|(metadata, &id)| Ok(self.finish_call(contract, metadata, id)?.calldata().clone()))
+            .collect::<Result<Vec<_>>>()?;
+
+        info!(?ids, "Sending batched {} transaction", FINISH_ESCROW);
+
+        let calldata = Escrow::multicallCall { data: calls }.abi_encode();
+        self.send_as_recipient(calldata.into(), MULTICALL).await?;
+
+        info!(?ids, "Batched {} confirmed", FINISH_ESCROW);
+        Ok(())
+    }
+
+    /// Cancels all escrows in `metadata` in a single `multicall` transaction.
+    ///
+    /// The batch is atomic: if any escrow cannot be cancelled, none are.
+    async fn cancel_escrows(&self, metadata: &[EscrowMetadata]) -> Result<()> {
+        if let [single] = metadata {
+            return self.cancel_escrow(single).await;
+        }
+        if metadata.is_empty() {
+            return Ok(());
+        }
+
+        let ids = batch_ids(metadata, CANCEL_ESCROW)?;
+        let calls: Vec<Bytes> = ids
+            .iter()
+            .map(|&id| {
+                Escrow::cancelEscrowCall {
+                    escrowId: U256::from(id),
+                }
+                .abi_encode()
+                .into()
+            })
+            .collect();
+
+        info!(?ids, "Sending batched {} transaction", CANCEL_ESCROW);
+
+        let calldata = Escrow::multicallCall { data: calls }.abi_encode();
+        if self.send_as_sender(calldata.into(), MULTICALL).await? {
+            info!(?ids, "Batched {} confirmed", CANCEL_ESCROW);
+        }
+        Ok(())
+    }
+
+    async fn wait_for_transaction(&self, pending: &PendingTransaction) -> Result<()> {
+        if !matches!(pending.chain, Chain::Ethereum) {
+            return Err(ClientError::ConfigMismatch {
+                expected: Chain::Ethereum.as_ref().to_owned(),
+                actual: pending.chain.as_ref().to_owned(),
+            });
+        }
+        let operation = [CREATE_ESCROW, FINISH_ESCROW, CANCEL_ESCROW, MULTICALL]
+            .into_iter()
+            .find(|operation| *operation == pending.operation)
+            .unwrap_or(WAIT_FOR_TRANSACTION);
+        let tx_hash = B256::from_str(&pending.id)?;
+
+        if self.paymaster.is_some() {
+            self.await_sponsored(tx_hash, operation, self.deadline())
+                .await?;
+            info!(%tx_hash, "{} transaction final", operation);
+            return Ok(());
+        }
+        self.await_final(&self.provider, tx_hash, operation, self.deadline())
+            .await?
+            .ok_or_else(|| {
+                ClientError::tx_dropped(format!(
+                    "{operation} transaction {tx_hash} was reorged out"
+                ))
+            })?;
+        info!(%tx_hash, "{} transaction final", operation);
         Ok(())
     }
 }
@@ -0,0 +1,222 @@
+//! Backfills and follows Escrow contract events.
+//!
+//! [`EscrowIndexer`] polls `eth_getLogs` in bounded block ranges, so it
+//! works against plain HTTP endpoints and resumes from a checkpoint after a
+//! restart. Only blocks at the configured confirmation depth are indexed,
+//! which keeps reorged-out events out of the index.
+
+use std::collections::VecDeque;
+use std::time::Duration;
+
+use alloy::primitives::{Address, B256};
+use alloy::providers::{DynProvider, Provider};
+use alloy::rpc::types::Filter;
+use alloy::sol_types::SolEvent;
+use futures::{stream, Stream};
+use tracing::{debug, warn};
+use zescrow_core::{EscrowMetadata, ExecutionState};
+
+use super::{Escrow, EscrowEvent};
+use crate::error::ClientError;
+use crate::Result;
+
+/// Operation name for indexer RPC errors.
+const INDEX: &str = "index";
+
+/// Default block range per `eth_getLogs` request; many providers reject
+/// wider ranges.
+pub const DEFAULT_BATCH_BLOCKS: u64 = 2_000;
+
+/// Default delay between polls once the indexer has caught up.
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(12);
+
+/// An escrow event together with where it was emitted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct IndexedEvent {
+    /// Block the event was emitted in.
+    pub block_number: u64,
+    /// Emitting transaction.
+    pub tx_hash: Option<B256>,
+    /// Position of the log within its block.
+    pub log_index: Option<u64>,
+    /// Decoded event.
+    pub event: EscrowEvent,
+}
+
+/// Indexes Escrow contract events from a starting block onward.
+#[derive(Debug, Clone)]
+pub struct EscrowIndexer {
+    provider: DynProvider,
+    contract: Address,
+    next_block: u64,
+    confirmations: u64,
+    batch_blocks: u64,
+    poll_interval: Duration,
+}
+
+impl EscrowIndexer {
+    /// Creates an indexer for the contract at `contract`, starting at
+    /// `from_block` (e.g., the contract's deployment block or a saved
+    /// [`next_block`](Self::next_block) checkpoint).
+    pub fn new(provider: DynProvider, contract: Address, from_block: u64) -> Self {
+        Self {
+            provider,
+            contract,
+            next_block: from_block,
+            confirmations: 1,
+            batch_blocks: DEFAULT_BATCH_BLOCKS,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Only indexes blocks at least `confirmations` deep (at least one).
+    pub fn with_confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = confirmations.max(1);
+        self
+    }
+
+    /// Sets the block range per `eth_getLogs` request (at least one).
+    pub fn with_batch_blocks(mut self, batch_blocks: u64) -> Self {
+        self.batch_blocks = batch_blocks.max(1);
+        self
+    }
+
+    /// Sets the delay between polls once caught up.
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// First block not yet indexed; persist it to resume later.
+    pub fn next_block(&self) -> u64 {
+        self.next_block
+    }
+
+    /// Indexes all events up to the confirmed head.
+    ///
+    /// Progress is kept per batch, so after an error the next call resumes
+    /// from the first unindexed batch.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a block number or log query fails.
+    pub async fn backfill(&mut self) -> Result<Vec<IndexedEvent>> {
+        let head = self
+            .provider
+            .get_block_number()
+            .await
+            .map_err(|e| ClientError::ethereum(INDEX, e))?;
+        let Some(confirmed) = (head + 1).checked_sub(self.confirmations) else {
+            return Ok(Vec::new());
+        };
+
+        let mut events = Vec::new();
+        while self.next_block <= confirmed {
+            let to_block = confirmed.min(self.next_block + self.batch_blocks - 1);
+            events.extend(self.fetch(self.next_block, to_block).await?);
+            self.next_block = to_block + 1;
+        }
+        debug!(
+            next_block = self.next_block,
+            count = events.len(),
+            "Backfilled escrow events"
+        );
+        Ok(events)
+    }
+
+    /// Backfills, then keeps polling for newly confirmed events.
+    ///
+    /// Query errors are yielded and retried on the next poll.
+    pub fn follow(self) -> impl Stream<Item = Result<IndexedEvent>> + Send {
+        stream::unfold(
+            (self, VecDeque::new(), false),
+            |(mut indexer, mut pending, mut caught_up)| async move {
+                loop {
+                    if let Some(event) = pending.pop_front() {
+                        return Some((Ok(event), (indexer, pending, caught_up)));
+                    }
+                    if caught_up {
+                        tokio::time::sleep(indexer.poll_interval).await;
+                    }
+                    match indexer.backfill().await {
+                        Ok(events) => {
+                            pending.extend(events);
+                            caught_up = true;
+                        }
+                        Err(e) => {
+                            warn!(%e, "Failed to index escrow events");
+                            return Some((Err(e), (indexer, pending, true)));
+                        }
+                    }
+                }
+            },
+        )
+    }
+
+    /// Fetches and decodes events in `from_block..=to_block`.
+    async fn fetch(&self, from_block: u64, to_block: u64) -> Result<Vec<IndexedEvent>> {
+        let filter = Filter::new()
+            .address(self.contract)
+            .event_signature(vec![
+                Escrow::EscrowCreated::SIGNATURE_HASH,
+                Escrow::EscrowFinished::SIGNATURE_HASH,
+                Escrow::EscrowCancelled::SIGNATURE_HASH,
+            ])
+            .from_block(from_block)
+            .to_block(to_block);
+
+        let logs = self
+            .provider
+            .get_logs(&filter)
+            .await
+            .map_err(|e| ClientError::ethereum(INDEX, e))?;
+
+        logs.iter()
+            .map(|log| {
+                Ok(IndexedEvent {
+                    block_number: log.block_number.unwrap_or(from_block),
+                    tx_hash: log.transaction_hash,
+                    log_index: log.log_index,
+                    event: EscrowEvent::decode(log)?,
+                })
+            })
+            .collect()
+    }
+}
+
+impl EscrowEvent {
+    /// Contract-assigned ID of the escrow this event concerns.
+    pub fn escrow_id(&self) -> u64 {
+        match self {
+            Self::Created { escrow_id, .. }
+            | Self::Finished { escrow_id, .. }
+            | Self::Cancelled { escrow_id, .. } => *escrow_id,
+        }
+    }
+
+    /// Updates `metadata` to the state this event implies, e.g. marking an
+    /// escrow refunded after the sender cancelled it from another tool.
+    ///
+    /// Returns `true` if `metadata` changed. Events for other escrows are
+    /// ignored.
+    pub fn reconcile(&self, metadata: &mut EscrowMetadata) -> bool {
+        if metadata.escrow_id != Some(self.escrow_id()) {
+            return false;
+        }
+        let state = match self {
+            Self::Created { .. } => ExecutionState::Funded,
+            Self::Finished { .. } => ExecutionState::Released,
+            Self::Cancelled { .. } => ExecutionState::Refunded,
+        };
+        // Settlement is final; a late `Created` must not roll it back.
+        let settled = matches!(
+            metadata.state,
+            ExecutionState::Released | ExecutionState::Refunded
+        );
+        if settled || metadata.state == state {
+            return false;
+        }
+        metadata.state = state;
+        true
+    }
+}
@@ -0,0 +1,108 @@
+//! Layer 2 quirks for Arbitrum, OP Stack, and zkSync networks.
+//!
+//! Rollups charge an L1 data fee on top of L2 execution gas, and they
+//! disagree on what `block.number` means inside a contract: Arbitrum returns
+//! an approximate L1 block number while OP Stack chains and zkSync return
+//! their own L2 block number. The escrow contract measures
+//! `finishAfter`/`cancelAfter` against `block.number`, so timelocks must be
+//! set in those units.
+
+use alloy::primitives::{address, Address, Bytes, U256};
+use alloy::providers::{DynProvider, Provider};
+use alloy::sol;
+use zescrow_core::{EvmNetwork, Rollup};
+
+use crate::error::ClientError;
+use crate::Result;
+
+/// OP Stack `GasPriceOracle` predeploy.
+const GAS_PRICE_ORACLE: Address = address!("420000000000000000000000000000000000000F");
+
+/// Arbitrum `NodeInterface` precompile (only reachable through `eth_call`).
+const NODE_INTERFACE: Address = address!("00000000000000000000000000000000000000C8");
+
+/// Multicall3, deployed at the same address on every supported network.
+const MULTICALL3: Address = address!("cA11bde05977b3631167028862bE2a173976CA11");
+
+sol! {
+    #[sol(rpc)]
+    interface IGasPriceOracle {
+        function getL1Fee(bytes memory data) external view returns (uint256);
+    }
+
+    #[sol(rpc)]
+    interface INodeInterface {
+        function gasEstimateL1Component(address to, bool contractCreation, bytes calldata data)
+            external
+            payable
+            returns (uint64 gasEstimateForL1, uint256 baseFee, uint256 l1BaseFeeEstimate);
+    }
+
+    #[sol(rpc)]
+    interface IMulticall3 {
+        function getBlockNumber() external view returns (uint256 blockNumber);
+    }
+}
+
+/// Estimates the L1 data fee, in wei, for a call to `to` with `calldata`.
+///
+/// The estimate excludes the signature and envelope bytes of the final
+/// transaction, so it slightly understates the fee actually charged.
+///
+/// # Errors
+///
+/// Returns an error if the fee oracle call fails.
+pub async fn l1_data_fee(
+    provider: &DynProvider,
+    rollup: Rollup,
+    to: Address,
+    calldata: Bytes,
+    operation: &'static str,
+) -> Result<U256> {
+    let err = |e| ClientError::ethereum(operation, e);
+
+    match rollup {
+        Rollup::OpStack => IGasPriceOracle::new(GAS_PRICE_ORACLE, provider)
+            .getL1Fee(calldata)
+            .call()
+            .await
+            .map_err(err),
+        Rollup::Arbitrum => {
+            let estimate = INodeInterface::new(NODE_INTERFACE, provider)
+                .gasEstimateL1Component(to, false, calldata)
+                .call()
+                .await
+                .map_err(err)?;
+            // L1 calldata is charged as L2 gas at the current L2 base fee.
+            Ok(U256::from(estimate.gasEstimateForL1) * estimate.baseFee)
+        }
+        // Pubdata is charged as L2 gas, already covered by the gas estimate.
+        Rollup::ZkSync => Ok(U256::ZERO),
+    }
+}
+
+/// Returns `block.number` as the escrow contract currently sees it.
+///
+/// # Errors
+///
+/// Returns an error if the block number query fails.
+pub async fn timelock_height(
+    provider: &DynProvider,
+    network: EvmNetwork,
+    operation: &'static str,
+) -> Result<u64> {
+    match network.rollup() {
+        Some(Rollup::Arbitrum) => {
+            let height = IMulticall3::new(MULTICALL3, provider)
+                .getBlockNumber()
+                .call()
+                .await
+                .map_err(|e| ClientError::ethereum(operation, e))?;
+            u64::try_from(height).map_err(|e| ClientError::ethereum(operation, e))
+        }
+        None | Some(Rollup::OpStack | Rollup::ZkSync) => provider
+            .get_block_number()
+            .await
+            .map_err(|e| ClientError::ethereum(operation, e)),
+    }
+}
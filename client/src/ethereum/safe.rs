@@ -0,0 +1,248 @@
+//! Safe (formerly Gnosis Safe) sender support.
+//!
+//! When the escrow sender is a Safe, sender-only calls (`createEscrow`,
+//! `cancelEscrow`) are wrapped in a Safe transaction and signed by the
+//! configured owner key. Safes with a threshold of one are executed
+//! immediately; otherwise the transaction is proposed to the Safe Transaction
+//! Service for the remaining owners to confirm and execute.
+
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::DynProvider;
+use alloy::rpc::types::TransactionReceipt;
+use alloy::signers::Signer;
+use alloy::sol;
+use serde_json::json;
+use tracing::{debug, info};
+use url::Url;
+
+use super::{EthereumSigner, Fees};
+use crate::error::ClientError;
+use crate::Result;
+
+/// `Enum.Operation.Call` in the Safe contracts.
+const CALL: u8 = 0;
+
+sol! {
+    /// Subset of the Safe contract interface used to build and execute transactions.
+    #[sol(rpc)]
+    interface ISafe {
+        function nonce() external view returns (uint256);
+        function getThreshold() external view returns (uint256);
+        function getTransactionHash(
+            address to,
+            uint256 value,
+            bytes calldata data,
+            uint8 operation,
+            uint256 safeTxGas,
+            uint256 baseGas,
+            uint256 gasPrice,
+            address gasToken,
+            address refundReceiver,
+            uint256 _nonce
+        ) external view returns (bytes32);
+        function execTransaction(
+            address to,
+            uint256 value,
+            bytes calldata data,
+            uint8 operation,
+            uint256 safeTxGas,
+            uint256 baseGas,
+            uint256 gasPrice,
+            address gasToken,
+            address refundReceiver,
+            bytes memory signatures
+        ) external payable returns (bool success);
+    }
+}
+
+/// Configuration for an escrow sender that is a Safe.
+#[derive(Debug, Clone)]
+pub struct SafeConfig {
+    /// Safe contract acting as the escrow sender.
+    pub address: Address,
+    /// Safe Transaction Service base URL
+    /// (e.g., `https://safe-transaction-sepolia.safe.global`).
+    ///
+    /// Required for Safes whose threshold is above one.
+    pub service_url: Option<Url>,
+}
+
+/// Outcome of submitting a call through a Safe.
+#[derive(Debug)]
+pub enum SafeSubmission {
+    /// The Safe executed the call on-chain.
+    Executed(Box<TransactionReceipt>),
+    /// The call was proposed to the transaction service and awaits
+    /// confirmations from the other owners.
+    Proposed {
+        /// Safe transaction hash owners must confirm.
+        safe_tx_hash: B256,
+        /// Safe nonce the transaction was proposed at.
+        nonce: U256,
+    },
+}
+
+/// Submits escrow calls on behalf of a Safe.
+pub(crate) struct SafeSender {
+    config: SafeConfig,
+    safe: ISafe::ISafeInstance<DynProvider>,
+    http: reqwest::Client,
+}
+
+impl SafeSender {
+    /// Binds `config` to a provider that signs as one of the Safe owners.
+    pub(crate) fn new(config: SafeConfig, provider: DynProvider) -> Self {
+        let safe = ISafe::new(config.address, provider);
+        Self {
+            config,
+            safe,
+            http: reqwest::Client::new(),
+        }
+    }
+
+    /// The Safe address, i.e. the escrow sender.
+    pub(crate) fn address(&self) -> Address {
+        self.config.address
+    }
+
+    /// Wraps a call to `to` in a Safe transaction signed by `owner`, then
+    /// executes it (threshold of one) or proposes it to the transaction service.
+    ///
+    /// `value` is paid from the Safe's own balance.
+    pub(crate) async fn submit(
+        &self,
+        owner: &EthereumSigner,
+        to: Address,
+        value: U256,
+        data: Bytes,
+        fees: Fees,
+        operation: &'static str,
+    ) -> Result<SafeSubmission> {
+        let err = |e| ClientError::ethereum(operation, e);
+
+        let nonce = self.safe.nonce().call().await.map_err(err)?;
+        let threshold = self.safe.getThreshold().call().await.map_err(err)?;
+        let safe_tx_hash = self
+            .safe
+            .getTransactionHash(
+                to,
+                value,
+                data.clone(),
+                CALL,
+                U256::ZERO,
+                U256::ZERO,
+                U256::ZERO,
+                Address::ZERO,
+                Address::ZERO,
+                nonce,
+            )
+            .call()
+            .await
+            .map_err(err)?;
+
+        let signature = owner
+            .sign_hash(&safe_tx_hash)
+            .await
+            .map_err(|e| ClientError::ethereum(operation, e))?;
+        // Safe expects r || s || v with v in {27, 28} for ECDSA owner signatures.
+        let signature = Bytes::from(signature.as_bytes().to_vec());
+        debug!(safe = %self.config.address, %safe_tx_hash, %nonce, %threshold, "Signed Safe transaction");
+
+        if threshold > U256::from(1) {
+            self.propose(
+                owner.address(),
+                to,
+                value,
+                data,
+                nonce,
+                safe_tx_hash,
+                signature,
+                operation,
+            )
+            .await?;
+            return Ok(SafeSubmission::Proposed {
+                safe_tx_hash,
+                nonce,
+            });
+        }
+
+        let receipt = self
+            .safe
+            .execTransaction(
+                to,
+                value,
+                data,
+                CALL,
+                U256::ZERO,
+                U256::ZERO,
+                U256::ZERO,
+                Address::ZERO,
+                Address::ZERO,
+                signature,
+            )
+            .max_fee_per_gas(fees.max_fee_per_gas)
+            .max_priority_fee_per_gas(fees.max_priority_fee_per_gas)
+            .send()
+            .await
+            .map_err(err)?
+            .get_receipt()
+            .await
+            .map_err(|e| ClientError::ethereum(operation, e))?;
+        info!(tx_hash = ?receipt.transaction_hash, "Safe transaction executed");
+
+        Ok(SafeSubmission::Executed(Box::new(receipt)))
+    }
+
+    /// Posts a signed Safe transaction to the Safe Transaction Service.
+    #[allow(clippy::too_many_arguments)]
+    async fn propose(
+        &self,
+        proposer: Address,
+        to: Address,
+        value: U256,
+        data: Bytes,
+        nonce: U256,
+        safe_tx_hash: B256,
+        signature: Bytes,
+        operation: &'static str,
+    ) -> Result<()> {
+        let service_url = self.config.service_url.as_ref().ok_or_else(|| {
+            ClientError::ethereum(
+                operation,
+                "Safe threshold is above one; a transaction service URL is required",
+            )
+        })?;
+        let url = service_url.join(&format!(
+            "api/v1/safes/{}/multisig-transactions/",
+            self.config.address.to_checksum(None)
+        ))?;
+
+        let body = json!({
+            "to": to.to_checksum(None),
+            "value": value.to_string(),
+            "data": data,
+            "operation": CALL,
+            "safeTxGas": "0",
+            "baseGas": "0",
+            "gasPrice": "0",
+            "gasToken": Address::ZERO,
+            "refundReceiver": Address::ZERO,
+            "nonce": nonce.to_string(),
+            "contractTransactionHash": safe_tx_hash,
+            "sender": proposer.to_checksum(None),
+            "signature": signature,
+            "origin": "zescrow",
+        });
+
+        self.http
+            .post(url)
+            .json(&body)
+            .send()
+            .await
+            .and_then(reqwest::Response::error_for_status)
+            .map_err(|e| ClientError::ethereum(operation, e))?;
+        info!(safe = %self.config.address, %safe_tx_hash, "Safe transaction proposed");
+
+        Ok(())
+    }
+}
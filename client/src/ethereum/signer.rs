@@ -0,0 +1,183 @@
+//! Signer backends for Ethereum transactions.
+//!
+//! Besides local private keys, keys held in AWS KMS (`aws-kms` feature) or
+//! GCP Cloud KMS (`gcp-kms` feature) can sign, so server deployments never
+//! hold key material. The backend is selected from `sender_private_id`:
+//!
+//! - `0x...` or bare hex: local private key
+//! - `aws-kms:<key-id-or-arn>`: AWS KMS key
+//! - `gcp-kms:projects/<p>/locations/<l>/keyRings/<r>/cryptoKeys/<k>/cryptoKeyVersions/<v>`:
+//!   GCP Cloud KMS key version
+//! - `keystore:<name>`: key in the encrypted [`Keystore`](crate::keystore::Keystore)
+
+use alloy::consensus::SignableTransaction;
+use alloy::network::TxSigner;
+use alloy::primitives::{Address, ChainId, Signature, B256};
+#[cfg(feature = "aws-kms")]
+use alloy::signers::aws::AwsSigner;
+#[cfg(feature = "gcp-kms")]
+use alloy::signers::gcp::GcpSigner;
+use alloy::signers::local::PrivateKeySigner;
+use alloy::signers::Signer;
+
+use crate::error::ClientError;
+use crate::{keystore, Result};
+
+/// Prefix selecting an AWS KMS key.
+pub const AWS_KMS_PREFIX: &str = "aws-kms:";
+
+/// Prefix selecting a GCP Cloud KMS key version.
+pub const GCP_KMS_PREFIX: &str = "gcp-kms:";
+
+/// Forwards a method call to whichever backend is active.
+macro_rules! with_signer {
+    ($self:expr, $signer:ident => $body:expr) => {
+        match $self {
+            EthereumSigner::Local($signer) => $body,
+            #[cfg(feature = "aws-kms")]
+            EthereumSigner::Aws($signer) => $body,
+            #[cfg(feature = "gcp-kms")]
+            EthereumSigner::Gcp($signer) => $body,
+        }
+    };
+}
+
+/// Signs Ethereum transactions and hashes with a local or KMS-held key.
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub enum EthereumSigner {
+    /// Private key held in memory.
+    Local(PrivateKeySigner),
+    /// Key held in AWS KMS.
+    #[cfg(feature = "aws-kms")]
+    Aws(AwsSigner),
+    /// Key held in GCP Cloud KMS.
+    #[cfg(feature = "gcp-kms")]
+    Gcp(GcpSigner),
+}
+
+impl EthereumSigner {
+    /// Builds a signer from a `sender_private_id`-style string.
+    ///
+    /// KMS credentials are taken from the environment, as the respective
+    /// cloud SDKs do by default.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key cannot be parsed or loaded, or if a KMS
+    /// key is requested without the matching feature enabled.
+    pub async fn from_id(id: &str) -> Result<Self> {
+        if let Some(key_id) = id.strip_prefix(AWS_KMS_PREFIX) {
+            return Self::aws(key_id).await;
+        }
+        if let Some(resource) = id.strip_prefix(GCP_KMS_PREFIX) {
+            return Self::gcp(resource).await;
+        }
+        if let Some(signer) = keystore::ethereum_signer(id) {
+            return signer;
+        }
+        Ok(Self::Local(id.parse()?))
+    }
+
+    #[cfg(feature = "aws-kms")]
+    async fn aws(key_id: &str) -> Result<Self> {
+        let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+        let client = aws_sdk_kms::Client::new(&config);
+        AwsSigner::new(client, key_id.to_owned(), None)
+            .await
+            .map(Self::Aws)
+            .map_err(|e| ClientError::Keypair(format!("AWS KMS key {key_id}: {e}")))
+    }
+
+    #[cfg(not(feature = "aws-kms"))]
+    async fn aws(_key_id: &str) -> Result<Self> {
+        Err(ClientError::Keypair(
+            "AWS KMS signer requires the `aws-kms` feature".into(),
+        ))
+    }
+
+    #[cfg(feature = "gcp-kms")]
+    async fn gcp(resource: &str) -> Result<Self> {
+        use alloy::signers::gcp::{GcpKeyRingRef, KeySpecifier};
+        use gcloud_sdk::google::cloud::kms::v1::key_management_service_client::KeyManagementServiceClient;
+        use gcloud_sdk::GoogleApi;
+
+        let invalid = || ClientError::Keypair(format!("invalid GCP KMS key version: {resource}"));
+        let mut segments = resource.split('/');
+        let mut segment = |name| {
+            (segments.next() == Some(name))
+                .then(|| segments.next())
+                .flatten()
+                .ok_or_else(invalid)
+        };
+        let project = segment("projects")?;
+        let location = segment("locations")?;
+        let key_ring = segment("keyRings")?;
+        let key = segment("cryptoKeys")?;
+        let version = segment("cryptoKeyVersions")?;
+        let version = version.parse().map_err(|_| invalid())?;
+
+        let client = GoogleApi::from_function(
+            KeyManagementServiceClient::new,
+            "https://cloudkms.googleapis.com",
+            None,
+        )
+        .await
+        .map_err(|e| ClientError::Keypair(format!("GCP KMS client: {e}")))?;
+        let specifier = KeySpecifier::new(
+            GcpKeyRingRef::new(project, location, key_ring),
+            key,
+            version,
+        );
+        GcpSigner::new(client, specifier, None)
+            .await
+            .map(Self::Gcp)
+            .map_err(|e| ClientError::Keypair(format!("GCP KMS key {resource}: {e}")))
+    }
+
+    #[cfg(not(feature = "gcp-kms"))]
+    async fn gcp(_resource: &str) -> Result<Self> {
+        Err(ClientError::Keypair(
+            "GCP Cloud KMS signer requires the `gcp-kms` feature".into(),
+        ))
+    }
+}
+
+impl From<PrivateKeySigner> for EthereumSigner {
+    fn from(signer: PrivateKeySigner) -> Self {
+        Self::Local(signer)
+    }
+}
+
+#[async_trait::async_trait]
+impl Signer for EthereumSigner {
+    async fn sign_hash(&self, hash: &B256) -> alloy::signers::Result<Signature> {
+        with_signer!(self, signer => signer.sign_hash(hash).await)
+    }
+
+    fn address(&self) -> Address {
+        with_signer!(self, signer => Signer::address(signer))
+    }
+
+    fn chain_id(&self) -> Option<ChainId> {
+        with_signer!(self, signer => Signer::chain_id(signer))
+    }
+
+    fn set_chain_id(&mut self, chain_id: Option<ChainId>) {
+        with_signer!(self, signer => signer.set_chain_id(chain_id))
+    }
+}
+
+#[async_trait::async_trait]
+impl TxSigner<Signature> for EthereumSigner {
+    fn address(&self) -> Address {
+        Signer::address(self)
+    }
+
+    async fn sign_transaction(
+        &self,
+        tx: &mut dyn SignableTransaction<Signature>,
+    ) -> alloy::signers::Result<Signature> {
+        with_signer!(self, signer => signer.sign_transaction(tx).await)
+    }
+}
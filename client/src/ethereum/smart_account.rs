@@ -0,0 +1,383 @@
+//! ERC-4337 smart account support.
+//!
+//! When a party's escrow address is a smart account, its escrow calls are
+//! wrapped in a v0.7 UserOperation calling the account's
+//! `execute(address,uint256,bytes)` (as implemented by SimpleAccount,
+//! LightAccount, and Coinbase Smart Wallet), signed by the party's key as
+//! the account owner, and submitted through a bundler. With an ERC-7677
+//! paymaster configured, gas is sponsored and the owner key needs no ETH.
+//!
+//! The account must already be deployed; `initCode` is never set.
+
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::time::Duration;
+
+use alloy::primitives::{address, bytes, Address, Bytes, B256, U256};
+use alloy::providers::{DynProvider, Provider, ProviderBuilder};
+use alloy::rpc::types::TransactionReceipt;
+use alloy::signers::Signer;
+use alloy::sol;
+use alloy::sol_types::SolCall;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tracing::{debug, info};
+use url::Url;
+use zescrow_core::SmartAccountConfig;
+
+use super::{EthereumSigner, Fees, RECEIPT_POLL_INTERVAL};
+use crate::error::ClientError;
+use crate::Result;
+
+/// Canonical EntryPoint v0.7 deployment.
+pub const ENTRY_POINT_V07: Address = address!("0000000071727De22E5E9d8BAf0edAc6f37da032");
+
+/// How long to wait for a bundler to include a UserOperation.
+const INCLUSION_TIMEOUT: Duration = Duration::from_secs(300);
+
+/// Well-formed ECDSA signature used while estimating gas, before the
+/// UserOperation hash (which covers the gas limits) is known.
+const DUMMY_SIGNATURE: Bytes = bytes!(
+    "fffffffffffffffffffffffffffffff0000000000000000000000000000000007aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa1c"
+);
+
+sol! {
+    #[sol(rpc)]
+    interface IEntryPoint {
+        struct PackedUserOperation {
+            address sender;
+            uint256 nonce;
+            bytes initCode;
+            bytes callData;
+            bytes32 accountGasLimits;
+            uint256 preVerificationGas;
+            bytes32 gasFees;
+            bytes paymasterAndData;
+            bytes signature;
+        }
+
+        function getNonce(address sender, uint192 key) external view returns (uint256 nonce);
+        function getUserOpHash(PackedUserOperation calldata userOp) external view returns (bytes32);
+    }
+
+    interface ISmartAccount {
+        function execute(address dest, uint256 value, bytes calldata func) external;
+    }
+}
+
+/// v0.7 UserOperation in the unpacked form used by bundler RPCs.
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct UserOperation {
+    sender: Address,
+    nonce: U256,
+    call_data: Bytes,
+    call_gas_limit: U256,
+    verification_gas_limit: U256,
+    pre_verification_gas: U256,
+    max_fee_per_gas: U256,
+    max_priority_fee_per_gas: U256,
+    #[serde(flatten)]
+    paymaster: Option<Paymaster>,
+    signature: Bytes,
+}
+
+/// Paymaster fields of a UserOperation, as returned by ERC-7677 services.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Paymaster {
+    paymaster: Address,
+    paymaster_data: Bytes,
+    #[serde(default)]
+    paymaster_verification_gas_limit: U256,
+    #[serde(default)]
+    paymaster_post_op_gas_limit: U256,
+}
+
+/// Response of `eth_estimateUserOperationGas`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct GasEstimate {
+    call_gas_limit: U256,
+    verification_gas_limit: U256,
+    pre_verification_gas: U256,
+    paymaster_verification_gas_limit: Option<U256>,
+}
+
+/// Response of `eth_getUserOperationReceipt`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct UserOperationReceipt {
+    success: bool,
+    #[serde(default)]
+    reason: Option<String>,
+    receipt: TransactionReceipt,
+}
+
+impl UserOperation {
+    /// Packs into the struct the EntryPoint hashes.
+    fn packed(&self) -> IEntryPoint::PackedUserOperation {
+        let paymaster_and_data = self
+            .paymaster
+            .as_ref()
+            .map(|pm| {
+                [
+                    pm.paymaster.as_slice(),
+                    &pack_u128(pm.paymaster_verification_gas_limit),
+                    &pack_u128(pm.paymaster_post_op_gas_limit),
+                    &pm.paymaster_data,
+                ]
+                .concat()
+            })
+            .unwrap_or_default();
+
+        IEntryPoint::PackedUserOperation {
+            sender: self.sender,
+            nonce: self.nonce,
+            initCode: Bytes::new(),
+            callData: self.call_data.clone(),
+            accountGasLimits: pack_pair(self.verification_gas_limit, self.call_gas_limit),
+            preVerificationGas: self.pre_verification_gas,
+            gasFees: pack_pair(self.max_priority_fee_per_gas, self.max_fee_per_gas),
+            paymasterAndData: paymaster_and_data.into(),
+            signature: self.signature.clone(),
+        }
+    }
+}
+
+/// Low 16 bytes of `value`, big-endian.
+fn pack_u128(value: U256) -> [u8; 16] {
+    let bytes = value.to_be_bytes::<32>();
+    bytes[16..].try_into().expect("16-byte slice")
+}
+
+/// Packs two 128-bit values into one word, `high` first.
+fn pack_pair(high: U256, low: U256) -> B256 {
+    let mut word = [0u8; 32];
+    word[..16].copy_from_slice(&pack_u128(high));
+    word[16..].copy_from_slice(&pack_u128(low));
+    B256::from(word)
+}
+
+/// Submits escrow calls from an ERC-4337 smart account.
+pub(crate) struct SmartAccountSender {
+    account: Address,
+    entry_point: IEntryPoint::IEntryPointInstance<DynProvider>,
+    bundler: DynProvider,
+    paymaster: Option<DynProvider>,
+}
+
+impl SmartAccountSender {
+    /// Builds a sender for `account` from `config`, reading chain state
+    /// through `provider`.
+    pub(crate) fn new(
+        config: &SmartAccountConfig,
+        account: &str,
+        provider: DynProvider,
+    ) -> Result<Self> {
+        let entry_point = config
+            .entry_point
+            .as_deref()
+            .map(Address::from_str)
+            .transpose()?
+            .unwrap_or(ENTRY_POINT_V07);
+        let connect = |url: &str| {
+            Url::parse(url).map(|url| ProviderBuilder::new().connect_http(url).erased())
+        };
+
+        let account = Address::from_str(account)?;
+        debug!(%account, %entry_point, "Using ERC-4337 smart account");
+
+        Ok(Self {
+            account,
+            entry_point: IEntryPoint::new(entry_point, provider),
+            bundler: connect(&config.bundler_url)?,
+            paymaster: config.paymaster_url.as_deref().map(connect).transpose()?,
+        })
+    }
+
+    /// The smart account address, i.e. the escrow party.
+    pub(crate) fn account(&self) -> Address {
+        self.account
+    }
+
+    /// Calls `to` with `data` and `value` (paid from the account) in a
+    /// UserOperation signed by `owner`, and waits for it to be included.
+    ///
+    /// Returns the receipt of the bundle transaction that included it.
+    pub(crate) async fn submit(
+        &self,
+        owner: &EthereumSigner,
+        to: Address,
+        value: U256,
+        data: Bytes,
+        fees: Fees,
+        operation: &'static str,
+    ) -> Result<TransactionReceipt> {
+        let err = |e| ClientError::ethereum(operation, e);
+        let entry_point = *self.entry_point.address();
+        let chain_id = self
+            .entry_point
+            .provider()
+            .get_chain_id()
+            .await
+            .map_err(err)?;
+
+        let nonce = self
+            .entry_point
+            .getNonce(self.account, Default::default())
+            .call()
+            .await
+            .map_err(err)?;
+        let call_data = ISmartAccount::executeCall {
+            dest: to,
+            value,
+            func: data,
+        }
+        .abi_encode();
+
+        let mut user_op = UserOperation {
+            sender: self.account,
+            nonce,
+            call_data: call_data.into(),
+            call_gas_limit: U256::ZERO,
+            verification_gas_limit: U256::ZERO,
+            pre_verification_gas: U256::ZERO,
+            max_fee_per_gas: U256::from(fees.max_fee_per_gas),
+            max_priority_fee_per_gas: U256::from(fees.max_priority_fee_per_gas),
+            paymaster: None,
+            signature: DUMMY_SIGNATURE,
+        };
+
+        // ERC-7677: stub data for estimation, then final data for the
+        // estimated operation.
+        if self.paymaster.is_some() {
+            user_op.paymaster = Some(
+                self.paymaster_data("pm_getPaymasterStubData", &user_op, chain_id, operation)
+                    .await?,
+            );
+        }
+
+        let estimate: GasEstimate = self
+            .bundler
+            .raw_request(
+                Cow::Borrowed("eth_estimateUserOperationGas"),
+                (&user_op, entry_point),
+            )
+            .await
+            .map_err(err)?;
+        user_op.call_gas_limit = estimate.call_gas_limit;
+        user_op.verification_gas_limit = estimate.verification_gas_limit;
+        user_op.pre_verification_gas = estimate.pre_verification_gas;
+        if let (Some(paymaster), Some(limit)) = (
+            user_op.paymaster.as_mut(),
+            estimate.paymaster_verification_gas_limit,
+        ) {
+            paymaster.paymaster_verification_gas_limit = limit;
+        }
+
+        if self.paymaster.is_some() {
+            let mut paymaster = self
+                .paymaster_data("pm_getPaymasterData", &user_op, chain_id, operation)
+                .await?;
+            if let Some(stub) = &user_op.paymaster {
+                if paymaster.paymaster_verification_gas_limit.is_zero() {
+                    paymaster.paymaster_verification_gas_limit =
+                        stub.paymaster_verification_gas_limit;
+                }
+                if paymaster.paymaster_post_op_gas_limit.is_zero() {
+                    paymaster.paymaster_post_op_gas_limit = stub.paymaster_post_op_gas_limit;
+                }
+            }
+            user_op.paymaster = Some(paymaster);
+        }
+
+        let user_op_hash = self
+            .entry_point
+            .getUserOpHash(user_op.packed())
+            .call()
+            .await
+            .map_err(err)?;
+        // Owner-validated accounts expect an EIP-191 signature over the hash.
+        let signature = owner
+            .sign_message(user_op_hash.as_slice())
+            .await
+            .map_err(|e| ClientError::ethereum(operation, e))?;
+        user_op.signature = signature.as_bytes().to_vec().into();
+
+        let sent: B256 = self
+            .bundler
+            .raw_request(
+                Cow::Borrowed("eth_sendUserOperation"),
+                (&user_op, entry_point),
+            )
+            .await
+            .map_err(err)?;
+        info!(account = %self.account, user_op_hash = %sent, "UserOperation sent for {}", operation);
+
+        self.await_inclusion(sent, operation).await
+    }
+
+    /// Asks the paymaster service to sponsor `user_op`.
+    async fn paymaster_data(
+        &self,
+        method: &'static str,
+        user_op: &UserOperation,
+        chain_id: u64,
+        operation: &'static str,
+    ) -> Result<Paymaster> {
+        let paymaster = self
+            .paymaster
+            .as_ref()
+            .ok_or_else(|| ClientError::ethereum(operation, "paymaster not configured"))?;
+        let params = (
+            user_op,
+            *self.entry_point.address(),
+            format!("{chain_id:#x}"),
+            json!({}),
+        );
+        let data: Paymaster = paymaster
+            .raw_request(Cow::Borrowed(method), params)
+            .await
+            .map_err(|e| ClientError::ethereum(operation, e))?;
+        debug!(paymaster = %data.paymaster, method, "Paymaster sponsorship received");
+        Ok(data)
+    }
+
+    /// Polls the bundler until the UserOperation is included.
+    async fn await_inclusion(
+        &self,
+        user_op_hash: B256,
+        operation: &'static str,
+    ) -> Result<TransactionReceipt> {
+        let deadline = tokio::time::Instant::now() + INCLUSION_TIMEOUT;
+        loop {
+            let receipt: Option<UserOperationReceipt> = self
+                .bundler
+                .raw_request(
+                    Cow::Borrowed("eth_getUserOperationReceipt"),
+                    (user_op_hash,),
+                )
+                .await
+                .map_err(|e| ClientError::ethereum(operation, e))?;
+
+            if let Some(receipt) = receipt {
+                if !receipt.success {
+                    let reason = receipt
+                        .reason
+                        .unwrap_or_else(|| "execution reverted".into());
+                    return Err(ClientError::ethereum(operation, reason));
+                }
+                info!(tx_hash = ?receipt.receipt.transaction_hash, "UserOperation included");
+                return Ok(receipt.receipt);
+            }
+            if tokio::time::Instant::now() >= deadline {
+                return Err(ClientError::tx_dropped(format!(
+                    "{operation} UserOperation {user_op_hash} not included after {}s",
+                    INCLUSION_TIMEOUT.as_secs()
+                )));
+            }
+            tokio::time::sleep(RECEIPT_POLL_INTERVAL).await;
+        }
+    }
+}
@@ -0,0 +1,317 @@
+//! zkSync Era paymaster support.
+//!
+//! zkSync accounts, including those controlled by a plain key, support
+//! paymasters natively: an EIP-712 transaction (type `0x71`) may name a
+//! paymaster contract that pays its fees. With a paymaster configured,
+//! recipients' `finishEscrow` calls, and under
+//! [`SponsorshipPolicy::All`] every escrow call, are sent this way through
+//! the paymaster's general flow, so the signing key needs no ETH.
+//!
+//! The paymaster must sponsor the escrow contract without an allowance,
+//! e.g. a sponsoring paymaster funded by the escrow operator.
+
+use std::borrow::Cow;
+use std::str::FromStr;
+use std::time::Instant;
+
+use alloy::primitives::{Address, Bytes, Signature, B256, U256, U64};
+use alloy::providers::{DynProvider, Provider};
+use alloy::rlp::{BufMut, Encodable, Header};
+use alloy::rpc::types::Log;
+use alloy::signers::Signer;
+use alloy::sol;
+use alloy::sol_types::{Eip712Domain, SolCall, SolStruct};
+use serde::Deserialize;
+use serde_json::json;
+use tracing::{debug, info};
+use zescrow_core::{SponsorshipPolicy, ZksyncConfig};
+
+use super::{pending, EthereumAgent, EthereumSigner, Fees, RECEIPT_POLL_INTERVAL};
+use crate::error::ClientError;
+use crate::Result;
+
+/// Transaction type of zkSync EIP-712 transactions.
+const EIP712_TX_TYPE: u8 = 0x71;
+
+/// Gas per pubdata byte the sender is willing to pay, as the zkSync SDKs
+/// default to.
+const DEFAULT_GAS_PER_PUBDATA: u64 = 50_000;
+
+sol! {
+    /// zkSync EIP-712 `Transaction` typed-data struct signed by the sender.
+    struct Transaction {
+        uint256 txType;
+        uint256 from;
+        uint256 to;
+        uint256 gasLimit;
+        uint256 gasPerPubdataByteLimit;
+        uint256 maxFeePerGas;
+        uint256 maxPriorityFeePerGas;
+        uint256 paymaster;
+        uint256 nonce;
+        uint256 value;
+        bytes data;
+        bytes32[] factoryDeps;
+        bytes paymasterInput;
+    }
+
+    /// Flows a paymaster may be asked to follow.
+    interface IPaymasterFlow {
+        function general(bytes calldata input) external;
+    }
+}
+
+/// Response of `zks_estimateFee`.
+#[derive(Debug, Deserialize)]
+struct FeeEstimate {
+    gas_limit: U256,
+    gas_per_pubdata_limit: U256,
+}
+
+/// Receipt fields read from `eth_getTransactionReceipt`, whose full form
+/// alloy's Ethereum receipts reject for `0x71` transactions.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct ZksyncReceipt {
+    status: Option<U64>,
+    block_number: Option<U64>,
+    #[serde(default)]
+    pub(crate) logs: Vec<Log>,
+}
+
+/// Sends escrow calls whose fees a zkSync paymaster pays.
+pub(crate) struct PaymasterSender {
+    paymaster: Address,
+    sponsorship: SponsorshipPolicy,
+    provider: DynProvider,
+}
+
+impl PaymasterSender {
+    /// Builds a sender for the paymaster in `config`, sending through
+    /// `provider`.
+    pub(crate) fn new(config: &ZksyncConfig, provider: DynProvider) -> Result<Self> {
+        let paymaster = Address::from_str(&config.paymaster)?;
+        debug!(%paymaster, sponsorship = ?config.sponsorship, "Using zkSync paymaster");
+        Ok(Self {
+            paymaster,
+            sponsorship: config.sponsorship,
+            provider,
+        })
+    }
+
+    /// Whether the paymaster also sponsors the sender's calls.
+    pub(crate) fn sponsors_sender(&self) -> bool {
+        matches!(self.sponsorship, SponsorshipPolicy::All)
+    }
+
+    /// Calls `to` with `data` and `value` (paid by `signer`) in an EIP-712
+    /// transaction sponsored by the paymaster, and returns its hash.
+    pub(crate) async fn send(
+        &self,
+        signer: &EthereumSigner,
+        to: Address,
+        value: U256,
+        data: Bytes,
+        fees: Fees,
+        operation: &'static str,
+    ) -> Result<B256> {
+        let err = |e| ClientError::ethereum(operation, e);
+        let from = signer.address();
+        let paymaster_input: Bytes = IPaymasterFlow::generalCall {
+            input: Bytes::new(),
+        }
+        .abi_encode()
+        .into();
+
+        let chain_id = self.provider.get_chain_id().await.map_err(err)?;
+        let nonce = self
+            .provider
+            .get_transaction_count(from)
+            .pending()
+            .await
+            .map_err(err)?;
+
+        // The estimate runs the paymaster's validation, so it also fails
+        // early if the paymaster refuses to sponsor the call.
+        let request = json!({
+            "from": from,
+            "to": to,
+            "value": value,
+            "data": data,
+            "eip712Meta": {
+                "gasPerPubdata": U256::from(DEFAULT_GAS_PER_PUBDATA),
+                "paymasterParams": {
+                    "paymaster": self.paymaster,
+                    "paymasterInput": paymaster_input.to_vec(),
+                },
+            },
+        });
+        let estimate: FeeEstimate = self
+            .provider
+            .raw_request(Cow::Borrowed("zks_estimateFee"), (request,))
+            .await
+            .map_err(err)?;
+
+        let tx = Transaction {
+            txType: U256::from(EIP712_TX_TYPE),
+            from: address_word(from),
+            to: address_word(to),
+            gasLimit: estimate.gas_limit,
+            gasPerPubdataByteLimit: estimate.gas_per_pubdata_limit,
+            maxFeePerGas: U256::from(fees.max_fee_per_gas),
+            maxPriorityFeePerGas: U256::from(fees.max_priority_fee_per_gas),
+            paymaster: address_word(self.paymaster),
+            nonce: U256::from(nonce),
+            value,
+            data,
+            factoryDeps: Vec::new(),
+            paymasterInput: paymaster_input,
+        };
+        let domain = Eip712Domain::new(
+            Some("zkSync".into()),
+            Some("2".into()),
+            Some(U256::from(chain_id)),
+            None,
+            None,
+        );
+        let signature = signer
+            .sign_hash(&tx.eip712_signing_hash(&domain))
+            .await
+            .map_err(err)?;
+
+        let raw = encode_signed(&tx, from, to, self.paymaster, chain_id, &signature);
+        let tx_hash = *self
+            .provider
+            .send_raw_transaction(&raw)
+            .await
+            .map_err(err)?
+            .tx_hash();
+        info!(%tx_hash, paymaster = %self.paymaster, "Sent sponsored {} transaction", operation);
+        Ok(tx_hash)
+    }
+}
+
+impl EthereumAgent {
+    /// Calls the escrow contract from `signer` through `paymaster`, then
+    /// waits for the transaction to be final.
+    ///
+    /// Returns the logs the transaction emitted.
+    pub(super) async fn submit_sponsored(
+        &self,
+        paymaster: &PaymasterSender,
+        signer: &EthereumSigner,
+        value: U256,
+        calldata: Bytes,
+        fees: Fees,
+        operation: &'static str,
+    ) -> Result<Vec<Log>> {
+        let tx_hash = paymaster
+            .send(
+                signer,
+                *self.escrow_as_sender.address(),
+                value,
+                calldata,
+                fees,
+                operation,
+            )
+            .await?;
+        let receipt = self
+            .await_sponsored(tx_hash, operation, self.deadline())
+            .await?;
+        Ok(receipt.logs)
+    }
+
+    /// Waits for `tx_hash` to reach the configured confirmation depth.
+    ///
+    /// The zkSync sequencer does not reorg its blocks, so inclusion at the
+    /// required depth is final. Returns [`ClientError::Pending`] if the
+    /// transaction is not final by `deadline`.
+    pub(super) async fn await_sponsored(
+        &self,
+        tx_hash: B256,
+        operation: &'static str,
+        deadline: Option<Instant>,
+    ) -> Result<ZksyncReceipt> {
+        let err = |e| ClientError::ethereum(operation, e);
+        let poll_interval = self.wait.poll_interval.unwrap_or(RECEIPT_POLL_INTERVAL);
+
+        loop {
+            let receipt: Option<ZksyncReceipt> = self
+                .provider
+                .raw_request(Cow::Borrowed("eth_getTransactionReceipt"), (tx_hash,))
+                .await
+                .map_err(err)?;
+
+            if let Some(receipt) = receipt {
+                if let Some(block_number) = receipt.block_number {
+                    if receipt.status != Some(U64::from(1)) {
+                        return Err(ClientError::ethereum(
+                            operation,
+                            format!("transaction {tx_hash} reverted"),
+                        ));
+                    }
+                    let head = self.provider.get_block_number().await.map_err(err)?;
+                    if head + 1 >= block_number.to::<u64>() + self.confirmations {
+                        debug!(%tx_hash, confirmations = self.confirmations, "Transaction final");
+                        return Ok(receipt);
+                    }
+                }
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(pending(operation, tx_hash));
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
+
+/// Left-pads `address` to a word, as the typed-data struct types it.
+fn address_word(address: Address) -> U256 {
+    U256::from_be_slice(address.as_slice())
+}
+
+/// Encodes a signed EIP-712 transaction as `0x71 || rlp(fields)`.
+fn encode_signed(
+    tx: &Transaction,
+    from: Address,
+    to: Address,
+    paymaster: Address,
+    chain_id: u64,
+    signature: &Signature,
+) -> Vec<u8> {
+    let paymaster_params_length = paymaster.length() + tx.paymasterInput.length();
+    let custom_signature = Bytes::from(signature.as_bytes().to_vec());
+
+    let mut fields = Vec::new();
+    tx.nonce.encode(&mut fields);
+    tx.maxPriorityFeePerGas.encode(&mut fields);
+    tx.maxFeePerGas.encode(&mut fields);
+    tx.gasLimit.encode(&mut fields);
+    to.encode(&mut fields);
+    tx.value.encode(&mut fields);
+    tx.data.encode(&mut fields);
+    u8::from(signature.v()).encode(&mut fields);
+    signature.r().encode(&mut fields);
+    signature.s().encode(&mut fields);
+    chain_id.encode(&mut fields);
+    from.encode(&mut fields);
+    tx.gasPerPubdataByteLimit.encode(&mut fields);
+    Vec::<B256>::new().encode(&mut fields);
+    custom_signature.encode(&mut fields);
+    Header {
+        list: true,
+        payload_length: paymaster_params_length,
+    }
+    .encode(&mut fields);
+    paymaster.encode(&mut fields);
+    tx.paymasterInput.encode(&mut fields);
+
+    let mut raw = vec![EIP712_TX_TYPE];
+    Header {
+        list: true,
+        payload_length: fields.len(),
+    }
+    .encode(&mut raw);
+    raw.put_slice(&fields);
+    raw
+}
@@ -0,0 +1,318 @@
+//! Offers mirrored across several chains.
+//!
+//! A [`FanOut`] creates one escrow per configured chain from the same
+//! logical escrow, so the recipient can redeem the offer on whichever chain
+//! they prefer:
+//!
+//! 1. The sender proposes the offer with the terms of each leg, e.g. the
+//!    same amount payable to the recipient's address on each chain. All
+//!    legs are tracked under one [`EscrowId`], which unset memos are set to
+//!    on chains [storing memos](crate::stores_memo) so the legs can be
+//!    linked on-chain.
+//! 2. The sender [locks](FanOut::lock) every leg.
+//! 3. The recipient finishes one leg, through [`FanOut::finish`] or
+//!    directly on its chain.
+//! 4. [`FanOut::reconcile`] notices the finished leg and cancels the others
+//!    as each becomes cancellable.
+//!
+//! Every leg is fully funded, and nothing on-chain stops the recipient
+//! finishing more than one leg before the others are cancelled. Offers
+//! should therefore keep `cancel_after` short, or lock the legs by
+//! conditions that can be fulfilled on one leg only.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut offer = FanOut::new(b"order-42", vec![ethereum_params, solana_params])?;
+//! offer.lock(&[&ethereum, &solana]).await?;
+//!
+//! // ... once the recipient has finished a leg, and again until complete:
+//! if offer.reconcile(&[&ethereum, &solana]).await? == FanOutPhase::Completed {
+//!     // The other legs are refunded.
+//! }
+//! ```
+
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tracing::{info, warn};
+use zescrow_core::{EscrowId, EscrowMetadata, EscrowParams, ExecutionState};
+
+use crate::error::ClientError;
+use crate::{Result, ZescrowClient};
+
+/// Domain separating fan-out IDs from per-chain [`EscrowId`]s.
+const FAN_OUT_DOMAIN: &[u8] = b"zescrow/fan-out/v1";
+
+/// Progress of a [`FanOut`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FanOutPhase {
+    /// Not every leg is locked yet.
+    Proposed,
+    /// Every leg is locked, and none is finished.
+    Locked,
+    /// One leg is finished, and some others are not cancelled yet.
+    Finished,
+    /// One leg is finished, and the others are settled.
+    Completed,
+}
+
+/// One of the mirrored escrows of a [`FanOut`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanOutLeg {
+    /// Terms of the escrow.
+    pub params: EscrowParams,
+    /// Metadata of the escrow, once locked. Its state records whether the
+    /// fan-out finished or cancelled the leg.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<EscrowMetadata>,
+}
+
+impl FanOutLeg {
+    /// Returns the leg's metadata, or an error naming it if unlocked.
+    fn metadata(&self, leg: usize) -> Result<&EscrowMetadata> {
+        self.metadata
+            .as_ref()
+            .ok_or_else(|| ClientError::FanOut(format!("leg {leg} is not locked")))
+    }
+}
+
+/// One logical escrow mirrored on several chains, of which the recipient
+/// redeems one.
+///
+/// Fan-outs are serializable, so progress can be saved between steps and
+/// resumed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct FanOut {
+    /// ID the legs are tracked under, derived from the nonce and the legs'
+    /// chains and parties.
+    pub uid: EscrowId,
+    /// Mirrored escrows, one per chain.
+    pub legs: Vec<FanOutLeg>,
+    /// Progress of the fan-out.
+    pub phase: FanOutPhase,
+    /// Index of the leg the recipient finished, once finished.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub finished: Option<usize>,
+}
+
+impl FanOut {
+    /// Proposes an offer mirrored as escrows with each of `legs`' terms.
+    ///
+    /// Legs without a memo get the fan-out's [`uid`](Self::uid) as memo on
+    /// chains [storing memos](crate::stores_memo).
+    ///
+    /// # Arguments
+    ///
+    /// * `nonce` - Value distinguishing this offer from others between the
+    ///   same parties, e.g. an order ID
+    /// * `legs` - Terms of the escrow on each chain
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there are fewer than two legs, or a leg sets no
+    /// `cancel_after` to refund it by.
+    pub fn new(nonce: &[u8], legs: Vec<EscrowParams>) -> Result<Self> {
+        if legs.len() < 2 {
+            return Err(ClientError::FanOut(
+                "a fan-out needs at least two legs".into(),
+            ));
+        }
+        if let Some(leg) = legs.iter().position(|leg| leg.cancel_after.is_none()) {
+            return Err(ClientError::FanOut(format!(
+                "leg {leg} needs a `cancel_after` to be refunded by"
+            )));
+        }
+
+        let uid = fan_out_id(nonce, &legs);
+        let legs = legs
+            .into_iter()
+            .map(|mut params| {
+                if crate::stores_memo(params.chain_config.chain) {
+                    params.memo.get_or_insert_with(|| uid.to_string());
+                }
+                FanOutLeg {
+                    params,
+                    metadata: None,
+                }
+            })
+            .collect();
+        Ok(Self {
+            uid,
+            legs,
+            phase: FanOutPhase::Proposed,
+            finished: None,
+        })
+    }
+
+    /// Locks every leg not locked yet, in order.
+    ///
+    /// # Arguments
+    ///
+    /// * `clients` - Client on each leg's chain, sending as the sender, in
+    ///   the order of the legs
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is not one client per leg, the fan-out is
+    /// already locked, or creating an escrow fails. Legs locked before the
+    /// failure stay locked, so locking can be resumed.
+    pub async fn lock(&mut self, clients: &[&ZescrowClient]) -> Result<()> {
+        self.expect(FanOutPhase::Proposed)?;
+        self.check_clients(clients)?;
+        for (index, (leg, client)) in self.legs.iter_mut().zip(clients).enumerate() {
+            if leg.metadata.is_some() {
+                continue;
+            }
+            let metadata = client.create_escrow(&leg.params).await?;
+            info!(uid = %self.uid, leg = index, escrow_id = ?metadata.escrow_id, "Locked leg");
+            leg.metadata = Some(metadata);
+        }
+        self.phase = FanOutPhase::Locked;
+        Ok(())
+    }
+
+    /// Finishes leg `leg` for the recipient.
+    ///
+    /// # Arguments
+    ///
+    /// * `leg` - Index of the leg to redeem
+    /// * `client` - Client on the leg's chain with the recipient as
+    ///   recipient, and the proof seal for the leg where finishing requires
+    ///   one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the legs are not all locked, a leg is already
+    /// finished, or finishing fails.
+    pub async fn finish(&mut self, leg: usize, client: &ZescrowClient) -> Result<()> {
+        self.expect(FanOutPhase::Locked)?;
+        let metadata = self
+            .legs
+            .get_mut(leg)
+            .ok_or_else(|| ClientError::FanOut(format!("there is no leg {leg}")))?
+            .metadata
+            .as_mut()
+            .ok_or_else(|| ClientError::FanOut(format!("leg {leg} is not locked")))?;
+        client.finish_escrow(metadata).await?;
+        info!(uid = %self.uid, leg, escrow_id = ?metadata.escrow_id, "Finished leg");
+        metadata.state = ExecutionState::Released;
+        self.finished = Some(leg);
+        self.phase = FanOutPhase::Finished;
+        Ok(())
+    }
+
+    /// Checks the legs against their chains: records a leg the recipient
+    /// finished, then cancels each other leg once it can be cancelled.
+    ///
+    /// While the legs are locked, the first leg settled on-chain is taken
+    /// to be the one finished, as only the fan-out cancels legs. Call it
+    /// again until the fan-out is [completed](FanOutPhase::Completed).
+    ///
+    /// # Arguments
+    ///
+    /// * `clients` - Client on each leg's chain, sending as the sender, in
+    ///   the order of the legs
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is not one client per leg, the legs are
+    /// not all locked, or reading or cancelling an escrow fails.
+    pub async fn reconcile(&mut self, clients: &[&ZescrowClient]) -> Result<FanOutPhase> {
+        if self.phase == FanOutPhase::Proposed {
+            return Err(ClientError::FanOut("not every leg is locked".into()));
+        }
+        self.check_clients(clients)?;
+
+        if self.phase == FanOutPhase::Locked {
+            for (index, (leg, client)) in self.legs.iter_mut().zip(clients).enumerate() {
+                let metadata = leg.metadata.as_mut().expect("locked legs have metadata");
+                if client.get_escrow_state(metadata).await?.settled {
+                    info!(uid = %self.uid, leg = index, "Recipient finished leg");
+                    metadata.state = ExecutionState::Released;
+                    self.finished = Some(index);
+                    self.phase = FanOutPhase::Finished;
+                    break;
+                }
+            }
+        }
+        let Some(finished) = self.finished else {
+            return Ok(self.phase);
+        };
+
+        let mut uncancelled = 0;
+        for (index, (leg, client)) in self.legs.iter_mut().zip(clients).enumerate() {
+            let metadata = leg.metadata.as_mut().expect("locked legs have metadata");
+            if index == finished || metadata.state != ExecutionState::Funded {
+                continue;
+            }
+            let status = client.get_escrow_state(metadata).await?;
+            if status.settled {
+                warn!(uid = %self.uid, leg = index, "Leg was settled outside the fan-out");
+            } else if status.can_cancel() {
+                client.cancel_escrow(metadata).await?;
+                info!(uid = %self.uid, leg = index, "Cancelled leg");
+                metadata.state = ExecutionState::Refunded;
+            } else {
+                uncancelled += 1;
+            }
+        }
+        if uncancelled == 0 {
+            self.phase = FanOutPhase::Completed;
+        }
+        Ok(self.phase)
+    }
+
+    /// Returns the metadata of leg `leg`, once locked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if there is no such leg or it is not locked.
+    pub fn leg(&self, leg: usize) -> Result<&EscrowMetadata> {
+        self.legs
+            .get(leg)
+            .ok_or_else(|| ClientError::FanOut(format!("there is no leg {leg}")))?
+            .metadata(leg)
+    }
+
+    fn check_clients(&self, clients: &[&ZescrowClient]) -> Result<()> {
+        if clients.len() != self.legs.len() {
+            return Err(ClientError::FanOut(format!(
+                "expected a client for each of the {} legs, got {}",
+                self.legs.len(),
+                clients.len()
+            )));
+        }
+        Ok(())
+    }
+
+    fn expect(&self, phase: FanOutPhase) -> Result<()> {
+        if self.phase != phase {
+            return Err(ClientError::FanOut(format!(
+                "expected the fan-out to be {phase:?}, but it is {:?}",
+                self.phase
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// ID of a fan-out: the SHA-256 digest of [`FAN_OUT_DOMAIN`], `nonce`, and
+/// each leg's chain, escrow contract or program, parties, and amount.
+fn fan_out_id(nonce: &[u8], legs: &[EscrowParams]) -> EscrowId {
+    let mut hasher = Sha256::new();
+    hasher.update(FAN_OUT_DOMAIN);
+    hasher.update(Sha256::digest(nonce));
+    for leg in legs {
+        for part in [
+            leg.chain_config.chain.as_ref(),
+            &leg.chain_config.agent_id,
+            &leg.sender.to_string(),
+            &leg.recipient.to_string(),
+            &leg.asset.amount.to_string(),
+        ] {
+            hasher.update(Sha256::digest(part.as_bytes()));
+        }
+    }
+    EscrowId(hasher.finalize().into())
+}
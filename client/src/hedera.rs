@@ -0,0 +1,317 @@
+//! Hedera blockchain agent implementation.
+//!
+//! Hedera runs the Solidity escrow contract of `agent/ethereum` on its
+//! smart contract service, which a JSON-RPC relay (e.g. Hashio) serves as an
+//! Ethereum endpoint. [`HederaAgent`] therefore drives an [`EthereumAgent`]
+//! against the relay, signing with the ECDSA key behind an account's EVM
+//! address alias, and adapts what differs from Ethereum:
+//!
+//! - Parties may be given as account IDs (`0.0.x`), which the mirror node
+//!   resolves to the EVM addresses their transactions come from.
+//! - Escrows lock HBAR. Amounts are in tinybars (10^-8 HBAR), as the
+//!   contract sees them, while the relay takes transaction values and
+//!   quotes fees in weibars (10^-18 HBAR).
+//! - HTS tokens cannot be escrowed, as the contract locks the call value
+//!   only.
+//!
+//! Hedera blocks are final once their consensus round closes, so one
+//! confirmation suffices.
+
+use std::str::FromStr;
+
+use alloy::primitives::Address;
+use serde::Deserialize;
+use tracing::{debug, warn};
+use url::Url;
+use zescrow_core::interface::ChainConfig;
+use zescrow_core::{AssetKind, BigNumber, Chain, EscrowMetadata, EscrowParams, Party, ID};
+
+use crate::error::ClientError;
+use crate::estimate::LifecycleEstimate;
+use crate::ethereum::FeeConfig;
+use crate::pending::{PendingTransaction, WaitConfig};
+use crate::rpc::hedera::MirrorClient;
+use crate::rpc::RpcPolicy;
+use crate::simulation::{Operation, Simulation};
+use crate::watch::UpdateStream;
+use crate::{Agent, EscrowStatus, EscrowSummary, EthereumAgent, EthereumSigner, Result};
+
+// Contract method names.
+const CREATE_ESCROW: &str = "createEscrow";
+const FINISH_ESCROW: &str = "finishEscrow";
+const CANCEL_ESCROW: &str = "cancelEscrow";
+
+/// Weibars, the unit of transaction values on the relay, per tinybar.
+const WEIBARS_PER_TINYBAR: u64 = 10_000_000_000;
+
+/// Account as returned by the mirror node's `accounts/{id}`.
+#[derive(Deserialize)]
+struct MirrorAccount {
+    evm_address: Option<String>,
+}
+
+/// Converts a fee the relay quotes in weibars to tinybars, rounding up.
+fn to_tinybars(weibars: BigNumber) -> BigNumber {
+    BigNumber((weibars.0 + (WEIBARS_PER_TINYBAR - 1)) / WEIBARS_PER_TINYBAR)
+}
+
+/// Reports transactions the Ethereum agent left pending as Hedera ones.
+fn on_hedera(error: ClientError) -> ClientError {
+    match error {
+        ClientError::Pending(mut pending) => {
+            pending.chain = Chain::Hedera;
+            ClientError::Pending(pending)
+        }
+        e => e,
+    }
+}
+
+/// Hedera blockchain agent for escrow operations.
+///
+/// Calls the Solidity escrow contract through a JSON-RPC relay, resolving
+/// account IDs through a mirror node.
+pub struct HederaAgent {
+    /// Ethereum agent connected to the relay.
+    inner: EthereumAgent,
+    /// Mirror node REST API.
+    mirror: MirrorClient,
+}
+
+impl HederaAgent {
+    /// Creates a new Hedera agent from chain configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Chain configuration with the relay URL, the contract's
+    ///   EVM address, and the mirror node settings
+    /// * `sender` - Optional signer overriding `sender_private_id`
+    /// * `recipient` - Optional recipient signer for finish operations
+    /// * `fees` - EIP-1559 fee overrides and cap, in weibars
+    /// * `rpc` - Retry settings and metrics for RPC requests, which fail
+    ///   over to `fallback_rpc_urls` when `rpc_url` is unavailable
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the mirror node settings are missing, a URL or
+    /// the contract address cannot be parsed, or the relay is unreachable.
+    pub async fn new(
+        config: &ChainConfig,
+        sender: Option<EthereumSigner>,
+        recipient: Option<EthereumSigner>,
+        fees: FeeConfig,
+        rpc: RpcPolicy,
+    ) -> Result<Self> {
+        let hedera = config.hedera.as_ref().ok_or_else(|| {
+            ClientError::hedera("config", "`hedera` settings are required on Hedera")
+        })?;
+        let mirror = MirrorClient::new(&Url::parse(&hedera.mirror_url)?, rpc.clone());
+        let inner = EthereumAgent::new(config, sender, recipient, fees, rpc).await?;
+        debug!(mirror = %hedera.mirror_url, "Connected to Hedera");
+        Ok(Self { inner, mirror })
+    }
+
+    /// Sets the Groth16 seal submitted when finishing an escrow with
+    /// conditions.
+    pub fn with_proof_seal(mut self, seal: impl Into<alloy::primitives::Bytes>) -> Self {
+        self.inner = self.inner.with_proof_seal(seal);
+        self
+    }
+
+    /// Sets the fingerprint of the condition escrows with conditions are
+    /// created with.
+    pub fn with_condition_fingerprint(mut self, fingerprint: [u8; 32]) -> Self {
+        self.inner = self.inner.with_condition_fingerprint(fingerprint);
+        self
+    }
+
+    /// Scans escrow events from `block` onward when listing escrows, e.g.
+    /// the contract's deployment block.
+    pub fn with_from_block(mut self, block: u64) -> Self {
+        self.inner = self.inner.with_from_block(block);
+        self
+    }
+
+    /// Waits until transactions are `confirmations` blocks deep.
+    pub fn with_confirmations(mut self, confirmations: u64) -> Self {
+        self.inner = self.inner.with_confirmations(confirmations);
+        self
+    }
+
+    /// Sets the timeout and polling interval of confirmation waits.
+    pub fn with_wait(mut self, wait: WaitConfig) -> Self {
+        self.inner = self.inner.with_wait(wait);
+        self
+    }
+
+    /// Returns `party` as the EVM address the contract sees, looking up
+    /// account IDs on the mirror node.
+    async fn evm_party(&self, party: &Party, operation: &'static str) -> Result<Party> {
+        let id = ID::from_str(&party.to_string())?;
+        let ID::Hedera(account) = &id else {
+            return Ok(party.clone());
+        };
+        let resolved: MirrorAccount = self
+            .mirror
+            .get(&["accounts", account])
+            .await
+            .map_err(|e| ClientError::hedera(operation, format!("account {account}: {e}")))?;
+        let address = resolved
+            .evm_address
+            .as_deref()
+            .map(Address::from_str)
+            .transpose()?
+            .ok_or_else(|| {
+                ClientError::hedera(operation, format!("account {account} has no EVM address"))
+            })?;
+        if id.hedera_address() == Some(address.into()) {
+            warn!(%account, "Account has no ECDSA alias to call the escrow contract with");
+        }
+        debug!(%account, %address, "Resolved Hedera account");
+        Ok(Party::from(ID::Hex(hex::encode(address))))
+    }
+
+    /// Returns `params` with both parties as EVM addresses.
+    async fn resolve(
+        &self,
+        params: &EscrowParams,
+        operation: &'static str,
+    ) -> Result<EscrowParams> {
+        let mut resolved = params.clone();
+        resolved.sender = self.evm_party(&params.sender, operation).await?;
+        resolved.recipient = self.evm_party(&params.recipient, operation).await?;
+        Ok(resolved)
+    }
+
+    /// Returns `params` as the Ethereum agent takes them: with EVM
+    /// addresses, and the amount in weibars.
+    async fn evm_params(&self, params: &EscrowParams) -> Result<EscrowParams> {
+        if !matches!(params.asset.kind, AssetKind::Native) {
+            return Err(ClientError::InvalidChainOperation(format!(
+                "{:?} assets are not supported on Hedera; the escrow contract locks HBAR only",
+                params.asset.kind
+            )));
+        }
+        let mut evm = self.resolve(params, CREATE_ESCROW).await?;
+        evm.asset.amount = BigNumber(&params.asset.amount.0 * WEIBARS_PER_TINYBAR);
+        Ok(evm)
+    }
+
+    /// Returns `metadata` with both parties as EVM addresses.
+    async fn evm_metadata(
+        &self,
+        metadata: &EscrowMetadata,
+        operation: &'static str,
+    ) -> Result<EscrowMetadata> {
+        Ok(EscrowMetadata {
+            params: self.resolve(&metadata.params, operation).await?,
+            ..metadata.clone()
+        })
+    }
+}
+
+#[async_trait::async_trait]
+impl Agent for HederaAgent {
+    async fn create_escrow(&self, params: &EscrowParams) -> Result<EscrowMetadata> {
+        let evm = self.evm_params(params).await?;
+        // Record the parties the contract knows, with the amount in tinybars.
+        let mut recorded = evm.clone();
+        recorded.asset.amount = params.asset.amount.clone();
+        let metadata = self
+            .inner
+            .create_escrow_as(&evm, &recorded)
+            .await
+            .map_err(on_hedera)?;
+        Ok(EscrowMetadata {
+            params: recorded,
+            ..metadata
+        })
+    }
+
+    async fn finish_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
+        let metadata = self.evm_metadata(metadata, FINISH_ESCROW).await?;
+        self.inner.finish_escrow(&metadata).await.map_err(on_hedera)
+    }
+
+    async fn cancel_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
+        let metadata = self.evm_metadata(metadata, CANCEL_ESCROW).await?;
+        self.inner.cancel_escrow(&metadata).await.map_err(on_hedera)
+    }
+
+    /// Simulates through the relay, reporting the fee in tinybars.
+    async fn simulate(&self, operation: Operation<'_>) -> Result<Simulation> {
+        let (evm, resolved);
+        let operation = match operation {
+            Operation::Create(params) => {
+                evm = self.evm_params(params).await?;
+                Operation::Create(&evm)
+            }
+            Operation::Finish(metadata) => {
+                resolved = self.evm_metadata(metadata, FINISH_ESCROW).await?;
+                Operation::Finish(&resolved)
+            }
+            Operation::Cancel(metadata) => {
+                resolved = self.evm_metadata(metadata, CANCEL_ESCROW).await?;
+                Operation::Cancel(&resolved)
+            }
+        };
+        let mut simulation = self.inner.simulate(operation).await?;
+        simulation.fee = simulation.fee.map(to_tinybars);
+        Ok(simulation)
+    }
+
+    /// Estimates through the relay, reporting fees in tinybars.
+    async fn estimate(&self, params: &EscrowParams) -> Result<LifecycleEstimate> {
+        let evm = self.evm_params(params).await?;
+        let mut estimate = self.inner.estimate(&evm).await?;
+        for cost in [
+            &mut estimate.create,
+            &mut estimate.finish,
+            &mut estimate.cancel,
+        ] {
+            cost.fee = cost.fee.take().map(to_tinybars);
+        }
+        Ok(estimate)
+    }
+
+    async fn get_escrow_state(&self, metadata: &EscrowMetadata) -> Result<EscrowStatus> {
+        self.inner.get_escrow_state(metadata).await
+    }
+
+    async fn current_height(&self) -> Result<u64> {
+        Agent::current_height(&self.inner).await
+    }
+
+    /// Lists escrows of the parties' EVM addresses.
+    async fn list_escrows(&self) -> Result<Vec<EscrowSummary>> {
+        self.inner.list_escrows().await
+    }
+
+    async fn watch_escrows(&self, metadata: Option<&EscrowMetadata>) -> Result<UpdateStream> {
+        self.inner.watch_escrows(metadata).await
+    }
+
+    async fn finish_escrows(&self, metadata: &[EscrowMetadata]) -> Result<()> {
+        self.inner.finish_escrows(metadata).await.map_err(on_hedera)
+    }
+
+    async fn cancel_escrows(&self, metadata: &[EscrowMetadata]) -> Result<()> {
+        self.inner.cancel_escrows(metadata).await.map_err(on_hedera)
+    }
+
+    async fn wait_for_transaction(&self, pending: &PendingTransaction) -> Result<()> {
+        if !matches!(pending.chain, Chain::Hedera) {
+            return Err(ClientError::ConfigMismatch {
+                expected: Chain::Hedera.as_ref().to_owned(),
+                actual: pending.chain.as_ref().to_owned(),
+            });
+        }
+        let pending = PendingTransaction {
+            chain: Chain::Ethereum,
+            ..pending.clone()
+        };
+        self.inner
+            .wait_for_transaction(&pending)
+            .await
+            .map_err(on_hedera)
+    }
+}
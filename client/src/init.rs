@@ -0,0 +1,772 @@
+//! Interactive `init` wizard.
+//!
+//! Prompts for the chain, endpoints, keys, parties, asset, and timelocks,
+//! validating each answer, then writes `escrow_params.json`. Ethereum,
+//! Cosmos, Substrate, Cardano, Tron, and Hedera private keys are written to
+//! `.env` and referenced from the parameters file rather than stored in it.
+//!
+//! Prompts and progress go to stderr, leaving stdout for the command's
+//! result.
+
+use std::fmt::Display;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+use alloy::primitives::Address;
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::Context;
+#[cfg(feature = "cosmos")]
+use cosmrs::AccountId;
+use dialoguer::{Confirm, Input, Password, Select};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::read_keypair_file;
+use solana_sdk::signer::Signer;
+#[cfg(feature = "substrate")]
+use subxt::utils::AccountId32;
+use tracing::info;
+use url::Url;
+#[cfg(feature = "cardano")]
+use zescrow_client::CardanoSigner;
+#[cfg(feature = "cosmos")]
+use zescrow_client::CosmosSigner;
+#[cfg(feature = "substrate")]
+use zescrow_client::SubstrateSigner;
+#[cfg(feature = "cosmos")]
+use zescrow_core::interface::CosmosConfig;
+#[cfg(feature = "substrate")]
+use zescrow_core::interface::SubstrateConfig;
+use zescrow_core::interface::{
+    save_escrow_data, ChainConfig, HederaConfig, TronConfig, MAX_MEMO_LEN,
+};
+#[cfg(feature = "cardano")]
+use zescrow_core::interface::{CardanoConfig, CardanoNetwork};
+use zescrow_core::{Asset, BigNumber, Chain, EscrowParams, Party, ID};
+
+/// Environment file loaded by the CLI on startup.
+const ENV_PATH: &str = ".env";
+
+/// Environment variable holding the Ethereum sender's private key.
+const ETHEREUM_SENDER_KEY_VAR: &str = "ETHEREUM_SENDER_PRIVATE_KEY";
+
+/// Environment variable holding the Cosmos sender's private key.
+const COSMOS_SENDER_KEY_VAR: &str = "COSMOS_SENDER_PRIVATE_KEY";
+
+/// Environment variable holding the Substrate sender's seed or secret URI.
+const SUBSTRATE_SENDER_KEY_VAR: &str = "SUBSTRATE_SENDER_SEED";
+
+/// Environment variable holding the Cardano sender's signing key.
+const CARDANO_SENDER_KEY_VAR: &str = "CARDANO_SENDER_SIGNING_KEY";
+
+/// Environment variable holding the Tron sender's private key.
+const TRON_SENDER_KEY_VAR: &str = "TRON_SENDER_PRIVATE_KEY";
+
+/// Environment variable holding the Hedera sender's ECDSA private key.
+const HEDERA_SENDER_KEY_VAR: &str = "HEDERA_SENDER_PRIVATE_KEY";
+
+/// Runs the wizard and writes the escrow parameters to `params_path`.
+///
+/// Returns the parameters written, or `None` if the user declined to
+/// overwrite an existing file.
+pub fn run(params_path: &Path) -> anyhow::Result<Option<EscrowParams>> {
+    let chain = match Select::new()
+        .with_prompt("Chain")
+        .items(&[
+            "Ethereum",
+            "Solana",
+            "Cosmos",
+            "Substrate",
+            "Cardano",
+            "Tron",
+            "Hedera",
+        ])
+        .default(0)
+        .interact()?
+    {
+        0 => Chain::Ethereum,
+        1 => Chain::Solana,
+        2 => Chain::Cosmos,
+        3 => Chain::Substrate,
+        4 => Chain::Cardano,
+        5 => Chain::Tron,
+        _ => Chain::Hedera,
+    };
+
+    let rpc_url: String = Input::new()
+        .with_prompt(match chain {
+            Chain::Cosmos => "LCD (REST) URL",
+            Chain::Substrate => "Node WebSocket URL",
+            Chain::Cardano => "Blockfrost API URL",
+            Chain::Tron => "Full node HTTP API URL",
+            Chain::Hedera => "JSON-RPC relay URL",
+            _ => "RPC URL",
+        })
+        .default(
+            match chain {
+                Chain::Ethereum => "http://localhost:8545",
+                Chain::Solana => "http://localhost:8899",
+                Chain::Cosmos => "http://localhost:1317",
+                Chain::Substrate => "ws://localhost:9944",
+                Chain::Cardano => "https://cardano-preprod.blockfrost.io/api/v0",
+                Chain::Tron => "https://api.shasta.trongrid.io",
+                Chain::Hedera => "https://testnet.hashio.io/api",
+            }
+            .into(),
+        )
+        .validate_with(|url: &String| Url::parse(url).map(|_| ()).map_err(|e| e.to_string()))
+        .interact_text()?;
+
+    let (params, sender_key) = match chain {
+        Chain::Ethereum => ethereum_params(rpc_url)?,
+        Chain::Solana => (solana_params(rpc_url)?, None),
+        #[cfg(feature = "cosmos")]
+        Chain::Cosmos => cosmos_params(rpc_url)?,
+        #[cfg(not(feature = "cosmos"))]
+        Chain::Cosmos => anyhow::bail!("Cosmos requires the `cosmos` feature"),
+        #[cfg(feature = "substrate")]
+        Chain::Substrate => substrate_params(rpc_url)?,
+        #[cfg(not(feature = "substrate"))]
+        Chain::Substrate => anyhow::bail!("Substrate requires the `substrate` feature"),
+        #[cfg(feature = "cardano")]
+        Chain::Cardano => cardano_params(rpc_url)?,
+        #[cfg(not(feature = "cardano"))]
+        Chain::Cardano => anyhow::bail!("Cardano requires the `cardano` feature"),
+        Chain::Tron => tron_params(rpc_url)?,
+        Chain::Hedera => hedera_params(rpc_url)?,
+    };
+    let key_var = match chain {
+        Chain::Cosmos => COSMOS_SENDER_KEY_VAR,
+        Chain::Substrate => SUBSTRATE_SENDER_KEY_VAR,
+        Chain::Cardano => CARDANO_SENDER_KEY_VAR,
+        Chain::Tron => TRON_SENDER_KEY_VAR,
+        Chain::Hedera => HEDERA_SENDER_KEY_VAR,
+        _ => ETHEREUM_SENDER_KEY_VAR,
+    };
+
+    if params_path.exists()
+        && !Confirm::new()
+            .with_prompt(format!("Overwrite {}?", params_path.display()))
+            .default(false)
+            .interact()?
+    {
+        eprintln!("Aborted; nothing written");
+        return Ok(None);
+    }
+    if let Some(key) = sender_key {
+        write_env_var(Path::new(ENV_PATH), key_var, &key)?;
+        eprintln!("Saved the sender key as {key_var} in {ENV_PATH}");
+    }
+    save_escrow_data(params_path, &params)?;
+    eprintln!("Wrote {}", params_path.display());
+    Ok(Some(params))
+}
+
+/// Prompts for the Ethereum-specific settings, returning the parameters
+/// and the sender's private key if one was entered.
+fn ethereum_params(rpc_url: String) -> anyhow::Result<(EscrowParams, Option<String>)> {
+    let agent_id = prompt_parsed::<Address>("Escrow contract address")?;
+
+    let (sender_private_id, sender, sender_key) = match Select::new()
+        .with_prompt("Sender key")
+        .items(&["Local private key", "AWS KMS key", "GCP Cloud KMS key"])
+        .default(0)
+        .interact()?
+    {
+        0 => {
+            let key = Password::new()
+                .with_prompt("Sender private key (hex)")
+                .validate_with(|key: &String| {
+                    key.parse::<PrivateKeySigner>()
+                        .map(|_| ())
+                        .map_err(|e| e.to_string())
+                })
+                .interact()?;
+            let address = key.parse::<PrivateKeySigner>()?.address();
+            eprintln!("Sender address: {address}");
+            (
+                format!("${{{ETHEREUM_SENDER_KEY_VAR}}}"),
+                address.to_string(),
+                Some(key),
+            )
+        }
+        choice => {
+            let (prefix, label) = match choice {
+                1 => ("aws-kms", "AWS KMS key ID"),
+                _ => ("gcp-kms", "GCP KMS key version name"),
+            };
+            let key: String = Input::new().with_prompt(label).interact_text()?;
+            let address = prompt_parsed::<Address>("Sender address")?;
+            (format!("{prefix}:{key}"), address, None)
+        }
+    };
+    let recipient = prompt_parsed::<Address>("Recipient address")?;
+    let amount = prompt_amount("Amount (wei)")?;
+    let (finish_after, cancel_after) = prompt_timelocks("block")?;
+
+    let params = finish_params(
+        ChainConfig {
+            chain: Chain::Ethereum,
+            rpc_url,
+            fallback_rpc_urls: Vec::new(),
+            sender_private_id,
+            agent_id,
+            pubsub_url: None,
+            network: None,
+            smart_account: None,
+            cosmos: None,
+            substrate: None,
+            cardano: None,
+            tron: None,
+            zksync: None,
+            hedera: None,
+            custom_chain: None,
+        },
+        Asset::native(amount),
+        &sender,
+        &recipient,
+        finish_after,
+        cancel_after,
+    )?;
+    Ok((params, sender_key))
+}
+
+/// Prompts for the Solana-specific settings.
+fn solana_params(rpc_url: String) -> anyhow::Result<EscrowParams> {
+    let agent_id = prompt_parsed::<Pubkey>("Escrow program ID")?;
+
+    let default_keypair = std::env::var("HOME")
+        .map(|home| format!("{home}/.config/solana/id.json"))
+        .unwrap_or_default();
+    let keypair_path: String = Input::new()
+        .with_prompt("Sender keypair file")
+        .default(default_keypair)
+        .validate_with(|path: &String| {
+            read_keypair_file(path)
+                .map(|_| ())
+                .map_err(|e| format!("cannot read keypair {path}: {e}"))
+        })
+        .interact_text()?;
+    let sender = read_keypair_file(&keypair_path)
+        .map_err(|e| anyhow::anyhow!("reading keypair {keypair_path}: {e}"))?
+        .pubkey()
+        .to_string();
+    eprintln!("Sender public key: {sender}");
+
+    let recipient = prompt_parsed::<Pubkey>("Recipient public key")?;
+    let asset = match Select::new()
+        .with_prompt("Asset")
+        .items(&["Native SOL", "Stake account"])
+        .default(0)
+        .interact()?
+    {
+        0 => Asset::native(prompt_amount("Amount (lamports)")?),
+        _ => {
+            let stake_account = prompt_parsed::<Pubkey>("Stake account")?;
+            let lamports = prompt_amount("Stake account balance (lamports)")?;
+            Asset::stake(ID::from_str(&stake_account)?, lamports)
+        }
+    };
+    let (finish_after, cancel_after) = prompt_timelocks("slot")?;
+
+    finish_params(
+        ChainConfig {
+            chain: Chain::Solana,
+            rpc_url,
+            fallback_rpc_urls: Vec::new(),
+            sender_private_id: keypair_path,
+            agent_id,
+            pubsub_url: None,
+            network: None,
+            smart_account: None,
+            cosmos: None,
+            substrate: None,
+            cardano: None,
+            tron: None,
+            zksync: None,
+            hedera: None,
+            custom_chain: None,
+        },
+        asset,
+        &sender,
+        &recipient,
+        finish_after,
+        cancel_after,
+    )
+}
+
+/// Prompts for the Cosmos-specific settings, returning the parameters and
+/// the sender's private key.
+#[cfg(feature = "cosmos")]
+fn cosmos_params(rpc_url: String) -> anyhow::Result<(EscrowParams, Option<String>)> {
+    let agent_id = prompt_parsed::<AccountId>("Escrow contract address")?;
+    let chain_id: String = Input::new().with_prompt("Chain ID").interact_text()?;
+    let denom: String = Input::new()
+        .with_prompt("Fee and native asset denom")
+        .interact_text()?;
+    let prefix = agent_id
+        .parse::<AccountId>()
+        .map_err(|e| anyhow::anyhow!("{e}"))?
+        .prefix()
+        .to_owned();
+    let gas_price: f64 = Input::new()
+        .with_prompt(format!("Gas price ({denom})"))
+        .interact_text()?;
+
+    let key = Password::new()
+        .with_prompt("Sender private key (hex)")
+        .validate_with(|key: &String| {
+            CosmosSigner::from_id(key)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .interact()?;
+    let sender = CosmosSigner::from_id(&key)?
+        .account_id(&prefix)?
+        .to_string();
+    eprintln!("Sender address: {sender}");
+
+    let recipient = prompt_parsed::<AccountId>("Recipient address")?;
+    let amount = prompt_amount(&format!("Amount ({denom})"))?;
+    let (finish_after, cancel_after) = prompt_timelocks("block")?;
+
+    let params = finish_params(
+        ChainConfig {
+            chain: Chain::Cosmos,
+            rpc_url,
+            fallback_rpc_urls: Vec::new(),
+            sender_private_id: format!("${{{COSMOS_SENDER_KEY_VAR}}}"),
+            agent_id,
+            pubsub_url: None,
+            network: None,
+            smart_account: None,
+            cosmos: Some(CosmosConfig {
+                chain_id,
+                denom,
+                prefix,
+                gas_price,
+                gas_adjustment: CosmosConfig::DEFAULT_GAS_ADJUSTMENT,
+                ibc_settler: None,
+            }),
+            substrate: None,
+            cardano: None,
+            tron: None,
+            zksync: None,
+            hedera: None,
+            custom_chain: None,
+        },
+        Asset::native(amount),
+        &sender,
+        &recipient,
+        finish_after,
+        cancel_after,
+    )?;
+    Ok((params, Some(key)))
+}
+
+/// Prompts for the Substrate-specific settings, returning the parameters
+/// and the sender's seed or secret URI.
+#[cfg(feature = "substrate")]
+fn substrate_params(rpc_url: String) -> anyhow::Result<(EscrowParams, Option<String>)> {
+    let agent_id = prompt_parsed::<AccountId32>("Escrow contract address")?;
+    let prefix = ID::Ss58(agent_id.clone())
+        .ss58_prefix()
+        .context("escrow contract address is not an SS58 address")?;
+
+    let key = Password::new()
+        .with_prompt("Sender seed (hex) or secret URI")
+        .validate_with(|key: &String| {
+            SubstrateSigner::from_id(key)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .interact()?;
+    let sender = SubstrateSigner::from_id(&key)?.address(prefix);
+    eprintln!("Sender address: {sender}");
+
+    let recipient = prompt_parsed::<AccountId32>("Recipient address")?;
+    let amount = prompt_amount("Amount (planck)")?;
+    let (finish_after, cancel_after) = prompt_timelocks("block")?;
+
+    let params = finish_params(
+        ChainConfig {
+            chain: Chain::Substrate,
+            rpc_url,
+            fallback_rpc_urls: Vec::new(),
+            sender_private_id: format!("${{{SUBSTRATE_SENDER_KEY_VAR}}}"),
+            agent_id,
+            pubsub_url: None,
+            network: None,
+            smart_account: None,
+            cosmos: None,
+            substrate: Some(SubstrateConfig::default()),
+            cardano: None,
+            tron: None,
+            zksync: None,
+            hedera: None,
+            custom_chain: None,
+        },
+        Asset::native(amount),
+        &sender,
+        &recipient,
+        finish_after,
+        cancel_after,
+    )?;
+    Ok((params, Some(key)))
+}
+
+/// Prompts for the Cardano-specific settings, returning the parameters and
+/// the sender's signing key.
+#[cfg(feature = "cardano")]
+fn cardano_params(rpc_url: String) -> anyhow::Result<(EscrowParams, Option<String>)> {
+    let network = match Select::new()
+        .with_prompt("Network")
+        .items(&["Preprod", "Preview", "Mainnet"])
+        .default(0)
+        .interact()?
+    {
+        0 => CardanoNetwork::Preprod,
+        1 => CardanoNetwork::Preview,
+        _ => CardanoNetwork::Mainnet,
+    };
+    let agent_id = prompt_cardano_address("Escrow validator script address")?;
+    let reference_script: String = Input::new()
+        .with_prompt("Reference script UTxO (<tx hash>#<index>)")
+        .validate_with(|utxo: &String| {
+            utxo.split_once('#')
+                .filter(|(hash, index)| hash.len() == 64 && index.parse::<u32>().is_ok())
+                .map(|_| ())
+                .ok_or("expected <tx hash>#<index>")
+        })
+        .interact_text()?;
+    let project_id: String = Input::new()
+        .with_prompt("Blockfrost project ID (optional)")
+        .allow_empty(true)
+        .interact_text()?;
+
+    let key = Password::new()
+        .with_prompt("Sender ed25519 signing key (hex or ed25519_sk1...)")
+        .validate_with(|key: &String| {
+            CardanoSigner::from_id(key)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .interact()?;
+    let sender = CardanoSigner::from_id(&key)?.address(network);
+    eprintln!("Sender address: {sender} (fund it before creating escrows)");
+
+    let recipient = prompt_cardano_address("Recipient address")?;
+    let amount = prompt_amount("Amount (lovelace)")?;
+    let (finish_after, cancel_after) = prompt_timelocks("slot")?;
+
+    let params = finish_params(
+        ChainConfig {
+            chain: Chain::Cardano,
+            rpc_url,
+            fallback_rpc_urls: Vec::new(),
+            sender_private_id: format!("${{{CARDANO_SENDER_KEY_VAR}}}"),
+            agent_id,
+            pubsub_url: None,
+            network: None,
+            smart_account: None,
+            cosmos: None,
+            substrate: None,
+            cardano: Some(CardanoConfig {
+                network,
+                reference_script,
+                project_id: (!project_id.is_empty()).then_some(project_id),
+            }),
+            tron: None,
+            zksync: None,
+            hedera: None,
+            custom_chain: None,
+        },
+        Asset::native(amount),
+        &sender,
+        &recipient,
+        finish_after,
+        cancel_after,
+    )?;
+    Ok((params, Some(key)))
+}
+
+/// Prompts for the Tron-specific settings, returning the parameters and
+/// the sender's private key.
+fn tron_params(rpc_url: String) -> anyhow::Result<(EscrowParams, Option<String>)> {
+    let agent_id = prompt_tron_address("Escrow contract address")?;
+    let api_key: String = Input::new()
+        .with_prompt("TronGrid API key (optional)")
+        .allow_empty(true)
+        .interact_text()?;
+
+    let key = Password::new()
+        .with_prompt("Sender private key (hex)")
+        .validate_with(|key: &String| {
+            key.parse::<PrivateKeySigner>()
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .interact()?;
+    let sender = ID::tron(key.parse::<PrivateKeySigner>()?.address().into()).to_string();
+    eprintln!("Sender address: {sender} (fund it before creating escrows)");
+
+    let recipient = prompt_tron_address("Recipient address")?;
+    let amount = prompt_amount("Amount (sun)")?;
+    let (finish_after, cancel_after) = prompt_timelocks("block")?;
+
+    let params = finish_params(
+        ChainConfig {
+            chain: Chain::Tron,
+            rpc_url,
+            fallback_rpc_urls: Vec::new(),
+            sender_private_id: format!("${{{TRON_SENDER_KEY_VAR}}}"),
+            agent_id,
+            pubsub_url: None,
+            network: None,
+            smart_account: None,
+            cosmos: None,
+            substrate: None,
+            cardano: None,
+            tron: Some(TronConfig {
+                api_key: (!api_key.is_empty()).then_some(api_key),
+                ..TronConfig::default()
+            }),
+            zksync: None,
+            hedera: None,
+            custom_chain: None,
+        },
+        Asset::native(amount),
+        &sender,
+        &recipient,
+        finish_after,
+        cancel_after,
+    )?;
+    Ok((params, Some(key)))
+}
+
+/// Prompts for the Hedera-specific settings, returning the parameters and
+/// the sender's ECDSA private key.
+fn hedera_params(rpc_url: String) -> anyhow::Result<(EscrowParams, Option<String>)> {
+    let agent_id = prompt_parsed::<Address>("Escrow contract EVM address")?;
+    let mirror_url: String = Input::new()
+        .with_prompt("Mirror node URL")
+        .default("https://testnet.mirrornode.hedera.com".into())
+        .validate_with(|url: &String| Url::parse(url).map(|_| ()).map_err(|e| e.to_string()))
+        .interact_text()?;
+
+    let key = Password::new()
+        .with_prompt("Sender ECDSA private key (hex)")
+        .validate_with(|key: &String| {
+            key.parse::<PrivateKeySigner>()
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .interact()?;
+    let sender = key.parse::<PrivateKeySigner>()?.address().to_string();
+    eprintln!("Sender EVM address: {sender} (fund it before creating escrows)");
+
+    let recipient = prompt_hedera_party("Recipient account ID (0.0.x) or EVM address")?;
+    let amount = prompt_amount("Amount (tinybars)")?;
+    let (finish_after, cancel_after) = prompt_timelocks("block")?;
+
+    let params = finish_params(
+        ChainConfig {
+            chain: Chain::Hedera,
+            rpc_url,
+            fallback_rpc_urls: Vec::new(),
+            sender_private_id: format!("${{{HEDERA_SENDER_KEY_VAR}}}"),
+            agent_id,
+            pubsub_url: None,
+            network: None,
+            smart_account: None,
+            cosmos: None,
+            substrate: None,
+            cardano: None,
+            tron: None,
+            zksync: None,
+            hedera: Some(HederaConfig { mirror_url }),
+            custom_chain: None,
+        },
+        Asset::native(amount),
+        &sender,
+        &recipient,
+        finish_after,
+        cancel_after,
+    )?;
+    Ok((params, Some(key)))
+}
+
+/// Prompts for the chain-agnostic remainder and assembles the parameters.
+fn finish_params(
+    chain_config: ChainConfig,
+    asset: Asset,
+    sender: &str,
+    recipient: &str,
+    finish_after: Option<u64>,
+    cancel_after: Option<u64>,
+) -> anyhow::Result<EscrowParams> {
+    let has_conditions = Confirm::new()
+        .with_prompt("Release subject to cryptographic conditions?")
+        .default(false)
+        .interact()?;
+    // Only some chains store memos; the others reject them.
+    let memo: String = if zescrow_client::stores_memo(chain_config.chain) {
+        Input::new()
+            .with_prompt("Memo (optional)")
+            .allow_empty(true)
+            .validate_with(|memo: &String| {
+                (memo.len() <= MAX_MEMO_LEN)
+                    .then_some(())
+                    .ok_or_else(|| format!("memo must be at most {MAX_MEMO_LEN} bytes"))
+            })
+            .interact_text()?
+    } else {
+        String::new()
+    };
+
+    Ok(EscrowParams {
+        chain_config,
+        asset,
+        sender: Party::new(sender)?,
+        recipient: Party::new(recipient)?,
+        finish_after,
+        cancel_after,
+        has_conditions,
+        memo: (!memo.is_empty()).then_some(memo),
+        rent_destination: None,
+        recipient_contact: None,
+    })
+}
+
+/// Prompts until the answer parses as `T`, returning the answer as entered.
+fn prompt_parsed<T>(prompt: &str) -> anyhow::Result<String>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    Input::new()
+        .with_prompt(prompt)
+        .validate_with(|s: &String| s.parse::<T>().map(|_| ()).map_err(|e| e.to_string()))
+        .interact_text()
+        .map_err(Into::into)
+}
+
+/// Prompts until the answer is a Bech32 Cardano address.
+#[cfg(feature = "cardano")]
+fn prompt_cardano_address(prompt: &str) -> anyhow::Result<String> {
+    Input::new()
+        .with_prompt(prompt)
+        .validate_with(|s: &String| {
+            pallas_addresses::Address::from_bech32(s)
+                .map(|_| ())
+                .map_err(|e| e.to_string())
+        })
+        .interact_text()
+        .map_err(Into::into)
+}
+
+/// Prompts until the answer is a base58check Tron address.
+fn prompt_tron_address(prompt: &str) -> anyhow::Result<String> {
+    Input::new()
+        .with_prompt(prompt)
+        .validate_with(|s: &String| {
+            ID::Base58(s.clone())
+                .tron_account()
+                .map(|_| ())
+                .ok_or("not a base58check Tron address")
+        })
+        .interact_text()
+        .map_err(Into::into)
+}
+
+/// Prompts until the answer is a Hedera account ID (`0.0.x`) or an EVM
+/// address.
+fn prompt_hedera_party(prompt: &str) -> anyhow::Result<String> {
+    Input::new()
+        .with_prompt(prompt)
+        .validate_with(|s: &String| match ID::from_str(s) {
+            Ok(ID::Hedera(_)) => Ok(()),
+            _ => Address::from_str(s)
+                .map(|_| ())
+                .map_err(|_| "not a Hedera account ID or EVM address"),
+        })
+        .interact_text()
+        .map_err(Into::into)
+}
+
+/// Prompts for a non-zero amount in the asset's smallest unit.
+fn prompt_amount(prompt: &str) -> anyhow::Result<BigNumber> {
+    let amount: u128 = Input::new()
+        .with_prompt(prompt)
+        .validate_with(|amount: &u128| {
+            (*amount > 0)
+                .then_some(())
+                .ok_or("amount must be greater than zero")
+        })
+        .interact_text()?;
+    Ok(BigNumber::from(amount))
+}
+
+/// Prompts for optional `finish_after` and `cancel_after` heights, where
+/// `unit` is "block" or "slot", ensuring finish comes before cancel.
+fn prompt_timelocks(unit: &str) -> anyhow::Result<(Option<u64>, Option<u64>)> {
+    let finish_after = prompt_height(&format!("Finish after {unit} (empty for none)"), None)?;
+    let cancel_after = prompt_height(
+        &format!("Cancel after {unit} (empty to disable cancellation)"),
+        finish_after,
+    )?;
+    Ok((finish_after, cancel_after))
+}
+
+/// Prompts for an optional height, which must exceed `after` if set.
+fn prompt_height(prompt: &str, after: Option<u64>) -> anyhow::Result<Option<u64>> {
+    let height: String = Input::new()
+        .with_prompt(prompt)
+        .allow_empty(true)
+        .validate_with(|s: &String| {
+            if s.is_empty() {
+                return Ok(());
+            }
+            let height = s.parse::<u64>().map_err(|e| e.to_string())?;
+            match after {
+                Some(after) if height <= after => {
+                    Err(format!("must be greater than finish_after ({after})"))
+                }
+                _ => Ok(()),
+            }
+        })
+        .interact_text()?;
+    Ok((!height.is_empty()).then(|| height.parse()).transpose()?)
+}
+
+/// Sets `key` to `value` in the env file at `path`, replacing an existing
+/// assignment or appending one. New files are readable by the owner only.
+fn write_env_var(path: &Path, key: &str, value: &str) -> anyhow::Result<()> {
+    let existing = match fs::read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e).with_context(|| format!("reading {path:?}")),
+    };
+
+    let assignment = format!("{key}={value}");
+    let prefix = format!("{key}=");
+    let mut lines: Vec<String> = existing
+        .as_deref()
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_owned)
+        .collect();
+    match lines.iter_mut().find(|line| line.starts_with(&prefix)) {
+        Some(line) => *line = assignment,
+        None => lines.push(assignment),
+    }
+    fs::write(path, lines.join("\n") + "\n").with_context(|| format!("writing {path:?}"))?;
+
+    #[cfg(unix)]
+    if existing.is_none() {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .with_context(|| format!("restricting permissions of {path:?}"))?;
+    }
+    info!(?path, key, "Updated env file");
+    Ok(())
+}
@@ -0,0 +1,381 @@
+//! Write-ahead journal of escrow operations.
+//!
+//! A crash between broadcasting a transaction and recording its outcome
+//! (e.g. writing `escrow_metadata.json`) would lose track of a created
+//! escrow, and retrying would create it twice. Recording each operation as
+//! pending before it is sent, and completing it once its outcome is
+//! recorded, leaves a trace of every interrupted operation; [`reconcile`]
+//! then checks the chain to tell whether its transaction landed.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let journal = Journal::open("journal.db")?;
+//! for entry in journal.pending()? {
+//!     match reconcile(&client, &entry.op).await? {
+//!         Reconciled::Created(metadata) => { /* record metadata */ }
+//!         Reconciled::Settled => { /* record settlement */ }
+//!         Reconciled::NotLanded => {
+//!             journal.abandon(entry.id)?;
+//!             continue;
+//!         }
+//!     }
+//!     journal.complete(entry.id)?;
+//! }
+//!
+//! let op = JournalOp::Create(params.clone());
+//! let id = journal.begin(&op, None)?;
+//! let metadata = client.create_escrow(&params).await?;
+//! // ... record metadata ...
+//! journal.complete(id)?;
+//! ```
+//!
+//! The journal is kept in SQLite and requires the `sqlite` feature; without
+//! it, operations are not journaled.
+
+use std::path::Path;
+#[cfg(feature = "sqlite")]
+use std::sync::{Mutex, MutexGuard, PoisonError};
+#[cfg(feature = "sqlite")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "sqlite")]
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use zescrow_core::{
+    Chain, ChainConfig, ChainMetadata, EscrowMetadata, EscrowParams, ExecutionState,
+};
+
+#[cfg(feature = "sqlite")]
+use crate::ClientError;
+use crate::{Result, ZescrowClient};
+
+/// Tables created on first use.
+#[cfg(feature = "sqlite")]
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS journal (
+    id          INTEGER PRIMARY KEY AUTOINCREMENT,
+    operation   TEXT NOT NULL,
+    escrow      INTEGER,
+    state       TEXT NOT NULL,
+    started_at  INTEGER NOT NULL,
+    finished_at INTEGER
+);
+";
+
+/// Journal entry states.
+#[cfg(feature = "sqlite")]
+const PENDING: &str = "pending";
+#[cfg(feature = "sqlite")]
+const COMPLETED: &str = "completed";
+#[cfg(feature = "sqlite")]
+const ABANDONED: &str = "abandoned";
+
+/// An escrow operation recorded before it is sent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "operation", content = "data", rename_all = "snake_case")]
+pub enum JournalOp {
+    /// Create an escrow with these parameters.
+    Create(EscrowParams),
+    /// Finish this escrow.
+    Finish(EscrowMetadata),
+    /// Cancel this escrow.
+    Cancel(EscrowMetadata),
+}
+
+impl JournalOp {
+    /// Chain configuration the operation runs against.
+    pub fn chain_config(&self) -> &ChainConfig {
+        match self {
+            Self::Create(params) => &params.chain_config,
+            Self::Finish(metadata) | Self::Cancel(metadata) => &metadata.params.chain_config,
+        }
+    }
+
+    /// Operation name, e.g. `create`.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Create(_) => "create",
+            Self::Finish(_) => "finish",
+            Self::Cancel(_) => "cancel",
+        }
+    }
+}
+
+/// A pending operation in the journal.
+#[derive(Debug, Clone)]
+pub struct JournalEntry {
+    /// Journal identifier, assigned in order.
+    pub id: i64,
+    /// The operation.
+    pub op: JournalOp,
+    /// Local identifier of the escrow in the [`EscrowStore`](crate::EscrowStore),
+    /// if any.
+    pub escrow: Option<i64>,
+    /// When the operation was recorded, in seconds since the Unix epoch.
+    pub started_at: u64,
+}
+
+/// Whether an interrupted operation took effect on-chain.
+#[derive(Debug, Clone)]
+pub enum Reconciled {
+    /// The escrow was created; its metadata is recovered.
+    Created(EscrowMetadata),
+    /// The escrow is settled, so the finish or cancel landed (or the escrow
+    /// was settled otherwise).
+    Settled,
+    /// The transaction did not land; the operation can be retried.
+    NotLanded,
+}
+
+/// SQLite-backed write-ahead journal of escrow operations.
+pub struct Journal {
+    #[cfg(feature = "sqlite")]
+    conn: Mutex<Connection>,
+}
+
+#[cfg(feature = "sqlite")]
+impl Journal {
+    /// Opens the journal at `path`, creating it if needed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Opens a journal held in memory, discarded when dropped.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Records `op` as pending before it is sent, returning its journal
+    /// identifier. `escrow` is the escrow's local identifier in the store,
+    /// if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::Journal`] if the same operation is already
+    /// pending, since it may have landed.
+    pub fn begin(&self, op: &JournalOp, escrow: Option<i64>) -> Result<i64> {
+        let operation =
+            serde_json::to_string(op).map_err(|e| ClientError::Serialization(e.to_string()))?;
+        let conn = self.lock();
+        let pending: Option<i64> = conn
+            .query_row(
+                "SELECT id FROM journal WHERE state = ?1 AND operation = ?2",
+                params![PENDING, operation],
+                |row| row.get(0),
+            )
+            .optional()?;
+        if let Some(id) = pending {
+            return Err(ClientError::Journal(format!(
+                "an identical {} (journal entry {id}) may have landed and has not been \
+                 reconciled yet",
+                op.name()
+            )));
+        }
+        conn.execute(
+            "INSERT INTO journal (operation, escrow, state, started_at)
+             VALUES (?1, ?2, ?3, ?4)",
+            params![operation, escrow, PENDING, now()],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    /// Marks operation `id` as completed, once its outcome is recorded.
+    pub fn complete(&self, id: i64) -> Result<()> {
+        self.finish(id, COMPLETED)
+    }
+
+    /// Marks operation `id` as abandoned, once it is known not to have
+    /// landed.
+    pub fn abandon(&self, id: i64) -> Result<()> {
+        self.finish(id, ABANDONED)
+    }
+
+    /// Returns the pending operations, oldest first.
+    pub fn pending(&self) -> Result<Vec<JournalEntry>> {
+        let conn = self.lock();
+        let mut query = conn.prepare(
+            "SELECT id, operation, escrow, started_at FROM journal
+             WHERE state = ?1 ORDER BY id",
+        )?;
+        let rows = query
+            .query_map([PENDING], |row| {
+                Ok((
+                    row.get::<_, i64>(0)?,
+                    row.get::<_, String>(1)?,
+                    row.get::<_, Option<i64>>(2)?,
+                    row.get::<_, u64>(3)?,
+                ))
+            })?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows.into_iter()
+            .map(|(id, operation, escrow, started_at)| {
+                let op = serde_json::from_str(&operation)
+                    .map_err(|e| ClientError::Journal(format!("decoding entry {id}: {e}")))?;
+                Ok(JournalEntry {
+                    id,
+                    op,
+                    escrow,
+                    started_at,
+                })
+            })
+            .collect()
+    }
+
+    fn finish(&self, id: i64, state: &str) -> Result<()> {
+        let updated = self.lock().execute(
+            "UPDATE journal SET state = ?2, finished_at = ?3 WHERE id = ?1 AND state = ?4",
+            params![id, state, now(), PENDING],
+        )?;
+        if updated == 0 {
+            return Err(ClientError::Journal(format!("no pending entry {id}")));
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Without the `sqlite` feature, the journal records nothing and has no
+/// pending operations.
+#[cfg(not(feature = "sqlite"))]
+impl Journal {
+    pub fn open(_path: impl AsRef<Path>) -> Result<Self> {
+        Ok(Self {})
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Ok(Self {})
+    }
+
+    pub fn begin(&self, _op: &JournalOp, _escrow: Option<i64>) -> Result<i64> {
+        Ok(0)
+    }
+
+    pub fn complete(&self, _id: i64) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn abandon(&self, _id: i64) -> Result<()> {
+        Ok(())
+    }
+
+    pub fn pending(&self) -> Result<Vec<JournalEntry>> {
+        Ok(Vec::new())
+    }
+}
+
+impl std::fmt::Debug for Journal {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Journal").finish_non_exhaustive()
+    }
+}
+
+/// Checks whether the interrupted operation `op` took effect, using
+/// `client` built for its chain configuration.
+///
+/// A create is found by its escrow account on Solana, and on Ethereum,
+/// Cosmos, and Substrate by the most recent unsettled escrow of the sender matching its recipient,
+/// amount, and timelocks; an identical older escrow is indistinguishable.
+/// On Cardano it is found by an unspent escrow UTxO matching the same
+/// fields, with identical UTxOs likewise indistinguishable.
+/// A finish or cancel landed if the escrow is settled.
+///
+/// # Errors
+///
+/// Returns an error if the escrow's state cannot be queried.
+pub async fn reconcile(client: &ZescrowClient, op: &JournalOp) -> Result<Reconciled> {
+    match op {
+        JournalOp::Create(params) => {
+            let metadata = EscrowMetadata {
+                params: params.clone(),
+                state: ExecutionState::Funded,
+                escrow_id: None,
+                uid: None,
+                chain_metadata: None,
+                guest: crate::pinned_guest(),
+                observed: None,
+            };
+            match params.chain_config.chain {
+                Chain::Solana => {
+                    // Escrow accounts are derived from the parties, and a
+                    // missing account counts as settled.
+                    let status = client.get_escrow_state(&metadata).await?;
+                    Ok(if status.settled {
+                        Reconciled::NotLanded
+                    } else {
+                        Reconciled::Created(metadata)
+                    })
+                }
+                Chain::Ethereum
+                | Chain::Cosmos
+                | Chain::Substrate
+                | Chain::Cardano
+                | Chain::Tron
+                | Chain::Hedera => {
+                    let same = |a: &str, b: &str| a.eq_ignore_ascii_case(b);
+                    let candidates = client.list_escrows().await?.into_iter().filter(|escrow| {
+                        !escrow.status.settled
+                            && same(&escrow.sender, &params.sender.to_string())
+                            && same(&escrow.recipient, &params.recipient.to_string())
+                            && escrow.status.amount == params.asset.amount
+                            && escrow.status.finish_after == params.finish_after
+                            && escrow.status.cancel_after == params.cancel_after
+                    });
+                    if matches!(params.chain_config.chain, Chain::Cardano) {
+                        // Escrows are UTxOs, listed as `<tx hash>#<index>`.
+                        let utxo = candidates.into_iter().find_map(|escrow| {
+                            let (tx_hash, index) = escrow.id.split_once('#')?;
+                            Some(ChainMetadata::Cardano {
+                                tx_hash: tx_hash.to_owned(),
+                                output_index: index.parse().ok()?,
+                            })
+                        });
+                        return Ok(match utxo {
+                            Some(utxo) => Reconciled::Created(EscrowMetadata {
+                                chain_metadata: Some(utxo),
+                                ..metadata
+                            }),
+                            None => Reconciled::NotLanded,
+                        });
+                    }
+                    let escrow_id = candidates
+                        .filter_map(|escrow| escrow.id.parse::<u64>().ok())
+                        .max();
+                    Ok(match escrow_id {
+                        Some(escrow_id) => Reconciled::Created(EscrowMetadata {
+                            escrow_id: Some(escrow_id),
+                            ..metadata
+                        }),
+                        None => Reconciled::NotLanded,
+                    })
+                }
+            }
+        }
+        JournalOp::Finish(metadata) | JournalOp::Cancel(metadata) => {
+            let status = client.get_escrow_state(metadata).await?;
+            Ok(if status.settled {
+                Reconciled::Settled
+            } else {
+                Reconciled::NotLanded
+            })
+        }
+    }
+}
+
+/// Seconds since the Unix epoch.
+#[cfg(feature = "sqlite")]
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
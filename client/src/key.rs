@@ -0,0 +1,523 @@
+//! `key` commands managing the encrypted keystore.
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{anyhow, bail, Context};
+use clap::{value_parser, Args, Subcommand};
+use dialoguer::Password;
+use serde::Serialize;
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_sdk::native_token::sol_to_lamports;
+use solana_sdk::signature::{read_keypair_file, Keypair, Signer};
+use zescrow_client::keystore::{self, KeyInfo, Keystore, PASSWORD_VAR};
+#[cfg(feature = "cardano")]
+use zescrow_client::CardanoSigner;
+#[cfg(feature = "cosmos")]
+use zescrow_client::CosmosSigner;
+#[cfg(feature = "substrate")]
+use zescrow_client::SubstrateSigner;
+use zescrow_core::{Chain, ID};
+
+use crate::output::{print_json, OutputFormat};
+
+#[derive(Subcommand, Debug)]
+pub enum KeyCmd {
+    /// Encrypt a key into the keystore, to be referred to as
+    /// `keystore:<NAME>` in `sender_private_id` or `--recipient`
+    Import {
+        /// Name to store the key under
+        name: String,
+
+        /// Chain the key signs for (`solana`, `ethereum`, `cosmos`,
+        /// `substrate`, `cardano`, or `tron`)
+        #[arg(long)]
+        chain: Chain,
+
+        /// Solana keypair file, or a file holding a hex Ethereum, Tron,
+        /// Hedera, or Cosmos private key, Substrate seed, or Cardano signing
+        /// key
+        /// (prompted for if omitted, except on Solana)
+        #[arg(long, value_name = "FILE", value_parser = value_parser!(PathBuf))]
+        from: Option<PathBuf>,
+    },
+
+    /// Decrypt a key: a keypair file for Solana, a 0x-prefixed private key
+    /// for Ethereum, Tron, and Hedera, a hex private key for Cosmos, a
+    /// 0x-prefixed seed for Substrate, a hex signing key for Cardano
+    Export {
+        /// Name of the key
+        name: String,
+
+        /// File to write the key to, readable only by its owner (stdout if
+        /// omitted)
+        #[arg(long, value_name = "FILE", value_parser = value_parser!(PathBuf))]
+        outfile: Option<PathBuf>,
+    },
+
+    /// List stored keys with their chain and address
+    List,
+}
+
+/// SS58 prefix of the addresses `keygen` prints for Substrate keys, which
+/// are not tied to a network.
+#[cfg(feature = "substrate")]
+const GENERIC_SS58_PREFIX: u16 = 42;
+
+/// Default RPC endpoint for `keygen --airdrop`.
+const DEVNET_RPC_URL: &str = "https://api.devnet.solana.com";
+
+/// Options of the `keygen` command.
+#[derive(Args, Debug)]
+pub struct KeygenOpts {
+    /// Chain to generate a key for (`solana`, `ethereum`, `cosmos`,
+    /// `substrate`, `cardano`, or `tron`)
+    #[arg(long)]
+    chain: Chain,
+
+    /// File to write the key to, readable only by its owner [default:
+    /// ~/.config/solana/id.json for Solana, deploy/ethereum_key.txt for
+    /// Ethereum, deploy/cosmos_key.txt for Cosmos,
+    /// deploy/substrate_key.txt for Substrate, deploy/cardano_key.txt for
+    /// Cardano, deploy/tron_key.txt for Tron, deploy/hedera_key.txt for
+    /// Hedera]
+    #[arg(long, value_name = "FILE", value_parser = value_parser!(PathBuf))]
+    outfile: Option<PathBuf>,
+
+    /// Overwrite the file if it exists
+    #[arg(long)]
+    force: bool,
+
+    /// Request an airdrop of this many SOL to the new key (Solana devnet or
+    /// localnet only)
+    #[arg(long, value_name = "SOL")]
+    airdrop: Option<f64>,
+
+    /// RPC endpoint to request the airdrop from
+    #[arg(long, value_name = "URL", default_value = DEVNET_RPC_URL, requires = "airdrop")]
+    url: String,
+}
+
+/// Key generated by `keygen`.
+#[derive(Debug, Serialize)]
+struct GeneratedKey {
+    chain: Chain,
+    address: String,
+    path: PathBuf,
+    /// Lamports airdropped to the key, if requested.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    airdrop: Option<u64>,
+}
+
+/// Generates a Solana keypair file or an Ethereum private key file in the
+/// format `sender_private_id` expects, optionally funding it on devnet.
+pub async fn keygen(opts: KeygenOpts, output: OutputFormat) -> anyhow::Result<()> {
+    let path = match opts.outfile {
+        Some(path) => path,
+        None => default_key_path(opts.chain)?,
+    };
+    if path.exists() && !opts.force {
+        bail!(
+            "{} already exists; pass --force to overwrite it",
+            path.display()
+        );
+    }
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir).with_context(|| format!("creating {}", dir.display()))?;
+    }
+
+    let (address, encoded) = match opts.chain {
+        Chain::Solana => {
+            let keypair = Keypair::new();
+            (
+                keypair.pubkey().to_string(),
+                serde_json::to_string(&keypair.to_bytes().to_vec())?,
+            )
+        }
+        Chain::Ethereum => {
+            let signer = PrivateKeySigner::random();
+            (
+                signer.address().to_string(),
+                format!("0x{}", hex::encode(signer.to_bytes())),
+            )
+        }
+        // Addresses depend on the chain's Bech32 prefix, so the public key
+        // stands in for one.
+        #[cfg(feature = "cosmos")]
+        Chain::Cosmos => {
+            let secret = PrivateKeySigner::random().to_bytes();
+            (
+                CosmosSigner::from_bytes(secret.as_slice())?.public_key(),
+                hex::encode(secret),
+            )
+        }
+        #[cfg(not(feature = "cosmos"))]
+        Chain::Cosmos => bail!("Cosmos keys require the `cosmos` feature"),
+        // Addresses depend on the network's SS58 prefix, so the generic
+        // one stands in.
+        #[cfg(feature = "substrate")]
+        Chain::Substrate => {
+            let seed = PrivateKeySigner::random().to_bytes();
+            (
+                SubstrateSigner::from_bytes(seed.as_slice())?.address(GENERIC_SS58_PREFIX),
+                format!("0x{}", hex::encode(seed)),
+            )
+        }
+        #[cfg(not(feature = "substrate"))]
+        Chain::Substrate => bail!("Substrate keys require the `substrate` feature"),
+        // Addresses depend on the network, so the payment key hash stands
+        // in for one.
+        #[cfg(feature = "cardano")]
+        Chain::Cardano => {
+            let secret = PrivateKeySigner::random().to_bytes();
+            (
+                CardanoSigner::from_bytes(secret.as_slice())?
+                    .key_hash()
+                    .to_string(),
+                hex::encode(secret),
+            )
+        }
+        #[cfg(not(feature = "cardano"))]
+        Chain::Cardano => bail!("Cardano keys require the `cardano` feature"),
+        Chain::Tron => {
+            let signer = PrivateKeySigner::random();
+            (
+                ID::tron(signer.address().into()).to_string(),
+                format!("0x{}", hex::encode(signer.to_bytes())),
+            )
+        }
+        // Hedera accounts are created by funding the key's EVM address,
+        // which the network then assigns an account ID.
+        Chain::Hedera => {
+            let signer = PrivateKeySigner::random();
+            (
+                signer.address().to_string(),
+                format!("0x{}", hex::encode(signer.to_bytes())),
+            )
+        }
+    };
+    write_private(&path, encoded.as_bytes()).with_context(|| format!("writing {path:?}"))?;
+
+    let airdrop = match opts.airdrop {
+        Some(sol) => Some(airdrop(&opts.url, opts.chain, &address, sol).await?),
+        None => None,
+    };
+
+    let key = GeneratedKey {
+        chain: opts.chain,
+        address,
+        path,
+        airdrop,
+    };
+    match output {
+        OutputFormat::Text => print_generated(&key),
+        OutputFormat::Json => print_json(&key)?,
+    }
+    Ok(())
+}
+
+/// Returns where `keygen` writes keys by default.
+fn default_key_path(chain: Chain) -> anyhow::Result<PathBuf> {
+    match chain {
+        Chain::Solana => {
+            let home = std::env::var("HOME").context("HOME is not set; pass --outfile")?;
+            Ok(Path::new(&home).join(".config/solana/id.json"))
+        }
+        Chain::Ethereum => {
+            Ok(concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/ethereum_key.txt").into())
+        }
+        Chain::Cosmos => {
+            Ok(concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/cosmos_key.txt").into())
+        }
+        Chain::Substrate => {
+            Ok(concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/substrate_key.txt").into())
+        }
+        Chain::Cardano => {
+            Ok(concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/cardano_key.txt").into())
+        }
+        Chain::Tron => Ok(concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/tron_key.txt").into()),
+        Chain::Hedera => {
+            Ok(concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/hedera_key.txt").into())
+        }
+    }
+}
+
+/// Requests an airdrop of `sol` to `address` and waits for it to land,
+/// returning the lamports received.
+async fn airdrop(url: &str, chain: Chain, address: &str, sol: f64) -> anyhow::Result<u64> {
+    if !matches!(chain, Chain::Solana) {
+        bail!("airdrops are only available on Solana; use a faucet for other testnets");
+    }
+    let pubkey = address.parse()?;
+    let lamports = sol_to_lamports(sol);
+    let client = RpcClient::new(url.to_owned());
+    let signature = client
+        .request_airdrop(&pubkey, lamports)
+        .await
+        .with_context(|| format!("requesting airdrop from {url} (devnet and localnet only)"))?;
+    client
+        .poll_for_signature(&signature)
+        .await
+        .with_context(|| format!("waiting for airdrop {signature}"))?;
+    Ok(lamports)
+}
+
+/// Prints the generated key and where to configure it.
+fn print_generated(key: &GeneratedKey) {
+    println!(
+        "Generated {} key {} in {}",
+        key.chain.as_ref(),
+        key.address,
+        key.path.display()
+    );
+    if let Some(lamports) = key.airdrop {
+        println!("Airdropped {lamports} lamports");
+    }
+    println!("\nAdd to your .env:");
+    match key.chain {
+        Chain::Solana => {
+            println!("  SOLANA_SENDER_KEYPAIR_PATH={}", key.path.display());
+            println!("  SOLANA_SENDER_PUBKEY={}", key.address);
+        }
+        Chain::Ethereum => {
+            println!("  ETHEREUM_SENDER_ADDRESS={}", key.address);
+            println!(
+                "  ETHEREUM_SENDER_PRIVATE_KEY=<contents of {}>",
+                key.path.display()
+            );
+            println!(
+                "\nOr encrypt it with `key import <NAME> --chain ethereum --from {}`, and set \
+                 ETHEREUM_SENDER_PRIVATE_KEY=keystore:<NAME>",
+                key.path.display()
+            );
+        }
+        Chain::Cosmos => {
+            println!("  COSMOS_SENDER_PUBLIC_KEY={}", key.address);
+            println!(
+                "  COSMOS_SENDER_PRIVATE_KEY=<contents of {}>",
+                key.path.display()
+            );
+            println!(
+                "\nOr encrypt it with `key import <NAME> --chain cosmos --from {}`, and set \
+                 COSMOS_SENDER_PRIVATE_KEY=keystore:<NAME>",
+                key.path.display()
+            );
+        }
+        Chain::Substrate => {
+            println!("  SUBSTRATE_SENDER_ADDRESS={}", key.address);
+            println!(
+                "  SUBSTRATE_SENDER_SEED=<contents of {}>",
+                key.path.display()
+            );
+            println!(
+                "\nOr encrypt it with `key import <NAME> --chain substrate --from {}`, and set \
+                 SUBSTRATE_SENDER_SEED=keystore:<NAME>",
+                key.path.display()
+            );
+        }
+        Chain::Cardano => {
+            println!("  CARDANO_SENDER_KEY_HASH={}", key.address);
+            println!(
+                "  CARDANO_SENDER_SIGNING_KEY=<contents of {}>",
+                key.path.display()
+            );
+            println!(
+                "\nOr encrypt it with `key import <NAME> --chain cardano --from {}`, and set \
+                 CARDANO_SENDER_SIGNING_KEY=keystore:<NAME>",
+                key.path.display()
+            );
+        }
+        Chain::Tron => {
+            println!("  TRON_SENDER_ADDRESS={}", key.address);
+            println!(
+                "  TRON_SENDER_PRIVATE_KEY=<contents of {}>",
+                key.path.display()
+            );
+            println!(
+                "\nOr encrypt it with `key import <NAME> --chain tron --from {}`, and set \
+                 TRON_SENDER_PRIVATE_KEY=keystore:<NAME>",
+                key.path.display()
+            );
+        }
+        Chain::Hedera => {
+            println!("  HEDERA_SENDER_ADDRESS={}", key.address);
+            println!(
+                "  HEDERA_SENDER_PRIVATE_KEY=<contents of {}>",
+                key.path.display()
+            );
+            println!(
+                "\nOr encrypt it with `key import <NAME> --chain hedera --from {}`, and set \
+                 HEDERA_SENDER_PRIVATE_KEY=keystore:<NAME>",
+                key.path.display()
+            );
+        }
+    }
+}
+
+/// Runs a `key` command against the default keystore.
+pub fn run(command: KeyCmd, output: OutputFormat) -> anyhow::Result<()> {
+    let keystore = Keystore::open_default()?;
+    match command {
+        KeyCmd::Import { name, chain, from } => {
+            let secret = read_secret(chain, from.as_deref())?;
+            let password = match std::env::var(PASSWORD_VAR) {
+                Ok(password) => password,
+                Err(_) => Password::new()
+                    .with_prompt("Keystore password")
+                    .with_confirmation("Repeat password", "Passwords do not match")
+                    .interact()?,
+            };
+            let info = keystore.import(&name, chain, &secret, &password)?;
+            match output {
+                OutputFormat::Text => println!(
+                    "Imported {} key `{}` ({}); refer to it as {}{}",
+                    info.chain.as_ref(),
+                    info.name,
+                    info.address,
+                    keystore::KEYSTORE_PREFIX,
+                    info.name
+                ),
+                OutputFormat::Json => print_json(&info)?,
+            }
+        }
+
+        KeyCmd::Export { name, outfile } => {
+            let (info, secret) = keystore.export(&name, &keystore::password()?)?;
+            let encoded = match info.chain {
+                Chain::Ethereum | Chain::Tron | Chain::Hedera => {
+                    format!("0x{}", hex::encode(&secret))
+                }
+                Chain::Solana => serde_json::to_string(&secret)?,
+                Chain::Cosmos => hex::encode(&secret),
+                Chain::Substrate => format!("0x{}", hex::encode(&secret)),
+                Chain::Cardano => hex::encode(&secret),
+            };
+            match outfile {
+                Some(path) => {
+                    write_private(&path, encoded.as_bytes())
+                        .with_context(|| format!("writing {path:?}"))?;
+                    eprintln!("Wrote key `{name}` to {}", path.display());
+                }
+                None => println!("{encoded}"),
+            }
+        }
+
+        KeyCmd::List => {
+            let keys = keystore.list()?;
+            match output {
+                OutputFormat::Text => print_keys(&keys, keystore.dir()),
+                OutputFormat::Json => print_json(&keys)?,
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Reads the secret to import: the 64-byte keypair of a Solana keypair
+/// file, or a 32-byte Ethereum, Tron, Hedera, or Cosmos private key,
+/// Substrate seed, or Cardano signing key from a file or prompt.
+fn read_secret(chain: Chain, from: Option<&Path>) -> anyhow::Result<Vec<u8>> {
+    match chain {
+        Chain::Solana => {
+            let path = from.ok_or_else(|| anyhow!("Solana keys are imported with --from"))?;
+            let keypair = read_keypair_file(path)
+                .map_err(|e| anyhow!("reading keypair {}: {e}", path.display()))?;
+            Ok(keypair.to_bytes().to_vec())
+        }
+        Chain::Ethereum | Chain::Tron | Chain::Hedera => {
+            let key = match from {
+                Some(path) => std::fs::read_to_string(path)
+                    .with_context(|| format!("reading {}", path.display()))?,
+                None => Password::new()
+                    .with_prompt("Ethereum private key (hex)")
+                    .interact()?,
+            };
+            let signer: PrivateKeySigner = key
+                .trim()
+                .parse()
+                .map_err(|e| anyhow!("invalid Ethereum private key: {e}"))?;
+            Ok(signer.to_bytes().to_vec())
+        }
+        Chain::Cosmos => {
+            let key = match from {
+                Some(path) => std::fs::read_to_string(path)
+                    .with_context(|| format!("reading {}", path.display()))?,
+                None => Password::new()
+                    .with_prompt("Cosmos private key (hex)")
+                    .interact()?,
+            };
+            let secret = hex::decode(key.trim().trim_start_matches("0x"))
+                .map_err(|e| anyhow!("invalid Cosmos private key: {e}"))?;
+            #[cfg(feature = "cosmos")]
+            CosmosSigner::from_bytes(&secret)?;
+            Ok(secret)
+        }
+        Chain::Substrate => {
+            let key = match from {
+                Some(path) => std::fs::read_to_string(path)
+                    .with_context(|| format!("reading {}", path.display()))?,
+                None => Password::new()
+                    .with_prompt("Substrate sr25519 seed (hex)")
+                    .interact()?,
+            };
+            let seed = hex::decode(key.trim().trim_start_matches("0x"))
+                .map_err(|e| anyhow!("invalid Substrate seed: {e}"))?;
+            #[cfg(feature = "substrate")]
+            SubstrateSigner::from_bytes(&seed)?;
+            Ok(seed)
+        }
+        Chain::Cardano => {
+            let key = match from {
+                Some(path) => std::fs::read_to_string(path)
+                    .with_context(|| format!("reading {}", path.display()))?,
+                None => Password::new()
+                    .with_prompt("Cardano ed25519 signing key (hex)")
+                    .interact()?,
+            };
+            // `cardano-cli` key files prefix the key with its CBOR header.
+            let key = key.trim().trim_start_matches("0x");
+            let key = match key.len() {
+                68 => key.strip_prefix("5820").unwrap_or(key),
+                _ => key,
+            };
+            let secret =
+                hex::decode(key).map_err(|e| anyhow!("invalid Cardano signing key: {e}"))?;
+            #[cfg(feature = "cardano")]
+            CardanoSigner::from_bytes(&secret)?;
+            Ok(secret)
+        }
+    }
+}
+
+/// Prints one row per stored key.
+fn print_keys(keys: &[KeyInfo], dir: &Path) {
+    if keys.is_empty() {
+        println!("No keys in {}", dir.display());
+        return;
+    }
+    let width = keys
+        .iter()
+        .map(|key| key.name.len())
+        .max()
+        .unwrap_or(0)
+        .max(4);
+    println!("{:<width$}  {:<8}  ADDRESS", "NAME", "CHAIN");
+    for key in keys {
+        println!(
+            "{:<width$}  {:<8}  {}",
+            key.name,
+            key.chain.as_ref(),
+            key.address
+        );
+    }
+}
+
+/// Writes `contents` to `path`, readable only by its owner.
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents)
+}
@@ -0,0 +1,569 @@
+//! Password-protected keystore for signing keys.
+//!
+//! Keys are stored one per file as `<name>.json` in [`Keystore::dir`],
+//! encrypted with AES-256-GCM under a key derived from the password with
+//! scrypt. The chain and public address are kept in the clear (and
+//! authenticated) so keys can be listed without the password.
+//!
+//! Configurations refer to a stored key as `keystore:<name>`, both in
+//! `sender_private_id` and as a recipient. The password is read from
+//! [`PASSWORD_VAR`], or prompted for once per process on a terminal.
+//!
+//! [`Encrypted`] applies the same scheme to other secrets, such as escrow
+//! conditions handed to another party.
+
+use std::fs;
+use std::io::{IsTerminal, Write};
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use aes_gcm::aead::rand_core::RngCore;
+use aes_gcm::aead::{Aead, AeadCore, OsRng, Payload};
+use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+use alloy::signers::local::PrivateKeySigner;
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Keypair;
+use solana_sdk::signer::Signer;
+use zescrow_core::{Chain, ID};
+
+use crate::error::ClientError;
+#[cfg(feature = "cardano")]
+use crate::CardanoSigner;
+#[cfg(feature = "cosmos")]
+use crate::CosmosSigner;
+#[cfg(feature = "substrate")]
+use crate::SubstrateSigner;
+use crate::{EthereumSigner, Result, SolanaSigner};
+
+/// Prefix referring to a key in the keystore.
+pub const KEYSTORE_PREFIX: &str = "keystore:";
+
+/// Environment variable overriding the keystore directory.
+pub const DIR_VAR: &str = "ZESCROW_KEYSTORE_DIR";
+
+/// Environment variable holding the keystore password.
+pub const PASSWORD_VAR: &str = "ZESCROW_KEYSTORE_PASSWORD";
+
+/// SS58 prefix of Substrate addresses not tied to a network.
+#[cfg(feature = "substrate")]
+const GENERIC_SS58_PREFIX: u16 = 42;
+
+/// Key file format version.
+const VERSION: u32 = 1;
+
+/// scrypt cost parameters: N = 2^15, r = 8, p = 1 (32 MiB per derivation).
+const SCRYPT_LOG_N: u8 = 15;
+const SCRYPT_R: u32 = 8;
+const SCRYPT_P: u32 = 1;
+
+/// Public details of a stored key.
+#[derive(Debug, Clone, Serialize)]
+pub struct KeyInfo {
+    /// Name the key is referred to by.
+    pub name: String,
+    /// Chain the key signs for.
+    pub chain: Chain,
+    /// Ethereum address, Solana public key, hex-encoded Cosmos public key
+    /// (whose address depends on the chain's Bech32 prefix), generic SS58
+    /// address of a Substrate key (prefix 42), hex-encoded payment key
+    /// hash of a Cardano key (whose address depends on the network), or
+    /// base58check Tron address, or EVM address of a Hedera key.
+    pub address: String,
+}
+
+/// On-disk format of a stored key.
+#[derive(Debug, Serialize, Deserialize)]
+struct KeyFile {
+    version: u32,
+    chain: Chain,
+    address: String,
+    kdf: ScryptParams,
+    #[serde(with = "hex::serde")]
+    nonce: Vec<u8>,
+    #[serde(with = "hex::serde")]
+    ciphertext: Vec<u8>,
+}
+
+/// Data encrypted under a password, as in key files.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Encrypted {
+    kdf: ScryptParams,
+    #[serde(with = "hex::serde")]
+    nonce: Vec<u8>,
+    #[serde(with = "hex::serde")]
+    ciphertext: Vec<u8>,
+}
+
+impl Encrypted {
+    /// Encrypts `plaintext` under `password`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if key derivation or encryption fails.
+    pub fn encrypt(plaintext: &[u8], password: &str) -> Result<Self> {
+        let kdf = ScryptParams::generate();
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = kdf
+            .cipher(password)?
+            .encrypt(&nonce, plaintext)
+            .map_err(|_| ClientError::Keypair("encryption failed".into()))?;
+        Ok(Self {
+            kdf,
+            nonce: nonce.to_vec(),
+            ciphertext,
+        })
+    }
+
+    /// Decrypts the data with `password`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the password is wrong or the data corrupted.
+    pub fn decrypt(&self, password: &str) -> Result<Vec<u8>> {
+        self.kdf
+            .cipher(password)?
+            .decrypt(Nonce::from_slice(&self.nonce), self.ciphertext.as_slice())
+            .map_err(|_| ClientError::Keypair("wrong password or corrupted data".into()))
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ScryptParams {
+    log_n: u8,
+    r: u32,
+    p: u32,
+    #[serde(with = "hex::serde")]
+    salt: Vec<u8>,
+}
+
+/// Directory of encrypted signing keys.
+///
+/// # Example
+///
+/// ```ignore
+/// let keystore = Keystore::open_default()?;
+/// keystore.import("prod-sender", Chain::Solana, &keypair.to_bytes(), "password")?;
+/// // `sender_private_id` can now be "keystore:prod-sender".
+/// ```
+#[derive(Debug, Clone)]
+pub struct Keystore {
+    dir: PathBuf,
+}
+
+impl Keystore {
+    /// Opens the keystore in `dir`, which is created on first import.
+    pub fn open(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+
+    /// Opens the keystore in [`DIR_VAR`], or `~/.config/zescrow/keystore`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if neither [`DIR_VAR`] nor `HOME` is set.
+    pub fn open_default() -> Result<Self> {
+        if let Ok(dir) = std::env::var(DIR_VAR) {
+            return Ok(Self::open(dir));
+        }
+        let home = std::env::var("HOME")
+            .map_err(|_| ClientError::Keypair(format!("neither {DIR_VAR} nor HOME is set")))?;
+        Ok(Self::open(
+            Path::new(&home).join(".config/zescrow/keystore"),
+        ))
+    }
+
+    /// Returns the keystore directory.
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Encrypts `secret` under `password` and stores it as `name`.
+    ///
+    /// `secret` is a 32-byte private key for Ethereum, Tron, Hedera, and
+    /// Cosmos, a 32-byte sr25519 seed for Substrate, a 32-byte ed25519
+    /// signing key for Cardano, or a 64-byte keypair for Solana.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `name` is invalid or taken, `secret` is not a
+    /// valid key for `chain`, or the key file cannot be written.
+    pub fn import(
+        &self,
+        name: &str,
+        chain: Chain,
+        secret: &[u8],
+        password: &str,
+    ) -> Result<KeyInfo> {
+        let path = self.path(name)?;
+        if path.exists() {
+            return Err(ClientError::Keypair(format!(
+                "key `{name}` already exists in {}",
+                self.dir.display()
+            )));
+        }
+        let address = address(chain, secret)?;
+
+        let kdf = ScryptParams::generate();
+        let cipher = kdf.cipher(password)?;
+        let nonce = Aes256Gcm::generate_nonce(&mut OsRng);
+        let ciphertext = cipher
+            .encrypt(
+                &nonce,
+                Payload {
+                    msg: secret,
+                    aad: aad(chain, &address).as_bytes(),
+                },
+            )
+            .map_err(|_| ClientError::Keypair("encryption failed".into()))?;
+
+        let file = KeyFile {
+            version: VERSION,
+            chain,
+            address: address.clone(),
+            kdf,
+            nonce: nonce.to_vec(),
+            ciphertext,
+        };
+        let json = serde_json::to_vec_pretty(&file)
+            .map_err(|e| ClientError::Serialization(e.to_string()))?;
+        fs::create_dir_all(&self.dir).map_err(|e| io_error(&self.dir, e))?;
+        write_private(&path, &json).map_err(|e| io_error(&path, e))?;
+
+        Ok(KeyInfo {
+            name: name.to_owned(),
+            chain,
+            address,
+        })
+    }
+
+    /// Decrypts key `name`, returning its details and secret.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key does not exist or the password is wrong.
+    pub fn export(&self, name: &str, password: &str) -> Result<(KeyInfo, Vec<u8>)> {
+        let file = self.read(name)?;
+        let cipher = file.kdf.cipher(password)?;
+        let secret = cipher
+            .decrypt(
+                Nonce::from_slice(&file.nonce),
+                Payload {
+                    msg: &file.ciphertext,
+                    aad: aad(file.chain, &file.address).as_bytes(),
+                },
+            )
+            .map_err(|_| {
+                ClientError::Keypair(format!("wrong password or corrupted key `{name}`"))
+            })?;
+        let info = KeyInfo {
+            name: name.to_owned(),
+            chain: file.chain,
+            address: file.address,
+        };
+        Ok((info, secret))
+    }
+
+    /// Returns the details of every stored key, by name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the directory or a key file cannot be read.
+    pub fn list(&self) -> Result<Vec<KeyInfo>> {
+        if !self.dir.exists() {
+            return Ok(Vec::new());
+        }
+        let entries = fs::read_dir(&self.dir).map_err(|e| io_error(&self.dir, e))?;
+        let mut keys = Vec::new();
+        for entry in entries {
+            let path = entry.map_err(|e| io_error(&self.dir, e))?.path();
+            let Some(name) = path
+                .extension()
+                .filter(|extension| *extension == "json")
+                .and_then(|_| path.file_stem())
+                .and_then(|name| name.to_str())
+            else {
+                continue;
+            };
+            let file = self.read(name)?;
+            keys.push(KeyInfo {
+                name: name.to_owned(),
+                chain: file.chain,
+                address: file.address,
+            });
+        }
+        keys.sort_by(|a, b| a.name.cmp(&b.name));
+        Ok(keys)
+    }
+
+    /// Returns the chain key `name` signs for, without decrypting it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key does not exist.
+    pub fn chain(&self, name: &str) -> Result<Chain> {
+        Ok(self.read(name)?.chain)
+    }
+
+    /// Decrypts key `name` as an Ethereum signer, which also signs for
+    /// Tron and Hedera.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key does not exist, is not an Ethereum,
+    /// Tron, or Hedera key, or the password is wrong.
+    pub fn ethereum_signer(&self, name: &str, password: &str) -> Result<EthereumSigner> {
+        let (info, secret) = self.export(name, password)?;
+        if !matches!(info.chain, Chain::Tron | Chain::Hedera) {
+            expect_chain(&info, Chain::Ethereum)?;
+        }
+        PrivateKeySigner::from_slice(&secret)
+            .map(Into::into)
+            .map_err(|e| ClientError::Keypair(format!("key `{name}`: {e}")))
+    }
+
+    /// Decrypts key `name` as a Solana signer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key does not exist, is not a Solana key, or
+    /// the password is wrong.
+    pub fn solana_signer(&self, name: &str, password: &str) -> Result<SolanaSigner> {
+        let (info, secret) = self.export(name, password)?;
+        expect_chain(&info, Chain::Solana)?;
+        Keypair::try_from(secret.as_slice())
+            .map(SolanaSigner::new)
+            .map_err(|e| ClientError::Keypair(format!("key `{name}`: {e}")))
+    }
+
+    /// Decrypts key `name` as a Cosmos signer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key does not exist, is not a Cosmos key, or
+    /// the password is wrong.
+    #[cfg(feature = "cosmos")]
+    pub fn cosmos_signer(&self, name: &str, password: &str) -> Result<CosmosSigner> {
+        let (info, secret) = self.export(name, password)?;
+        expect_chain(&info, Chain::Cosmos)?;
+        CosmosSigner::from_bytes(&secret)
+            .map_err(|e| ClientError::Keypair(format!("key `{name}`: {e}")))
+    }
+
+    /// Decrypts key `name` as a Substrate signer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key does not exist, is not a Substrate key,
+    /// or the password is wrong.
+    #[cfg(feature = "substrate")]
+    pub fn substrate_signer(&self, name: &str, password: &str) -> Result<SubstrateSigner> {
+        let (info, secret) = self.export(name, password)?;
+        expect_chain(&info, Chain::Substrate)?;
+        SubstrateSigner::from_bytes(&secret)
+            .map_err(|e| ClientError::Keypair(format!("key `{name}`: {e}")))
+    }
+
+    /// Decrypts key `name` as a Cardano signer.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key does not exist, is not a Cardano key, or
+    /// the password is wrong.
+    #[cfg(feature = "cardano")]
+    pub fn cardano_signer(&self, name: &str, password: &str) -> Result<CardanoSigner> {
+        let (info, secret) = self.export(name, password)?;
+        expect_chain(&info, Chain::Cardano)?;
+        CardanoSigner::from_bytes(&secret)
+            .map_err(|e| ClientError::Keypair(format!("key `{name}`: {e}")))
+    }
+
+    fn read(&self, name: &str) -> Result<KeyFile> {
+        let path = self.path(name)?;
+        let json = fs::read(&path).map_err(|e| match e.kind() {
+            std::io::ErrorKind::NotFound => {
+                ClientError::Keypair(format!("no key `{name}` in {}", self.dir.display()))
+            }
+            _ => io_error(&path, e),
+        })?;
+        let file: KeyFile = serde_json::from_slice(&json)
+            .map_err(|e| ClientError::Keypair(format!("key `{name}`: {e}")))?;
+        if file.version != VERSION {
+            return Err(ClientError::Keypair(format!(
+                "key `{name}` has unsupported version {}",
+                file.version
+            )));
+        }
+        Ok(file)
+    }
+
+    fn path(&self, name: &str) -> Result<PathBuf> {
+        let valid = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid {
+            return Err(ClientError::Keypair(format!(
+                "invalid key name `{name}`: use letters, digits, `-`, and `_`"
+            )));
+        }
+        Ok(self.dir.join(format!("{name}.json")))
+    }
+}
+
+impl ScryptParams {
+    /// Returns the default cost parameters with a fresh random salt.
+    fn generate() -> Self {
+        let mut salt = vec![0; 32];
+        OsRng.fill_bytes(&mut salt);
+        Self {
+            log_n: SCRYPT_LOG_N,
+            r: SCRYPT_R,
+            p: SCRYPT_P,
+            salt,
+        }
+    }
+
+    /// Derives the AES key from `password`.
+    fn cipher(&self, password: &str) -> Result<Aes256Gcm> {
+        let params = scrypt::Params::new(self.log_n, self.r, self.p, 32)
+            .map_err(|e| ClientError::Keypair(format!("invalid scrypt parameters: {e}")))?;
+        let mut key = [0; 32];
+        scrypt::scrypt(password.as_bytes(), &self.salt, &params, &mut key)
+            .map_err(|e| ClientError::Keypair(format!("scrypt: {e}")))?;
+        Aes256Gcm::new_from_slice(&key).map_err(|e| ClientError::Keypair(e.to_string()))
+    }
+}
+
+/// Loads the Ethereum signer `id` refers to, if it is a keystore reference.
+pub(crate) fn ethereum_signer(id: &str) -> Option<Result<EthereumSigner>> {
+    let name = id.strip_prefix(KEYSTORE_PREFIX)?;
+    Some(Keystore::open_default().and_then(|keystore| keystore.ethereum_signer(name, &password()?)))
+}
+
+/// Loads the Solana signer `id` refers to, if it is a keystore reference.
+pub(crate) fn solana_signer(id: &str) -> Option<Result<SolanaSigner>> {
+    let name = id.strip_prefix(KEYSTORE_PREFIX)?;
+    Some(Keystore::open_default().and_then(|keystore| keystore.solana_signer(name, &password()?)))
+}
+
+/// Loads the Cosmos signer `id` refers to, if it is a keystore reference.
+#[cfg(feature = "cosmos")]
+pub(crate) fn cosmos_signer(id: &str) -> Option<Result<CosmosSigner>> {
+    let name = id.strip_prefix(KEYSTORE_PREFIX)?;
+    Some(Keystore::open_default().and_then(|keystore| keystore.cosmos_signer(name, &password()?)))
+}
+
+/// Loads the Substrate signer `id` refers to, if it is a keystore reference.
+#[cfg(feature = "substrate")]
+pub(crate) fn substrate_signer(id: &str) -> Option<Result<SubstrateSigner>> {
+    let name = id.strip_prefix(KEYSTORE_PREFIX)?;
+    Some(
+        Keystore::open_default().and_then(|keystore| keystore.substrate_signer(name, &password()?)),
+    )
+}
+
+/// Loads the Cardano signer `id` refers to, if it is a keystore reference.
+#[cfg(feature = "cardano")]
+pub(crate) fn cardano_signer(id: &str) -> Option<Result<CardanoSigner>> {
+    let name = id.strip_prefix(KEYSTORE_PREFIX)?;
+    Some(Keystore::open_default().and_then(|keystore| keystore.cardano_signer(name, &password()?)))
+}
+
+/// Returns the keystore password from [`PASSWORD_VAR`], prompting for it
+/// on a terminal if unset. A prompted password is reused for the rest of
+/// the process.
+///
+/// # Errors
+///
+/// Returns an error if the variable is unset and there is no terminal.
+pub fn password() -> Result<String> {
+    static PROMPTED: OnceLock<String> = OnceLock::new();
+
+    if let Ok(password) = std::env::var(PASSWORD_VAR) {
+        return Ok(password);
+    }
+    if let Some(password) = PROMPTED.get() {
+        return Ok(password.clone());
+    }
+    if !std::io::stdin().is_terminal() {
+        return Err(ClientError::Keypair(format!(
+            "keystore password required; set {PASSWORD_VAR}"
+        )));
+    }
+    let password = dialoguer::Password::new()
+        .with_prompt("Keystore password")
+        .interact()
+        .map_err(|e| ClientError::Keypair(format!("reading password: {e}")))?;
+    Ok(PROMPTED.get_or_init(|| password).clone())
+}
+
+/// Returns the public address of `secret` on `chain`.
+fn address(chain: Chain, secret: &[u8]) -> Result<String> {
+    match chain {
+        Chain::Ethereum => PrivateKeySigner::from_slice(secret)
+            .map(|signer| signer.address().to_string())
+            .map_err(|e| ClientError::Keypair(format!("invalid Ethereum private key: {e}"))),
+        Chain::Tron => PrivateKeySigner::from_slice(secret)
+            .map(|signer| ID::tron(signer.address().into()).to_string())
+            .map_err(|e| ClientError::Keypair(format!("invalid Tron private key: {e}"))),
+        Chain::Hedera => PrivateKeySigner::from_slice(secret)
+            .map(|signer| signer.address().to_string())
+            .map_err(|e| ClientError::Keypair(format!("invalid Hedera private key: {e}"))),
+        Chain::Solana => Keypair::try_from(secret)
+            .map(|keypair| keypair.pubkey().to_string())
+            .map_err(|e| ClientError::Keypair(format!("invalid Solana keypair: {e}"))),
+        #[cfg(feature = "cosmos")]
+        Chain::Cosmos => CosmosSigner::from_bytes(secret).map(|signer| signer.public_key()),
+        #[cfg(not(feature = "cosmos"))]
+        Chain::Cosmos => Err(ClientError::Keypair(
+            "Cosmos keys require the `cosmos` feature".into(),
+        )),
+        #[cfg(feature = "substrate")]
+        Chain::Substrate => {
+            SubstrateSigner::from_bytes(secret).map(|signer| signer.address(GENERIC_SS58_PREFIX))
+        }
+        #[cfg(not(feature = "substrate"))]
+        Chain::Substrate => Err(ClientError::Keypair(
+            "Substrate keys require the `substrate` feature".into(),
+        )),
+        #[cfg(feature = "cardano")]
+        Chain::Cardano => {
+            CardanoSigner::from_bytes(secret).map(|signer| signer.key_hash().to_string())
+        }
+        #[cfg(not(feature = "cardano"))]
+        Chain::Cardano => Err(ClientError::Keypair(
+            "Cardano keys require the `cardano` feature".into(),
+        )),
+    }
+}
+
+/// Associated data binding the ciphertext to the key's public details.
+fn aad(chain: Chain, address: &str) -> String {
+    format!("{}:{address}", chain.as_ref())
+}
+
+fn expect_chain(info: &KeyInfo, chain: Chain) -> Result<()> {
+    if info.chain.as_ref() == chain.as_ref() {
+        Ok(())
+    } else {
+        Err(ClientError::Keypair(format!(
+            "key `{}` is a {} key, expected {}",
+            info.name,
+            info.chain.as_ref(),
+            chain.as_ref()
+        )))
+    }
+}
+
+/// Writes `contents` to a new file at `path` readable only by its owner.
+fn write_private(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create_new(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    options.open(path)?.write_all(contents)
+}
+
+fn io_error(path: &Path, e: std::io::Error) -> ClientError {
+    ClientError::Keypair(format!("{}: {e}", path.display()))
+}
@@ -7,10 +7,26 @@
 //!
 //! - **Ethereum**: Via [`EthereumAgent`]
 //! - **Solana**: Via [`SolanaAgent`]
+//! - **Cosmos**: Via [`CosmosAgent`], on chains running the CosmWasm escrow contract
+//!   (`cosmos` feature)
+//! - **Substrate**: Via [`SubstrateAgent`], on chains running the ink! escrow contract
+//!   (`substrate` feature)
+//! - **Cardano**: Via [`CardanoAgent`], with the Plutus escrow validator (`cardano` feature)
+//! - **Tron**: Via [`TronAgent`], with the Ethereum escrow contract on the TVM
+//! - **Hedera**: Via [`HederaAgent`], with the Ethereum escrow contract behind a JSON-RPC relay
 //!
 //! # Features
 //!
+//! - `sqlite`: Enables the SQLite [`EscrowStore`] and operation [`Journal`] (default)
 //! - `prover`: Enables RISC Zero zkVM proof generation via `zescrow-prover` (opt-in)
+//! - `cuda`, `metal`: Enable `prover` with GPU-accelerated local proving (opt-in)
+//! - `aws-kms`: Enables Ethereum signing with AWS KMS keys (opt-in)
+//! - `gcp-kms`: Enables Ethereum signing with GCP Cloud KMS keys (opt-in)
+//! - `aws-secrets`: Enables `aws-sm:` key references to AWS Secrets Manager (opt-in)
+//! - `cosmos`: Enables the Cosmos agent and the CosmWasm escrow contract bindings (opt-in)
+//! - `substrate`: Enables the Substrate agent and the ink! escrow contract bindings (opt-in)
+//! - `cardano`: Enables the Cardano agent (opt-in)
+//! - `test-utils`: Enables [`mock::MockAgent`], an in-memory agent for tests (opt-in)
 //!
 //! # Example
 //!
@@ -26,18 +42,78 @@
 //! ```
 
 use std::path::PathBuf;
+use std::sync::Arc;
 
+use alloy::signers::local::PrivateKeySigner;
+#[cfg(feature = "cardano")]
+pub use cardano::{CardanoAgent, CardanoSigner};
+#[cfg(feature = "cosmos")]
+pub use cosmos::{CosmosAgent, CosmosSigner};
 pub use error::ClientError;
-pub use ethereum::EthereumAgent;
-use ethers::signers::LocalWallet;
-pub use solana::SolanaAgent;
+pub use estimate::{Cost, LifecycleEstimate};
+pub use ethereum::safe::SafeConfig;
+pub use ethereum::{
+    EthereumAgent, EthereumSigner, FeeBump, FeeConfig, GasEstimate, FLASHBOTS_PROTECT_RPC,
+};
+pub use fanout::{FanOut, FanOutLeg, FanOutPhase};
+pub use hedera::HederaAgent;
+pub use journal::{Journal, JournalEntry, JournalOp, Reconciled};
+pub use keystore::{Encrypted, KeyInfo, Keystore};
+pub use offline::{sign_transaction, Broadcast, OfflineTransaction};
+pub use pending::{PendingTransaction, WaitConfig};
+pub use quote::{FeeToken, PriceFeed, PriceFeeds, Quote};
+pub use registry::{AgentFactory, AgentRegistry};
+pub use reorg::Reconciliation;
+pub use rpc::{RateLimit, RateLimiter, RetryConfig, RpcMetrics, RpcMetricsSnapshot, RpcPolicy};
+pub use simulation::{Operation, Simulation};
+pub use solana::{
+    ComputeBudget, ConfirmationConfig, PriorityFee, PriorityFees, ProgramEvent, SolanaAgent,
+    SolanaSigner,
+};
+use solana_sdk::pubkey::Pubkey;
+pub use status::{EscrowStatus, EscrowSummary};
+pub use store::{EscrowStore, StoredEscrow, Transition};
+#[cfg(feature = "substrate")]
+pub use substrate::{SubstrateAgent, SubstrateSigner};
+pub use swap::{Swap, SwapLeg, SwapPhase};
 use tracing::{debug, info};
-use zescrow_core::interface::ChainConfig;
-use zescrow_core::{Chain, EscrowMetadata, EscrowParams};
+pub use tron::TronAgent;
+use url::Url;
+pub use watch::{EscrowUpdate, UpdateKind, UpdateStream};
+use zescrow_core::condition::cross_chain::Observation;
+use zescrow_core::interface::{ChainConfig, ChainState, GuestPin};
+use zescrow_core::{Chain, Condition, EscrowMetadata, EscrowParams, ObservedBlock, Party, ID};
 
+#[cfg(feature = "cardano")]
+pub mod cardano;
+pub mod chain_state;
+#[cfg(feature = "cosmos")]
+pub mod cosmos;
 pub mod error;
+pub mod estimate;
 pub mod ethereum;
+pub mod fanout;
+pub mod hedera;
+pub mod journal;
+pub mod keystore;
+#[cfg(feature = "test-utils")]
+pub mod mock;
+pub mod offline;
+pub mod pending;
+pub mod quote;
+pub mod registry;
+pub mod reorg;
+pub mod rpc;
+pub mod secrets;
+pub mod simulation;
 pub mod solana;
+pub mod status;
+pub mod store;
+#[cfg(feature = "substrate")]
+pub mod substrate;
+pub mod swap;
+pub mod tron;
+pub mod watch;
 
 /// Re-export of the prover crate when the `prover` feature is enabled.
 #[cfg(feature = "prover")]
@@ -106,6 +182,256 @@ pub trait Agent: Send + Sync {
     ///
     /// Returns an error if cancellation is not allowed or timelocks are not met.
     async fn cancel_escrow(&self, metadata: &EscrowMetadata) -> Result<()>;
+
+    /// Builds and simulates the transaction for `operation` without
+    /// broadcasting it.
+    ///
+    /// A transaction that would fail is reported in
+    /// [`Simulation::error`], not as an `Err`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be built (e.g., a missing
+    /// recipient key) or the simulation request fails.
+    async fn simulate(&self, operation: Operation<'_>) -> Result<Simulation>;
+
+    /// Estimates the costs of creating an escrow from `params` and of
+    /// finishing or cancelling it, without broadcasting anything.
+    ///
+    /// The default simulates the create and leaves the settlement costs
+    /// unknown.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transactions cannot be built or the estimate
+    /// requests fail.
+    async fn estimate(&self, params: &EscrowParams) -> Result<LifecycleEstimate> {
+        let create = self.simulate(Operation::Create(params)).await?;
+        Ok(LifecycleEstimate {
+            create: create.into(),
+            finish: Cost::unknown("finish"),
+            cancel: Cost::unknown("cancel"),
+        })
+    }
+
+    /// Reads the escrow's live state from the chain.
+    ///
+    /// # Arguments
+    ///
+    /// * `metadata` - Escrow metadata from creation
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the escrow cannot be located or queried.
+    async fn get_escrow_state(&self, metadata: &EscrowMetadata) -> Result<EscrowStatus>;
+
+    /// Reads the current block height (or slot), in the units timelocks
+    /// are measured in.
+    ///
+    /// The default reports the height as unsupported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chain cannot be queried.
+    async fn current_height(&self) -> Result<u64> {
+        Err(ClientError::InvalidChainOperation(
+            "reading the current height is not supported by this agent".into(),
+        ))
+    }
+
+    /// Fetches and checks recent chain state to attest to in proofs (see
+    /// [`chain_state`]).
+    ///
+    /// The default reports attestation as unsupported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chain state cannot be fetched or does not
+    /// check out.
+    async fn chain_state(&self) -> Result<ChainState> {
+        Err(ClientError::InvalidChainOperation(
+            "chain state attestation is not supported by this agent".into(),
+        ))
+    }
+
+    /// Observes the settlement of an escrow at a final block, for attestors
+    /// to sign as the claim of a cross-chain condition (see
+    /// [`CrossChain`](zescrow_core::condition::cross_chain::CrossChain)).
+    ///
+    /// The default reports observation as unsupported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the escrow is not settled, or its settlement is
+    /// not yet final.
+    async fn observe_escrow(&self, _metadata: &EscrowMetadata) -> Result<Observation> {
+        Err(ClientError::InvalidChainOperation(
+            "observing escrows is not supported by this agent".into(),
+        ))
+    }
+
+    /// Observes the state at `key` (see
+    /// [`RemoteClaim::State`](zescrow_core::condition::cross_chain::RemoteClaim))
+    /// at a final block, for attestors to sign.
+    ///
+    /// The default reports observation as unsupported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `key` is malformed or the state cannot be read.
+    async fn observe_state(&self, _key: &[u8]) -> Result<Observation> {
+        Err(ClientError::InvalidChainOperation(
+            "observing chain state is not supported by this agent".into(),
+        ))
+    }
+
+    /// Reads the latest block (or slot), to record the block a pending
+    /// state was observed in (see [`reorg`]).
+    ///
+    /// The default reports block lookups as unsupported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the block cannot be read.
+    async fn latest_block(&self) -> Result<ObservedBlock> {
+        Err(ClientError::InvalidChainOperation(
+            "reading blocks is not supported by this agent".into(),
+        ))
+    }
+
+    /// Reads the hash of the block (or slot) at `height` on the canonical
+    /// chain, or `None` if it has none there, e.g. a skipped slot.
+    ///
+    /// The default reports block lookups as unsupported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chain cannot be queried.
+    async fn block_hash(&self, _height: u64) -> Result<Option<[u8; 32]>> {
+        Err(ClientError::InvalidChainOperation(
+            "reading blocks is not supported by this agent".into(),
+        ))
+    }
+
+    /// Reads the height of the latest final block (or slot), which no reorg
+    /// can orphan.
+    ///
+    /// The default reports block lookups as unsupported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the chain cannot be queried.
+    async fn finalized_height(&self) -> Result<u64> {
+        Err(ClientError::InvalidChainOperation(
+            "reading blocks is not supported by this agent".into(),
+        ))
+    }
+
+    /// Lists the escrows in which the configured sender or recipient is a
+    /// party, with their live state.
+    ///
+    /// Settled escrows are included where the chain retains them; settled
+    /// Solana escrows are closed and therefore omitted.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the escrows cannot be queried.
+    async fn list_escrows(&self) -> Result<Vec<EscrowSummary>>;
+
+    /// Streams lifecycle updates for the escrow in `metadata`, or for every
+    /// escrow in which the configured sender or recipient is a party if
+    /// `None`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the subscription cannot be established. Errors
+    /// while streaming are yielded by the stream.
+    async fn watch_escrows(&self, metadata: Option<&EscrowMetadata>) -> Result<UpdateStream>;
+
+    /// Releases several escrows to their beneficiaries.
+    ///
+    /// Chains that support batching settle them in one transaction; the
+    /// default finishes them one by one, stopping at the first failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any escrow cannot be finished.
+    async fn finish_escrows(&self, metadata: &[EscrowMetadata]) -> Result<()> {
+        for metadata in metadata {
+            self.finish_escrow(metadata).await?;
+        }
+        Ok(())
+    }
+
+    /// Refunds several escrows to their depositors.
+    ///
+    /// Chains that support batching settle them in one transaction; the
+    /// default cancels them one by one, stopping at the first failure.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any escrow cannot be cancelled.
+    async fn cancel_escrows(&self, metadata: &[EscrowMetadata]) -> Result<()> {
+        for metadata in metadata {
+            self.cancel_escrow(metadata).await?;
+        }
+        Ok(())
+    }
+
+    /// Resumes waiting for a transaction that an operation gave up on with
+    /// [`ClientError::Pending`], under the agent's [`WaitConfig`].
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::Pending`] if the transaction is still pending
+    /// when the wait times out again, or an error if it failed or was
+    /// dropped.
+    async fn wait_for_transaction(&self, pending: &PendingTransaction) -> Result<()>;
+
+    /// Builds the unsigned transaction for `operation`, to be signed with
+    /// [`sign_transaction`] and sent with
+    /// [`broadcast_transaction`](Agent::broadcast_transaction).
+    ///
+    /// The default reports offline transactions as unsupported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the transaction cannot be built.
+    async fn build_transaction(&self, _operation: Operation<'_>) -> Result<OfflineTransaction> {
+        Err(ClientError::InvalidChainOperation(
+            "offline transactions are not supported by this agent".into(),
+        ))
+    }
+
+    /// Broadcasts a fully signed transaction and waits for it to confirm.
+    ///
+    /// The default reports offline transactions as unsupported.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if signatures are missing, or the transaction fails
+    /// or is not confirmed.
+    async fn broadcast_transaction(&self, _tx: &OfflineTransaction) -> Result<Broadcast> {
+        Err(ClientError::InvalidChainOperation(
+            "offline transactions are not supported by this agent".into(),
+        ))
+    }
+}
+
+/// Returns the guest program to pin in the metadata of escrows created now:
+/// this build's, if built with the `prover` feature.
+#[cfg(feature = "prover")]
+pub fn pinned_guest() -> Option<GuestPin> {
+    Some(GuestPin {
+        image_id: prover::guest_image_id(),
+        version: prover::GUEST_VERSION.into(),
+    })
+}
+
+/// Without the prover, no guest is pinned.
+#[cfg(not(feature = "prover"))]
+pub fn pinned_guest() -> Option<GuestPin> {
+    None
 }
 
 /// Unified client for cross-chain escrow management.
@@ -115,6 +441,8 @@ pub trait Agent: Send + Sync {
 pub struct ZescrowClient {
     /// The underlying blockchain agent.
     pub agent: Box<dyn Agent>,
+    /// Counters of the agent's RPC requests.
+    rpc_metrics: Arc<RpcMetrics>,
 }
 
 /// Builder for constructing [`ZescrowClient`] instances.
@@ -122,20 +450,73 @@ pub struct ZescrowClient {
 /// Use [`ZescrowClient::builder`] to create a new builder.
 pub struct ZescrowClientBuilder {
     config: ChainConfig,
+    sender: Option<Sender>,
     recipient: Option<Recipient>,
+    fees: FeeConfig,
+    priority_fees: PriorityFees,
+    lookup_tables: Vec<Pubkey>,
+    nonce_account: Option<Pubkey>,
+    confirmation: ConfirmationConfig,
+    wait: WaitConfig,
+    safe: Option<SafeConfig>,
+    private_relay: Option<Url>,
+    confirmations: Option<u64>,
+    proof_seal: Option<Vec<u8>>,
+    condition: Option<[u8; 32]>,
+    from_block: u64,
+    rpc: RpcPolicy,
+    registry: AgentRegistry,
 }
 
 /// Recipient key configuration for escrow operations.
 ///
 /// Different chains use different key formats:
-/// - Ethereum uses wallet private keys (hex-encoded)
-/// - Solana uses keypair files (JSON)
+/// - Ethereum, Tron, and Hedera use wallet private keys (hex-encoded) or
+///   KMS-held keys
+/// - Solana uses keypair files (JSON) or any [`SolanaSigner`]
+/// - Cosmos uses secp256k1 private keys, so local Ethereum keys also serve
+/// - Substrate uses sr25519 seeds, so local Ethereum keys also serve
+/// - Cardano uses ed25519 signing keys, so local Ethereum keys also serve
 #[derive(Debug, Clone)]
 pub enum Recipient {
-    /// Ethereum wallet for signing transactions.
-    Ethereum(LocalWallet),
+    /// Ethereum signer for signing transactions.
+    Ethereum(EthereumSigner),
+    /// Cosmos signer for signing transactions.
+    #[cfg(feature = "cosmos")]
+    Cosmos(CosmosSigner),
+    /// Substrate signer for signing extrinsics.
+    #[cfg(feature = "substrate")]
+    Substrate(SubstrateSigner),
+    /// Cardano signer for signing transactions.
+    #[cfg(feature = "cardano")]
+    Cardano(CardanoSigner),
     /// Path to a Solana keypair JSON file.
     Solana(PathBuf),
+    /// Solana signer, e.g. backed by a remote signing service.
+    SolanaSigner(SolanaSigner),
+    /// Name of a key in the encrypted [`Keystore`], for any chain.
+    Keystore(String),
+    /// [Secret reference](secrets) to a key for any chain, resolved
+    /// when the client is built.
+    Secret(String),
+}
+
+/// Sender signer overriding `sender_private_id` in the chain configuration.
+#[derive(Debug, Clone)]
+pub enum Sender {
+    /// Ethereum signer (local or KMS-held key).
+    Ethereum(EthereumSigner),
+    /// Solana signer, e.g. backed by a remote signing service.
+    Solana(SolanaSigner),
+    /// Cosmos signer.
+    #[cfg(feature = "cosmos")]
+    Cosmos(CosmosSigner),
+    /// Substrate signer.
+    #[cfg(feature = "substrate")]
+    Substrate(SubstrateSigner),
+    /// Cardano signer.
+    #[cfg(feature = "cardano")]
+    Cardano(CardanoSigner),
 }
 
 impl ZescrowClient {
@@ -147,10 +528,40 @@ impl ZescrowClient {
     pub fn builder(config: &ChainConfig) -> ZescrowClientBuilder {
         ZescrowClientBuilder {
             config: config.clone(),
+            sender: None,
             recipient: None,
+            fees: FeeConfig::default(),
+            priority_fees: PriorityFees::default(),
+            lookup_tables: Vec::new(),
+            nonce_account: None,
+            confirmation: ConfirmationConfig::default(),
+            wait: WaitConfig::default(),
+            safe: None,
+            private_relay: None,
+            confirmations: None,
+            proof_seal: None,
+            condition: None,
+            from_block: 0,
+            rpc: RpcPolicy::default(),
+            registry: AgentRegistry::default(),
         }
     }
 
+    /// Creates a client driving `agent` directly, e.g. a
+    /// [`MockAgent`](mock::MockAgent) in tests.
+    pub fn from_agent(agent: impl Agent + 'static) -> Self {
+        Self {
+            agent: Box::new(agent),
+            rpc_metrics: Arc::new(RpcMetrics::default()),
+        }
+    }
+
+    /// Returns the counters of RPC requests made by this client, or by every
+    /// client sharing its [`RpcPolicy`].
+    pub fn rpc_metrics(&self) -> RpcMetricsSnapshot {
+        self.rpc_metrics.snapshot()
+    }
+
     /// Creates an escrow on-chain.
     ///
     /// # Arguments
@@ -161,7 +572,8 @@ impl ZescrowClient {
     ///
     /// Metadata for the created escrow, including chain-specific identifiers.
     pub async fn create_escrow(&self, params: &EscrowParams) -> Result<EscrowMetadata> {
-        let metadata = self.agent.create_escrow(params).await?;
+        let mut metadata = self.agent.create_escrow(params).await?;
+        metadata.guest = metadata.guest.or_else(pinned_guest);
         debug!(?metadata, "Escrow created");
         Ok(metadata)
     }
@@ -187,9 +599,162 @@ impl ZescrowClient {
             debug!("Escrow cancelled");
         })
     }
+
+    /// Resumes waiting for a transaction that an operation gave up on.
+    ///
+    /// # Arguments
+    ///
+    /// * `pending` - The transaction carried by [`ClientError::Pending`]
+    pub async fn wait_for_transaction(&self, pending: &PendingTransaction) -> Result<()> {
+        self.agent
+            .wait_for_transaction(pending)
+            .await
+            .inspect(|_| debug!(id = %pending.id, "Pending transaction confirmed"))
+    }
+
+    /// Simulates `operation` against current chain state without
+    /// broadcasting, e.g. to check a mainnet configuration before sending.
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The create, finish, or cancel to simulate
+    pub async fn simulate(&self, operation: Operation<'_>) -> Result<Simulation> {
+        self.agent.simulate(operation).await.inspect(|simulation| {
+            debug!(?simulation, "Transaction simulated");
+        })
+    }
+
+    /// Builds the unsigned transaction for `operation`, e.g. to sign it on
+    /// an offline machine with [`sign_transaction`].
+    ///
+    /// # Arguments
+    ///
+    /// * `operation` - The create, finish, or cancel to build
+    pub async fn build_transaction(&self, operation: Operation<'_>) -> Result<OfflineTransaction> {
+        self.agent.build_transaction(operation).await.inspect(|tx| {
+            debug!(operation = %tx.operation, signers = ?tx.signers, "Transaction built");
+        })
+    }
+
+    /// Broadcasts a transaction signed offline and waits for it to confirm.
+    ///
+    /// # Arguments
+    ///
+    /// * `tx` - A transaction from [`build_transaction`](Self::build_transaction)
+    ///   with every signature added
+    pub async fn broadcast_transaction(&self, tx: &OfflineTransaction) -> Result<Broadcast> {
+        self.agent
+            .broadcast_transaction(tx)
+            .await
+            .inspect(|broadcast| {
+                debug!(?broadcast, "Transaction broadcast");
+            })
+    }
+
+    /// Fetches the escrow's live on-chain state: amount, timelocks against
+    /// the current height, and whether finish/cancel would currently pass.
+    ///
+    /// # Arguments
+    ///
+    /// * `metadata` - Escrow metadata from creation
+    pub async fn get_escrow_state(&self, metadata: &EscrowMetadata) -> Result<EscrowStatus> {
+        self.agent.get_escrow_state(metadata).await
+    }
+
+    /// Fetches the current block height (or slot), in the units timelocks
+    /// are measured in.
+    pub async fn current_height(&self) -> Result<u64> {
+        self.agent.current_height().await
+    }
+
+    /// Fetches recent chain state to attest to in proofs.
+    pub async fn chain_state(&self) -> Result<ChainState> {
+        self.agent.chain_state().await
+    }
+
+    /// Observes the settlement of an escrow at a final block, for attestors
+    /// of cross-chain conditions to sign.
+    pub async fn observe_escrow(&self, metadata: &EscrowMetadata) -> Result<Observation> {
+        self.agent.observe_escrow(metadata).await
+    }
+
+    /// Observes the state at `key` at a final block, for attestors of
+    /// cross-chain conditions to sign.
+    pub async fn observe_state(&self, key: &[u8]) -> Result<Observation> {
+        self.agent.observe_state(key).await
+    }
+
+    /// Reads the latest block (or slot).
+    pub async fn latest_block(&self) -> Result<ObservedBlock> {
+        self.agent.latest_block().await
+    }
+
+    /// Reads the hash of the block (or slot) at `height` on the canonical
+    /// chain, if it has one.
+    pub async fn block_hash(&self, height: u64) -> Result<Option<[u8; 32]>> {
+        self.agent.block_hash(height).await
+    }
+
+    /// Reads the height of the latest final block (or slot).
+    pub async fn finalized_height(&self) -> Result<u64> {
+        self.agent.finalized_height().await
+    }
+
+    /// Estimates the costs of the escrow lifecycle for `params`: creating
+    /// the escrow, and finishing or cancelling it.
+    pub async fn estimate(&self, params: &EscrowParams) -> Result<LifecycleEstimate> {
+        self.agent.estimate(params).await
+    }
+
+    /// Lists the escrows in which the configured wallets are a party, with
+    /// their live state.
+    pub async fn list_escrows(&self) -> Result<Vec<EscrowSummary>> {
+        self.agent.list_escrows().await.inspect(|escrows| {
+            debug!(count = escrows.len(), "Escrows listed");
+        })
+    }
+
+    /// Streams `Created`/`Finished`/`Cancelled` updates for one escrow, or
+    /// for all escrows of the configured wallets if `metadata` is `None`.
+    ///
+    /// # Arguments
+    ///
+    /// * `metadata` - Escrow metadata from creation, or `None` for all
+    pub async fn watch_escrows(&self, metadata: Option<&EscrowMetadata>) -> Result<UpdateStream> {
+        self.agent.watch_escrows(metadata).await
+    }
+
+    /// Releases several escrows, batched into one transaction on Ethereum.
+    ///
+    /// # Arguments
+    ///
+    /// * `metadata` - Metadata of each escrow to finish
+    pub async fn finish_escrows(&self, metadata: &[EscrowMetadata]) -> Result<()> {
+        self.agent.finish_escrows(metadata).await.inspect(|_| {
+            debug!(count = metadata.len(), "Escrows released");
+        })
+    }
+
+    /// Cancels several escrows, batched into one transaction on Ethereum.
+    ///
+    /// # Arguments
+    ///
+    /// * `metadata` - Metadata of each escrow to cancel
+    pub async fn cancel_escrows(&self, metadata: &[EscrowMetadata]) -> Result<()> {
+        self.agent.cancel_escrows(metadata).await.inspect(|_| {
+            debug!(count = metadata.len(), "Escrows cancelled");
+        })
+    }
 }
 
 impl ZescrowClientBuilder {
+    /// Sets the sender signer, overriding `sender_private_id` in the chain
+    /// configuration.
+    pub fn sender(mut self, sender: Sender) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
     /// Sets the recipient key for finish operations.
     ///
     /// This is required when calling [`ZescrowClient::finish_escrow`].
@@ -198,57 +763,628 @@ impl ZescrowClientBuilder {
         self
     }
 
-    /// Builds the client, instantiating the appropriate chain agent.
+    /// Sets EIP-1559 fee overrides and the gas price cap.
+    ///
+    /// Only applies to Ethereum; ignored on other chains.
+    pub fn fees(mut self, fees: FeeConfig) -> Self {
+        self.fees = fees;
+        self
+    }
+
+    /// Replaces Ethereum transactions that stay unmined past
+    /// [`FeeBump::timeout`] with higher-fee copies.
+    ///
+    /// Only applies to Ethereum; ignored on other chains.
+    pub fn fee_bump(mut self, bump: FeeBump) -> Self {
+        self.fees.bump = Some(bump);
+        self
+    }
+
+    /// Sets the compute unit limit and priority fee of each operation's
+    /// transactions.
+    ///
+    /// Only applies to Solana; ignored on other chains.
+    pub fn priority_fees(mut self, fees: PriorityFees) -> Self {
+        self.priority_fees = fees;
+        self
+    }
+
+    /// Compiles transactions against the address lookup tables at `tables`,
+    /// for transactions whose accounts would exceed the size limit.
+    ///
+    /// Only applies to Solana; ignored on other chains.
+    pub fn lookup_tables(mut self, tables: Vec<Pubkey>) -> Self {
+        self.lookup_tables = tables;
+        self
+    }
+
+    /// Builds offline transactions against the durable nonce in `account`,
+    /// so they do not expire with their blockhash before being broadcast.
+    ///
+    /// Only applies to Solana; ignored on other chains.
+    pub fn durable_nonce(mut self, account: Pubkey) -> Self {
+        self.nonce_account = Some(account);
+        self
+    }
+
+    /// Sets how long to wait for transaction confirmation before failing
+    /// with [`ClientError::Pending`], and how often to poll meanwhile.
+    /// Waits are unbounded by default.
+    pub fn wait(mut self, wait: WaitConfig) -> Self {
+        self.wait = wait;
+        self
+    }
+
+    /// Sets the commitment that reads and confirmations use, and how
+    /// persistently to resend transactions until they land. Defaults to
+    /// finalization, resending every two seconds.
+    ///
+    /// Only applies to Solana; ignored on other chains.
+    pub fn confirmation_strategy(mut self, confirmation: ConfirmationConfig) -> Self {
+        self.confirmation = confirmation;
+        self
+    }
+
+    /// Treats the escrow sender as a Safe, with the configured sender key
+    /// signing as one of its owners.
+    ///
+    /// Only applies to Ethereum; ignored on other chains.
+    pub fn safe(mut self, safe: SafeConfig) -> Self {
+        self.safe = Some(safe);
+        self
+    }
+
+    /// Submits finish and cancel transactions through a private relay such
+    /// as [`FLASHBOTS_PROTECT_RPC`] instead of the public mempool.
+    ///
+    /// Only applies to Ethereum; ignored on other chains.
+    pub fn private_relay(mut self, url: Url) -> Self {
+        self.private_relay = Some(url);
+        self
+    }
+
+    /// Sets how many blocks deep a transaction must be before it is treated
+    /// as final. Transactions dropped by a reorg are resent. Defaults to the
+    /// depth the configured network preset assumes for finality, or one.
+    ///
+    /// Only applies to Ethereum; ignored on other chains.
+    pub fn confirmations(mut self, confirmations: u64) -> Self {
+        self.confirmations = Some(confirmations);
+        self
+    }
+
+    /// Sets the Groth16 seal proving the escrow's conditions are met, which
+    /// the escrow contract verifies on finish.
+    ///
+    /// Only applies to Ethereum; ignored on other chains.
+    pub fn proof_seal(mut self, seal: Vec<u8>) -> Self {
+        self.proof_seal = Some(seal);
+        self
+    }
+
+    /// Sets the condition escrows with conditions are created with, whose
+    /// fingerprint the escrow contract stores and checks proofs against.
+    ///
+    /// Only applies to Ethereum, Tron, and Hedera; ignored on other chains.
+    pub fn condition(mut self, condition: &Condition) -> Self {
+        self.condition = Some(condition.fingerprint());
+        self
+    }
+
+    /// Sets the block from which escrow events are scanned when listing
+    /// escrows, e.g. the escrow contract's deployment block. Defaults to
+    /// zero.
+    ///
+    /// Only applies to Ethereum; ignored on other chains.
+    pub fn from_block(mut self, block: u64) -> Self {
+        self.from_block = block;
+        self
+    }
+
+    /// Sets how transient RPC errors are retried, and the metrics they are
+    /// counted in. Clients built with clones of one policy share its
+    /// metrics.
+    ///
+    /// Requests fail over to the chain configuration's `fallback_rpc_urls`
+    /// before being retried.
+    pub fn rpc_policy(mut self, policy: RpcPolicy) -> Self {
+        self.rpc = policy;
+        self
+    }
+
+    /// Sets the registry of agents for custom chains, replacing any
+    /// agents registered before.
+    pub fn agent_registry(mut self, registry: AgentRegistry) -> Self {
+        self.registry = registry;
+        self
+    }
+
+    /// Registers `factory` to build the agent for `chain`, the configured
+    /// `custom_chain` or a built-in chain name to replace its agent.
+    pub fn register_agent(
+        mut self,
+        chain: impl AsRef<str>,
+        factory: impl AgentFactory + 'static,
+    ) -> Self {
+        self.registry.register(chain, factory);
+        self
+    }
+
+    /// Builds the client, instantiating the appropriate chain agent: the
+    /// one registered for the chain, if any, otherwise the built-in one.
     ///
     /// # Errors
     ///
     /// Returns an error if:
-    /// - The recipient key type doesn't match the chain
+    /// - No agent is registered for the configured `custom_chain`
+    /// - The sender or recipient key type doesn't match the chain
     /// - Agent initialization fails
-    pub async fn build(self) -> Result<ZescrowClient> {
+    pub async fn build(mut self) -> Result<ZescrowClient> {
         debug!("Building ZescrowClient with config: {:?}", self.config);
+        self.resolve_secrets().await?;
+
+        let chain = registry::chain_id(&self.config).to_owned();
+        if let Some(factory) = self.registry.get(&chain) {
+            debug!(%chain, "Selected registered agent");
+            let agent = factory
+                .build(self.config.clone(), self.recipient.clone())
+                .await?;
+            info!("Agent initialized successfully");
+            return Ok(ZescrowClient {
+                agent,
+                rpc_metrics: self.rpc.metrics,
+            });
+        }
+        if self.config.custom_chain.is_some() {
+            return Err(ClientError::UnsupportedChain(format!(
+                "{chain} (no agent registered)"
+            )));
+        }
 
         let agent: Box<dyn Agent> = match &self.config.chain {
             Chain::Ethereum => {
+                let sender = self.ethereum_sender()?;
                 let wallet = self.ethereum_wallet()?;
                 debug!(wallet_present = wallet.is_some(), "Selected EthereumAgent");
-                Box::new(EthereumAgent::new(&self.config, wallet).await?)
+                let mut agent =
+                    EthereumAgent::new(&self.config, sender, wallet, self.fees, self.rpc.clone())
+                        .await?
+                        .with_from_block(self.from_block)
+                        .with_wait(self.wait);
+                if let Some(confirmations) = self.confirmations {
+                    agent = agent.with_confirmations(confirmations);
+                }
+                if let Some(url) = &self.private_relay {
+                    agent = agent.with_private_relay(url);
+                }
+                if let Some(seal) = self.proof_seal.clone() {
+                    agent = agent.with_proof_seal(seal);
+                }
+                if let Some(condition) = self.condition {
+                    agent = agent.with_condition_fingerprint(condition);
+                }
+                match self.safe.clone() {
+                    Some(safe) => Box::new(agent.with_safe(safe)),
+                    None => Box::new(agent),
+                }
             }
             Chain::Solana => {
-                let keypair_path = self.solana_keypair()?;
+                let sender = self.solana_sender()?;
+                let recipient = self.solana_recipient()?;
                 debug!(
-                    keypair_present = keypair_path.is_some(),
+                    recipient_present = recipient.is_some(),
                     "Selected SolanaAgent"
                 );
-                Box::new(SolanaAgent::new(&self.config, keypair_path).await?)
+                let agent = SolanaAgent::new(
+                    &self.config,
+                    sender,
+                    recipient,
+                    self.rpc.clone(),
+                    self.confirmation,
+                )
+                .await?
+                .with_priority_fees(self.priority_fees)
+                .with_lookup_tables(self.lookup_tables.clone())
+                .with_wait(self.wait);
+                match self.nonce_account {
+                    Some(account) => Box::new(agent.with_durable_nonce(account)),
+                    None => Box::new(agent),
+                }
+            }
+            #[cfg(feature = "cosmos")]
+            Chain::Cosmos => {
+                let sender = self.cosmos_sender()?;
+                let recipient = self.cosmos_recipient()?;
+                debug!(
+                    recipient_present = recipient.is_some(),
+                    "Selected CosmosAgent"
+                );
+                Box::new(
+                    CosmosAgent::new(&self.config, sender, recipient, self.rpc.clone())?
+                        .with_wait(self.wait),
+                )
+            }
+            #[cfg(not(feature = "cosmos"))]
+            Chain::Cosmos => return Err(cosmos_disabled()),
+            #[cfg(feature = "substrate")]
+            Chain::Substrate => {
+                let sender = self.substrate_sender()?;
+                let recipient = self.substrate_recipient()?;
+                debug!(
+                    recipient_present = recipient.is_some(),
+                    "Selected SubstrateAgent"
+                );
+                Box::new(
+                    SubstrateAgent::new(&self.config, sender, recipient, self.rpc.clone())
+                        .await?
+                        .with_wait(self.wait),
+                )
+            }
+            #[cfg(not(feature = "substrate"))]
+            Chain::Substrate => return Err(substrate_disabled()),
+            #[cfg(feature = "cardano")]
+            Chain::Cardano => {
+                let sender = self.cardano_sender()?;
+                let recipient = self.cardano_recipient()?;
+                debug!(
+                    recipient_present = recipient.is_some(),
+                    "Selected CardanoAgent"
+                );
+                Box::new(
+                    CardanoAgent::new(&self.config, sender, recipient, self.rpc.clone())?
+                        .with_wait(self.wait),
+                )
+            }
+            #[cfg(not(feature = "cardano"))]
+            Chain::Cardano => return Err(cardano_disabled()),
+            Chain::Tron => {
+                let sender = self.ethereum_sender()?;
+                let wallet = self.ethereum_wallet()?;
+                debug!(wallet_present = wallet.is_some(), "Selected TronAgent");
+                let mut agent = TronAgent::new(&self.config, sender, wallet, self.rpc.clone())
+                    .await?
+                    .with_from_block(self.from_block)
+                    .with_wait(self.wait);
+                if let Some(condition) = self.condition {
+                    agent = agent.with_condition_fingerprint(condition);
+                }
+                match self.proof_seal.clone() {
+                    Some(seal) => Box::new(agent.with_proof_seal(seal)),
+                    None => Box::new(agent),
+                }
+            }
+            Chain::Hedera => {
+                let sender = self.ethereum_sender()?;
+                let wallet = self.ethereum_wallet()?;
+                debug!(wallet_present = wallet.is_some(), "Selected HederaAgent");
+                let mut agent =
+                    HederaAgent::new(&self.config, sender, wallet, self.fees, self.rpc.clone())
+                        .await?
+                        .with_from_block(self.from_block)
+                        .with_wait(self.wait);
+                if let Some(confirmations) = self.confirmations {
+                    agent = agent.with_confirmations(confirmations);
+                }
+                if let Some(condition) = self.condition {
+                    agent = agent.with_condition_fingerprint(condition);
+                }
+                match self.proof_seal.clone() {
+                    Some(seal) => Box::new(agent.with_proof_seal(seal)),
+                    None => Box::new(agent),
+                }
             }
         };
 
         info!("Agent initialized successfully");
-        Ok(ZescrowClient { agent })
+        Ok(ZescrowClient {
+            agent,
+            rpc_metrics: self.rpc.metrics,
+        })
     }
 
-    /// Extracts the Ethereum wallet from the recipient configuration.
-    fn ethereum_wallet(&self) -> Result<Option<LocalWallet>> {
+    /// Replaces secret references in the sender key and the recipient with
+    /// the keys they resolve to, which are only held in memory.
+    async fn resolve_secrets(&mut self) -> Result<()> {
+        if let Some(key) = secrets::resolve(&self.config.sender_private_id).await? {
+            self.config.sender_private_id = key;
+        }
+        if let Some(Recipient::Secret(reference)) = &self.recipient {
+            let key = secrets::resolve(reference).await?.ok_or_else(|| {
+                ClientError::Secret(format!("not a secret reference: {reference}"))
+            })?;
+            self.recipient = Some(match self.config.chain {
+                Chain::Ethereum | Chain::Tron | Chain::Hedera => {
+                    Recipient::Ethereum(EthereumSigner::from_id(&key).await?)
+                }
+                Chain::Solana => Recipient::SolanaSigner(SolanaSigner::from_id(&key)?),
+                #[cfg(feature = "cosmos")]
+                Chain::Cosmos => Recipient::Cosmos(CosmosSigner::from_id(&key)?),
+                #[cfg(not(feature = "cosmos"))]
+                Chain::Cosmos => return Err(cosmos_disabled()),
+                #[cfg(feature = "substrate")]
+                Chain::Substrate => Recipient::Substrate(SubstrateSigner::from_id(&key)?),
+                #[cfg(not(feature = "substrate"))]
+                Chain::Substrate => return Err(substrate_disabled()),
+                #[cfg(feature = "cardano")]
+                Chain::Cardano => Recipient::Cardano(CardanoSigner::from_id(&key)?),
+                #[cfg(not(feature = "cardano"))]
+                Chain::Cardano => return Err(cardano_disabled()),
+            });
+        }
+        Ok(())
+    }
+
+    /// Extracts the Ethereum signer from the sender override.
+    fn ethereum_sender(&self) -> Result<Option<EthereumSigner>> {
+        match &self.sender {
+            Some(Sender::Ethereum(signer)) => Ok(Some(signer.clone())),
+            Some(_) => Err(ClientError::Keypair(
+                "expected Ethereum sender for Ethereum, Tron, or Hedera chain".into(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Extracts the Ethereum signer from the recipient configuration.
+    fn ethereum_wallet(&self) -> Result<Option<EthereumSigner>> {
         match &self.recipient {
             Some(Recipient::Ethereum(w)) => Ok(Some(w.clone())),
-            Some(Recipient::Solana(_)) => Err(ClientError::Keypair(
-                "expected Ethereum wallet for Ethereum chain".into(),
+            Some(Recipient::Keystore(name)) => Keystore::open_default()?
+                .ethereum_signer(name, &keystore::password()?)
+                .map(Some),
+            Some(Recipient::Secret(reference)) => Err(ClientError::Secret(format!(
+                "unresolved secret reference: {reference}"
+            ))),
+            Some(_) => Err(ClientError::Keypair(
+                "expected Ethereum wallet for Ethereum, Tron, or Hedera chain".into(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Extracts the Solana signer from the sender override.
+    fn solana_sender(&self) -> Result<Option<SolanaSigner>> {
+        match &self.sender {
+            Some(Sender::Solana(signer)) => Ok(Some(signer.clone())),
+            Some(_) => Err(ClientError::Keypair(
+                "expected Solana sender for Solana chain".into(),
             )),
             None => Ok(None),
         }
     }
 
-    /// Extracts the Solana keypair path from the recipient configuration.
-    fn solana_keypair(&self) -> Result<Option<PathBuf>> {
+    /// Loads the Solana signer from the recipient configuration.
+    fn solana_recipient(&self) -> Result<Option<SolanaSigner>> {
         match &self.recipient {
-            Some(Recipient::Solana(path)) => Ok(Some(path.clone())),
-            Some(Recipient::Ethereum(_)) => Err(ClientError::Keypair(
+            Some(Recipient::Solana(path)) => SolanaSigner::from_keypair_file(path).map(Some),
+            Some(Recipient::SolanaSigner(signer)) => Ok(Some(signer.clone())),
+            Some(Recipient::Keystore(name)) => Keystore::open_default()?
+                .solana_signer(name, &keystore::password()?)
+                .map(Some),
+            Some(Recipient::Secret(reference)) => Err(ClientError::Secret(format!(
+                "unresolved secret reference: {reference}"
+            ))),
+            Some(_) => Err(ClientError::Keypair(
                 "expected Solana keypair file for Solana chain".into(),
             )),
             None => Ok(None),
         }
     }
+
+    /// Extracts the Cosmos signer from the sender override.
+    #[cfg(feature = "cosmos")]
+    fn cosmos_sender(&self) -> Result<Option<CosmosSigner>> {
+        match &self.sender {
+            Some(Sender::Cosmos(signer)) => Ok(Some(signer.clone())),
+            Some(Sender::Ethereum(signer)) => CosmosSigner::from_ethereum(signer).map(Some),
+            Some(_) => Err(ClientError::Keypair(
+                "expected Cosmos sender for Cosmos chain".into(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Loads the Cosmos signer from the recipient configuration. Hex keys
+    /// parse as Ethereum keys, whose secp256k1 secret is used as is.
+    #[cfg(feature = "cosmos")]
+    fn cosmos_recipient(&self) -> Result<Option<CosmosSigner>> {
+        match &self.recipient {
+            Some(Recipient::Cosmos(signer)) => Ok(Some(signer.clone())),
+            Some(Recipient::Ethereum(signer)) => CosmosSigner::from_ethereum(signer).map(Some),
+            Some(Recipient::Keystore(name)) => Keystore::open_default()?
+                .cosmos_signer(name, &keystore::password()?)
+                .map(Some),
+            Some(Recipient::Secret(reference)) => Err(ClientError::Secret(format!(
+                "unresolved secret reference: {reference}"
+            ))),
+            Some(_) => Err(ClientError::Keypair(
+                "expected secp256k1 key for Cosmos chain".into(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Extracts the Substrate signer from the sender override.
+    #[cfg(feature = "substrate")]
+    fn substrate_sender(&self) -> Result<Option<SubstrateSigner>> {
+        match &self.sender {
+            Some(Sender::Substrate(signer)) => Ok(Some(signer.clone())),
+            Some(Sender::Ethereum(signer)) => SubstrateSigner::from_ethereum(signer).map(Some),
+            Some(_) => Err(ClientError::Keypair(
+                "expected Substrate sender for Substrate chain".into(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Loads the Substrate signer from the recipient configuration. Hex
+    /// keys parse as Ethereum keys, whose secret is used as the sr25519
+    /// seed.
+    #[cfg(feature = "substrate")]
+    fn substrate_recipient(&self) -> Result<Option<SubstrateSigner>> {
+        match &self.recipient {
+            Some(Recipient::Substrate(signer)) => Ok(Some(signer.clone())),
+            Some(Recipient::Ethereum(signer)) => SubstrateSigner::from_ethereum(signer).map(Some),
+            Some(Recipient::Keystore(name)) => Keystore::open_default()?
+                .substrate_signer(name, &keystore::password()?)
+                .map(Some),
+            Some(Recipient::Secret(reference)) => Err(ClientError::Secret(format!(
+                "unresolved secret reference: {reference}"
+            ))),
+            Some(_) => Err(ClientError::Keypair(
+                "expected sr25519 seed for Substrate chain".into(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Extracts the Cardano signer from the sender override.
+    #[cfg(feature = "cardano")]
+    fn cardano_sender(&self) -> Result<Option<CardanoSigner>> {
+        match &self.sender {
+            Some(Sender::Cardano(signer)) => Ok(Some(signer.clone())),
+            Some(Sender::Ethereum(signer)) => CardanoSigner::from_ethereum(signer).map(Some),
+            Some(_) => Err(ClientError::Keypair(
+                "expected Cardano sender for Cardano chain".into(),
+            )),
+            None => Ok(None),
+        }
+    }
+
+    /// Loads the Cardano signer from the recipient configuration. Hex keys
+    /// parse as Ethereum keys, whose secret is used as the ed25519 signing
+    /// key.
+    #[cfg(feature = "cardano")]
+    fn cardano_recipient(&self) -> Result<Option<CardanoSigner>> {
+        match &self.recipient {
+            Some(Recipient::Cardano(signer)) => Ok(Some(signer.clone())),
+            Some(Recipient::Ethereum(signer)) => CardanoSigner::from_ethereum(signer).map(Some),
+            Some(Recipient::Keystore(name)) => Keystore::open_default()?
+                .cardano_signer(name, &keystore::password()?)
+                .map(Some),
+            Some(Recipient::Secret(reference)) => Err(ClientError::Secret(format!(
+                "unresolved secret reference: {reference}"
+            ))),
+            Some(_) => Err(ClientError::Keypair(
+                "expected ed25519 signing key for Cardano chain".into(),
+            )),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Derives the escrow sender from the key configured in `config`.
+///
+/// On Ethereum this is the configured smart account if any, otherwise the
+/// address of the sender key; on Solana, the public key of the sender keypair;
+/// on Cosmos, the Bech32 address of the sender key; on Substrate, the SS58
+/// address of the sender key, with the network prefix of the contract's;
+/// on Cardano, the enterprise address of the sender key; on Tron, the
+/// base58check address of the sender key; on Hedera, the EVM address of the
+/// sender key.
+///
+/// # Errors
+///
+/// Returns an error if the sender key cannot be resolved or loaded.
+pub async fn sender_party(config: &ChainConfig) -> Result<Party> {
+    let key = secrets::resolve(&config.sender_private_id)
+        .await?
+        .unwrap_or_else(|| config.sender_private_id.clone());
+    match config.chain {
+        Chain::Ethereum => {
+            let account = config
+                .smart_account
+                .as_ref()
+                .and_then(|account| account.sender_account.as_deref());
+            if let Some(account) = account {
+                return Party::new(account).map_err(|e| ClientError::Keypair(e.to_string()));
+            }
+            let address = alloy::signers::Signer::address(&EthereumSigner::from_id(&key).await?);
+            Ok(Party::from(ID::Hex(hex::encode(address))))
+        }
+        Chain::Solana => {
+            let pubkey = SolanaSigner::from_id(&key)?.pubkey();
+            Ok(Party::from(ID::Base58(pubkey.to_string())))
+        }
+        #[cfg(feature = "cosmos")]
+        Chain::Cosmos => {
+            let prefix = config.cosmos.as_ref().map(|cosmos| cosmos.prefix.as_str());
+            let prefix = prefix.ok_or_else(|| {
+                ClientError::cosmos("config", "`cosmos` settings are required on Cosmos")
+            })?;
+            let address = CosmosSigner::from_id(&key)?.account_id(prefix)?;
+            Ok(Party::from(ID::Bech32(address.to_string())))
+        }
+        #[cfg(not(feature = "cosmos"))]
+        Chain::Cosmos => Err(cosmos_disabled()),
+        #[cfg(feature = "substrate")]
+        Chain::Substrate => {
+            let prefix = ID::Ss58(config.agent_id.clone())
+                .ss58_prefix()
+                .ok_or_else(|| {
+                    ClientError::substrate("config", "`agent_id` must be an SS58 address")
+                })?;
+            let address = SubstrateSigner::from_id(&key)?.address(prefix);
+            Ok(Party::from(ID::Ss58(address)))
+        }
+        #[cfg(not(feature = "substrate"))]
+        Chain::Substrate => Err(substrate_disabled()),
+        #[cfg(feature = "cardano")]
+        Chain::Cardano => {
+            let network = config.cardano.as_ref().map(|cardano| cardano.network);
+            let network = network.ok_or_else(|| {
+                ClientError::cardano("config", "`cardano` settings are required on Cardano")
+            })?;
+            let address = CardanoSigner::from_id(&key)?.address(network);
+            Ok(Party::from(ID::Bech32(address)))
+        }
+        #[cfg(not(feature = "cardano"))]
+        Chain::Cardano => Err(cardano_disabled()),
+        Chain::Tron => {
+            let address = alloy::signers::Signer::address(&EthereumSigner::from_id(&key).await?);
+            Ok(Party::from(ID::tron(address.into())))
+        }
+        Chain::Hedera => {
+            let address = alloy::signers::Signer::address(&EthereumSigner::from_id(&key).await?);
+            Ok(Party::from(ID::Hex(hex::encode(address))))
+        }
+    }
+}
+
+/// Error for Cosmos chains in builds without the `cosmos` feature.
+#[cfg(not(feature = "cosmos"))]
+fn cosmos_disabled() -> ClientError {
+    ClientError::UnsupportedChain("cosmos (requires the `cosmos` feature)".into())
+}
+
+/// Error for Substrate chains in builds without the `substrate` feature.
+#[cfg(not(feature = "substrate"))]
+fn substrate_disabled() -> ClientError {
+    ClientError::UnsupportedChain("substrate (requires the `substrate` feature)".into())
+}
+
+/// Error for Cardano in builds without the `cardano` feature.
+#[cfg(not(feature = "cardano"))]
+fn cardano_disabled() -> ClientError {
+    ClientError::UnsupportedChain("cardano (requires the `cardano` feature)".into())
+}
+
+/// Whether the escrow program or contract on `chain` stores memos: on
+/// Solana, Cosmos, and Cardano.
+pub fn stores_memo(chain: Chain) -> bool {
+    matches!(chain, Chain::Solana | Chain::Cosmos | Chain::Cardano)
+}
+
+/// Rejects the memo in `params`, if any, on chains whose escrow contract
+/// cannot store it, rather than dropping it.
+pub(crate) fn reject_memo(params: &EscrowParams) -> Result<()> {
+    if params.memo.is_some() {
+        return Err(ClientError::InvalidChainOperation(format!(
+            "memos are not stored on {}; remove `memo` from the escrow parameters",
+            params.chain_config.chain.as_ref()
+        )));
+    }
+    Ok(())
 }
 
 impl std::str::FromStr for Recipient {
@@ -256,13 +1392,22 @@ impl std::str::FromStr for Recipient {
 
     /// Parses a recipient from a string.
     ///
-    /// - Strings starting with `0x` are parsed as Ethereum private keys (prefix required)
+    /// - Strings starting with `0x` are parsed as 32-byte private keys, for
+    ///   Ethereum, Cosmos, Substrate, Cardano, Tron, or Hedera (prefix required)
+    /// - `keystore:<name>` refers to a key in the encrypted [`Keystore`]
+    /// - `env:`, `vault:`, and `aws-sm:` are [secret references](secrets)
     /// - Other strings are treated as paths to Solana keypair files
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        if secrets::is_reference(s) {
+            return Ok(Self::Secret(s.to_owned()));
+        }
+        if let Some(name) = s.strip_prefix(keystore::KEYSTORE_PREFIX) {
+            return Ok(Self::Keystore(name.to_owned()));
+        }
         s.strip_prefix("0x")
             .map(|_| {
-                s.parse::<LocalWallet>()
-                    .map(Self::Ethereum)
+                s.parse::<PrivateKeySigner>()
+                    .map(|signer| Self::Ethereum(signer.into()))
                     .map_err(|e| ClientError::Keypair(e.to_string()))
             })
             .unwrap_or_else(|| Ok(Self::Solana(PathBuf::from(s))))
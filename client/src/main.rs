@@ -1,53 +1,1408 @@
-use std::path::PathBuf;
+use std::io::IsTerminal;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
-use anyhow::{anyhow, Context};
-use clap::{value_parser, Parser, Subcommand};
+use alloy::primitives::utils::parse_units;
+use anyhow::{anyhow, bail, Context};
+use clap::{value_parser, Args, Parser, Subcommand, ValueEnum};
+use futures::StreamExt;
+use serde::Serialize;
 use sha2::{Digest, Sha256};
-use tracing::info;
+use solana_sdk::commitment_config::CommitmentLevel;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use tracing::{debug, info, warn};
+use url::Url;
 #[cfg(feature = "prover")]
 use zescrow_client::prover;
-use zescrow_client::{Recipient, ZescrowClient};
+use zescrow_client::solana::compute_budget::DEFAULT_FEE_PERCENTILE;
+use zescrow_client::{
+    journal, quote, reorg, secrets, solana, ClientError, ComputeBudget, ConfirmationConfig,
+    EscrowStore, EscrowSummary, FeeBump, FeeConfig, Journal, JournalOp, LifecycleEstimate,
+    Operation, PendingTransaction, PriceFeeds, PriorityFee, PriorityFees, Quote, RateLimit,
+    RateLimiter, Recipient, Reconciled, RetryConfig, RpcPolicy, SafeConfig, Simulation,
+    SolanaAgent, StoredEscrow, WaitConfig, ZescrowClient, ZescrowClientBuilder,
+    FLASHBOTS_PROTECT_RPC,
+};
+use zescrow_core::condition::bridge::{BridgeMessage, Packet, Verifiers};
+use zescrow_core::condition::cross_chain::RemoteClaim;
 use zescrow_core::interface::{
-    load_escrow_data, save_escrow_data, ESCROW_CONDITIONS_PATH, ESCROW_METADATA_PATH,
-    ESCROW_PARAMS_PATH,
+    load_escrow_data, save_escrow_data, ChainConfig, Freshness, ProofArtifact,
+    ESCROW_CONDITIONS_PATH, ESCROW_METADATA_PATH, ESCROW_PARAMS_PATH, PROOF_DATA_PATH,
 };
-use zescrow_core::{Condition, EscrowMetadata, EscrowParams};
+use zescrow_core::{Chain, Condition, EscrowMetadata, EscrowParams, ExecutionState, Party, ID};
+
+use crate::contacts::Contacts;
+use crate::output::{print_json, OutputFormat};
+use crate::profile::Profile;
+
+mod archive;
+mod batch;
+mod bulk;
+mod contacts;
+mod daemon;
+mod doctor;
+mod init;
+mod key;
+mod output;
+mod profile;
+mod proof;
+mod serve;
+mod share;
+mod tx;
+mod ui;
+mod webhook;
+
+/// Default path of the local escrow store.
+const STORE_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/zescrow.db");
+
+/// Default path of the price feeds used by `quote`.
+const PRICE_FEEDS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/price_feeds.json");
+
+/// Default path of the operation journal.
+const JOURNAL_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/zescrow-journal.db");
 
 #[derive(Parser)]
 #[command(author, version, about)]
 struct Cli {
     #[command(subcommand)]
     command: Commands,
+
+    #[command(flatten)]
+    fees: FeeArgs,
+
+    #[command(flatten)]
+    compute_budget: ComputeBudgetArgs,
+
+    #[command(flatten)]
+    confirmation: ConfirmationArgs,
+
+    #[command(flatten)]
+    wait: WaitArgs,
+
+    /// Address lookup table that Solana transactions may refer to accounts
+    /// in (repeatable; see `lookup-table`)
+    #[arg(
+        long = "lookup-table",
+        global = true,
+        env = "ZESCROW_LOOKUP_TABLES",
+        value_delimiter = ',',
+        value_name = "ADDRESS"
+    )]
+    lookup_tables: Vec<Pubkey>,
+
+    #[command(flatten)]
+    safe: SafeArgs,
+
+    #[command(flatten)]
+    rpc: RpcArgs,
+
+    #[command(flatten)]
+    chain: ChainArgs,
+
+    #[command(flatten)]
+    prover: proof::ProverArgs,
+
+    /// Send Ethereum finish/cancel transactions through a private relay
+    /// instead of the public mempool (Flashbots Protect if no URL is given)
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_PRIVATE_RPC",
+        value_name = "URL",
+        num_args = 0..=1,
+        default_missing_value = FLASHBOTS_PROTECT_RPC
+    )]
+    private_rpc: Option<Url>,
+
+    /// Blocks an Ethereum transaction must be buried under before it is
+    /// treated as final [default: the network preset's finality depth, or 1]
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_CONFIRMATIONS",
+        value_name = "BLOCKS"
+    )]
+    confirmations: Option<u64>,
+
+    /// Build and simulate create/finish/cancel transactions, printing the
+    /// outcome, without broadcasting them
+    #[arg(long, global = true, env = "ZESCROW_DRY_RUN")]
+    dry_run: bool,
+
+    /// Record escrows and their state transitions in a local SQLite store
+    /// (`deploy/zescrow.db` if no path is given), and operate on stored
+    /// escrows instead of `escrow_metadata.json`
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_STORE",
+        value_name = "PATH",
+        num_args = 0..=1,
+        default_missing_value = STORE_PATH,
+        value_parser = value_parser!(PathBuf)
+    )]
+    store: Option<PathBuf>,
+
+    /// Write-ahead journal of create, finish, and cancel operations, used
+    /// to recover from runs interrupted after broadcasting
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_JOURNAL",
+        value_name = "PATH",
+        default_value = JOURNAL_PATH,
+        value_parser = value_parser!(PathBuf)
+    )]
+    journal: PathBuf,
+
+    /// Use the chain configuration, escrow parameters, and recipient key of
+    /// this profile from the profiles file
+    #[arg(long, global = true, env = "ZESCROW_PROFILE", value_name = "NAME")]
+    profile: Option<String>,
+
+    /// Profiles file
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_PROFILES",
+        value_name = "FILE",
+        default_value = profile::PROFILES_PATH,
+        value_parser = value_parser!(PathBuf)
+    )]
+    profiles: PathBuf,
+
+    /// Contacts file, resolving `@<name>` recipients
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_CONTACTS",
+        value_name = "FILE",
+        default_value = contacts::CONTACTS_PATH,
+        value_parser = value_parser!(PathBuf)
+    )]
+    contacts: PathBuf,
+
+    /// Print command results as human-readable text or as JSON
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_OUTPUT",
+        value_enum,
+        default_value_t = OutputFormat::Text
+    )]
+    output: OutputFormat,
+}
+
+/// EIP-1559 fee options for Ethereum transactions (ignored on Solana).
+#[derive(Args, Debug)]
+struct FeeArgs {
+    /// Max fee per gas in gwei (estimated if omitted)
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_MAX_FEE",
+        value_name = "GWEI",
+        value_parser = parse_gwei
+    )]
+    max_fee: Option<u128>,
+
+    /// Max priority fee per gas in gwei (estimated if omitted)
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_PRIORITY_FEE",
+        value_name = "GWEI",
+        value_parser = parse_gwei
+    )]
+    priority_fee: Option<u128>,
+
+    /// Abort instead of sending if the max fee per gas exceeds this many gwei
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_GAS_PRICE_CAP",
+        value_name = "GWEI",
+        value_parser = parse_gwei
+    )]
+    gas_price_cap: Option<u128>,
+
+    /// Replace a transaction still unmined after this many seconds with a
+    /// higher-fee copy
+    #[arg(long, global = true, env = "ZESCROW_BUMP_AFTER", value_name = "SECS")]
+    bump_after: Option<u64>,
+
+    /// Fee increase per replacement, in percent
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_BUMP_PERCENT",
+        value_name = "PERCENT",
+        default_value_t = 15,
+        requires = "bump_after"
+    )]
+    bump_percent: u64,
+
+    /// Maximum number of replacements
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_MAX_BUMPS",
+        value_name = "N",
+        default_value_t = 5,
+        requires = "bump_after"
+    )]
+    max_bumps: u32,
+}
+
+impl From<FeeArgs> for FeeConfig {
+    fn from(args: FeeArgs) -> Self {
+        Self {
+            max_fee_per_gas: args.max_fee,
+            max_priority_fee_per_gas: args.priority_fee,
+            gas_price_cap: args.gas_price_cap,
+            bump: args.bump_after.map(|secs| FeeBump {
+                timeout: Duration::from_secs(secs),
+                percent: args.bump_percent,
+                max_bumps: args.max_bumps,
+            }),
+        }
+    }
+}
+
+/// Compute budget options for Solana transactions (ignored on Ethereum).
+#[derive(Args, Debug)]
+struct ComputeBudgetArgs {
+    /// Compute unit limit (sized from a simulation if omitted and a priority
+    /// fee is set)
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_COMPUTE_UNIT_LIMIT",
+        value_name = "UNITS"
+    )]
+    compute_unit_limit: Option<u32>,
+
+    /// Priority fee in micro-lamports per compute unit, or `auto` to estimate
+    /// it from fees recently paid for the escrow's accounts
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_COMPUTE_UNIT_PRICE",
+        value_name = "MICROLAMPORTS|auto",
+        value_parser = parse_unit_price
+    )]
+    compute_unit_price: Option<UnitPrice>,
+
+    /// Compute unit limit of finish transactions, overriding
+    /// --compute-unit-limit
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_FINISH_COMPUTE_UNIT_LIMIT",
+        value_name = "UNITS"
+    )]
+    finish_compute_unit_limit: Option<u32>,
+
+    /// Priority fee of finish transactions, overriding --compute-unit-price
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_FINISH_COMPUTE_UNIT_PRICE",
+        value_name = "MICROLAMPORTS|auto",
+        value_parser = parse_unit_price
+    )]
+    finish_compute_unit_price: Option<UnitPrice>,
+
+    /// Percentile of recent priority fees paid by `auto` prices
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_FEE_PERCENTILE",
+        value_name = "PERCENTILE",
+        default_value_t = DEFAULT_FEE_PERCENTILE,
+        value_parser = value_parser!(u8).range(0..=100)
+    )]
+    fee_percentile: u8,
+
+    /// Cap on `auto` prices, in micro-lamports per compute unit
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_MAX_COMPUTE_UNIT_PRICE",
+        value_name = "MICROLAMPORTS"
+    )]
+    max_compute_unit_price: Option<u64>,
+}
+
+/// Confirmation options for Solana transactions (ignored on Ethereum).
+#[derive(Args, Debug)]
+struct ConfirmationArgs {
+    /// Commitment that transactions are confirmed at and accounts are read
+    /// at
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_COMMITMENT",
+        value_enum,
+        default_value_t = Commitment::Finalized
+    )]
+    commitment: Commitment,
+
+    /// Seconds between resends of an unconfirmed transaction
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_RESUBMIT_INTERVAL",
+        value_name = "SECS",
+        default_value_t = 2
+    )]
+    resubmit_interval: u64,
+
+    /// Don't show a spinner while waiting for confirmation
+    #[arg(long, global = true, env = "ZESCROW_NO_SPINNER")]
+    no_spinner: bool,
+}
+
+/// Confirmation wait options for both chains.
+#[derive(Args, Debug)]
+struct WaitArgs {
+    /// Stop waiting for a transaction to be confirmed after this many
+    /// seconds, printing how to resume the wait (unbounded if omitted)
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_CONFIRM_TIMEOUT",
+        value_name = "SECS"
+    )]
+    confirm_timeout: Option<u64>,
+
+    /// Milliseconds between confirmation polls (defaults to 4000 on
+    /// Ethereum and 500 on Solana)
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_POLL_INTERVAL_MS",
+        value_name = "MILLIS"
+    )]
+    poll_interval_ms: Option<u64>,
+}
+
+impl From<WaitArgs> for WaitConfig {
+    fn from(args: WaitArgs) -> Self {
+        Self {
+            timeout: args.confirm_timeout.map(Duration::from_secs),
+            poll_interval: args.poll_interval_ms.map(Duration::from_millis),
+        }
+    }
+}
+
+/// Webhook notification options of `daemon` and `serve`.
+#[derive(Args, Debug)]
+struct WebhookArgs {
+    /// URL to POST escrow state transitions to; repeat for several
+    #[arg(long = "webhook", value_name = "URL")]
+    webhooks: Vec<Url>,
+
+    /// Secret that webhook payloads are signed with (HMAC-SHA256).
+    /// Defaults to `ZESCROW_WEBHOOK_SECRET`
+    #[arg(long, value_name = "SECRET")]
+    webhook_secret: Option<String>,
+
+    /// Delivery attempts per notification, with exponential backoff
+    #[arg(long, value_name = "N", default_value_t = 5)]
+    webhook_attempts: u32,
+}
+
+impl WebhookArgs {
+    fn webhooks(self) -> anyhow::Result<webhook::Webhooks> {
+        webhook::Webhooks::new(self.webhooks, self.webhook_secret, self.webhook_attempts)
+    }
+}
+
+/// Solana commitment level.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+enum Commitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl ConfirmationArgs {
+    /// Builds the confirmation strategy, showing a spinner only if
+    /// `interactive` and stderr is a terminal.
+    fn config(&self, interactive: bool) -> ConfirmationConfig {
+        ConfirmationConfig {
+            commitment: match self.commitment {
+                Commitment::Processed => CommitmentLevel::Processed,
+                Commitment::Confirmed => CommitmentLevel::Confirmed,
+                Commitment::Finalized => CommitmentLevel::Finalized,
+            },
+            resubmit_interval: Duration::from_secs(self.resubmit_interval),
+            spinner: interactive && !self.no_spinner && std::io::stderr().is_terminal(),
+        }
+    }
+}
+
+/// Priority fee given on the command line.
+#[derive(Debug, Clone, Copy)]
+enum UnitPrice {
+    Fixed(u64),
+    Auto,
+}
+
+impl ComputeBudgetArgs {
+    /// Builds a compute budget, estimating `auto` prices at the configured
+    /// percentile and cap.
+    fn budget(&self, unit_limit: Option<u32>, unit_price: Option<UnitPrice>) -> ComputeBudget {
+        ComputeBudget {
+            unit_limit,
+            unit_price: match unit_price {
+                None => PriorityFee::None,
+                Some(UnitPrice::Fixed(price)) => PriorityFee::Fixed(price),
+                Some(UnitPrice::Auto) => PriorityFee::Auto {
+                    percentile: self.fee_percentile,
+                    max: self.max_compute_unit_price,
+                },
+            },
+        }
+    }
+}
+
+impl From<ComputeBudgetArgs> for PriorityFees {
+    fn from(args: ComputeBudgetArgs) -> Self {
+        let finish = args.budget(
+            args.finish_compute_unit_limit.or(args.compute_unit_limit),
+            args.finish_compute_unit_price.or(args.compute_unit_price),
+        );
+        Self {
+            finish,
+            ..Self::uniform(args.budget(args.compute_unit_limit, args.compute_unit_price))
+        }
+    }
+}
+
+fn parse_unit_price(s: &str) -> Result<UnitPrice, String> {
+    match s {
+        "auto" => Ok(UnitPrice::Auto),
+        _ => s
+            .parse()
+            .map(UnitPrice::Fixed)
+            .map_err(|_| format!("expected micro-lamports or `auto`, got {s}")),
+    }
+}
+
+/// Retry and rate limiting options for RPC requests.
+#[derive(Args, Debug)]
+struct RpcArgs {
+    /// Times a request is retried after every configured RPC endpoint
+    /// failed with a transient error
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_RPC_RETRIES",
+        value_name = "N",
+        default_value_t = 3
+    )]
+    rpc_retries: u32,
+
+    /// Delay before the first retry, doubled on each further retry
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_RPC_BACKOFF_MS",
+        value_name = "MILLIS",
+        default_value_t = 250
+    )]
+    rpc_backoff_ms: u64,
+
+    /// Maximum requests per second to each RPC endpoint, or with `HOST=`
+    /// to the endpoints at HOST only (e.g. `api.devnet.solana.com=4`).
+    /// Repeatable; unlimited by default
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_RPC_RATE_LIMITS",
+        value_delimiter = ',',
+        value_name = "[HOST=]RATE",
+        value_parser = parse_rate_limit
+    )]
+    rpc_rate_limit: Vec<(Option<String>, RateLimit)>,
+}
+
+impl From<RpcArgs> for RpcPolicy {
+    fn from(args: RpcArgs) -> Self {
+        let mut default = None;
+        let mut endpoints = Vec::new();
+        for (host, limit) in args.rpc_rate_limit {
+            match host {
+                Some(host) => endpoints.push((host, limit)),
+                None => default = Some(limit),
+            }
+        }
+        let limiter = endpoints
+            .into_iter()
+            .fold(RateLimiter::new(default), |limiter, (host, limit)| {
+                limiter.endpoint(host, limit)
+            });
+        Self::new(RetryConfig {
+            max_retries: args.rpc_retries,
+            initial_backoff: Duration::from_millis(args.rpc_backoff_ms),
+            ..RetryConfig::default()
+        })
+        .rate_limiter(limiter)
+    }
+}
+
+/// Parses a `[HOST=]RATE` rate limit.
+fn parse_rate_limit(s: &str) -> Result<(Option<String>, RateLimit), String> {
+    match s.rsplit_once('=') {
+        Some((host, rate)) if !host.is_empty() => Ok((Some(host.to_owned()), rate.parse()?)),
+        Some(_) => Err(format!("expected [HOST=]RATE, got {s}")),
+        None => Ok((None, s.parse()?)),
+    }
+}
+
+/// Options for an Ethereum sender that is a Safe (ignored on Solana).
+#[derive(Args, Debug)]
+struct SafeArgs {
+    /// Address of the Safe acting as escrow sender; the configured sender
+    /// key must be one of its owners
+    #[arg(long, global = true, env = "ZESCROW_SAFE", value_name = "ADDRESS")]
+    safe: Option<alloy::primitives::Address>,
+
+    /// Safe Transaction Service URL, required for Safes with a threshold above one
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_SAFE_TX_SERVICE",
+        value_name = "URL",
+        requires = "safe"
+    )]
+    safe_tx_service: Option<Url>,
+}
+
+impl From<SafeArgs> for Option<SafeConfig> {
+    fn from(args: SafeArgs) -> Self {
+        args.safe.map(|address| SafeConfig {
+            address,
+            service_url: args.safe_tx_service,
+        })
+    }
+}
+
+/// Settings overriding those of the profile and the escrow parameters
+/// file.
+#[derive(Args, Debug)]
+struct ChainArgs {
+    /// Escrow parameters file [default: the profile's, or
+    /// templates/escrow_params.json]
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_PARAMS",
+        value_name = "FILE",
+        value_parser = value_parser!(PathBuf)
+    )]
+    params: Option<PathBuf>,
+
+    /// JSON-RPC endpoint, overriding `chain_config.rpc_url`
+    #[arg(long, global = true, env = "ZESCROW_RPC_URL", value_name = "URL")]
+    rpc_url: Option<String>,
+
+    /// Fallback JSON-RPC endpoint, overriding
+    /// `chain_config.fallback_rpc_urls` (repeatable)
+    #[arg(
+        long = "fallback-rpc-url",
+        global = true,
+        env = "ZESCROW_FALLBACK_RPC_URLS",
+        value_delimiter = ',',
+        value_name = "URL"
+    )]
+    fallback_rpc_urls: Vec<String>,
+
+    /// WebSocket URL or IPC socket path, overriding
+    /// `chain_config.pubsub_url`
+    #[arg(long, global = true, env = "ZESCROW_PUBSUB_URL", value_name = "URL")]
+    pubsub_url: Option<String>,
+
+    /// Sender key or secret reference, overriding
+    /// `chain_config.sender_private_id`
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_SENDER_PRIVATE_ID",
+        hide_env_values = true,
+        value_name = "KEY"
+    )]
+    sender_private_id: Option<String>,
+
+    /// Program ID or contract address of new escrows, overriding
+    /// `chain_config.agent_id`
+    #[arg(long, global = true, env = "ZESCROW_AGENT_ID", value_name = "ID")]
+    agent_id: Option<String>,
+}
+
+impl ChainArgs {
+    /// Overrides the endpoints and sender key of `config`. Existing escrows
+    /// keep the program or contract they were created with.
+    fn connect(&self, config: &mut ChainConfig) {
+        if let Some(url) = &self.rpc_url {
+            config.rpc_url.clone_from(url);
+        }
+        if !self.fallback_rpc_urls.is_empty() {
+            config.fallback_rpc_urls.clone_from(&self.fallback_rpc_urls);
+        }
+        if let Some(url) = &self.pubsub_url {
+            config.pubsub_url = Some(url.clone());
+        }
+        if let Some(key) = &self.sender_private_id {
+            config.sender_private_id.clone_from(key);
+        }
+    }
+
+    /// Overrides every setting of `config`, for new escrows.
+    fn apply(&self, config: &mut ChainConfig) {
+        self.connect(config);
+        if let Some(agent_id) = &self.agent_id {
+            config.agent_id.clone_from(agent_id);
+        }
+    }
+}
+
+/// Environment variable holding the recipient key, used when neither
+/// `--recipient` nor the profile gives one.
+const RECIPIENT_VAR: &str = "ZESCROW_RECIPIENT";
+
+/// Options shared by all commands.
+struct ClientOpts {
+    fees: FeeConfig,
+    priority_fees: PriorityFees,
+    lookup_tables: Vec<Pubkey>,
+    confirmation: ConfirmationConfig,
+    wait: WaitConfig,
+    safe: Option<SafeConfig>,
+    private_rpc: Option<Url>,
+    confirmations: Option<u64>,
+    dry_run: bool,
+    output: OutputFormat,
+    store: Option<EscrowStore>,
+    journal: Journal,
+    rpc: RpcPolicy,
+    profile: Option<Profile>,
+    profiles: PathBuf,
+    contacts: PathBuf,
+    chain: ChainArgs,
+    prover: proof::ProverArgs,
+}
+
+impl ClientOpts {
+    /// Starts a client builder for `config` with the shared options applied.
+    fn builder(&self, config: &ChainConfig) -> ZescrowClientBuilder {
+        let mut config = config.clone();
+        self.chain.connect(&mut config);
+        let mut builder = ZescrowClient::builder(&config)
+            .fees(self.fees)
+            .priority_fees(self.priority_fees)
+            .lookup_tables(self.lookup_tables.clone())
+            .confirmation_strategy(self.confirmation)
+            .wait(self.wait)
+            .rpc_policy(self.rpc.clone());
+        if let Some(confirmations) = self.confirmations {
+            builder = builder.confirmations(confirmations);
+        }
+        if let Some(safe) = &self.safe {
+            builder = builder.safe(safe.clone());
+        }
+        if let Some(url) = &self.private_rpc {
+            builder = builder.private_relay(url.clone());
+        }
+        builder
+    }
+
+    /// Returns the escrow parameters file: `--params`, the selected
+    /// profile's, or [`ESCROW_PARAMS_PATH`].
+    fn params_path(&self) -> &std::path::Path {
+        self.params_path_for(self.profile.as_ref())
+    }
+
+    /// Returns the escrow parameters file: `--params`, `profile`'s, or
+    /// [`ESCROW_PARAMS_PATH`].
+    fn params_path_for<'a>(&'a self, profile: Option<&'a Profile>) -> &'a std::path::Path {
+        self.chain
+            .params
+            .as_deref()
+            .or_else(|| profile.and_then(|profile| profile.params.as_deref()))
+            .unwrap_or(std::path::Path::new(ESCROW_PARAMS_PATH))
+    }
+
+    /// Loads the escrow parameters from [`params_path`](Self::params_path),
+    /// with the chain configuration of the selected profile if any, and
+    /// flags and `ZESCROW_*` variables overriding both.
+    ///
+    /// Without a `sender`, the escrow is sent from the Safe if one is
+    /// selected, or from the account of the configured sender key. A
+    /// `"@<name>"` recipient is looked up in the contacts file.
+    async fn load_params(&self) -> anyhow::Result<EscrowParams> {
+        self.load_params_for(self.profile.as_ref()).await
+    }
+
+    /// Loads the escrow parameters as [`load_params`](Self::load_params)
+    /// does, with `profile` in place of the selected profile.
+    async fn load_params_for(&self, profile: Option<&Profile>) -> anyhow::Result<EscrowParams> {
+        let path = self.params_path_for(profile);
+        info!("Loading escrow parameters from {}", path.display());
+        let mut params: serde_json::Value = load_escrow_data(path)?;
+        if let Some(profile) = profile {
+            params["chain_config"] = serde_json::to_value(&profile.chain_config)?;
+        }
+        let mut config: ChainConfig = serde_json::from_value(params["chain_config"].clone())
+            .with_context(|| format!("parsing chain configuration from {path:?}"))?;
+        self.chain.apply(&mut config);
+        params["chain_config"] = serde_json::to_value(&config)?;
+        if let Some(name) = params["recipient"]
+            .as_str()
+            .and_then(|recipient| recipient.strip_prefix(contacts::CONTACT_PREFIX))
+        {
+            let name = name.to_owned();
+            let recipient = Contacts::load(&self.contacts)?.party(&name, config.chain)?;
+            info!("Resolved recipient @{name} to {recipient}");
+            params["recipient"] = serde_json::to_value(&recipient)?;
+            params["recipient_contact"] = name.into();
+        }
+        if matches!(params.get("sender"), None | Some(serde_json::Value::Null)) {
+            let sender = match &self.safe {
+                Some(safe) => Party::from(ID::Hex(hex::encode(safe.address))),
+                None => zescrow_client::sender_party(&config)
+                    .await
+                    .context("deriving the sender from the configured key")?,
+            };
+            info!("No sender given, using {sender}");
+            params["sender"] = serde_json::to_value(&sender)?;
+        }
+        serde_json::from_value(params).with_context(|| format!("parsing JSON from {path:?}"))
+    }
+
+    /// Returns the selected profile's chain configuration, or that in the
+    /// escrow parameters file without one, with flags and `ZESCROW_*`
+    /// variables overriding either.
+    fn chain_config(&self) -> anyhow::Result<ChainConfig> {
+        let mut config = match &self.profile {
+            Some(profile) => profile.chain_config.clone(),
+            None => {
+                let path = self.params_path();
+                info!("Loading chain configuration from {}", path.display());
+                let mut params: serde_json::Value = load_escrow_data(path)?;
+                serde_json::from_value(params["chain_config"].take())
+                    .with_context(|| format!("parsing chain configuration from {path:?}"))?
+            }
+        };
+        self.chain.apply(&mut config);
+        Ok(config)
+    }
+
+    /// Returns `recipient`, or the key in [`RECIPIENT_VAR`], or the selected
+    /// profile's recipient key.
+    fn recipient(&self, recipient: Option<Recipient>) -> anyhow::Result<Option<Recipient>> {
+        if let Some(recipient) = recipient {
+            return Ok(Some(recipient));
+        }
+        if let Ok(key) = std::env::var(RECIPIENT_VAR) {
+            return Ok(Some(key.parse()?));
+        }
+        match &self.profile {
+            Some(profile) => profile.recipient(),
+            None => Ok(None),
+        }
+    }
+}
+
+/// Prints the outcome of a dry run, failing if the transaction would fail.
+fn report_simulation(simulation: Simulation, output: OutputFormat) -> anyhow::Result<()> {
+    match output {
+        OutputFormat::Text => println!("{simulation}"),
+        OutputFormat::Json => print_json(&simulation)?,
+    }
+    if !simulation.succeeded() {
+        bail!("dry run: {} would fail", simulation.operation);
+    }
+    Ok(())
+}
+
+/// Parses a decimal gwei amount (e.g. `1.5`) into wei.
+fn parse_gwei(s: &str) -> Result<u128, String> {
+    parse_units(s, "gwei")
+        .map_err(|e| e.to_string())?
+        .get_absolute()
+        .try_into()
+        .map_err(|_| format!("{s} gwei is out of range"))
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
+    /// Interactively write `templates/escrow_params.json`, validating each
+    /// answer.
+    Init {
+        /// Output path for the escrow parameters
+        #[arg(
+            long,
+            default_value = ESCROW_PARAMS_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        outfile: PathBuf,
+    },
+
     /// Create an escrow using the specified parameters in
     /// `templates/escrow_params.json`.
     /// Generates `templates/escrow_metadata.json` on success.
-    Create,
+    Create {
+        /// Create one escrow per row of this CSV or JSON file (columns
+        /// `recipient`, `amount`, and optionally `finish_after`,
+        /// `cancel_after`, `memo`), using `escrow_params.json` for the rest
+        #[arg(long, value_name = "FILE", value_parser = value_parser!(PathBuf))]
+        batch: Option<PathBuf>,
+
+        /// Combined report of a batch; rerunning with the same report skips
+        /// rows already created and retries failed ones
+        #[arg(
+            long,
+            value_name = "FILE",
+            requires = "batch",
+            default_value = batch::BATCH_METADATA_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        batch_output: PathBuf,
+    },
 
     /// Complete/release an existing escrow to the beneficiary.
-    /// Reads `templates/escrow_metadata.json`.
+    /// Reads `templates/escrow_metadata.json`, or the store with `--store`.
     Finish {
         /// `RECIPIENT` is either:
         /// - a path to a keypair file (for Solana), or
         /// - a hex private key with 0x prefix (for Ethereum)
+        ///
+        /// Defaults to the recipient key of the selected profile.
         #[arg(long, value_name = "RECIPIENT")]
-        recipient: Recipient,
+        recipient: Option<Recipient>,
+
+        /// Local ID of the stored escrow (with `--store`; defaults to the
+        /// most recently created)
+        #[arg(long, value_name = "ID")]
+        escrow: Option<i64>,
+
+        /// Finish every funded escrow in the store (with `--store`) whose
+        /// `finish_after` has passed, printing a summary report. Escrows
+        /// with conditions are skipped
+        #[arg(long, conflicts_with = "escrow")]
+        all_ready: bool,
+
+        /// Escrows settled per transaction with `--all-ready` (batched on
+        /// Ethereum only)
+        #[arg(long, value_name = "N", default_value_t = 20, requires = "all_ready")]
+        batch_size: usize,
+
+        /// Proof data from `prove`, used instead of proving the escrow's
+        /// conditions here
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with = "all_ready",
+            value_parser = value_parser!(PathBuf)
+        )]
+        proof: Option<PathBuf>,
+    },
+
+    /// Prove that an escrow's conditions are fulfilled without finishing
+    /// it, writing the proof data for `finish --proof`. Requires the
+    /// `prover` feature.
+    Prove {
+        /// Metadata of the escrow to prove
+        #[arg(
+            long,
+            value_name = "FILE",
+            default_value = ESCROW_METADATA_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        metadata: PathBuf,
+
+        /// Fulfillment of the escrow's conditions
+        #[arg(
+            long,
+            value_name = "FILE",
+            default_value = ESCROW_CONDITIONS_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        conditions: PathBuf,
+
+        /// Output path for the proof data
+        #[arg(
+            long,
+            value_name = "FILE",
+            default_value = PROOF_DATA_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        out: PathBuf,
+
+        /// Also write the full receipt, e.g. to archive or `proof inspect`
+        #[arg(long, value_name = "FILE", value_parser = value_parser!(PathBuf))]
+        receipt: Option<PathBuf>,
+
+        /// Compress the proof to Groth16 and record its proof points even
+        /// for Solana escrows, e.g. for a Groth16 verifier router (always
+        /// done on Ethereum; needs Docker unless `--remote-prover`)
+        #[arg(long)]
+        groth16: bool,
+    },
+
+    /// Inspect proofs written by `prove`.
+    Proof {
+        #[command(subcommand)]
+        command: proof::ProofCmd,
+    },
+
+    /// Verify a proof data file or receipt handed over by someone else
+    /// against a pinned guest image ID, and check that it proves fulfilled
+    /// conditions (requires the `prover` feature)
+    VerifyProof {
+        /// Proof data or receipt file
+        #[arg(value_parser = value_parser!(PathBuf))]
+        path: PathBuf,
+
+        /// Guest image ID to verify against, as hex (e.g. the one the
+        /// escrow contract was deployed with) [default: this build's]
+        #[arg(long, value_name = "HEX")]
+        image_id: Option<String>,
+
+        /// Also check that the proof is bound to the escrow in this
+        /// metadata file
+        #[arg(long, value_name = "FILE", value_parser = value_parser!(PathBuf))]
+        metadata: Option<PathBuf>,
     },
 
     /// Cancel/refund an existing escrow to the creator.
-    /// Reads `templates/escrow_metadata.json`.
-    Cancel,
+    /// Reads `templates/escrow_metadata.json`, or the store with `--store`.
+    Cancel {
+        /// Local ID of the stored escrow (with `--store`; defaults to the
+        /// most recently created)
+        #[arg(long, value_name = "ID")]
+        escrow: Option<i64>,
+
+        /// Cancel every funded escrow in the store (with `--store`) whose
+        /// `cancel_after` has passed, printing a summary report
+        #[arg(long, conflicts_with = "escrow")]
+        all_expired: bool,
+
+        /// Escrows settled per transaction with `--all-expired` (batched on
+        /// Ethereum only)
+        #[arg(long, value_name = "N", default_value_t = 20, requires = "all_expired")]
+        batch_size: usize,
+    },
+
+    /// Estimate the costs of the escrow lifecycle for the parameters in
+    /// `templates/escrow_params.json`: creating the escrow, proving its
+    /// conditions, and finishing or cancelling it. Nothing is broadcast.
+    Estimate {
+        /// Conditions to execute for the proving estimate, for escrows with
+        /// conditions (requires the `prover` feature) [default:
+        /// templates/escrow_conditions.json]
+        #[arg(long, value_name = "FILE", value_parser = value_parser!(PathBuf))]
+        conditions: Option<PathBuf>,
+    },
+
+    /// Quote the escrow lifecycle on the chain of each candidate profile,
+    /// with its escrow parameters, in the currency of the price feeds, and
+    /// recommend the cheapest viable chain. Nothing is broadcast.
+    Quote {
+        /// Profiles of the candidate chains, comma-separated
+        #[arg(long, value_name = "NAMES", value_delimiter = ',', required = true)]
+        candidates: Vec<String>,
+
+        /// Price feeds file, pricing each candidate's fee token
+        #[arg(
+            long,
+            value_name = "FILE",
+            default_value = PRICE_FEEDS_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        prices: PathBuf,
+    },
+
+    /// Fetch a Solana transaction and print the escrow program events it
+    /// emitted, decoded. Reads the RPC endpoint and program ID from the
+    /// chain configuration of `templates/escrow_params.json`.
+    DecodeLogs {
+        /// Transaction signature
+        #[arg(long, value_name = "SIG")]
+        signature: Signature,
+    },
+
+    /// Check the configuration: template files, RPC endpoints, the escrow
+    /// program or contract at `agent_id`, the sender key and its balance,
+    /// and prover availability. Prints a fix for each problem found.
+    Doctor,
+
+    /// Show the live on-chain state of an existing escrow.
+    /// Reads `templates/escrow_metadata.json`, or the store with `--store`,
+    /// which also shows the escrow's recorded history.
+    Status {
+        /// Local ID of the stored escrow (with `--store`; defaults to the
+        /// most recently created)
+        #[arg(long, value_name = "ID")]
+        escrow: Option<i64>,
+    },
+
+    /// List escrows involving the configured wallet, with their live state.
+    /// Reads the chain configuration from `templates/escrow_params.json`.
+    List {
+        /// Also list escrows paying this recipient (keypair file for
+        /// Solana, 0x-prefixed private key for Ethereum)
+        #[arg(long, value_name = "RECIPIENT")]
+        recipient: Option<Recipient>,
+
+        /// Block to start scanning escrow events from, e.g. the contract's
+        /// deployment block (Ethereum only)
+        #[arg(long, value_name = "BLOCK", default_value_t = 0)]
+        from_block: u64,
+
+        /// List the escrows recorded in the store (with `--store`) instead
+        /// of scanning the chain
+        #[arg(long, conflicts_with_all = ["recipient", "from_block"])]
+        local: bool,
+    },
+
+    /// Stream created/finished/cancelled updates until interrupted.
+    /// Watches the escrow in `templates/escrow_metadata.json` unless `--all`
+    /// is given.
+    Watch {
+        /// Watch every escrow involving the configured wallet, reading the
+        /// chain configuration from `templates/escrow_params.json`
+        #[arg(long)]
+        all: bool,
+
+        /// Also watch escrows paying this recipient (with `--all`)
+        #[arg(long, value_name = "RECIPIENT", requires = "all")]
+        recipient: Option<Recipient>,
+
+        /// Block to start scanning for the wallet's escrows from (Ethereum
+        /// only, with `--all`)
+        #[arg(long, value_name = "BLOCK", default_value_t = 0, requires = "all")]
+        from_block: u64,
+    },
+
+    /// Open a terminal dashboard of the active escrows in the store (or
+    /// `templates/escrow_metadata.json` without `--store`), with live
+    /// state, countdowns, and keys to finish and cancel them.
+    Ui {
+        /// Recipient key used to finish escrows (keypair file for Solana,
+        /// 0x-prefixed private key for Ethereum)
+        #[arg(long, value_name = "RECIPIENT")]
+        recipient: Option<Recipient>,
+
+        /// Seconds between refreshes of the escrows' on-chain state
+        #[arg(long, value_name = "SECS", default_value_t = 10)]
+        interval: u64,
+    },
+
+    /// Settle escrows automatically until interrupted: finish each escrow
+    /// as soon as its timelock and conditions allow, and cancel expired
+    /// ones, as the escrow's `policy.json` permits.
+    Daemon {
+        /// Directory with one subdirectory per escrow, holding its
+        /// `escrow_metadata.json` and optionally `escrow_conditions.json`
+        /// and `policy.json`
+        #[arg(
+            long,
+            default_value = daemon::ESCROWS_DIR,
+            value_parser = value_parser!(PathBuf)
+        )]
+        dir: PathBuf,
+
+        /// Recipient key used to finish escrows whose policy names none
+        /// (keypair file for Solana, 0x-prefixed private key for Ethereum)
+        #[arg(long, value_name = "RECIPIENT")]
+        recipient: Option<Recipient>,
+
+        /// Seconds between passes over the directory
+        #[arg(long, value_name = "SECS", default_value_t = 15)]
+        interval: u64,
+
+        /// Run a single pass and exit (implied by `--dry-run`)
+        #[arg(long)]
+        once: bool,
+
+        /// Notify webhooks once an escrow is this many blocks/slots from
+        /// its cancellation height
+        #[arg(long, value_name = "BLOCKS")]
+        expiry_warning: Option<u64>,
+
+        #[command(flatten)]
+        webhooks: WebhookArgs,
+    },
+
+    /// Serve a REST API for creating, finishing, cancelling, inspecting,
+    /// and listing escrows and generating proofs, until interrupted.
+    Serve {
+        /// Address to listen on
+        #[arg(long, value_name = "ADDR", default_value = "127.0.0.1:8080")]
+        listen: std::net::SocketAddr,
+
+        /// Accepted API key; repeat for several. Defaults to the
+        /// comma-separated keys in `ZESCROW_API_KEYS`
+        #[arg(long = "api-key", value_name = "KEY")]
+        api_keys: Vec<String>,
+
+        /// Recipient key used to finish escrows (keypair file for Solana,
+        /// 0x-prefixed private key for Ethereum)
+        #[arg(long, value_name = "RECIPIENT")]
+        recipient: Option<Recipient>,
+
+        /// Profile whose chain configuration the API serves; repeat for
+        /// several chains. Defaults to the selected profile's, or that in
+        /// the escrow parameters file
+        #[arg(long = "chain-profile", value_name = "NAME")]
+        chain_profiles: Vec<String>,
+
+        #[command(flatten)]
+        webhooks: WebhookArgs,
+    },
 
     /// Generate a cryptographic condition JSON file.
     Generate(GenerateOpts),
+
+    /// Check a condition's fulfillment natively, without the zkVM, and
+    /// print the outcome of each (sub)condition, to catch a bad witness
+    /// before proving.
+    VerifyCondition {
+        /// Condition file with its fulfillment
+        #[arg(
+            default_value = ESCROW_CONDITIONS_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        path: PathBuf,
+    },
+
+    /// Observe the claim of a cross-chain condition on its chain and sign
+    /// the observation as one of the condition's attestors, adding the
+    /// signature to the condition file. Escrow claims connect with the
+    /// chain configuration of the remote escrow's metadata, and state
+    /// claims with that of the selected profile or
+    /// `templates/escrow_params.json`.
+    Attest {
+        /// Condition file holding the cross-chain condition
+        #[arg(
+            default_value = ESCROW_CONDITIONS_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        path: PathBuf,
+
+        /// Metadata of the remote escrow an escrow claim is about
+        #[arg(
+            long,
+            default_value = ESCROW_METADATA_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        escrow: PathBuf,
+
+        /// Hex-encoded Ed25519 secret key of the attestor
+        #[arg(long, env = "ZESCROW_ATTESTOR_KEY", hide_env_values = true)]
+        key: String,
+    },
+
+    /// Generate a new Solana keypair or Ethereum private key, and optionally
+    /// fund it from the Solana devnet faucet.
+    Keygen(key::KeygenOpts),
+
+    /// Manage the encrypted keystore of signing keys.
+    Key {
+        #[command(subcommand)]
+        command: key::KeyCmd,
+    },
+
+    /// Manage the address book of named parties, usable as
+    /// `"recipient": "@<name>"` in `templates/escrow_params.json`.
+    Contacts {
+        #[command(subcommand)]
+        command: contacts::ContactsCmd,
+    },
+
+    /// Share an escrow with another party, e.g. a recipient on mobile, as a
+    /// `zescrow:` payload or a QR code. Keys and RPC endpoints are left out.
+    /// Reads `templates/escrow_metadata.json`, or the store with `--store`.
+    Export {
+        /// Local ID of the stored escrow (with `--store`; defaults to the
+        /// most recently created)
+        #[arg(long, value_name = "ID")]
+        escrow: Option<i64>,
+
+        /// Render the payload as a QR code in the terminal
+        #[arg(long)]
+        qr: bool,
+
+        /// Also write the QR code to this PNG image
+        #[arg(long, value_name = "FILE", value_parser = value_parser!(PathBuf))]
+        png: Option<PathBuf>,
+    },
+
+    /// Import an escrow shared with `export`, connecting it with the chain
+    /// configuration of the selected profile or
+    /// `templates/escrow_params.json`.
+    Import {
+        /// `zescrow:` payload, or `-` to read it from stdin
+        #[arg(required_unless_present = "png")]
+        payload: Option<String>,
+
+        /// Scan the QR code in this image instead
+        #[arg(
+            long,
+            value_name = "FILE",
+            conflicts_with = "payload",
+            value_parser = value_parser!(PathBuf)
+        )]
+        png: Option<PathBuf>,
+
+        /// Output path for the escrow metadata
+        #[arg(
+            long,
+            value_name = "FILE",
+            default_value = ESCROW_METADATA_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        out: PathBuf,
+    },
+
+    /// Bundle an escrow's metadata, conditions (optionally encrypted), proof
+    /// artifacts, and store history into one archive, to hand the escrow
+    /// off to another machine or party. Keys and RPC endpoints are left
+    /// out.
+    ExportArchive(archive::ExportArgs),
+
+    /// Extract an archive from `export-archive`, connecting the escrow with
+    /// the chain configuration of the selected profile or
+    /// `templates/escrow_params.json`.
+    ImportArchive(archive::ImportArgs),
+
+    /// Build the unsigned transaction of an escrow operation, for signing
+    /// offline with `sign-tx`: a base64 Solana transaction or an EIP-2718
+    /// Ethereum transaction. Needs no key.
+    BuildTx {
+        #[command(subcommand)]
+        op: tx::BuildOp,
+
+        /// Output path for the transaction
+        #[arg(
+            long,
+            global = true,
+            value_name = "FILE",
+            default_value = tx::TX_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        out: PathBuf,
+
+        /// Use this durable nonce account, whose authority is the fee payer,
+        /// instead of a recent blockhash (Solana), so the transaction does
+        /// not expire while awaiting signatures
+        #[arg(long, global = true, value_name = "PUBKEY")]
+        nonce_account: Option<Pubkey>,
+    },
+
+    /// Sign a transaction from `build-tx`. Needs no network access.
+    SignTx {
+        /// Transaction file from `build-tx`
+        #[arg(value_parser = value_parser!(PathBuf))]
+        file: PathBuf,
+
+        /// Signing key: a keypair file (Solana), a hex private key
+        /// (Ethereum), or a secret reference
+        #[arg(long)]
+        key: String,
+
+        /// Output path for the signed transaction [default: FILE]
+        #[arg(long, value_name = "FILE", value_parser = value_parser!(PathBuf))]
+        out: Option<PathBuf>,
+    },
+
+    /// Broadcast a transaction signed with `sign-tx`, recording the escrow
+    /// it creates, finishes, or cancels.
+    BroadcastTx {
+        /// Signed transaction file
+        #[arg(
+            default_value = tx::TX_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        file: PathBuf,
+    },
+
+    /// Wait for a transaction that an earlier command stopped waiting for
+    /// after --confirm-timeout. Reads the chain configuration from
+    /// `templates/escrow_params.json`.
+    Wait {
+        /// Transaction signature (Solana) or hash (Ethereum)
+        id: String,
+
+        /// Operation the transaction performs, as reported when the wait
+        /// stopped
+        #[arg(long, default_value = "wait")]
+        operation: String,
+
+        /// Blockhash the transaction expires with (Solana), as reported when
+        /// the wait stopped
+        #[arg(long, value_name = "HASH")]
+        blockhash: Option<String>,
+    },
+
+    /// Create a Solana address lookup table owned by the sender, for use
+    /// with `--lookup-table`. Holds the given addresses, or the accounts of
+    /// the escrow's finish and cancel transactions if none are given.
+    LookupTable {
+        /// Addresses to hold in the table
+        addresses: Vec<Pubkey>,
+
+        /// Local ID of the stored escrow (with `--store`; defaults to the
+        /// most recently created)
+        #[arg(long, value_name = "ID", conflicts_with = "addresses")]
+        escrow: Option<i64>,
+    },
+
+    /// Finish a Cosmos escrow on another IBC chain with a settlement packet
+    /// sent by this chain's escrow contract, signed by the settler the
+    /// escrow names. Reads this chain's configuration from
+    /// `templates/escrow_params.json`.
+    IbcSettle {
+        /// ID of the escrow on the other chain
+        id: u64,
+
+        /// Channel of this chain's end of the settlement channel
+        #[arg(long)]
+        channel: String,
+
+        /// Reference to the fulfillment recorded with the settlement, e.g.
+        /// the digest of the proof
+        #[arg(long)]
+        proof: Option<String>,
+
+        /// Seconds the packet may wait to be relayed [default: 600]
+        #[arg(long, value_name = "SECS")]
+        packet_timeout: Option<u64>,
+
+        /// Relay the packet with this Hermes binary (not needed if a
+        /// relayer serves the channel)
+        #[arg(long, value_name = "PATH")]
+        hermes: Option<PathBuf>,
+
+        /// Hermes configuration file
+        #[arg(long, value_name = "PATH", requires = "hermes")]
+        hermes_config: Option<PathBuf>,
+    },
 }
 
 /// Options for `generate` command
 #[derive(Parser, Debug)]
 struct GenerateOpts {
+    /// Write the condition even if its fulfillment does not verify, e.g. a
+    /// subcondition of a threshold that is not signed yet
+    #[arg(long)]
+    allow_unfulfilled: bool,
+
     #[command(subcommand)]
     condition: GenerateCmd,
 }
@@ -70,7 +1425,7 @@ enum GenerateCmd {
             default_value = ESCROW_CONDITIONS_PATH,
             value_parser = value_parser!(PathBuf)
         )]
-        output: PathBuf,
+        outfile: PathBuf,
     },
 
     /// Ed25519 signature over a message
@@ -93,7 +1448,7 @@ enum GenerateCmd {
             default_value = ESCROW_CONDITIONS_PATH,
             value_parser = value_parser!(PathBuf)
         )]
-        output: PathBuf,
+        outfile: PathBuf,
     },
 
     /// Secp256k1 signature over a message
@@ -110,31 +1465,208 @@ enum GenerateCmd {
         #[arg(long, value_name = "SIG", help = "Hex-encoded signature")]
         sig: String,
 
-        /// Output path for condition JSON
+        /// Output path for condition JSON
+        #[arg(
+            long,
+            default_value = ESCROW_CONDITIONS_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        outfile: PathBuf,
+    },
+
+    /// Threshold condition: at least `threshold` of the given
+    /// subconditions must hold
+    Threshold {
+        /// One or more JSON files containing child conditions
+        #[arg(
+            long,
+            value_name = "FILES...",
+            value_parser = value_parser!(PathBuf),
+            num_args = 1..,
+            help = "Comma- or space-separated list of condition JSON files"
+        )]
+        subconditions: Vec<PathBuf>,
+
+        /// Minimum number of child conditions required
+        #[arg(long, help = "Number of conditions to satisfy")]
+        threshold: usize,
+
+        /// Output path for condition JSON
+        #[arg(
+            long,
+            default_value = ESCROW_CONDITIONS_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        outfile: PathBuf,
+    },
+
+    /// Boolean AND: all of the given subconditions must hold (a threshold
+    /// of all of them)
+    All {
+        /// One or more JSON files containing child conditions
+        #[arg(
+            long,
+            value_name = "FILES...",
+            value_parser = value_parser!(PathBuf),
+            num_args = 1..
+        )]
+        subconditions: Vec<PathBuf>,
+
+        /// Output path for condition JSON
+        #[arg(
+            long,
+            default_value = ESCROW_CONDITIONS_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        outfile: PathBuf,
+    },
+
+    /// Boolean OR: at least one of the given subconditions must hold (a
+    /// threshold of one)
+    Any {
+        /// One or more JSON files containing child conditions
+        #[arg(
+            long,
+            value_name = "FILES...",
+            value_parser = value_parser!(PathBuf),
+            num_args = 1..
+        )]
+        subconditions: Vec<PathBuf>,
+
+        /// Output path for condition JSON
+        #[arg(
+            long,
+            default_value = ESCROW_CONDITIONS_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        outfile: PathBuf,
+    },
+
+    /// Cross-chain: a claim about another chain, e.g. the release of an
+    /// escrow there, must be attested by a quorum of attestors (signed
+    /// later with `attest`)
+    CrossChain {
+        /// CAIP-2 ID of the chain, e.g. `eip155:1`, or `solana:` and the
+        /// first 32 characters of the cluster's genesis hash
+        #[arg(long)]
+        chain: String,
+
+        /// Metadata of the remote escrow that must be released
+        #[arg(
+            long,
+            value_parser = value_parser!(PathBuf),
+            conflicts_with_all = ["state_key", "value_hash"]
+        )]
+        escrow: Option<PathBuf>,
+
+        /// Require the remote escrow to be refunded instead
+        #[arg(long, requires = "escrow")]
+        refunded: bool,
+
+        /// Hex-encoded account address (Solana), or contract address and
+        /// storage slot (EVM), whose state must match `--value-hash`
+        #[arg(long, requires = "value_hash")]
+        state_key: Option<String>,
+
+        /// Hex-encoded SHA-256 digest of the account data or slot value
+        #[arg(long, requires = "state_key")]
+        value_hash: Option<String>,
+
+        /// Hex-encoded Ed25519 public keys of the attestors
+        #[arg(long, value_name = "PUBKEYS...", value_delimiter = ',', num_args = 1..)]
+        attestors: Vec<String>,
+
+        /// Minimum number of attestors that must sign
+        #[arg(long)]
+        quorum: usize,
+
+        /// Output path for condition JSON
+        #[arg(
+            long,
+            default_value = ESCROW_CONDITIONS_PATH,
+            value_parser = value_parser!(PathBuf)
+        )]
+        outfile: PathBuf,
+    },
+
+    /// Bridge: a message from a contract on another chain must be delivered
+    /// by Wormhole (a VAA signed by a guardian quorum) or LayerZero (a packet
+    /// signed by DVNs)
+    Bridge {
+        /// Wormhole chain ID (e.g. 2 for Ethereum) or LayerZero endpoint ID
+        /// (e.g. 30101) of the chain the message is sent from
+        #[arg(long)]
+        source_chain: u32,
+
+        /// Hex-encoded address of the contract sending the message
+        #[arg(long)]
+        emitter: String,
+
+        /// Hex-encoded message payload the escrow waits for (LayerZero: the
+        /// application message)
+        #[arg(
+            long,
+            conflicts_with = "payload_hash",
+            required_unless_present = "payload_hash"
+        )]
+        payload: Option<String>,
+
+        /// Hex-encoded SHA-256 digest of the payload, instead of the payload
+        #[arg(long)]
+        payload_hash: Option<String>,
+
+        /// Hex-encoded Ethereum addresses of the Wormhole guardians, in
+        /// guardian set order
+        #[arg(
+            long,
+            value_name = "ADDRESSES...",
+            value_delimiter = ',',
+            num_args = 1..,
+            requires = "guardian_set_index",
+            conflicts_with = "dvns"
+        )]
+        guardians: Vec<String>,
+
+        /// Index of the Wormhole guardian set
+        #[arg(long, requires = "guardians")]
+        guardian_set_index: Option<u32>,
+
+        /// Hex-encoded Ethereum addresses of the LayerZero DVN signers
         #[arg(
             long,
-            default_value = ESCROW_CONDITIONS_PATH,
-            value_parser = value_parser!(PathBuf)
+            value_name = "ADDRESSES...",
+            value_delimiter = ',',
+            num_args = 1..,
+            requires_all = ["destination", "receiver", "dvn_threshold"]
         )]
-        output: PathBuf,
-    },
+        dvns: Vec<String>,
 
-    /// Threshold condition: at least `threshold` of the given
-    /// subconditions must hold
-    Threshold {
-        /// One or more JSON files containing child conditions
+        /// LayerZero endpoint ID of the chain the packet is delivered to
+        #[arg(long, requires = "dvns")]
+        destination: Option<u32>,
+
+        /// Hex-encoded address of the application receiving the packet
+        #[arg(long, requires = "dvns")]
+        receiver: Option<String>,
+
+        /// Minimum number of DVNs that must sign the packet
+        #[arg(long, requires = "dvns")]
+        dvn_threshold: Option<usize>,
+
+        /// File holding the hex-encoded VAA or LayerZero packet, if it was
+        /// already sent
+        #[arg(long, value_parser = value_parser!(PathBuf))]
+        message: Option<PathBuf>,
+
+        /// Hex-encoded DVN signatures of the packet
         #[arg(
             long,
-            value_name = "FILES...",
-            value_parser = value_parser!(PathBuf),
+            value_name = "SIGNATURES...",
+            value_delimiter = ',',
             num_args = 1..,
-            help = "Comma- or space-separated list of condition JSON files"
+            requires_all = ["message", "dvns"]
         )]
-        subconditions: Vec<PathBuf>,
-
-        /// Minimum number of child conditions required
-        #[arg(long, help = "Number of conditions to satisfy")]
-        threshold: usize,
+        dvn_signatures: Vec<String>,
 
         /// Output path for condition JSON
         #[arg(
@@ -142,7 +1674,7 @@ enum GenerateCmd {
             default_value = ESCROW_CONDITIONS_PATH,
             value_parser = value_parser!(PathBuf)
         )]
-        output: PathBuf,
+        outfile: PathBuf,
     },
 }
 
@@ -155,137 +1687,1465 @@ async fn main() -> anyhow::Result<()> {
     }
 
     // Initialize tracing. In order to view logs, run `RUST_LOG=info cargo run`
+    // Logs go to stderr so stdout carries only command results.
     tracing_subscriber::fmt()
         .with_env_filter(tracing_subscriber::filter::EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
         .init();
 
     let cli = Cli::parse();
     info!("Starting command handling");
 
-    execute(cli.command).await
+    let opts = ClientOpts {
+        fees: cli.fees.into(),
+        priority_fees: cli.compute_budget.into(),
+        lookup_tables: cli.lookup_tables,
+        // Spinners would garble JSON output and long-running service logs.
+        confirmation: cli.confirmation.config(
+            cli.output == OutputFormat::Text
+                && !matches!(
+                    cli.command,
+                    Commands::Daemon { .. } | Commands::Serve { .. }
+                ),
+        ),
+        wait: cli.wait.into(),
+        safe: cli.safe.into(),
+        private_rpc: cli.private_rpc,
+        confirmations: cli.confirmations,
+        dry_run: cli.dry_run,
+        output: cli.output,
+        store: cli.store.map(EscrowStore::open).transpose()?,
+        journal: Journal::open(&cli.journal)?,
+        rpc: cli.rpc.into(),
+        profile: cli
+            .profile
+            .map(|name| Profile::load(&cli.profiles, &name))
+            .transpose()?,
+        profiles: cli.profiles,
+        contacts: cli.contacts,
+        chain: cli.chain,
+        prover: cli.prover,
+    };
+    let metrics = opts.rpc.metrics.clone();
+    let result = execute(cli.command, opts).await;
+    if let Some(ClientError::Pending(pending)) =
+        result.as_ref().err().and_then(|e| e.downcast_ref())
+    {
+        let blockhash = pending
+            .blockhash
+            .as_ref()
+            .map(|hash| format!(" --blockhash {hash}"))
+            .unwrap_or_default();
+        eprintln!(
+            "Resume waiting with: zescrow-client wait {} --operation {}{blockhash}",
+            pending.id, pending.operation
+        );
+    }
+
+    let metrics = metrics.snapshot();
+    if metrics.errors > 0 {
+        warn!(%metrics, "RPC errors occurred");
+    } else {
+        debug!(%metrics, "RPC requests");
+    }
+    result
 }
 
-async fn execute(command: Commands) -> anyhow::Result<()> {
+async fn execute(command: Commands, opts: ClientOpts) -> anyhow::Result<()> {
+    let recovered = match command {
+        Commands::Create { .. }
+        | Commands::Finish { .. }
+        | Commands::Cancel { .. }
+        | Commands::Ui { .. }
+            if !opts.dry_run =>
+        {
+            reconcile_journal(&opts).await?
+        }
+        _ => Vec::new(),
+    };
+
     match command {
-        Commands::Create => {
-            info!("Loading escrow parameters from {}", ESCROW_PARAMS_PATH);
-            let params: EscrowParams = load_escrow_data(ESCROW_PARAMS_PATH)?;
+        Commands::Init { outfile } => {
+            if let Some(params) = init::run(&outfile)? {
+                if opts.output == OutputFormat::Json {
+                    print_json(&params)?;
+                }
+            }
+        }
+
+        Commands::Create {
+            batch,
+            batch_output,
+        } => {
+            let params = opts.load_params().await?;
 
             info!("Building ZescrowClient");
-            let client = ZescrowClient::builder(&params.chain_config).build().await?;
+            let builder = opts.builder(&params.chain_config);
+            let client = with_condition(builder, &params, Path::new(ESCROW_CONDITIONS_PATH))?
+                .build()
+                .await?;
+
+            if let Some(batch) = batch {
+                if opts.dry_run {
+                    info!("Simulating escrows from {batch:?}");
+                    return batch::simulate_escrows(&client, &params, &batch, opts.output).await;
+                }
+                info!("Creating escrows from {batch:?}");
+                return batch::create_escrows(
+                    &client,
+                    &params,
+                    &batch,
+                    &batch_output,
+                    opts.store.as_ref(),
+                    opts.output,
+                )
+                .await;
+            }
+            if opts.dry_run {
+                let simulation = client.simulate(Operation::Create(&params)).await?;
+                return report_simulation(simulation, opts.output);
+            }
+            // Rerunning a create that an interrupted run already landed
+            // must not create the escrow twice.
+            if let Some(metadata) = recovered.iter().find(|metadata| {
+                serde_json::to_value(&metadata.params).ok() == serde_json::to_value(&params).ok()
+            }) {
+                info!("Escrow was already created by an interrupted run");
+                if opts.output == OutputFormat::Json {
+                    print_json(metadata)?;
+                }
+                return Ok(());
+            }
+
+            let op = JournalOp::Create(params.clone());
+            let entry = opts.journal.begin(&op, None)?;
             info!("Creating escrow on-chain");
-            let metadata = client.create_escrow(&params).await?;
+            let metadata = match client.create_escrow(&params).await {
+                Ok(metadata) => metadata,
+                Err(e) => match check_failed(&opts, &client, entry, &op, e).await? {
+                    Reconciled::Created(metadata) => metadata,
+                    _ => bail!("create landed but its escrow was not found"),
+                },
+            };
             info!("Escrow created!");
+            let metadata = observe(&client, metadata, ExecutionState::Funded).await;
 
             info!("Saving metadata to {}", ESCROW_METADATA_PATH);
             save_escrow_data(ESCROW_METADATA_PATH, &metadata)?;
+            let id = opts
+                .store
+                .as_ref()
+                .map(|store| store.insert(&metadata, None))
+                .transpose()?;
+            opts.journal.complete(entry)?;
+            match opts.output {
+                OutputFormat::Text => {
+                    if let Some(uid) = metadata.uid {
+                        println!("Escrow ID: {uid}");
+                    }
+                    if let Some(id) = id {
+                        println!("Recorded as escrow {id} in the store");
+                    }
+                }
+                OutputFormat::Json => print_json(&metadata)?,
+            }
         }
 
-        Commands::Finish { recipient } => {
-            info!("Loading escrow metadata from {}", ESCROW_METADATA_PATH);
-            let metadata: EscrowMetadata = load_escrow_data(ESCROW_METADATA_PATH)?;
+        Commands::Finish {
+            recipient,
+            escrow,
+            all_ready,
+            batch_size,
+            proof,
+        } => {
+            let recipient = opts
+                .recipient(recipient)?
+                .ok_or_else(|| anyhow!("`finish` requires --recipient or a profile with one"))?;
+            if all_ready {
+                let store = opts
+                    .store
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("--all-ready requires --store"))?;
+                return bulk::settle_all(
+                    &opts,
+                    store,
+                    bulk::Action::Finish,
+                    Some(recipient),
+                    batch_size,
+                )
+                .await;
+            }
+            let (id, metadata) = load_escrow(&opts, escrow)?;
 
             info!("Building ZescrowClient for `finish`");
-            let client = ZescrowClient::builder(&metadata.params.chain_config)
-                .recipient(recipient)
-                .build()
-                .await?;
+            let mut builder = opts
+                .builder(&metadata.params.chain_config)
+                .recipient(recipient);
 
-            // Invoke the prover if escrow has cryptographic conditions
+            // Invoke the prover if escrow has cryptographic conditions,
+            // unless it was proven elsewhere
+            let mut freshness = None;
             if metadata.params.has_conditions {
-                #[cfg(feature = "prover")]
-                prover::run()?;
+                let seal = match proof {
+                    Some(path) => {
+                        info!("Loading proof data from {}", path.display());
+                        let proof = ProofArtifact::load(&path)?;
+                        opts.prover
+                            .check_guest(&metadata, proof.image_id.as_deref())?;
+                        opts.prover.check_provenance(&proof)?;
+                        freshness = proof.freshness()?;
+                        proof.seal_for(&metadata)?
+                    }
+                    None => {
+                        info!("Loading conditions from {}", ESCROW_CONDITIONS_PATH);
+                        let condition: Condition = load_escrow_data(ESCROW_CONDITIONS_PATH)?;
+                        prove_escrow(&opts, &metadata, condition).await?
+                    }
+                };
+                if let Some(seal) = seal {
+                    if let (Some(store), Some(id)) = (&opts.store, id) {
+                        store.record_proof(id, &seal)?;
+                    }
+                    builder = builder.proof_seal(seal);
+                }
+            }
 
-                #[cfg(not(feature = "prover"))]
-                return Err(anyhow!(
-                    "escrow has conditions but the 'prover' feature is disabled; \
-                     rebuild with `--features prover` to enable ZK proof generation"
-                ));
+            let client = builder.build().await?;
+            // Solana proofs are not verified on-chain, so stale proof data
+            // is rejected here for either chain.
+            if let Some(freshness) = freshness.filter(|f| *f != Freshness::NEVER) {
+                let height = client.get_escrow_state(&metadata).await?.current_height;
+                if freshness.is_expired(height) {
+                    return Err(ClientError::ProofExpired {
+                        expires_at: freshness.expires_at,
+                        height,
+                    }
+                    .into());
+                }
+            }
+            if opts.dry_run {
+                let simulation = client.simulate(Operation::Finish(&metadata)).await?;
+                return report_simulation(simulation, opts.output);
             }
 
+            let op = JournalOp::Finish(metadata.clone());
+            let entry = opts.journal.begin(&op, id)?;
             info!("Finishing escrow");
-            client.finish_escrow(&metadata).await?;
+            if let Err(e) = client.finish_escrow(&metadata).await {
+                check_failed(&opts, &client, entry, &op, e).await?;
+            }
             info!("Escrow completed and released successfully");
+            let metadata = observe(&client, metadata, ExecutionState::Released).await;
+            if let (Some(store), Some(id)) = (&opts.store, id) {
+                store.update(id, &metadata, None)?;
+            }
+            opts.journal.complete(entry)?;
+            if opts.output == OutputFormat::Json {
+                print_json(&metadata)?;
+            }
         }
 
-        Commands::Cancel => {
-            info!("Loading escrow metadata from {}", ESCROW_METADATA_PATH);
-            let metadata: EscrowMetadata = load_escrow_data(ESCROW_METADATA_PATH)?;
+        Commands::Prove {
+            metadata,
+            conditions,
+            out,
+            receipt,
+            groth16,
+        } => {
+            info!("Loading escrow metadata from {}", metadata.display());
+            let metadata: EscrowMetadata = load_escrow_data(&metadata)?;
+            if !metadata.params.has_conditions {
+                bail!("escrow has no conditions to prove");
+            }
+            info!("Loading conditions from {}", conditions.display());
+            let condition: Condition = load_escrow_data(&conditions)?;
+
+            let proof =
+                proof::prove(&opts, &metadata, condition, receipt.as_deref(), groth16).await?;
+            info!("Saving proof data to {}", out.display());
+            proof.save(&out)?;
+            match opts.output {
+                OutputFormat::Text => println!("Proof data written to {}", out.display()),
+                OutputFormat::Json => print_json(&proof)?,
+            }
+        }
+
+        Commands::Cancel {
+            escrow,
+            all_expired,
+            batch_size,
+        } => {
+            if all_expired {
+                let store = opts
+                    .store
+                    .as_ref()
+                    .ok_or_else(|| anyhow!("--all-expired requires --store"))?;
+                return bulk::settle_all(&opts, store, bulk::Action::Cancel, None, batch_size)
+                    .await;
+            }
+            let (id, metadata) = load_escrow(&opts, escrow)?;
 
             info!("Building ZescrowClient for `cancel`");
-            let client = ZescrowClient::builder(&metadata.params.chain_config)
-                .build()
-                .await?;
+            let client = opts.builder(&metadata.params.chain_config).build().await?;
+            if opts.dry_run {
+                let simulation = client.simulate(Operation::Cancel(&metadata)).await?;
+                return report_simulation(simulation, opts.output);
+            }
 
+            let op = JournalOp::Cancel(metadata.clone());
+            let entry = opts.journal.begin(&op, id)?;
             info!("Cancelling escrow");
-            client.cancel_escrow(&metadata).await?;
+            if let Err(e) = client.cancel_escrow(&metadata).await {
+                check_failed(&opts, &client, entry, &op, e).await?;
+            }
             info!("Escrow cancelled and refunded successfully");
+            let metadata = observe(&client, metadata, ExecutionState::Refunded).await;
+            if let (Some(store), Some(id)) = (&opts.store, id) {
+                store.update(id, &metadata, None)?;
+            }
+            opts.journal.complete(entry)?;
+            if opts.output == OutputFormat::Json {
+                print_json(&metadata)?;
+            }
+        }
+
+        Commands::Estimate { conditions } => {
+            let params = opts.load_params().await?;
+            let path = conditions.unwrap_or_else(|| ESCROW_CONDITIONS_PATH.into());
+            info!("Building ZescrowClient for `estimate`");
+            let builder = opts.builder(&params.chain_config);
+            let client = with_condition(builder, &params, &path)?.build().await?;
+            let costs = client.estimate(&params).await?;
+
+            let proving = if params.has_conditions {
+                info!("Loading conditions from {}", path.display());
+                match load_escrow_data::<Condition>(&path) {
+                    Ok(condition) => estimate_proving(&params, condition).await?,
+                    Err(e) => {
+                        warn!(error = %e, "Skipping the proving estimate");
+                        None
+                    }
+                }
+            } else {
+                None
+            };
+
+            let report = EstimateReport {
+                chain: params.chain_config.chain,
+                costs,
+                proving,
+            };
+            match opts.output {
+                OutputFormat::Text => println!("{report}"),
+                OutputFormat::Json => print_json(&report)?,
+            }
+        }
+
+        Commands::Quote { candidates, prices } => {
+            info!("Loading price feeds from {}", prices.display());
+            let feeds: PriceFeeds = load_escrow_data(&prices)?;
+            let mut quotes = Vec::new();
+            for name in &candidates {
+                let profile = Profile::load(&opts.profiles, name)?;
+                let params = opts.load_params_for(Some(&profile)).await?;
+                info!("Building ZescrowClient for `quote` on {name}");
+                let quoted = match opts.builder(&params.chain_config).build().await {
+                    Ok(client) => quote::quote(name, &client, &params, &feeds).await,
+                    Err(e) => Err(e),
+                };
+                match quoted {
+                    Ok(quote) => quotes.push(quote),
+                    Err(e) => warn!(candidate = %name, error = %e, "Skipping candidate"),
+                }
+            }
+
+            let report = QuoteReport {
+                currency: feeds.currency,
+                recommended: quote::cheapest(&quotes).map(|quote| quote.label.clone()),
+                quotes,
+            };
+            match opts.output {
+                OutputFormat::Text => println!("{report}"),
+                OutputFormat::Json => print_json(&report)?,
+            }
+        }
+
+        Commands::Status { escrow } => {
+            let (id, mut metadata) = load_escrow(&opts, escrow)?;
+
+            let client = opts.builder(&metadata.params.chain_config).build().await?;
+            if metadata.state.is_pending() {
+                let recorded = metadata.state;
+                let reconciliation = reorg::reconcile(&client, &mut metadata).await?;
+                info!(?recorded, state = ?metadata.state, ?reconciliation, "Reconciled state");
+                match (&opts.store, id) {
+                    (Some(store), Some(id)) => store.update(id, &metadata, None)?,
+                    _ => save_escrow_data(ESCROW_METADATA_PATH, &metadata)?,
+                }
+            }
+            let status = client.get_escrow_state(&metadata).await?;
+            let history = match (&opts.store, id) {
+                (Some(store), Some(id)) => Some(store.transitions(id)?),
+                _ => None,
+            };
+            match (opts.output, history) {
+                (OutputFormat::Text, history) => {
+                    if let Some(uid) = metadata.uid {
+                        println!("Escrow ID: {uid}");
+                    }
+                    println!("{status}");
+                    if let Some(history) = history {
+                        println!("History (Unix time):");
+                        for transition in history {
+                            let state = format!("{:?}", transition.state);
+                            match transition.tx {
+                                Some(tx) => println!("  {state:<14} {} (tx {tx})", transition.at),
+                                None => println!("  {state:<14} {}", transition.at),
+                            }
+                        }
+                    }
+                }
+                (OutputFormat::Json, None) => print_json(&status)?,
+                (OutputFormat::Json, Some(history)) => print_json(&serde_json::json!({
+                    "escrow": id,
+                    "status": status,
+                    "history": history,
+                }))?,
+            }
+        }
+
+        Commands::List { local: true, .. } => {
+            let store = opts
+                .store
+                .as_ref()
+                .ok_or_else(|| anyhow!("`list --local` requires --store"))?;
+            let escrows = store.list(None)?;
+            match opts.output {
+                OutputFormat::Text => print_stored_escrows(&escrows),
+                OutputFormat::Json => print_json(&escrows)?,
+            }
+        }
+
+        Commands::List {
+            recipient,
+            from_block,
+            local: false,
+        } => {
+            let config = opts.chain_config()?;
+            let mut builder = opts.builder(&config).from_block(from_block);
+            if let Some(recipient) = opts.recipient(recipient)? {
+                builder = builder.recipient(recipient);
+            }
+            let client = builder.build().await?;
+
+            let escrows = client.list_escrows().await?;
+            match opts.output {
+                OutputFormat::Text => print_escrow_table(&escrows),
+                OutputFormat::Json => print_json(&escrows)?,
+            }
+        }
+
+        Commands::Watch {
+            all,
+            recipient,
+            from_block,
+        } => {
+            let metadata: Option<EscrowMetadata> = if all {
+                None
+            } else {
+                info!("Loading escrow metadata from {}", ESCROW_METADATA_PATH);
+                Some(load_escrow_data(ESCROW_METADATA_PATH)?)
+            };
+            let config = match &metadata {
+                Some(metadata) => metadata.params.chain_config.clone(),
+                None => opts.chain_config()?,
+            };
+
+            let mut builder = opts.builder(&config).from_block(from_block);
+            if let Some(recipient) = opts.recipient(recipient)? {
+                builder = builder.recipient(recipient);
+            }
+            let client = builder.build().await?;
+
+            let mut updates = client.watch_escrows(metadata.as_ref()).await?;
+            loop {
+                let update = tokio::select! {
+                    update = updates.next() => update,
+                    _ = tokio::signal::ctrl_c() => break,
+                };
+                match update {
+                    Some(Ok(update)) if opts.output == OutputFormat::Json => {
+                        println!("{}", serde_json::to_string(&update)?)
+                    }
+                    Some(Ok(update)) => println!("{update}"),
+                    Some(Err(e)) => warn!(%e, "Escrow watch error"),
+                    None => break,
+                }
+            }
         }
 
-        Commands::Generate(opts) => {
+        Commands::Ui {
+            recipient,
+            interval,
+        } => {
+            let recipient = opts.recipient(recipient)?;
+            ui::run(&opts, recipient, Duration::from_secs(interval)).await?;
+        }
+
+        Commands::Daemon {
+            dir,
+            recipient,
+            interval,
+            once,
+            expiry_warning,
+            webhooks,
+        } => {
+            let recipient = opts.recipient(recipient)?;
+            let webhooks = webhooks.webhooks()?;
+            let notifier = daemon::Notifier::new(&webhooks, expiry_warning);
+            daemon::run(
+                &opts,
+                &dir,
+                recipient,
+                Duration::from_secs(interval),
+                once,
+                notifier,
+            )
+            .await?;
+        }
+
+        Commands::Serve {
+            listen,
+            api_keys,
+            recipient,
+            chain_profiles,
+            webhooks,
+        } => {
+            // Requests name a chain; its endpoints and keys come from here.
+            let chains = if chain_profiles.is_empty() {
+                vec![opts.chain_config()?]
+            } else {
+                chain_profiles
+                    .iter()
+                    .map(|name| Ok(Profile::load(&opts.profiles, name)?.chain_config))
+                    .collect::<anyhow::Result<_>>()?
+            };
+            let recipient = opts.recipient(recipient)?;
+            let webhooks = webhooks.webhooks()?;
+            serve::run(opts, listen, api_keys, chains, recipient, webhooks).await?;
+        }
+
+        Commands::Doctor => doctor::run(&opts).await?,
+
+        Commands::DecodeLogs { signature } => {
+            let config = opts.load_params().await?.chain_config;
+            if !matches!(config.chain, Chain::Solana) {
+                bail!("decode-logs requires a Solana chain configuration");
+            }
+            let program_id: Pubkey = config.agent_id.parse()?;
+            info!("Fetching transaction {signature}");
+            let events =
+                solana::transaction_events(&config.rpc_url, &program_id, &signature).await?;
+            match opts.output {
+                OutputFormat::Text if events.is_empty() => {
+                    println!("No escrow program events in {signature}")
+                }
+                OutputFormat::Text => {
+                    for event in &events {
+                        println!("{event}");
+                    }
+                }
+                OutputFormat::Json => print_json(&events)?,
+            }
+        }
+
+        Commands::VerifyCondition { path } => {
+            info!("Loading conditions from {}", path.display());
+            let condition: Condition = load_escrow_data(&path)?;
+            let verification = condition.verification();
+            match opts.output {
+                OutputFormat::Text => println!("{verification}"),
+                OutputFormat::Json => print_json(&verification)?,
+            }
+            if !verification.passed() {
+                bail!("condition is not fulfilled");
+            }
+        }
+
+        Commands::Attest { path, escrow, key } => {
+            info!("Loading conditions from {}", path.display());
+            let mut condition: Condition = load_escrow_data(&path)?;
+            let Condition::CrossChain(cross_chain) = &mut condition else {
+                bail!("{} is not a cross-chain condition", path.display());
+            };
+            let key: [u8; 32] = decode_hex("key", &key)?
+                .as_slice()
+                .try_into()
+                .map_err(|_| anyhow!("key must be 32 bytes"))?;
+            let key = ed25519_dalek::SigningKey::from_bytes(&key);
+
+            let observation = match &cross_chain.claim {
+                RemoteClaim::Escrow { .. } => {
+                    info!("Loading escrow metadata from {}", escrow.display());
+                    let metadata: EscrowMetadata = load_escrow_data(&escrow)?;
+                    let client = opts.builder(&metadata.params.chain_config).build().await?;
+                    client.observe_escrow(&metadata).await?
+                }
+                RemoteClaim::State { key, .. } => {
+                    let config = opts.load_params().await?.chain_config;
+                    let client = opts.builder(&config).build().await?;
+                    client.observe_state(key).await?
+                }
+            };
+            if observation.chain != cross_chain.chain || observation.claim != cross_chain.claim {
+                bail!(
+                    "observed {:?} on {}, not the condition's claim",
+                    observation.claim,
+                    observation.chain
+                );
+            }
+            cross_chain.attest(&observation, &key)?;
+            let attested = cross_chain.attestation.signatures.len();
+            let quorum = cross_chain.quorum;
+
+            save_escrow_data(&path, &condition)?;
+            info!(
+                height = observation.height,
+                "Attested; {attested} of {quorum} required signatures collected"
+            );
+            if opts.output == OutputFormat::Json {
+                print_json(&serde_json::json!({
+                    "observation": observation,
+                    "attested": attested,
+                    "quorum": quorum,
+                }))?;
+            }
+        }
+
+        Commands::Generate(generate) => {
             info!("Generating a new conditions JSON file");
-            handle_generate_cmd(opts)?;
+            handle_generate_cmd(generate, opts.output)?;
+        }
+
+        Commands::Proof { command } => proof::run(command, opts.output)?,
+
+        Commands::VerifyProof {
+            path,
+            image_id,
+            metadata,
+        } => proof::verify(&path, image_id.as_deref(), metadata.as_deref(), opts.output)?,
+
+        Commands::Keygen(keygen) => key::keygen(keygen, opts.output).await?,
+
+        Commands::Key { command } => key::run(command, opts.output)?,
+
+        Commands::Contacts { command } => contacts::run(command, &opts.contacts, opts.output)?,
+
+        Commands::Export { escrow, qr, png } => {
+            let (_, metadata) = load_escrow(&opts, escrow)?;
+            share::export(&metadata, qr, png.as_deref(), opts.output)?;
+        }
+
+        Commands::Import { payload, png, out } => {
+            let payload = share::read_payload(payload.as_deref(), png.as_deref())?;
+            share::import(&opts, &payload, &out)?;
+        }
+
+        Commands::ExportArchive(args) => archive::export(&opts, args)?,
+
+        Commands::ImportArchive(args) => archive::import(&opts, args)?,
+
+        Commands::BuildTx {
+            op,
+            out,
+            nonce_account,
+        } => tx::build(&opts, op, &out, nonce_account).await?,
+
+        Commands::SignTx { file, key, out } => {
+            tx::sign(&file, &key, out.as_deref(), opts.output).await?
+        }
+
+        Commands::BroadcastTx { file } => tx::broadcast(&opts, &file).await?,
+
+        Commands::Wait {
+            id,
+            operation,
+            blockhash,
+        } => {
+            let config = opts.chain_config()?;
+            let pending = PendingTransaction {
+                chain: config.chain,
+                operation,
+                id,
+                blockhash,
+            };
+            let client = opts.builder(&config).build().await?;
+            client.wait_for_transaction(&pending).await?;
+            match opts.output {
+                OutputFormat::Text => println!("Transaction {} confirmed", pending.id),
+                OutputFormat::Json => print_json(&pending)?,
+            }
+        }
+
+        Commands::LookupTable { addresses, escrow } => {
+            let metadata = addresses
+                .is_empty()
+                .then(|| load_escrow(&opts, escrow).map(|(_, metadata)| metadata))
+                .transpose()?;
+            let mut config = match &metadata {
+                Some(metadata) => metadata.params.chain_config.clone(),
+                None => opts.chain_config()?,
+            };
+            if !matches!(config.chain, Chain::Solana) {
+                bail!("address lookup tables are only supported on Solana");
+            }
+            if let Some(key) = secrets::resolve(&config.sender_private_id).await? {
+                config.sender_private_id = key;
+            }
+
+            let agent = SolanaAgent::new(&config, None, None, opts.rpc.clone(), opts.confirmation)
+                .await?
+                .with_wait(opts.wait);
+            let addresses = match &metadata {
+                Some(metadata) => agent.escrow_accounts(metadata)?,
+                None => addresses,
+            };
+            let table = agent.create_lookup_table(&addresses)?;
+            match opts.output {
+                OutputFormat::Text => {
+                    println!(
+                        "Created lookup table {table} ({} addresses)",
+                        addresses.len()
+                    )
+                }
+                OutputFormat::Json => print_json(&serde_json::json!({
+                    "address": table.to_string(),
+                    "addresses": addresses.iter().map(ToString::to_string).collect::<Vec<_>>(),
+                }))?,
+            }
+        }
+
+        Commands::IbcSettle {
+            id,
+            channel,
+            proof,
+            packet_timeout,
+            hermes,
+            hermes_config,
+        } => {
+            let packet_timeout = packet_timeout.map(Duration::from_secs);
+            ibc_settle(
+                &opts,
+                id,
+                &channel,
+                proof,
+                packet_timeout,
+                hermes,
+                hermes_config,
+            )
+            .await?;
         }
     }
     Ok(())
 }
 
-fn handle_generate_cmd(opts: GenerateOpts) -> anyhow::Result<()> {
-    match opts.condition {
-        GenerateCmd::Hashlock { preimage, output } => {
+/// Finishes escrow `id` on another IBC chain with a settlement packet sent
+/// over `channel`, relayed with the `hermes` binary if given.
+#[cfg(feature = "cosmos")]
+async fn ibc_settle(
+    opts: &ClientOpts,
+    id: u64,
+    channel: &str,
+    proof: Option<String>,
+    packet_timeout: Option<Duration>,
+    hermes: Option<PathBuf>,
+    hermes_config: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    use zescrow_client::cosmos::ibc::Hermes;
+    use zescrow_client::CosmosAgent;
+
+    let mut config = opts.chain_config()?;
+    if !matches!(config.chain, Chain::Cosmos) {
+        bail!("IBC settlement is only supported on Cosmos");
+    }
+    if let Some(key) = secrets::resolve(&config.sender_private_id).await? {
+        config.sender_private_id = key;
+    }
+
+    let agent = CosmosAgent::new(&config, None, None, opts.rpc.clone())?.with_wait(opts.wait);
+    let packet = agent
+        .send_settlement(channel, id, proof, packet_timeout)
+        .await?;
+    if let Some(binary) = hermes {
+        let hermes = Hermes {
+            binary,
+            config: hermes_config,
+        };
+        agent.relay_settlement(&packet, &hermes).await?;
+    }
+    agent.await_settlement(&packet).await?;
+    match opts.output {
+        OutputFormat::Text => println!(
+            "Escrow {id} settled over {} (packet {})",
+            packet.channel, packet.sequence
+        ),
+        OutputFormat::Json => print_json(&packet)?,
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "cosmos"))]
+async fn ibc_settle(
+    _opts: &ClientOpts,
+    _id: u64,
+    _channel: &str,
+    _proof: Option<String>,
+    _packet_timeout: Option<Duration>,
+    _hermes: Option<PathBuf>,
+    _hermes_config: Option<PathBuf>,
+) -> anyhow::Result<()> {
+    bail!("IBC settlement requires the `cosmos` feature; rebuild with `--features cosmos`")
+}
+
+/// Loads the escrow to operate on, with its local ID if from the store:
+/// escrow `id` (or the most recently created) with `--store`, otherwise
+/// [`ESCROW_METADATA_PATH`].
+fn load_escrow(
+    opts: &ClientOpts,
+    id: Option<i64>,
+) -> anyhow::Result<(Option<i64>, EscrowMetadata)> {
+    let stored = match (&opts.store, id) {
+        (Some(store), Some(id)) => store
+            .get(id)?
+            .ok_or_else(|| anyhow!("no escrow with ID {id} in the store"))?,
+        (Some(store), None) => store
+            .latest()?
+            .ok_or_else(|| anyhow!("the store has no escrows"))?,
+        (None, Some(_)) => bail!("--escrow requires --store"),
+        (None, None) => {
+            info!("Loading escrow metadata from {}", ESCROW_METADATA_PATH);
+            return Ok((None, load_escrow_data(ESCROW_METADATA_PATH)?));
+        }
+    };
+    info!(id = stored.id, "Loaded escrow from the store");
+    Ok((Some(stored.id), stored.metadata))
+}
+
+/// Reconciles the operations that interrupted runs left pending in the
+/// journal, recording the escrows they created or settled, and returns the
+/// metadata of the escrows recovered.
+///
+/// Operations whose outcome cannot be determined yet stay pending.
+async fn reconcile_journal(opts: &ClientOpts) -> anyhow::Result<Vec<EscrowMetadata>> {
+    let mut recovered = Vec::new();
+    for entry in opts.journal.pending()? {
+        let name = entry.op.name();
+        let client = opts.builder(entry.op.chain_config()).build().await?;
+        let reconciled = match journal::reconcile(&client, &entry.op).await {
+            Ok(reconciled) => reconciled,
+            Err(e) => {
+                warn!(entry = entry.id, error = %e, "Could not reconcile interrupted {name}");
+                continue;
+            }
+        };
+        match reconciled {
+            Reconciled::Created(metadata) => {
+                warn!(
+                    entry = entry.id,
+                    "Recovered escrow created by an interrupted run"
+                );
+                info!("Saving metadata to {}", ESCROW_METADATA_PATH);
+                save_escrow_data(ESCROW_METADATA_PATH, &metadata)?;
+                if let Some(store) = &opts.store {
+                    store.insert(&metadata, None)?;
+                }
+                recovered.push(metadata);
+            }
+            Reconciled::Settled => {
+                warn!(entry = entry.id, "Interrupted {name} landed");
+                let state = match entry.op {
+                    JournalOp::Cancel(_) => ExecutionState::Refunded,
+                    _ => ExecutionState::Released,
+                };
+                if let (Some(store), Some(id)) = (&opts.store, entry.escrow) {
+                    store.update_state(id, state, None)?;
+                }
+            }
+            Reconciled::NotLanded => {
+                info!(entry = entry.id, "Interrupted {name} did not land");
+                opts.journal.abandon(entry.id)?;
+                continue;
+            }
+        }
+        opts.journal.complete(entry.id)?;
+    }
+    Ok(recovered)
+}
+
+/// Handles `error` from journaled operation `op`: checks whether its
+/// transaction landed anyway, returning the outcome if it did, and `error`
+/// otherwise.
+///
+/// Transactions that timed out while pending, or whose outcome cannot be
+/// determined, stay pending in the journal until the next run.
+async fn check_failed(
+    opts: &ClientOpts,
+    client: &ZescrowClient,
+    entry: i64,
+    op: &JournalOp,
+    error: ClientError,
+) -> anyhow::Result<Reconciled> {
+    if matches!(error, ClientError::Pending(_)) {
+        return Err(error.into());
+    }
+    match journal::reconcile(client, op).await {
+        Ok(Reconciled::NotLanded) => {
+            opts.journal.abandon(entry)?;
+            Err(error.into())
+        }
+        Ok(reconciled) => {
+            warn!(%error, "{} landed despite the error", op.name());
+            Ok(reconciled)
+        }
+        Err(e) => {
+            warn!(error = %e, "Could not check whether the {} landed", op.name());
+            Err(error.into())
+        }
+    }
+}
+
+/// Records `metadata` as moved to `state` by a transaction just awaited,
+/// pending until the block including it is final (see [`reorg::observe`]).
+async fn observe(
+    client: &ZescrowClient,
+    mut metadata: EscrowMetadata,
+    state: ExecutionState,
+) -> EscrowMetadata {
+    if let Err(e) = reorg::observe(client, &mut metadata, state).await {
+        warn!(error = %e, "Could not read the including block; recording the state as pending");
+    }
+    metadata
+}
+
+/// Prints one row per stored escrow with its recorded state.
+fn print_stored_escrows(escrows: &[StoredEscrow]) {
+    if escrows.is_empty() {
+        println!("No escrows recorded");
+        return;
+    }
+    println!(
+        "{:>6}  {:<8}  {:>10}  {:<13}  {:<44}  {:>20}",
+        "ID", "CHAIN", "ESCROW ID", "STATE", "RECIPIENT", "AMOUNT"
+    );
+    for escrow in escrows {
+        let metadata = &escrow.metadata;
+        println!(
+            "{:>6}  {:<8}  {:>10}  {:<13}  {:<44}  {:>20}",
+            escrow.id,
+            metadata.params.chain_config.chain.as_ref(),
+            metadata
+                .escrow_id
+                .map_or_else(|| "-".to_string(), |id| id.to_string()),
+            format!("{:?}", metadata.state),
+            metadata.params.recipient.to_string(),
+            metadata.params.asset.amount.to_string(),
+        );
+    }
+}
+
+/// Prints one row per escrow with its state, amount, and deadlines.
+fn print_escrow_table(escrows: &[EscrowSummary]) {
+    if escrows.is_empty() {
+        println!("No escrows found");
+        return;
+    }
+    let height = |h: Option<u64>| h.map_or_else(|| "-".to_string(), |h| h.to_string());
+    let id_width = escrows.iter().map(|e| e.id.len()).max().unwrap_or(0).max(2);
+
+    println!(
+        "{:<id_width$}  {:<44}  {:<44}  {:<8}  {:>20}  {:>12}  {:>12}",
+        "ID", "SENDER", "RECIPIENT", "STATE", "AMOUNT", "FINISH AFTER", "CANCEL AFTER"
+    );
+    for escrow in escrows {
+        let status = &escrow.status;
+        println!(
+            "{:<id_width$}  {:<44}  {:<44}  {:<8}  {:>20}  {:>12}  {:>12}",
+            escrow.id,
+            escrow.sender,
+            escrow.recipient,
+            status.state(),
+            status.amount.to_string(),
+            height(status.finish_after),
+            height(status.cancel_after),
+        );
+    }
+    if let Some(escrow) = escrows.first() {
+        println!("\nCurrent height: {}", escrow.status.current_height);
+    }
+}
+
+/// Estimated costs of an escrow lifecycle, for `estimate`.
+#[derive(Debug, Serialize)]
+struct EstimateReport {
+    chain: Chain,
+    #[serde(flatten)]
+    costs: LifecycleEstimate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    proving: Option<ProvingEstimate>,
+}
+
+/// Lifecycle quotes on the candidate chains, for `quote`.
+#[derive(Debug, Serialize)]
+struct QuoteReport {
+    currency: String,
+    quotes: Vec<Quote>,
+    /// Label of the cheapest viable candidate.
+    recommended: Option<String>,
+}
+
+impl std::fmt::Display for QuoteReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "Costs in {} (total: create and the costlier settlement)",
+            self.currency
+        )?;
+        writeln!(
+            f,
+            "{:<16} {:<10} {:>12} {:>12} {:>12} {:>12}",
+            "CANDIDATE", "CHAIN", "CREATE", "FINISH", "CANCEL", "TOTAL"
+        )?;
+        for quote in &self.quotes {
+            writeln!(f, "{quote}")?;
+        }
+        match &self.recommended {
+            Some(label) => write!(f, "\nRecommended: {label}"),
+            None => write!(f, "\nNo viable candidate"),
+        }
+    }
+}
+
+/// Proving cost of an escrow's conditions, measured by executing the guest.
+#[derive(Debug, Serialize)]
+struct ProvingEstimate {
+    /// Cycles the proof covers.
+    cycles: u64,
+    /// Rough proving time on a local CPU, in seconds, including Groth16
+    /// compression on Ethereum, Tron, and Hedera.
+    seconds: u64,
+}
+
+impl std::fmt::Display for EstimateReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let unit = match self.chain {
+            Chain::Ethereum => "wei",
+            Chain::Solana => "lamports",
+            Chain::Cosmos => "the fee denom",
+            Chain::Substrate => "planck",
+            Chain::Cardano => "lovelace",
+            Chain::Tron => "sun",
+            Chain::Hedera => "tinybars",
+        };
+        writeln!(
+            f,
+            "Chain: {} (fees in {unit}, ~ marks reference figures)",
+            self.chain.as_ref()
+        )?;
+        write!(f, "{}", self.costs)?;
+        if let Some(proving) = &self.proving {
+            write!(
+                f,
+                "\n{:<22} cycles {:>8}  ~{}s on a local CPU",
+                "prove", proving.cycles, proving.seconds
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Executes the guest for the escrow in `params` with `condition` to
+/// measure the cycles its proof covers, and estimates the proving time.
+#[cfg(feature = "prover")]
+async fn estimate_proving(
+    params: &EscrowParams,
+    condition: Condition,
+) -> anyhow::Result<Option<ProvingEstimate>> {
+    use zescrow_core::interface::{ChainState, EscrowBinding};
+
+    /// Cycles proven per second on a typical multi-core CPU.
+    const CPU_CYCLES_PER_SEC: u64 = 10_000;
+    /// Seconds to compress a proof to Groth16.
+    const GROTH16_SECS: u64 = 45;
+
+    let metadata = EscrowMetadata {
+        params: params.clone(),
+        state: ExecutionState::Funded,
+        escrow_id: None,
+        uid: None,
+        chain_metadata: None,
+        guest: None,
+        observed: None,
+    };
+    // The escrow does not exist yet; the binding does not affect cycles.
+    let binding = EscrowBinding {
+        fingerprint: metadata.fingerprint(),
+        instance: [0; 32],
+        freshness: Freshness::NEVER,
+        chain_state: ChainState::NONE,
+    };
+    let cycles = tokio::task::spawn_blocking(move || {
+        prover::count_cycles(&metadata, Some(&condition), binding)
+    })
+    .await??;
+    let compression = match params.chain_config.chain {
+        Chain::Ethereum | Chain::Tron | Chain::Hedera => GROTH16_SECS,
+        Chain::Solana | Chain::Cosmos | Chain::Substrate | Chain::Cardano => 0,
+    };
+    Ok(Some(ProvingEstimate {
+        cycles,
+        seconds: cycles.div_ceil(CPU_CYCLES_PER_SEC) + compression,
+    }))
+}
+
+#[cfg(not(feature = "prover"))]
+async fn estimate_proving(
+    _params: &EscrowParams,
+    _condition: Condition,
+) -> anyhow::Result<Option<ProvingEstimate>> {
+    warn!("Skipping the proving estimate; rebuild with `--features prover` to enable it");
+    Ok(None)
+}
+
+/// Proves that `condition` is fulfilled for the escrow in `metadata`,
+/// returning the seal to submit on Ethereum.
+#[cfg(feature = "prover")]
+async fn prove_escrow(
+    opts: &ClientOpts,
+    metadata: &EscrowMetadata,
+    condition: Condition,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    let config = opts.prover.config(&metadata.params.chain_config)?;
+    opts.prover
+        .check_guest(metadata, Some(&prover::guest_image_id()))?;
+    let groth16 = matches!(metadata.params.chain_config.chain, Chain::Ethereum);
+    let binding = proof::binding(opts, metadata).await?;
+    let (metadata, fulfillment) = (metadata.clone(), condition.clone());
+    // Proving is CPU-bound and takes minutes; keep it off the runtime.
+    let seal = tokio::task::spawn_blocking(move || {
+        let receipt = prover::prove_escrow_receipt_with(
+            &metadata,
+            Some(&fulfillment),
+            binding,
+            groth16,
+            &config,
+        )?;
+        groth16.then(|| prover::ethereum_seal(&receipt)).transpose()
+    })
+    .await?
+    .map_err(|e| ClientError::from_proving(e, &condition))?;
+    Ok(seal)
+}
+
+#[cfg(not(feature = "prover"))]
+async fn prove_escrow(
+    _opts: &ClientOpts,
+    _metadata: &EscrowMetadata,
+    _condition: Condition,
+) -> anyhow::Result<Option<Vec<u8>>> {
+    Err(anyhow!(
+        "escrow has conditions but the 'prover' feature is disabled; \
+         rebuild with `--features prover` to enable ZK proof generation"
+    ))
+}
+
+/// Decodes the hex-encoded `value` of argument `name`, with or without a
+/// `0x` prefix.
+fn decode_hex(name: &str, value: &str) -> anyhow::Result<Vec<u8>> {
+    hex::decode(value.trim_start_matches("0x")).with_context(|| format!("{name} is not valid hex"))
+}
+
+/// Decodes hex-encoded Ethereum addresses, e.g. of bridge verifiers.
+fn decode_addresses(name: &str, addresses: &[String]) -> anyhow::Result<Vec<[u8; 20]>> {
+    addresses
+        .iter()
+        .map(|address| {
+            decode_hex(name, address)?
+                .as_slice()
+                .try_into()
+                .map_err(|_| anyhow!("{name} addresses must be 20 bytes"))
+        })
+        .collect()
+}
+
+/// Decodes a hex-encoded address of at most 32 bytes, left-padded to 32
+/// bytes as bridges encode them.
+fn left_pad(name: &str, address: &str) -> anyhow::Result<[u8; 32]> {
+    let bytes = decode_hex(name, address)?;
+    if bytes.len() > 32 {
+        bail!("{name} must be at most 32 bytes");
+    }
+    let mut padded = [0; 32];
+    padded[32 - bytes.len()..].copy_from_slice(&bytes);
+    Ok(padded)
+}
+
+/// Returns `builder` creating escrows with the condition in `path` when
+/// `params` has conditions and the file exists, as Ethereum, Tron, and
+/// Hedera escrows store the condition's fingerprint.
+fn with_condition(
+    builder: ZescrowClientBuilder,
+    params: &EscrowParams,
+    path: &Path,
+) -> anyhow::Result<ZescrowClientBuilder> {
+    if !params.has_conditions || !path.is_file() {
+        return Ok(builder);
+    }
+    info!("Loading conditions from {}", path.display());
+    let condition: Condition = load_escrow_data(path)?;
+    Ok(builder.condition(&condition))
+}
+
+/// Loads the condition files combined by a threshold.
+fn load_subconditions(paths: &[PathBuf]) -> anyhow::Result<Vec<Condition>> {
+    paths
+        .iter()
+        .map(|path| {
+            load_escrow_data(path).with_context(|| format!("loading condition {}", path.display()))
+        })
+        .collect()
+}
+
+fn handle_generate_cmd(opts: GenerateOpts, output: OutputFormat) -> anyhow::Result<()> {
+    let (cond, outfile, kind) = match opts.condition {
+        GenerateCmd::Hashlock { preimage, outfile } => {
             let preimage = std::fs::read_to_string(&preimage)
                 .with_context(|| format!("reading preimage file {preimage:?}"))?;
             let hash = Sha256::digest(preimage.as_bytes());
             let cond = Condition::hashlock(hash.into(), preimage.into_bytes());
-            save_escrow_data(&output, &cond)?;
-            info!("Saved hashlock condition file to: {output:?}");
+            (cond, outfile, "hashlock")
         }
 
         GenerateCmd::Ed25519 {
             pubkey,
             msg,
             sig,
-            output,
+            outfile,
         } => {
-            let pk: [u8; 32] = hex::decode(&pubkey)?
+            let pk: [u8; 32] = decode_hex("pubkey", &pubkey)?
                 .as_slice()
                 .try_into()
-                .map_err(|_| anyhow!("pubkey wrong length"))?;
-            let message = hex::decode(msg)?;
-            let signature = hex::decode(sig)?;
-            let cond = Condition::ed25519(pk, message, signature);
-            save_escrow_data(&output, &cond)?;
-            info!("Saved Ed25519 condition file to: {output:?}");
+                .map_err(|_| anyhow!("pubkey must be 32 bytes"))?;
+            let message = decode_hex("msg", &msg)?;
+            let signature = decode_hex("sig", &sig)?;
+            if signature.len() != 64 {
+                bail!("sig must be 64 bytes, got {}", signature.len());
+            }
+            (
+                Condition::ed25519(pk, message, signature),
+                outfile,
+                "Ed25519",
+            )
         }
 
         GenerateCmd::Secp256k1 {
             pubkey,
             msg,
             sig,
-            output,
+            outfile,
         } => {
-            let pk = hex::decode(&pubkey)?;
-            let message = hex::decode(msg)?;
-            let signature = hex::decode(sig)?;
-            let cond = Condition::secp256k1(pk, message, signature);
-            save_escrow_data(&output, &cond)?;
-            info!("Saved Secp256k1 condition file to: {output:?}");
+            let pk = decode_hex("pubkey", &pubkey)?;
+            if !matches!(pk.len(), 33 | 65) {
+                bail!(
+                    "pubkey must be a 33- or 65-byte SEC1 key, got {} bytes",
+                    pk.len()
+                );
+            }
+            let message = decode_hex("msg", &msg)?;
+            let signature = decode_hex("sig", &sig)?;
+            (
+                Condition::secp256k1(pk, message, signature),
+                outfile,
+                "Secp256k1",
+            )
         }
 
         GenerateCmd::Threshold {
             subconditions,
             threshold,
-            output,
+            outfile,
+        } => {
+            let subs = load_subconditions(&subconditions)?;
+            if !(1..=subs.len()).contains(&threshold) {
+                bail!(
+                    "threshold must be between 1 and the number of subconditions ({}); \
+                     a threshold of 0 is always satisfied",
+                    subs.len()
+                );
+            }
+            (Condition::threshold(threshold, subs), outfile, "threshold")
+        }
+
+        GenerateCmd::All {
+            subconditions,
+            outfile,
+        } => {
+            let subs = load_subconditions(&subconditions)?;
+            (Condition::threshold(subs.len(), subs), outfile, "all-of")
+        }
+
+        GenerateCmd::Any {
+            subconditions,
+            outfile,
+        } => {
+            let subs = load_subconditions(&subconditions)?;
+            (Condition::threshold(1, subs), outfile, "any-of")
+        }
+
+        GenerateCmd::CrossChain {
+            chain,
+            escrow,
+            refunded,
+            state_key,
+            value_hash,
+            attestors,
+            quorum,
+            outfile,
         } => {
-            let mut subs = Vec::with_capacity(subconditions.len());
-            for path in subconditions {
-                let c: Condition = load_escrow_data(&path)?;
-                subs.push(c);
+            let claim = match (escrow, state_key, value_hash) {
+                (Some(escrow), None, None) => RemoteClaim::Escrow {
+                    instance: load_escrow_data::<_, EscrowMetadata>(&escrow)
+                        .with_context(|| format!("loading metadata {}", escrow.display()))?
+                        .binding()?
+                        .instance,
+                    state: if refunded {
+                        ExecutionState::Refunded
+                    } else {
+                        ExecutionState::Released
+                    },
+                },
+                (None, Some(key), Some(value_hash)) => RemoteClaim::State {
+                    key: decode_hex("state-key", &key)?,
+                    value_hash: decode_hex("value-hash", &value_hash)?
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| anyhow!("value-hash must be 32 bytes"))?,
+                },
+                _ => bail!("pass either --escrow, or --state-key and --value-hash"),
+            };
+            let attestors = attestors
+                .iter()
+                .map(|attestor| {
+                    decode_hex("attestor", attestor)?
+                        .as_slice()
+                        .try_into()
+                        .map_err(|_| anyhow!("attestor keys must be 32 bytes"))
+                })
+                .collect::<anyhow::Result<Vec<[u8; 32]>>>()?;
+            if !(1..=attestors.len()).contains(&quorum) {
+                bail!(
+                    "quorum must be between 1 and the number of attestors ({})",
+                    attestors.len()
+                );
             }
-            let cond = Condition::threshold(threshold, subs);
-            save_escrow_data(&output, &cond)?;
-            info!("Saved threshold condition file to: {output:?}");
+            (
+                Condition::cross_chain(chain, claim, attestors, quorum),
+                outfile,
+                "cross-chain",
+            )
         }
+
+        GenerateCmd::Bridge {
+            source_chain,
+            emitter,
+            payload,
+            payload_hash,
+            guardians,
+            guardian_set_index,
+            dvns,
+            destination,
+            receiver,
+            dvn_threshold,
+            message,
+            dvn_signatures,
+            outfile,
+        } => {
+            let payload_hash = match (payload, payload_hash) {
+                (Some(payload), None) => Sha256::digest(decode_hex("payload", &payload)?).into(),
+                (None, Some(hash)) => decode_hex("payload-hash", &hash)?
+                    .as_slice()
+                    .try_into()
+                    .map_err(|_| anyhow!("payload-hash must be 32 bytes"))?,
+                _ => bail!("pass either --payload or --payload-hash"),
+            };
+            let verifiers = match (guardian_set_index, destination, receiver, dvn_threshold) {
+                (Some(guardian_set_index), None, None, None) => Verifiers::Wormhole {
+                    guardian_set_index,
+                    guardians: decode_addresses("guardian", &guardians)?,
+                },
+                (None, Some(destination), Some(receiver), Some(threshold)) => {
+                    let dvns = decode_addresses("DVN", &dvns)?;
+                    if !(1..=dvns.len()).contains(&threshold) {
+                        bail!(
+                            "dvn-threshold must be between 1 and the number of DVNs ({})",
+                            dvns.len()
+                        );
+                    }
+                    Verifiers::LayerZero {
+                        destination,
+                        receiver: left_pad("receiver", &receiver)?,
+                        dvns,
+                        threshold,
+                    }
+                }
+                _ => bail!("pass either --guardians, or --dvns and their destination"),
+            };
+            let mut cond = Condition::bridge(
+                verifiers,
+                source_chain,
+                left_pad("emitter", &emitter)?,
+                payload_hash,
+            );
+            if let (Some(path), Condition::Bridge(bridge)) = (message, &mut cond) {
+                let data = std::fs::read_to_string(&path)
+                    .with_context(|| format!("reading message {}", path.display()))?;
+                let data = decode_hex("message", data.trim())?;
+                if dvn_signatures.is_empty() {
+                    bridge.message = BridgeMessage::vaa(data);
+                } else {
+                    let packet = Packet::parse(&data)?;
+                    for signature in &dvn_signatures {
+                        bridge
+                            .add_dvn_signature(&packet, decode_hex("DVN signature", signature)?)?;
+                    }
+                }
+            }
+            (cond, outfile, "bridge")
+        }
+    };
+
+    // Catch a bad witness now rather than when proving. Cross-chain
+    // conditions are attested after they are generated, with `attest`, and
+    // bridge messages may not have been sent yet.
+    let verification = cond.verification();
+    let deferred = match &cond {
+        Condition::CrossChain(_) => true,
+        Condition::Bridge(bridge) => bridge.message.data.is_empty(),
+        _ => false,
+    };
+    if !verification.passed() && !deferred {
+        if !opts.allow_unfulfilled {
+            bail!(
+                "{kind} condition is not fulfilled (pass --allow-unfulfilled to write it \
+                 anyway):\n{verification}"
+            );
+        }
+        warn!("Writing {kind} condition that is not fulfilled:\n{verification}");
+    }
+
+    save_escrow_data(&outfile, &cond)?;
+    info!("Saved {kind} condition file to: {outfile:?}");
+    if output == OutputFormat::Json {
+        print_json(&serde_json::json!({ "path": outfile, "condition": cond }))?;
     }
     Ok(())
 }
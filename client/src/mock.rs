@@ -0,0 +1,602 @@
+//! In-memory [`Agent`] for testing escrow flows without a chain.
+//!
+//! [`MockAgent`] keeps escrows in an in-memory ledger, advances block height
+//! only when told to, and fails operations on demand, so integrations can be
+//! unit-tested deterministically without devnet RPC or a local validator.
+//! Clones share the same ledger, so a test can keep one handle while a
+//! [`ZescrowClient`](crate::ZescrowClient) owns another.
+//!
+//! # Example
+//!
+//! ```ignore
+//! use zescrow_client::mock::{MockAgent, MockOperation};
+//! use zescrow_client::ZescrowClient;
+//!
+//! let mock = MockAgent::new(Chain::Ethereum);
+//! let client = ZescrowClient::from_agent(mock.clone());
+//!
+//! let metadata = client.create_escrow(&params).await?;
+//! assert!(client.finish_escrow(&metadata).await.is_err()); // before finish_after
+//!
+//! mock.advance(100);
+//! mock.fail_next(MockOperation::Finish, ClientError::tx_dropped("test"));
+//! assert!(client.finish_escrow(&metadata).await.is_err());
+//! client.finish_escrow(&metadata).await?;
+//! assert_eq!(mock.balance(&params.recipient.to_string()), params.asset.amount);
+//! ```
+//!
+//! Conditions are not verified: finishing an escrow with conditions only
+//! checks its timelocks.
+//!
+//! Blocks are final once [`set_finality_depth`](MockAgent::set_finality_depth)
+//! blocks deep (at once by default), and [`reorg`](MockAgent::reorg)
+//! replaces the latest blocks, undoing the escrow changes made in them.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use futures::StreamExt;
+use num_traits::ToPrimitive;
+use tokio::sync::broadcast;
+use zescrow_core::{
+    BigNumber, Chain, EscrowId, EscrowMetadata, EscrowParams, ExecutionState, ObservedBlock,
+};
+
+use crate::estimate::{Cost, LifecycleEstimate};
+use crate::pending::PendingTransaction;
+use crate::simulation::{Operation, Simulation};
+use crate::status::{EscrowStatus, EscrowSummary};
+use crate::watch::{EscrowUpdate, UpdateKind, UpdateStream};
+use crate::{Agent, ClientError, Result};
+
+/// Capacity of the update channel; slower watchers miss older updates.
+const UPDATE_CAPACITY: usize = 256;
+
+/// Operation that [`MockAgent::fail_next`] can fail.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MockOperation {
+    Create,
+    Finish,
+    Cancel,
+    Simulate,
+    GetState,
+    List,
+    Watch,
+    WaitForTransaction,
+}
+
+/// An escrow in the mock ledger.
+#[derive(Debug, Clone)]
+pub struct MockEscrow {
+    /// Identifier assigned at creation, on either chain.
+    pub id: u64,
+    /// Parameters the escrow was created with.
+    pub params: EscrowParams,
+    /// `Funded` until finished (`Released`) or cancelled (`Refunded`).
+    pub state: ExecutionState,
+    /// Transaction that created the escrow.
+    pub tx: String,
+}
+
+/// Mutable state shared by clones of a [`MockAgent`].
+#[derive(Debug)]
+struct Ledger {
+    escrows: BTreeMap<u64, MockEscrow>,
+    /// Amounts paid out per party, by finish or cancel.
+    balances: HashMap<String, BigNumber>,
+    /// Transactions sent, in order.
+    transactions: Vec<String>,
+    height: u64,
+    next_id: u64,
+    failures: HashMap<MockOperation, VecDeque<ClientError>>,
+    /// Blocks a block must be below the latest to be final.
+    finality_depth: u64,
+    /// First heights of the blocks replaced by each reorg.
+    reorgs: Vec<u64>,
+    /// Escrow changes, in order, undone when a reorg replaces their block.
+    changes: Vec<Change>,
+    /// Costs returned by `estimate`, if set.
+    estimate: Option<LifecycleEstimate>,
+}
+
+/// A change to an escrow, made in the block at `height`.
+#[derive(Debug)]
+struct Change {
+    height: u64,
+    escrow: u64,
+    /// Whether the escrow was created, rather than settled.
+    created: bool,
+}
+
+/// In-memory [`Agent`] with deterministic time and failure injection.
+#[derive(Debug, Clone)]
+pub struct MockAgent {
+    chain: Chain,
+    ledger: Arc<Mutex<Ledger>>,
+    updates: broadcast::Sender<EscrowUpdate>,
+}
+
+impl MockAgent {
+    /// Creates an empty ledger at height zero. `chain` only determines the
+    /// format of transaction IDs.
+    pub fn new(chain: Chain) -> Self {
+        Self {
+            chain,
+            ledger: Arc::new(Mutex::new(Ledger {
+                escrows: BTreeMap::new(),
+                balances: HashMap::new(),
+                transactions: Vec::new(),
+                height: 0,
+                next_id: 0,
+                failures: HashMap::new(),
+                finality_depth: 0,
+                reorgs: Vec::new(),
+                changes: Vec::new(),
+                estimate: None,
+            })),
+            updates: broadcast::channel(UPDATE_CAPACITY).0,
+        }
+    }
+
+    /// Returns the current block height (or slot).
+    pub fn height(&self) -> u64 {
+        self.lock().height
+    }
+
+    /// Sets the current block height (or slot).
+    pub fn set_height(&self, height: u64) {
+        self.lock().height = height;
+    }
+
+    /// Advances the current block height (or slot) by `blocks`.
+    pub fn advance(&self, blocks: u64) {
+        let mut ledger = self.lock();
+        ledger.height = ledger.height.saturating_add(blocks);
+    }
+
+    /// Makes blocks final only once they are `blocks` below the latest.
+    pub fn set_finality_depth(&self, blocks: u64) {
+        self.lock().finality_depth = blocks;
+    }
+
+    /// Replaces the latest `blocks` blocks with new ones at the same
+    /// heights, regardless of finality: escrows created in them are
+    /// removed, and escrows settled in them are funded again.
+    pub fn reorg(&self, blocks: u64) {
+        let mut ledger = self.lock();
+        let from = (ledger.height + 1).saturating_sub(blocks);
+        ledger.reorgs.push(from);
+        while ledger
+            .changes
+            .last()
+            .is_some_and(|change| change.height >= from)
+        {
+            let change = ledger.changes.pop().expect("change exists");
+            if change.created {
+                ledger.escrows.remove(&change.escrow);
+                continue;
+            }
+            let escrow = ledger
+                .escrows
+                .get_mut(&change.escrow)
+                .expect("settled escrow exists");
+            let payee = match escrow.state {
+                ExecutionState::Refunded => escrow.params.sender.to_string(),
+                _ => escrow.params.recipient.to_string(),
+            };
+            escrow.state = ExecutionState::Funded;
+            let amount = escrow.params.asset.amount.clone();
+            if let Some(balance) = ledger.balances.get_mut(&payee) {
+                *balance = BigNumber(&balance.0 - &amount.0);
+            }
+        }
+    }
+
+    /// Makes `estimate` return `estimate`, rather than a simulated create
+    /// with unknown settlement costs.
+    pub fn set_estimate(&self, estimate: LifecycleEstimate) {
+        self.lock().estimate = Some(estimate);
+    }
+
+    /// Makes the next call of `operation` fail with `error`, without
+    /// changing the ledger. Queued errors are returned in order.
+    pub fn fail_next(&self, operation: MockOperation, error: ClientError) {
+        self.lock()
+            .failures
+            .entry(operation)
+            .or_default()
+            .push_back(error);
+    }
+
+    /// Returns the escrow with identifier `id`, if created.
+    pub fn escrow(&self, id: u64) -> Option<MockEscrow> {
+        self.lock().escrows.get(&id).cloned()
+    }
+
+    /// Returns every escrow created, in creation order.
+    pub fn escrows(&self) -> Vec<MockEscrow> {
+        self.lock().escrows.values().cloned().collect()
+    }
+
+    /// Returns the total paid out to `party` (as formatted in escrow
+    /// parameters) by finished and cancelled escrows.
+    pub fn balance(&self, party: &str) -> BigNumber {
+        self.lock()
+            .balances
+            .get(party)
+            .cloned()
+            .unwrap_or_else(BigNumber::zero)
+    }
+
+    /// Returns the IDs of the transactions sent, in order.
+    pub fn transactions(&self) -> Vec<String> {
+        self.lock().transactions.clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Ledger> {
+        self.ledger.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Returns the error queued for `operation`, if any.
+    fn injected(&self, operation: MockOperation) -> Result<()> {
+        match self
+            .lock()
+            .failures
+            .get_mut(&operation)
+            .and_then(VecDeque::pop_front)
+        {
+            Some(error) => Err(error),
+            None => Ok(()),
+        }
+    }
+
+    /// Settles the escrow in `metadata` as `state` if its timelocks allow,
+    /// crediting the recipient (finish) or sender (cancel).
+    fn settle(&self, metadata: &EscrowMetadata, state: ExecutionState) -> Result<()> {
+        let update = {
+            let mut ledger = self.lock();
+            check_settlement(&ledger, metadata, state)?;
+            let id = find(&ledger, metadata)?.id;
+            let tx = next_tx(&mut ledger, self.chain);
+            let height = ledger.height;
+            ledger.changes.push(Change {
+                height,
+                escrow: id,
+                created: false,
+            });
+            let escrow = ledger.escrows.get_mut(&id).expect("escrow was found");
+            escrow.state = state;
+            let params = escrow.params.clone();
+            let payee = match state {
+                ExecutionState::Refunded => params.sender.to_string(),
+                _ => params.recipient.to_string(),
+            };
+            let balance = ledger.balances.entry(payee).or_insert_with(BigNumber::zero);
+            *balance = BigNumber(&balance.0 + &params.asset.amount.0);
+            EscrowUpdate {
+                escrow: id.to_string(),
+                kind: match state {
+                    ExecutionState::Refunded => UpdateKind::Cancelled,
+                    _ => UpdateKind::Finished,
+                },
+                sender: Some(params.sender.to_string()),
+                recipient: Some(params.recipient.to_string()),
+                amount: params.asset.amount,
+                tx: Some(tx),
+            }
+        };
+        // Nobody watching is fine.
+        let _ = self.updates.send(update);
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl Agent for MockAgent {
+    async fn create_escrow(&self, params: &EscrowParams) -> Result<EscrowMetadata> {
+        self.injected(MockOperation::Create)?;
+        check_params(params)?;
+
+        let (id, tx) = {
+            let mut ledger = self.lock();
+            let id = ledger.next_id;
+            ledger.next_id += 1;
+            let tx = next_tx(&mut ledger, self.chain);
+            let height = ledger.height;
+            ledger.changes.push(Change {
+                height,
+                escrow: id,
+                created: true,
+            });
+            ledger.escrows.insert(
+                id,
+                MockEscrow {
+                    id,
+                    params: params.clone(),
+                    state: ExecutionState::Funded,
+                    tx: tx.clone(),
+                },
+            );
+            (id, tx)
+        };
+        let _ = self.updates.send(EscrowUpdate {
+            escrow: id.to_string(),
+            kind: UpdateKind::Created,
+            sender: Some(params.sender.to_string()),
+            recipient: Some(params.recipient.to_string()),
+            amount: params.asset.amount.clone(),
+            tx: Some(tx),
+        });
+
+        Ok(EscrowMetadata {
+            params: params.clone(),
+            state: ExecutionState::Funded,
+            escrow_id: Some(id),
+            uid: Some(uid(id, params)),
+            chain_metadata: None,
+            guest: None,
+            observed: None,
+        })
+    }
+
+    async fn finish_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
+        self.injected(MockOperation::Finish)?;
+        self.settle(metadata, ExecutionState::Released)
+    }
+
+    async fn cancel_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
+        self.injected(MockOperation::Cancel)?;
+        self.settle(metadata, ExecutionState::Refunded)
+    }
+
+    async fn simulate(&self, operation: Operation<'_>) -> Result<Simulation> {
+        self.injected(MockOperation::Simulate)?;
+        let (name, outcome) = match operation {
+            Operation::Create(params) => ("create", check_params(params)),
+            Operation::Finish(metadata) => (
+                "finish",
+                check_settlement(&self.lock(), metadata, ExecutionState::Released),
+            ),
+            Operation::Cancel(metadata) => (
+                "cancel",
+                check_settlement(&self.lock(), metadata, ExecutionState::Refunded),
+            ),
+        };
+        Ok(Simulation {
+            operation: name,
+            error: outcome.err().map(|e| e.to_string()),
+            logs: Vec::new(),
+            units: Some(0),
+            fee: Some(BigNumber::zero()),
+        })
+    }
+
+    async fn estimate(&self, params: &EscrowParams) -> Result<LifecycleEstimate> {
+        let estimate = self.lock().estimate.clone();
+        match estimate {
+            Some(estimate) => {
+                self.injected(MockOperation::Simulate)?;
+                check_params(params)?;
+                Ok(estimate)
+            }
+            None => {
+                let create = self.simulate(Operation::Create(params)).await?;
+                Ok(LifecycleEstimate {
+                    create: create.into(),
+                    finish: Cost::unknown("finish"),
+                    cancel: Cost::unknown("cancel"),
+                })
+            }
+        }
+    }
+
+    async fn get_escrow_state(&self, metadata: &EscrowMetadata) -> Result<EscrowStatus> {
+        self.injected(MockOperation::GetState)?;
+        let ledger = self.lock();
+        let escrow = find(&ledger, metadata)?;
+        Ok(status(escrow, ledger.height))
+    }
+
+    async fn current_height(&self) -> Result<u64> {
+        self.injected(MockOperation::GetState)?;
+        Ok(self.height())
+    }
+
+    async fn latest_block(&self) -> Result<ObservedBlock> {
+        self.injected(MockOperation::GetState)?;
+        let ledger = self.lock();
+        Ok(ObservedBlock {
+            height: ledger.height,
+            hash: block_hash(&ledger, ledger.height),
+        })
+    }
+
+    async fn block_hash(&self, height: u64) -> Result<Option<[u8; 32]>> {
+        self.injected(MockOperation::GetState)?;
+        let ledger = self.lock();
+        Ok((height <= ledger.height).then(|| block_hash(&ledger, height)))
+    }
+
+    async fn finalized_height(&self) -> Result<u64> {
+        self.injected(MockOperation::GetState)?;
+        let ledger = self.lock();
+        Ok(ledger.height.saturating_sub(ledger.finality_depth))
+    }
+
+    async fn list_escrows(&self) -> Result<Vec<EscrowSummary>> {
+        self.injected(MockOperation::List)?;
+        let ledger = self.lock();
+        Ok(ledger
+            .escrows
+            .values()
+            .map(|escrow| EscrowSummary {
+                id: escrow.id.to_string(),
+                uid: Some(uid(escrow.id, &escrow.params)),
+                sender: escrow.params.sender.to_string(),
+                recipient: escrow.params.recipient.to_string(),
+                status: status(escrow, ledger.height),
+            })
+            .collect())
+    }
+
+    async fn watch_escrows(&self, metadata: Option<&EscrowMetadata>) -> Result<UpdateStream> {
+        self.injected(MockOperation::Watch)?;
+        let escrow = metadata
+            .map(|metadata| find(&self.lock(), metadata).map(|escrow| escrow.id.to_string()))
+            .transpose()?;
+        let updates = futures::stream::unfold(self.updates.subscribe(), |mut updates| async move {
+            loop {
+                match updates.recv().await {
+                    Ok(update) => return Some((Ok(update), updates)),
+                    Err(broadcast::error::RecvError::Lagged(_)) => continue,
+                    Err(broadcast::error::RecvError::Closed) => return None,
+                }
+            }
+        });
+        Ok(updates
+            .filter(move |update| {
+                let keep = match (&escrow, update) {
+                    (Some(escrow), Ok(update)) => update.escrow == *escrow,
+                    _ => true,
+                };
+                futures::future::ready(keep)
+            })
+            .boxed())
+    }
+
+    async fn wait_for_transaction(&self, pending: &PendingTransaction) -> Result<()> {
+        self.injected(MockOperation::WaitForTransaction)?;
+        if self.lock().transactions.contains(&pending.id) {
+            Ok(())
+        } else {
+            Err(ClientError::tx_dropped(format!(
+                "{} was never sent",
+                pending.id
+            )))
+        }
+    }
+}
+
+/// Rejects parameters the escrow program or contract would reject.
+fn check_params(params: &EscrowParams) -> Result<()> {
+    if params.finish_after.is_none() && params.cancel_after.is_none() {
+        return Err(ClientError::Blockchain(
+            "either finish_after or cancel_after must be set".into(),
+        ));
+    }
+    if let (Some(finish), Some(cancel)) = (params.finish_after, params.cancel_after) {
+        if finish >= cancel {
+            return Err(ClientError::Blockchain(format!(
+                "finish_after ({finish}) must be less than cancel_after ({cancel})"
+            )));
+        }
+    }
+    params.memo_bytes()?;
+    if !crate::stores_memo(params.chain_config.chain) {
+        crate::reject_memo(params)?;
+    }
+    Ok(())
+}
+
+/// Checks that the escrow in `metadata` can be settled as `state` now.
+fn check_settlement(
+    ledger: &Ledger,
+    metadata: &EscrowMetadata,
+    state: ExecutionState,
+) -> Result<()> {
+    let escrow = find(ledger, metadata)?;
+    if escrow.state != ExecutionState::Funded {
+        return Err(ClientError::Blockchain(format!(
+            "escrow {} is already settled",
+            escrow.id
+        )));
+    }
+    let status = status(escrow, ledger.height);
+    let allowed = match state {
+        ExecutionState::Refunded => status.can_cancel(),
+        _ => status.can_finish(),
+    };
+    if !allowed {
+        return Err(ClientError::Blockchain(format!(
+            "escrow {} cannot be {} at height {}",
+            escrow.id,
+            match state {
+                ExecutionState::Refunded => "cancelled",
+                _ => "finished",
+            },
+            ledger.height
+        )));
+    }
+    Ok(())
+}
+
+/// Looks up the escrow in `metadata`: by ID if set, otherwise the most
+/// recent funded escrow between the same parties, as Solana escrows are
+/// derived from them.
+fn find<'a>(ledger: &'a Ledger, metadata: &EscrowMetadata) -> Result<&'a MockEscrow> {
+    let escrow = match metadata.escrow_id {
+        Some(id) => ledger.escrows.get(&id),
+        None => ledger.escrows.values().rev().find(|escrow| {
+            escrow.state == ExecutionState::Funded
+                && escrow.params.sender.to_string() == metadata.params.sender.to_string()
+                && escrow.params.recipient.to_string() == metadata.params.recipient.to_string()
+        }),
+    };
+    escrow.ok_or_else(|| ClientError::Blockchain("escrow not found".into()))
+}
+
+/// Chain-agnostic ID of escrow `id`, under a `mock:` chain ID and over the
+/// parties' addresses as given.
+fn uid(id: u64, params: &EscrowParams) -> EscrowId {
+    EscrowId::new(
+        &format!("mock:{}", params.chain_config.chain.as_ref()),
+        &[],
+        params.sender.to_string().as_bytes(),
+        params.recipient.to_string().as_bytes(),
+        &EscrowId::word(id),
+        &[],
+        params.asset.amount.0.to_u128().unwrap_or(u128::MAX),
+    )
+}
+
+fn status(escrow: &MockEscrow, height: u64) -> EscrowStatus {
+    let settled = escrow.state != ExecutionState::Funded;
+    EscrowStatus {
+        settled,
+        amount: if settled {
+            BigNumber::zero()
+        } else {
+            escrow.params.asset.amount.clone()
+        },
+        finish_after: escrow.params.finish_after,
+        cancel_after: escrow.params.cancel_after,
+        current_height: height,
+        has_conditions: escrow.params.has_conditions,
+    }
+}
+
+/// Hash of the block at `height`: its height and the number of reorgs that
+/// replaced it.
+fn block_hash(ledger: &Ledger, height: u64) -> [u8; 32] {
+    let replaced = ledger.reorgs.iter().filter(|from| **from <= height).count() as u64;
+    let mut hash = [0; 32];
+    hash[..8].copy_from_slice(&height.to_be_bytes());
+    hash[8..16].copy_from_slice(&replaced.to_be_bytes());
+    hash
+}
+
+/// Records and returns the ID of a new transaction, formatted like the
+/// chain's: a hex hash on Ethereum, Substrate, and Hedera, a base58
+/// signature on Solana, an uppercase hex hash on Cosmos, a bare hex hash on
+/// Cardano and Tron.
+fn next_tx(ledger: &mut Ledger, chain: Chain) -> String {
+    let n = ledger.transactions.len() as u64 + 1;
+    let tx = match chain {
+        Chain::Ethereum | Chain::Substrate | Chain::Hedera => format!("0x{n:064x}"),
+        Chain::Solana => solana_sdk::bs58::encode(n.to_be_bytes()).into_string(),
+        Chain::Cosmos => format!("{n:064X}"),
+        Chain::Cardano | Chain::Tron => format!("{n:064x}"),
+    };
+    ledger.transactions.push(tx.clone());
+    tx
+}
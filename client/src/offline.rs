@@ -0,0 +1,209 @@
+//! Transactions built, signed, and broadcast in separate steps, so that
+//! escrow transactions can go through an offline signing ceremony.
+//!
+//! [`ZescrowClient::build_transaction`](crate::ZescrowClient::build_transaction)
+//! produces an unsigned [`OfflineTransaction`] in the chain's open wire
+//! format, [`sign_transaction`] adds a signature without network access,
+//! and [`ZescrowClient::broadcast_transaction`](crate::ZescrowClient::broadcast_transaction)
+//! submits it once every signer has signed:
+//!
+//! - **Solana**: a base64-encoded wire-format `VersionedTransaction`, with
+//!   zeroed signatures for the missing signers
+//! - **Ethereum**: a 0x-prefixed EIP-2718 EIP-1559 transaction; unsigned,
+//!   its signing payload (`0x02 || rlp(fields)`)
+
+use alloy::consensus::{SignableTransaction, TxEip1559, TxEnvelope};
+use alloy::eips::eip2718::Encodable2718;
+use alloy::network::TxSigner;
+use alloy::primitives::Address;
+use alloy::rlp::Decodable;
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use serde::{Deserialize, Serialize};
+use solana_sdk::signature::Signature;
+use solana_sdk::signer::Signer;
+use solana_sdk::transaction::VersionedTransaction;
+use zescrow_core::{Chain, ChainMetadata};
+
+use crate::error::ClientError;
+use crate::{secrets, EthereumSigner, Result, SolanaSigner};
+
+/// EIP-2718 type byte of EIP-1559 transactions.
+const EIP1559_TX_TYPE: u8 = 0x02;
+
+/// A transaction awaiting signatures or broadcast.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OfflineTransaction {
+    /// Chain the transaction is for.
+    pub chain: Chain,
+    /// Contract method or program instruction the transaction calls.
+    pub operation: String,
+    /// Base64 Solana transaction, or 0x-prefixed EIP-2718 Ethereum
+    /// transaction.
+    pub transaction: String,
+    /// Addresses whose signatures are still missing.
+    pub signers: Vec<String>,
+}
+
+/// Outcome of broadcasting an [`OfflineTransaction`].
+#[derive(Debug, Clone, Serialize)]
+pub struct Broadcast {
+    /// Signature (Solana) or hash (Ethereum) of the confirmed transaction.
+    pub transaction: String,
+    /// ID assigned to the escrow by a create, on chains that number them.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub escrow_id: Option<u64>,
+    /// Record of the escrow created by a create, if captured.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub chain_metadata: Option<ChainMetadata>,
+}
+
+impl OfflineTransaction {
+    /// Whether every required signature is present.
+    pub fn is_signed(&self) -> bool {
+        self.signers.is_empty()
+    }
+
+    /// Wraps a Solana transaction, listing the signers whose signatures
+    /// are still zeroed.
+    pub(crate) fn solana(tx: &VersionedTransaction, operation: &str) -> Result<Self> {
+        let bytes =
+            bincode1::serialize(tx).map_err(|e| ClientError::Serialization(e.to_string()))?;
+        let signers = tx
+            .signatures
+            .iter()
+            .zip(tx.message.static_account_keys())
+            .filter(|(signature, _)| **signature == Signature::default())
+            .map(|(_, key)| key.to_string())
+            .collect();
+        Ok(Self {
+            chain: Chain::Solana,
+            operation: operation.to_owned(),
+            transaction: STANDARD.encode(bytes),
+            signers,
+        })
+    }
+
+    /// Wraps the signing payload of an unsigned Ethereum transaction from
+    /// `from`.
+    pub(crate) fn ethereum(tx: &TxEip1559, from: Address, operation: &str) -> Self {
+        let mut payload = Vec::new();
+        tx.encode_for_signing(&mut payload);
+        Self {
+            chain: Chain::Ethereum,
+            operation: operation.to_owned(),
+            transaction: alloy::hex::encode_prefixed(payload),
+            signers: vec![from.to_string()],
+        }
+    }
+
+    /// Decodes the Solana transaction.
+    pub(crate) fn solana_transaction(&self) -> Result<VersionedTransaction> {
+        self.expect_chain(Chain::Solana)?;
+        let bytes = STANDARD
+            .decode(self.transaction.trim())
+            .map_err(|e| ClientError::Serialization(format!("invalid base64 transaction: {e}")))?;
+        bincode1::deserialize(&bytes)
+            .map_err(|e| ClientError::Serialization(format!("invalid Solana transaction: {e}")))
+    }
+
+    /// Decodes the raw bytes of the Ethereum transaction.
+    pub(crate) fn ethereum_bytes(&self) -> Result<Vec<u8>> {
+        self.expect_chain(Chain::Ethereum)?;
+        alloy::hex::decode(self.transaction.trim())
+            .map_err(|e| ClientError::Serialization(format!("invalid hex transaction: {e}")))
+    }
+
+    fn expect_chain(&self, chain: Chain) -> Result<()> {
+        match (self.chain, chain) {
+            (Chain::Solana, Chain::Solana) | (Chain::Ethereum, Chain::Ethereum) => Ok(()),
+            _ => Err(ClientError::ConfigMismatch {
+                expected: chain.as_ref().to_owned(),
+                actual: self.chain.as_ref().to_owned(),
+            }),
+        }
+    }
+}
+
+/// Adds the signature of `key`, a `sender_private_id`-style key or secret
+/// reference, to `tx`. Needs no network access.
+///
+/// # Errors
+///
+/// Returns an error if the key cannot be loaded, is not one of the missing
+/// signers, or the transaction cannot be decoded or signed.
+pub async fn sign_transaction(tx: &OfflineTransaction, key: &str) -> Result<OfflineTransaction> {
+    let key = secrets::resolve(key)
+        .await?
+        .unwrap_or_else(|| key.to_owned());
+    match tx.chain {
+        Chain::Solana => sign_solana(tx, &key),
+        Chain::Ethereum => sign_ethereum(tx, &key).await,
+        Chain::Cosmos | Chain::Substrate | Chain::Cardano | Chain::Tron | Chain::Hedera => {
+            Err(ClientError::InvalidChainOperation(format!(
+                "offline transactions are not supported on {}",
+                tx.chain.as_ref()
+            )))
+        }
+    }
+}
+
+fn sign_solana(tx: &OfflineTransaction, key: &str) -> Result<OfflineTransaction> {
+    let signer = SolanaSigner::from_id(key)?;
+    let pubkey = signer.pubkey();
+    let mut transaction = tx.solana_transaction()?;
+    let required = usize::from(transaction.message.header().num_required_signatures);
+    let index = transaction.message.static_account_keys()[..required]
+        .iter()
+        .position(|key| *key == pubkey)
+        .ok_or_else(|| {
+            ClientError::Keypair(format!("{pubkey} is not a signer of this transaction"))
+        })?;
+    transaction.signatures[index] = signer
+        .as_signer()
+        .try_sign_message(&transaction.message.serialize())
+        .map_err(|e| ClientError::Keypair(e.to_string()))?;
+    OfflineTransaction::solana(&transaction, &tx.operation)
+}
+
+async fn sign_ethereum(tx: &OfflineTransaction, key: &str) -> Result<OfflineTransaction> {
+    if tx.is_signed() {
+        return Err(ClientError::InvalidChainOperation(
+            "transaction is already signed".into(),
+        ));
+    }
+    let signer = EthereumSigner::from_id(key).await?;
+    let address = TxSigner::address(&signer);
+    if !tx
+        .signers
+        .iter()
+        .any(|signer| signer.parse::<Address>().ok() == Some(address))
+    {
+        return Err(ClientError::Keypair(format!(
+            "{address} is not a signer of this transaction"
+        )));
+    }
+
+    let bytes = tx.ethereum_bytes()?;
+    let mut transaction = match bytes.split_first() {
+        Some((&EIP1559_TX_TYPE, mut fields)) => TxEip1559::decode(&mut fields).map_err(|e| {
+            ClientError::Serialization(format!("invalid EIP-1559 transaction: {e}"))
+        })?,
+        _ => {
+            return Err(ClientError::Serialization(
+                "expected an unsigned EIP-1559 transaction".into(),
+            ))
+        }
+    };
+    let signature = signer
+        .sign_transaction(&mut transaction)
+        .await
+        .map_err(|e| ClientError::Keypair(e.to_string()))?;
+    let envelope = TxEnvelope::from(transaction.into_signed(signature));
+    Ok(OfflineTransaction {
+        chain: Chain::Ethereum,
+        operation: tx.operation.clone(),
+        transaction: alloy::hex::encode_prefixed(envelope.encoded_2718()),
+        signers: Vec::new(),
+    })
+}
@@ -0,0 +1,21 @@
+//! Output formats for command results.
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+/// How commands print their results.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    /// Human-readable text.
+    #[default]
+    Text,
+    /// JSON on stdout: one document per command, or one object per line
+    /// for `watch`.
+    Json,
+}
+
+/// Prints `value` to stdout as a pretty-printed JSON document.
+pub fn print_json<T: Serialize + ?Sized>(value: &T) -> anyhow::Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
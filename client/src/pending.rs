@@ -0,0 +1,59 @@
+//! Bounded waits for transaction confirmation.
+//!
+//! By default agents wait until a transaction is confirmed or dropped. With
+//! a [`WaitConfig::timeout`], they stop waiting and fail with
+//! [`ClientError::Pending`](crate::ClientError::Pending), carrying the
+//! [`PendingTransaction`] to resume with
+//! [`ZescrowClient::wait_for_transaction`](crate::ZescrowClient::wait_for_transaction).
+
+use std::fmt;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use zescrow_core::Chain;
+
+/// How long, and how often, to poll for transaction confirmation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WaitConfig {
+    /// Stop waiting after this long. Unbounded if unset, though Solana
+    /// transactions stop being awaited once their blockhash expires,
+    /// Substrate extrinsics once their era ends, Cardano transactions once
+    /// their validity interval ends, and Tron transactions once they expire.
+    pub timeout: Option<Duration>,
+    /// Interval between confirmation polls. Defaults to four seconds on
+    /// Ethereum, half a second on Solana, two seconds on Cosmos, six
+    /// seconds on Substrate, twenty seconds on Cardano, three seconds on
+    /// Tron, and four seconds on Hedera.
+    pub poll_interval: Option<Duration>,
+}
+
+/// A transaction that was sent but not yet confirmed when its wait timed
+/// out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingTransaction {
+    /// Chain the transaction was sent to.
+    pub chain: Chain,
+    /// Contract method or program instruction the transaction calls.
+    pub operation: String,
+    /// Transaction signature (Solana), hash (Ethereum, Cosmos, Substrate,
+    /// Cardano, Hedera), or ID (Tron).
+    pub id: String,
+    /// Blockhash the transaction expires with (Solana), the block its era
+    /// starts at (Substrate), the slot it expires after (Cardano), or the
+    /// Unix time in milliseconds it expires at (Tron), so a resumed wait
+    /// can tell a dropped transaction from a slow one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blockhash: Option<String>,
+}
+
+impl fmt::Display for PendingTransaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} transaction {} ({}) is still pending",
+            self.chain.as_ref(),
+            self.id,
+            self.operation
+        )
+    }
+}
@@ -0,0 +1,78 @@
+//! Named configuration profiles, selected with `--profile`.
+//!
+//! Each profile bundles what differs between environments, so switching
+//! between them no longer means copying templates over
+//! `escrow_params.json`:
+//!
+//! ```json
+//! {
+//!   "prod-solana": {
+//!     "chain_config": {
+//!       "chain": "solana",
+//!       "rpc_url": "${PROD_SOLANA_RPC_URL}",
+//!       "sender_private_id": "${PROD_SOLANA_SENDER_KEYPAIR_PATH}",
+//!       "agent_id": "${PROD_SOLANA_PROGRAM_ID}"
+//!     },
+//!     "params": "solana/escrow_params.json",
+//!     "recipient": "${PROD_SOLANA_RECIPIENT_KEYPAIR_PATH}"
+//!   }
+//! }
+//! ```
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use anyhow::anyhow;
+use serde::Deserialize;
+use zescrow_client::Recipient;
+use zescrow_core::interface::{load_escrow_data, ChainConfig};
+
+/// Default path of the profiles file.
+pub const PROFILES_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/profiles.json");
+
+/// Configuration of one environment.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Profile {
+    /// Chain, endpoints, sender key, and program or contract address,
+    /// replacing `chain_config` in the escrow parameters.
+    pub chain_config: ChainConfig,
+    /// Escrow parameters template, relative to the profiles file; defaults
+    /// to `escrow_params.json`.
+    #[serde(default)]
+    pub params: Option<PathBuf>,
+    /// Recipient key used when no `--recipient` is given: a Solana keypair
+    /// file or a 0x-prefixed Ethereum private key.
+    #[serde(default)]
+    recipient: Option<String>,
+}
+
+impl Profile {
+    /// Loads profile `name` from the profiles file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or has no such profile.
+    pub fn load(path: &Path, name: &str) -> anyhow::Result<Self> {
+        let mut profiles: BTreeMap<String, Profile> = load_escrow_data(path)?;
+        let names = profiles.keys().cloned().collect::<Vec<_>>().join(", ");
+        let mut profile = profiles
+            .remove(name)
+            .ok_or_else(|| anyhow!("no profile `{name}` in {path:?} (available: {names})"))?;
+        if let Some(params) = &mut profile.params {
+            if params.is_relative() {
+                *params = path.parent().unwrap_or(Path::new(".")).join(&*params);
+            }
+        }
+        Ok(profile)
+    }
+
+    /// Returns the profile's recipient key, if it has one.
+    pub fn recipient(&self) -> anyhow::Result<Option<Recipient>> {
+        Ok(self
+            .recipient
+            .as_deref()
+            .map(str::parse::<Recipient>)
+            .transpose()?)
+    }
+}
@@ -0,0 +1,706 @@
+//! Proof artifacts written by `prove` and read by `finish --proof`, and the
+//! `proof` commands inspecting them.
+//!
+//! Proving takes minutes of CPU, so it can run on a separate machine or CI
+//! job, and only `proof_data.json` be shipped to where the finish
+//! transaction is signed.
+
+use std::fmt;
+use std::path::{Path, PathBuf};
+#[cfg(feature = "prover")]
+use std::time::Duration;
+
+use anyhow::{bail, Context};
+use clap::{value_parser, Args, Subcommand};
+use serde::Serialize;
+use tracing::{info, warn};
+#[cfg(feature = "prover")]
+use zescrow_client::prover;
+use zescrow_client::ClientError;
+#[cfg(feature = "prover")]
+use zescrow_core::interface::{ChainConfig, EscrowBinding, Groth16Points};
+use zescrow_core::interface::{Freshness, Journal, ProofArtifact, PROOF_DATA_PATH};
+#[cfg(feature = "prover")]
+use zescrow_core::Chain;
+use zescrow_core::{Condition, EscrowMetadata};
+
+use crate::output::{print_json, OutputFormat};
+use crate::ClientOpts;
+
+/// Where proofs are generated.
+#[derive(Args, Debug, Clone)]
+#[cfg_attr(not(feature = "prover"), allow(dead_code))]
+pub struct ProverArgs {
+    /// Prove on the Bonsai proving service instead of locally, with the API
+    /// URL and key from `BONSAI_API_URL` and `BONSAI_API_KEY`
+    #[arg(long, global = true, env = "ZESCROW_REMOTE_PROVER")]
+    remote_prover: bool,
+
+    /// Seconds to wait for a remote proof before giving up
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_PROVER_TIMEOUT",
+        value_name = "SECS",
+        default_value_t = 1800
+    )]
+    prover_timeout: u64,
+
+    /// Segment size for local proving, as a power of two of cycles; lower
+    /// it to prove with less memory [default: 20]
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_SEGMENT_PO2",
+        value_name = "PO2",
+        value_parser = value_parser!(u32).range(13..=24)
+    )]
+    segment_po2: Option<u32>,
+
+    /// Worker threads for local proving [default: one per core]
+    #[arg(long, global = true, env = "ZESCROW_PROVER_THREADS", value_name = "N")]
+    prover_threads: Option<usize>,
+
+    /// Largest keccak precompile batch for local proving, as a power of
+    /// two of cycles
+    #[arg(long, global = true, env = "ZESCROW_KECCAK_PO2", value_name = "PO2")]
+    keccak_po2: Option<u32>,
+
+    /// Hardware to require for local proving; the GPU the build's `cuda`
+    /// or `metal` feature enables, or the CPU [default: the build's]
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_PROVER_ACCELERATOR",
+        value_parser = ["cpu", "cuda", "metal"]
+    )]
+    accelerator: Option<String>,
+
+    /// Kind of receipt local proofs are produced as, unless compressed to
+    /// Groth16 anyway; succinct receipts take longer to prove but are far
+    /// smaller to store [default: composite]
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_RECEIPT_KIND",
+        value_parser = ["composite", "succinct", "groth16"]
+    )]
+    receipt_kind: Option<String>,
+
+    /// Commit which subconditions are satisfied to the proof journal, for
+    /// audit trails and off-chain policies; not accepted by the Ethereum
+    /// escrow contract
+    #[arg(long, global = true, env = "ZESCROW_REPORT_SATISFIED")]
+    report_satisfied: bool,
+
+    /// Blocks (Ethereum) or slots (Solana) from now after which proofs
+    /// expire and are rejected [default: never]
+    #[arg(long, global = true, env = "ZESCROW_PROOF_TTL", value_name = "BLOCKS")]
+    proof_ttl: Option<u64>,
+
+    /// Attest a recent block (Ethereum) or slot hash (Solana) in proofs,
+    /// which must then be used within 256 blocks on Ethereum
+    #[arg(long, global = true, env = "ZESCROW_ATTEST_CHAIN_STATE")]
+    attest_chain_state: bool,
+
+    /// Skip proving and produce fake proofs, accepted only by the
+    /// dev-mode verifier; for CI against a local node (requires the
+    /// `dev-mode` feature)
+    #[arg(
+        long,
+        global = true,
+        env = "ZESCROW_DEV_MODE",
+        conflicts_with = "remote_prover"
+    )]
+    dev_mode: bool,
+
+    /// Prove or submit proofs from another guest program than the one
+    /// pinned in the escrow's metadata, e.g. after an upgrade
+    #[arg(long, global = true, env = "ZESCROW_ALLOW_IMAGE_MISMATCH")]
+    allow_image_mismatch: bool,
+
+    /// Solana keypair file whose key signs proof data, so recipients can
+    /// verify who generated a proof and when
+    #[arg(long, global = true, env = "ZESCROW_PROVER_KEY", value_name = "FILE")]
+    prover_key: Option<PathBuf>,
+
+    /// Only accept proof data signed by this prover public key (base58 or
+    /// hex); may be repeated
+    #[arg(
+        long = "trusted-prover",
+        global = true,
+        value_name = "PUBKEY",
+        value_parser = parse_prover_key
+    )]
+    trusted_provers: Vec<[u8; 32]>,
+}
+
+/// Parses a prover public key, base58 as Solana prints them or hex.
+fn parse_prover_key(key: &str) -> Result<[u8; 32], String> {
+    if let Ok(pubkey) = key.parse::<solana_sdk::pubkey::Pubkey>() {
+        return Ok(pubkey.to_bytes());
+    }
+    hex::decode(key.trim_start_matches("0x"))
+        .ok()
+        .and_then(|bytes| bytes.try_into().ok())
+        .ok_or_else(|| format!("{key} is not a base58 or hex Ed25519 public key"))
+}
+
+impl ProverArgs {
+    /// Checks that `image_id` (hex), if known, is the guest pinned in
+    /// `metadata`, if any, warning instead with `--allow-image-mismatch`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ClientError::GuestMismatch`] if another guest is pinned.
+    pub fn check_guest(
+        &self,
+        metadata: &EscrowMetadata,
+        image_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let (Some(pin), Some(image_id)) = (&metadata.guest, image_id) else {
+            return Ok(());
+        };
+        if pin.matches(image_id) {
+            return Ok(());
+        }
+        if self.allow_image_mismatch {
+            warn!(pinned = %pin.image_id, image_id, "Guest image ID mismatch allowed");
+            return Ok(());
+        }
+        Err(ClientError::GuestMismatch {
+            pinned: pin.image_id.clone(),
+            version: pin.version.clone(),
+            image_id: image_id.into(),
+        }
+        .into())
+    }
+
+    /// Verifies the provenance signature of `artifact`, if signed, and
+    /// that it was signed by a `--trusted-prover`, if any are given.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signature does not match the proof data,
+    /// or [`ClientError::UntrustedProver`] if the proof data is unsigned
+    /// or signed by another prover while provers are trusted.
+    pub fn check_provenance(&self, artifact: &ProofArtifact) -> anyhow::Result<()> {
+        let provenance = artifact.verify_provenance()?;
+        if let Some(provenance) = provenance {
+            info!(
+                signer = %provenance.signer,
+                signed_at = provenance.signed_at,
+                host = %provenance.host,
+                "Proof data signature verified"
+            );
+        }
+        if self.trusted_provers.is_empty() {
+            return Ok(());
+        }
+        let trusted = provenance.is_some_and(|provenance| {
+            hex::decode(&provenance.signer)
+                .is_ok_and(|signer| self.trusted_provers.iter().any(|key| key[..] == signer[..]))
+        });
+        if trusted {
+            return Ok(());
+        }
+        Err(ClientError::UntrustedProver {
+            signer: provenance.map(|provenance| provenance.signer.clone()),
+        }
+        .into())
+    }
+
+    /// Returns the secret of the `--prover-key` proof data is signed with,
+    /// if given.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the keypair file cannot be read.
+    pub fn signing_key(&self) -> anyhow::Result<Option<[u8; 32]>> {
+        let Some(path) = &self.prover_key else {
+            return Ok(None);
+        };
+        let keypair = solana_sdk::signature::read_keypair_file(path)
+            .map_err(|e| anyhow::anyhow!("reading prover key {}: {e}", path.display()))?;
+        let mut secret = [0; 32];
+        secret.copy_from_slice(&keypair.to_bytes()[..32]);
+        Ok(Some(secret))
+    }
+}
+
+#[cfg(feature = "prover")]
+impl ProverArgs {
+    /// Returns the prover configuration selected to prove escrows on
+    /// `chain`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if proving remotely without the Bonsai API URL and
+    /// key set, in dev mode against a node other than a local one, with an
+    /// unknown accelerator or receipt kind, or reporting the satisfied
+    /// subconditions of an Ethereum escrow.
+    pub fn config(&self, chain: &ChainConfig) -> anyhow::Result<prover::ProverBackend> {
+        if self.dev_mode {
+            check_dev_mode(chain)?;
+        }
+        if self.report_satisfied && matches!(chain.chain, Chain::Ethereum) {
+            bail!("--report-satisfied proofs are not accepted by the Ethereum escrow contract");
+        }
+        if !self.remote_prover {
+            return Ok(prover::ProverBackend::Local(prover::ProvingOptions {
+                segment_po2: self.segment_po2,
+                threads: self.prover_threads,
+                keccak_po2: self.keccak_po2,
+                dev_mode: self.dev_mode,
+                accelerator: self.accelerator.as_deref().map(str::parse).transpose()?,
+                receipt_kind: self
+                    .receipt_kind
+                    .as_deref()
+                    .map(str::parse)
+                    .transpose()?
+                    .unwrap_or_default(),
+                report_satisfied: self.report_satisfied,
+            }));
+        }
+        let remote = prover::RemoteConfig::from_env()?
+            .timeout(Duration::from_secs(self.prover_timeout))
+            .report_satisfied(self.report_satisfied);
+        Ok(prover::ProverBackend::Remote(remote))
+    }
+}
+
+/// Refuses dev mode unless `chain` is served by a local node, so fake
+/// proofs can never target a live network.
+#[cfg(feature = "prover")]
+fn check_dev_mode(chain: &ChainConfig) -> anyhow::Result<()> {
+    let url = url::Url::parse(&chain.rpc_url)
+        .with_context(|| format!("invalid RPC URL {}", chain.rpc_url))?;
+    let local = match url.host() {
+        Some(url::Host::Domain(domain)) => domain == "localhost",
+        Some(url::Host::Ipv4(ip)) => ip.is_loopback(),
+        Some(url::Host::Ipv6(ip)) => ip.is_loopback(),
+        None => false,
+    };
+    if !local {
+        bail!(
+            "dev mode produces fake proofs and is only allowed against a local node, not {}",
+            chain.rpc_url
+        );
+    }
+    Ok(())
+}
+
+/// Returns the binding to prove the escrow in `metadata` under, expiring
+/// `--proof-ttl` blocks or slots from now if set, and attesting recent
+/// chain state with `--attest-chain-state`.
+#[cfg(feature = "prover")]
+pub async fn binding(
+    opts: &ClientOpts,
+    metadata: &EscrowMetadata,
+) -> anyhow::Result<EscrowBinding> {
+    let mut binding = metadata.binding()?;
+    if opts.prover.proof_ttl.is_none() && !opts.prover.attest_chain_state {
+        return Ok(binding);
+    }
+    let client = opts.builder(&metadata.params.chain_config).build().await?;
+    if let Some(ttl) = opts.prover.proof_ttl {
+        let height = client.get_escrow_state(metadata).await?.current_height;
+        binding.freshness = Freshness::until(height.saturating_add(ttl));
+    }
+    if opts.prover.attest_chain_state {
+        binding.chain_state = client.chain_state().await?;
+    }
+    Ok(binding)
+}
+
+/// Hex-encodes the proof points of a Groth16 proof.
+#[cfg(feature = "prover")]
+fn groth16_points(proof: &prover::Groth16Proof) -> Groth16Points {
+    let hex = |element: &[u8; 32]| format!("0x{}", hex::encode(element));
+    Groth16Points {
+        pi_a: proof.pi_a.each_ref().map(hex),
+        pi_b: proof.pi_b.each_ref().map(|pair| pair.each_ref().map(hex)),
+        pi_c: proof.pi_c.each_ref().map(hex),
+    }
+}
+
+/// Proves the escrow in `metadata` like `finish` would, returning its proof
+/// artifact and writing the full receipt to `receipt` if given.
+///
+/// Ethereum escrows are always proven with a Groth16 seal; Solana escrows
+/// only with `groth16`, for verifiers outside the escrow program.
+#[cfg(feature = "prover")]
+pub async fn prove(
+    opts: &ClientOpts,
+    metadata: &EscrowMetadata,
+    condition: Condition,
+    receipt: Option<&Path>,
+    groth16: bool,
+) -> anyhow::Result<ProofArtifact> {
+    let config = opts.prover.config(&metadata.params.chain_config)?;
+    opts.prover
+        .check_guest(metadata, Some(&prover::guest_image_id()))?;
+    let ethereum = matches!(metadata.params.chain_config.chain, Chain::Ethereum);
+    let groth16 = groth16 || ethereum;
+    let binding = binding(opts, metadata).await?;
+    let (escrow_metadata, fulfillment) = (metadata.clone(), condition.clone());
+    // Proving is CPU-bound and takes minutes; keep it off the runtime.
+    let proven = tokio::task::spawn_blocking(move || {
+        prover::prove_escrow_receipt_with(
+            &escrow_metadata,
+            Some(&fulfillment),
+            binding,
+            groth16,
+            &config,
+        )
+    })
+    .await?
+    .map_err(|e| ClientError::from_proving(e, &condition))?;
+    if let Some(path) = receipt {
+        prover::save_receipt(path, &proven)?;
+    }
+    let seal = ethereum
+        .then(|| prover::ethereum_seal(&proven))
+        .transpose()?;
+    // Fake receipts have no proof points.
+    let points = (groth16 && !opts.prover.dev_mode)
+        .then(|| prover::groth16_proof(&proven))
+        .transpose()?;
+    let journal = &proven.journal.bytes;
+    let mut artifact = ProofArtifact {
+        receipt: receipt.map(Path::to_path_buf),
+        image_id: Some(prover::guest_image_id()),
+        journal: Some(format!("0x{}", hex::encode(journal))),
+        journal_digest: Some(format!("0x{}", prover::journal_digest(journal))),
+        groth16: points.as_ref().map(groth16_points),
+        guest_version: Some(prover::GUEST_VERSION.into()),
+        ..ProofArtifact::new(metadata, seal.as_deref())
+    };
+    if let Some(key) = opts.prover.signing_key()? {
+        artifact.sign(&key, prover::host_info());
+    }
+    Ok(artifact)
+}
+
+#[cfg(not(feature = "prover"))]
+pub async fn prove(
+    _opts: &ClientOpts,
+    _metadata: &EscrowMetadata,
+    _condition: Condition,
+    _receipt: Option<&Path>,
+    _groth16: bool,
+) -> anyhow::Result<ProofArtifact> {
+    bail!(
+        "proving requires the 'prover' feature; rebuild with `--features prover` to enable \
+         ZK proof generation"
+    )
+}
+
+#[derive(Subcommand, Debug)]
+pub enum ProofCmd {
+    /// Print what a proof data file (from `prove`) or receipt (from `prove
+    /// --receipt`) proves: its image ID, decoded journal, seal type, and
+    /// whether it verifies against this build's guest program
+    Inspect {
+        /// Proof data or receipt file
+        #[arg(default_value = PROOF_DATA_PATH, value_parser = value_parser!(PathBuf))]
+        path: PathBuf,
+    },
+}
+
+/// Runs a `proof` command.
+pub fn run(command: ProofCmd, output: OutputFormat) -> anyhow::Result<()> {
+    match command {
+        ProofCmd::Inspect { path } => {
+            let inspection = inspect(&path)?;
+            match output {
+                OutputFormat::Text => println!("{inspection}"),
+                OutputFormat::Json => print_json(&inspection)?,
+            }
+            if inspection.verified == Some(false) {
+                bail!("proof does not verify");
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Outcome of `verify-proof`.
+#[cfg(feature = "prover")]
+#[derive(Debug, Serialize)]
+struct Verification {
+    /// Image ID the proof verified against.
+    image_id: String,
+    /// What was verified: a receipt, an Ethereum seal, or Groth16 points.
+    proof: &'static str,
+    /// Execution result and escrow binding committed to the journal.
+    journal: String,
+    /// Whether the journal reports fulfilled conditions.
+    conditions_met: bool,
+    /// Whether the proof is bound to the escrow given with `--metadata`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    escrow_matches: Option<bool>,
+}
+
+#[cfg(feature = "prover")]
+impl fmt::Display for Verification {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let yes_no = |value: bool| if value { "yes" } else { "NO" };
+        writeln!(
+            f,
+            "Verified:       {} against image ID {}",
+            self.proof, self.image_id
+        )?;
+        writeln!(f, "Journal:        {}", self.journal)?;
+        write!(f, "Conditions met: {}", yes_no(self.conditions_met))?;
+        if let Some(matches) = self.escrow_matches {
+            write!(f, "\nEscrow matches: {}", yes_no(matches))?;
+        }
+        Ok(())
+    }
+}
+
+/// Verifies the proof at `path` against `image_id` (hex), or this build's
+/// guest program, and that it proves fulfilled conditions of the escrow in
+/// `metadata` if given.
+///
+/// # Errors
+///
+/// Returns an error if the proof does not verify, does not prove fulfilled
+/// conditions, or is bound to another escrow.
+#[cfg(feature = "prover")]
+pub fn verify(
+    path: &Path,
+    image_id: Option<&str>,
+    metadata: Option<&Path>,
+    output: OutputFormat,
+) -> anyhow::Result<()> {
+    use zescrow_core::interface::load_escrow_data;
+    use zescrow_core::ExecutionState;
+
+    let verified = match image_id {
+        Some(image_id) => {
+            let bytes: [u8; 32] = hex::decode(image_id.trim_start_matches("0x"))
+                .ok()
+                .and_then(|bytes| bytes.try_into().ok())
+                .context("image ID must be 32 bytes of hex")?;
+            prover::verify_artifact_against(path, prover::Digest::from(bytes))?
+        }
+        None => prover::verify_artifact(path)?,
+    };
+    let escrow_matches = metadata
+        .map(|path| -> anyhow::Result<bool> {
+            let metadata: EscrowMetadata = load_escrow_data(path)
+                .with_context(|| format!("loading escrow metadata {}", path.display()))?;
+            Ok(verified.journal.binding.same_escrow(&metadata.binding()?))
+        })
+        .transpose()?;
+    let verification = Verification {
+        image_id: verified.image_id,
+        proof: verified.kind,
+        journal: describe(&verified.journal),
+        conditions_met: verified.journal.result.state() == Some(ExecutionState::ConditionsMet),
+        escrow_matches,
+    };
+    match output {
+        OutputFormat::Text => println!("{verification}"),
+        OutputFormat::Json => print_json(&verification)?,
+    }
+    if !verification.conditions_met {
+        bail!("proof does not prove fulfilled conditions");
+    }
+    if verification.escrow_matches == Some(false) {
+        bail!("proof is bound to another escrow");
+    }
+    Ok(())
+}
+
+#[cfg(not(feature = "prover"))]
+pub fn verify(
+    _path: &Path,
+    _image_id: Option<&str>,
+    _metadata: Option<&Path>,
+    _output: OutputFormat,
+) -> anyhow::Result<()> {
+    bail!("verifying proofs requires the 'prover' feature; rebuild with `--features prover`")
+}
+
+/// What a proof file proves.
+#[derive(Debug, Serialize)]
+struct Inspection {
+    /// `proof data` or `receipt`.
+    format: &'static str,
+    /// Image ID of the guest program proven.
+    image_id: Option<String>,
+    /// Image ID of this build's guest program (`prover` feature only).
+    expected_image_id: Option<String>,
+    /// Kind of proof the seal holds.
+    seal: String,
+    /// Execution result decoded from the journal, or why it could not be.
+    journal: Option<String>,
+    /// Whether the proof verifies against `expected_image_id`, if it could
+    /// be checked.
+    verified: Option<bool>,
+    /// Who signed the proof data, when, and on what host, if signed, or
+    /// why the signature is invalid.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    provenance: Option<String>,
+    /// Why the proof does not verify, or could not be checked.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+impl fmt::Display for Inspection {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let or_unknown = |value: &Option<String>| value.clone().unwrap_or_else(|| "?".into());
+        writeln!(f, "Format:            {}", self.format)?;
+        writeln!(f, "Image ID:          {}", or_unknown(&self.image_id))?;
+        writeln!(
+            f,
+            "Expected image ID: {}",
+            or_unknown(&self.expected_image_id)
+        )?;
+        writeln!(f, "Seal:              {}", self.seal)?;
+        writeln!(f, "Journal:           {}", or_unknown(&self.journal))?;
+        if let Some(provenance) = &self.provenance {
+            writeln!(f, "Signed:            {provenance}")?;
+        }
+        let verified = match self.verified {
+            Some(true) => "yes",
+            Some(false) => "NO",
+            None => "not checked",
+        };
+        write!(f, "Verified:          {verified}")?;
+        if let Some(error) = &self.error {
+            write!(f, " ({error})")?;
+        }
+        Ok(())
+    }
+}
+
+/// Inspects a proof data file, or a receipt file if it is not JSON.
+fn inspect(path: &Path) -> anyhow::Result<Inspection> {
+    let bytes = std::fs::read(path).with_context(|| format!("reading {}", path.display()))?;
+    if bytes.trim_ascii_start().starts_with(b"{") {
+        let data: ProofArtifact = serde_json::from_slice(&bytes)
+            .with_context(|| format!("parsing proof data {}", path.display()))?;
+        inspect_proof_data(&data)
+    } else {
+        inspect_receipt(path)
+    }
+}
+
+fn inspect_proof_data(data: &ProofArtifact) -> anyhow::Result<Inspection> {
+    let seal = data.seal()?;
+    let journal = data.journal_bytes()?;
+    let mut inspection = Inspection {
+        format: "proof data",
+        image_id: data.image_id.clone(),
+        expected_image_id: expected_image_id(),
+        seal: match (&seal, &data.groth16) {
+            (Some(seal), _) => format!("Groth16, encoded for Ethereum ({} bytes)", seal.len()),
+            (None, Some(_)) => "Groth16 proof points".into(),
+            (None, None) => "none (Solana escrows are proven off-chain)".into(),
+        },
+        journal: journal.as_deref().map(describe_journal),
+        verified: None,
+        provenance: match data.verify_provenance() {
+            Ok(provenance) => provenance.map(|provenance| {
+                format!(
+                    "by {} at {} on {}",
+                    provenance.signer, provenance.signed_at, provenance.host
+                )
+            }),
+            Err(e) => Some(format!("INVALID ({e:#})")),
+        },
+        error: None,
+    };
+    if let (Some(seal), Some(journal)) = (seal, journal) {
+        verify_seal(&mut inspection, &seal, &journal);
+    } else if data.journal.is_none() {
+        inspection.error = Some("no journal recorded; re-run `prove`".into());
+    }
+    Ok(inspection)
+}
+
+#[cfg(feature = "prover")]
+fn verify_seal(inspection: &mut Inspection, seal: &[u8], journal: &[u8]) {
+    let result = prover::verify_ethereum_seal(seal, journal);
+    inspection.verified = Some(result.is_ok());
+    inspection.error = result.err().map(|e| format!("{e:#}"));
+}
+
+#[cfg(not(feature = "prover"))]
+fn verify_seal(inspection: &mut Inspection, _seal: &[u8], _journal: &[u8]) {
+    inspection.error = Some("verifying requires the `prover` feature".into());
+}
+
+#[cfg(feature = "prover")]
+fn inspect_receipt(path: &Path) -> anyhow::Result<Inspection> {
+    let receipt = prover::load_receipt(path)?;
+    let inspection = prover::inspect_receipt(&receipt);
+    Ok(Inspection {
+        format: "receipt",
+        image_id: inspection.image_id,
+        expected_image_id: expected_image_id(),
+        seal: inspection.kind.into(),
+        journal: Some(match inspection.journal {
+            Ok(journal) => describe(&journal),
+            Err(e) => format!("undecodable: {e:#}"),
+        }),
+        verified: Some(inspection.verification.is_ok()),
+        provenance: None,
+        error: inspection.verification.err().map(|e| format!("{e:#}")),
+    })
+}
+
+#[cfg(not(feature = "prover"))]
+fn inspect_receipt(path: &Path) -> anyhow::Result<Inspection> {
+    bail!(
+        "{} is not proof data, and inspecting receipts requires the `prover` feature",
+        path.display()
+    )
+}
+
+/// Image ID of this build's guest program.
+#[cfg(feature = "prover")]
+fn expected_image_id() -> Option<String> {
+    Some(prover::guest_image_id())
+}
+
+#[cfg(not(feature = "prover"))]
+fn expected_image_id() -> Option<String> {
+    None
+}
+
+/// Describes the execution result and escrow binding committed to
+/// `journal`.
+fn describe_journal(journal: &[u8]) -> String {
+    match Journal::decode(journal) {
+        Ok(journal) => describe(&journal),
+        Err(e) => format!("undecodable: {e}"),
+    }
+}
+
+fn describe(journal: &Journal) -> String {
+    let binding = &journal.binding;
+    let mut description = format!(
+        "{:?} for escrow 0x{}",
+        journal.result,
+        hex::encode(binding.fingerprint)
+    );
+    if !binding.chain_state.is_none() {
+        description += &format!(
+            ", attests {} at height {}",
+            hex::encode(binding.chain_state.hash),
+            binding.chain_state.height
+        );
+    }
+    if journal.condition != [0; 32] {
+        description += &format!(", condition 0x{}", hex::encode(journal.condition));
+    }
+    if binding.freshness != Freshness::NEVER {
+        description += &format!(", expires after {}", binding.freshness.expires_at);
+    }
+    description
+}
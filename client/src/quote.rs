@@ -0,0 +1,286 @@
+//! Lifecycle cost quotes comparable across chains.
+//!
+//! Each chain prices its [`LifecycleEstimate`] in its own fee unit (wei,
+//! lamports, ...). A [`Quote`] converts it to a common currency, e.g. USD,
+//! with a price per whole fee token from the configured [`PriceFeeds`], so
+//! the candidate chains for an escrow can be compared and the
+//! [`cheapest`] recommended:
+//!
+//! ```json
+//! {
+//!   "currency": "usd",
+//!   "tokens": {
+//!     "ethereum": {
+//!       "source": "http",
+//!       "url": "${ETH_PRICE_URL}",
+//!       "pointer": "/ethereum/usd"
+//!     },
+//!     "solana": { "source": "fixed", "price": 150.0 },
+//!     "astar": { "source": "fixed", "price": 0.05, "decimals": 18 }
+//!   }
+//! }
+//! ```
+//!
+//! Tokens are looked up by the candidate's label (e.g. its profile name),
+//! then by chain name, so chains sharing an agent but not a fee token,
+//! like two Cosmos chains, can be priced apart.
+//!
+//! A quote's [`total`](Quote::total) is the cost of creating the escrow and
+//! of the costlier way of settling it: finishing, including on-chain proof
+//! verification where the chain verifies proofs, or refunding. Deposits,
+//! such as Solana rent, are returned at settlement, so they are quoted
+//! apart from the total.
+
+use std::collections::BTreeMap;
+
+use num_traits::ToPrimitive;
+use serde::{Deserialize, Serialize};
+use tracing::debug;
+use url::Url;
+use zescrow_core::{BigNumber, Chain, EscrowParams};
+
+use crate::error::ClientError;
+use crate::estimate::LifecycleEstimate;
+use crate::{Result, ZescrowClient};
+
+/// Source of the price of a fee token.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(tag = "source", rename_all = "snake_case")]
+pub enum PriceFeed {
+    /// A price set in the configuration.
+    Fixed {
+        /// Price of one whole token, in the quote currency.
+        price: f64,
+    },
+    /// A price read from a JSON API, e.g. CoinGecko's `simple/price`
+    /// endpoint.
+    Http {
+        /// Endpoint returning the price.
+        url: Url,
+        /// JSON pointer to the price in the response, a number or a
+        /// decimal string.
+        pointer: String,
+    },
+}
+
+/// A fee token and where its price comes from.
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeeToken {
+    /// Decimals of the token's fee unit, defaulting to the chain's usual
+    /// ones: 18 on Ethereum, 9 on Solana, 6 on Cosmos, Cardano, and Tron,
+    /// and 8 on Hedera. Substrate chains differ too much for a default.
+    #[serde(default)]
+    pub decimals: Option<u8>,
+    /// Source of the price.
+    #[serde(flatten)]
+    pub feed: PriceFeed,
+}
+
+/// Prices of the fee tokens of candidate chains, in one currency.
+#[derive(Debug, Clone, Deserialize)]
+pub struct PriceFeeds {
+    /// Currency quotes are made in, e.g. `usd`.
+    pub currency: String,
+    /// Fee tokens, by candidate label or chain name.
+    pub tokens: BTreeMap<String, FeeToken>,
+}
+
+impl PriceFeeds {
+    /// Fetches the price of one whole fee token of the `label` candidate on
+    /// `chain`, and the decimals of its fee unit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no token is configured for the candidate or its
+    /// chain, its decimals are unknown, or its feed gives no price.
+    pub async fn price(&self, label: &str, chain: Chain) -> Result<(f64, u8)> {
+        let token = self
+            .tokens
+            .get(label)
+            .or_else(|| self.tokens.get(chain.as_ref()))
+            .ok_or_else(|| {
+                ClientError::Quote(format!(
+                    "no price feed for `{label}` or `{}`",
+                    chain.as_ref()
+                ))
+            })?;
+        let decimals = token
+            .decimals
+            .or_else(|| default_decimals(chain))
+            .ok_or_else(|| {
+                ClientError::Quote(format!("the fee token decimals of `{label}` are not set"))
+            })?;
+        let price = match &token.feed {
+            PriceFeed::Fixed { price } => *price,
+            PriceFeed::Http { url, pointer } => fetch_price(url, pointer).await?,
+        };
+        if !price.is_finite() || price < 0.0 {
+            return Err(ClientError::Quote(format!(
+                "invalid price {price} for `{label}`"
+            )));
+        }
+        debug!(label, price, decimals, currency = %self.currency, "Priced fee token");
+        Ok((price, decimals))
+    }
+}
+
+/// Lifecycle cost of an escrow on one candidate chain, in the quote
+/// currency.
+#[derive(Debug, Clone, Serialize)]
+pub struct Quote {
+    /// Candidate the quote is for, e.g. its profile name.
+    pub label: String,
+    /// Chain of the candidate.
+    pub chain: Chain,
+    /// Price of one whole fee token.
+    pub price: f64,
+    /// Costs in the chain's fee unit.
+    pub estimate: LifecycleEstimate,
+    /// Cost of creating the escrow, if known.
+    pub create: Option<f64>,
+    /// Cost of finishing the escrow, including on-chain proof
+    /// verification, if known.
+    pub finish: Option<f64>,
+    /// Cost of refunding the escrow, if known.
+    pub cancel: Option<f64>,
+    /// Deposit locked until settlement, if any.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deposit: Option<f64>,
+    /// Cost of creating and settling the escrow either way, if every cost
+    /// is known.
+    pub total: Option<f64>,
+    /// Whether creating the escrow simulates successfully and every cost
+    /// is known.
+    pub viable: bool,
+}
+
+impl Quote {
+    /// Prices `estimate` at `price` per whole fee token of `decimals`.
+    pub fn new(
+        label: impl Into<String>,
+        chain: Chain,
+        estimate: LifecycleEstimate,
+        price: f64,
+        decimals: u8,
+    ) -> Self {
+        let scale = 10f64.powi(decimals.into());
+        let convert = |amount: &Option<BigNumber>| {
+            amount
+                .as_ref()
+                .and_then(|amount| amount.0.to_f64())
+                .map(|amount| amount / scale * price)
+        };
+        let (create, finish, cancel) = (
+            convert(&estimate.create.fee),
+            convert(&estimate.finish.fee),
+            convert(&estimate.cancel.fee),
+        );
+        let total = create
+            .zip(finish)
+            .zip(cancel)
+            .map(|((create, finish), cancel)| create + finish.max(cancel));
+        Self {
+            label: label.into(),
+            chain,
+            price,
+            deposit: convert(&estimate.create.deposit),
+            viable: total.is_some() && estimate.create.units.is_some(),
+            estimate,
+            create,
+            finish,
+            cancel,
+            total,
+        }
+    }
+}
+
+impl std::fmt::Display for Quote {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let amount =
+            |amount: Option<f64>| amount.map_or("?".into(), |amount| format!("{amount:.4}"));
+        write!(
+            f,
+            "{:<16} {:<10} {:>12} {:>12} {:>12} {:>12}",
+            self.label,
+            self.chain.as_ref(),
+            amount(self.create),
+            amount(self.finish),
+            amount(self.cancel),
+            amount(self.total),
+        )?;
+        if let Some(deposit) = self.deposit {
+            write!(f, "  deposit {deposit:.4} (refunded)")?;
+        }
+        if !self.viable {
+            write!(f, "  not viable")?;
+        }
+        Ok(())
+    }
+}
+
+/// Quotes the lifecycle of an escrow with `params` on `client`'s chain.
+///
+/// # Arguments
+///
+/// * `label` - Candidate the quote is for, looked up in `feeds` before the
+///   chain name
+/// * `client` - Client on the candidate chain
+/// * `params` - Escrow terms on the candidate chain
+/// * `feeds` - Prices of the candidates' fee tokens
+///
+/// # Errors
+///
+/// Returns an error if the escrow cannot be estimated or its fee token
+/// priced.
+pub async fn quote(
+    label: &str,
+    client: &ZescrowClient,
+    params: &EscrowParams,
+    feeds: &PriceFeeds,
+) -> Result<Quote> {
+    let chain = params.chain_config.chain;
+    let estimate = client.estimate(params).await?;
+    let (price, decimals) = feeds.price(label, chain).await?;
+    Ok(Quote::new(label, chain, estimate, price, decimals))
+}
+
+/// Returns the viable quote with the lowest total, if any.
+pub fn cheapest(quotes: &[Quote]) -> Option<&Quote> {
+    quotes.iter().filter(|quote| quote.viable).min_by(|a, b| {
+        a.total
+            .unwrap_or_default()
+            .total_cmp(&b.total.unwrap_or_default())
+    })
+}
+
+/// Usual decimals of the fee unit on `chain`, as reported by its agent:
+/// wei, lamports, the micro-denominations of Cosmos chains, lovelace, sun,
+/// and tinybars.
+fn default_decimals(chain: Chain) -> Option<u8> {
+    match chain {
+        Chain::Ethereum => Some(18),
+        Chain::Solana => Some(9),
+        Chain::Cosmos | Chain::Cardano | Chain::Tron => Some(6),
+        Chain::Hedera => Some(8),
+        Chain::Substrate => None,
+    }
+}
+
+/// Reads the price at `pointer` in the JSON that `url` returns.
+async fn fetch_price(url: &Url, pointer: &str) -> Result<f64> {
+    let error = |e: String| ClientError::Quote(format!("price feed {url}: {e}"));
+    let body: serde_json::Value = reqwest::get(url.clone())
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| error(e.to_string()))?
+        .json()
+        .await
+        .map_err(|e| error(e.to_string()))?;
+    let value = body
+        .pointer(pointer)
+        .ok_or_else(|| error(format!("no value at `{pointer}`")))?;
+    value
+        .as_f64()
+        .or_else(|| value.as_str().and_then(|price| price.parse().ok()))
+        .ok_or_else(|| error(format!("`{pointer}` is not a price: {value}")))
+}
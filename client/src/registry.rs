@@ -0,0 +1,119 @@
+//! Registry of agents for chains without built-in support.
+//!
+//! [`ZescrowClientBuilder`](crate::ZescrowClientBuilder) builds an
+//! [`EthereumAgent`](crate::EthereumAgent),
+//! [`SolanaAgent`](crate::SolanaAgent),
+//! [`CosmosAgent`](crate::CosmosAgent),
+//! [`SubstrateAgent`](crate::SubstrateAgent),
+//! [`CardanoAgent`](crate::CardanoAgent),
+//! [`TronAgent`](crate::TronAgent), or
+//! [`HederaAgent`](crate::HederaAgent) for the built-in chains. Third
+//! parties add a chain by registering an [`AgentFactory`] under an
+//! identifier and setting `custom_chain` to it in the [`ChainConfig`]; a
+//! factory registered as `ethereum`, `solana`, `cosmos`, `substrate`,
+//! `cardano`, `tron`, or `hedera` replaces the built-in agent.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut registry = AgentRegistry::new();
+//! registry.register("aptos", |config: ChainConfig, _: Option<Recipient>| async move {
+//!     Ok(Box::new(AptosAgent::new(&config).await?) as Box<dyn Agent>)
+//! });
+//!
+//! // With `"custom_chain": "aptos"` in the chain configuration:
+//! let client = ZescrowClient::builder(&config)
+//!     .agent_registry(registry)
+//!     .build()
+//!     .await?;
+//! ```
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::sync::Arc;
+
+use zescrow_core::interface::ChainConfig;
+
+use crate::{Agent, Recipient, Result};
+
+/// Builds an [`Agent`] for a registered chain.
+#[async_trait::async_trait]
+pub trait AgentFactory: Send + Sync {
+    /// Builds the agent for `config`, with secret references in the sender
+    /// key and `recipient` already resolved.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the agent cannot be initialized.
+    async fn build(
+        &self,
+        config: ChainConfig,
+        recipient: Option<Recipient>,
+    ) -> Result<Box<dyn Agent>>;
+}
+
+#[async_trait::async_trait]
+impl<F, Fut> AgentFactory for F
+where
+    F: Fn(ChainConfig, Option<Recipient>) -> Fut + Send + Sync,
+    Fut: Future<Output = Result<Box<dyn Agent>>> + Send,
+{
+    async fn build(
+        &self,
+        config: ChainConfig,
+        recipient: Option<Recipient>,
+    ) -> Result<Box<dyn Agent>> {
+        self(config, recipient).await
+    }
+}
+
+/// Agent factories keyed by chain identifier (case-insensitive).
+#[derive(Clone, Default)]
+pub struct AgentRegistry {
+    factories: HashMap<String, Arc<dyn AgentFactory>>,
+}
+
+impl AgentRegistry {
+    /// Creates an empty registry.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers `factory` for `chain`, replacing any factory registered
+    /// for it before.
+    pub fn register(
+        &mut self,
+        chain: impl AsRef<str>,
+        factory: impl AgentFactory + 'static,
+    ) -> &mut Self {
+        self.factories
+            .insert(chain.as_ref().to_lowercase(), Arc::new(factory));
+        self
+    }
+
+    /// Returns the factory registered for `chain`, if any.
+    pub fn get(&self, chain: &str) -> Option<Arc<dyn AgentFactory>> {
+        self.factories.get(&chain.to_lowercase()).cloned()
+    }
+
+    /// Returns the registered chain identifiers, in no particular order.
+    pub fn chains(&self) -> impl Iterator<Item = &str> {
+        self.factories.keys().map(String::as_str)
+    }
+}
+
+impl fmt::Debug for AgentRegistry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_set().entries(self.chains()).finish()
+    }
+}
+
+/// Identifier of the chain `config` runs against: `custom_chain` if set,
+/// otherwise the built-in chain's name.
+pub fn chain_id(config: &ChainConfig) -> &str {
+    config
+        .custom_chain
+        .as_deref()
+        .unwrap_or_else(|| config.chain.as_ref())
+}
@@ -0,0 +1,159 @@
+//! Reorg-aware escrow states.
+//!
+//! An agent returns once a transaction is included, but on Ethereum,
+//! Solana, and Cardano a reorg can still orphan its block until the block is
+//! final, undoing the transaction. [`observe`] therefore records the state
+//! the transaction moved an escrow to as pending (e.g.
+//! [`PendingFinish`](ExecutionState::PendingFinish) rather than
+//! [`Released`](ExecutionState::Released)), along with the block it was
+//! observed in, and [`reconcile`] later promotes it once that block is final,
+//! or demotes it if the block was orphaned and the chain no longer agrees.
+//! A local record thus never claims an escrow is settled while the chain
+//! disagrees.
+//!
+//! Cosmos and Hedera blocks are final once committed, and the Substrate and
+//! Tron agents await finalized and solidified blocks, so states on those
+//! chains are recorded as final at once.
+//!
+//! # Example
+//!
+//! ```ignore
+//! client.finish_escrow(&metadata).await?;
+//! reorg::observe(&client, &mut metadata, ExecutionState::Released).await?;
+//! // ... later:
+//! match reorg::reconcile(&client, &mut metadata).await? {
+//!     Reconciliation::Final => { /* metadata.state is `Released` */ }
+//!     Reconciliation::Pending | Reconciliation::Reobserved => { /* check again later */ }
+//!     Reconciliation::Demoted => { /* metadata.state is `Funded` again */ }
+//! }
+//! ```
+
+use serde::Serialize;
+use zescrow_core::{Chain, ChainMetadata, EscrowMetadata, ExecutionState};
+
+use crate::{Result, ZescrowClient};
+
+/// Outcome of [`reconcile`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Reconciliation {
+    /// The state is final: the observed block is, or the state was not
+    /// pending.
+    Final,
+    /// The observed block is canonical but not final yet.
+    Pending,
+    /// The observed block was orphaned, but the transaction landed in
+    /// another block, which is now the one observed.
+    Reobserved,
+    /// The observed block was orphaned along with the transaction, so the
+    /// state fell back to the one before it.
+    Demoted,
+}
+
+/// Whether a reorg can still undo transactions that agents on `chain`
+/// awaited.
+pub fn can_reorg(chain: Chain) -> bool {
+    matches!(chain, Chain::Ethereum | Chain::Solana | Chain::Cardano)
+}
+
+/// Records in `metadata` that a transaction moved the escrow to `state`:
+/// as the [pending](ExecutionState::pending) state observed at the latest
+/// block, unless that block is already final or the chain cannot reorg.
+///
+/// Call it right after the transaction is awaited, so the latest block
+/// builds on the one including it.
+///
+/// # Errors
+///
+/// Returns an error if the chain's blocks cannot be read, leaving the
+/// state pending without a block, for [`reconcile`] to check against the
+/// chain.
+pub async fn observe(
+    client: &ZescrowClient,
+    metadata: &mut EscrowMetadata,
+    state: ExecutionState,
+) -> Result<()> {
+    metadata.state = state;
+    metadata.observed = None;
+    let Some(pending) = state.pending() else {
+        return Ok(());
+    };
+    if !can_reorg(metadata.params.chain_config.chain) {
+        return Ok(());
+    }
+    metadata.state = pending;
+    let block = client.latest_block().await?;
+    if client.finalized_height().await? < block.height {
+        metadata.observed = Some(block);
+    } else {
+        metadata.state = state;
+    }
+    Ok(())
+}
+
+/// Checks a pending state in `metadata` against the chain, updating it:
+/// promoted once the observed block is final, re-observed if the block was
+/// orphaned but the chain still shows the state, and demoted otherwise.
+///
+/// # Errors
+///
+/// Returns an error if the chain's blocks or the escrow cannot be read.
+pub async fn reconcile(
+    client: &ZescrowClient,
+    metadata: &mut EscrowMetadata,
+) -> Result<Reconciliation> {
+    let Some((reached, fallback)) = metadata.state.outcomes() else {
+        return Ok(Reconciliation::Final);
+    };
+    if let Some(observed) = metadata.observed {
+        if client.block_hash(observed.height).await? == Some(observed.hash) {
+            if client.finalized_height().await? < observed.height {
+                return Ok(Reconciliation::Pending);
+            }
+            metadata.state = reached;
+            metadata.observed = None;
+            return Ok(Reconciliation::Final);
+        }
+    }
+
+    if agrees(client, metadata, reached).await? {
+        observe(client, metadata, reached).await?;
+        return Ok(match metadata.state.is_pending() {
+            true => Reconciliation::Reobserved,
+            false => Reconciliation::Final,
+        });
+    }
+    metadata.state = fallback;
+    metadata.observed = None;
+    Ok(Reconciliation::Demoted)
+}
+
+/// Whether the chain currently shows the escrow in `metadata` in `state`.
+async fn agrees(
+    client: &ZescrowClient,
+    metadata: &EscrowMetadata,
+    state: ExecutionState,
+) -> Result<bool> {
+    if state != ExecutionState::Funded {
+        return Ok(client.get_escrow_state(metadata).await?.settled);
+    }
+    // An orphaned creation leaves no escrow, which agents report
+    // differently (missing Solana escrow accounts count as settled), so
+    // look for it among the listed ones.
+    let id = match &metadata.chain_metadata {
+        Some(ChainMetadata::Solana { pda, .. }) => Some(pda.clone()),
+        Some(ChainMetadata::Cardano {
+            tx_hash,
+            output_index,
+        }) => Some(format!("{tx_hash}#{output_index}")),
+        _ => metadata.escrow_id.map(|id| id.to_string()),
+    };
+    let Some(id) = id else {
+        return Ok(!client.get_escrow_state(metadata).await?.settled);
+    };
+    Ok(client
+        .list_escrows()
+        .await?
+        .iter()
+        .any(|escrow| escrow.id == id && !escrow.status.settled))
+}
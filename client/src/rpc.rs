@@ -0,0 +1,361 @@
+//! Resilient JSON-RPC access shared by the chain agents.
+//!
+//! Each agent talks to its chain through a [`Failover`] over the configured
+//! `rpc_url` followed by any `fallback_rpc_urls`. A request failing with a
+//! transient error (connection failure, timeout, rate limiting, a 5xx
+//! response, or an unhealthy node) moves on to the next endpoint; once
+//! every endpoint has failed, the round is retried with exponential backoff
+//! up to [`RetryConfig::max_retries`] times. The endpoint that last answered
+//! is tried first on the next request.
+//!
+//! Requests can be throttled per endpoint by a [`RateLimiter`], so batch
+//! runs and the daemon stay within the request rate of public providers
+//! instead of getting the operator's API key throttled or banned.
+//!
+//! Counters of requests, transient errors, retries, failovers, requests
+//! that ultimately failed, and requests delayed by rate limits are kept in [`RpcMetrics`], shared by all agents
+//! built with the same [`RpcPolicy`].
+
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde::Serialize;
+use tokio::time::Instant;
+use tracing::{debug, info, trace, warn};
+use url::Url;
+
+#[cfg(feature = "cardano")]
+pub mod cardano;
+#[cfg(feature = "cosmos")]
+pub mod cosmos;
+pub mod ethereum;
+pub mod hedera;
+pub mod solana;
+pub mod tron;
+
+/// Retry settings for transient RPC errors.
+#[derive(Debug, Clone, Copy)]
+pub struct RetryConfig {
+    /// Rounds over all endpoints retried after the first one fails.
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled on every further retry.
+    pub initial_backoff: Duration,
+    /// Upper bound on the delay between retries.
+    pub max_backoff: Duration,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(8),
+        }
+    }
+}
+
+impl RetryConfig {
+    /// Returns the delay before retry number `attempt`, counting from zero.
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.max_backoff)
+    }
+}
+
+/// Token-bucket limit on the request rate to an endpoint.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    /// Sustained requests per second.
+    pub per_second: f64,
+    /// Requests that may be sent at once after a quiet period.
+    pub burst: u32,
+}
+
+impl RateLimit {
+    /// Allows `rate` requests per second, in bursts of up to one second's
+    /// worth.
+    pub fn per_second(rate: f64) -> Self {
+        Self {
+            per_second: rate,
+            burst: rate.ceil().max(1.0) as u32,
+        }
+    }
+}
+
+impl FromStr for RateLimit {
+    type Err = String;
+
+    /// Parses a rate in requests per second, e.g. `10` or `0.5`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<f64>() {
+            Ok(rate) if rate.is_finite() && rate > 0.0 => Ok(Self::per_second(rate)),
+            _ => Err(format!(
+                "expected a positive number of requests per second, got {s}"
+            )),
+        }
+    }
+}
+
+/// Requests available to an endpoint, refilled over time.
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Rate limits of RPC endpoints, shared by every agent built with the same
+/// [`RpcPolicy`] so that concurrent clients draw from the same budget.
+///
+/// Endpoints without a limit of their own use the default limit, if any.
+#[derive(Debug, Default)]
+pub struct RateLimiter {
+    default: Option<RateLimit>,
+    /// Limits by endpoint host or full URL.
+    endpoints: Vec<(String, RateLimit)>,
+    /// Buckets by endpoint URL, created on first use.
+    buckets: Mutex<HashMap<String, Bucket>>,
+}
+
+impl RateLimiter {
+    /// Creates a limiter applying `default` to every endpoint.
+    pub fn new(default: Option<RateLimit>) -> Self {
+        Self {
+            default,
+            ..Self::default()
+        }
+    }
+
+    /// Applies `limit` to the endpoints at `endpoint`, a host name (e.g.
+    /// `api.mainnet-beta.solana.com`) or full URL, instead of the default.
+    pub fn endpoint(mut self, endpoint: impl Into<String>, limit: RateLimit) -> Self {
+        self.endpoints.push((endpoint.into(), limit));
+        self
+    }
+
+    /// Returns the limit applying to `url`.
+    fn limit(&self, url: &str) -> Option<RateLimit> {
+        let host = Url::parse(url).ok();
+        let host = host.as_ref().and_then(Url::host_str);
+        self.endpoints
+            .iter()
+            .find(|(endpoint, _)| {
+                endpoint.trim_end_matches('/') == url.trim_end_matches('/')
+                    || host.is_some_and(|host| endpoint.eq_ignore_ascii_case(host))
+            })
+            .map(|(_, limit)| *limit)
+            .or(self.default)
+    }
+
+    /// Waits until a request to `url` is within its limit, returning
+    /// whether it had to wait.
+    pub(crate) async fn acquire(&self, url: &str) -> bool {
+        let Some(limit) = self.limit(url) else {
+            return false;
+        };
+        let burst = f64::from(limit.burst.max(1));
+        let mut waited = false;
+        loop {
+            let delay = {
+                let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+                let now = Instant::now();
+                let bucket = buckets.entry(url.to_owned()).or_insert(Bucket {
+                    tokens: burst,
+                    updated: now,
+                });
+                let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+                bucket.tokens = (bucket.tokens + elapsed * limit.per_second).min(burst);
+                bucket.updated = now;
+                if bucket.tokens >= 1.0 {
+                    bucket.tokens -= 1.0;
+                    return waited;
+                }
+                Duration::from_secs_f64((1.0 - bucket.tokens) / limit.per_second)
+            };
+            trace!(%url, ?delay, "Rate limiting RPC request");
+            tokio::time::sleep(delay).await;
+            waited = true;
+        }
+    }
+}
+
+/// Counters of RPC activity, updated by every agent sharing them.
+#[derive(Debug, Default)]
+pub struct RpcMetrics {
+    requests: AtomicU64,
+    errors: AtomicU64,
+    retries: AtomicU64,
+    failovers: AtomicU64,
+    failures: AtomicU64,
+    throttled: AtomicU64,
+}
+
+/// Point-in-time copy of [`RpcMetrics`].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RpcMetricsSnapshot {
+    /// Requests sent, counting each once however often it was retried.
+    pub requests: u64,
+    /// Transient errors returned by any endpoint.
+    pub errors: u64,
+    /// Backoff rounds started after every endpoint had failed.
+    pub retries: u64,
+    /// Requests answered by an endpoint other than the preferred one.
+    pub failovers: u64,
+    /// Requests that failed on every endpoint and retry.
+    pub failures: u64,
+    /// Attempts delayed to stay within an endpoint's rate limit.
+    pub throttled: u64,
+}
+
+impl RpcMetrics {
+    /// Returns the current counter values.
+    pub fn snapshot(&self) -> RpcMetricsSnapshot {
+        let load = |counter: &AtomicU64| counter.load(Ordering::Relaxed);
+        RpcMetricsSnapshot {
+            requests: load(&self.requests),
+            errors: load(&self.errors),
+            retries: load(&self.retries),
+            failovers: load(&self.failovers),
+            failures: load(&self.failures),
+            throttled: load(&self.throttled),
+        }
+    }
+}
+
+impl fmt::Display for RpcMetricsSnapshot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} requests, {} errors, {} retries, {} failovers, {} failures, {} throttled",
+            self.requests, self.errors, self.retries, self.failovers, self.failures, self.throttled
+        )
+    }
+}
+
+/// Retry settings, rate limits, and the metrics they report to.
+#[derive(Debug, Clone, Default)]
+pub struct RpcPolicy {
+    /// Retry settings for transient errors.
+    pub retry: RetryConfig,
+    /// Counters updated by every request.
+    pub metrics: Arc<RpcMetrics>,
+    /// Rate limits of the endpoints; unlimited by default.
+    pub limiter: Arc<RateLimiter>,
+}
+
+impl RpcPolicy {
+    /// Creates a policy with `retry`, no rate limits, and fresh metrics.
+    pub fn new(retry: RetryConfig) -> Self {
+        Self {
+            retry,
+            ..Self::default()
+        }
+    }
+
+    /// Throttles requests to the endpoints with `limiter`.
+    pub fn rate_limiter(mut self, limiter: RateLimiter) -> Self {
+        self.limiter = Arc::new(limiter);
+        self
+    }
+}
+
+/// Endpoints of one chain, tried in turn under an [`RpcPolicy`].
+#[derive(Debug)]
+pub(crate) struct Failover<T> {
+    /// Endpoint URLs, for logging, with their clients. Never empty.
+    endpoints: Vec<(String, T)>,
+    /// Index of the endpoint that last answered.
+    preferred: AtomicUsize,
+    policy: RpcPolicy,
+}
+
+impl<T> Failover<T> {
+    /// Creates a failover over `primary` followed by `fallbacks`.
+    pub(crate) fn new(
+        primary: (String, T),
+        fallbacks: impl IntoIterator<Item = (String, T)>,
+        policy: RpcPolicy,
+    ) -> Self {
+        Self {
+            endpoints: std::iter::once(primary).chain(fallbacks).collect(),
+            preferred: AtomicUsize::new(0),
+            policy,
+        }
+    }
+
+    /// Returns the URL of the endpoint currently tried first.
+    pub(crate) fn url(&self) -> &str {
+        &self.endpoints[self.preferred()].0
+    }
+
+    /// Returns the client of the endpoint currently tried first.
+    pub(crate) fn preferred_endpoint(&self) -> &T {
+        &self.endpoints[self.preferred()].1
+    }
+
+    fn preferred(&self) -> usize {
+        self.preferred.load(Ordering::Relaxed) % self.endpoints.len()
+    }
+
+    /// Sends `request` to each endpoint until one answers, retrying with
+    /// backoff while every endpoint fails with an error `is_transient`
+    /// accepts. Other errors are returned immediately.
+    pub(crate) async fn run<R, E, F, Fut>(
+        &self,
+        mut request: F,
+        is_transient: impl Fn(&E) -> bool,
+    ) -> Result<R, E>
+    where
+        F: FnMut(&T) -> Fut,
+        Fut: Future<Output = Result<R, E>>,
+        E: fmt::Display,
+    {
+        let metrics = &self.policy.metrics;
+        metrics.requests.fetch_add(1, Ordering::Relaxed);
+
+        let mut attempt = 0;
+        loop {
+            let start = self.preferred();
+            let mut last_error = None;
+            for offset in 0..self.endpoints.len() {
+                let index = (start + offset) % self.endpoints.len();
+                let (url, endpoint) = &self.endpoints[index];
+                if self.policy.limiter.acquire(url).await {
+                    metrics.throttled.fetch_add(1, Ordering::Relaxed);
+                }
+                match request(endpoint).await {
+                    Ok(response) => {
+                        if offset > 0 {
+                            self.preferred.store(index, Ordering::Relaxed);
+                            metrics.failovers.fetch_add(1, Ordering::Relaxed);
+                            info!(%url, "Failed over to RPC endpoint");
+                        }
+                        return Ok(response);
+                    }
+                    Err(e) if is_transient(&e) => {
+                        metrics.errors.fetch_add(1, Ordering::Relaxed);
+                        warn!(%url, error = %e, "RPC request failed");
+                        last_error = Some(e);
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+
+            let error = last_error.expect("endpoints are never empty");
+            if attempt >= self.policy.retry.max_retries {
+                metrics.failures.fetch_add(1, Ordering::Relaxed);
+                return Err(error);
+            }
+            let delay = self.policy.retry.backoff(attempt);
+            metrics.retries.fetch_add(1, Ordering::Relaxed);
+            debug!(?delay, attempt = attempt + 1, "Retrying RPC request");
+            tokio::time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
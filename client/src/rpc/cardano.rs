@@ -0,0 +1,163 @@
+//! Failover client for Blockfrost-compatible Cardano API endpoints.
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use url::Url;
+
+use super::{Failover, RpcPolicy};
+
+/// Header carrying the Blockfrost project ID.
+const PROJECT_ID_HEADER: &str = "project_id";
+
+/// Failed Blockfrost request.
+#[derive(Debug)]
+pub(crate) enum BlockfrostError {
+    /// The request could not be sent or its response not read.
+    Http(reqwest::Error),
+    /// The endpoint answered with an error status.
+    Status {
+        /// HTTP status code.
+        status: u16,
+        /// Error message from the body, or the body itself.
+        message: String,
+    },
+}
+
+impl BlockfrostError {
+    /// Whether the endpoint reported the requested resource as missing,
+    /// e.g. a transaction not yet included in a block.
+    pub(crate) fn is_not_found(&self) -> bool {
+        matches!(self, Self::Status { status: 404, .. })
+    }
+}
+
+impl fmt::Display for BlockfrostError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "{e}"),
+            Self::Status { status, message } => write!(f, "HTTP {status}: {message}"),
+        }
+    }
+}
+
+/// Error body of Blockfrost endpoints.
+#[derive(Deserialize)]
+struct ErrorBody {
+    message: String,
+}
+
+/// Blockfrost client over several endpoints, retrying transient errors.
+#[derive(Debug)]
+pub(crate) struct BlockfrostClient {
+    http: reqwest::Client,
+    project_id: Option<String>,
+    inner: Failover<Url>,
+}
+
+impl BlockfrostClient {
+    /// Creates a client over `primary` followed by `fallbacks`, sending
+    /// `project_id` with every request if set.
+    pub(crate) fn new(
+        primary: &Url,
+        fallbacks: &[Url],
+        project_id: Option<String>,
+        policy: RpcPolicy,
+    ) -> Self {
+        let endpoint = |url: &Url| (url.to_string(), url.clone());
+        Self {
+            http: reqwest::Client::new(),
+            project_id,
+            inner: Failover::new(endpoint(primary), fallbacks.iter().map(endpoint), policy),
+        }
+    }
+
+    /// Returns the URL of the endpoint currently tried first.
+    pub(crate) fn url(&self) -> &str {
+        self.inner.url()
+    }
+
+    /// Sends a GET request for `path` below the endpoint's base URL, with
+    /// `query` parameters.
+    pub(crate) async fn get<T: DeserializeOwned>(
+        &self,
+        path: &[&str],
+        query: &[(&str, &str)],
+    ) -> Result<T, BlockfrostError> {
+        self.inner
+            .run(
+                |base| {
+                    let request = self.http.get(join(base, path)).query(query);
+                    send(self.authorize(request))
+                },
+                is_transient,
+            )
+            .await
+    }
+
+    /// Sends a POST request with a CBOR `body` to `path`, e.g. a signed
+    /// transaction to `tx/submit`.
+    pub(crate) async fn post_cbor<T: DeserializeOwned>(
+        &self,
+        path: &[&str],
+        body: &[u8],
+    ) -> Result<T, BlockfrostError> {
+        self.inner
+            .run(
+                |base| {
+                    let request = self
+                        .http
+                        .post(join(base, path))
+                        .header(reqwest::header::CONTENT_TYPE, "application/cbor")
+                        .body(body.to_vec());
+                    send(self.authorize(request))
+                },
+                is_transient,
+            )
+            .await
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.project_id {
+            Some(project_id) => request.header(PROJECT_ID_HEADER, project_id),
+            None => request,
+        }
+    }
+}
+
+/// Appends `path` to `base`, percent-encoding each segment.
+fn join(base: &Url, path: &[&str]) -> Url {
+    let mut url = base.clone();
+    if let Ok(mut segments) = url.path_segments_mut() {
+        segments.pop_if_empty().extend(path);
+    }
+    url
+}
+
+async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder) -> Result<T, BlockfrostError> {
+    let response = request.send().await.map_err(BlockfrostError::Http)?;
+    let status = response.status();
+    if status.is_success() {
+        return response.json().await.map_err(BlockfrostError::Http);
+    }
+    let body = response.text().await.map_err(BlockfrostError::Http)?;
+    let message = match serde_json::from_str::<ErrorBody>(&body) {
+        Ok(error) => error.message,
+        Err(_) => body,
+    };
+    Err(BlockfrostError::Status {
+        status: status.as_u16(),
+        message,
+    })
+}
+
+/// Timeouts, connection failures, rate limiting, and server errors are
+/// transient. Blockfrost answers exhausted daily quotas with a 402, which
+/// another endpoint may not share.
+fn is_transient(error: &BlockfrostError) -> bool {
+    match error {
+        BlockfrostError::Http(e) => e.is_timeout() || e.is_connect(),
+        BlockfrostError::Status { status, .. } => matches!(*status, 402 | 429) || *status >= 500,
+    }
+}
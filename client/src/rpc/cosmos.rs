@@ -0,0 +1,158 @@
+//! Failover client for Cosmos SDK LCD (REST) endpoints.
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use url::Url;
+
+use super::{Failover, RpcPolicy};
+
+/// gRPC status codes, relayed in LCD error bodies, of failures that may
+/// pass on another attempt: deadline exceeded, resource exhausted, and
+/// unavailable.
+const TRANSIENT_GRPC_CODES: [i64; 3] = [4, 8, 14];
+
+/// Failed LCD request.
+#[derive(Debug)]
+pub(crate) enum LcdError {
+    /// The request could not be sent or its response not read.
+    Http(reqwest::Error),
+    /// The endpoint answered with an error status.
+    Status {
+        /// HTTP status code.
+        status: u16,
+        /// gRPC status code from the error body, if any.
+        code: Option<i64>,
+        /// Error message from the body, or the body itself.
+        message: String,
+    },
+}
+
+impl LcdError {
+    /// Whether the endpoint reported the requested resource as missing,
+    /// e.g. a transaction not yet included in a block.
+    pub(crate) fn is_not_found(&self) -> bool {
+        matches!(
+            self,
+            Self::Status { status: 404, .. } | Self::Status { code: Some(5), .. }
+        )
+    }
+}
+
+impl fmt::Display for LcdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "{e}"),
+            Self::Status {
+                status, message, ..
+            } => write!(f, "HTTP {status}: {message}"),
+        }
+    }
+}
+
+/// Error body of the gRPC gateway behind LCD endpoints.
+#[derive(Deserialize)]
+struct ErrorBody {
+    code: Option<i64>,
+    message: String,
+}
+
+/// LCD client over several endpoints, retrying transient errors.
+#[derive(Debug)]
+pub(crate) struct LcdClient {
+    http: reqwest::Client,
+    inner: Failover<Url>,
+}
+
+impl LcdClient {
+    /// Creates a client over `primary` followed by `fallbacks`.
+    pub(crate) fn new(primary: &Url, fallbacks: &[Url], policy: RpcPolicy) -> Self {
+        let endpoint = |url: &Url| (url.to_string(), url.clone());
+        Self {
+            http: reqwest::Client::new(),
+            inner: Failover::new(endpoint(primary), fallbacks.iter().map(endpoint), policy),
+        }
+    }
+
+    /// Returns the URL of the endpoint currently tried first.
+    pub(crate) fn url(&self) -> &str {
+        self.inner.url()
+    }
+
+    /// Sends a GET request for `path` below the endpoint's base URL, with
+    /// `query` parameters.
+    pub(crate) async fn get<T: DeserializeOwned>(
+        &self,
+        path: &[&str],
+        query: &[(&str, &str)],
+    ) -> Result<T, LcdError> {
+        self.inner
+            .run(
+                |base| {
+                    let request = self.http.get(join(base, path)).query(query);
+                    send(request)
+                },
+                is_transient,
+            )
+            .await
+    }
+
+    /// Sends a POST request with a JSON `body` to `path`.
+    pub(crate) async fn post<T: DeserializeOwned>(
+        &self,
+        path: &[&str],
+        body: &serde_json::Value,
+    ) -> Result<T, LcdError> {
+        self.inner
+            .run(
+                |base| {
+                    let request = self.http.post(join(base, path)).json(body);
+                    send(request)
+                },
+                is_transient,
+            )
+            .await
+    }
+}
+
+/// Appends `path` to `base`, percent-encoding each segment.
+fn join(base: &Url, path: &[&str]) -> Url {
+    let mut url = base.clone();
+    if let Ok(mut segments) = url.path_segments_mut() {
+        segments.pop_if_empty().extend(path);
+    }
+    url
+}
+
+async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder) -> Result<T, LcdError> {
+    let response = request.send().await.map_err(LcdError::Http)?;
+    let status = response.status();
+    if status.is_success() {
+        return response.json().await.map_err(LcdError::Http);
+    }
+    let body = response.text().await.map_err(LcdError::Http)?;
+    let (code, message) = match serde_json::from_str::<ErrorBody>(&body) {
+        Ok(error) => (error.code, error.message),
+        Err(_) => (None, body),
+    };
+    Err(LcdError::Status {
+        status: status.as_u16(),
+        code,
+        message,
+    })
+}
+
+/// Timeouts, connection failures, rate limiting, and server errors are
+/// transient, unless the gateway relays a gRPC status that is not: the
+/// gateway answers application errors (e.g. a failing contract query) with
+/// a 500 as well.
+fn is_transient(error: &LcdError) -> bool {
+    match error {
+        LcdError::Http(e) => e.is_timeout() || e.is_connect(),
+        LcdError::Status {
+            code: Some(code), ..
+        } => TRANSIENT_GRPC_CODES.contains(code),
+        LcdError::Status { status, .. } => *status == 429 || *status >= 500,
+    }
+}
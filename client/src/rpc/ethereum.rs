@@ -0,0 +1,103 @@
+//! Failover transport for Ethereum JSON-RPC providers.
+
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+use alloy::rpc::client::RpcClient;
+use alloy::rpc::json_rpc::{RequestPacket, ResponsePacket};
+use alloy::transports::http::{reqwest, Http};
+use alloy::transports::utils::guess_local_url;
+use alloy::transports::{RpcError, TransportError, TransportErrorKind, TransportFut};
+use tower::Service;
+use url::Url;
+
+use super::{Failover, RpcPolicy};
+
+/// JSON-RPC error code some providers use for rate limiting.
+const LIMIT_EXCEEDED: i64 = -32005;
+
+/// HTTP transport over several endpoints, retrying transient errors.
+#[derive(Debug, Clone)]
+pub(crate) struct FailoverTransport {
+    inner: Arc<Failover<Http<reqwest::Client>>>,
+}
+
+impl FailoverTransport {
+    /// Creates a transport over `primary` followed by `fallbacks`.
+    pub(crate) fn new(primary: &Url, fallbacks: &[Url], policy: RpcPolicy) -> Self {
+        let endpoint = |url: &Url| (url.to_string(), Http::new(url.clone()));
+        Self {
+            inner: Arc::new(Failover::new(
+                endpoint(primary),
+                fallbacks.iter().map(endpoint),
+                policy,
+            )),
+        }
+    }
+
+    /// Wraps the transport in an RPC client for a provider.
+    pub(crate) fn into_client(self) -> RpcClient {
+        let is_local = guess_local_url(self.inner.url());
+        RpcClient::new(self, is_local)
+    }
+}
+
+impl Service<RequestPacket> for FailoverTransport {
+    type Response = ResponsePacket;
+    type Error = TransportError;
+    type Future = TransportFut<'static>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: RequestPacket) -> Self::Future {
+        let inner = self.inner.clone();
+        Box::pin(async move {
+            inner
+                .run(
+                    |http| {
+                        let mut http = http.clone();
+                        let request = request.clone();
+                        async move {
+                            let response = http.call(request).await?;
+                            if is_rate_limited(&response) {
+                                return Err(TransportErrorKind::custom_str(
+                                    "rate limited by the endpoint",
+                                ));
+                            }
+                            Ok(response)
+                        }
+                    },
+                    is_transient,
+                )
+                .await
+        })
+    }
+}
+
+/// Returns whether any response in `response` reports rate limiting.
+fn is_rate_limited(response: &ResponsePacket) -> bool {
+    let responses = match response {
+        ResponsePacket::Single(response) => std::slice::from_ref(response),
+        ResponsePacket::Batch(responses) => responses.as_slice(),
+    };
+    responses.iter().any(|response| {
+        response
+            .payload
+            .as_error()
+            .is_some_and(|error| matches!(error.code, 429 | LIMIT_EXCEEDED))
+    })
+}
+
+/// Transport failures are transient, except HTTP errors other than rate
+/// limiting and server errors.
+fn is_transient(error: &TransportError) -> bool {
+    match error {
+        RpcError::Transport(TransportErrorKind::HttpError(error)) => {
+            error.status == 429 || error.status >= 500
+        }
+        RpcError::Transport(_) => true,
+        _ => false,
+    }
+}
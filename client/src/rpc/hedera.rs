@@ -0,0 +1,115 @@
+//! Retrying client for the Hedera mirror node REST API.
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use url::Url;
+
+use super::{Failover, RpcPolicy};
+
+/// Failed mirror node request.
+#[derive(Debug)]
+pub(crate) enum MirrorError {
+    /// The request could not be sent or its response not read.
+    Http(reqwest::Error),
+    /// The endpoint answered with an error status.
+    Status {
+        /// HTTP status code.
+        status: u16,
+        /// Error message from the body, or the body itself.
+        message: String,
+    },
+}
+
+impl fmt::Display for MirrorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "{e}"),
+            Self::Status { status, message } => write!(f, "HTTP {status}: {message}"),
+        }
+    }
+}
+
+/// Error body of mirror node endpoints.
+#[derive(Deserialize)]
+struct ErrorBody {
+    #[serde(rename = "_status")]
+    status: ErrorStatus,
+}
+
+#[derive(Deserialize)]
+struct ErrorStatus {
+    messages: Vec<ErrorMessage>,
+}
+
+#[derive(Deserialize)]
+struct ErrorMessage {
+    message: String,
+}
+
+/// Mirror node client, retrying transient errors.
+#[derive(Debug)]
+pub(crate) struct MirrorClient {
+    http: reqwest::Client,
+    inner: Failover<Url>,
+}
+
+impl MirrorClient {
+    /// Creates a client for the mirror node at `url`.
+    pub(crate) fn new(url: &Url, policy: RpcPolicy) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            inner: Failover::new((url.to_string(), url.clone()), [], policy),
+        }
+    }
+
+    /// Sends a GET request for `path` below `/api/v1`, e.g.
+    /// `accounts/0.0.1234`.
+    pub(crate) async fn get<T: DeserializeOwned>(&self, path: &[&str]) -> Result<T, MirrorError> {
+        self.inner
+            .run(|base| send(self.http.get(join(base, path))), is_transient)
+            .await
+    }
+}
+
+/// Appends `/api/v1` and `path` to `base`, percent-encoding each segment.
+fn join(base: &Url, path: &[&str]) -> Url {
+    let mut url = base.clone();
+    if let Ok(mut segments) = url.path_segments_mut() {
+        segments.pop_if_empty().extend(["api", "v1"]).extend(path);
+    }
+    url
+}
+
+async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder) -> Result<T, MirrorError> {
+    let response = request.send().await.map_err(MirrorError::Http)?;
+    let status = response.status();
+    if status.is_success() {
+        return response.json().await.map_err(MirrorError::Http);
+    }
+    let body = response.text().await.map_err(MirrorError::Http)?;
+    let message = match serde_json::from_str::<ErrorBody>(&body) {
+        Ok(error) => error
+            .status
+            .messages
+            .into_iter()
+            .map(|m| m.message)
+            .collect::<Vec<_>>()
+            .join("; "),
+        Err(_) => body,
+    };
+    Err(MirrorError::Status {
+        status: status.as_u16(),
+        message,
+    })
+}
+
+/// Timeouts, connection failures, rate limiting, and server errors are
+/// transient.
+fn is_transient(error: &MirrorError) -> bool {
+    match error {
+        MirrorError::Http(e) => e.is_timeout() || e.is_connect(),
+        MirrorError::Status { status, .. } => *status == 429 || *status >= 500,
+    }
+}
@@ -0,0 +1,78 @@
+//! Failover sender for Solana RPC clients.
+
+use async_trait::async_trait;
+use solana_client::client_error::{ClientError, ClientErrorKind, Result};
+use solana_client::http_sender::HttpSender;
+use solana_client::rpc_client::{RpcClient, RpcClientConfig};
+use solana_client::rpc_request::{RpcError, RpcRequest};
+use solana_client::rpc_sender::{RpcSender, RpcTransportStats};
+use solana_sdk::commitment_config::CommitmentConfig;
+
+use super::{Failover, RpcPolicy};
+
+/// JSON-RPC error code of a node that is behind or otherwise unhealthy.
+const NODE_UNHEALTHY: i64 = -32005;
+
+/// RPC sender over several endpoints, retrying transient errors.
+pub(crate) struct FailoverSender {
+    inner: Failover<HttpSender>,
+}
+
+impl FailoverSender {
+    /// Creates a sender over `primary` followed by `fallbacks`.
+    pub(crate) fn new(primary: &str, fallbacks: &[String], policy: RpcPolicy) -> Self {
+        let endpoint = |url: &str| (url.to_owned(), HttpSender::new(url));
+        Self {
+            inner: Failover::new(
+                endpoint(primary),
+                fallbacks.iter().map(|url| endpoint(url.as_str())),
+                policy,
+            ),
+        }
+    }
+
+    /// Wraps the sender in an RPC client reading at `commitment`.
+    pub(crate) fn into_client(self, commitment: CommitmentConfig) -> RpcClient {
+        RpcClient::new_sender(self, RpcClientConfig::with_commitment(commitment))
+    }
+}
+
+#[async_trait]
+impl RpcSender for FailoverSender {
+    async fn send(
+        &self,
+        request: RpcRequest,
+        params: serde_json::Value,
+    ) -> Result<serde_json::Value> {
+        self.inner
+            .run(|http| http.send(request, params.clone()), is_transient)
+            .await
+    }
+
+    fn get_transport_stats(&self) -> RpcTransportStats {
+        self.inner.preferred_endpoint().get_transport_stats()
+    }
+
+    fn url(&self) -> String {
+        self.inner.url().to_owned()
+    }
+}
+
+/// I/O errors, timeouts, connection failures, rate limiting, server errors,
+/// and unhealthy nodes are transient.
+fn is_transient(error: &ClientError) -> bool {
+    match error.kind() {
+        ClientErrorKind::Io(_) => true,
+        ClientErrorKind::Reqwest(error) => {
+            error.is_timeout()
+                || error.is_connect()
+                || error
+                    .status()
+                    .is_some_and(|status| status.as_u16() == 429 || status.is_server_error())
+        }
+        ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. }) => {
+            *code == NODE_UNHEALTHY
+        }
+        _ => false,
+    }
+}
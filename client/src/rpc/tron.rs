@@ -0,0 +1,125 @@
+//! Failover client for Tron full node HTTP APIs.
+
+use std::fmt;
+
+use serde::de::DeserializeOwned;
+use url::Url;
+
+use super::{Failover, RpcPolicy};
+
+/// Header carrying the TronGrid API key.
+const API_KEY_HEADER: &str = "TRON-PRO-API-KEY";
+
+/// Failed Tron API request.
+#[derive(Debug)]
+pub(crate) enum TronError {
+    /// The request could not be sent or its response not read.
+    Http(reqwest::Error),
+    /// The endpoint answered with an error status.
+    Status {
+        /// HTTP status code.
+        status: u16,
+        /// The response body.
+        message: String,
+    },
+    /// The node rejected the request in a successful response, as it does
+    /// for malformed addresses or parameters.
+    Api(String),
+}
+
+impl fmt::Display for TronError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "{e}"),
+            Self::Status { status, message } => write!(f, "HTTP {status}: {message}"),
+            Self::Api(message) => write!(f, "{message}"),
+        }
+    }
+}
+
+/// Tron HTTP API client over several endpoints, retrying transient errors.
+#[derive(Debug)]
+pub(crate) struct TronClient {
+    http: reqwest::Client,
+    api_key: Option<String>,
+    inner: Failover<Url>,
+}
+
+impl TronClient {
+    /// Creates a client over `primary` followed by `fallbacks`, sending
+    /// `api_key` with every request if set.
+    pub(crate) fn new(
+        primary: &Url,
+        fallbacks: &[Url],
+        api_key: Option<String>,
+        policy: RpcPolicy,
+    ) -> Self {
+        let endpoint = |url: &Url| (url.to_string(), url.clone());
+        Self {
+            http: reqwest::Client::new(),
+            api_key,
+            inner: Failover::new(endpoint(primary), fallbacks.iter().map(endpoint), policy),
+        }
+    }
+
+    /// Sends a POST request with a JSON `body` to `path`, e.g.
+    /// `wallet/triggersmartcontract`.
+    pub(crate) async fn post<T: DeserializeOwned>(
+        &self,
+        path: &[&str],
+        body: &serde_json::Value,
+    ) -> Result<T, TronError> {
+        self.inner
+            .run(
+                |base| {
+                    let request = self.http.post(join(base, path)).json(body);
+                    send(self.authorize(request))
+                },
+                is_transient,
+            )
+            .await
+    }
+
+    fn authorize(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(api_key) => request.header(API_KEY_HEADER, api_key),
+            None => request,
+        }
+    }
+}
+
+/// Appends `path` to `base`, percent-encoding each segment.
+fn join(base: &Url, path: &[&str]) -> Url {
+    let mut url = base.clone();
+    if let Ok(mut segments) = url.path_segments_mut() {
+        segments.pop_if_empty().extend(path);
+    }
+    url
+}
+
+async fn send<T: DeserializeOwned>(request: reqwest::RequestBuilder) -> Result<T, TronError> {
+    let response = request.send().await.map_err(TronError::Http)?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(TronError::Status {
+            status: status.as_u16(),
+            message: response.text().await.map_err(TronError::Http)?,
+        });
+    }
+    let body: serde_json::Value = response.json().await.map_err(TronError::Http)?;
+    if let Some(error) = body.get("Error").and_then(serde_json::Value::as_str) {
+        return Err(TronError::Api(error.to_owned()));
+    }
+    serde_json::from_value(body).map_err(|e| TronError::Api(format!("unexpected response: {e}")))
+}
+
+/// Timeouts, connection failures, rate limiting, and server errors are
+/// transient; TronGrid answers exhausted API key quotas with a 403, which
+/// another endpoint may not share.
+fn is_transient(error: &TronError) -> bool {
+    match error {
+        TronError::Http(e) => e.is_timeout() || e.is_connect(),
+        TronError::Status { status, .. } => matches!(*status, 403 | 429) || *status >= 500,
+        TronError::Api(_) => false,
+    }
+}
@@ -0,0 +1,144 @@
+//! Key references resolved from secret managers at runtime.
+//!
+//! Instead of a key (or keypair path), `sender_private_id` and recipients
+//! may name where the key is kept, so long-running deployments never write
+//! it to disk:
+//!
+//! - `env:<VAR>`: environment variable
+//! - `vault:<mount>/<path>[#<field>]`: HashiCorp Vault KV v2 secret, read
+//!   with `VAULT_ADDR`, `VAULT_TOKEN`, and optionally `VAULT_NAMESPACE`
+//! - `aws-sm:<secret-id-or-arn>[#<field>]`: AWS Secrets Manager secret
+//!   (`aws-secrets` feature), with credentials from the environment
+//!
+//! `<field>` selects a field of a JSON secret; it defaults to `value` for
+//! Vault and to the whole secret string for AWS. The resolved secret is an
+//! Ethereum private key, or a Solana keypair as a JSON byte array (the
+//! contents of a keypair file).
+
+use serde_json::Value;
+
+use crate::error::ClientError;
+use crate::Result;
+
+/// Prefix referring to an environment variable.
+pub const ENV_PREFIX: &str = "env:";
+
+/// Prefix referring to a HashiCorp Vault KV v2 secret.
+pub const VAULT_PREFIX: &str = "vault:";
+
+/// Prefix referring to an AWS Secrets Manager secret.
+pub const AWS_SECRETS_PREFIX: &str = "aws-sm:";
+
+/// Field read from Vault secrets when the reference names none.
+const DEFAULT_VAULT_FIELD: &str = "value";
+
+/// Returns whether `id` is a secret reference rather than a key.
+pub fn is_reference(id: &str) -> bool {
+    [ENV_PREFIX, VAULT_PREFIX, AWS_SECRETS_PREFIX]
+        .iter()
+        .any(|prefix| id.starts_with(prefix))
+}
+
+/// Resolves `id` if it is a secret reference, returning `None` otherwise.
+///
+/// # Errors
+///
+/// Returns an error if the secret cannot be fetched or has no such field.
+pub async fn resolve(id: &str) -> Result<Option<String>> {
+    if let Some(var) = id.strip_prefix(ENV_PREFIX) {
+        return std::env::var(var)
+            .map(Some)
+            .map_err(|_| ClientError::Secret(format!("environment variable {var} is not set")));
+    }
+    if let Some(reference) = id.strip_prefix(VAULT_PREFIX) {
+        return vault(reference).await.map(Some);
+    }
+    if let Some(reference) = id.strip_prefix(AWS_SECRETS_PREFIX) {
+        return aws(reference).await.map(Some);
+    }
+    Ok(None)
+}
+
+/// Reads a field of a Vault KV v2 secret.
+async fn vault(reference: &str) -> Result<String> {
+    let (path, field) = split_field(reference);
+    let (mount, path) = path
+        .split_once('/')
+        .ok_or_else(|| ClientError::Secret(format!("Vault path {path} has no mount")))?;
+    let addr = vault_env("VAULT_ADDR")?;
+    let url = format!("{}/v1/{mount}/data/{path}", addr.trim_end_matches('/'));
+
+    let mut request = reqwest::Client::new()
+        .get(&url)
+        .header("X-Vault-Token", vault_env("VAULT_TOKEN")?);
+    if let Ok(namespace) = std::env::var("VAULT_NAMESPACE") {
+        request = request.header("X-Vault-Namespace", namespace);
+    }
+    let response = request
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| ClientError::Secret(format!("Vault secret {mount}/{path}: {e}")))?;
+    let body: Value = response
+        .json()
+        .await
+        .map_err(|e| ClientError::Secret(format!("Vault secret {mount}/{path}: {e}")))?;
+
+    let field = field.unwrap_or(DEFAULT_VAULT_FIELD);
+    string_field(&body["data"]["data"], field)
+        .ok_or_else(|| ClientError::Secret(format!("Vault secret {mount}/{path} has no {field}")))
+}
+
+/// Reads an AWS Secrets Manager secret, or a field of a JSON secret.
+#[cfg(feature = "aws-secrets")]
+async fn aws(reference: &str) -> Result<String> {
+    let (secret_id, field) = split_field(reference);
+    let config = aws_config::load_defaults(aws_config::BehaviorVersion::latest()).await;
+    let secret = aws_sdk_secretsmanager::Client::new(&config)
+        .get_secret_value()
+        .secret_id(secret_id)
+        .send()
+        .await
+        .map_err(|e| ClientError::Secret(format!("AWS secret {secret_id}: {e}")))?
+        .secret_string()
+        .map(str::to_owned)
+        .ok_or_else(|| ClientError::Secret(format!("AWS secret {secret_id} is not a string")))?;
+
+    match field {
+        None => Ok(secret),
+        Some(field) => serde_json::from_str::<Value>(&secret)
+            .ok()
+            .and_then(|json| string_field(&json, field))
+            .ok_or_else(|| ClientError::Secret(format!("AWS secret {secret_id} has no {field}"))),
+    }
+}
+
+#[cfg(not(feature = "aws-secrets"))]
+async fn aws(_reference: &str) -> Result<String> {
+    Err(ClientError::Secret(
+        "AWS Secrets Manager references require the `aws-secrets` feature".into(),
+    ))
+}
+
+/// Splits `reference` into a path and an optional `#field`.
+fn split_field(reference: &str) -> (&str, Option<&str>) {
+    match reference.rsplit_once('#') {
+        Some((path, field)) => (path, Some(field)),
+        None => (reference, None),
+    }
+}
+
+/// Returns `field` of a JSON object as a string, serializing non-strings
+/// such as keypair byte arrays.
+fn string_field(object: &Value, field: &str) -> Option<String> {
+    match object.get(field)? {
+        Value::String(value) => Some(value.clone()),
+        Value::Null => None,
+        value => Some(value.to_string()),
+    }
+}
+
+fn vault_env(var: &str) -> Result<String> {
+    std::env::var(var)
+        .map_err(|_| ClientError::Secret(format!("{var} must be set to read Vault secrets")))
+}
@@ -0,0 +1,442 @@
+//! `serve` mode: a REST API over the client.
+//!
+//! Every route except `GET /health` requires one of the configured API keys,
+//! sent as `Authorization: Bearer <key>` or `X-API-Key: <key>`. Requests and
+//! responses are JSON; errors are `{"error": "..."}`.
+//!
+//! | Route                        | Body                              | Response              |
+//! | ---------------------------- | --------------------------------- | --------------------- |
+//! | `POST /v1/escrows`           | escrow parameters, `"condition"`? | escrow metadata       |
+//! | `GET /v1/escrows`            | (`?chain=C&from_block=N`)         | escrow summaries      |
+//! | `POST /v1/escrows/status`    | escrow metadata                   | live escrow state     |
+//! | `POST /v1/escrows/finish`    | `{"metadata", "proof_seal"?}`     | escrow metadata       |
+//! | `POST /v1/escrows/cancel`    | `{"metadata"}`                    | escrow metadata       |
+//! | `POST /v1/proofs`            | `{"metadata", "condition"}`       | `{"proof_seal"}`      |
+//! | `GET /v1/metrics`            |                                   | RPC request counters  |
+//!
+//! Create, finish, and cancel accept `?dry_run=true` to return the
+//! simulated transaction instead of broadcasting it. Configured webhooks are
+//! notified of created, proven, finished, and cancelled escrows.
+//!
+//! Endpoints, keys, and escrow contracts come from the chain configurations
+//! the server is started with. A request's `chain_config` only names the
+//! chain, as in `{"chain": "ethereum"}`, and requests setting any other
+//! field of it, such as `rpc_url`, `agent_id`, or `sender_private_id`, are
+//! rejected. Returned metadata carries the same reduced `chain_config`.
+
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use anyhow::Context;
+use axum::extract::{Query, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+use tracing::{info, warn};
+use zescrow_client::{ClientError, Operation, Recipient, RpcMetricsSnapshot};
+use zescrow_core::auth::{self, ApiKeys, API_KEY_HEADER};
+use zescrow_core::interface::ChainConfig;
+use zescrow_core::{Chain, Condition, EscrowMetadata, EscrowParams, ExecutionState};
+
+use crate::webhook::{self, Event, Webhooks};
+use crate::{prove_escrow, ClientOpts};
+
+/// Environment variable holding comma-separated API keys.
+pub const API_KEYS_VAR: &str = "ZESCROW_API_KEYS";
+
+/// Shared server state.
+struct Server {
+    opts: ClientOpts,
+    /// Configurations of the chains served, at most one per chain.
+    chains: Vec<ChainConfig>,
+    /// Recipient key used to finish escrows.
+    recipient: Option<Recipient>,
+    /// Accepted API keys.
+    api_keys: ApiKeys,
+    /// Endpoints notified of escrow state transitions.
+    webhooks: Webhooks,
+}
+
+/// Query parameters of create, finish, and cancel.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct DryRun {
+    dry_run: bool,
+}
+
+/// Query parameters of the list route.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default)]
+struct ListQuery {
+    /// Chain to list escrows on; defaults to the first chain served.
+    chain: Option<Chain>,
+    /// Block to start scanning escrow events from (Ethereum only).
+    from_block: u64,
+}
+
+/// Body of the finish route.
+#[derive(Debug, Deserialize)]
+struct FinishRequest {
+    metadata: EscrowMetadata,
+    /// Hex-encoded seal from `POST /v1/proofs`, for Ethereum escrows with
+    /// conditions.
+    #[serde(default)]
+    proof_seal: Option<String>,
+}
+
+/// Body of the cancel route.
+#[derive(Debug, Deserialize)]
+struct CancelRequest {
+    metadata: EscrowMetadata,
+}
+
+/// Body of the proof route.
+#[derive(Debug, Deserialize)]
+struct ProofRequest {
+    metadata: EscrowMetadata,
+    /// Condition with its fulfillment.
+    condition: Condition,
+}
+
+/// Error response, rendered as `{"error": "..."}`.
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self {
+            status: StatusCode::BAD_REQUEST,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<ClientError> for ApiError {
+    fn from(e: ClientError) -> Self {
+        let status = match &e {
+            ClientError::UnsupportedChain(_)
+            | ClientError::ConfigMismatch { .. }
+            | ClientError::Keypair(_)
+            | ClientError::Serialization(_)
+            | ClientError::UrlParse(_)
+            | ClientError::AddressParse(_)
+            | ClientError::InvalidChainOperation(_)
+            | ClientError::AssetOverflow
+            | ClientError::Core(_) => StatusCode::BAD_REQUEST,
+            ClientError::AddressBlocked(_) => StatusCode::FORBIDDEN,
+            _ => StatusCode::BAD_GATEWAY,
+        };
+        Self {
+            status,
+            message: e.to_string(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for ApiError {
+    fn from(e: anyhow::Error) -> Self {
+        Self {
+            status: StatusCode::INTERNAL_SERVER_ERROR,
+            message: format!("{e:#}"),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if self.status.is_server_error() {
+            warn!(status = %self.status, error = %self.message, "Request failed");
+        }
+        let body = Json(serde_json::json!({ "error": self.message }));
+        (self.status, body).into_response()
+    }
+}
+
+type ApiResult = Result<Response, ApiError>;
+
+impl Server {
+    /// Returns the server's configuration of `chain`.
+    fn chain_config(&self, chain: Chain) -> Result<&ChainConfig, ApiError> {
+        self.chains
+            .iter()
+            .find(|config| config.chain.as_ref() == chain.as_ref())
+            .ok_or_else(|| {
+                ApiError::bad_request(format!("the server does not serve {}", chain.as_ref()))
+            })
+    }
+
+    /// Parses a request body, replacing the chain configuration at
+    /// `pointer`, which may only name the chain, with the server's
+    /// configuration of that chain.
+    fn parse<T: DeserializeOwned>(&self, mut body: Value, pointer: &str) -> Result<T, ApiError> {
+        let config = body
+            .pointer_mut(pointer)
+            .and_then(Value::as_object_mut)
+            .ok_or_else(|| {
+                ApiError::bad_request(format!("missing chain configuration at {pointer}"))
+            })?;
+        if let Some(field) = config.keys().find(|field| *field != "chain") {
+            return Err(ApiError::bad_request(format!(
+                "`{field}` of the chain configuration is set by the server, not requests"
+            )));
+        }
+        let chain = config
+            .get("chain")
+            .cloned()
+            .ok_or_else(|| ApiError::bad_request("the chain configuration names no chain"))
+            .and_then(|chain| {
+                serde_json::from_value(chain)
+                    .map_err(|e| ApiError::bad_request(format!("invalid chain: {e}")))
+            })?;
+        let config =
+            serde_json::to_value(self.chain_config(chain)?).map_err(anyhow::Error::from)?;
+        if let Some(requested) = body.pointer_mut(pointer) {
+            *requested = config;
+        }
+        serde_json::from_value(body).map_err(|e| ApiError::bad_request(e.to_string()))
+    }
+}
+
+/// Renders escrow metadata for a response, with its chain configuration
+/// reduced to the chain so keys and endpoints are not disclosed.
+fn redacted(metadata: &EscrowMetadata) -> Result<Json<Value>, ApiError> {
+    let body = webhook::redact(metadata).map_err(anyhow::Error::from)?;
+    Ok(Json(body))
+}
+
+/// Serves the API on `listen` until interrupted.
+///
+/// `api_keys` falls back to the comma-separated keys in [`API_KEYS_VAR`];
+/// `chains` are the configurations of the chains served, and `recipient`
+/// is the key escrows are finished with. State transitions are reported to
+/// `webhooks`.
+///
+/// # Errors
+///
+/// Returns an error if no API key or chain is configured, a chain is
+/// configured twice, or the address cannot be bound.
+pub async fn run(
+    opts: ClientOpts,
+    listen: SocketAddr,
+    api_keys: Vec<String>,
+    chains: Vec<ChainConfig>,
+    recipient: Option<Recipient>,
+    webhooks: Webhooks,
+) -> anyhow::Result<()> {
+    let api_keys = ApiKeys::from_args_or_env(&api_keys, API_KEYS_VAR);
+    if api_keys.is_empty() {
+        anyhow::bail!("no API key configured; pass --api-key or set {API_KEYS_VAR}");
+    }
+    if chains.is_empty() {
+        anyhow::bail!("no chain configured; pass --chain-profile or select a profile");
+    }
+    for (index, config) in chains.iter().enumerate() {
+        let chain = config.chain.as_ref();
+        if chains[..index]
+            .iter()
+            .any(|other| other.chain.as_ref() == chain)
+        {
+            anyhow::bail!("{chain} is configured more than once");
+        }
+    }
+
+    let server = Arc::new(Server {
+        opts,
+        chains,
+        recipient,
+        api_keys,
+        webhooks,
+    });
+    let app = Router::new()
+        .route("/v1/escrows", post(create).get(list))
+        .route("/v1/escrows/status", post(status))
+        .route("/v1/escrows/finish", post(finish))
+        .route("/v1/escrows/cancel", post(cancel))
+        .route("/v1/proofs", post(proof))
+        .route("/v1/metrics", get(metrics))
+        .route_layer(middleware::from_fn_with_state(
+            server.clone(),
+            require_api_key,
+        ))
+        .route("/health", get(|| async { "ok" }))
+        .with_state(server);
+
+    let listener = tokio::net::TcpListener::bind(listen)
+        .await
+        .with_context(|| format!("binding {listen}"))?;
+    info!(%listen, "Serving the escrow API");
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await?;
+    info!("Escrow API stopped");
+    Ok(())
+}
+
+/// Rejects requests without a configured API key.
+async fn require_api_key(
+    State(server): State<Arc<Server>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let headers = request.headers();
+    let key = auth::presented_key(
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok()),
+        headers
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    );
+    match key {
+        Some(key) if server.api_keys.accepts(key) => next.run(request).await,
+        _ => ApiError {
+            status: StatusCode::UNAUTHORIZED,
+            message: "missing or invalid API key".into(),
+        }
+        .into_response(),
+    }
+}
+
+async fn create(
+    State(server): State<Arc<Server>>,
+    Query(query): Query<DryRun>,
+    Json(mut body): Json<Value>,
+) -> ApiResult {
+    // Ethereum, Tron, and Hedera escrows with conditions store the
+    // condition's fingerprint.
+    let condition: Option<Condition> = body
+        .as_object_mut()
+        .and_then(|body| body.remove("condition"))
+        .map(serde_json::from_value)
+        .transpose()
+        .map_err(|e| ApiError::bad_request(format!("invalid condition: {e}")))?;
+    let params: EscrowParams = server.parse(body, "/chain_config")?;
+    let mut builder = server.opts.builder(&params.chain_config);
+    if let Some(condition) = &condition {
+        builder = builder.condition(condition);
+    }
+    let client = builder.build().await?;
+    if query.dry_run || server.opts.dry_run {
+        let simulation = client.simulate(Operation::Create(&params)).await?;
+        return Ok(Json(simulation).into_response());
+    }
+    let metadata = client.create_escrow(&params).await?;
+    server
+        .webhooks
+        .notify_in_background(Event::Created, metadata.clone());
+    Ok((StatusCode::CREATED, redacted(&metadata)?).into_response())
+}
+
+async fn list(State(server): State<Arc<Server>>, Query(query): Query<ListQuery>) -> ApiResult {
+    let config = match query.chain {
+        Some(chain) => server.chain_config(chain)?,
+        None => &server.chains[0],
+    };
+    let mut builder = server.opts.builder(config).from_block(query.from_block);
+    if let Some(recipient) = &server.recipient {
+        builder = builder.recipient(recipient.clone());
+    }
+    let escrows = builder.build().await?.list_escrows().await?;
+    Ok(Json(escrows).into_response())
+}
+
+async fn status(State(server): State<Arc<Server>>, Json(body): Json<Value>) -> ApiResult {
+    let metadata: EscrowMetadata = server.parse(body, "/params/chain_config")?;
+    let client = server
+        .opts
+        .builder(&metadata.params.chain_config)
+        .build()
+        .await?;
+    let status = client.get_escrow_state(&metadata).await?;
+    Ok(Json(status).into_response())
+}
+
+async fn finish(
+    State(server): State<Arc<Server>>,
+    Query(query): Query<DryRun>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let request: FinishRequest = server.parse(body, "/metadata/params/chain_config")?;
+    let metadata = request.metadata;
+    let recipient = server
+        .recipient
+        .clone()
+        .ok_or_else(|| ApiError::bad_request("the server has no recipient key to finish with"))?;
+    let mut builder = server
+        .opts
+        .builder(&metadata.params.chain_config)
+        .recipient(recipient);
+    if let Some(seal) = &request.proof_seal {
+        let seal = hex::decode(seal.trim_start_matches("0x"))
+            .map_err(|e| ApiError::bad_request(format!("invalid proof_seal: {e}")))?;
+        builder = builder.proof_seal(seal);
+    }
+    let client = builder.build().await?;
+
+    if query.dry_run || server.opts.dry_run {
+        let simulation = client.simulate(Operation::Finish(&metadata)).await?;
+        return Ok(Json(simulation).into_response());
+    }
+    client.finish_escrow(&metadata).await?;
+    let metadata = EscrowMetadata {
+        state: ExecutionState::Released,
+        ..metadata
+    };
+    server
+        .webhooks
+        .notify_in_background(Event::Finished, metadata.clone());
+    Ok(redacted(&metadata)?.into_response())
+}
+
+async fn cancel(
+    State(server): State<Arc<Server>>,
+    Query(query): Query<DryRun>,
+    Json(body): Json<Value>,
+) -> ApiResult {
+    let request: CancelRequest = server.parse(body, "/metadata/params/chain_config")?;
+    let metadata = request.metadata;
+    let client = server
+        .opts
+        .builder(&metadata.params.chain_config)
+        .build()
+        .await?;
+
+    if query.dry_run || server.opts.dry_run {
+        let simulation = client.simulate(Operation::Cancel(&metadata)).await?;
+        return Ok(Json(simulation).into_response());
+    }
+    client.cancel_escrow(&metadata).await?;
+    let metadata = EscrowMetadata {
+        state: ExecutionState::Refunded,
+        ..metadata
+    };
+    server
+        .webhooks
+        .notify_in_background(Event::Cancelled, metadata.clone());
+    Ok(redacted(&metadata)?.into_response())
+}
+
+async fn proof(State(server): State<Arc<Server>>, Json(body): Json<Value>) -> ApiResult {
+    let request: ProofRequest = server.parse(body, "/metadata/params/chain_config")?;
+    let seal = prove_escrow(&server.opts, &request.metadata, request.condition).await?;
+    server
+        .webhooks
+        .notify_in_background(Event::Proven, request.metadata);
+    let seal = seal.map(|seal| format!("0x{}", hex::encode(seal)));
+    Ok(Json(serde_json::json!({ "proof_seal": seal })).into_response())
+}
+
+/// Returns the RPC counters of every client the server has built.
+async fn metrics(State(server): State<Arc<Server>>) -> Json<RpcMetricsSnapshot> {
+    Json(server.opts.rpc.metrics.snapshot())
+}
@@ -0,0 +1,209 @@
+//! `export` and `import` commands, handing an escrow to another party as a
+//! QR code or a `zescrow:` payload instead of a metadata file.
+//!
+//! The payload is `zescrow:` followed by the unpadded base64url encoding of
+//! the escrow metadata in CBOR. Local connection settings (keys, RPC
+//! endpoints, smart account) are left out: the importer supplies their own.
+
+use std::io::Read;
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use qrcode::render::unicode::Dense1x2;
+use qrcode::QrCode;
+use serde::Serialize;
+use tracing::{info, warn};
+use zescrow_core::interface::{save_escrow_data, ChainConfig};
+use zescrow_core::EscrowMetadata;
+
+use crate::output::{print_json, OutputFormat};
+use crate::ClientOpts;
+
+/// Scheme prefix of shared escrow payloads.
+pub const PAYLOAD_PREFIX: &str = "zescrow:";
+
+/// Minimum side length, in pixels, of QR code images.
+const QR_IMAGE_SIZE: u32 = 512;
+
+/// JSON output of `export`.
+#[derive(Serialize)]
+struct Exported<'a> {
+    payload: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    png: Option<&'a Path>,
+}
+
+/// Encodes `metadata` as a `zescrow:` payload, without its local
+/// connection settings.
+pub fn encode(metadata: &EscrowMetadata) -> anyhow::Result<String> {
+    let shared = without_local_settings(metadata);
+    let mut cbor = Vec::new();
+    ciborium::into_writer(&shared, &mut cbor).context("encoding escrow metadata as CBOR")?;
+    Ok(format!("{PAYLOAD_PREFIX}{}", URL_SAFE_NO_PAD.encode(cbor)))
+}
+
+/// Returns a copy of `metadata` without its local connection settings:
+/// keys, RPC endpoints, smart account, zkSync paymaster, Tron API key and
+/// fee limit, and Hedera mirror node.
+pub fn without_local_settings(metadata: &EscrowMetadata) -> EscrowMetadata {
+    let mut shared = metadata.clone();
+    let config = &mut shared.params.chain_config;
+    config.sender_private_id.clear();
+    config.rpc_url.clear();
+    config.fallback_rpc_urls.clear();
+    config.pubsub_url = None;
+    config.smart_account = None;
+    config.tron = None;
+    config.zksync = None;
+    config.hedera = None;
+    shared
+}
+
+/// Decodes a `zescrow:` payload.
+pub fn decode(payload: &str) -> anyhow::Result<EscrowMetadata> {
+    let encoded = payload
+        .trim()
+        .strip_prefix(PAYLOAD_PREFIX)
+        .ok_or_else(|| anyhow!("not a zescrow payload: expected a `{PAYLOAD_PREFIX}` prefix"))?;
+    let cbor = URL_SAFE_NO_PAD
+        .decode(encoded)
+        .context("decoding base64 payload")?;
+    ciborium::from_reader(cbor.as_slice()).context("decoding escrow metadata from CBOR")
+}
+
+/// Exports `metadata`: prints its payload, or with `qr` renders it as a QR
+/// code in the terminal, and writes a PNG of the QR code to `png`.
+pub fn export(
+    metadata: &EscrowMetadata,
+    qr: bool,
+    png: Option<&Path>,
+    output: OutputFormat,
+) -> anyhow::Result<()> {
+    let payload = encode(metadata)?;
+    let code = QrCode::new(payload.as_bytes()).context("payload too large for a QR code")?;
+    if let Some(path) = png {
+        code.render::<image::Luma<u8>>()
+            .min_dimensions(QR_IMAGE_SIZE, QR_IMAGE_SIZE)
+            .build()
+            .save(path)
+            .with_context(|| format!("writing QR code to {}", path.display()))?;
+        info!("Saved QR code to {}", path.display());
+    }
+    match output {
+        OutputFormat::Text if qr => {
+            // Light modules on a dark terminal background scan inverted;
+            // draw dark modules as spaces instead.
+            let rendered = code
+                .render::<Dense1x2>()
+                .dark_color(Dense1x2::Light)
+                .light_color(Dense1x2::Dark)
+                .build();
+            println!("{rendered}");
+        }
+        OutputFormat::Text => println!("{payload}"),
+        OutputFormat::Json => print_json(&Exported {
+            payload: &payload,
+            png,
+        })?,
+    }
+    Ok(())
+}
+
+/// Reads a payload from `payload` (`-` for stdin), or from the QR code in
+/// the image at `png`.
+pub fn read_payload(payload: Option<&str>, png: Option<&Path>) -> anyhow::Result<String> {
+    match (payload, png) {
+        (_, Some(path)) => {
+            info!("Scanning QR code in {}", path.display());
+            let luma = image::open(path)
+                .with_context(|| format!("opening {}", path.display()))?
+                .to_luma8();
+            let mut prepared = rqrr::PreparedImage::prepare(luma);
+            let grids = prepared.detect_grids();
+            let grid = grids
+                .first()
+                .ok_or_else(|| anyhow!("no QR code found in {}", path.display()))?;
+            let (_, content) = grid.decode().context("decoding QR code")?;
+            Ok(content)
+        }
+        (Some("-"), None) => {
+            let mut content = String::new();
+            std::io::stdin()
+                .read_to_string(&mut content)
+                .context("reading payload from stdin")?;
+            Ok(content)
+        }
+        (Some(payload), None) => Ok(payload.to_owned()),
+        (None, None) => bail!("`import` requires a payload or --png"),
+    }
+}
+
+/// Imports a shared escrow with the local connection settings of `opts`,
+/// writing its metadata to `out` and recording it in the store.
+pub fn import(opts: &ClientOpts, payload: &str, out: &Path) -> anyhow::Result<()> {
+    let mut metadata = decode(payload)?;
+    connect(opts, &mut metadata)?;
+
+    info!("Saving metadata to {}", out.display());
+    save_escrow_data(out, &metadata)?;
+    let id = opts
+        .store
+        .as_ref()
+        .map(|store| store.insert(&metadata, None))
+        .transpose()?;
+    match opts.output {
+        OutputFormat::Text => {
+            let params = &metadata.params;
+            println!(
+                "Imported {} escrow of {} from {} to {}",
+                params.chain_config.chain.as_ref(),
+                params.asset,
+                params.sender,
+                params.recipient
+            );
+            println!("Metadata written to {}", out.display());
+            if let Some(id) = id {
+                println!("Recorded as escrow {id} in the store");
+            }
+        }
+        OutputFormat::Json => print_json(&metadata)?,
+    }
+    Ok(())
+}
+
+/// Fills the connection settings left out of a shared escrow from the
+/// chain configuration of `opts`, which must be for the same chain.
+pub fn connect(opts: &ClientOpts, metadata: &mut EscrowMetadata) -> anyhow::Result<()> {
+    let local = opts
+        .chain_config()
+        .context("loading the local chain configuration to import the escrow with")?;
+    let shared = &mut metadata.params.chain_config;
+    if shared.chain.as_ref() != local.chain.as_ref() {
+        bail!(
+            "escrow is on {} but the local configuration is for {}; select a matching --profile",
+            shared.chain.as_ref(),
+            local.chain.as_ref()
+        );
+    }
+    if !local.agent_id.eq_ignore_ascii_case(&shared.agent_id) {
+        warn!(
+            shared = %shared.agent_id,
+            local = %local.agent_id,
+            "Escrow uses a different program or contract than the local configuration"
+        );
+    }
+    *shared = ChainConfig {
+        chain: shared.chain,
+        agent_id: std::mem::take(&mut shared.agent_id),
+        network: shared.network.take(),
+        cosmos: shared.cosmos.take().or_else(|| local.cosmos.clone()),
+        substrate: shared.substrate.take().or_else(|| local.substrate.clone()),
+        cardano: shared.cardano.take().or_else(|| local.cardano.clone()),
+        tron: shared.tron.take().or_else(|| local.tron.clone()),
+        custom_chain: shared.custom_chain.take(),
+        ..local
+    };
+    Ok(())
+}
@@ -0,0 +1,67 @@
+//! Transaction simulation for dry runs.
+
+use std::fmt;
+
+use serde::Serialize;
+use zescrow_core::{BigNumber, EscrowMetadata, EscrowParams};
+
+/// An escrow operation to simulate with
+/// [`ZescrowClient::simulate`](crate::ZescrowClient::simulate).
+#[derive(Debug, Clone, Copy)]
+pub enum Operation<'a> {
+    /// Create an escrow from parameters.
+    Create(&'a EscrowParams),
+    /// Release an existing escrow to its recipient.
+    Finish(&'a EscrowMetadata),
+    /// Refund an existing escrow to its sender.
+    Cancel(&'a EscrowMetadata),
+}
+
+/// Outcome of simulating a transaction without broadcasting it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Simulation {
+    /// Contract method or program instruction simulated.
+    pub operation: &'static str,
+    /// Why the transaction would fail (e.g., the decoded revert or program
+    /// error), or `None` if it would succeed.
+    pub error: Option<String>,
+    /// Program logs emitted during simulation (Solana), or the contract's
+    /// debug messages (Substrate).
+    pub logs: Vec<String>,
+    /// Gas (Ethereum, Cosmos), compute units (Solana), reference-time
+    /// weight (Substrate), script execution steps (Cardano), or energy
+    /// (Tron) the transaction would use.
+    pub units: Option<u64>,
+    /// Upper bound on the fee, in wei, lamports, the Cosmos fee denom,
+    /// planck, lovelace, sun, or tinybars.
+    pub fee: Option<BigNumber>,
+}
+
+impl Simulation {
+    /// Whether the transaction would succeed.
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+impl fmt::Display for Simulation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.error {
+            None => write!(f, "{} would succeed", self.operation)?,
+            Some(error) => write!(f, "{} would fail: {error}", self.operation)?,
+        }
+        if let Some(units) = self.units {
+            write!(f, "\nUnits:   {units}")?;
+        }
+        if let Some(fee) = &self.fee {
+            write!(f, "\nMax fee: {fee}")?;
+        }
+        if !self.logs.is_empty() {
+            write!(f, "\nLogs:")?;
+            for line in &self.logs {
+                write!(f, "\n  {line}")?;
+            }
+        }
+        Ok(())
+    }
+}
@@ -4,29 +4,161 @@
 //! program. Supports creating, finishing, and canceling escrows on Solana.
 
 use core::str::FromStr;
-use std::path::{Path, PathBuf};
+use std::fmt;
+use std::path::Path;
+use std::sync::Arc;
 
-use anchor_lang::{system_program, InstructionData};
-use escrow::{instruction as escrow_instruction, CreateEscrowArgs, ESCROW};
+use anchor_lang::{AccountDeserialize, Discriminator};
+use escrow_cpi::{
+    instruction as escrow_instruction, pda, BridgeTrigger, ComplianceList, CreateEscrowArgs,
+    CreateStakeEscrowArgs, EscrowAccount, StakeEscrowAccount,
+};
+use futures::channel::mpsc;
+use futures::StreamExt;
 use num_traits::ToPrimitive;
-use solana_client::rpc_client::RpcClient;
-use solana_sdk::instruction::{AccountMeta, Instruction};
+use sha2::{Digest, Sha256};
+use solana_account_decoder_client_types::UiAccountEncoding;
+use solana_client::client_error::ClientErrorKind;
+use solana_client::nonblocking::pubsub_client::PubsubClient;
+use solana_client::nonce_utils;
+use solana_client::rpc_client::{GetConfirmedSignaturesForAddress2Config, RpcClient};
+use solana_client::rpc_config::{
+    RpcAccountInfoConfig, RpcBlockConfig, RpcProgramAccountsConfig, RpcSimulateTransactionConfig,
+    RpcTransactionLogsConfig, RpcTransactionLogsFilter,
+};
+use solana_client::rpc_filter::{Memcmp, RpcFilterType};
+use solana_client::rpc_request::RpcError;
+use solana_client::rpc_response::RpcLogsResponse;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::hash::Hash;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::{v0, AddressLookupTableAccount, VersionedMessage};
 use solana_sdk::pubkey::Pubkey;
-use solana_sdk::signature::{read_keypair_file, Keypair};
+use solana_sdk::signature::{read_keypair_file, Keypair, Signature};
 use solana_sdk::signer::Signer;
-use solana_sdk::transaction::Transaction;
-use tracing::{debug, info, trace};
-use zescrow_core::interface::ChainConfig;
-use zescrow_core::{EscrowMetadata, EscrowParams, ExecutionState};
+use solana_sdk::system_instruction;
+use solana_sdk::transaction::VersionedTransaction;
+use solana_transaction_status_client_types::TransactionDetails;
+use tracing::{debug, info, trace, warn};
+use url::Url;
+use zescrow_core::condition::cross_chain::{Observation, RemoteClaim};
+use zescrow_core::interface::{ChainConfig, ChainState};
+use zescrow_core::{
+    AssetKind, BigNumber, Chain, ChainMetadata, EscrowId, EscrowMetadata, EscrowParams,
+    ExecutionState, ObservedBlock,
+};
 
 use super::Agent;
 use crate::error::ClientError;
-use crate::Result;
+use crate::estimate::{Cost, LifecycleEstimate};
+use crate::offline::{Broadcast, OfflineTransaction};
+use crate::pending::{PendingTransaction, WaitConfig};
+use crate::rpc::solana::FailoverSender;
+use crate::rpc::RpcPolicy;
+use crate::simulation::{Operation, Simulation};
+use crate::watch::{UpdateKind, UpdateStream};
+use crate::{EscrowStatus, EscrowSummary, Result};
+
+pub mod bridge;
+pub mod compute_budget;
+pub mod confirmation;
+mod events;
+pub mod lookup_table;
+
+pub use compute_budget::{ComputeBudget, PriorityFee, PriorityFees};
+pub use confirmation::ConfirmationConfig;
+pub use events::{transaction_events, ProgramEvent};
 
 // Instruction names for logging.
 const CREATE_ESCROW: &str = "create_escrow";
 const FINISH_ESCROW: &str = "finish_escrow";
+const FINISH_ESCROW_WITH_VAA: &str = "finish_escrow_with_vaa";
 const CANCEL_ESCROW: &str = "cancel_escrow";
+const CREATE_STAKE_ESCROW: &str = "create_stake_escrow";
+const GET_ESCROW: &str = "get_escrow";
+const LIST_ESCROWS: &str = "list_escrows";
+const WATCH: &str = "watch";
+const WAIT_FOR_TRANSACTION: &str = "wait_for_transaction";
+const BROADCAST_TRANSACTION: &str = "broadcast_transaction";
+const OBSERVE: &str = "observe";
+const GET_BLOCK: &str = "get_block";
+
+/// JSON-RPC error codes of slots without a block: skipped, or skipped and
+/// pruned to long-term storage.
+const SLOT_SKIPPED: [i64; 2] = [-32007, -32009];
+
+/// Offset of the `sender` field in escrow accounts, after the discriminator.
+const SENDER_OFFSET: usize = 8;
+/// Offset of the `recipient` field in escrow accounts.
+const RECIPIENT_OFFSET: usize = SENDER_OFFSET + 32;
+
+/// Shared handle to a Solana signer.
+///
+/// Wraps any [`Signer`], so keys held by a remote signing service can be
+/// used in place of a local keypair file.
+#[derive(Clone)]
+pub struct SolanaSigner(Arc<dyn Signer + Send + Sync>);
+
+impl SolanaSigner {
+    /// Wraps `signer`.
+    pub fn new(signer: impl Signer + Send + Sync + 'static) -> Self {
+        Self(Arc::new(signer))
+    }
+
+    /// Reads a keypair file (e.g., `~/.config/solana/id.json`).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn from_keypair_file(path: impl AsRef<Path>) -> Result<Self> {
+        read_keypair_file(path.as_ref())
+            .map(Self::new)
+            .map_err(|e| {
+                ClientError::Keypair(format!(
+                    "failed to load keypair {}: {}",
+                    path.as_ref().display(),
+                    e
+                ))
+            })
+    }
+
+    /// Loads a signer from a `sender_private_id`-style string: a keypair
+    /// file path, a keypair as a JSON byte array (as resolved from a
+    /// [secret reference](crate::secrets)), or `keystore:<name>` for a key
+    /// in the encrypted [`Keystore`](crate::keystore::Keystore).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key cannot be read, parsed, or decrypted.
+    pub fn from_id(id: &str) -> Result<Self> {
+        if id.trim_start().starts_with('[') {
+            let bytes: Vec<u8> = serde_json::from_str(id)
+                .map_err(|e| ClientError::Keypair(format!("invalid keypair bytes: {e}")))?;
+            return Keypair::try_from(bytes.as_slice())
+                .map(Self::new)
+                .map_err(|e| ClientError::Keypair(format!("invalid keypair: {e}")));
+        }
+        crate::keystore::solana_signer(id).unwrap_or_else(|| Self::from_keypair_file(id))
+    }
+
+    /// Returns the signer's public key.
+    pub fn pubkey(&self) -> Pubkey {
+        self.0.pubkey()
+    }
+
+    /// Returns the signer as a trait object.
+    pub(crate) fn as_signer(&self) -> &dyn Signer {
+        &*self.0
+    }
+}
+
+impl fmt::Debug for SolanaSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SolanaSigner")
+            .field(&self.0.pubkey())
+            .finish()
+    }
+}
 
 /// Solana blockchain agent for escrow operations.
 ///
@@ -35,12 +167,26 @@ const CANCEL_ESCROW: &str = "cancel_escrow";
 pub struct SolanaAgent {
     /// JSON-RPC client for the Solana cluster.
     client: RpcClient,
-    /// Keypair of the escrow creator (sender).
-    sender_keypair: Keypair,
-    /// Optional keypair of the escrow beneficiary (recipient).
-    recipient_keypair: Option<Keypair>,
+    /// Signer of the escrow creator (sender).
+    sender: SolanaSigner,
+    /// Optional signer of the escrow beneficiary (recipient).
+    recipient: Option<SolanaSigner>,
     /// Program ID of the deployed escrow program.
     escrow_program_id: Pubkey,
+    /// WebSocket endpoint for log subscriptions.
+    pubsub_url: String,
+    /// Compute unit limits and priority fees per operation.
+    priority_fees: PriorityFees,
+    /// Address lookup tables that transactions may refer to accounts in.
+    lookup_tables: Vec<Pubkey>,
+    /// Commitment and confirmation strategy of transactions.
+    confirmation: ConfirmationConfig,
+    /// Timeout and polling interval of confirmation waits.
+    wait: WaitConfig,
+    /// Durable nonce account that offline transactions are built against.
+    nonce_account: Option<Pubkey>,
+    /// Wormhole message that lets anyone finish the escrows created.
+    bridge_trigger: Option<BridgeTrigger>,
 }
 
 impl SolanaAgent {
@@ -49,62 +195,304 @@ impl SolanaAgent {
     /// # Arguments
     ///
     /// * `config` - Chain configuration containing RPC URL and sender keypair path
-    /// * `recipient_keypair_path` - Optional path to recipient keypair for finish operations
+    /// * `sender` - Optional signer overriding `sender_private_id`
+    /// * `recipient` - Optional recipient signer for finish operations
+    /// * `rpc` - Retry settings and metrics for RPC requests, which fail
+    ///   over to `fallback_rpc_urls` when `rpc_url` is unavailable
+    /// * `confirmation` - Commitment that reads and confirmations use, and
+    ///   how long and how persistently to wait for transactions to land
     ///
     /// # Errors
     ///
     /// Returns an error if:
-    /// - Keypair files cannot be read
+    /// - The sender keypair file cannot be read
     /// - Program ID parsing fails
+    /// - No WebSocket URL is configured and none can be derived from the RPC URL
     pub async fn new(
         config: &ChainConfig,
-        recipient_keypair_path: Option<PathBuf>,
+        sender: Option<SolanaSigner>,
+        recipient: Option<SolanaSigner>,
+        rpc: RpcPolicy,
+        confirmation: ConfirmationConfig,
     ) -> Result<Self> {
         let ChainConfig {
             rpc_url,
+            fallback_rpc_urls,
             sender_private_id,
             agent_id,
+            pubsub_url,
             ..
         } = config;
 
-        let sender_keypair = Self::load_keypair(sender_private_id, "sender")?;
-        debug!(sender = %sender_keypair.pubkey(), "Loaded sender keypair");
-
-        let recipient_keypair = recipient_keypair_path
-            .map(|path| Self::load_keypair(&path, "recipient"))
-            .transpose()?;
+        let sender = match sender {
+            Some(sender) => sender,
+            None => SolanaSigner::from_id(sender_private_id)?,
+        };
+        debug!(sender = %sender.0.pubkey(), "Loaded sender signer");
 
-        if let Some(ref kp) = recipient_keypair {
-            debug!(recipient = %kp.pubkey(), "Loaded recipient keypair");
+        if let Some(ref recipient) = recipient {
+            debug!(recipient = %recipient.0.pubkey(), "Loaded recipient signer");
         }
 
         let escrow_program_id =
             Self::parse_pubkey(agent_id).map_err(|e| ClientError::solana("parse_program_id", e))?;
         info!(%escrow_program_id, "Using escrow program");
 
+        let pubsub_url = match pubsub_url {
+            Some(url) => url.clone(),
+            None => websocket_url(rpc_url)?,
+        };
+
         Ok(Self {
-            client: RpcClient::new(rpc_url),
-            sender_keypair,
-            recipient_keypair,
+            client: FailoverSender::new(rpc_url, fallback_rpc_urls, rpc)
+                .into_client(confirmation.commitment_config()),
+            sender,
+            recipient,
             escrow_program_id,
+            pubsub_url,
+            priority_fees: PriorityFees::default(),
+            lookup_tables: Vec::new(),
+            confirmation,
+            wait: WaitConfig::default(),
+            nonce_account: None,
+            bridge_trigger: None,
         })
     }
 
-    /// Reads a Solana keypair from a file path.
-    fn load_keypair(path: impl AsRef<Path>, name: &str) -> Result<Keypair> {
-        read_keypair_file(path.as_ref())
-            .map_err(|e| ClientError::Keypair(format!("failed to load {} keypair: {}", name, e)))
+    /// Stops waiting for confirmation after [`WaitConfig::timeout`],
+    /// failing with [`ClientError::Pending`], and polls at
+    /// [`WaitConfig::poll_interval`].
+    pub fn with_wait(mut self, wait: WaitConfig) -> Self {
+        self.wait = wait;
+        self
+    }
+
+    /// Prepends compute budget instructions setting the unit limit and
+    /// priority fee of each operation's transactions.
+    pub fn with_priority_fees(mut self, fees: PriorityFees) -> Self {
+        self.priority_fees = fees;
+        self
+    }
+
+    /// Compiles transactions against the address lookup tables at `tables`,
+    /// so accounts held in them take one byte each instead of 32.
+    pub fn with_lookup_tables(mut self, tables: Vec<Pubkey>) -> Self {
+        self.lookup_tables = tables;
+        self
+    }
+
+    /// Builds offline transactions against the durable nonce in `account`
+    /// instead of a recent blockhash, so they stay valid until signed and
+    /// broadcast. The nonce authority must be the transaction's fee payer.
+    pub fn with_durable_nonce(mut self, account: Pubkey) -> Self {
+        self.nonce_account = Some(account);
+        self
+    }
+
+    /// Creates lamport escrows that anyone can also finish with a posted
+    /// Wormhole VAA matching `trigger` (see [`bridge`]).
+    pub fn with_bridge_trigger(mut self, trigger: BridgeTrigger) -> Self {
+        self.bridge_trigger = Some(trigger);
+        self
+    }
+
+    /// Creates an address lookup table holding `addresses`, owned and paid
+    /// for by the sender, and returns its address.
+    ///
+    /// The table is created and extended with up to
+    /// [`lookup_table::MAX_ADDRESSES_PER_EXTEND`] addresses in one
+    /// transaction, then extended with the rest in further transactions.
+    /// It can be used from the slot after its last extension.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if any of the transactions fails.
+    pub fn create_lookup_table(&self, addresses: &[Pubkey]) -> Result<Pubkey> {
+        let authority = self.sender.0.pubkey();
+        let recent_slot = self
+            .client
+            .get_slot_with_commitment(CommitmentConfig::finalized())
+            .map_err(|e| ClientError::solana(lookup_table::LOOKUP_TABLE, e))?;
+        let (create, table) = lookup_table::create_instruction(&authority, &authority, recent_slot);
+        info!(%table, count = addresses.len(), "Creating address lookup table");
+
+        let mut extends =
+            lookup_table::extend_instructions(&table, &authority, &authority, addresses)
+                .into_iter();
+        let first: Vec<Instruction> = std::iter::once(create).chain(extends.next()).collect();
+        for instructions in std::iter::once(first).chain(extends.map(|extend| vec![extend])) {
+            self.send_instructions(
+                &instructions,
+                &authority,
+                &[self.sender.as_signer()],
+                lookup_table::LOOKUP_TABLE,
+            )?;
+        }
+        info!(%table, "Address lookup table created");
+        Ok(table)
+    }
+
+    /// Returns the accounts of the finish and cancel transactions of the
+    /// escrow in `metadata` that a lookup table can hold: every account
+    /// except the signers, and the programs invoked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the escrow parameters hold invalid addresses.
+    pub fn escrow_accounts(&self, metadata: &EscrowMetadata) -> Result<Vec<Pubkey>> {
+        let instructions = [
+            self.finish_instruction_unchecked(metadata)?,
+            self.cancel_instruction(metadata)?,
+        ];
+        let mut accounts = Vec::new();
+        for instruction in &instructions {
+            let keys = instruction
+                .accounts
+                .iter()
+                .filter(|account| !account.is_signer)
+                .map(|account| account.pubkey)
+                .chain(std::iter::once(instruction.program_id));
+            for key in keys {
+                if !accounts.contains(&key) {
+                    accounts.push(key);
+                }
+            }
+        }
+        Ok(accounts)
     }
 
     /// Derives the escrow PDA from sender and recipient public keys.
     fn derive_escrow_pda(&self, sender: &Pubkey, recipient: &Pubkey) -> Pubkey {
-        let (pda, _bump) = Pubkey::find_program_address(
-            &[ESCROW, sender.as_ref(), recipient.as_ref()],
+        let (pda, _bump) =
+            pda::find_escrow_address_with_program_id(sender, recipient, &self.escrow_program_id);
+        pda
+    }
+
+    /// Derives the stake escrow PDA from sender, recipient, and stake account public keys.
+    fn derive_stake_escrow_pda(
+        &self,
+        sender: &Pubkey,
+        recipient: &Pubkey,
+        stake_account: &Pubkey,
+    ) -> Pubkey {
+        let (pda, _bump) = pda::find_stake_escrow_address_with_program_id(
+            sender,
+            recipient,
+            stake_account,
             &self.escrow_program_id,
         );
         pda
     }
 
+    /// Derives the escrow or stake escrow PDA for `params`.
+    fn escrow_address(&self, params: &EscrowParams) -> Result<Pubkey> {
+        let sender = Self::parse_pubkey(&params.sender)?;
+        let recipient = Self::parse_pubkey(&params.recipient)?;
+        Ok(match params.asset.kind {
+            AssetKind::Stake => {
+                self.derive_stake_escrow_pda(&sender, &recipient, &Self::stake_account(params)?)
+            }
+            _ => self.derive_escrow_pda(&sender, &recipient),
+        })
+    }
+
+    /// Returns the escrow PDA of `metadata`: the address recorded at
+    /// creation if any, otherwise derived from the escrow's seeds.
+    fn escrow_pda(&self, metadata: &EscrowMetadata) -> Result<Pubkey> {
+        match &metadata.chain_metadata {
+            Some(ChainMetadata::Solana { pda, .. }) => Pubkey::from_str(pda).map_err(Into::into),
+            _ => self.escrow_address(&metadata.params),
+        }
+    }
+
+    /// Records the escrow created by transaction `signature`, and its
+    /// chain-agnostic ID if emitted, from the `EscrowEvent` or
+    /// `StakeEscrowEvent` it emitted.
+    fn creation_record(
+        &self,
+        signature: &Signature,
+        operation: &'static str,
+    ) -> Result<(ChainMetadata, Option<EscrowId>)> {
+        // Transactions cannot be fetched below confirmed commitment.
+        let commitment = Some(self.client.commitment())
+            .filter(CommitmentConfig::is_at_least_confirmed)
+            .unwrap_or_else(CommitmentConfig::confirmed);
+        let transaction = self
+            .client
+            .get_transaction_with_config(signature, events::transaction_config(commitment))
+            .map_err(|e| ClientError::solana(operation, e))?;
+        let event = events::transaction_logs(&transaction, operation)?
+            .iter()
+            .filter_map(|line| events::decode(line, &self.escrow_program_id))
+            .find(|event| event.kind == UpdateKind::Created)
+            .ok_or_else(|| ClientError::MissingEvent(format!("EscrowEvent in {signature}")))?;
+        let record = ChainMetadata::Solana {
+            pda: event.escrow.to_string(),
+            bump: event.bump,
+            signature: signature.to_string(),
+            slot: transaction.slot,
+        };
+        Ok((record, event.uid.map(EscrowId)))
+    }
+
+    /// Returns the CAIP-2 ID of the connected cluster: `solana:` followed by
+    /// the first 32 characters of its base58 genesis hash.
+    fn caip2_id(&self) -> Result<String> {
+        let genesis = self
+            .client
+            .get_genesis_hash()
+            .map_err(|e| ClientError::solana(OBSERVE, e))?
+            .to_string();
+        Ok(format!("solana:{}", &genesis[..genesis.len().min(32)]))
+    }
+
+    /// Returns the hash of the finalized block at `slot`.
+    fn finalized_blockhash(&self, slot: u64) -> Result<[u8; 32]> {
+        self.blockhash(slot, CommitmentConfig::finalized(), OBSERVE)?
+            .ok_or_else(|| ClientError::solana(OBSERVE, format!("slot {slot} was skipped")))
+    }
+
+    /// Returns the hash of the block at `slot` as of `commitment`, or `None`
+    /// if the slot was skipped, e.g. because its block was orphaned.
+    fn blockhash(
+        &self,
+        slot: u64,
+        commitment: CommitmentConfig,
+        operation: &'static str,
+    ) -> Result<Option<[u8; 32]>> {
+        let config = RpcBlockConfig {
+            transaction_details: Some(TransactionDetails::None),
+            rewards: Some(false),
+            commitment: Some(commitment),
+            max_supported_transaction_version: Some(0),
+            ..RpcBlockConfig::default()
+        };
+        let block = match self.client.get_block_with_config(slot, config) {
+            Ok(block) => block,
+            Err(e) => match e.kind() {
+                ClientErrorKind::RpcError(RpcError::RpcResponseError { code, .. })
+                    if SLOT_SKIPPED.contains(code) =>
+                {
+                    return Ok(None);
+                }
+                _ => return Err(ClientError::solana(operation, e)),
+            },
+        };
+        let hash =
+            Hash::from_str(&block.blockhash).map_err(|e| ClientError::solana(operation, e))?;
+        Ok(Some(hash.to_bytes()))
+    }
+
+    /// Returns the stake account of a stake escrow asset.
+    fn stake_account(params: &EscrowParams) -> Result<Pubkey> {
+        let id = params
+            .asset
+            .id
+            .as_ref()
+            .ok_or_else(|| ClientError::solana("stake_account", "missing stake account ID"))?;
+        Self::parse_pubkey(id)
+    }
+
     /// Parses a public key from a party's string representation.
     fn parse_pubkey(party: &impl ToString) -> Result<Pubkey> {
         Pubkey::from_str(&party.to_string()).map_err(Into::into)
@@ -118,96 +506,401 @@ impl SolanaAgent {
         escrow_pda: Pubkey,
         args: CreateEscrowArgs,
     ) -> Instruction {
-        Instruction {
-            program_id: self.escrow_program_id,
-            accounts: vec![
-                AccountMeta::new(sender, true),
-                AccountMeta::new_readonly(recipient, false),
-                AccountMeta::new(escrow_pda, false),
-                AccountMeta::new_readonly(system_program::ID, false),
-            ],
-            data: InstructionData::data(&escrow_instruction::CreateEscrow { args }),
-        }
+        escrow_instruction::create_escrow(
+            self.escrow_program_id,
+            sender,
+            recipient,
+            escrow_pda,
+            args,
+        )
     }
 
     /// Builds the finish_escrow instruction.
-    fn build_finish_instruction(&self, recipient: Pubkey, escrow_pda: Pubkey) -> Instruction {
-        Instruction {
-            program_id: self.escrow_program_id,
-            accounts: vec![
-                AccountMeta::new(recipient, true),
-                AccountMeta::new(escrow_pda, false),
-            ],
-            data: InstructionData::data(&escrow_instruction::FinishEscrow {}),
-        }
+    fn build_finish_instruction(
+        &self,
+        recipient: Pubkey,
+        escrow_pda: Pubkey,
+        rent_destination: Option<Pubkey>,
+    ) -> Instruction {
+        escrow_instruction::finish_escrow(
+            self.escrow_program_id,
+            recipient,
+            escrow_pda,
+            rent_destination,
+        )
     }
 
     /// Builds the cancel_escrow instruction.
-    fn build_cancel_instruction(&self, sender: Pubkey, escrow_pda: Pubkey) -> Instruction {
-        Instruction {
-            program_id: self.escrow_program_id,
-            accounts: vec![
-                AccountMeta::new(sender, true),
-                AccountMeta::new(escrow_pda, false),
-            ],
-            data: InstructionData::data(&escrow_instruction::CancelEscrow {}),
+    fn build_cancel_instruction(
+        &self,
+        sender: Pubkey,
+        escrow_pda: Pubkey,
+        rent_destination: Option<Pubkey>,
+    ) -> Instruction {
+        escrow_instruction::cancel_escrow(
+            self.escrow_program_id,
+            sender,
+            escrow_pda,
+            rent_destination,
+        )
+    }
+
+    /// Parses the optional rent destination of a lamport escrow.
+    fn rent_destination(params: &EscrowParams) -> Result<Option<Pubkey>> {
+        params
+            .rent_destination
+            .as_ref()
+            .map(Self::parse_pubkey)
+            .transpose()
+    }
+
+    /// Rejects `parties` that the program's compliance list would block,
+    /// before paying fees for a transaction that is bound to fail.
+    ///
+    /// Passes if the compliance list has not been initialized.
+    fn ensure_permitted(&self, parties: &[Pubkey], operation: &'static str) -> Result<()> {
+        let (compliance_pda, _) =
+            pda::find_compliance_address_with_program_id(&self.escrow_program_id);
+        let Some(list) =
+            self.fetch_program_account::<ComplianceList>(&compliance_pda, operation)?
+        else {
+            trace!(%compliance_pda, "No compliance list configured");
+            return Ok(());
+        };
+        parties
+            .iter()
+            .find(|party| !list.permits(party))
+            .map_or(Ok(()), |party| {
+                Err(ClientError::AddressBlocked(party.to_string()))
+            })
+    }
+
+    /// Fetches and deserializes a program-owned account, or `None` if it
+    /// does not exist (e.g., a settled escrow's closed PDA).
+    fn fetch_program_account<T: AccountDeserialize>(
+        &self,
+        address: &Pubkey,
+        operation: &'static str,
+    ) -> Result<Option<T>> {
+        self.client
+            .get_account_with_commitment(address, self.client.commitment())
+            .map_err(|e| ClientError::solana(operation, e))?
+            .value
+            .filter(|account| account.owner == self.escrow_program_id)
+            .map(|account| {
+                T::try_deserialize(&mut account.data.as_slice())
+                    .map_err(|e| ClientError::solana(operation, e))
+            })
+            .transpose()
+    }
+
+    /// Fetches all program accounts of type `T` whose public key at `offset`
+    /// equals `party`.
+    fn program_accounts_with_party<T: AccountDeserialize + Discriminator>(
+        &self,
+        party: &Pubkey,
+        offset: usize,
+    ) -> Result<Vec<(Pubkey, T)>> {
+        let config = RpcProgramAccountsConfig {
+            filters: Some(vec![
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(0, T::DISCRIMINATOR)),
+                RpcFilterType::Memcmp(Memcmp::new_base58_encoded(offset, party.as_ref())),
+            ]),
+            account_config: RpcAccountInfoConfig {
+                encoding: Some(UiAccountEncoding::Base64),
+                commitment: Some(self.client.commitment()),
+                ..RpcAccountInfoConfig::default()
+            },
+            ..RpcProgramAccountsConfig::default()
+        };
+        self.client
+            .get_program_accounts_with_config(&self.escrow_program_id, config)
+            .map_err(|e| ClientError::solana(LIST_ESCROWS, e))?
+            .into_iter()
+            .map(|(address, account)| {
+                T::try_deserialize(&mut account.data.as_slice())
+                    .map(|escrow| (address, escrow))
+                    .map_err(|e| ClientError::solana(LIST_ESCROWS, e))
+            })
+            .collect()
+    }
+
+    /// Returns the compute budget instructions to place before
+    /// `instruction`, estimating the priority fee from recent fees if so
+    /// configured. Without a configured unit limit, the limit is sized from
+    /// a simulation if `size_limit` is set and a priority fee applies.
+    fn compute_budget_instructions(
+        &self,
+        instruction: &Instruction,
+        payer: &Pubkey,
+        operation: &'static str,
+        size_limit: bool,
+    ) -> Result<Vec<Instruction>> {
+        let budget = self.priority_fees.for_operation(operation);
+        let unit_price = match budget.unit_price {
+            PriorityFee::None => None,
+            PriorityFee::Fixed(price) => Some(price),
+            PriorityFee::Auto { percentile, max } => {
+                let writable: Vec<Pubkey> = instruction
+                    .accounts
+                    .iter()
+                    .filter(|account| account.is_writable)
+                    .map(|account| account.pubkey)
+                    .collect();
+                let price =
+                    compute_budget::estimate_unit_price(&self.client, &writable, percentile, max)
+                        .map_err(|e| ClientError::solana(operation, e))?;
+                debug!(price, percentile, "Estimated priority fee");
+                Some(price)
+            }
+        };
+
+        let unit_limit = match budget.unit_limit {
+            Some(limit) => Some(limit),
+            None if size_limit && unit_price.is_some_and(|price| price > 0) => {
+                // A failing simulation leaves the default limit, and the
+                // send reports the failure.
+                let simulation =
+                    self.simulate_transaction(instruction.clone(), payer, operation)?;
+                simulation
+                    .units
+                    .filter(|_| simulation.error.is_none())
+                    .and_then(compute_budget::unit_limit_for)
+            }
+            None => None,
+        };
+        debug!(?unit_limit, ?unit_price, "Resolved compute budget");
+        Ok(compute_budget::instructions(unit_limit, unit_price))
+    }
+
+    /// Fetches the configured address lookup tables.
+    fn lookup_table_accounts(
+        &self,
+        operation: &'static str,
+    ) -> Result<Vec<AddressLookupTableAccount>> {
+        if self.lookup_tables.is_empty() {
+            return Ok(Vec::new());
         }
+        let accounts = self
+            .client
+            .get_multiple_accounts(&self.lookup_tables)
+            .map_err(|e| ClientError::solana(operation, e))?;
+        self.lookup_tables
+            .iter()
+            .zip(accounts)
+            .map(|(address, account)| {
+                let account = account.ok_or_else(|| {
+                    ClientError::solana(operation, format!("lookup table {address} not found"))
+                })?;
+                lookup_table::decode(*address, &account.data)
+            })
+            .collect()
     }
 
-    /// Signs and submits a transaction.
-    fn submit_transaction(
+    /// Compiles `instructions` into a v0 message paid by `payer`, referring
+    /// to accounts held in the configured lookup tables by index.
+    fn compile_message(
         &self,
-        instruction: Instruction,
+        instructions: &[Instruction],
         payer: &Pubkey,
-        signers: &[&Keypair],
         operation: &'static str,
-    ) -> Result<()> {
+    ) -> Result<v0::Message> {
         let recent_hash = self
             .client
             .get_latest_blockhash()
             .map_err(|e| ClientError::solana(operation, e))?;
-
         debug!(%recent_hash, "Fetched recent blockhash");
+        self.compile_message_at(instructions, payer, recent_hash, operation)
+    }
 
-        let tx =
-            Transaction::new_signed_with_payer(&[instruction], Some(payer), signers, recent_hash);
+    /// Compiles `instructions` into a v0 message with `recent_hash`, paid
+    /// by `payer`.
+    fn compile_message_at(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        recent_hash: Hash,
+        operation: &'static str,
+    ) -> Result<v0::Message> {
+        let tables = self.lookup_table_accounts(operation)?;
+        debug!(tables = tables.len(), "Fetched lookup tables");
 
-        self.client
-            .send_and_confirm_transaction(&tx)
+        v0::Message::try_compile(payer, instructions, &tables, recent_hash)
+            .map_err(|e| ClientError::solana(operation, e))
+    }
+
+    /// Returns the instruction advancing the configured durable nonce,
+    /// which must come first in the transaction, and the nonce to use as
+    /// its blockhash.
+    fn advance_nonce(
+        &self,
+        nonce_account: &Pubkey,
+        authority: &Pubkey,
+        operation: &'static str,
+    ) -> Result<(Instruction, Hash)> {
+        let account = self
+            .client
+            .get_account(nonce_account)
+            .map_err(|e| ClientError::solana(operation, e))?;
+        let data = nonce_utils::data_from_account(&account)
             .map_err(|e| ClientError::solana(operation, e))?;
+        if data.authority != *authority {
+            return Err(ClientError::solana(
+                operation,
+                format!(
+                    "nonce account {nonce_account} is controlled by {}, not the fee payer {authority}",
+                    data.authority
+                ),
+            ));
+        }
+        debug!(%nonce_account, nonce = %data.blockhash(), "Fetched durable nonce");
+        Ok((
+            system_instruction::advance_nonce_account(nonce_account, authority),
+            data.blockhash(),
+        ))
+    }
 
-        Ok(())
+    /// Signs and submits a transaction, preceded by the operation's compute
+    /// budget instructions.
+    fn submit_transaction(
+        &self,
+        instruction: Instruction,
+        payer: &Pubkey,
+        signers: &[&dyn Signer],
+        operation: &'static str,
+    ) -> Result<Signature> {
+        let mut instructions =
+            self.compute_budget_instructions(&instruction, payer, operation, true)?;
+        instructions.push(instruction);
+        self.send_instructions(&instructions, payer, signers, operation)
+    }
+
+    /// Signs and submits a v0 transaction of `instructions`.
+    fn send_instructions(
+        &self,
+        instructions: &[Instruction],
+        payer: &Pubkey,
+        signers: &[&dyn Signer],
+        operation: &'static str,
+    ) -> Result<Signature> {
+        let message = self.compile_message(instructions, payer, operation)?;
+        let tx = VersionedTransaction::try_new(VersionedMessage::V0(message), signers)
+            .map_err(|e| ClientError::solana(operation, e))?;
+
+        let signature = confirmation::send_and_confirm(
+            &self.client,
+            &tx,
+            &self.confirmation,
+            &self.wait,
+            operation,
+        )?;
+        debug!(%signature, "Transaction confirmed");
+
+        Ok(signature)
+    }
+
+    /// Simulates a transaction paid by `payer` without signing or sending
+    /// it. The reported fee includes any fixed or estimated priority fee.
+    fn simulate_transaction(
+        &self,
+        instruction: Instruction,
+        payer: &Pubkey,
+        operation: &'static str,
+    ) -> Result<Simulation> {
+        let mut instructions =
+            self.compute_budget_instructions(&instruction, payer, operation, false)?;
+        instructions.push(instruction);
+
+        let message = self.compile_message(&instructions, payer, operation)?;
+        let fee = self
+            .client
+            .get_fee_for_message(&message)
+            .map_err(|e| ClientError::solana(operation, e))?;
+
+        let config = RpcSimulateTransactionConfig {
+            sig_verify: false,
+            commitment: Some(self.client.commitment()),
+            ..RpcSimulateTransactionConfig::default()
+        };
+        let tx = VersionedTransaction {
+            signatures: vec![
+                Signature::default();
+                usize::from(message.header.num_required_signatures)
+            ],
+            message: VersionedMessage::V0(message),
+        };
+        let result = self
+            .client
+            .simulate_transaction_with_config(&tx, config)
+            .map_err(|e| ClientError::solana(operation, e))?
+            .value;
+
+        Ok(Simulation {
+            operation,
+            error: result.err.map(|e| e.to_string()),
+            logs: result.logs.unwrap_or_default(),
+            units: result.units_consumed,
+            fee: Some(BigNumber::from(fee)),
+        })
     }
 
-    /// Returns the recipient keypair, or an error if not configured.
-    fn recipient_keypair(&self) -> Result<&Keypair> {
-        self.recipient_keypair
+    /// Returns the recipient signer, or an error if not configured.
+    fn recipient_signer(&self) -> Result<&SolanaSigner> {
+        self.recipient
             .as_ref()
             .ok_or_else(|| ClientError::solana(FINISH_ESCROW, "recipient keypair not configured"))
     }
 
-    /// Verifies that a keypair matches the expected public key.
-    fn validate_keypair(keypair: &Keypair, expected: &Pubkey, role: &str) -> Result<()> {
-        (keypair.pubkey() == *expected)
+    /// Verifies that a signer matches the expected public key.
+    fn validate_signer(signer: &SolanaSigner, expected: &Pubkey, role: &str) -> Result<()> {
+        (signer.0.pubkey() == *expected)
             .then_some(())
             .ok_or_else(|| {
                 ClientError::Keypair(format!(
                     "{} keypair mismatch: expected {}, got {}",
                     role,
                     expected,
-                    keypair.pubkey()
+                    signer.0.pubkey()
                 ))
             })
     }
-}
 
-#[async_trait::async_trait]
-impl Agent for SolanaAgent {
-    async fn create_escrow(&self, params: &EscrowParams) -> Result<EscrowMetadata> {
+    /// Builds the instruction creating the escrow for `params`, after
+    /// checking the sender key and the compliance list. Returns the
+    /// instruction and its name.
+    fn create_instruction(&self, params: &EscrowParams) -> Result<(Instruction, &'static str)> {
         let sender = Self::parse_pubkey(&params.sender)?;
-        Self::validate_keypair(&self.sender_keypair, &sender, "sender")?;
+        Self::validate_signer(&self.sender, &sender, "sender")?;
+        self.create_instruction_unsigned(params)
+    }
 
+    /// Builds the instruction creating the escrow for `params`, for a
+    /// sender signing elsewhere, after checking the compliance list.
+    fn create_instruction_unsigned(
+        &self,
+        params: &EscrowParams,
+    ) -> Result<(Instruction, &'static str)> {
+        let sender = Self::parse_pubkey(&params.sender)?;
         let recipient = Self::parse_pubkey(&params.recipient)?;
+        self.ensure_permitted(&[sender, recipient], CREATE_ESCROW)?;
+
+        match params.asset.kind {
+            AssetKind::Stake => self
+                .stake_escrow_instruction(params, sender, recipient)
+                .map(|instruction| (instruction, CREATE_STAKE_ESCROW)),
+            _ => self
+                .lamport_escrow_instruction(params, sender, recipient)
+                .map(|instruction| (instruction, CREATE_ESCROW)),
+        }
+    }
+
+    /// Builds the instruction locking lamports in the escrow PDA.
+    fn lamport_escrow_instruction(
+        &self,
+        params: &EscrowParams,
+        sender: Pubkey,
+        recipient: Pubkey,
+    ) -> Result<Instruction> {
         let amount = params
             .asset
             .amount()
@@ -223,53 +916,652 @@ impl Agent for SolanaAgent {
             amount,
             finish_after: params.finish_after,
             cancel_after: params.cancel_after,
+            memo: params.memo_bytes()?,
+            rent_destination: Self::rent_destination(params)?,
+            chain: Sha256::digest(self.caip2_id()?).into(),
+            bridge_trigger: self.bridge_trigger,
         };
 
         let instruction = self.build_create_instruction(sender, recipient, escrow_pda, args);
         debug!("{} instruction built", CREATE_ESCROW);
+        Ok(instruction)
+    }
 
-        self.submit_transaction(instruction, &sender, &[&self.sender_keypair], CREATE_ESCROW)?;
-        info!("{} transaction confirmed", CREATE_ESCROW);
+    /// Builds the instruction transferring the stake and withdraw
+    /// authorities of the asset's stake account to the stake escrow PDA.
+    fn stake_escrow_instruction(
+        &self,
+        params: &EscrowParams,
+        sender: Pubkey,
+        recipient: Pubkey,
+    ) -> Result<Instruction> {
+        let stake_account = Self::stake_account(params)?;
+        let escrow_pda = self.derive_stake_escrow_pda(&sender, &recipient, &stake_account);
+        info!(%escrow_pda, %stake_account, "Derived stake escrow PDA");
+
+        let args = CreateStakeEscrowArgs {
+            finish_after: params.finish_after,
+            cancel_after: params.cancel_after,
+            memo: params.memo_bytes()?,
+        };
+
+        let instruction = escrow_instruction::create_stake_escrow(
+            self.escrow_program_id,
+            sender,
+            recipient,
+            stake_account,
+            escrow_pda,
+            args,
+        );
+        debug!("{} instruction built", CREATE_STAKE_ESCROW);
+        Ok(instruction)
+    }
+
+    /// Builds the instruction releasing the escrow in `metadata`, after
+    /// checking the recipient key and the compliance list.
+    fn finish_instruction(&self, metadata: &EscrowMetadata) -> Result<Instruction> {
+        let sender = Self::parse_pubkey(&metadata.params.sender)?;
+        let recipient = Self::parse_pubkey(&metadata.params.recipient)?;
+
+        let recipient_signer = self.recipient_signer()?;
+        Self::validate_signer(recipient_signer, &recipient, "recipient")?;
+        self.ensure_permitted(&[sender, recipient], FINISH_ESCROW)?;
+
+        self.finish_instruction_unchecked(metadata)
+    }
+
+    /// Builds the instruction releasing the escrow in `metadata`.
+    fn finish_instruction_unchecked(&self, metadata: &EscrowMetadata) -> Result<Instruction> {
+        let recipient = Self::parse_pubkey(&metadata.params.recipient)?;
+
+        let instruction = match metadata.params.asset.kind {
+            AssetKind::Stake => {
+                let stake_account = Self::stake_account(&metadata.params)?;
+                let escrow_pda = self.escrow_pda(metadata)?;
+                debug!(%escrow_pda, %stake_account, "Using stake escrow PDA");
+                escrow_instruction::finish_stake_escrow(
+                    self.escrow_program_id,
+                    recipient,
+                    stake_account,
+                    escrow_pda,
+                )
+            }
+            _ => {
+                let escrow_pda = self.escrow_pda(metadata)?;
+                debug!(%escrow_pda, "Using escrow PDA");
+                let rent_destination = Self::rent_destination(&metadata.params)?;
+                self.build_finish_instruction(recipient, escrow_pda, rent_destination)
+            }
+        };
+        debug!("{} instruction built", FINISH_ESCROW);
+        Ok(instruction)
+    }
+
+    /// Builds the instruction refunding the escrow in `metadata`.
+    fn cancel_instruction(&self, metadata: &EscrowMetadata) -> Result<Instruction> {
+        let sender = Self::parse_pubkey(&metadata.params.sender)?;
+
+        let instruction = match metadata.params.asset.kind {
+            AssetKind::Stake => {
+                let stake_account = Self::stake_account(&metadata.params)?;
+                let escrow_pda = self.escrow_pda(metadata)?;
+                debug!(%escrow_pda, %stake_account, "Using stake escrow PDA");
+                escrow_instruction::cancel_stake_escrow(
+                    self.escrow_program_id,
+                    sender,
+                    stake_account,
+                    escrow_pda,
+                )
+            }
+            _ => {
+                let escrow_pda = self.escrow_pda(metadata)?;
+                debug!(%escrow_pda, "Using escrow PDA");
+                let rent_destination = Self::rent_destination(&metadata.params)?;
+                self.build_cancel_instruction(sender, escrow_pda, rent_destination)
+            }
+        };
+        debug!("{} instruction built", CANCEL_ESCROW);
+        Ok(instruction)
+    }
+}
+
+#[async_trait::async_trait]
+impl Agent for SolanaAgent {
+    async fn create_escrow(&self, params: &EscrowParams) -> Result<EscrowMetadata> {
+        let (instruction, operation) = self.create_instruction(params)?;
+
+        let signature = self.submit_transaction(
+            instruction,
+            &self.sender.0.pubkey(),
+            &[self.sender.as_signer()],
+            operation,
+        )?;
+        info!("{} transaction confirmed", operation);
+
+        // The escrow exists either way; without the record, later calls
+        // derive its address from the seeds.
+        let (chain_metadata, uid) = match self.creation_record(&signature, operation) {
+            Ok((record, uid)) => (Some(record), uid),
+            Err(e) => {
+                warn!(error = %e, %signature, "Failed to record the escrow account");
+                (None, None)
+            }
+        };
 
         Ok(EscrowMetadata {
             params: params.clone(),
             state: ExecutionState::Funded,
             escrow_id: None,
+            uid,
+            chain_metadata,
+            guest: None,
+            observed: None,
         })
     }
 
     async fn finish_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
-        let sender = Self::parse_pubkey(&metadata.params.sender)?;
-        let recipient = Self::parse_pubkey(&metadata.params.recipient)?;
+        let instruction = self.finish_instruction(metadata)?;
+        let recipient_signer = self.recipient_signer()?;
 
-        let recipient_keypair = self.recipient_keypair()?;
-        Self::validate_keypair(recipient_keypair, &recipient, "recipient")?;
+        self.submit_transaction(
+            instruction,
+            &recipient_signer.0.pubkey(),
+            &[recipient_signer.as_signer()],
+            FINISH_ESCROW,
+        )?;
+        info!("{} transaction confirmed", FINISH_ESCROW);
 
-        let escrow_pda = self.derive_escrow_pda(&sender, &recipient);
-        debug!(%escrow_pda, "Using escrow PDA");
+        Ok(())
+    }
 
-        let instruction = self.build_finish_instruction(recipient, escrow_pda);
-        debug!("{} instruction built", FINISH_ESCROW);
+    async fn simulate(&self, operation: Operation<'_>) -> Result<Simulation> {
+        let (instruction, payer, name) = match operation {
+            Operation::Create(params) => {
+                let (instruction, name) = self.create_instruction(params)?;
+                (instruction, self.sender.0.pubkey(), name)
+            }
+            Operation::Finish(metadata) => (
+                self.finish_instruction(metadata)?,
+                self.recipient_signer()?.0.pubkey(),
+                FINISH_ESCROW,
+            ),
+            Operation::Cancel(metadata) => (
+                self.cancel_instruction(metadata)?,
+                self.sender.0.pubkey(),
+                CANCEL_ESCROW,
+            ),
+        };
+        self.simulate_transaction(instruction, &payer, name)
+    }
 
-        self.submit_transaction(instruction, &recipient, &[recipient_keypair], FINISH_ESCROW)?;
-        info!("{} transaction confirmed", FINISH_ESCROW);
+    async fn build_transaction(&self, operation: Operation<'_>) -> Result<OfflineTransaction> {
+        // The transaction is signed elsewhere, so only the parties'
+        // addresses are needed here.
+        let (instruction, payer, name) = match operation {
+            Operation::Create(params) => {
+                let (instruction, name) = self.create_instruction_unsigned(params)?;
+                (instruction, Self::parse_pubkey(&params.sender)?, name)
+            }
+            Operation::Finish(metadata) => {
+                let sender = Self::parse_pubkey(&metadata.params.sender)?;
+                let recipient = Self::parse_pubkey(&metadata.params.recipient)?;
+                self.ensure_permitted(&[sender, recipient], FINISH_ESCROW)?;
+                (
+                    self.finish_instruction_unchecked(metadata)?,
+                    recipient,
+                    FINISH_ESCROW,
+                )
+            }
+            Operation::Cancel(metadata) => (
+                self.cancel_instruction(metadata)?,
+                Self::parse_pubkey(&metadata.params.sender)?,
+                CANCEL_ESCROW,
+            ),
+        };
 
-        Ok(())
+        let mut instructions =
+            self.compute_budget_instructions(&instruction, &payer, name, true)?;
+        instructions.push(instruction);
+        let message = match &self.nonce_account {
+            Some(nonce_account) => {
+                let (advance, nonce) = self.advance_nonce(nonce_account, &payer, name)?;
+                instructions.insert(0, advance);
+                self.compile_message_at(&instructions, &payer, nonce, name)?
+            }
+            None => self.compile_message(&instructions, &payer, name)?,
+        };
+        let tx = VersionedTransaction {
+            signatures: vec![
+                Signature::default();
+                usize::from(message.header.num_required_signatures)
+            ],
+            message: VersionedMessage::V0(message),
+        };
+        OfflineTransaction::solana(&tx, name)
     }
 
-    async fn cancel_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
-        let sender = Self::parse_pubkey(&metadata.params.sender)?;
-        let recipient = Self::parse_pubkey(&metadata.params.recipient)?;
+    async fn broadcast_transaction(&self, tx: &OfflineTransaction) -> Result<Broadcast> {
+        let transaction = tx.solana_transaction()?;
+        let unsigned = OfflineTransaction::solana(&transaction, &tx.operation)?.signers;
+        if !unsigned.is_empty() {
+            return Err(ClientError::solana(
+                BROADCAST_TRANSACTION,
+                format!("missing signatures of {}", unsigned.join(", ")),
+            ));
+        }
+        if transaction.verify_with_results().contains(&false) {
+            return Err(ClientError::solana(
+                BROADCAST_TRANSACTION,
+                "transaction carries an invalid signature",
+            ));
+        }
 
-        let escrow_pda = self.derive_escrow_pda(&sender, &recipient);
-        debug!(%escrow_pda, "Using escrow PDA");
+        let signature = confirmation::send_and_confirm(
+            &self.client,
+            &transaction,
+            &self.confirmation,
+            &self.wait,
+            BROADCAST_TRANSACTION,
+        )?;
+        info!(%signature, "{} transaction confirmed", tx.operation);
 
-        let instruction = self.build_cancel_instruction(sender, escrow_pda);
-        debug!("{} instruction built", CANCEL_ESCROW);
+        let mut chain_metadata = None;
+        if [CREATE_ESCROW, CREATE_STAKE_ESCROW].contains(&tx.operation.as_str()) {
+            match self.creation_record(&signature, BROADCAST_TRANSACTION) {
+                Ok((record, _)) => chain_metadata = Some(record),
+                Err(e) => warn!(error = %e, %signature, "Failed to record the escrow account"),
+            }
+        }
+        Ok(Broadcast {
+            transaction: signature.to_string(),
+            escrow_id: None,
+            chain_metadata,
+        })
+    }
+
+    async fn estimate(&self, params: &EscrowParams) -> Result<LifecycleEstimate> {
+        let (instruction, operation) = self.create_instruction(params)?;
+        let mut create: Cost = self
+            .simulate_transaction(instruction, &self.sender.0.pubkey(), operation)?
+            .into();
+        let space = 8 + match params.asset.kind {
+            AssetKind::Stake => std::mem::size_of::<StakeEscrowAccount>(),
+            _ => std::mem::size_of::<EscrowAccount>(),
+        };
+        let rent = self
+            .client
+            .get_minimum_balance_for_rent_exemption(space)
+            .map_err(|e| ClientError::solana(operation, e))?;
+        create.deposit = Some(BigNumber::from(rent));
+
+        // The escrow does not exist yet, so these simulations fail, but
+        // still price the transactions at current fees.
+        let metadata = EscrowMetadata {
+            params: params.clone(),
+            state: ExecutionState::Funded,
+            escrow_id: None,
+            uid: None,
+            chain_metadata: None,
+            guest: None,
+            observed: None,
+        };
+        let finish = self.simulate_transaction(
+            self.finish_instruction_unchecked(&metadata)?,
+            &Self::parse_pubkey(&params.recipient)?,
+            FINISH_ESCROW,
+        )?;
+        let cancel = self.simulate_transaction(
+            self.cancel_instruction(&metadata)?,
+            &self.sender.0.pubkey(),
+            CANCEL_ESCROW,
+        )?;
+
+        Ok(LifecycleEstimate {
+            create,
+            finish: finish.into(),
+            cancel: cancel.into(),
+        })
+    }
+
+    async fn get_escrow_state(&self, metadata: &EscrowMetadata) -> Result<EscrowStatus> {
+        let params = &metadata.params;
+        let current_height = self
+            .client
+            .get_slot()
+            .map_err(|e| ClientError::solana(GET_ESCROW, e))?;
+
+        // (amount, finish_after, cancel_after) of a live escrow
+        let live = match params.asset.kind {
+            AssetKind::Stake => {
+                let stake_account = Self::stake_account(params)?;
+                let escrow_pda = self.escrow_pda(metadata)?;
+                debug!(%escrow_pda, %stake_account, "Fetching stake escrow");
+                match self.fetch_program_account::<StakeEscrowAccount>(&escrow_pda, GET_ESCROW)? {
+                    Some(escrow) => {
+                        let lamports = self
+                            .client
+                            .get_balance(&stake_account)
+                            .map_err(|e| ClientError::solana(GET_ESCROW, e))?;
+                        Some((lamports, escrow.finish_after, escrow.cancel_after))
+                    }
+                    None => None,
+                }
+            }
+            _ => {
+                let escrow_pda = self.escrow_pda(metadata)?;
+                debug!(%escrow_pda, "Fetching escrow");
+                self.fetch_program_account::<EscrowAccount>(&escrow_pda, GET_ESCROW)?
+                    .map(|escrow| (escrow.amount, escrow.finish_after, escrow.cancel_after))
+            }
+        };
 
-        self.submit_transaction(instruction, &sender, &[&self.sender_keypair], CANCEL_ESCROW)?;
+        let settled = live.is_none();
+        let (amount, finish_after, cancel_after) =
+            live.unwrap_or((0, params.finish_after, params.cancel_after));
+        Ok(EscrowStatus {
+            settled,
+            amount: BigNumber::from(amount),
+            finish_after,
+            cancel_after,
+            current_height,
+            has_conditions: params.has_conditions,
+        })
+    }
+
+    async fn current_height(&self) -> Result<u64> {
+        self.client
+            .get_slot()
+            .map_err(|e| ClientError::solana(GET_ESCROW, e))
+    }
+
+    async fn chain_state(&self) -> Result<ChainState> {
+        crate::chain_state::solana(&self.client)
+    }
+
+    /// Observes the first finalized transaction settling the escrow PDA
+    /// after the escrow's creation, as PDAs are reused by later escrows
+    /// between the same parties.
+    async fn observe_escrow(&self, metadata: &EscrowMetadata) -> Result<Observation> {
+        let err = |e| ClientError::solana(OBSERVE, e);
+        let escrow_pda = self.escrow_pda(metadata)?;
+        let instance = metadata.binding()?.instance;
+        let created = match &metadata.chain_metadata {
+            Some(ChainMetadata::Solana { signature, .. }) => {
+                Some(Signature::from_str(signature).map_err(|e| ClientError::solana(OBSERVE, e))?)
+            }
+            _ => None,
+        };
+        let config = GetConfirmedSignaturesForAddress2Config {
+            until: created,
+            commitment: Some(CommitmentConfig::finalized()),
+            ..Default::default()
+        };
+        let statuses = self
+            .client
+            .get_signatures_for_address_with_config(&escrow_pda, config)
+            .map_err(err)?;
+
+        // Signatures are returned newest first.
+        for status in statuses.iter().rev().filter(|status| status.err.is_none()) {
+            let signature = Signature::from_str(&status.signature)
+                .map_err(|e| ClientError::solana(OBSERVE, e))?;
+            let transaction = self
+                .client
+                .get_transaction_with_config(
+                    &signature,
+                    events::transaction_config(CommitmentConfig::finalized()),
+                )
+                .map_err(err)?;
+            let state = events::transaction_logs(&transaction, OBSERVE)?
+                .iter()
+                .filter_map(|line| events::decode(line, &self.escrow_program_id))
+                .filter(|event| event.escrow == escrow_pda)
+                .find_map(|event| match event.kind {
+                    UpdateKind::Finished => Some(ExecutionState::Released),
+                    UpdateKind::Cancelled => Some(ExecutionState::Refunded),
+                    UpdateKind::Created => None,
+                });
+            if let Some(state) = state {
+                return Ok(Observation {
+                    chain: self.caip2_id()?,
+                    claim: RemoteClaim::Escrow { instance, state },
+                    height: transaction.slot,
+                    block_hash: self.finalized_blockhash(transaction.slot)?,
+                });
+            }
+        }
+        Err(ClientError::solana(
+            OBSERVE,
+            format!("escrow {escrow_pda} has no finalized settlement"),
+        ))
+    }
+
+    /// Observes the data of the account at `key`, a 32-byte address, at
+    /// the finalized slot.
+    async fn observe_state(&self, key: &[u8]) -> Result<Observation> {
+        let address = Pubkey::try_from(key)
+            .map_err(|_| ClientError::solana(OBSERVE, "state key must be a 32-byte address"))?;
+        let response = self
+            .client
+            .get_account_with_commitment(&address, CommitmentConfig::finalized())
+            .map_err(|e| ClientError::solana(OBSERVE, e))?;
+        let account = response
+            .value
+            .ok_or_else(|| ClientError::solana(OBSERVE, format!("account {address} not found")))?;
+        let slot = response.context.slot;
+
+        Ok(Observation {
+            chain: self.caip2_id()?,
+            claim: RemoteClaim::State {
+                key: key.to_vec(),
+                value_hash: Sha256::digest(&account.data).into(),
+            },
+            height: slot,
+            block_hash: self.finalized_blockhash(slot)?,
+        })
+    }
+
+    /// Reads the latest confirmed slot, which a supermajority voted on.
+    async fn latest_block(&self) -> Result<ObservedBlock> {
+        let slot = self
+            .client
+            .get_slot_with_commitment(CommitmentConfig::confirmed())
+            .map_err(|e| ClientError::solana(GET_BLOCK, e))?;
+        let hash = self
+            .blockhash(slot, CommitmentConfig::confirmed(), GET_BLOCK)?
+            .ok_or_else(|| ClientError::solana(GET_BLOCK, format!("slot {slot} was skipped")))?;
+        Ok(ObservedBlock { height: slot, hash })
+    }
+
+    async fn block_hash(&self, height: u64) -> Result<Option<[u8; 32]>> {
+        self.blockhash(height, CommitmentConfig::confirmed(), GET_BLOCK)
+    }
+
+    async fn finalized_height(&self) -> Result<u64> {
+        self.client
+            .get_slot_with_commitment(CommitmentConfig::finalized())
+            .map_err(|e| ClientError::solana(GET_BLOCK, e))
+    }
+
+    /// Queries escrow and stake escrow PDAs by sender and recipient with
+    /// `getProgramAccounts`. Settled escrows are closed, so only live ones
+    /// are found.
+    async fn list_escrows(&self) -> Result<Vec<EscrowSummary>> {
+        let current_height = self
+            .client
+            .get_slot()
+            .map_err(|e| ClientError::solana(LIST_ESCROWS, e))?;
+        let parties: Vec<Pubkey> = std::iter::once(self.sender.0.pubkey())
+            .chain(self.recipient.as_ref().map(|signer| signer.0.pubkey()))
+            .collect();
+        // Conditions are verified off-chain and not recorded in the account.
+        let status = |amount: u64, finish_after, cancel_after| EscrowStatus {
+            settled: false,
+            amount: BigNumber::from(amount),
+            finish_after,
+            cancel_after,
+            current_height,
+            has_conditions: false,
+        };
+
+        let mut escrows = Vec::new();
+        for party in &parties {
+            for offset in [SENDER_OFFSET, RECIPIENT_OFFSET] {
+                for (address, escrow) in
+                    self.program_accounts_with_party::<EscrowAccount>(party, offset)?
+                {
+                    escrows.push(EscrowSummary {
+                        id: address.to_string(),
+                        uid: None,
+                        sender: escrow.sender.to_string(),
+                        recipient: escrow.recipient.to_string(),
+                        status: status(escrow.amount, escrow.finish_after, escrow.cancel_after),
+                    });
+                }
+                for (address, escrow) in
+                    self.program_accounts_with_party::<StakeEscrowAccount>(party, offset)?
+                {
+                    let lamports = self
+                        .client
+                        .get_balance(&escrow.stake_account)
+                        .map_err(|e| ClientError::solana(LIST_ESCROWS, e))?;
+                    escrows.push(EscrowSummary {
+                        id: address.to_string(),
+                        uid: None,
+                        sender: escrow.sender.to_string(),
+                        recipient: escrow.recipient.to_string(),
+                        status: status(lamports, escrow.finish_after, escrow.cancel_after),
+                    });
+                }
+            }
+        }
+
+        // An escrow between two configured parties matches more than once.
+        escrows.sort_by(|a, b| a.id.cmp(&b.id));
+        escrows.dedup_by(|a, b| a.id == b.id);
+        info!(count = escrows.len(), "Found escrows");
+        Ok(escrows)
+    }
+
+    /// Subscribes to logs of transactions mentioning the escrow program and
+    /// decodes the events it emits.
+    async fn watch_escrows(&self, metadata: Option<&EscrowMetadata>) -> Result<UpdateStream> {
+        let escrow = metadata
+            .map(|metadata| self.escrow_pda(metadata))
+            .transpose()?;
+        let parties: Vec<Pubkey> = std::iter::once(self.sender.0.pubkey())
+            .chain(self.recipient.as_ref().map(|signer| signer.0.pubkey()))
+            .collect();
+        let program_id = self.escrow_program_id;
+        let filter = RpcTransactionLogsFilter::Mentions(vec![program_id.to_string()]);
+        let config = RpcTransactionLogsConfig {
+            commitment: Some(self.client.commitment()),
+        };
+
+        let pubsub = PubsubClient::new(&self.pubsub_url)
+            .await
+            .map_err(|e| ClientError::solana(WATCH, e))?;
+        info!(url = %self.pubsub_url, "Watching escrow program logs");
+
+        // The log stream borrows the pubsub client, so both live in a task
+        // that forwards updates until the receiver is dropped.
+        let (updates, stream) = mpsc::unbounded();
+        tokio::spawn(async move {
+            let (mut responses, _unsubscribe) = match pubsub.logs_subscribe(filter, config).await {
+                Ok(subscription) => subscription,
+                Err(e) => {
+                    let _ = updates.unbounded_send(Err(ClientError::solana(WATCH, e)));
+                    return;
+                }
+            };
+            while let Some(response) = responses.next().await {
+                let RpcLogsResponse {
+                    signature,
+                    err,
+                    logs,
+                } = response.value;
+                if err.is_some() {
+                    continue;
+                }
+                for event in logs
+                    .iter()
+                    .filter_map(|line| events::decode(line, &program_id))
+                {
+                    let wanted = match escrow {
+                        Some(escrow) => event.escrow == escrow,
+                        None => {
+                            parties.contains(&event.sender) || parties.contains(&event.recipient)
+                        }
+                    };
+                    if wanted
+                        && updates
+                            .unbounded_send(Ok(event.into_update(&signature)))
+                            .is_err()
+                    {
+                        return;
+                    }
+                }
+            }
+            let _ = updates.unbounded_send(Err(ClientError::solana(WATCH, "subscription closed")));
+        });
+        Ok(stream.boxed())
+    }
+
+    async fn wait_for_transaction(&self, pending: &PendingTransaction) -> Result<()> {
+        if !matches!(pending.chain, Chain::Solana) {
+            return Err(ClientError::ConfigMismatch {
+                expected: Chain::Solana.as_ref().to_owned(),
+                actual: pending.chain.as_ref().to_owned(),
+            });
+        }
+        let operation = [
+            CREATE_ESCROW,
+            CREATE_STAKE_ESCROW,
+            FINISH_ESCROW,
+            CANCEL_ESCROW,
+            lookup_table::LOOKUP_TABLE,
+        ]
+        .into_iter()
+        .find(|operation| *operation == pending.operation)
+        .unwrap_or(WAIT_FOR_TRANSACTION);
+
+        confirmation::resume(
+            &self.client,
+            pending,
+            &self.confirmation,
+            &self.wait,
+            operation,
+        )?;
+        info!(signature = %pending.id, "{} transaction confirmed", operation);
+        Ok(())
+    }
+
+    async fn cancel_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
+        let instruction = self.cancel_instruction(metadata)?;
+
+        self.submit_transaction(
+            instruction,
+            &self.sender.0.pubkey(),
+            &[self.sender.as_signer()],
+            CANCEL_ESCROW,
+        )?;
         info!("{} transaction confirmed", CANCEL_ESCROW);
 
         Ok(())
     }
 }
+
+/// Derives the WebSocket URL from an RPC URL the way the Solana CLI does:
+/// `http` becomes `ws`, `https` becomes `wss`, and an explicit port is
+/// incremented (e.g., `8899` to `8900` for a local validator).
+fn websocket_url(rpc_url: &str) -> Result<String> {
+    let unsupported =
+        |()| ClientError::solana(WATCH, format!("cannot derive WebSocket URL from {rpc_url}"));
+    let mut url = Url::parse(rpc_url)?;
+    let scheme = if url.scheme() == "https" { "wss" } else { "ws" };
+    url.set_scheme(scheme).map_err(unsupported)?;
+    if let Some(port) = url.port() {
+        url.set_port(Some(port + 1)).map_err(unsupported)?;
+    }
+    Ok(url.to_string())
+}
@@ -0,0 +1,83 @@
+//! Lamport escrows finished by Wormhole messages.
+//!
+//! An escrow created by an agent
+//! [`with_bridge_trigger`](SolanaAgent::with_bridge_trigger) can be finished
+//! by anyone once Wormhole's core bridge has verified and posted a VAA from
+//! the trigger's emitter carrying its payload, e.g. one sent by a contract
+//! on another chain once a delivery was paid for there:
+//!
+//! 1. A relayer or the Wormhole SDK posts the VAA, having the core bridge
+//!    verify the guardian signatures.
+//! 2. [`finish_escrow_with_vaa`](SolanaAgent::finish_escrow_with_vaa)
+//!    finishes the escrow, naming the account the VAA was posted to (see
+//!    [`posted_vaa_address`]).
+
+use escrow_cpi::{instruction as escrow_instruction, BridgeTrigger, WORMHOLE_CORE_BRIDGE};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::Signer;
+use tracing::info;
+use zescrow_core::condition::bridge::{Bridge, Vaa, Verifiers};
+use zescrow_core::EscrowMetadata;
+
+use super::{SolanaAgent, FINISH_ESCROW_WITH_VAA};
+use crate::Result;
+
+/// Seed prefix of the accounts the core bridge posts VAAs to.
+const POSTED_VAA: &[u8] = b"PostedVAA";
+
+/// Returns the trigger finishing an escrow on the message `bridge` waits
+/// for, or `None` unless it is a Wormhole message.
+pub fn bridge_trigger(bridge: &Bridge) -> Option<BridgeTrigger> {
+    let Verifiers::Wormhole { .. } = bridge.verifiers else {
+        return None;
+    };
+    Some(BridgeTrigger {
+        emitter_chain: bridge.source_chain.try_into().ok()?,
+        emitter: bridge.emitter,
+        payload_hash: bridge.payload_hash,
+    })
+}
+
+/// Returns the account the core bridge posts `vaa` to.
+pub fn posted_vaa_address(vaa: &Vaa) -> Pubkey {
+    Pubkey::find_program_address(&[POSTED_VAA, &vaa.body_hash()], &WORMHOLE_CORE_BRIDGE).0
+}
+
+impl SolanaAgent {
+    /// Finishes the escrow in `metadata` with the VAA posted to
+    /// `posted_vaa`, crediting the recipient. The sender pays the fees, so
+    /// the recipient's key is not needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the compliance list blocks a party, or the
+    /// transaction fails, e.g. because the escrow has no bridge trigger or
+    /// the VAA does not match it.
+    pub async fn finish_escrow_with_vaa(
+        &self,
+        metadata: &EscrowMetadata,
+        posted_vaa: Pubkey,
+    ) -> Result<()> {
+        let sender = Self::parse_pubkey(&metadata.params.sender)?;
+        let recipient = Self::parse_pubkey(&metadata.params.recipient)?;
+        self.ensure_permitted(&[sender, recipient], FINISH_ESCROW_WITH_VAA)?;
+
+        let payer = self.sender.0.pubkey();
+        let instruction = escrow_instruction::finish_escrow_with_vaa(
+            self.escrow_program_id,
+            payer,
+            recipient,
+            self.escrow_pda(metadata)?,
+            posted_vaa,
+            Self::rent_destination(&metadata.params)?,
+        );
+        self.submit_transaction(
+            instruction,
+            &payer,
+            &[self.sender.as_signer()],
+            FINISH_ESCROW_WITH_VAA,
+        )?;
+        info!(%posted_vaa, "{} transaction confirmed", FINISH_ESCROW_WITH_VAA);
+        Ok(())
+    }
+}
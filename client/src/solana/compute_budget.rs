@@ -0,0 +1,125 @@
+//! Compute budget settings for Solana transactions.
+//!
+//! Transactions may start with `ComputeBudget` instructions setting their
+//! compute unit limit and a priority fee per compute unit. Validators order
+//! transactions by that fee during congestion, so finish transactions that
+//! verify proofs still land instead of expiring with their blockhash.
+
+use solana_client::client_error::Result;
+use solana_client::rpc_client::RpcClient;
+use solana_sdk::compute_budget::ComputeBudgetInstruction;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::pubkey::Pubkey;
+
+use super::{
+    CANCEL_ESCROW, CREATE_ESCROW, CREATE_STAKE_ESCROW, FINISH_ESCROW, FINISH_ESCROW_WITH_VAA,
+};
+
+/// Percentile of recent priority fees used by [`PriorityFee::Auto`] unless
+/// another is given.
+pub const DEFAULT_FEE_PERCENTILE: u8 = 75;
+
+/// Margin added to simulated compute usage when sizing the unit limit, in
+/// percent.
+const UNIT_LIMIT_MARGIN_PERCENT: u64 = 10;
+
+/// Priority fee of a transaction.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PriorityFee {
+    /// No priority fee.
+    #[default]
+    None,
+    /// Fixed price, in micro-lamports per compute unit.
+    Fixed(u64),
+    /// Price estimated from the fees recently paid by transactions locking
+    /// the same writable accounts, via `getRecentPrioritizationFees`.
+    Auto {
+        /// Percentile of recent fees to pay, from 0 to 100.
+        percentile: u8,
+        /// Cap on the estimated price, in micro-lamports per compute unit.
+        max: Option<u64>,
+    },
+}
+
+/// Compute unit limit and priority fee of one kind of transaction.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ComputeBudget {
+    /// Compute unit limit. When unset and a priority fee applies, the limit
+    /// is sized from a simulation of the transaction, since the fee is
+    /// charged on the requested limit rather than on actual usage.
+    pub unit_limit: Option<u32>,
+    /// Priority fee per compute unit.
+    pub unit_price: PriorityFee,
+}
+
+/// Compute budgets per escrow operation.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PriorityFees {
+    /// Budget of create transactions, including stake escrows.
+    pub create: ComputeBudget,
+    /// Budget of finish transactions, including those with a Wormhole VAA.
+    pub finish: ComputeBudget,
+    /// Budget of cancel transactions.
+    pub cancel: ComputeBudget,
+}
+
+impl PriorityFees {
+    /// Applies `budget` to every operation.
+    pub fn uniform(budget: ComputeBudget) -> Self {
+        Self {
+            create: budget,
+            finish: budget,
+            cancel: budget,
+        }
+    }
+
+    /// Returns the budget of the instruction named `operation`.
+    pub(crate) fn for_operation(&self, operation: &str) -> ComputeBudget {
+        match operation {
+            CREATE_ESCROW | CREATE_STAKE_ESCROW => self.create,
+            FINISH_ESCROW | FINISH_ESCROW_WITH_VAA => self.finish,
+            CANCEL_ESCROW => self.cancel,
+            _ => ComputeBudget::default(),
+        }
+    }
+}
+
+/// Builds the instructions setting `unit_limit` and `unit_price`, to be
+/// placed before the transaction's other instructions.
+pub(crate) fn instructions(unit_limit: Option<u32>, unit_price: Option<u64>) -> Vec<Instruction> {
+    unit_limit
+        .map(ComputeBudgetInstruction::set_compute_unit_limit)
+        .into_iter()
+        .chain(unit_price.map(ComputeBudgetInstruction::set_compute_unit_price))
+        .collect()
+}
+
+/// Returns the `percentile`th of the priority fees paid in recent slots by
+/// transactions locking `accounts`, capped at `max`.
+pub(crate) fn estimate_unit_price(
+    client: &RpcClient,
+    accounts: &[Pubkey],
+    percentile: u8,
+    max: Option<u64>,
+) -> Result<u64> {
+    let mut fees: Vec<u64> = client
+        .get_recent_prioritization_fees(accounts)?
+        .into_iter()
+        .map(|fee| fee.prioritization_fee)
+        .collect();
+    fees.sort_unstable();
+
+    let price = match fees.len() {
+        0 => 0,
+        len => fees[(len - 1) * usize::from(percentile.min(100)) / 100],
+    };
+    Ok(max.map_or(price, |max| price.min(max)))
+}
+
+/// Returns a unit limit covering `units` consumed in simulation, with a
+/// margin for the compute budget instructions and state changes between
+/// simulation and execution.
+pub(crate) fn unit_limit_for(units: u64) -> Option<u32> {
+    let limit = units.saturating_mul(100 + UNIT_LIMIT_MARGIN_PERCENT) / 100;
+    u32::try_from(limit).ok()
+}
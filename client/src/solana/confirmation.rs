@@ -0,0 +1,219 @@
+//! Commitment level and confirmation strategy of Solana transactions.
+
+use std::time::{Duration, Instant};
+
+use indicatif::ProgressBar;
+use solana_client::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcSendTransactionConfig;
+use solana_sdk::commitment_config::{CommitmentConfig, CommitmentLevel};
+use solana_sdk::hash::Hash;
+use solana_sdk::signature::Signature;
+use solana_sdk::transaction::VersionedTransaction;
+use tracing::{debug, trace};
+use zescrow_core::Chain;
+
+use crate::error::ClientError;
+use crate::pending::{PendingTransaction, WaitConfig};
+use crate::Result;
+
+/// Interval between signature status polls unless configured otherwise.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// How Solana transactions are confirmed.
+#[derive(Debug, Clone, Copy)]
+pub struct ConfirmationConfig {
+    /// Commitment that transactions are confirmed at, and that account
+    /// reads and preflight simulations use.
+    pub commitment: CommitmentLevel,
+    /// Resend the unconfirmed transaction at this interval, in case a
+    /// leader dropped it. Resending the same signed transaction cannot
+    /// execute it twice.
+    pub resubmit_interval: Duration,
+    /// Show a progress spinner on stderr while waiting.
+    pub spinner: bool,
+}
+
+impl Default for ConfirmationConfig {
+    fn default() -> Self {
+        Self {
+            commitment: CommitmentLevel::Finalized,
+            resubmit_interval: Duration::from_secs(2),
+            spinner: false,
+        }
+    }
+}
+
+impl ConfirmationConfig {
+    /// Returns the commitment as an RPC configuration.
+    pub fn commitment_config(&self) -> CommitmentConfig {
+        CommitmentConfig {
+            commitment: self.commitment,
+        }
+    }
+}
+
+/// Sends `tx` and waits until it reaches the configured commitment,
+/// resending it until then, and returns its signature.
+pub(crate) fn send_and_confirm(
+    client: &RpcClient,
+    tx: &VersionedTransaction,
+    config: &ConfirmationConfig,
+    wait: &WaitConfig,
+    operation: &'static str,
+) -> Result<Signature> {
+    let send_config = RpcSendTransactionConfig {
+        preflight_commitment: Some(config.commitment),
+        ..RpcSendTransactionConfig::default()
+    };
+    let signature = client
+        .send_transaction_with_config(tx, send_config)
+        .map_err(|e| ClientError::solana(operation, e))?;
+    debug!(%signature, commitment = ?config.commitment, "Transaction sent");
+
+    let waiter = Waiter {
+        client,
+        config,
+        wait,
+        operation,
+    };
+    // A durable nonce takes the place of the blockhash and does not expire.
+    let blockhash = (!tx.uses_durable_nonce()).then(|| tx.message.recent_blockhash());
+    waiter
+        .wait(&signature, blockhash, Some(tx))
+        .map(|()| signature)
+}
+
+/// Resumes waiting for the transaction in `pending` to reach the configured
+/// commitment.
+pub(crate) fn resume(
+    client: &RpcClient,
+    pending: &PendingTransaction,
+    config: &ConfirmationConfig,
+    wait: &WaitConfig,
+    operation: &'static str,
+) -> Result<()> {
+    let invalid = |e: String| ClientError::solana(operation, e);
+    let signature: Signature = pending.id.parse().map_err(|e| invalid(format!("{e}")))?;
+    let blockhash: Option<Hash> = pending
+        .blockhash
+        .as_deref()
+        .map(str::parse)
+        .transpose()
+        .map_err(|e| invalid(format!("{e}")))?;
+
+    let waiter = Waiter {
+        client,
+        config,
+        wait,
+        operation,
+    };
+    waiter.wait(&signature, blockhash.as_ref(), None)
+}
+
+/// Polls transaction status under a confirmation strategy.
+struct Waiter<'a> {
+    client: &'a RpcClient,
+    config: &'a ConfirmationConfig,
+    wait: &'a WaitConfig,
+    operation: &'static str,
+}
+
+impl Waiter<'_> {
+    /// Waits for `signature`, showing a spinner if configured.
+    fn wait(
+        &self,
+        signature: &Signature,
+        blockhash: Option<&Hash>,
+        tx: Option<&VersionedTransaction>,
+    ) -> Result<()> {
+        let spinner = self.config.spinner.then(|| {
+            let spinner = ProgressBar::new_spinner();
+            spinner.enable_steady_tick(Duration::from_millis(100));
+            spinner.set_message(format!(
+                "Waiting for {} transaction {signature}",
+                self.operation
+            ));
+            spinner
+        });
+        let outcome = self.poll(signature, blockhash, tx);
+        if let Some(spinner) = spinner {
+            spinner.finish_and_clear();
+        }
+        outcome
+    }
+
+    /// Polls the status of `signature` until it reaches the configured
+    /// commitment, fails, expires with `blockhash`, or times out, resending
+    /// `tx` meanwhile.
+    fn poll(
+        &self,
+        signature: &Signature,
+        blockhash: Option<&Hash>,
+        tx: Option<&VersionedTransaction>,
+    ) -> Result<()> {
+        let resend_config = RpcSendTransactionConfig {
+            skip_preflight: true,
+            ..RpcSendTransactionConfig::default()
+        };
+        let poll_interval = self.wait.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL);
+        let started = Instant::now();
+        let mut last_sent = started;
+        loop {
+            let status = self
+                .client
+                .get_signature_statuses(&[*signature])
+                .map_err(|e| ClientError::solana(self.operation, e))?
+                .value
+                .pop()
+                .flatten();
+            match status {
+                Some(status) => {
+                    if let Some(err) = status.err {
+                        return Err(ClientError::solana(
+                            self.operation,
+                            format!("transaction {signature} failed: {err}"),
+                        ));
+                    }
+                    if status.satisfies_commitment(self.config.commitment_config()) {
+                        return Ok(());
+                    }
+                    trace!(%signature, status = ?status.confirmation_status, "Awaiting commitment");
+                }
+                None => {
+                    if let Some(blockhash) = blockhash {
+                        let valid = self
+                            .client
+                            .is_blockhash_valid(blockhash, CommitmentConfig::processed())
+                            .map_err(|e| ClientError::solana(self.operation, e))?;
+                        if !valid {
+                            return Err(ClientError::tx_dropped(format!(
+                                "{signature} expired with its blockhash before landing"
+                            )));
+                        }
+                    }
+                }
+            }
+
+            if self
+                .wait
+                .timeout
+                .is_some_and(|timeout| started.elapsed() >= timeout)
+            {
+                return Err(ClientError::Pending(PendingTransaction {
+                    chain: Chain::Solana,
+                    operation: self.operation.to_owned(),
+                    id: signature.to_string(),
+                    blockhash: blockhash.map(ToString::to_string),
+                }));
+            }
+            if let Some(tx) = tx.filter(|_| last_sent.elapsed() >= self.config.resubmit_interval) {
+                trace!(%signature, "Resending transaction");
+                if let Err(e) = self.client.send_transaction_with_config(tx, resend_config) {
+                    debug!(%signature, error = %e, "Resending transaction failed");
+                }
+                last_sent = Instant::now();
+            }
+            std::thread::sleep(poll_interval);
+        }
+    }
+}
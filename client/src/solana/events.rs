@@ -0,0 +1,345 @@
+//! Decodes escrow program events from transaction logs.
+
+use std::fmt;
+
+use anchor_lang::{AnchorDeserialize, Discriminator};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use escrow_cpi::{
+    pda, ComplianceEvent, ComplianceMode, EscrowEvent, EscrowState, StakeEscrowEvent,
+};
+use serde::{Serialize, Serializer};
+use solana_client::nonblocking::rpc_client::RpcClient;
+use solana_client::rpc_config::RpcTransactionConfig;
+use solana_sdk::commitment_config::CommitmentConfig;
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signature::Signature;
+use solana_transaction_status_client_types::option_serializer::OptionSerializer;
+use solana_transaction_status_client_types::{
+    EncodedConfirmedTransactionWithStatusMeta, UiTransactionEncoding,
+};
+use zescrow_core::{BigNumber, EscrowId};
+
+use crate::error::ClientError;
+use crate::watch::{EscrowUpdate, UpdateKind};
+use crate::Result;
+
+/// Log prefix Anchor uses for events emitted with `emit!`.
+const PROGRAM_DATA: &str = "Program data: ";
+
+// Operation name for errors.
+const DECODE_LOGS: &str = "decode_logs";
+
+/// An event emitted by the escrow program, decoded from transaction logs.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum ProgramEvent {
+    /// `EscrowEvent`: a native or SPL token escrow changed state.
+    Escrow {
+        /// Escrow PDA.
+        #[serde(serialize_with = "base58")]
+        escrow: Pubkey,
+        /// Bump seed of the escrow PDA.
+        bump: u8,
+        /// Original depositor.
+        #[serde(serialize_with = "base58")]
+        sender: Pubkey,
+        /// Intended beneficiary.
+        #[serde(serialize_with = "base58")]
+        recipient: Pubkey,
+        /// Escrowed amount, in lamports or token base units.
+        amount: u64,
+        /// Memo supplied at creation.
+        memo: Option<String>,
+        /// Lifecycle stage just executed.
+        action: UpdateKind,
+        /// Chain-agnostic escrow ID, emitted on creation.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        uid: Option<EscrowId>,
+    },
+    /// `StakeEscrowEvent`: a stake escrow changed state.
+    StakeEscrow {
+        /// Stake escrow PDA.
+        #[serde(serialize_with = "base58")]
+        escrow: Pubkey,
+        /// Bump seed of the stake escrow PDA.
+        bump: u8,
+        /// Original stake authority.
+        #[serde(serialize_with = "base58")]
+        sender: Pubkey,
+        /// Intended beneficiary.
+        #[serde(serialize_with = "base58")]
+        recipient: Pubkey,
+        /// Escrowed stake account.
+        #[serde(serialize_with = "base58")]
+        stake_account: Pubkey,
+        /// Stake account balance at the time of the event.
+        lamports: u64,
+        /// Memo supplied at creation.
+        memo: Option<String>,
+        /// Lifecycle stage just executed.
+        action: UpdateKind,
+    },
+    /// `ComplianceEvent`: the compliance list changed.
+    Compliance {
+        /// Address whose entry changed.
+        #[serde(serialize_with = "base58")]
+        address: Pubkey,
+        /// Whether the address is now listed.
+        listed: bool,
+        /// List mode: `allow` or `deny`.
+        mode: &'static str,
+    },
+}
+
+impl fmt::Display for ProgramEvent {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Escrow {
+                escrow,
+                sender,
+                recipient,
+                amount,
+                memo,
+                action,
+                ..
+            } => {
+                write!(
+                    f,
+                    "EscrowEvent {action}: escrow {escrow}, {sender} -> {recipient}, amount {amount}"
+                )?;
+                if let Some(memo) = memo {
+                    write!(f, ", memo {memo:?}")?;
+                }
+                Ok(())
+            }
+            Self::StakeEscrow {
+                escrow,
+                sender,
+                recipient,
+                stake_account,
+                lamports,
+                memo,
+                action,
+                ..
+            } => {
+                write!(
+                    f,
+                    "StakeEscrowEvent {action}: escrow {escrow}, {sender} -> {recipient}, \
+                     stake account {stake_account} ({lamports} lamports)"
+                )?;
+                if let Some(memo) = memo {
+                    write!(f, ", memo {memo:?}")?;
+                }
+                Ok(())
+            }
+            Self::Compliance {
+                address,
+                listed,
+                mode,
+            } => {
+                let change = if *listed { "listed" } else { "unlisted" };
+                write!(f, "ComplianceEvent: {address} {change} ({mode} list)")
+            }
+        }
+    }
+}
+
+/// Fetches transaction `signature` and decodes the events the escrow program
+/// at `program_id` emitted in it, in order.
+///
+/// # Errors
+///
+/// Returns an error if the transaction cannot be fetched, e.g. because it is
+/// not confirmed yet or the RPC node no longer holds it.
+pub async fn transaction_events(
+    rpc_url: &str,
+    program_id: &Pubkey,
+    signature: &Signature,
+) -> Result<Vec<ProgramEvent>> {
+    let client = RpcClient::new(rpc_url.to_owned());
+    let transaction = client
+        .get_transaction_with_config(signature, transaction_config(CommitmentConfig::confirmed()))
+        .await
+        .map_err(|e| ClientError::solana(DECODE_LOGS, e))?;
+    Ok(transaction_logs(&transaction, DECODE_LOGS)?
+        .iter()
+        .filter_map(|line| decode_event(line, program_id))
+        .collect())
+}
+
+/// Configuration for fetching a transaction with its logs.
+pub(crate) fn transaction_config(commitment: CommitmentConfig) -> RpcTransactionConfig {
+    RpcTransactionConfig {
+        encoding: Some(UiTransactionEncoding::Json),
+        commitment: Some(commitment),
+        max_supported_transaction_version: Some(0),
+    }
+}
+
+/// Returns the log messages of a fetched transaction.
+pub(crate) fn transaction_logs<'a>(
+    transaction: &'a EncodedConfirmedTransactionWithStatusMeta,
+    operation: &'static str,
+) -> Result<&'a [String]> {
+    match transaction
+        .transaction
+        .meta
+        .as_ref()
+        .map(|meta| &meta.log_messages)
+    {
+        Some(OptionSerializer::Some(logs)) => Ok(logs),
+        _ => Err(ClientError::solana(
+            operation,
+            "no logs recorded for the transaction",
+        )),
+    }
+}
+
+/// Decodes an event of the escrow program at `program_id` from a log line.
+///
+/// Returns `None` for any other log line.
+fn decode_event(line: &str, program_id: &Pubkey) -> Option<ProgramEvent> {
+    let data = STANDARD.decode(line.strip_prefix(PROGRAM_DATA)?).ok()?;
+
+    if let Some(mut payload) = data.strip_prefix(EscrowEvent::DISCRIMINATOR) {
+        let event = EscrowEvent::deserialize(&mut payload).ok()?;
+        let (escrow, bump) =
+            pda::find_escrow_address_with_program_id(&event.sender, &event.recipient, program_id);
+        return Some(ProgramEvent::Escrow {
+            escrow,
+            bump,
+            sender: event.sender,
+            recipient: event.recipient,
+            amount: event.amount,
+            memo: event.memo.as_ref().map(memo),
+            action: kind(&event.action),
+            uid: event.uid.map(EscrowId),
+        });
+    }
+
+    if let Some(mut payload) = data.strip_prefix(StakeEscrowEvent::DISCRIMINATOR) {
+        let event = StakeEscrowEvent::deserialize(&mut payload).ok()?;
+        let (escrow, bump) = pda::find_stake_escrow_address_with_program_id(
+            &event.sender,
+            &event.recipient,
+            &event.stake_account,
+            program_id,
+        );
+        return Some(ProgramEvent::StakeEscrow {
+            escrow,
+            bump,
+            sender: event.sender,
+            recipient: event.recipient,
+            stake_account: event.stake_account,
+            lamports: event.lamports,
+            memo: event.memo.as_ref().map(memo),
+            action: kind(&event.action),
+        });
+    }
+
+    let mut payload = data.strip_prefix(ComplianceEvent::DISCRIMINATOR)?;
+    let event = ComplianceEvent::deserialize(&mut payload).ok()?;
+    Some(ProgramEvent::Compliance {
+        address: event.address,
+        listed: event.listed,
+        mode: match event.mode {
+            ComplianceMode::Allow => "allow",
+            ComplianceMode::Deny => "deny",
+        },
+    })
+}
+
+/// An escrow program event, tagged with the escrow PDA it concerns.
+pub(crate) struct LoggedEvent {
+    /// Escrow or stake escrow PDA.
+    pub(crate) escrow: Pubkey,
+    /// Bump seed of the PDA.
+    pub(crate) bump: u8,
+    pub(crate) sender: Pubkey,
+    pub(crate) recipient: Pubkey,
+    pub(crate) kind: UpdateKind,
+    /// Chain-agnostic escrow ID, emitted when a lamport escrow is created.
+    pub(crate) uid: Option<[u8; 32]>,
+    /// Escrowed lamports, or the stake account balance for stake escrows.
+    amount: u64,
+}
+
+impl LoggedEvent {
+    /// Converts the event into an update emitted by transaction `signature`.
+    pub(crate) fn into_update(self, signature: &str) -> EscrowUpdate {
+        EscrowUpdate {
+            escrow: self.escrow.to_string(),
+            kind: self.kind,
+            sender: Some(self.sender.to_string()),
+            recipient: Some(self.recipient.to_string()),
+            amount: BigNumber::from(self.amount),
+            tx: Some(signature.to_string()),
+        }
+    }
+}
+
+/// Decodes an [`EscrowEvent`] or [`StakeEscrowEvent`] from a log line of a
+/// transaction invoking the program at `program_id`.
+///
+/// Returns `None` for any other log line.
+pub(crate) fn decode(line: &str, program_id: &Pubkey) -> Option<LoggedEvent> {
+    match decode_event(line, program_id)? {
+        ProgramEvent::Escrow {
+            escrow,
+            bump,
+            sender,
+            recipient,
+            amount,
+            action,
+            uid,
+            ..
+        } => Some(LoggedEvent {
+            escrow,
+            bump,
+            sender,
+            recipient,
+            kind: action,
+            uid: uid.map(|uid| uid.0),
+            amount,
+        }),
+        ProgramEvent::StakeEscrow {
+            escrow,
+            bump,
+            sender,
+            recipient,
+            lamports,
+            action,
+            ..
+        } => Some(LoggedEvent {
+            escrow,
+            bump,
+            sender,
+            recipient,
+            kind: action,
+            uid: None,
+            amount: lamports,
+        }),
+        ProgramEvent::Compliance { .. } => None,
+    }
+}
+
+/// Maps the program's lifecycle action to an update kind.
+fn kind(action: &EscrowState) -> UpdateKind {
+    match action {
+        EscrowState::Created => UpdateKind::Created,
+        EscrowState::Finished => UpdateKind::Finished,
+        EscrowState::Cancelled => UpdateKind::Cancelled,
+    }
+}
+
+/// Decodes a zero-padded memo.
+fn memo(bytes: &[u8; 64]) -> String {
+    let len = bytes.iter().rposition(|b| *b != 0).map_or(0, |i| i + 1);
+    String::from_utf8_lossy(&bytes[..len]).into_owned()
+}
+
+/// Serializes a public key as base58.
+fn base58<S: Serializer>(key: &Pubkey, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+    serializer.collect_str(key)
+}
@@ -0,0 +1,64 @@
+//! Address lookup table helpers.
+//!
+//! Versioned (v0) transactions can refer to accounts by their index in an
+//! on-chain lookup table instead of by their 32-byte address, which keeps
+//! finish transactions carrying verifier and token accounts under the
+//! transaction size limit.
+
+use solana_sdk::address_lookup_table::instruction::{create_lookup_table, extend_lookup_table};
+use solana_sdk::address_lookup_table::state::AddressLookupTable;
+use solana_sdk::instruction::Instruction;
+use solana_sdk::message::AddressLookupTableAccount;
+use solana_sdk::pubkey::Pubkey;
+
+use crate::error::ClientError;
+use crate::Result;
+
+/// Instruction name for logging.
+pub(crate) const LOOKUP_TABLE: &str = "lookup_table";
+
+/// Addresses appended per extend instruction, keeping each transaction
+/// under the size limit.
+pub const MAX_ADDRESSES_PER_EXTEND: usize = 20;
+
+/// Builds the instruction creating a lookup table owned by `authority` and
+/// funded by `payer`, returning it with the table's address.
+///
+/// The table address is derived from `recent_slot`, which must be a recent
+/// finalized slot.
+pub fn create_instruction(
+    authority: &Pubkey,
+    payer: &Pubkey,
+    recent_slot: u64,
+) -> (Instruction, Pubkey) {
+    create_lookup_table(*authority, *payer, recent_slot)
+}
+
+/// Builds the instructions appending `addresses` to `table`, at most
+/// [`MAX_ADDRESSES_PER_EXTEND`] per instruction. Each instruction must be
+/// sent in its own transaction.
+pub fn extend_instructions(
+    table: &Pubkey,
+    authority: &Pubkey,
+    payer: &Pubkey,
+    addresses: &[Pubkey],
+) -> Vec<Instruction> {
+    addresses
+        .chunks(MAX_ADDRESSES_PER_EXTEND)
+        .map(|chunk| extend_lookup_table(*table, *authority, Some(*payer), chunk.to_vec()))
+        .collect()
+}
+
+/// Decodes the lookup table account at `address`.
+///
+/// # Errors
+///
+/// Returns an error if `data` is not a lookup table account.
+pub fn decode(address: Pubkey, data: &[u8]) -> Result<AddressLookupTableAccount> {
+    let table = AddressLookupTable::deserialize(data)
+        .map_err(|e| ClientError::solana(LOOKUP_TABLE, format!("{address}: {e}")))?;
+    Ok(AddressLookupTableAccount {
+        key: address,
+        addresses: table.addresses.to_vec(),
+    })
+}
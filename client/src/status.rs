@@ -0,0 +1,149 @@
+//! Live on-chain escrow state.
+
+use std::fmt;
+
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use solana_sdk::clock::DEFAULT_MS_PER_SLOT;
+use zescrow_core::interface::ChainConfig;
+use zescrow_core::{BigNumber, Chain, EscrowId};
+
+/// Live on-chain state of an escrow, as read by
+/// [`ZescrowClient::get_escrow_state`](crate::ZescrowClient::get_escrow_state).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EscrowStatus {
+    /// Whether the escrow has been finished or cancelled. Settled Solana
+    /// escrows are closed, so a missing escrow account also counts as settled.
+    pub settled: bool,
+    /// Amount still escrowed, in the asset's smallest unit.
+    pub amount: BigNumber,
+    /// Block/slot at or after which the escrow can be finished.
+    pub finish_after: Option<u64>,
+    /// Block/slot at or after which the escrow can be cancelled.
+    pub cancel_after: Option<u64>,
+    /// Current block/slot, in the units the timelocks are measured in.
+    pub current_height: u64,
+    /// Whether finishing requires a proof that the conditions are met.
+    pub has_conditions: bool,
+}
+
+/// An escrow found on-chain by
+/// [`ZescrowClient::list_escrows`](crate::ZescrowClient::list_escrows).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EscrowSummary {
+    /// Escrow identifier: the escrow PDA on Solana, the contract-assigned
+    /// escrow ID on Ethereum, Cosmos, Substrate, Tron, and Hedera, the UTxO
+    /// reference `<tx hash>#<index>` on Cardano.
+    pub id: String,
+    /// Chain-agnostic escrow ID, where the chain reports it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub uid: Option<EscrowId>,
+    /// Depositor.
+    pub sender: String,
+    /// Beneficiary.
+    pub recipient: String,
+    /// Live state of the escrow.
+    pub status: EscrowStatus,
+}
+
+impl EscrowStatus {
+    /// Short label for the escrow's lifecycle state.
+    pub fn state(&self) -> &'static str {
+        if self.settled {
+            "settled"
+        } else {
+            "funded"
+        }
+    }
+
+    /// Whether the timelocks currently allow the recipient to finish.
+    ///
+    /// Does not account for the proof required by escrows with conditions.
+    pub fn can_finish(&self) -> bool {
+        !self.settled
+            && self
+                .finish_after
+                .is_none_or(|height| self.current_height >= height)
+    }
+
+    /// Whether the timelocks currently allow the sender to cancel.
+    pub fn can_cancel(&self) -> bool {
+        !self.settled
+            && self
+                .cancel_after
+                .is_some_and(|height| self.current_height >= height)
+    }
+}
+
+/// Serializes the fields along with the derived `state`, `can_finish`, and
+/// `can_cancel`.
+impl Serialize for EscrowStatus {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut status = serializer.serialize_struct("EscrowStatus", 9)?;
+        status.serialize_field("state", self.state())?;
+        status.serialize_field("settled", &self.settled)?;
+        status.serialize_field("amount", &self.amount)?;
+        status.serialize_field("finish_after", &self.finish_after)?;
+        status.serialize_field("cancel_after", &self.cancel_after)?;
+        status.serialize_field("current_height", &self.current_height)?;
+        status.serialize_field("has_conditions", &self.has_conditions)?;
+        status.serialize_field("can_finish", &self.can_finish())?;
+        status.serialize_field("can_cancel", &self.can_cancel())?;
+        status.end()
+    }
+}
+
+impl fmt::Display for EscrowStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let timelock = |height: Option<u64>| match height {
+            None => "not set".to_string(),
+            Some(h) if self.current_height >= h => format!("{h} (passed)"),
+            Some(h) => format!("{h} (in {})", h - self.current_height),
+        };
+        let yes_no = |b: bool| if b { "yes" } else { "no" };
+
+        writeln!(f, "State:          {}", self.state())?;
+        writeln!(f, "Amount:         {}", self.amount)?;
+        writeln!(f, "Current height: {}", self.current_height)?;
+        writeln!(f, "Finish after:   {}", timelock(self.finish_after))?;
+        writeln!(f, "Cancel after:   {}", timelock(self.cancel_after))?;
+        writeln!(f, "Conditions:     {}", yes_no(self.has_conditions))?;
+        writeln!(f, "Can finish:     {}", yes_no(self.can_finish()))?;
+        write!(f, "Can cancel:     {}", yes_no(self.can_cancel()))
+    }
+}
+
+/// Seconds per block assumed for Cosmos chains.
+const COSMOS_BLOCK_SECS: f64 = 6.0;
+
+/// Seconds per block of Substrate chains with the default block time.
+const SUBSTRATE_BLOCK_SECS: f64 = 6.0;
+
+/// Seconds per slot on Cardano networks.
+const CARDANO_SLOT_SECS: f64 = 1.0;
+
+/// Seconds per block on Tron networks.
+const TRON_BLOCK_SECS: f64 = 3.0;
+
+/// Seconds per block on Hedera, whose blocks are the record files of about
+/// two seconds of transactions.
+const HEDERA_BLOCK_SECS: f64 = 2.0;
+
+/// Seconds per block or slot that timelocks on `config`'s chain advance
+/// by, to estimate when a timelock passes.
+pub fn block_secs(config: &ChainConfig) -> f64 {
+    match config.chain {
+        Chain::Solana => DEFAULT_MS_PER_SLOT as f64 / 1000.0,
+        Chain::Ethereum => config
+            .network
+            .as_ref()
+            .map_or(12, |network| network.timelock_block_time_secs())
+            as f64,
+        // Block times vary by chain; most CometBFT chains target 1-6s.
+        Chain::Cosmos => COSMOS_BLOCK_SECS,
+        Chain::Substrate => SUBSTRATE_BLOCK_SECS,
+        Chain::Cardano => CARDANO_SLOT_SECS,
+        Chain::Tron => TRON_BLOCK_SECS,
+        Chain::Hedera => HEDERA_BLOCK_SECS,
+    }
+}
@@ -0,0 +1,370 @@
+//! Local SQLite store of escrow history.
+//!
+//! Records every escrow created through the client, each state transition
+//! it goes through (with the transaction behind it, when known), and the
+//! proofs generated for it, so creating a new escrow no longer overwrites
+//! the last one's metadata.
+//!
+//! The store requires the `sqlite` feature; without it, stores cannot be
+//! opened.
+
+use std::path::Path;
+#[cfg(feature = "sqlite")]
+use std::sync::{Mutex, MutexGuard, PoisonError};
+#[cfg(feature = "sqlite")]
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "sqlite")]
+use rusqlite::{params, Connection, OptionalExtension, Row};
+use serde::Serialize;
+use zescrow_core::{EscrowMetadata, ExecutionState};
+
+use crate::{ClientError, Result};
+
+/// Tables created on first use.
+#[cfg(feature = "sqlite")]
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS escrows (
+    id         INTEGER PRIMARY KEY AUTOINCREMENT,
+    chain      TEXT NOT NULL,
+    escrow_id  INTEGER,
+    sender     TEXT NOT NULL,
+    recipient  TEXT NOT NULL,
+    state      TEXT NOT NULL,
+    metadata   TEXT NOT NULL,
+    created_at INTEGER NOT NULL,
+    updated_at INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS transitions (
+    escrow INTEGER NOT NULL REFERENCES escrows(id),
+    state  TEXT NOT NULL,
+    tx     TEXT,
+    at     INTEGER NOT NULL
+);
+CREATE TABLE IF NOT EXISTS proofs (
+    escrow INTEGER NOT NULL REFERENCES escrows(id),
+    seal   BLOB NOT NULL,
+    at     INTEGER NOT NULL
+);
+";
+
+/// Columns selected for a [`StoredEscrow`].
+#[cfg(feature = "sqlite")]
+const ESCROW_COLUMNS: &str = "id, metadata, created_at, updated_at";
+
+/// An escrow recorded in the store.
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredEscrow {
+    /// Local identifier, assigned in creation order.
+    pub id: i64,
+    /// Escrow metadata, with the latest recorded state.
+    pub metadata: EscrowMetadata,
+    /// When the escrow was recorded, in seconds since the Unix epoch.
+    pub created_at: u64,
+    /// When the escrow's state was last recorded, in seconds since the Unix
+    /// epoch.
+    pub updated_at: u64,
+}
+
+/// A recorded state transition of an escrow.
+#[derive(Debug, Clone, Serialize)]
+pub struct Transition {
+    /// State entered.
+    pub state: ExecutionState,
+    /// Transaction hash or signature that caused the transition, if known.
+    pub tx: Option<String>,
+    /// When the transition was recorded, in seconds since the Unix epoch.
+    pub at: u64,
+}
+
+/// SQLite-backed history of escrows.
+///
+/// # Example
+///
+/// ```ignore
+/// let store = EscrowStore::open("zescrow.db")?;
+/// let id = store.insert(&client.create_escrow(&params).await?, None)?;
+/// // ...
+/// store.update_state(id, ExecutionState::Released, None)?;
+/// ```
+pub struct EscrowStore {
+    #[cfg(feature = "sqlite")]
+    conn: Mutex<Connection>,
+    #[cfg(not(feature = "sqlite"))]
+    unavailable: std::convert::Infallible,
+}
+
+#[cfg(feature = "sqlite")]
+impl EscrowStore {
+    /// Opens the store at `path`, creating it if needed.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self> {
+        Self::init(Connection::open(path)?)
+    }
+
+    /// Opens a store held in memory, discarded when dropped.
+    pub fn open_in_memory() -> Result<Self> {
+        Self::init(Connection::open_in_memory()?)
+    }
+
+    fn init(conn: Connection) -> Result<Self> {
+        conn.execute_batch(SCHEMA)?;
+        Ok(Self {
+            conn: Mutex::new(conn),
+        })
+    }
+
+    /// Records a newly created escrow and its initial state, returning its
+    /// local identifier.
+    ///
+    /// `tx` is the transaction that created the escrow, if known.
+    pub fn insert(&self, metadata: &EscrowMetadata, tx: Option<&str>) -> Result<i64> {
+        let now = now();
+        let state = state_name(metadata.state)?;
+        let mut conn = self.lock();
+        let db = conn.transaction()?;
+        db.execute(
+            "INSERT INTO escrows
+                 (chain, escrow_id, sender, recipient, state, metadata, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?7)",
+            params![
+                metadata.params.chain_config.chain.as_ref(),
+                metadata.escrow_id,
+                metadata.params.sender.to_string(),
+                metadata.params.recipient.to_string(),
+                state,
+                to_json(metadata)?,
+                now,
+            ],
+        )?;
+        let id = db.last_insert_rowid();
+        db.execute(
+            "INSERT INTO transitions (escrow, state, tx, at) VALUES (?1, ?2, ?3, ?4)",
+            params![id, state, tx, now],
+        )?;
+        db.commit()?;
+        Ok(id)
+    }
+
+    /// Returns the escrow with local identifier `id`, if recorded.
+    pub fn get(&self, id: i64) -> Result<Option<StoredEscrow>> {
+        self.lock()
+            .query_row(
+                &format!("SELECT {ESCROW_COLUMNS} FROM escrows WHERE id = ?1"),
+                [id],
+                stored_escrow,
+            )
+            .optional()?
+            .transpose()
+    }
+
+    /// Returns the most recently created escrow, if any.
+    pub fn latest(&self) -> Result<Option<StoredEscrow>> {
+        self.lock()
+            .query_row(
+                &format!("SELECT {ESCROW_COLUMNS} FROM escrows ORDER BY id DESC LIMIT 1"),
+                [],
+                stored_escrow,
+            )
+            .optional()?
+            .transpose()
+    }
+
+    /// Returns all recorded escrows, oldest first, optionally only those in
+    /// `state`.
+    pub fn list(&self, state: Option<ExecutionState>) -> Result<Vec<StoredEscrow>> {
+        let state = state.map(state_name).transpose()?;
+        let conn = self.lock();
+        let mut query = conn.prepare(&format!(
+            "SELECT {ESCROW_COLUMNS} FROM escrows
+             WHERE ?1 IS NULL OR state = ?1 ORDER BY id"
+        ))?;
+        let rows = query
+            .query_map([state], stored_escrow)?
+            .collect::<rusqlite::Result<Vec<_>>>()?;
+        rows.into_iter().collect()
+    }
+
+    /// Records that escrow `id` entered `state`, caused by transaction `tx`
+    /// if known.
+    pub fn update_state(&self, id: i64, state: ExecutionState, tx: Option<&str>) -> Result<()> {
+        let mut escrow = self
+            .get(id)?
+            .ok_or_else(|| ClientError::Store(format!("no escrow with ID {id}")))?;
+        escrow.metadata.state = state;
+        escrow.metadata.observed = None;
+        self.update(id, &escrow.metadata, tx)
+    }
+
+    /// Records that escrow `id` entered the state in `metadata`, e.g. a
+    /// pending state with the block it was observed in (see
+    /// [`reorg`](crate::reorg)), caused by transaction `tx` if known.
+    pub fn update(&self, id: i64, metadata: &EscrowMetadata, tx: Option<&str>) -> Result<()> {
+        let now = now();
+        let name = state_name(metadata.state)?;
+        let mut conn = self.lock();
+        let db = conn.transaction()?;
+        let updated = db.execute(
+            "UPDATE escrows SET state = ?2, metadata = ?3, updated_at = ?4 WHERE id = ?1",
+            params![id, name, to_json(metadata)?, now],
+        )?;
+        if updated == 0 {
+            return Err(ClientError::Store(format!("no escrow with ID {id}")));
+        }
+        db.execute(
+            "INSERT INTO transitions (escrow, state, tx, at) VALUES (?1, ?2, ?3, ?4)",
+            params![id, name, tx, now],
+        )?;
+        db.commit()?;
+        Ok(())
+    }
+
+    /// Returns the state transitions of escrow `id`, oldest first.
+    pub fn transitions(&self, id: i64) -> Result<Vec<Transition>> {
+        let conn = self.lock();
+        let mut query =
+            conn.prepare("SELECT state, tx, at FROM transitions WHERE escrow = ?1 ORDER BY rowid")?;
+        let rows = query.query_map([id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, u64>(2)?,
+            ))
+        })?;
+        rows.map(|row| {
+            let (state, tx, at) = row?;
+            Ok(Transition {
+                state: parse_state(state)?,
+                tx,
+                at,
+            })
+        })
+        .collect()
+    }
+
+    /// Records a proof seal generated for escrow `id`.
+    pub fn record_proof(&self, id: i64, seal: &[u8]) -> Result<()> {
+        self.lock().execute(
+            "INSERT INTO proofs (escrow, seal, at) VALUES (?1, ?2, ?3)",
+            params![id, seal, now()],
+        )?;
+        Ok(())
+    }
+
+    /// Returns the most recent proof seal recorded for escrow `id`.
+    pub fn latest_proof(&self, id: i64) -> Result<Option<Vec<u8>>> {
+        Ok(self
+            .lock()
+            .query_row(
+                "SELECT seal FROM proofs WHERE escrow = ?1 ORDER BY rowid DESC LIMIT 1",
+                [id],
+                |row| row.get(0),
+            )
+            .optional()?)
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.conn.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+/// Without the `sqlite` feature, stores cannot be opened, so no other
+/// method can be called.
+#[cfg(not(feature = "sqlite"))]
+impl EscrowStore {
+    pub fn open(_path: impl AsRef<Path>) -> Result<Self> {
+        Err(ClientError::Store(
+            "the escrow store requires the `sqlite` feature".into(),
+        ))
+    }
+
+    pub fn open_in_memory() -> Result<Self> {
+        Self::open("")
+    }
+
+    pub fn insert(&self, _metadata: &EscrowMetadata, _tx: Option<&str>) -> Result<i64> {
+        match self.unavailable {}
+    }
+
+    pub fn get(&self, _id: i64) -> Result<Option<StoredEscrow>> {
+        match self.unavailable {}
+    }
+
+    pub fn latest(&self) -> Result<Option<StoredEscrow>> {
+        match self.unavailable {}
+    }
+
+    pub fn list(&self, _state: Option<ExecutionState>) -> Result<Vec<StoredEscrow>> {
+        match self.unavailable {}
+    }
+
+    pub fn update_state(&self, _id: i64, _state: ExecutionState, _tx: Option<&str>) -> Result<()> {
+        match self.unavailable {}
+    }
+
+    pub fn update(&self, _id: i64, _metadata: &EscrowMetadata, _tx: Option<&str>) -> Result<()> {
+        match self.unavailable {}
+    }
+
+    pub fn transitions(&self, _id: i64) -> Result<Vec<Transition>> {
+        match self.unavailable {}
+    }
+
+    pub fn record_proof(&self, _id: i64, _seal: &[u8]) -> Result<()> {
+        match self.unavailable {}
+    }
+
+    pub fn latest_proof(&self, _id: i64) -> Result<Option<Vec<u8>>> {
+        match self.unavailable {}
+    }
+}
+
+impl std::fmt::Debug for EscrowStore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EscrowStore").finish_non_exhaustive()
+    }
+}
+
+/// Maps a row of [`ESCROW_COLUMNS`], deferring metadata decoding errors.
+#[cfg(feature = "sqlite")]
+fn stored_escrow(row: &Row<'_>) -> rusqlite::Result<Result<StoredEscrow>> {
+    let metadata: String = row.get(1)?;
+    let (id, created_at, updated_at) = (row.get(0)?, row.get(2)?, row.get(3)?);
+    Ok(serde_json::from_str(&metadata)
+        .map_err(|e| ClientError::Store(format!("decoding escrow {id}: {e}")))
+        .map(|metadata| StoredEscrow {
+            id,
+            metadata,
+            created_at,
+            updated_at,
+        }))
+}
+
+#[cfg(feature = "sqlite")]
+fn to_json(metadata: &EscrowMetadata) -> Result<String> {
+    serde_json::to_string(metadata).map_err(|e| ClientError::Serialization(e.to_string()))
+}
+
+/// Returns the name `state` is serialized as, e.g. `Funded`.
+#[cfg(feature = "sqlite")]
+fn state_name(state: ExecutionState) -> Result<String> {
+    match serde_json::to_value(state) {
+        Ok(serde_json::Value::String(name)) => Ok(name),
+        _ => Err(ClientError::Serialization(format!(
+            "unexpected encoding of state {state:?}"
+        ))),
+    }
+}
+
+#[cfg(feature = "sqlite")]
+fn parse_state(name: String) -> Result<ExecutionState> {
+    serde_json::from_value(serde_json::Value::String(name))
+        .map_err(|e| ClientError::Store(format!("unknown escrow state: {e}")))
+}
+
+/// Seconds since the Unix epoch.
+#[cfg(feature = "sqlite")]
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
@@ -0,0 +1,995 @@
+//! Substrate blockchain agent implementation.
+//!
+//! Provides [`SubstrateAgent`] for interacting with the Zescrow ink! escrow
+//! contract on Substrate chains running `pallet-contracts` (e.g., Astar,
+//! Aleph Zero). Calls are dry-run through the `ContractsApi` runtime API to
+//! set their weight limit, then signed as mortal extrinsics, valid for
+//! [`SubstrateConfig::mortality`] blocks, and awaited until finalized.
+//!
+//! The contract does not verify proofs: as on Solana, conditions are
+//! checked off-chain before the recipient finishes an escrow.
+
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use futures::channel::mpsc;
+use futures::StreamExt;
+use num_traits::ToPrimitive;
+use sha2::{Digest, Sha256};
+use subxt::backend::legacy::LegacyRpcMethods;
+use subxt::backend::rpc::RpcClient;
+use subxt::blocks::ExtrinsicEvents;
+use subxt::config::substrate::BlakeTwo256;
+use subxt::config::{DefaultExtrinsicParamsBuilder, Hasher};
+use subxt::dynamic::Value;
+use subxt::ext::codec::{self, Decode, Encode, Input};
+use subxt::tx::DynamicPayload;
+use subxt::utils::{AccountId32, H256};
+use subxt::{OnlineClient, PolkadotConfig};
+use subxt_signer::sr25519::Keypair;
+use subxt_signer::SecretUri;
+use tokio::time::Instant;
+use tracing::{debug, info, warn};
+use zescrow_core::interface::{ChainConfig, SubstrateConfig};
+use zescrow_core::{
+    AssetKind, BigNumber, Chain, ChainMetadata, EscrowId, EscrowMetadata, EscrowParams,
+    ExecutionState, Party, ID,
+};
+use zescrow_ink::escrow::{Action, Error as ContractError, Escrow, EscrowState, EscrowUpdated};
+use zescrow_ink::{selectors, MAX_LIMIT};
+
+use super::Agent;
+use crate::error::ClientError;
+use crate::pending::{PendingTransaction, WaitConfig};
+use crate::rpc::{Failover, RpcPolicy};
+use crate::simulation::{Operation, Simulation};
+use crate::watch::{EscrowUpdate, UpdateKind, UpdateStream};
+use crate::{EscrowStatus, EscrowSummary, EthereumSigner, Result};
+
+// Contract messages for logging.
+const CREATE_ESCROW: &str = "create";
+const FINISH_ESCROW: &str = "finish";
+const CANCEL_ESCROW: &str = "cancel";
+const GET_ESCROW: &str = "escrow";
+const LIST_ESCROWS: &str = "escrows_by_party";
+const WATCH: &str = "watch";
+const WAIT_FOR_TRANSACTION: &str = "wait_for_transaction";
+
+/// Interval between confirmation polls unless configured: about a block.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(6);
+
+/// Flag set in a contract's return value when the call reverted.
+const REVERT_FLAG: u32 = 1;
+
+/// Longest mortality period of an extrinsic, in blocks.
+const MAX_ERA_PERIOD: u64 = 1 << 16;
+
+/// Shared handle to an sr25519 keypair signing Substrate extrinsics.
+#[derive(Clone)]
+pub struct SubstrateSigner(Arc<Keypair>);
+
+impl SubstrateSigner {
+    /// Creates a signer from a 32-byte sr25519 seed (mini secret key).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `seed` is not 32 bytes.
+    pub fn from_bytes(seed: &[u8]) -> Result<Self> {
+        let seed = <[u8; 32]>::try_from(seed)
+            .map_err(|_| ClientError::Keypair("sr25519 seeds are 32 bytes".into()))?;
+        Keypair::from_secret_key(seed)
+            .map(|keypair| Self(Arc::new(keypair)))
+            .map_err(|e| ClientError::Keypair(format!("invalid sr25519 seed: {e}")))
+    }
+
+    /// Loads a signer from a `sender_private_id`-style string: a hex seed,
+    /// with or without `0x`, `keystore:<name>` for a key in the encrypted
+    /// [`Keystore`](crate::keystore::Keystore), or a secret URI such as a
+    /// mnemonic with optional derivation path, or `//Alice` on development
+    /// chains.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key cannot be parsed or decrypted.
+    pub fn from_id(id: &str) -> Result<Self> {
+        if let Some(signer) = crate::keystore::substrate_signer(id) {
+            return signer;
+        }
+        let id = id.trim();
+        if let Ok(seed) = hex::decode(id.trim_start_matches("0x")) {
+            return Self::from_bytes(&seed);
+        }
+        let uri = SecretUri::from_str(id)
+            .map_err(|e| ClientError::Keypair(format!("invalid secret URI: {e}")))?;
+        Keypair::from_uri(&uri)
+            .map(|keypair| Self(Arc::new(keypair)))
+            .map_err(|e| ClientError::Keypair(format!("invalid secret URI: {e}")))
+    }
+
+    /// Creates a signer whose seed is the secret of a local Ethereum key,
+    /// so hex keys given for any chain can sign Substrate extrinsics.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the key is held by a KMS.
+    pub fn from_ethereum(signer: &EthereumSigner) -> Result<Self> {
+        match signer {
+            EthereumSigner::Local(signer) => Self::from_bytes(&signer.credential().to_bytes()),
+            #[cfg(any(feature = "aws-kms", feature = "gcp-kms"))]
+            _ => Err(ClientError::Keypair(
+                "KMS-held keys cannot sign Substrate extrinsics".into(),
+            )),
+        }
+    }
+
+    /// Returns the account ID (public key) of the signer.
+    pub fn account_id(&self) -> AccountId32 {
+        self.0.public_key().to_account_id()
+    }
+
+    /// Returns the SS58 address of the signer on a chain with the network
+    /// `prefix`.
+    pub fn address(&self, prefix: u16) -> String {
+        ID::ss58(&self.account_id().0, prefix).to_string()
+    }
+}
+
+impl fmt::Debug for SubstrateSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("SubstrateSigner")
+            .field(&self.account_id())
+            .finish()
+    }
+}
+
+/// Substrate blockchain agent for escrow operations.
+///
+/// Manages interactions with the Zescrow ink! contract, including dry runs,
+/// extrinsic signing, and submission.
+pub struct SubstrateAgent {
+    /// Client of the node, with the chain's metadata.
+    api: OnlineClient<PolkadotConfig>,
+    /// Legacy RPC methods, to look up blocks by number.
+    rpc: LegacyRpcMethods<PolkadotConfig>,
+    /// Account of the deployed escrow contract.
+    contract: AccountId32,
+    /// SS58 prefix of the chain's addresses, taken from the contract's.
+    prefix: u16,
+    /// Mortality of signed extrinsics.
+    config: SubstrateConfig,
+    /// Signer of the escrow creator (sender).
+    sender: SubstrateSigner,
+    /// Optional signer of the escrow beneficiary (recipient).
+    recipient: Option<SubstrateSigner>,
+    /// Timeout and polling interval of confirmation waits.
+    wait: WaitConfig,
+}
+
+impl SubstrateAgent {
+    /// Creates a new Substrate agent from chain configuration, connecting
+    /// to the node at `rpc_url`.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Chain configuration with the node's WebSocket URL,
+    ///   sender key, contract address, and optional [`SubstrateConfig`]
+    /// * `sender` - Optional signer overriding `sender_private_id`
+    /// * `recipient` - Optional recipient signer for finish operations
+    /// * `rpc` - Retry settings and metrics for connecting, which fails
+    ///   over to `fallback_rpc_urls` when `rpc_url` is unavailable
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if:
+    /// - A key cannot be loaded
+    /// - The contract address is not an SS58 address
+    /// - No endpoint can be connected to
+    pub async fn new(
+        config: &ChainConfig,
+        sender: Option<SubstrateSigner>,
+        recipient: Option<SubstrateSigner>,
+        rpc: RpcPolicy,
+    ) -> Result<Self> {
+        let ChainConfig {
+            rpc_url,
+            fallback_rpc_urls,
+            sender_private_id,
+            agent_id,
+            substrate,
+            ..
+        } = config;
+
+        let contract_id = ID::Ss58(agent_id.clone());
+        let prefix = contract_id.ss58_prefix().ok_or_else(|| {
+            ClientError::substrate(
+                "parse_contract",
+                format!("invalid SS58 address: {agent_id}"),
+            )
+        })?;
+        let contract = account_id(&contract_id.to_bytes()?, "parse_contract")?;
+        info!(%agent_id, "Using escrow contract");
+
+        let sender = match sender {
+            Some(sender) => sender,
+            None => SubstrateSigner::from_id(sender_private_id)?,
+        };
+        debug!(sender = %sender.address(prefix), "Loaded sender signer");
+        if let Some(recipient) = &recipient {
+            debug!(recipient = %recipient.address(prefix), "Loaded recipient signer");
+        }
+
+        let fallbacks = fallback_rpc_urls
+            .iter()
+            .map(|url| (url.clone(), url.clone()));
+        let endpoints = Failover::new((rpc_url.clone(), rpc_url.clone()), fallbacks, rpc);
+        let (api, rpc) = endpoints
+            .run(|url| connect(url.clone()), |_| true)
+            .await
+            .map_err(|e| ClientError::substrate("connect", e))?;
+        info!(url = %endpoints.url(), "Connected to node");
+
+        Ok(Self {
+            api,
+            rpc,
+            contract,
+            prefix,
+            config: substrate.clone().unwrap_or_default(),
+            sender,
+            recipient,
+            wait: WaitConfig::default(),
+        })
+    }
+
+    /// Stops waiting for finalization after [`WaitConfig::timeout`],
+    /// failing with [`ClientError::Pending`], and polls at
+    /// [`WaitConfig::poll_interval`] when resuming a wait.
+    pub fn with_wait(mut self, wait: WaitConfig) -> Self {
+        self.wait = wait;
+        self
+    }
+
+    fn poll_interval(&self) -> Duration {
+        self.wait.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL)
+    }
+
+    fn recipient_signer(&self) -> Result<&SubstrateSigner> {
+        self.recipient
+            .as_ref()
+            .ok_or_else(|| ClientError::substrate(FINISH_ESCROW, "recipient key not configured"))
+    }
+
+    /// Verifies that a signer's account matches the escrow party.
+    fn validate_signer(signer: &SubstrateSigner, expected: &Party, role: &str) -> Result<()> {
+        let account = signer.account_id();
+        (party_account(expected)? == account)
+            .then_some(())
+            .ok_or_else(|| {
+                ClientError::Keypair(format!(
+                    "{role} key mismatch: expected {expected}, got {account}"
+                ))
+            })
+    }
+
+    /// Returns the CAIP-2 ID of the connected chain: `polkadot:` followed by
+    /// the first 32 hex characters of its genesis hash.
+    fn caip2_id(&self) -> String {
+        let genesis = hex::encode(self.api.genesis_hash());
+        format!("polkadot:{}", &genesis[..32])
+    }
+
+    /// Returns the contract-assigned ID of the escrow in `metadata`.
+    fn escrow_id(metadata: &EscrowMetadata) -> Result<u64> {
+        metadata.escrow_id.ok_or_else(|| {
+            ClientError::InvalidChainOperation("escrow ID is required on Substrate".into())
+        })
+    }
+
+    /// Encodes the `create` call for `params`, with the value it locks.
+    fn create_call(&self, params: &EscrowParams) -> Result<(Vec<u8>, u128)> {
+        Self::validate_signer(&self.sender, &params.sender, "sender")?;
+        crate::reject_memo(params)?;
+        if !matches!(params.asset.kind, AssetKind::Native) {
+            return Err(ClientError::InvalidChainOperation(format!(
+                "{:?} assets are not supported on Substrate",
+                params.asset.kind
+            )));
+        }
+        let value = params.asset.amount.0.to_u128().ok_or_else(|| {
+            ClientError::substrate(CREATE_ESCROW, "amount exceeds the range of a balance")
+        })?;
+        let recipient = party_account(&params.recipient)?;
+        let args = (
+            recipient.0,
+            params.finish_after,
+            params.cancel_after,
+            params.has_conditions,
+            &params.memo,
+            <[u8; 32]>::from(Sha256::digest(self.caip2_id())),
+        );
+        Ok((message(selectors::CREATE, args), value))
+    }
+
+    /// Encodes the contract call for `operation`, with the value it
+    /// transfers, the signer sending it, and the name of the call.
+    fn operation_call(
+        &self,
+        operation: Operation<'_>,
+    ) -> Result<(Vec<u8>, u128, &SubstrateSigner, &'static str)> {
+        Ok(match operation {
+            Operation::Create(params) => {
+                let (data, value) = self.create_call(params)?;
+                (data, value, &self.sender, CREATE_ESCROW)
+            }
+            Operation::Finish(metadata) => {
+                let signer = self.recipient_signer()?;
+                Self::validate_signer(signer, &metadata.params.recipient, "recipient")?;
+                let id = Self::escrow_id(metadata)?;
+                (message(selectors::FINISH, id), 0, signer, FINISH_ESCROW)
+            }
+            Operation::Cancel(metadata) => {
+                Self::validate_signer(&self.sender, &metadata.params.sender, "sender")?;
+                let id = Self::escrow_id(metadata)?;
+                (
+                    message(selectors::CANCEL, id),
+                    0,
+                    &self.sender,
+                    CANCEL_ESCROW,
+                )
+            }
+        })
+    }
+
+    /// Returns the number of the latest finalized block.
+    async fn height(&self, operation: &'static str) -> Result<u64> {
+        let block = self
+            .api
+            .blocks()
+            .at_latest()
+            .await
+            .map_err(|e| ClientError::substrate(operation, e))?;
+        Ok(block.number().into())
+    }
+
+    /// Dry-runs a call of the contract with `data` and `value` from
+    /// `origin`, without a weight or storage deposit limit.
+    async fn dry_run(
+        &self,
+        origin: &AccountId32,
+        data: &[u8],
+        value: u128,
+        operation: &'static str,
+    ) -> Result<DryRun> {
+        let params = (
+            origin,
+            &self.contract,
+            value,
+            None::<Weight>,
+            None::<u128>,
+            data,
+        )
+            .encode();
+        self.api
+            .runtime_api()
+            .at_latest()
+            .await
+            .map_err(|e| ClientError::substrate(operation, e))?
+            .call_raw("ContractsApi_call", Some(params.as_slice()))
+            .await
+            .map_err(|e| ClientError::substrate(operation, e))
+    }
+
+    /// Runs a read-only message of the contract, decoding its return value.
+    async fn query<T: Decode>(&self, data: &[u8], operation: &'static str) -> Result<T> {
+        let dry_run = self
+            .dry_run(&self.sender.account_id(), data, 0, operation)
+            .await?;
+        let returned = self
+            .returned(&dry_run)
+            .map_err(|e| ClientError::substrate(operation, e))?;
+        match <std::result::Result<T, u8>>::decode(&mut returned.as_slice()) {
+            Ok(Ok(value)) => Ok(value),
+            Ok(Err(_)) => Err(ClientError::substrate(
+                operation,
+                "contract could not decode the call",
+            )),
+            Err(e) => Err(ClientError::Serialization(e.to_string())),
+        }
+    }
+
+    /// Returns the data a dry-run call returned, or why it failed: a
+    /// dispatch error, or the contract error it reverted with.
+    fn returned(&self, dry_run: &DryRun) -> std::result::Result<Vec<u8>, String> {
+        match &dry_run.outcome {
+            Err(error) => Err(self.describe(error)),
+            Ok(value) if value.flags & REVERT_FLAG != 0 => {
+                let error = <std::result::Result<ContractResult<()>, u8>>::decode(
+                    &mut value.data.as_slice(),
+                );
+                Err(match error {
+                    Ok(Ok(Err(error))) => format!("contract reverted: {error:?}"),
+                    _ => "contract reverted".into(),
+                })
+            }
+            Ok(value) => Ok(value.data.clone()),
+        }
+    }
+
+    /// Names a dispatch error after the pallet error it wraps, if any.
+    fn describe(&self, error: &DispatchError) -> String {
+        let DispatchError::Module { index, error } = *error else {
+            return format!("{error:?}");
+        };
+        let metadata = self.api.metadata();
+        metadata
+            .pallet_by_index(index)
+            .and_then(|pallet| {
+                let variant = pallet.error_variant_by_index(error)?;
+                Some(format!("{}::{}", pallet.name(), variant.name))
+            })
+            .unwrap_or_else(|| format!("module error {index}:{error}"))
+    }
+
+    /// Builds the dynamic `Contracts::call` extrinsic of a call the dry run
+    /// priced.
+    fn call_payload(&self, data: Vec<u8>, value: u128, dry_run: &DryRun) -> DynamicPayload {
+        let weight = Value::named_composite([
+            (
+                "ref_time",
+                Value::u128(dry_run.gas_required.ref_time.into()),
+            ),
+            (
+                "proof_size",
+                Value::u128(dry_run.gas_required.proof_size.into()),
+            ),
+        ]);
+        let storage_deposit_limit = match dry_run.storage_deposit {
+            StorageDeposit::Charge(amount) => Value::unnamed_variant("Some", [Value::u128(amount)]),
+            StorageDeposit::Refund(_) => Value::unnamed_variant("None", []),
+        };
+        subxt::dynamic::tx(
+            "Contracts",
+            "call",
+            vec![
+                Value::unnamed_variant("Id", [Value::from_bytes(self.contract.0)]),
+                Value::u128(value),
+                weight,
+                storage_deposit_limit,
+                Value::from_bytes(data),
+            ],
+        )
+    }
+
+    /// Dry-runs the call to price it, then signs it as a mortal extrinsic,
+    /// submits it, and waits for it to be finalized.
+    async fn execute(&self, operation: Operation<'_>) -> Result<ExtrinsicEvents<PolkadotConfig>> {
+        let (data, value, signer, name) = self.operation_call(operation)?;
+        let dry_run = self
+            .dry_run(&signer.account_id(), &data, value, name)
+            .await?;
+        self.returned(&dry_run)
+            .map_err(|e| ClientError::substrate(name, e))?;
+        debug!(weight = ?dry_run.gas_required, "Dry-ran {} call", name);
+
+        let payload = self.call_payload(data, value, &dry_run);
+        let birth = self
+            .api
+            .blocks()
+            .at_latest()
+            .await
+            .map_err(|e| ClientError::substrate(name, e))?;
+        let params = DefaultExtrinsicParamsBuilder::<PolkadotConfig>::new()
+            .mortal(birth.header(), self.config.mortality)
+            .build();
+        let progress = self
+            .api
+            .tx()
+            .sign_and_submit_then_watch(&payload, &*signer.0, params)
+            .await
+            .map_err(|e| ClientError::substrate(name, e))?;
+        let hash = progress.extrinsic_hash();
+        info!(tx_hash = ?hash, "{} extrinsic sent", name);
+
+        let finalized = progress.wait_for_finalized_success();
+        let events = match self.wait.timeout {
+            Some(timeout) => tokio::time::timeout(timeout, finalized)
+                .await
+                .map_err(|_| pending(name, hash, birth.hash()))?,
+            None => finalized.await,
+        };
+        events.map_err(|e| ClientError::substrate(name, e))
+    }
+
+    /// Scans finalized blocks from the one after `birth` for extrinsic
+    /// `hash` until it is found, its mortality period passes, or the wait
+    /// times out.
+    async fn await_extrinsic(
+        &self,
+        hash: H256,
+        birth: H256,
+        operation: &'static str,
+    ) -> Result<ExtrinsicEvents<PolkadotConfig>> {
+        let error = |e: subxt::Error| ClientError::substrate(operation, e);
+        let birth_number: u64 = self
+            .api
+            .blocks()
+            .at(birth)
+            .await
+            .map_err(error)?
+            .number()
+            .into();
+        let expiry = birth_number + era_period(self.config.mortality);
+        let deadline = self.wait.timeout.map(|timeout| Instant::now() + timeout);
+        let mut next = birth_number + 1;
+        loop {
+            let finalized = self.height(operation).await?;
+            while next <= finalized.min(expiry) {
+                let block_hash = self
+                    .rpc
+                    .chain_get_block_hash(Some(next.into()))
+                    .await
+                    .map_err(error)?
+                    .ok_or_else(|| {
+                        ClientError::substrate(operation, format!("block {next} not found"))
+                    })?;
+                let extrinsics = self
+                    .api
+                    .blocks()
+                    .at(block_hash)
+                    .await
+                    .map_err(error)?
+                    .extrinsics()
+                    .await
+                    .map_err(error)?;
+                for extrinsic in extrinsics.iter() {
+                    if BlakeTwo256::hash(extrinsic.bytes()) == hash {
+                        let events = extrinsic.events().await.map_err(error)?;
+                        return check(events, operation);
+                    }
+                }
+                next += 1;
+            }
+            if finalized >= expiry {
+                return Err(ClientError::tx_dropped(format!(
+                    "extrinsic {hash:?} was not included before its era ended at block {expiry}"
+                )));
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(pending(operation, hash, birth));
+            }
+            tokio::time::sleep(self.poll_interval()).await;
+        }
+    }
+
+    /// Accounts of the configured sender and recipient.
+    fn parties(&self) -> Vec<AccountId32> {
+        std::iter::once(&self.sender)
+            .chain(self.recipient.as_ref())
+            .map(SubstrateSigner::account_id)
+            .collect()
+    }
+
+    fn status(&self, escrow: &Escrow, current_height: u64) -> EscrowStatus {
+        EscrowStatus {
+            settled: escrow.state != EscrowState::Funded,
+            amount: BigNumber::from(escrow.amount),
+            finish_after: escrow.finish_after,
+            cancel_after: escrow.cancel_after,
+            current_height,
+            has_conditions: escrow.has_conditions,
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Agent for SubstrateAgent {
+    async fn create_escrow(&self, params: &EscrowParams) -> Result<EscrowMetadata> {
+        let events = self.execute(Operation::Create(params)).await?;
+        info!("{} extrinsic finalized", CREATE_ESCROW);
+
+        let (escrow_id, uid) = contract_events(&events, &self.contract)
+            .into_iter()
+            .find(|event| event.action == Action::Created)
+            .map(|event| (event.id, EscrowId(event.uid)))
+            .ok_or_else(|| ClientError::MissingEvent("EscrowUpdated contract event".into()))?;
+        let block_number = self
+            .api
+            .blocks()
+            .at(events.block_hash())
+            .await
+            .map_err(|e| ClientError::substrate(CREATE_ESCROW, e))?
+            .number()
+            .into();
+        debug!(escrow_id, %uid, "Escrow created");
+
+        Ok(EscrowMetadata {
+            params: params.clone(),
+            state: ExecutionState::Funded,
+            escrow_id: Some(escrow_id),
+            uid: Some(uid),
+            chain_metadata: Some(ChainMetadata::Substrate {
+                tx_hash: format!("{:?}", events.extrinsic_hash()),
+                block_number,
+            }),
+            guest: None,
+            observed: None,
+        })
+    }
+
+    async fn finish_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
+        self.execute(Operation::Finish(metadata)).await?;
+        info!("{} extrinsic finalized", FINISH_ESCROW);
+        Ok(())
+    }
+
+    async fn cancel_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
+        self.execute(Operation::Cancel(metadata)).await?;
+        info!("{} extrinsic finalized", CANCEL_ESCROW);
+        Ok(())
+    }
+
+    /// Dry-runs the call, and estimates the fee of the signed extrinsic
+    /// with the weight the dry run requires.
+    async fn simulate(&self, operation: Operation<'_>) -> Result<Simulation> {
+        let (data, value, signer, name) = self.operation_call(operation)?;
+        let dry_run = self
+            .dry_run(&signer.account_id(), &data, value, name)
+            .await?;
+        if let Err(error) = self.returned(&dry_run) {
+            return Ok(Simulation {
+                operation: name,
+                error: Some(error),
+                logs: Vec::new(),
+                units: None,
+                fee: None,
+            });
+        }
+
+        let payload = self.call_payload(data, value, &dry_run);
+        let fee = self
+            .api
+            .tx()
+            .create_signed(&payload, &*signer.0, Default::default())
+            .await
+            .map_err(|e| ClientError::substrate(name, e))?
+            .partial_fee_estimate()
+            .await
+            .map_err(|e| ClientError::substrate(name, e))?;
+        let logs = String::from_utf8_lossy(&dry_run.debug_message)
+            .lines()
+            .map(str::to_owned)
+            .collect();
+        Ok(Simulation {
+            operation: name,
+            error: None,
+            logs,
+            units: Some(dry_run.gas_required.ref_time),
+            fee: Some(BigNumber::from(fee)),
+        })
+    }
+
+    async fn get_escrow_state(&self, metadata: &EscrowMetadata) -> Result<EscrowStatus> {
+        let id = Self::escrow_id(metadata)?;
+        let current_height = self.height(GET_ESCROW).await?;
+        let escrow: Option<Escrow> = self
+            .query(&message(selectors::ESCROW, id), GET_ESCROW)
+            .await?;
+        let escrow =
+            escrow.ok_or_else(|| ClientError::substrate(GET_ESCROW, format!("no escrow {id}")))?;
+        Ok(self.status(&escrow, current_height))
+    }
+
+    async fn current_height(&self) -> Result<u64> {
+        self.height(GET_ESCROW).await
+    }
+
+    /// Pages through the escrows of each configured party. The contract
+    /// keeps settled escrows, so they are included.
+    async fn list_escrows(&self) -> Result<Vec<EscrowSummary>> {
+        let current_height = self.height(LIST_ESCROWS).await?;
+        let mut escrows = Vec::new();
+        for party in self.parties() {
+            let mut offset = 0u32;
+            loop {
+                let data = message(selectors::ESCROWS_BY_PARTY, (party.0, offset, MAX_LIMIT));
+                let page: Vec<Escrow> = self.query(&data, LIST_ESCROWS).await?;
+                escrows.extend(page.iter().map(|escrow| EscrowSummary {
+                    id: escrow.id.to_string(),
+                    uid: Some(EscrowId(escrow.uid)),
+                    sender: address(&bytes(&escrow.sender), self.prefix),
+                    recipient: address(&bytes(&escrow.recipient), self.prefix),
+                    status: self.status(escrow, current_height),
+                }));
+                if page.len() < MAX_LIMIT as usize {
+                    break;
+                }
+                offset += MAX_LIMIT;
+            }
+        }
+
+        // An escrow between two configured parties matches more than once.
+        escrows.sort_by_key(|escrow| escrow.id.parse::<u64>().unwrap_or_default());
+        escrows.dedup_by(|a, b| a.id == b.id);
+        info!(count = escrows.len(), "Found escrows");
+        Ok(escrows)
+    }
+
+    /// Subscribes to finalized blocks and decodes the `EscrowUpdated`
+    /// events the contract emits in them.
+    async fn watch_escrows(&self, metadata: Option<&EscrowMetadata>) -> Result<UpdateStream> {
+        let escrow = metadata.map(Self::escrow_id).transpose()?;
+        let parties = self.parties();
+        let contract = self.contract.clone();
+        let prefix = self.prefix;
+        let mut blocks = self
+            .api
+            .blocks()
+            .subscribe_finalized()
+            .await
+            .map_err(|e| ClientError::substrate(WATCH, e))?;
+        info!(contract = %address(&contract.0, prefix), "Watching escrow contract events");
+
+        let (updates, stream) = mpsc::unbounded();
+        tokio::spawn(async move {
+            while let Some(block) = blocks.next().await {
+                let extrinsics = match block {
+                    Ok(block) => block.extrinsics().await,
+                    Err(e) => Err(e),
+                };
+                let extrinsics = match extrinsics {
+                    Ok(extrinsics) => extrinsics,
+                    Err(e) => {
+                        warn!(error = %e, "Failed to fetch finalized block");
+                        if updates
+                            .unbounded_send(Err(ClientError::substrate(WATCH, e)))
+                            .is_err()
+                        {
+                            return;
+                        }
+                        continue;
+                    }
+                };
+                for extrinsic in extrinsics.iter() {
+                    let Ok(events) = extrinsic.events().await else {
+                        continue;
+                    };
+                    let tx = format!("{:?}", events.extrinsic_hash());
+                    for event in contract_events(&events, &contract) {
+                        let wanted = match escrow {
+                            Some(escrow) => event.id == escrow,
+                            None => parties.iter().any(|party| {
+                                party.0 == bytes(&event.sender)
+                                    || party.0 == bytes(&event.recipient)
+                            }),
+                        };
+                        if wanted
+                            && updates
+                                .unbounded_send(Ok(update(&event, prefix, &tx)))
+                                .is_err()
+                        {
+                            return;
+                        }
+                    }
+                }
+            }
+        });
+        Ok(stream.boxed())
+    }
+
+    async fn wait_for_transaction(&self, pending: &PendingTransaction) -> Result<()> {
+        if !matches!(pending.chain, Chain::Substrate) {
+            return Err(ClientError::ConfigMismatch {
+                expected: Chain::Substrate.as_ref().to_owned(),
+                actual: pending.chain.as_ref().to_owned(),
+            });
+        }
+        let operation = [CREATE_ESCROW, FINISH_ESCROW, CANCEL_ESCROW]
+            .into_iter()
+            .find(|operation| *operation == pending.operation)
+            .unwrap_or(WAIT_FOR_TRANSACTION);
+        let parse = |hash: &str| {
+            H256::from_str(hash).map_err(|e| {
+                ClientError::substrate(operation, format!("invalid block or extrinsic hash: {e}"))
+            })
+        };
+        let birth = pending.blockhash.as_deref().ok_or_else(|| {
+            ClientError::substrate(operation, "pending extrinsic has no era block hash")
+        })?;
+
+        self.await_extrinsic(parse(&pending.id)?, parse(birth)?, operation)
+            .await?;
+        info!(tx_hash = %pending.id, "{} extrinsic finalized", operation);
+        Ok(())
+    }
+}
+
+/// Connects to the node at `url`, which may be a `ws://` URL of a local
+/// node.
+async fn connect(
+    url: String,
+) -> std::result::Result<
+    (
+        OnlineClient<PolkadotConfig>,
+        LegacyRpcMethods<PolkadotConfig>,
+    ),
+    subxt::Error,
+> {
+    let client = RpcClient::from_insecure_url(&url).await?;
+    let api = OnlineClient::from_rpc_client(client.clone()).await?;
+    Ok((api, LegacyRpcMethods::new(client)))
+}
+
+/// Encodes a call of the contract message with `selector` and `args`.
+fn message(selector: [u8; 4], args: impl Encode) -> Vec<u8> {
+    let mut data = selector.to_vec();
+    args.encode_to(&mut data);
+    data
+}
+
+/// Returns the blocks an extrinsic signed with `mortality` stays valid
+/// for: a power of two from 4 to 65536.
+fn era_period(mortality: u64) -> u64 {
+    mortality
+        .checked_next_power_of_two()
+        .unwrap_or(MAX_ERA_PERIOD)
+        .clamp(4, MAX_ERA_PERIOD)
+}
+
+fn account_id(bytes: &[u8], operation: &'static str) -> Result<AccountId32> {
+    <[u8; 32]>::try_from(bytes)
+        .map(AccountId32)
+        .map_err(|_| ClientError::substrate(operation, "accounts are 32 bytes"))
+}
+
+/// Returns the account of an escrow party.
+fn party_account(party: &Party) -> Result<AccountId32> {
+    let bytes = ID::from_str(&party.to_string())?.to_bytes()?;
+    <[u8; 32]>::try_from(bytes).map(AccountId32).map_err(|_| {
+        ClientError::InvalidChainOperation(format!("{party} is not a Substrate account"))
+    })
+}
+
+/// Returns the bytes of an account of the contract.
+fn bytes(account: &impl AsRef<[u8; 32]>) -> [u8; 32] {
+    *account.as_ref()
+}
+
+fn address(account: &[u8], prefix: u16) -> String {
+    ID::ss58(account, prefix).to_string()
+}
+
+/// Builds the error for an extrinsic still pending when its wait timed
+/// out, with the block its era started at.
+fn pending(operation: &str, hash: H256, birth: H256) -> ClientError {
+    ClientError::Pending(PendingTransaction {
+        chain: Chain::Substrate,
+        operation: operation.to_owned(),
+        id: format!("{hash:?}"),
+        blockhash: Some(format!("{birth:?}")),
+    })
+}
+
+/// Fails if the extrinsic whose events are `events` failed to dispatch.
+fn check(
+    events: ExtrinsicEvents<PolkadotConfig>,
+    operation: &'static str,
+) -> Result<ExtrinsicEvents<PolkadotConfig>> {
+    let failed = events
+        .iter()
+        .flatten()
+        .any(|event| event.pallet_name() == "System" && event.variant_name() == "ExtrinsicFailed");
+    match failed {
+        false => Ok(events),
+        true => Err(ClientError::substrate(
+            operation,
+            format!("extrinsic {:?} failed", events.extrinsic_hash()),
+        )),
+    }
+}
+
+/// Decodes the `EscrowUpdated` events `contract` emitted in `events`.
+fn contract_events(
+    events: &ExtrinsicEvents<PolkadotConfig>,
+    contract: &AccountId32,
+) -> Vec<EscrowUpdated> {
+    events
+        .iter()
+        .flatten()
+        .filter(|event| {
+            event.pallet_name() == "Contracts" && event.variant_name() == "ContractEmitted"
+        })
+        .filter_map(|event| <(AccountId32, Vec<u8>)>::decode(&mut event.field_bytes()).ok())
+        .filter(|(emitter, _)| emitter == contract)
+        .filter_map(|(_, data)| EscrowUpdated::decode(&mut data.as_slice()).ok())
+        .collect()
+}
+
+fn update(event: &EscrowUpdated, prefix: u16, tx: &str) -> EscrowUpdate {
+    EscrowUpdate {
+        escrow: event.id.to_string(),
+        kind: match event.action {
+            Action::Created => UpdateKind::Created,
+            Action::Finished => UpdateKind::Finished,
+            Action::Cancelled => UpdateKind::Cancelled,
+        },
+        sender: Some(address(&bytes(&event.sender), prefix)),
+        recipient: Some(address(&bytes(&event.recipient), prefix)),
+        amount: BigNumber::from(event.amount),
+        tx: Some(tx.to_owned()),
+    }
+}
+
+/// Return value of a contract message that can fail.
+type ContractResult<T> = std::result::Result<T, ContractError>;
+
+/// Weight of a contract call: execution time and proof size.
+#[derive(Debug, Clone, Copy, Encode, Decode)]
+struct Weight {
+    #[codec(compact)]
+    ref_time: u64,
+    #[codec(compact)]
+    proof_size: u64,
+}
+
+/// Storage deposit a call charges or refunds.
+#[derive(Debug, Clone, Copy, Decode)]
+enum StorageDeposit {
+    Refund(u128),
+    Charge(u128),
+}
+
+/// Data a contract call returned.
+#[derive(Debug, Decode)]
+struct ExecReturnValue {
+    flags: u32,
+    data: Vec<u8>,
+}
+
+/// Why a dry run failed to dispatch, down to the pallet error.
+#[derive(Debug)]
+enum DispatchError {
+    /// Error of the pallet with `index`, whose first error byte is `error`.
+    Module { index: u8, error: u8 },
+    /// Any other dispatch error, by variant index.
+    Other(u8),
+}
+
+/// Result of dry-running a contract call through `ContractsApi_call`.
+struct DryRun {
+    gas_required: Weight,
+    storage_deposit: StorageDeposit,
+    debug_message: Vec<u8>,
+    outcome: std::result::Result<ExecReturnValue, DispatchError>,
+}
+
+impl Decode for DryRun {
+    /// Decodes the fields up to the call's outcome; the events recorded
+    /// after it are not needed.
+    fn decode<I: Input>(input: &mut I) -> std::result::Result<Self, codec::Error> {
+        let _gas_consumed = Weight::decode(input)?;
+        let gas_required = Weight::decode(input)?;
+        let storage_deposit = StorageDeposit::decode(input)?;
+        let debug_message = Vec::decode(input)?;
+        let outcome = match input.read_byte()? {
+            0 => Ok(ExecReturnValue::decode(input)?),
+            _ => Err(match input.read_byte()? {
+                3 => {
+                    let index = input.read_byte()?;
+                    let error = <[u8; 4]>::decode(input)?;
+                    DispatchError::Module {
+                        index,
+                        error: error[0],
+                    }
+                }
+                variant => DispatchError::Other(variant),
+            }),
+        };
+        Ok(Self {
+            gas_required,
+            storage_deposit,
+            debug_message,
+            outcome,
+        })
+    }
+}
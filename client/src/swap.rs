@@ -0,0 +1,412 @@
+//! Atomic swaps across two escrows.
+//!
+//! A [`Swap`] exchanges assets between two parties, typically on two
+//! chains, through a pair of mirrored escrows locked by the same hashlock:
+//!
+//! 1. The initiator, who alone knows the preimage, locks their side in the
+//!    initiator leg, payable to the counterparty.
+//! 2. The counterparty locks their side in the counterparty leg, payable to
+//!    the initiator.
+//! 3. The initiator claims the counterparty leg with the preimage, which
+//!    the swap then reveals (see [`Swap::revealed`]).
+//! 4. The counterparty claims the initiator leg with the same preimage.
+//!
+//! Escrows are finished with proofs that the hashlock is met, which keep
+//! the preimage off-chain; the swap hands it over instead. If the swap
+//! stalls, each party cancels their own leg after its `cancel_after`.
+//!
+//! The counterparty leg must become cancellable at least the swap's margin
+//! before the initiator leg, so that the counterparty, once the initiator
+//! has claimed, has that long to claim in turn. Timelocks are counted in
+//! each chain's own blocks, so they are compared in wall-clock time,
+//! estimated from the current heights and [`block_secs`]. The margin is
+//! enforced at every step: [`Swap::lock_counterparty`] derives the
+//! counterparty's `cancel_after` if unset and refuses one that cancels too
+//! late, and neither leg is claimed once the margin no longer holds.
+//!
+//! # Example
+//!
+//! ```ignore
+//! let mut swap = Swap::new(hash, initiator_params, counterparty_params, margin)?;
+//! swap.lock_initiator(&initiator_chain).await?;
+//! swap.lock_counterparty(&counterparty_chain).await?;
+//!
+//! // Each claim is made by the leg's recipient, with a proof seal for it.
+//! swap.claim(&counterparty_chain_as_initiator, preimage).await?;
+//! swap.complete(&initiator_chain_as_counterparty).await?;
+//! ```
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use serde::{Deserialize, Serialize};
+use tracing::{debug, info};
+use zescrow_core::{Condition, EscrowMetadata, EscrowParams};
+
+use crate::error::ClientError;
+use crate::status::block_secs;
+use crate::{Result, ZescrowClient};
+
+/// Progress of a [`Swap`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SwapPhase {
+    /// Neither leg is locked yet.
+    Proposed,
+    /// The initiator leg is locked.
+    InitiatorLocked,
+    /// Both legs are locked.
+    Locked,
+    /// The initiator has claimed the counterparty leg, revealing the
+    /// preimage.
+    Claimed,
+    /// The counterparty has claimed the initiator leg.
+    Completed,
+}
+
+/// One of the two escrows of a [`Swap`].
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SwapLeg {
+    /// Terms of the escrow.
+    pub params: EscrowParams,
+    /// Metadata of the escrow, once locked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<EscrowMetadata>,
+    /// Estimated Unix time, in seconds, at which the escrow becomes
+    /// cancellable, once locked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cancel_at: Option<u64>,
+}
+
+impl SwapLeg {
+    fn new(mut params: EscrowParams) -> Self {
+        params.has_conditions = true;
+        Self {
+            params,
+            metadata: None,
+            cancel_at: None,
+        }
+    }
+
+    /// Returns the leg's metadata, or an error naming it if unlocked.
+    fn metadata(&self, leg: &str) -> Result<&EscrowMetadata> {
+        self.metadata
+            .as_ref()
+            .ok_or_else(|| ClientError::Swap(format!("the {leg} leg is not locked")))
+    }
+}
+
+/// Two mirrored escrows exchanged atomically through a shared hashlock.
+///
+/// Swaps are serializable, so progress can be saved between steps and
+/// resumed, e.g. by the counterparty once the preimage is revealed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Swap {
+    /// SHA-256 digest of the preimage both legs are locked by.
+    #[serde(with = "hex::serde")]
+    pub hash: [u8; 32],
+    /// Escrow funded by the initiator, payable to the counterparty.
+    pub initiator: SwapLeg,
+    /// Escrow funded by the counterparty, payable to the initiator.
+    pub counterparty: SwapLeg,
+    /// Minimum time, in seconds, between the counterparty leg and the
+    /// initiator leg becoming cancellable.
+    pub margin_secs: u64,
+    /// Progress of the swap.
+    pub phase: SwapPhase,
+    /// Hashlock with the preimage, once the initiator has claimed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    revealed: Option<Condition>,
+}
+
+impl Swap {
+    /// Proposes a swap of the escrows with `initiator` and `counterparty`
+    /// terms, both locked by `hash`.
+    ///
+    /// The initiator leg must set `cancel_after`. If the counterparty leg
+    /// does not, [`lock_counterparty`](Self::lock_counterparty) derives it
+    /// from `margin`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `margin` is zero or the initiator leg sets no
+    /// `cancel_after`.
+    pub fn new(
+        hash: [u8; 32],
+        initiator: EscrowParams,
+        counterparty: EscrowParams,
+        margin: Duration,
+    ) -> Result<Self> {
+        if margin.is_zero() {
+            return Err(ClientError::Swap(
+                "the safety margin must be positive".into(),
+            ));
+        }
+        if initiator.cancel_after.is_none() {
+            return Err(ClientError::Swap(
+                "the initiator leg needs a `cancel_after` to bound the counterparty's".into(),
+            ));
+        }
+        Ok(Self {
+            hash,
+            initiator: SwapLeg::new(initiator),
+            counterparty: SwapLeg::new(counterparty),
+            margin_secs: margin.as_secs(),
+            phase: SwapPhase::Proposed,
+            revealed: None,
+        })
+    }
+
+    /// Minimum time between the counterparty leg and the initiator leg
+    /// becoming cancellable.
+    pub fn margin(&self) -> Duration {
+        Duration::from_secs(self.margin_secs)
+    }
+
+    /// Hashlock with the preimage, once the initiator has claimed: the
+    /// condition the counterparty proves to claim the initiator leg.
+    pub fn revealed(&self) -> Option<&Condition> {
+        self.revealed.as_ref()
+    }
+
+    /// Locks the initiator leg.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Client on the initiator leg's chain, sending as the
+    ///   initiator
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the leg is already locked, its `cancel_after`
+    /// leaves less than twice the margin, or creating the escrow fails.
+    pub async fn lock_initiator(&mut self, client: &ZescrowClient) -> Result<&EscrowMetadata> {
+        self.expect(SwapPhase::Proposed)?;
+        let height = client.current_height().await?;
+        let cancel_at = cancel_at(&self.initiator.params, height, now())?;
+        // The counterparty needs a margin to claim after the initiator, who
+        // needs one to claim before the counterparty leg can be cancelled.
+        let needed = 2 * self.margin_secs;
+        if cancel_at.saturating_sub(now()) < needed {
+            return Err(ClientError::Swap(format!(
+                "the initiator leg becomes cancellable in under {needed}s, twice the margin"
+            )));
+        }
+
+        let metadata = client.create_escrow(&self.initiator.params).await?;
+        info!(escrow_id = ?metadata.escrow_id, cancel_at, "Locked initiator leg");
+        self.initiator.metadata = Some(metadata);
+        self.initiator.cancel_at = Some(cancel_at);
+        self.phase = SwapPhase::InitiatorLocked;
+        self.initiator.metadata("initiator")
+    }
+
+    /// Locks the counterparty leg, cancellable at least the margin before
+    /// the initiator leg. An unset `cancel_after` is set to the latest
+    /// height that keeps the margin.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Client on the counterparty leg's chain, sending as the
+    ///   counterparty
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the initiator leg is not locked, the
+    /// counterparty leg would cancel too late or leave the initiator less
+    /// than the margin to claim, or creating the escrow fails.
+    pub async fn lock_counterparty(&mut self, client: &ZescrowClient) -> Result<&EscrowMetadata> {
+        self.expect(SwapPhase::InitiatorLocked)?;
+        let initiator_cancel_at = self.initiator.cancel_at.unwrap_or_default();
+        let latest = initiator_cancel_at.saturating_sub(self.margin_secs);
+        let height = client.current_height().await?;
+        let now = now();
+
+        // Keep the terms unchanged unless the leg is locked.
+        let mut params = self.counterparty.params.clone();
+        if params.cancel_after.is_none() {
+            let secs = latest.saturating_sub(now) as f64;
+            let blocks = (secs / block_secs(&params.chain_config)).floor() as u64;
+            params.cancel_after = Some(height + blocks);
+            debug!(
+                cancel_after = height + blocks,
+                "Derived counterparty cancel_after"
+            );
+        }
+        let cancel_at = cancel_at(&params, height, now)?;
+        if cancel_at > latest {
+            return Err(ClientError::Swap(format!(
+                "the counterparty leg becomes cancellable at {cancel_at}, less than the \
+                 {}s margin before the initiator leg at {initiator_cancel_at}",
+                self.margin_secs
+            )));
+        }
+        if cancel_at.saturating_sub(now) < self.margin_secs {
+            return Err(ClientError::Swap(format!(
+                "the counterparty leg leaves the initiator under the {}s margin to claim",
+                self.margin_secs
+            )));
+        }
+
+        let metadata = client.create_escrow(&params).await?;
+        info!(escrow_id = ?metadata.escrow_id, cancel_at, "Locked counterparty leg");
+        self.counterparty.params = params;
+        self.counterparty.metadata = Some(metadata);
+        self.counterparty.cancel_at = Some(cancel_at);
+        self.phase = SwapPhase::Locked;
+        self.counterparty.metadata("counterparty")
+    }
+
+    /// Claims the counterparty leg for the initiator, revealing `preimage`.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Client on the counterparty leg's chain with the
+    ///   initiator as recipient, and the proof seal for the leg where
+    ///   finishing requires one
+    /// * `preimage` - Secret the hashlock was made from
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if both legs are not locked, `preimage` does not
+    /// match the hashlock, the leg is settled or not yet finishable, the
+    /// counterparty would be left under the margin to claim, or finishing
+    /// fails.
+    pub async fn claim(&mut self, client: &ZescrowClient, preimage: Vec<u8>) -> Result<()> {
+        self.expect(SwapPhase::Locked)?;
+        let condition = Condition::hashlock(self.hash, preimage);
+        condition
+            .verify()
+            .map_err(|_| ClientError::Swap("the preimage does not match the hashlock".into()))?;
+        // Claiming any later would leave the counterparty too little time to
+        // claim the initiator leg before the initiator can cancel it.
+        let left = self
+            .initiator
+            .cancel_at
+            .unwrap_or_default()
+            .saturating_sub(now());
+        if left < self.margin_secs {
+            return Err(ClientError::Swap(format!(
+                "the initiator leg becomes cancellable in {left}s, leaving the \
+                 counterparty under the {}s margin to claim",
+                self.margin_secs
+            )));
+        }
+
+        let metadata = self.counterparty.metadata("counterparty")?;
+        check_claimable(client, metadata, "counterparty").await?;
+        client.finish_escrow(metadata).await?;
+        info!(escrow_id = ?metadata.escrow_id, "Claimed counterparty leg");
+        self.revealed = Some(condition);
+        self.phase = SwapPhase::Claimed;
+        Ok(())
+    }
+
+    /// Claims the initiator leg for the counterparty with the revealed
+    /// preimage.
+    ///
+    /// # Arguments
+    ///
+    /// * `client` - Client on the initiator leg's chain with the
+    ///   counterparty as recipient, and the proof seal for the leg (proving
+    ///   [`revealed`](Self::revealed)) where finishing requires one
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the initiator has not claimed, the leg is
+    /// settled or not finishable, or finishing fails.
+    pub async fn complete(&mut self, client: &ZescrowClient) -> Result<()> {
+        self.expect(SwapPhase::Claimed)?;
+        let metadata = self.initiator.metadata("initiator")?;
+        check_claimable(client, metadata, "initiator").await?;
+        client.finish_escrow(metadata).await?;
+        info!(escrow_id = ?metadata.escrow_id, "Claimed initiator leg");
+        self.phase = SwapPhase::Completed;
+        Ok(())
+    }
+
+    /// Drives both finishes of a locked swap: claims the counterparty leg
+    /// with `preimage`, then the initiator leg. For operators holding both
+    /// recipients' keys, with proof seals for both legs.
+    ///
+    /// # Arguments
+    ///
+    /// * `counterparty_chain` - Client claiming the counterparty leg, as in
+    ///   [`claim`](Self::claim)
+    /// * `initiator_chain` - Client claiming the initiator leg, as in
+    ///   [`complete`](Self::complete)
+    /// * `preimage` - Secret the hashlock was made from
+    ///
+    /// # Errors
+    ///
+    /// Returns the first error of either step; the swap keeps the progress
+    /// made, so it can be resumed.
+    pub async fn settle(
+        &mut self,
+        counterparty_chain: &ZescrowClient,
+        initiator_chain: &ZescrowClient,
+        preimage: Vec<u8>,
+    ) -> Result<()> {
+        if self.phase == SwapPhase::Locked {
+            self.claim(counterparty_chain, preimage).await?;
+        }
+        self.complete(initiator_chain).await
+    }
+
+    fn expect(&self, phase: SwapPhase) -> Result<()> {
+        if self.phase != phase {
+            return Err(ClientError::Swap(format!(
+                "expected the swap to be {phase:?}, but it is {:?}",
+                self.phase
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Checks that the `leg` escrow can be finished now and not yet cancelled,
+/// so claiming it does not race a refund.
+async fn check_claimable(
+    client: &ZescrowClient,
+    metadata: &EscrowMetadata,
+    leg: &str,
+) -> Result<()> {
+    let status = client.get_escrow_state(metadata).await?;
+    if status.settled {
+        return Err(ClientError::Swap(format!(
+            "the {leg} leg is already settled"
+        )));
+    }
+    if !status.can_finish() {
+        return Err(ClientError::Swap(format!(
+            "the {leg} leg cannot be finished yet"
+        )));
+    }
+    if status.can_cancel() {
+        return Err(ClientError::Swap(format!(
+            "the {leg} leg can already be cancelled"
+        )));
+    }
+    Ok(())
+}
+
+/// Estimates the Unix time at which an escrow with `params` becomes
+/// cancellable, from the chain's `height` at `now`.
+fn cancel_at(params: &EscrowParams, height: u64, now: u64) -> Result<u64> {
+    let cancel_after = params
+        .cancel_after
+        .ok_or_else(|| ClientError::Swap("both legs need a `cancel_after`".into()))?;
+    if cancel_after <= height {
+        return Err(ClientError::Swap(format!(
+            "`cancel_after` {cancel_after} has passed, the chain is at {height}"
+        )));
+    }
+    let secs = (cancel_after - height) as f64 * block_secs(&params.chain_config);
+    Ok(now + secs as u64)
+}
+
+/// Current Unix time, in seconds.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_secs())
+}
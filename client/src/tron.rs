@@ -0,0 +1,1020 @@
+//! Tron blockchain agent implementation.
+//!
+//! Tron runs the Solidity escrow contract of `agent/ethereum` on the TVM,
+//! so [`TronAgent`] reuses the Ethereum agent's contract bindings, calldata,
+//! and event indexing: reads go through the full node's Ethereum JSON-RPC
+//! endpoint (`/jsonrpc`), while transactions are built with the node's
+//! `wallet/triggersmartcontract`, signed over their ID with the same
+//! secp256k1 keys as on Ethereum, and awaited until their block is
+//! solidified.
+//!
+//! Contract calls burn TRX for the energy they use beyond the caller's
+//! staked energy, up to [`TronConfig::fee_limit`], and for the bandwidth
+//! of their bytes beyond the account's free allowance.
+//! Escrows lock TRX, and timelocks are block numbers, as on Ethereum.
+
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use alloy::primitives::{Address, Bytes, B256, U256};
+use alloy::providers::{DynProvider, Provider, ProviderBuilder};
+use alloy::signers::Signer;
+use alloy::sol_types::{SolCall, SolEvent, SolInterface};
+use futures::{future, StreamExt};
+use serde::Deserialize;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use tokio::time::Instant;
+use tracing::{debug, info};
+use url::Url;
+use zescrow_core::interface::{ChainConfig, TronConfig};
+use zescrow_core::{
+    AssetKind, BigNumber, Chain, EscrowId, EscrowMetadata, EscrowParams, ExecutionState, ID,
+};
+
+use crate::error::ClientError;
+use crate::ethereum::indexer::EscrowIndexer;
+use crate::ethereum::{self, Escrow, EscrowEvent};
+use crate::pending::{PendingTransaction, WaitConfig};
+use crate::rpc::ethereum::FailoverTransport;
+use crate::rpc::tron::TronClient;
+use crate::rpc::RpcPolicy;
+use crate::simulation::{Operation, Simulation};
+use crate::watch::UpdateStream;
+use crate::{Agent, EscrowStatus, EscrowSummary, EthereumSigner, Result};
+
+// Contract method names.
+const CREATE_ESCROW: &str = "createEscrow";
+const FINISH_ESCROW: &str = "finishEscrow";
+const CANCEL_ESCROW: &str = "cancelEscrow";
+const MULTICALL: &str = "multicall";
+const GET_ESCROW: &str = "getEscrow";
+const LIST_ESCROWS: &str = "listEscrows";
+const WATCH: &str = "watch";
+const WAIT_FOR_TRANSACTION: &str = "waitForTransaction";
+
+/// Interval between confirmation polls unless configured: a block.
+const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(3);
+
+/// Blocks after which a block is solidified: confirmed by more than two
+/// thirds of the 27 super representatives.
+const SOLIDIFIED_DEPTH: u64 = 19;
+
+/// Leading byte of Tron addresses in the hex form the HTTP API takes.
+const ADDRESS_PREFIX: &str = "41";
+
+/// Bytes a signed contract call takes besides its calldata: the
+/// transaction's other fields, its signature, and its result, as charged
+/// for bandwidth.
+const TX_OVERHEAD_BYTES: u64 = 300;
+
+/// Returns the base58check address of the EVM account `address`.
+fn tron_address(address: Address) -> String {
+    ID::tron(address.into()).to_string()
+}
+
+/// Returns the EVM account of a base58check Tron address.
+fn evm_address(address: &str, operation: &'static str) -> Result<Address> {
+    ID::Base58(address.to_owned())
+        .tron_account()
+        .map(Address::from)
+        .ok_or_else(|| ClientError::tron(operation, format!("invalid Tron address {address}")))
+}
+
+/// Returns `address` in the hex form the HTTP API takes.
+fn hex_address(address: Address) -> String {
+    format!("{ADDRESS_PREFIX}{}", hex::encode(address))
+}
+
+/// Returns `url` with `path` appended as one segment.
+fn with_segment(url: &Url, path: &str) -> Url {
+    let mut url = url.clone();
+    if let Ok(mut segments) = url.path_segments_mut() {
+        segments.pop_if_empty().push(path);
+    }
+    url
+}
+
+/// Decodes a message the node returns hex-encoded, keeping it as is if it
+/// is not.
+fn node_message(message: &str) -> String {
+    hex::decode(message)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .unwrap_or_else(|| message.to_owned())
+}
+
+/// Describes why a call reverted from its hex-encoded return data, naming
+/// the contract error when it is one of the Escrow contract's custom
+/// errors.
+fn revert_reason(data: Option<&str>, fallback: &str) -> String {
+    data.and_then(|data| hex::decode(data).ok())
+        .and_then(|data| Escrow::EscrowErrors::abi_decode(&data).ok())
+        .map(|decoded| format!("{decoded:?}"))
+        .unwrap_or_else(|| fallback.to_owned())
+}
+
+/// Current Unix time in milliseconds, as transaction expirations are given.
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.as_millis() as u64)
+}
+
+/// Builds the error for an `operation` transaction still pending at its
+/// deadline, recording when it expires.
+fn pending(operation: &str, tx_id: &str, expiration: u64) -> ClientError {
+    ClientError::Pending(PendingTransaction {
+        chain: Chain::Tron,
+        operation: operation.to_owned(),
+        id: tx_id.to_owned(),
+        blockhash: Some(expiration.to_string()),
+    })
+}
+
+/// Result of `wallet/triggersmartcontract` or `triggerconstantcontract`.
+#[derive(Deserialize)]
+struct Triggered {
+    result: TriggerResult,
+    #[serde(default)]
+    transaction: Option<Value>,
+    #[serde(default)]
+    energy_used: u64,
+    #[serde(default)]
+    constant_result: Vec<String>,
+}
+
+#[derive(Deserialize)]
+struct TriggerResult {
+    #[serde(default)]
+    result: bool,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+impl Triggered {
+    /// Why the call failed or would revert, if it does.
+    fn error(&self) -> Option<String> {
+        let message = self.result.message.as_deref().map(node_message);
+        if !self.result.result {
+            return Some(
+                message
+                    .or_else(|| self.result.code.clone())
+                    .unwrap_or_default(),
+            );
+        }
+        let ret = self
+            .transaction
+            .as_ref()
+            .and_then(|tx| tx["ret"][0]["ret"].as_str())
+            .filter(|ret| *ret != "SUCCESS")?;
+        let fallback = message.unwrap_or_else(|| ret.to_owned());
+        Some(revert_reason(
+            self.constant_result.first().map(String::as_str),
+            &fallback,
+        ))
+    }
+}
+
+/// Result of `wallet/broadcasttransaction`.
+#[derive(Deserialize)]
+struct Broadcasted {
+    #[serde(default)]
+    result: bool,
+    #[serde(default)]
+    code: Option<String>,
+    #[serde(default)]
+    message: Option<String>,
+}
+
+/// Outcome of an included transaction, from `gettransactioninfobyid`;
+/// empty until the transaction is found.
+#[derive(Default, Deserialize)]
+struct TxInfo {
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    fee: u64,
+    #[serde(default)]
+    receipt: Receipt,
+    #[serde(default)]
+    log: Vec<TxLog>,
+    #[serde(default, rename = "resMessage")]
+    res_message: Option<String>,
+    #[serde(default, rename = "contractResult")]
+    contract_result: Vec<String>,
+}
+
+#[derive(Default, Deserialize)]
+struct Receipt {
+    #[serde(default)]
+    result: Option<String>,
+    #[serde(default)]
+    energy_usage_total: u64,
+}
+
+/// Contract log of an included transaction, hex-encoded without prefixes.
+#[derive(Deserialize)]
+struct TxLog {
+    address: String,
+    #[serde(default)]
+    topics: Vec<String>,
+    #[serde(default)]
+    data: String,
+}
+
+impl TxLog {
+    /// Decodes the log as an `EscrowCreated` event of `contract`.
+    fn escrow_created(&self, contract: Address) -> Option<Escrow::EscrowCreated> {
+        let address = hex::decode(&self.address).ok()?;
+        if address.as_slice() != contract.as_slice() {
+            return None;
+        }
+        let topics = self
+            .topics
+            .iter()
+            .map(|topic| B256::from_str(topic).ok())
+            .collect::<Option<Vec<_>>>()?;
+        let data = hex::decode(&self.data).ok()?;
+        Escrow::EscrowCreated::decode_raw_log(topics, &data).ok()
+    }
+}
+
+/// A transaction built by the node and signed, with when it expires.
+struct Signed {
+    id: String,
+    expiration: u64,
+    transaction: Value,
+}
+
+/// Energy and bandwidth prices of the network, in sun.
+struct Prices {
+    energy: u64,
+    bandwidth: u64,
+}
+
+/// Tron blockchain agent for escrow operations.
+///
+/// Calls the Solidity escrow contract through a full node's HTTP API,
+/// signing with secp256k1 keys that also control the parties' Ethereum
+/// addresses.
+pub struct TronAgent {
+    /// Full node HTTP API.
+    api: Arc<TronClient>,
+    /// The node's Ethereum JSON-RPC endpoint, for reads and event logs.
+    provider: DynProvider,
+    /// Escrow contract, for reads.
+    escrow: Escrow::EscrowInstance<DynProvider>,
+    /// Sender key.
+    sender: EthereumSigner,
+    /// Recipient key (optional, for finish operations).
+    recipient: Option<EthereumSigner>,
+    /// Fee settings.
+    config: TronConfig,
+    /// Groth16 seal submitted when finishing an escrow with conditions.
+    proof_seal: Option<Bytes>,
+    /// Fingerprint of the condition escrows are created with.
+    condition: Option<B256>,
+    /// Block from which escrow events are scanned when listing escrows.
+    from_block: u64,
+    /// Timeout and polling interval of confirmation waits.
+    wait: WaitConfig,
+}
+
+impl TronAgent {
+    /// Creates a new Tron agent from chain configuration.
+    ///
+    /// # Arguments
+    ///
+    /// * `config` - Chain configuration with the full node's HTTP API URL
+    ///   and the contract's base58check address
+    /// * `sender` - Optional signer overriding `sender_private_id`
+    /// * `recipient` - Optional recipient signer for finish operations
+    /// * `rpc` - Retry settings and metrics for RPC requests, which fail
+    ///   over to `fallback_rpc_urls` when `rpc_url` is unavailable
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a URL, the contract address, or the sender key
+    /// cannot be parsed.
+    pub async fn new(
+        config: &ChainConfig,
+        sender: Option<EthereumSigner>,
+        recipient: Option<EthereumSigner>,
+        rpc: RpcPolicy,
+    ) -> Result<Self> {
+        let tron = config.tron.clone().unwrap_or_default();
+        let primary = Url::parse(&config.rpc_url)?;
+        let fallbacks = config
+            .fallback_rpc_urls
+            .iter()
+            .map(|url| Url::parse(url))
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        let api = TronClient::new(&primary, &fallbacks, tron.api_key.clone(), rpc.clone());
+        let jsonrpc = |url: &Url| with_segment(url, "jsonrpc");
+        let transport = FailoverTransport::new(
+            &jsonrpc(&primary),
+            &fallbacks.iter().map(jsonrpc).collect::<Vec<_>>(),
+            rpc,
+        );
+        let provider = ProviderBuilder::new()
+            .connect_client(transport.into_client())
+            .erased();
+
+        let contract = ID::Base58(config.agent_id.clone())
+            .tron_account()
+            .map(Address::from)
+            .ok_or_else(|| {
+                ClientError::tron("config", "`agent_id` must be a base58check Tron address")
+            })?;
+        let sender = match sender {
+            Some(sender) => sender,
+            None => EthereumSigner::from_id(&config.sender_private_id).await?,
+        };
+        debug!(sender = %tron_address(sender.address()), "Loaded sender signer");
+
+        Ok(Self {
+            api: Arc::new(api),
+            escrow: Escrow::new(contract, provider.clone()),
+            provider,
+            sender,
+            recipient,
+            config: tron,
+            proof_seal: None,
+            condition: None,
+            from_block: 0,
+            wait: WaitConfig::default(),
+        })
+    }
+
+    /// Sets the Groth16 seal submitted when finishing an escrow with
+    /// conditions.
+    pub fn with_proof_seal(mut self, seal: impl Into<Bytes>) -> Self {
+        self.proof_seal = Some(seal.into());
+        self
+    }
+
+    /// Sets the fingerprint of the condition escrows with conditions are
+    /// created with.
+    pub fn with_condition_fingerprint(mut self, fingerprint: [u8; 32]) -> Self {
+        self.condition = Some(fingerprint.into());
+        self
+    }
+
+    /// Scans escrow events from `block` onward when listing escrows, e.g.
+    /// the contract's deployment block.
+    pub fn with_from_block(mut self, block: u64) -> Self {
+        self.from_block = block;
+        self
+    }
+
+    /// Sets the timeout and polling interval of confirmation waits.
+    pub fn with_wait(mut self, wait: WaitConfig) -> Self {
+        self.wait = wait;
+        self
+    }
+
+    fn poll_interval(&self) -> Duration {
+        self.wait.poll_interval.unwrap_or(DEFAULT_POLL_INTERVAL)
+    }
+
+    fn contract(&self) -> Address {
+        *self.escrow.address()
+    }
+
+    /// Returns the recipient key, or an error if not configured.
+    fn recipient(&self, operation: &'static str) -> Result<&EthereumSigner> {
+        self.recipient
+            .as_ref()
+            .ok_or_else(|| ClientError::tron(operation, "recipient key not configured"))
+    }
+
+    /// Returns an indexer of the contract's events, starting at
+    /// `from_block` and only indexing solidified blocks.
+    fn indexer(&self, from_block: u64) -> EscrowIndexer {
+        EscrowIndexer::new(self.provider.clone(), self.contract(), from_block)
+            .with_confirmations(SOLIDIFIED_DEPTH)
+            .with_poll_interval(self.poll_interval())
+    }
+
+    /// Returns the escrow ID of `metadata`.
+    fn escrow_id(metadata: &EscrowMetadata, operation: &'static str) -> Result<u64> {
+        metadata
+            .escrow_id
+            .ok_or_else(|| ClientError::tron(operation, "missing escrow_id"))
+    }
+
+    /// Returns the calldata, caller, and TRX value of `operation`.
+    async fn plan(&self, operation: Operation<'_>) -> Result<(&'static str, Bytes, Address, u64)> {
+        Ok(match operation {
+            Operation::Create(params) => {
+                // The contract escrows the call value only, so TRC-20
+                // tokens such as USDT cannot be escrowed.
+                if !matches!(params.asset.kind, AssetKind::Native) {
+                    return Err(ClientError::InvalidChainOperation(format!(
+                        "{:?} assets are not supported on Tron",
+                        params.asset.kind
+                    )));
+                }
+                let recipient = evm_address(&params.recipient.to_string(), CREATE_ESCROW)?;
+                let fingerprints =
+                    ethereum::create_fingerprints(&self.escrow, params, self.condition).await?;
+                let (calldata, amount) =
+                    ethereum::create_calldata(recipient, params, fingerprints)?;
+                let amount = u64::try_from(amount).map_err(|_| ClientError::AssetOverflow)?;
+                (CREATE_ESCROW, calldata, self.sender.address(), amount)
+            }
+            Operation::Finish(metadata) => {
+                let id = Self::escrow_id(metadata, FINISH_ESCROW)?;
+                let calldata = ethereum::finish_calldata(metadata, id, self.proof_seal.as_ref())?;
+                let caller = self.recipient(FINISH_ESCROW)?.address();
+                (FINISH_ESCROW, calldata, caller, 0)
+            }
+            Operation::Cancel(metadata) => {
+                let calldata = Escrow::cancelEscrowCall {
+                    escrowId: U256::from(Self::escrow_id(metadata, CANCEL_ESCROW)?),
+                }
+                .abi_encode();
+                (CANCEL_ESCROW, calldata.into(), self.sender.address(), 0)
+            }
+        })
+    }
+
+    /// Runs a call from `caller` against the latest block without
+    /// broadcasting it.
+    async fn trigger_constant(
+        &self,
+        caller: Address,
+        calldata: &Bytes,
+        value: u64,
+        operation: &'static str,
+    ) -> Result<Triggered> {
+        let body = json!({
+            "owner_address": hex_address(caller),
+            "contract_address": hex_address(self.contract()),
+            "data": hex::encode(calldata),
+            "call_value": value,
+            "visible": false,
+        });
+        self.api
+            .post(&["wallet", "triggerconstantcontract"], &body)
+            .await
+            .map_err(|e| ClientError::tron(operation, e))
+    }
+
+    /// Returns the network's energy and bandwidth prices.
+    async fn prices(&self, operation: &'static str) -> Result<Prices> {
+        let params: Value = self
+            .api
+            .post(&["wallet", "getchainparameters"], &json!({}))
+            .await
+            .map_err(|e| ClientError::tron(operation, e))?;
+        let param = |key: &str| {
+            params["chainParameter"]
+                .as_array()
+                .into_iter()
+                .flatten()
+                .find(|param| param["key"] == key)
+                .and_then(|param| param["value"].as_u64())
+                .ok_or_else(|| ClientError::tron(operation, format!("no {key} chain parameter")))
+        };
+        Ok(Prices {
+            energy: param("getEnergyFee")?,
+            bandwidth: param("getTransactionFee")?,
+        })
+    }
+
+    /// Upper bound, in sun, of the TRX a call using `energy` with
+    /// `calldata` burns when the caller has no staked resources.
+    fn max_fee(prices: &Prices, energy: u64, calldata: &Bytes) -> u64 {
+        let bytes = calldata.len() as u64 + TX_OVERHEAD_BYTES;
+        energy * prices.energy + bytes * prices.bandwidth
+    }
+
+    /// Dry-runs a call, then has the node build it as a transaction and
+    /// signs it with `signer`.
+    async fn build(
+        &self,
+        signer: &EthereumSigner,
+        calldata: &Bytes,
+        value: u64,
+        operation: &'static str,
+    ) -> Result<Signed> {
+        let caller = signer.address();
+        let simulated = self
+            .trigger_constant(caller, calldata, value, operation)
+            .await?;
+        if let Some(error) = simulated.error() {
+            return Err(ClientError::tron(operation, error));
+        }
+        let prices = self.prices(operation).await?;
+        let energy_fee = simulated.energy_used * prices.energy;
+        if energy_fee > self.config.fee_limit {
+            return Err(ClientError::tron(
+                operation,
+                format!(
+                    "call may burn {energy_fee} sun for energy, above the fee limit of {} sun",
+                    self.config.fee_limit
+                ),
+            ));
+        }
+
+        let body = json!({
+            "owner_address": hex_address(caller),
+            "contract_address": hex_address(self.contract()),
+            "data": hex::encode(calldata),
+            "call_value": value,
+            "fee_limit": self.config.fee_limit,
+            "visible": false,
+        });
+        let built: Triggered = self
+            .api
+            .post(&["wallet", "triggersmartcontract"], &body)
+            .await
+            .map_err(|e| ClientError::tron(operation, e))?;
+        if let Some(error) = built.error() {
+            return Err(ClientError::tron(operation, error));
+        }
+        let mut transaction = built
+            .transaction
+            .ok_or_else(|| ClientError::tron(operation, "node returned no transaction"))?;
+        let id = self.check_built(&transaction, caller, calldata, value, operation)?;
+
+        let signature = signer
+            .sign_hash(&id)
+            .await
+            .map_err(|e| ClientError::tron(operation, e))?;
+        transaction["signature"] = json!([hex::encode(signature.as_bytes())]);
+        let expiration = transaction["raw_data"]["expiration"]
+            .as_u64()
+            .unwrap_or_default();
+        Ok(Signed {
+            id: hex::encode(id),
+            expiration,
+            transaction,
+        })
+    }
+
+    /// Checks that a transaction the node built calls the escrow contract
+    /// as requested and that its ID is the hash of its raw data, returning
+    /// the ID.
+    fn check_built(
+        &self,
+        transaction: &Value,
+        caller: Address,
+        calldata: &Bytes,
+        value: u64,
+        operation: &'static str,
+    ) -> Result<B256> {
+        let call = &transaction["raw_data"]["contract"][0]["parameter"]["value"];
+        let matches = call["owner_address"].as_str() == Some(&hex_address(caller))
+            && call["contract_address"].as_str() == Some(&hex_address(self.contract()))
+            && call["data"].as_str() == Some(&hex::encode(calldata))
+            && call["call_value"].as_u64().unwrap_or_default() == value;
+        let raw = transaction["raw_data_hex"]
+            .as_str()
+            .and_then(|raw| hex::decode(raw).ok());
+        let id = transaction["txID"]
+            .as_str()
+            .and_then(|id| B256::from_str(id).ok());
+        match (raw, id) {
+            (Some(raw), Some(id)) if matches && B256::from_slice(&Sha256::digest(raw)) == id => {
+                Ok(id)
+            }
+            _ => Err(ClientError::tron(
+                operation,
+                "node built a transaction other than the requested call",
+            )),
+        }
+    }
+
+    /// Broadcasts `operation` signed by `signer` and waits until its block
+    /// is solidified.
+    async fn execute(
+        &self,
+        signer: &EthereumSigner,
+        calldata: Bytes,
+        value: u64,
+        operation: &'static str,
+    ) -> Result<TxInfo> {
+        let signed = self.build(signer, &calldata, value, operation).await?;
+        let broadcast: Broadcasted = self
+            .api
+            .post(&["wallet", "broadcasttransaction"], &signed.transaction)
+            .await
+            .map_err(|e| ClientError::tron(operation, e))?;
+        if !broadcast.result {
+            let message = broadcast.message.as_deref().map(node_message);
+            return Err(ClientError::tron(
+                operation,
+                format!(
+                    "broadcast rejected: {}",
+                    message.or(broadcast.code).unwrap_or_default()
+                ),
+            ));
+        }
+        info!(tx_id = %signed.id, "{} transaction broadcast", operation);
+        self.await_tx(&signed.id, signed.expiration, operation)
+            .await
+    }
+
+    /// Polls for transaction `id` until its block is solidified, it expires
+    /// unincluded at `expiration`, or the wait times out.
+    async fn await_tx(&self, id: &str, expiration: u64, operation: &'static str) -> Result<TxInfo> {
+        let deadline = self.wait.timeout.map(|timeout| Instant::now() + timeout);
+        let body = json!({ "value": id });
+        loop {
+            let info: TxInfo = self
+                .api
+                .post(&["walletsolidity", "gettransactioninfobyid"], &body)
+                .await
+                .map_err(|e| ClientError::tron(operation, e))?;
+            if info.id.is_some() {
+                return check(info, operation);
+            }
+            if now_ms() > expiration {
+                // Past its expiration, a transaction is either in a block
+                // awaiting solidification or never will be.
+                let included: TxInfo = self
+                    .api
+                    .post(&["wallet", "gettransactioninfobyid"], &body)
+                    .await
+                    .map_err(|e| ClientError::tron(operation, e))?;
+                if included.id.is_none() {
+                    return Err(ClientError::tx_dropped(format!(
+                        "transaction {id} was not included before it expired"
+                    )));
+                }
+            }
+            if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                return Err(pending(operation, id, expiration));
+            }
+            tokio::time::sleep(self.poll_interval()).await;
+        }
+    }
+
+    /// Reads escrow `id` from the contract.
+    async fn escrow_status(
+        &self,
+        id: u64,
+        current_height: u64,
+        operation: &'static str,
+    ) -> Result<EscrowStatus> {
+        let escrow = self
+            .escrow
+            .getEscrow(U256::from(id))
+            .call()
+            .await
+            .map_err(|e| ClientError::tron(operation, e))?;
+        // Zero means the timelock is unset.
+        let timelock = |block: U256| {
+            (!block.is_zero())
+                .then(|| u64::try_from(block).map_err(|e| ClientError::tron(operation, e)))
+                .transpose()
+        };
+
+        Ok(EscrowStatus {
+            settled: escrow.settled,
+            amount: u128::try_from(escrow.amount)
+                .map(BigNumber::from)
+                .map_err(|_| ClientError::AssetOverflow)?,
+            finish_after: timelock(escrow.finishAfter)?,
+            cancel_after: timelock(escrow.cancelAfter)?,
+            current_height,
+            has_conditions: escrow.hasConditions,
+        })
+    }
+
+    async fn current_height(&self, operation: &'static str) -> Result<u64> {
+        self.provider
+            .get_block_number()
+            .await
+            .map_err(|e| ClientError::tron(operation, e))
+    }
+
+    /// Accounts of the configured sender and recipient.
+    fn parties(&self) -> Vec<Address> {
+        std::iter::once(&self.sender)
+            .chain(self.recipient.as_ref())
+            .map(Signer::address)
+            .collect()
+    }
+}
+
+/// Fails if an included transaction did not execute successfully.
+fn check(info: TxInfo, operation: &'static str) -> Result<TxInfo> {
+    match info.receipt.result.as_deref() {
+        Some("SUCCESS") => {
+            debug!(
+                fee = info.fee,
+                energy = info.receipt.energy_usage_total,
+                "{} transaction solidified",
+                operation
+            );
+            Ok(info)
+        }
+        result => {
+            let fallback = info
+                .res_message
+                .as_deref()
+                .map(node_message)
+                .or_else(|| result.map(str::to_owned))
+                .unwrap_or_else(|| "no receipt".into());
+            Err(ClientError::tron(
+                operation,
+                format!(
+                    "transaction {} failed: {}",
+                    info.id.as_deref().unwrap_or_default(),
+                    revert_reason(info.contract_result.first().map(String::as_str), &fallback)
+                ),
+            ))
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl Agent for TronAgent {
+    async fn create_escrow(&self, params: &EscrowParams) -> Result<EscrowMetadata> {
+        let (_, calldata, _, amount) = self.plan(Operation::Create(params)).await?;
+        info!(
+            "Sending {} transaction with amount {} sun",
+            CREATE_ESCROW, amount
+        );
+        let info = self
+            .execute(&self.sender, calldata, amount, CREATE_ESCROW)
+            .await?;
+
+        let sender = self.sender.address();
+        let event = info
+            .log
+            .iter()
+            .filter_map(|log| log.escrow_created(self.contract()))
+            .find(|event| event.sender == sender)
+            .ok_or_else(|| ClientError::MissingEvent("EscrowCreated event not found".into()))?;
+        if event.escrowId.is_zero() {
+            return Err(ClientError::MissingEvent("escrow_id is zero".into()));
+        }
+        let escrow_id =
+            u64::try_from(event.escrowId).map_err(|e| ClientError::tron(CREATE_ESCROW, e))?;
+        let uid = EscrowId(event.uid.0);
+        info!(%uid, "{} confirmed for escrow ID {}", CREATE_ESCROW, escrow_id);
+
+        Ok(EscrowMetadata {
+            params: params.clone(),
+            state: ExecutionState::Funded,
+            escrow_id: Some(escrow_id),
+            uid: Some(uid),
+            chain_metadata: None,
+            guest: None,
+            observed: None,
+        })
+    }
+
+    async fn finish_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
+        let (_, calldata, _, _) = self.plan(Operation::Finish(metadata)).await?;
+        let id = Self::escrow_id(metadata, FINISH_ESCROW)?;
+        info!("Sending {} transaction for escrow ID {}", FINISH_ESCROW, id);
+        self.execute(self.recipient(FINISH_ESCROW)?, calldata, 0, FINISH_ESCROW)
+            .await?;
+        info!("{} confirmed for escrow ID {}", FINISH_ESCROW, id);
+        Ok(())
+    }
+
+    async fn cancel_escrow(&self, metadata: &EscrowMetadata) -> Result<()> {
+        let (_, calldata, _, _) = self.plan(Operation::Cancel(metadata)).await?;
+        let id = Self::escrow_id(metadata, CANCEL_ESCROW)?;
+        info!("Sending {} transaction for escrow ID {}", CANCEL_ESCROW, id);
+        self.execute(&self.sender, calldata, 0, CANCEL_ESCROW)
+            .await?;
+        info!("{} confirmed for escrow ID {}", CANCEL_ESCROW, id);
+        Ok(())
+    }
+
+    /// Dry-runs the call with `triggerconstantcontract`, reporting the
+    /// energy it uses and the most TRX it burns when the caller has no
+    /// staked energy or bandwidth.
+    async fn simulate(&self, operation: Operation<'_>) -> Result<Simulation> {
+        let (name, calldata, caller, value) = self.plan(operation).await?;
+        let simulated = self
+            .trigger_constant(caller, &calldata, value, name)
+            .await?;
+        if let Some(error) = simulated.error() {
+            return Ok(Simulation {
+                operation: name,
+                error: Some(error),
+                logs: Vec::new(),
+                units: None,
+                fee: None,
+            });
+        }
+        let prices = self.prices(name).await?;
+        Ok(Simulation {
+            operation: name,
+            error: None,
+            logs: Vec::new(),
+            units: Some(simulated.energy_used),
+            fee: Some(BigNumber::from(Self::max_fee(
+                &prices,
+                simulated.energy_used,
+                &calldata,
+            ))),
+        })
+    }
+
+    async fn get_escrow_state(&self, metadata: &EscrowMetadata) -> Result<EscrowStatus> {
+        let id = Self::escrow_id(metadata, GET_ESCROW)?;
+        let current_height = self.current_height(GET_ESCROW).await?;
+        self.escrow_status(id, current_height, GET_ESCROW).await
+    }
+
+    async fn current_height(&self) -> Result<u64> {
+        self.current_height(GET_ESCROW).await
+    }
+
+    /// Scans `EscrowCreated` events in solidified blocks from the
+    /// configured start block for escrows in which the sender or recipient
+    /// is a party, then reads each one's live state.
+    async fn list_escrows(&self) -> Result<Vec<EscrowSummary>> {
+        let parties = self.parties();
+        let events = self.indexer(self.from_block).backfill().await?;
+        let current_height = self.current_height(LIST_ESCROWS).await?;
+
+        let mut escrows = Vec::new();
+        for indexed in events {
+            let EscrowEvent::Created {
+                escrow_id,
+                sender,
+                recipient,
+                uid,
+                ..
+            } = indexed.event
+            else {
+                continue;
+            };
+            if !parties.contains(&sender) && !parties.contains(&recipient) {
+                continue;
+            }
+            escrows.push(EscrowSummary {
+                id: escrow_id.to_string(),
+                uid: Some(uid),
+                sender: tron_address(sender),
+                recipient: tron_address(recipient),
+                status: self
+                    .escrow_status(escrow_id, current_height, LIST_ESCROWS)
+                    .await?,
+            });
+        }
+        info!(count = escrows.len(), "Found escrows");
+        Ok(escrows)
+    }
+
+    /// Polls contract events in solidified blocks through the Ethereum
+    /// agent's indexer.
+    ///
+    /// To recognize settlements of escrows created before the watch began,
+    /// watching all escrows first scans `EscrowCreated` events from the
+    /// configured start block.
+    async fn watch_escrows(&self, metadata: Option<&EscrowMetadata>) -> Result<UpdateStream> {
+        let parties = self.parties();
+        let is_party = move |event: &EscrowEvent| match *event {
+            EscrowEvent::Created {
+                sender, recipient, ..
+            } => parties.contains(&sender) || parties.contains(&recipient),
+            _ => false,
+        };
+
+        let (indexer, mut ids) = match metadata {
+            Some(metadata) => {
+                let id = Self::escrow_id(metadata, WATCH)?;
+                let head = self.current_height(WATCH).await?;
+                // Start at the first block not yet solidified.
+                let start = (head + 2).saturating_sub(SOLIDIFIED_DEPTH);
+                (self.indexer(start), HashSet::from([id]))
+            }
+            None => {
+                let mut indexer = self.indexer(self.from_block);
+                let ids = indexer
+                    .backfill()
+                    .await?
+                    .into_iter()
+                    .filter(|indexed| is_party(&indexed.event))
+                    .map(|indexed| indexed.event.escrow_id())
+                    .collect();
+                (indexer, ids)
+            }
+        };
+        let watch_all = metadata.is_none();
+        info!(
+            next_block = indexer.next_block(),
+            escrows = ids.len(),
+            "Watching escrow events"
+        );
+
+        let updates = indexer.follow().filter_map(move |item| {
+            let update = match item {
+                Ok(indexed) => {
+                    let id = indexed.event.escrow_id();
+                    if watch_all && is_party(&indexed.event) {
+                        ids.insert(id);
+                    }
+                    ids.contains(&id).then(|| {
+                        ethereum::to_update(indexed, tron_address).map(|mut update| {
+                            // Tron transaction IDs are bare hex.
+                            update.tx = update.tx.map(|tx| tx.trim_start_matches("0x").to_owned());
+                            update
+                        })
+                    })
+                }
+                Err(e) => Some(Err(e)),
+            };
+            future::ready(update)
+        });
+        Ok(updates.boxed())
+    }
+
+    /// Finishes all escrows in `metadata` in a single `multicall` transaction.
+    ///
+    /// The batch is atomic: if any escrow cannot be finished, none are.
+    async fn finish_escrows(&self, metadata: &[EscrowMetadata]) -> Result<()> {
+        if metadata.len() < 2 {
+            for metadata in metadata {
+                self.finish_escrow(metadata).await?;
+            }
+            return Ok(());
+        }
+        let calls = metadata
+            .iter()
+            .map(|metadata| {
+                let id = Self::escrow_id(metadata, FINISH_ESCROW)?;
+                ethereum::finish_calldata(metadata, id, self.proof_seal.as_ref())
+            })
+            .collect::<Result<Vec<_>>>()?;
+        info!(
+            count = calls.len(),
+            "Sending batched {} transaction", FINISH_ESCROW
+        );
+        let calldata = Escrow::multicallCall { data: calls }.abi_encode();
+        self.execute(self.recipient(MULTICALL)?, calldata.into(), 0, MULTICALL)
+            .await?;
+        info!("Batched {} confirmed", FINISH_ESCROW);
+        Ok(())
+    }
+
+    /// Cancels all escrows in `metadata` in a single `multicall` transaction.
+    ///
+    /// The batch is atomic: if any escrow cannot be cancelled, none are.
+    async fn cancel_escrows(&self, metadata: &[EscrowMetadata]) -> Result<()> {
+        if metadata.len() < 2 {
+            for metadata in metadata {
+                self.cancel_escrow(metadata).await?;
+            }
+            return Ok(());
+        }
+        let calls = metadata
+            .iter()
+            .map(|metadata| {
+                let id = Self::escrow_id(metadata, CANCEL_ESCROW)?;
+                Ok(Escrow::cancelEscrowCall {
+                    escrowId: U256::from(id),
+                }
+                .abi_encode()
+                .into())
+            })
+            .collect::<Result<Vec<Bytes>>>()?;
+        info!(
+            count = calls.len(),
+            "Sending batched {} transaction", CANCEL_ESCROW
+        );
+        let calldata = Escrow::multicallCall { data: calls }.abi_encode();
+        self.execute(&self.sender, calldata.into(), 0, MULTICALL)
+            .await?;
+        info!("Batched {} confirmed", CANCEL_ESCROW);
+        Ok(())
+    }
+
+    async fn wait_for_transaction(&self, pending: &PendingTransaction) -> Result<()> {
+        if !matches!(pending.chain, Chain::Tron) {
+            return Err(ClientError::ConfigMismatch {
+                expected: Chain::Tron.as_ref().to_owned(),
+                actual: pending.chain.as_ref().to_owned(),
+            });
+        }
+        let operation = [CREATE_ESCROW, FINISH_ESCROW, CANCEL_ESCROW, MULTICALL]
+            .into_iter()
+            .find(|operation| *operation == pending.operation)
+            .unwrap_or(WAIT_FOR_TRANSACTION);
+        let expiration = pending
+            .blockhash
+            .as_deref()
+            .and_then(|expiration| expiration.parse().ok())
+            .unwrap_or(u64::MAX);
+        self.await_tx(&pending.id, expiration, operation).await?;
+        info!(tx_id = %pending.id, "{} transaction solidified", operation);
+        Ok(())
+    }
+}
@@ -0,0 +1,250 @@
+//! `build-tx`, `sign-tx`, and `broadcast-tx` commands, routing escrow
+//! transactions through an offline signing ceremony.
+
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use alloy::signers::local::PrivateKeySigner;
+use anyhow::{anyhow, bail};
+use clap::{value_parser, Subcommand};
+use serde::{Deserialize, Serialize};
+use solana_sdk::pubkey::Pubkey;
+use solana_sdk::signer::null_signer::NullSigner;
+use tracing::info;
+use zescrow_client::{
+    sign_transaction, JournalOp, OfflineTransaction, Operation, Sender, SolanaSigner,
+};
+use zescrow_core::interface::{
+    load_escrow_data, save_escrow_data, ProofArtifact, ESCROW_CONDITIONS_PATH, ESCROW_METADATA_PATH,
+};
+use zescrow_core::{Chain, EscrowMetadata, ExecutionState};
+
+use crate::output::{print_json, OutputFormat};
+use crate::{load_escrow, with_condition, ClientOpts};
+
+/// Default path of transaction files.
+pub const TX_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/escrow_tx.json");
+
+/// Operation to build a transaction for.
+#[derive(Subcommand, Debug)]
+pub enum BuildOp {
+    /// Create an escrow from `templates/escrow_params.json`
+    Create,
+
+    /// Finish the escrow in `templates/escrow_metadata.json`, or the store
+    /// with `--store`
+    Finish {
+        /// Local ID of the stored escrow (with `--store`; defaults to the
+        /// most recently created)
+        #[arg(long, value_name = "ID")]
+        escrow: Option<i64>,
+
+        /// Proof data from `prove`, required for escrows with conditions
+        #[arg(long, value_name = "FILE", value_parser = value_parser!(PathBuf))]
+        proof: Option<PathBuf>,
+    },
+
+    /// Cancel the escrow in `templates/escrow_metadata.json`, or the store
+    /// with `--store`
+    Cancel {
+        /// Local ID of the stored escrow (with `--store`; defaults to the
+        /// most recently created)
+        #[arg(long, value_name = "ID")]
+        escrow: Option<i64>,
+    },
+}
+
+/// A transaction file: the transaction, and the escrow operation it
+/// performs, so broadcasting it can record the outcome.
+#[derive(Debug, Serialize, Deserialize)]
+struct TxFile {
+    #[serde(flatten)]
+    tx: OfflineTransaction,
+    escrow: JournalOp,
+    /// Local ID of the escrow in the store, if loaded from it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    store_id: Option<i64>,
+}
+
+/// Builds the unsigned transaction for `op` and writes it to `out`.
+///
+/// No key is needed: the parties' addresses come from the escrow
+/// parameters or metadata.
+pub async fn build(
+    opts: &ClientOpts,
+    op: BuildOp,
+    out: &Path,
+    nonce_account: Option<Pubkey>,
+) -> anyhow::Result<()> {
+    let (escrow, store_id, proof) = match op {
+        BuildOp::Create => (JournalOp::Create(opts.load_params().await?), None, None),
+        BuildOp::Finish { escrow, proof } => {
+            let (id, metadata) = load_escrow(opts, escrow)?;
+            (JournalOp::Finish(metadata), id, proof)
+        }
+        BuildOp::Cancel { escrow } => {
+            let (id, metadata) = load_escrow(opts, escrow)?;
+            (JournalOp::Cancel(metadata), id, None)
+        }
+    };
+
+    let mut builder = opts
+        .builder(escrow.chain_config())
+        .sender(watch_only(&escrow)?);
+    if let Some(account) = nonce_account {
+        builder = builder.durable_nonce(account);
+    }
+    if let JournalOp::Create(params) = &escrow {
+        builder = with_condition(builder, params, Path::new(ESCROW_CONDITIONS_PATH))?;
+    }
+    if let JournalOp::Finish(metadata) = &escrow {
+        if metadata.params.has_conditions {
+            let path = proof.ok_or_else(|| {
+                anyhow!("escrow has conditions; pass --proof with the proof data from `prove`")
+            })?;
+            info!("Loading proof data from {}", path.display());
+            let proof = ProofArtifact::load(&path)?;
+            opts.prover
+                .check_guest(metadata, proof.image_id.as_deref())?;
+            opts.prover.check_provenance(&proof)?;
+            if let Some(seal) = proof.seal_for(metadata)? {
+                builder = builder.proof_seal(seal);
+            }
+        }
+    }
+    let client = builder.build().await?;
+
+    let operation = match &escrow {
+        JournalOp::Create(params) => Operation::Create(params),
+        JournalOp::Finish(metadata) => Operation::Finish(metadata),
+        JournalOp::Cancel(metadata) => Operation::Cancel(metadata),
+    };
+    let tx = client.build_transaction(operation).await?;
+    let file = TxFile {
+        tx,
+        escrow,
+        store_id,
+    };
+    save_escrow_data(out, &file)?;
+    match opts.output {
+        OutputFormat::Text => {
+            println!(
+                "Unsigned {} transaction written to {}",
+                file.tx.operation,
+                out.display()
+            );
+            println!("To be signed by: {}", file.tx.signers.join(", "));
+        }
+        OutputFormat::Json => print_json(&file)?,
+    }
+    Ok(())
+}
+
+/// Signs the transaction in `path` with `key`, writing the result to `out`
+/// (or back to `path`). Needs no network access.
+pub async fn sign(
+    path: &Path,
+    key: &str,
+    out: Option<&Path>,
+    output: OutputFormat,
+) -> anyhow::Result<()> {
+    let mut file: TxFile = load_escrow_data(path)?;
+    file.tx = sign_transaction(&file.tx, key).await?;
+    let out = out.unwrap_or(path);
+    save_escrow_data(out, &file)?;
+    match output {
+        OutputFormat::Text if file.tx.is_signed() => {
+            println!("Fully signed transaction written to {}", out.display());
+        }
+        OutputFormat::Text => {
+            println!("Signed transaction written to {}", out.display());
+            println!("Still to be signed by: {}", file.tx.signers.join(", "));
+        }
+        OutputFormat::Json => print_json(&file)?,
+    }
+    Ok(())
+}
+
+/// Broadcasts the signed transaction in `path`, recording the escrow it
+/// creates or settles like `create`, `finish`, and `cancel` do.
+pub async fn broadcast(opts: &ClientOpts, path: &Path) -> anyhow::Result<()> {
+    let file: TxFile = load_escrow_data(path)?;
+    if !file.tx.is_signed() {
+        bail!(
+            "transaction is missing signatures of {}",
+            file.tx.signers.join(", ")
+        );
+    }
+
+    let client = opts
+        .builder(file.escrow.chain_config())
+        .sender(watch_only(&file.escrow)?)
+        .build()
+        .await?;
+    let broadcast = client.broadcast_transaction(&file.tx).await?;
+    info!(transaction = %broadcast.transaction, "Transaction confirmed");
+
+    let state = match &file.escrow {
+        JournalOp::Create(_) => ExecutionState::Funded,
+        JournalOp::Finish(_) => ExecutionState::Released,
+        JournalOp::Cancel(_) => ExecutionState::Refunded,
+    };
+    let metadata = match file.escrow {
+        JournalOp::Create(params) => {
+            let metadata = EscrowMetadata {
+                params,
+                state,
+                escrow_id: broadcast.escrow_id,
+                uid: None,
+                chain_metadata: broadcast.chain_metadata.clone(),
+                guest: zescrow_client::pinned_guest(),
+                observed: None,
+            };
+            info!("Saving metadata to {}", ESCROW_METADATA_PATH);
+            save_escrow_data(ESCROW_METADATA_PATH, &metadata)?;
+            if let Some(store) = &opts.store {
+                let id = store.insert(&metadata, Some(&broadcast.transaction))?;
+                info!(id, "Recorded escrow in the store");
+            }
+            metadata
+        }
+        JournalOp::Finish(metadata) | JournalOp::Cancel(metadata) => {
+            if let (Some(store), Some(id)) = (&opts.store, file.store_id) {
+                store.update_state(id, state, Some(&broadcast.transaction))?;
+            }
+            EscrowMetadata { state, ..metadata }
+        }
+    };
+    match opts.output {
+        OutputFormat::Text => println!(
+            "{} transaction confirmed: {}",
+            file.tx.operation, broadcast.transaction
+        ),
+        OutputFormat::Json => print_json(&metadata)?,
+    }
+    Ok(())
+}
+
+/// Returns a sender that cannot sign, so that building and broadcasting
+/// transactions signed elsewhere needs no key.
+fn watch_only(escrow: &JournalOp) -> anyhow::Result<Sender> {
+    let sender = match escrow {
+        JournalOp::Create(params) => &params.sender,
+        JournalOp::Finish(metadata) | JournalOp::Cancel(metadata) => &metadata.params.sender,
+    };
+    Ok(match escrow.chain_config().chain {
+        Chain::Solana => {
+            let pubkey = Pubkey::from_str(&sender.to_string())
+                .map_err(|e| anyhow!("invalid Solana sender {sender}: {e}"))?;
+            Sender::Solana(SolanaSigner::new(NullSigner::new(&pubkey)))
+        }
+        // Never used to sign: transactions come signed, and builds only
+        // read the parties' addresses.
+        Chain::Ethereum => Sender::Ethereum(PrivateKeySigner::random().into()),
+        Chain::Cosmos => bail!("offline transactions are not supported on Cosmos"),
+        Chain::Substrate => bail!("offline transactions are not supported on Substrate"),
+        Chain::Cardano => bail!("offline transactions are not supported on Cardano"),
+        Chain::Tron => bail!("offline transactions are not supported on Tron"),
+        Chain::Hedera => bail!("offline transactions are not supported on Hedera"),
+    })
+}
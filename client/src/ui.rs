@@ -0,0 +1,621 @@
+//! `ui` command: a terminal dashboard of active escrows.
+//!
+//! Lists the funded escrows of the store (or `escrow_metadata.json`
+//! without one) with their live on-chain state, countdowns to finish and
+//! cancel eligibility, and proof status. State is polled every
+//! `--interval` and refreshed early when a watched escrow changes;
+//! countdowns tick in between from the chain's block time. Finish and
+//! cancel run in the background, journaled like the `finish` and `cancel`
+//! commands.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use anyhow::anyhow;
+use crossterm::event::{Event, EventStream, KeyCode, KeyEventKind};
+use futures::StreamExt;
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::Line;
+use ratatui::widgets::{Block, Cell, Paragraph, Row, Table, TableState};
+use ratatui::{DefaultTerminal, Frame};
+use tokio::sync::mpsc;
+use tokio::time::Instant;
+use tracing::{info, warn};
+use zescrow_client::status::block_secs;
+use zescrow_client::{
+    journal, ClientError, EscrowStatus, JournalOp, Operation, Recipient, Reconciled, ZescrowClient,
+};
+use zescrow_core::interface::{load_escrow_data, ESCROW_METADATA_PATH};
+use zescrow_core::{EscrowMetadata, ExecutionState};
+
+use crate::ClientOpts;
+
+const LOADING: &str = "Loading escrow state...";
+const HELP: &str = "Press f to finish, c to cancel, r to refresh, q to quit";
+
+/// Settlement triggered from the dashboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Finish,
+    Cancel,
+}
+
+impl Action {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Finish => "finish",
+            Self::Cancel => "cancel",
+        }
+    }
+
+    fn progress(self) -> &'static str {
+        match self {
+            Self::Finish => "finishing...",
+            Self::Cancel => "cancelling...",
+        }
+    }
+}
+
+/// Result of background work, delivered to the event loop.
+enum Message {
+    /// Live state of the escrow in a row was read.
+    Status(usize, Result<EscrowStatus, String>),
+    /// A watched escrow changed on-chain.
+    Changed,
+    /// A settlement finished: journal entry, escrow, action, and outcome.
+    Settled {
+        entry: Option<i64>,
+        row: usize,
+        action: Action,
+        outcome: Outcome,
+    },
+}
+
+/// How a settlement ended.
+enum Outcome {
+    Landed,
+    Simulated(String),
+    /// Failed; `abandon` if it certainly did not land.
+    Failed {
+        error: String,
+        abandon: bool,
+    },
+}
+
+/// One escrow on the dashboard.
+struct Entry {
+    /// Local ID in the store, if loaded from it.
+    id: Option<i64>,
+    metadata: EscrowMetadata,
+    /// Latest state and when it was read.
+    status: Option<(Result<EscrowStatus, String>, Instant)>,
+    proven: bool,
+    /// Settlement in flight.
+    busy: Option<Action>,
+}
+
+struct App {
+    entries: Vec<Entry>,
+    table: TableState,
+    /// Settlement awaiting confirmation.
+    confirm: Option<(usize, Action)>,
+    message: String,
+    /// Clients by chain configuration, shared by refreshes and actions.
+    clients: HashMap<String, Arc<ZescrowClient>>,
+}
+
+/// Runs the dashboard until the user quits.
+///
+/// # Errors
+///
+/// Returns an error if the escrows cannot be loaded or the terminal fails.
+pub async fn run(
+    opts: &ClientOpts,
+    recipient: Option<Recipient>,
+    interval: Duration,
+) -> anyhow::Result<()> {
+    let mut app = App {
+        entries: load_entries(opts)?,
+        table: TableState::default().with_selected(Some(0)),
+        confirm: None,
+        message: LOADING.into(),
+        clients: HashMap::new(),
+    };
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    for entry in &app.entries {
+        let config = &entry.metadata.params.chain_config;
+        let key = serde_json::to_string(config)?;
+        if app.clients.contains_key(&key) {
+            continue;
+        }
+        let mut builder = opts.builder(config);
+        if let Some(recipient) = &recipient {
+            builder = builder.recipient(recipient.clone());
+        }
+        let client = Arc::new(builder.build().await?);
+        watch(&client, tx.clone());
+        app.clients.insert(key, client);
+    }
+
+    let mut terminal = ratatui::init();
+    let result = event_loop(
+        &mut terminal,
+        &mut app,
+        opts,
+        &recipient,
+        interval,
+        tx,
+        &mut rx,
+    )
+    .await;
+    ratatui::restore();
+    result
+}
+
+async fn event_loop(
+    terminal: &mut DefaultTerminal,
+    app: &mut App,
+    opts: &ClientOpts,
+    recipient: &Option<Recipient>,
+    interval: Duration,
+    tx: mpsc::UnboundedSender<Message>,
+    rx: &mut mpsc::UnboundedReceiver<Message>,
+) -> anyhow::Result<()> {
+    let mut events = EventStream::new();
+    let mut refresh = tokio::time::interval(interval);
+    // Countdowns tick between refreshes.
+    let mut tick = tokio::time::interval(Duration::from_secs(1));
+    loop {
+        terminal.draw(|frame| draw(frame, app))?;
+        tokio::select! {
+            _ = refresh.tick() => app.refresh(opts, &tx)?,
+            _ = tick.tick() => {}
+            Some(message) = rx.recv() => {
+                let changed = matches!(message, Message::Changed);
+                app.handle(message, opts)?;
+                if changed {
+                    app.refresh(opts, &tx)?;
+                }
+            }
+            event = events.next() => {
+                let Some(Event::Key(key)) = event.transpose()? else {
+                    continue;
+                };
+                if key.kind != KeyEventKind::Press {
+                    continue;
+                }
+                if let Some((row, action)) = app.confirm.take() {
+                    if matches!(key.code, KeyCode::Char('y') | KeyCode::Char('Y')) {
+                        app.settle(row, action, opts, recipient, &tx)?;
+                    } else {
+                        app.message = format!("Cancelled {}", action.name());
+                    }
+                    continue;
+                }
+                match key.code {
+                    KeyCode::Char('q') | KeyCode::Esc => return Ok(()),
+                    KeyCode::Down | KeyCode::Char('j') => app.table.select_next(),
+                    KeyCode::Up | KeyCode::Char('k') => app.table.select_previous(),
+                    KeyCode::Char('r') => app.refresh(opts, &tx)?,
+                    KeyCode::Char('f') => app.request(Action::Finish, recipient.is_some()),
+                    KeyCode::Char('c') => app.request(Action::Cancel, true),
+                    _ => {}
+                }
+            }
+        }
+    }
+}
+
+impl App {
+    fn selected(&self) -> Option<usize> {
+        self.table
+            .selected()
+            .filter(|&row| row < self.entries.len())
+    }
+
+    /// Reloads proof status from the store and reads every escrow's live
+    /// state in the background.
+    fn refresh(
+        &mut self,
+        opts: &ClientOpts,
+        tx: &mpsc::UnboundedSender<Message>,
+    ) -> anyhow::Result<()> {
+        for (row, entry) in self.entries.iter_mut().enumerate() {
+            if let (Some(store), Some(id)) = (&opts.store, entry.id) {
+                entry.proven = store.latest_proof(id)?.is_some();
+            }
+            let key = serde_json::to_string(&entry.metadata.params.chain_config)?;
+            let Some(client) = self.clients.get(&key).cloned() else {
+                continue;
+            };
+            let metadata = entry.metadata.clone();
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                let status = client
+                    .get_escrow_state(&metadata)
+                    .await
+                    .map_err(|e| e.to_string());
+                let _ = tx.send(Message::Status(row, status));
+            });
+        }
+        Ok(())
+    }
+
+    /// Asks to confirm `action` on the selected escrow, if it is allowed.
+    fn request(&mut self, action: Action, has_key: bool) {
+        let Some(row) = self.selected() else {
+            return;
+        };
+        let entry = &self.entries[row];
+        let status = entry
+            .status
+            .as_ref()
+            .and_then(|(status, _)| status.as_ref().ok());
+        self.message = match (action, status) {
+            _ if entry.busy.is_some() => "A settlement of this escrow is in flight".into(),
+            (Action::Finish, _) if !has_key => {
+                "Finishing needs a recipient key: pass --recipient or select a profile".into()
+            }
+            (Action::Finish, _) if entry.metadata.params.has_conditions && !entry.proven => {
+                "Escrow has conditions and no recorded proof; use `finish` to prove them".into()
+            }
+            (_, None) => "Escrow state not loaded yet".into(),
+            (Action::Finish, Some(status)) if !status.can_finish() => {
+                "Timelocks do not allow finishing yet".into()
+            }
+            (Action::Cancel, Some(status)) if !status.can_cancel() => {
+                "Timelocks do not allow cancelling yet".into()
+            }
+            _ => {
+                self.confirm = Some((row, action));
+                format!(
+                    "{} escrow {}? (y/n)",
+                    capitalize(action.name()),
+                    label(entry)
+                )
+            }
+        };
+    }
+
+    /// Starts `action` on escrow `row` in the background.
+    fn settle(
+        &mut self,
+        row: usize,
+        action: Action,
+        opts: &ClientOpts,
+        recipient: &Option<Recipient>,
+        tx: &mpsc::UnboundedSender<Message>,
+    ) -> anyhow::Result<()> {
+        let entry = &mut self.entries[row];
+        let metadata = entry.metadata.clone();
+        let config = &metadata.params.chain_config;
+        let mut builder = opts.builder(config);
+        if let Some(recipient) = recipient {
+            builder = builder.recipient(recipient.clone());
+        }
+        if let (Action::Finish, Some(store), Some(id)) = (action, &opts.store, entry.id) {
+            if let Some(seal) = store.latest_proof(id)? {
+                builder = builder.proof_seal(seal);
+            }
+        }
+        let op = match action {
+            Action::Finish => JournalOp::Finish(metadata.clone()),
+            Action::Cancel => JournalOp::Cancel(metadata.clone()),
+        };
+        let dry_run = opts.dry_run;
+        let journal_entry = (!dry_run)
+            .then(|| opts.journal.begin(&op, entry.id))
+            .transpose()?;
+        entry.busy = Some(action);
+        self.message = format!("Sending {} of escrow {}...", action.name(), label(entry));
+
+        let tx = tx.clone();
+        tokio::spawn(async move {
+            let outcome = match builder.build().await {
+                Ok(client) => execute(&client, &op, action, dry_run).await,
+                Err(e) => Outcome::Failed {
+                    error: e.to_string(),
+                    abandon: true,
+                },
+            };
+            let _ = tx.send(Message::Settled {
+                entry: journal_entry,
+                row,
+                action,
+                outcome,
+            });
+        });
+        Ok(())
+    }
+
+    fn handle(&mut self, message: Message, opts: &ClientOpts) -> anyhow::Result<()> {
+        match message {
+            Message::Status(row, status) => {
+                if let Some(entry) = self.entries.get_mut(row) {
+                    entry.status = Some((status, Instant::now()));
+                }
+                if self.message == LOADING {
+                    self.message = HELP.into();
+                }
+            }
+            Message::Changed => {
+                info!("Watched escrow changed");
+                self.message = "Escrow changed on-chain; refreshing".into();
+            }
+            Message::Settled {
+                entry: journal_entry,
+                row,
+                action,
+                outcome,
+            } => {
+                let entry = &mut self.entries[row];
+                entry.busy = None;
+                self.message = match outcome {
+                    Outcome::Landed => {
+                        let state = match action {
+                            Action::Finish => ExecutionState::Released,
+                            Action::Cancel => ExecutionState::Refunded,
+                        };
+                        if let (Some(store), Some(id)) = (&opts.store, entry.id) {
+                            store.update_state(id, state, None)?;
+                        }
+                        if let Some(journal_entry) = journal_entry {
+                            opts.journal.complete(journal_entry)?;
+                        }
+                        entry.metadata.state = state;
+                        format!("Escrow {} {:?}", label(entry), state)
+                    }
+                    Outcome::Simulated(simulation) => simulation,
+                    Outcome::Failed { error, abandon } => {
+                        if let (true, Some(journal_entry)) = (abandon, journal_entry) {
+                            opts.journal.abandon(journal_entry)?;
+                        }
+                        warn!(%error, "{} failed", action.name());
+                        format!(
+                            "{} of escrow {} failed: {error}",
+                            capitalize(action.name()),
+                            label(entry)
+                        )
+                    }
+                };
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Sends (or with `dry_run`, simulates) `action`, checking whether a failed
+/// transaction landed anyway.
+async fn execute(client: &ZescrowClient, op: &JournalOp, action: Action, dry_run: bool) -> Outcome {
+    let metadata = match op {
+        JournalOp::Finish(metadata) | JournalOp::Cancel(metadata) => metadata,
+        JournalOp::Create(_) => unreachable!("the dashboard only settles escrows"),
+    };
+    if dry_run {
+        let operation = match action {
+            Action::Finish => Operation::Finish(metadata),
+            Action::Cancel => Operation::Cancel(metadata),
+        };
+        return match client.simulate(operation).await {
+            Ok(simulation) => Outcome::Simulated(simulation.to_string().replace('\n', "; ")),
+            Err(e) => Outcome::Failed {
+                error: e.to_string(),
+                abandon: false,
+            },
+        };
+    }
+    let result = match action {
+        Action::Finish => client.finish_escrow(metadata).await,
+        Action::Cancel => client.cancel_escrow(metadata).await,
+    };
+    let error = match result {
+        Ok(()) => return Outcome::Landed,
+        Err(error) => error,
+    };
+    // Timed-out transactions stay pending in the journal until the next run.
+    if matches!(error, ClientError::Pending(_)) {
+        return Outcome::Failed {
+            error: error.to_string(),
+            abandon: false,
+        };
+    }
+    match journal::reconcile(client, op).await {
+        Ok(Reconciled::NotLanded) => Outcome::Failed {
+            error: error.to_string(),
+            abandon: true,
+        },
+        Ok(_) => Outcome::Landed,
+        Err(_) => Outcome::Failed {
+            error: error.to_string(),
+            abandon: false,
+        },
+    }
+}
+
+/// Forwards updates of the escrows `client` watches as refresh requests.
+fn watch(client: &Arc<ZescrowClient>, tx: mpsc::UnboundedSender<Message>) {
+    let client = client.clone();
+    tokio::spawn(async move {
+        let mut updates = match client.watch_escrows(None).await {
+            Ok(updates) => updates,
+            Err(e) => {
+                warn!(%e, "Not watching escrows; relying on polling");
+                return;
+            }
+        };
+        while let Some(update) = updates.next().await {
+            if update.is_ok() && tx.send(Message::Changed).is_err() {
+                return;
+            }
+        }
+    });
+}
+
+/// Loads the funded escrows of the store, or the escrow in
+/// [`ESCROW_METADATA_PATH`] without one.
+fn load_entries(opts: &ClientOpts) -> anyhow::Result<Vec<Entry>> {
+    let escrows = match &opts.store {
+        Some(store) => store
+            .list(None)?
+            .into_iter()
+            .filter(|escrow| {
+                matches!(
+                    escrow.metadata.state,
+                    ExecutionState::Funded
+                        | ExecutionState::PendingCreate
+                        | ExecutionState::ConditionsMet
+                )
+            })
+            .map(|escrow| {
+                Ok((
+                    Some(escrow.id),
+                    escrow.metadata,
+                    store.latest_proof(escrow.id)?.is_some(),
+                ))
+            })
+            .collect::<anyhow::Result<Vec<_>>>()?,
+        None => {
+            info!("Loading escrow metadata from {}", ESCROW_METADATA_PATH);
+            let metadata: EscrowMetadata = load_escrow_data(ESCROW_METADATA_PATH)?;
+            vec![(None, metadata, false)]
+        }
+    };
+    if escrows.is_empty() {
+        return Err(anyhow!("no active escrows in the store"));
+    }
+    Ok(escrows
+        .into_iter()
+        .map(|(id, metadata, proven)| Entry {
+            id,
+            metadata,
+            status: None,
+            proven,
+            busy: None,
+        })
+        .collect())
+}
+
+fn draw(frame: &mut Frame, app: &mut App) {
+    let [table_area, footer_area] =
+        Layout::vertical([Constraint::Min(3), Constraint::Length(3)]).areas(frame.area());
+
+    let header = Row::new([
+        "ID",
+        "CHAIN",
+        "AMOUNT",
+        "RECIPIENT",
+        "STATE",
+        "FINISH",
+        "CANCEL",
+        "PROOF",
+    ])
+    .bold();
+    let rows = app.entries.iter().map(|entry| {
+        let params = &entry.metadata.params;
+        let (state, finish, cancel) = match &entry.status {
+            None => ("loading".into(), "-".into(), "-".into()),
+            Some((Err(error), _)) => (format!("error: {error}"), "-".into(), "-".into()),
+            Some((Ok(status), read_at)) => {
+                let block_secs = block_secs(&params.chain_config);
+                let elapsed = read_at.elapsed();
+                (
+                    status.state().to_owned(),
+                    countdown(status.finish_after, status, block_secs, elapsed),
+                    countdown(status.cancel_after, status, block_secs, elapsed),
+                )
+            }
+        };
+        let state = match entry.busy {
+            Some(action) => action.progress().to_owned(),
+            None => state,
+        };
+        let proof = match (params.has_conditions, entry.proven) {
+            (false, _) => "-",
+            (true, true) => "proven",
+            (true, false) => "needed",
+        };
+        Row::new([
+            Cell::from(label(entry)),
+            Cell::from(params.chain_config.chain.as_ref().to_owned()),
+            Cell::from(params.asset.to_string()),
+            Cell::from(params.recipient.to_string()),
+            Cell::from(state),
+            Cell::from(finish),
+            Cell::from(cancel),
+            Cell::from(proof),
+        ])
+    });
+    let widths = [
+        Constraint::Length(6),
+        Constraint::Length(8),
+        Constraint::Length(24),
+        Constraint::Min(20),
+        Constraint::Length(12),
+        Constraint::Length(20),
+        Constraint::Length(20),
+        Constraint::Length(7),
+    ];
+    let table = Table::new(rows, widths)
+        .header(header)
+        .block(Block::bordered().title(" Active escrows "))
+        .row_highlight_style(Style::new().reversed());
+    frame.render_stateful_widget(table, table_area, &mut app.table);
+
+    let footer = Paragraph::new(Line::from(app.message.as_str()))
+        .block(Block::bordered().title(" j/k move · f finish · c cancel · r refresh · q quit "))
+        .style(Style::new().fg(if app.confirm.is_some() {
+            Color::Yellow
+        } else {
+            Color::Reset
+        }));
+    frame.render_widget(footer, footer_area);
+}
+
+/// Describes the time left until `height`, estimated from the blocks left
+/// at the last refresh less the time since.
+fn countdown(
+    height: Option<u64>,
+    status: &EscrowStatus,
+    block_secs: f64,
+    elapsed: Duration,
+) -> String {
+    let Some(height) = height else {
+        return "-".into();
+    };
+    if status.current_height >= height {
+        return "now".into();
+    }
+    let blocks = height - status.current_height;
+    let secs = (blocks as f64 * block_secs - elapsed.as_secs_f64()).max(0.0) as u64;
+    format!("{blocks} blk, ~{}", format_secs(secs))
+}
+
+/// Formats `secs` as e.g. `2d 3h`, `4h 5m`, `6m 7s`.
+fn format_secs(secs: u64) -> String {
+    let (d, h, m, s) = (secs / 86_400, secs / 3600 % 24, secs / 60 % 60, secs % 60);
+    match (d, h, m) {
+        (0, 0, 0) => format!("{s}s"),
+        (0, 0, _) => format!("{m}m {s}s"),
+        (0, _, _) => format!("{h}h {m}m"),
+        _ => format!("{d}d {h}h"),
+    }
+}
+
+/// Store ID of the escrow, or its on-chain ID without one.
+fn label(entry: &Entry) -> String {
+    match (entry.id, entry.metadata.escrow_id) {
+        (Some(id), _) => id.to_string(),
+        (None, Some(escrow_id)) => format!("#{escrow_id}"),
+        (None, None) => "-".into(),
+    }
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    chars
+        .next()
+        .map(|first| first.to_uppercase().chain(chars).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,74 @@
+//! Escrow lifecycle updates streamed by
+//! [`ZescrowClient::watch_escrows`](crate::ZescrowClient::watch_escrows).
+
+use std::fmt;
+
+use futures::stream::BoxStream;
+use serde::Serialize;
+use zescrow_core::BigNumber;
+
+use crate::Result;
+
+/// Stream of escrow lifecycle updates; runs until dropped.
+pub type UpdateStream = BoxStream<'static, Result<EscrowUpdate>>;
+
+/// Escrow lifecycle transition.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UpdateKind {
+    /// The escrow was funded.
+    Created,
+    /// The escrow was released to its recipient.
+    Finished,
+    /// The escrow was refunded to its sender.
+    Cancelled,
+}
+
+impl fmt::Display for UpdateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Created => "created",
+            Self::Finished => "finished",
+            Self::Cancelled => "cancelled",
+        })
+    }
+}
+
+/// A lifecycle transition of one escrow, as observed on-chain.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EscrowUpdate {
+    /// Escrow identifier: the escrow PDA on Solana, the contract-assigned
+    /// escrow ID on Ethereum, Cosmos, Substrate, Tron, and Hedera, the UTxO
+    /// reference `<tx hash>#<index>` on Cardano.
+    pub escrow: String,
+    /// What happened to the escrow.
+    pub kind: UpdateKind,
+    /// Depositor, if the event records it.
+    pub sender: Option<String>,
+    /// Beneficiary, if the event records it.
+    pub recipient: Option<String>,
+    /// Amount locked, released, or refunded, in the asset's smallest unit.
+    pub amount: BigNumber,
+    /// Transaction hash or signature that emitted the event.
+    pub tx: Option<String>,
+}
+
+impl fmt::Display for EscrowUpdate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "escrow {} {}: amount {}",
+            self.escrow, self.kind, self.amount
+        )?;
+        if let Some(sender) = &self.sender {
+            write!(f, ", sender {sender}")?;
+        }
+        if let Some(recipient) = &self.recipient {
+            write!(f, ", recipient {recipient}")?;
+        }
+        if let Some(tx) = &self.tx {
+            write!(f, " (tx {tx})")?;
+        }
+        Ok(())
+    }
+}
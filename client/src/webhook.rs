@@ -0,0 +1,298 @@
+//! Webhook notifications of escrow state transitions.
+//!
+//! In `daemon` and `serve` mode, every configured URL receives a `POST` with
+//! a JSON [`Notification`] when an escrow is created, proven, finished,
+//! cancelled, or nears expiry:
+//!
+//! ```json
+//! {"event": "finished", "timestamp": 1760000000, "escrow": "invoice-42", "metadata": {...}}
+//! ```
+//!
+//! The metadata's `chain_config` is reduced to the chain, as in
+//! `{"chain": "ethereum"}`, so keys and RPC endpoints never leave the client.
+//!
+//! With a secret, the body is signed with HMAC-SHA256 and the hex-encoded
+//! digest sent as `X-Zescrow-Signature: sha256=<hex>`, so receivers can
+//! authenticate it; the signed timestamp lets them reject replays. Failed
+//! deliveries are retried with exponential backoff, and never fail the
+//! escrow operation they report.
+
+use std::fmt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use anyhow::Context;
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use serde_json::Value;
+use sha2::Sha256;
+use tracing::{debug, warn};
+use url::Url;
+use zescrow_core::EscrowMetadata;
+
+/// Environment variable holding the webhook signing secret.
+pub const WEBHOOK_SECRET_VAR: &str = "ZESCROW_WEBHOOK_SECRET";
+
+/// Header carrying the payload signature.
+const SIGNATURE_HEADER: &str = "x-zescrow-signature";
+
+/// Header carrying the event name, for routing without parsing the body.
+const EVENT_HEADER: &str = "x-zescrow-event";
+
+/// Timeout of a single delivery attempt.
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Delay before the first retry, doubled after every failed attempt.
+const INITIAL_BACKOFF: Duration = Duration::from_secs(1);
+
+/// Escrow state transition reported to webhooks.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Event {
+    Created,
+    Proven,
+    Finished,
+    Cancelled,
+    /// The escrow is close to its cancellation height.
+    Expiring,
+}
+
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Created => "created",
+            Self::Proven => "proven",
+            Self::Finished => "finished",
+            Self::Cancelled => "cancelled",
+            Self::Expiring => "expiring",
+        })
+    }
+}
+
+/// Body of a webhook request.
+#[derive(Debug, Serialize)]
+struct Notification<'a> {
+    event: Event,
+    /// Unix time of the transition, in seconds.
+    timestamp: u64,
+    /// Name of the escrow's directory, in `daemon` mode.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    escrow: Option<&'a str>,
+    /// Escrow metadata after the transition, [redacted](redact).
+    metadata: Value,
+}
+
+/// Serializes `metadata` with its chain configuration reduced to the chain,
+/// so keys and endpoints are not disclosed.
+pub fn redact(metadata: &EscrowMetadata) -> serde_json::Result<Value> {
+    let mut value = serde_json::to_value(metadata)?;
+    value["params"]["chain_config"] =
+        serde_json::json!({ "chain": metadata.params.chain_config.chain });
+    Ok(value)
+}
+
+/// Configured webhook endpoints.
+#[derive(Debug, Clone)]
+pub struct Webhooks {
+    client: reqwest::Client,
+    urls: Vec<Url>,
+    secret: Option<Vec<u8>>,
+    attempts: u32,
+}
+
+impl Webhooks {
+    /// Notifies `urls`, signing payloads with `secret` (or the secret in
+    /// [`WEBHOOK_SECRET_VAR`]) and making up to `attempts` delivery attempts
+    /// per notification.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be built.
+    pub fn new(urls: Vec<Url>, secret: Option<String>, attempts: u32) -> anyhow::Result<Self> {
+        let secret = secret
+            .or_else(|| std::env::var(WEBHOOK_SECRET_VAR).ok())
+            .filter(|secret| !secret.is_empty())
+            .map(String::into_bytes);
+        if secret.is_none() && !urls.is_empty() {
+            warn!("Webhook payloads are unsigned; set --webhook-secret or {WEBHOOK_SECRET_VAR}");
+        }
+        let client = reqwest::Client::builder()
+            .timeout(REQUEST_TIMEOUT)
+            .build()
+            .context("building webhook HTTP client")?;
+        Ok(Self {
+            client,
+            urls,
+            secret,
+            attempts: attempts.max(1),
+        })
+    }
+
+    /// Notifies every endpoint of `event` on the escrow in `metadata`,
+    /// named `escrow` in `daemon` mode.
+    ///
+    /// Delivery failures are logged, not returned.
+    pub async fn notify(&self, event: Event, escrow: Option<&str>, metadata: &EscrowMetadata) {
+        if self.urls.is_empty() {
+            return;
+        }
+        let body = redact(metadata).and_then(|metadata| {
+            serde_json::to_vec(&Notification {
+                event,
+                timestamp: SystemTime::now()
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, |elapsed| elapsed.as_secs()),
+                escrow,
+                metadata,
+            })
+        });
+        let body = match body {
+            Ok(body) => body,
+            Err(e) => {
+                warn!(%event, error = %e, "Failed to encode webhook payload");
+                return;
+            }
+        };
+        let signature = self.secret.as_deref().map(|secret| sign(secret, &body));
+
+        let deliveries = self
+            .urls
+            .iter()
+            .map(|url| self.deliver(url, event, &body, signature.as_deref()));
+        futures::future::join_all(deliveries).await;
+    }
+
+    /// Like [`notify`](Self::notify), but returns immediately, delivering in
+    /// a background task.
+    pub fn notify_in_background(&self, event: Event, metadata: EscrowMetadata) {
+        if self.urls.is_empty() {
+            return;
+        }
+        let webhooks = self.clone();
+        tokio::spawn(async move { webhooks.notify(event, None, &metadata).await });
+    }
+
+    /// Posts `body` to `url`, retrying failed attempts.
+    async fn deliver(&self, url: &Url, event: Event, body: &[u8], signature: Option<&str>) {
+        let mut backoff = INITIAL_BACKOFF;
+        for attempt in 1..=self.attempts {
+            let mut request = self
+                .client
+                .post(url.clone())
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .header(EVENT_HEADER, event.to_string())
+                .body(body.to_vec());
+            if let Some(signature) = signature {
+                request = request.header(SIGNATURE_HEADER, format!("sha256={signature}"));
+            }
+            match request.send().await.and_then(|r| r.error_for_status()) {
+                Ok(_) => {
+                    debug!(%url, %event, attempt, "Webhook delivered");
+                    return;
+                }
+                Err(e) if attempt < self.attempts => {
+                    debug!(%url, %event, attempt, error = %e, "Webhook delivery failed; retrying");
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                }
+                Err(e) => {
+                    warn!(%url, %event, attempts = self.attempts, error = %e, "Webhook delivery failed")
+                }
+            }
+        }
+    }
+}
+
+/// Returns the hex-encoded HMAC-SHA256 of `body` under `secret`.
+fn sign(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret).expect("HMAC accepts keys of any length");
+    mac.update(body);
+    hex::encode(mac.finalize().into_bytes())
+}
+
+#[cfg(test)]
+mod tests {
+    use axum::body::Bytes;
+    use axum::routing::post;
+    use axum::Router;
+    use tokio::sync::mpsc;
+    use zescrow_core::interface::ChainConfig;
+    use zescrow_core::{Asset, BigNumber, Chain, EscrowParams, ExecutionState, Party};
+
+    use super::*;
+
+    const PRIVATE_KEY: &str = "0x4c0883a69102937d6231471b5dbb6204fe512961708279f1d8e3f6e1c7b2a3d4";
+    const RPC_URL: &str = "https://mainnet.example.com/v3/rpc-api-token";
+
+    fn metadata() -> EscrowMetadata {
+        EscrowMetadata {
+            params: EscrowParams {
+                chain_config: ChainConfig {
+                    chain: Chain::Ethereum,
+                    rpc_url: RPC_URL.into(),
+                    fallback_rpc_urls: vec![format!("{RPC_URL}/fallback")],
+                    sender_private_id: PRIVATE_KEY.into(),
+                    agent_id: "0x0000000000000000000000000000000000000001".into(),
+                    pubsub_url: Some(format!("wss://mainnet.example.com/{PRIVATE_KEY}")),
+                    network: None,
+                    smart_account: None,
+                    cosmos: None,
+                    substrate: None,
+                    cardano: None,
+                    tron: None,
+                    zksync: None,
+                    hedera: None,
+                    custom_chain: None,
+                },
+                asset: Asset::native(BigNumber::from(1_000u64)),
+                sender: Party::new("0xdeadbeef").unwrap(),
+                recipient: Party::new("0xcafebabe").unwrap(),
+                finish_after: None,
+                cancel_after: Some(100),
+                has_conditions: false,
+                memo: None,
+                rent_destination: None,
+                recipient_contact: None,
+            },
+            state: ExecutionState::Funded,
+            escrow_id: Some(1),
+            uid: None,
+            chain_metadata: None,
+            guest: None,
+            observed: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn notify_posts_redacted_metadata() {
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        let app = Router::new().route(
+            "/",
+            post(move |body: Bytes| async move {
+                tx.send(body).unwrap();
+            }),
+        );
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = Url::parse(&format!("http://{}/", listener.local_addr().unwrap())).unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        let webhooks = Webhooks::new(vec![url], Some("secret".into()), 1).unwrap();
+        webhooks
+            .notify(Event::Created, Some("invoice-42"), &metadata())
+            .await;
+
+        let body = rx.recv().await.unwrap();
+        let text = std::str::from_utf8(&body).unwrap();
+        assert!(!text.contains(PRIVATE_KEY));
+        assert!(!text.contains("rpc-api-token"));
+        assert!(!text.contains("sender_private_id"));
+
+        let body: Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(body["event"], "created");
+        assert_eq!(body["escrow"], "invoice-42");
+        assert_eq!(
+            body["metadata"]["params"]["chain_config"],
+            serde_json::json!({ "chain": "ethereum" })
+        );
+        assert_eq!(body["metadata"]["escrow_id"], 1);
+    }
+}
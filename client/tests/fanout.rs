@@ -0,0 +1,170 @@
+//! Exercises offers mirrored across three [`MockAgent`] chains.
+//!
+//! ```sh
+//! cargo test -p zescrow-client --features test-utils --test fanout
+//! ```
+
+#![cfg(feature = "test-utils")]
+
+use zescrow_client::mock::MockAgent;
+use zescrow_client::{ClientError, FanOut, FanOutPhase, ZescrowClient};
+use zescrow_core::interface::ChainConfig;
+use zescrow_core::{Asset, BigNumber, Chain, EscrowParams, ExecutionState, Party};
+
+const CHAINS: [Chain; 3] = [Chain::Ethereum, Chain::Solana, Chain::Cosmos];
+const CANCEL_AFTER: u64 = 100;
+
+fn params(chain: Chain, cancel_after: Option<u64>) -> EscrowParams {
+    EscrowParams {
+        chain_config: ChainConfig {
+            chain,
+            rpc_url: "http://localhost:8545".into(),
+            fallback_rpc_urls: Vec::new(),
+            sender_private_id: "0x01".into(),
+            agent_id: "0x0000000000000000000000000000000000000001".into(),
+            pubsub_url: None,
+            network: None,
+            smart_account: None,
+            cosmos: None,
+            substrate: None,
+            cardano: None,
+            tron: None,
+            zksync: None,
+            hedera: None,
+            custom_chain: None,
+        },
+        asset: Asset::native(BigNumber::from(1_000u64)),
+        sender: Party::new("0xdeadbeef").unwrap(),
+        recipient: Party::new("0xcafebabe").unwrap(),
+        finish_after: None,
+        cancel_after,
+        has_conditions: false,
+        memo: None,
+        rent_destination: None,
+        recipient_contact: None,
+    }
+}
+
+/// A locked offer mirrored on each of [`CHAINS`], with the mock ledgers and
+/// clients behind each leg.
+async fn locked() -> (FanOut, Vec<MockAgent>, Vec<ZescrowClient>) {
+    let legs = CHAINS
+        .iter()
+        .map(|chain| params(*chain, Some(CANCEL_AFTER)))
+        .collect();
+    let mut offer = FanOut::new(b"order-42", legs).unwrap();
+    let mocks: Vec<_> = CHAINS.iter().map(|chain| MockAgent::new(*chain)).collect();
+    let clients: Vec<_> = mocks
+        .iter()
+        .map(|mock| ZescrowClient::from_agent(mock.clone()))
+        .collect();
+    offer
+        .lock(&clients.iter().collect::<Vec<_>>())
+        .await
+        .unwrap();
+    (offer, mocks, clients)
+}
+
+#[tokio::test]
+async fn cancels_other_legs_once_one_is_finished() {
+    let (mut offer, mocks, clients) = locked().await;
+    let clients: Vec<_> = clients.iter().collect();
+    assert_eq!(offer.phase, FanOutPhase::Locked);
+    // Ethereum stores no memos.
+    let memos: Vec<_> = mocks
+        .iter()
+        .map(|mock| mock.escrow(0).unwrap().params.memo)
+        .collect();
+    let uid = Some(offer.uid.to_string());
+    assert_eq!(memos, [None, uid.clone(), uid]);
+
+    offer.finish(1, clients[1]).await.unwrap();
+    assert_eq!(offer.phase, FanOutPhase::Finished);
+    assert!(matches!(
+        offer.finish(2, clients[2]).await,
+        Err(ClientError::FanOut(_))
+    ));
+
+    // The other legs cannot be cancelled yet.
+    assert_eq!(
+        offer.reconcile(&clients).await.unwrap(),
+        FanOutPhase::Finished
+    );
+    for mock in &mocks {
+        mock.set_height(CANCEL_AFTER);
+    }
+    assert_eq!(
+        offer.reconcile(&clients).await.unwrap(),
+        FanOutPhase::Completed
+    );
+
+    let states: Vec<_> = mocks
+        .iter()
+        .map(|mock| mock.escrow(0).unwrap().state)
+        .collect();
+    assert_eq!(
+        states,
+        [
+            ExecutionState::Refunded,
+            ExecutionState::Released,
+            ExecutionState::Refunded
+        ]
+    );
+    for (index, state) in states.iter().enumerate() {
+        assert_eq!(offer.leg(index).unwrap().state, *state);
+    }
+}
+
+#[tokio::test]
+async fn notices_legs_finished_on_chain() {
+    let (mut offer, mocks, clients) = locked().await;
+    let clients: Vec<_> = clients.iter().collect();
+    assert_eq!(
+        offer.reconcile(&clients).await.unwrap(),
+        FanOutPhase::Locked
+    );
+
+    // The recipient redeems the Cosmos leg without the fan-out.
+    clients[2]
+        .finish_escrow(offer.leg(2).unwrap())
+        .await
+        .unwrap();
+    for mock in &mocks {
+        mock.set_height(CANCEL_AFTER);
+    }
+    assert_eq!(
+        offer.reconcile(&clients).await.unwrap(),
+        FanOutPhase::Completed
+    );
+    assert_eq!(offer.finished, Some(2));
+    assert_eq!(
+        mocks[0].balance(&params(Chain::Ethereum, None).sender.to_string()),
+        BigNumber::from(1_000u64)
+    );
+}
+
+#[tokio::test]
+async fn refuses_invalid_offers() {
+    let single = vec![params(Chain::Ethereum, Some(CANCEL_AFTER))];
+    assert!(matches!(
+        FanOut::new(b"order-42", single),
+        Err(ClientError::FanOut(_))
+    ));
+    let unrefundable = vec![
+        params(Chain::Ethereum, Some(CANCEL_AFTER)),
+        params(Chain::Solana, None),
+    ];
+    assert!(matches!(
+        FanOut::new(b"order-42", unrefundable),
+        Err(ClientError::FanOut(_))
+    ));
+
+    let legs = || {
+        CHAINS
+            .map(|chain| params(chain, Some(CANCEL_AFTER)))
+            .to_vec()
+    };
+    let offer = FanOut::new(b"order-42", legs()).unwrap();
+    assert_eq!(offer.uid, FanOut::new(b"order-42", legs()).unwrap().uid);
+    assert_ne!(offer.uid, FanOut::new(b"order-43", legs()).unwrap().uid);
+}
@@ -0,0 +1,74 @@
+//! Checks the L2 presets against public testnets.
+//!
+//! These hit live RPC endpoints and are ignored by default:
+//!
+//! ```sh
+//! cargo test -p zescrow-client --test l2_testnets -- --ignored
+//! ```
+//!
+//! Override an endpoint with `ARBITRUM_SEPOLIA_RPC_URL`,
+//! `OPTIMISM_SEPOLIA_RPC_URL`, or `BASE_SEPOLIA_RPC_URL`.
+
+use alloy::primitives::{address, Address, Bytes};
+use alloy::providers::{DynProvider, Provider, ProviderBuilder};
+use zescrow_client::ethereum::l2;
+use zescrow_core::EvmNetwork;
+
+/// Arbitrary call target; the L1 fee only depends on the calldata size.
+const TARGET: Address = address!("000000000000000000000000000000000000dEaD");
+
+fn provider(env: &str, default: &str) -> DynProvider {
+    let url = std::env::var(env).unwrap_or_else(|_| default.to_owned());
+    ProviderBuilder::new()
+        .connect_http(url.parse().expect("valid RPC URL"))
+        .erased()
+}
+
+async fn check_preset(network: EvmNetwork, provider: DynProvider) {
+    let chain_id = provider.get_chain_id().await.unwrap();
+    assert_eq!(chain_id, network.chain_id());
+
+    let height = l2::timelock_height(&provider, network, "test")
+        .await
+        .unwrap();
+    assert!(height > 0);
+
+    let rollup = network.rollup().expect("L2 preset");
+    let calldata = Bytes::from(vec![0xab; 100]);
+    let fee = l2::l1_data_fee(&provider, rollup, TARGET, calldata, "test")
+        .await
+        .unwrap();
+    assert!(!fee.is_zero());
+}
+
+#[tokio::test]
+#[ignore = "requires network access"]
+async fn arbitrum_sepolia_preset() {
+    let provider = provider(
+        "ARBITRUM_SEPOLIA_RPC_URL",
+        "https://sepolia-rollup.arbitrum.io/rpc",
+    );
+
+    // `block.number` on Arbitrum tracks L1, far behind the L2 block height.
+    let l2_height = provider.get_block_number().await.unwrap();
+    let height = l2::timelock_height(&provider, EvmNetwork::ArbitrumSepolia, "test")
+        .await
+        .unwrap();
+    assert!(height < l2_height);
+
+    check_preset(EvmNetwork::ArbitrumSepolia, provider).await;
+}
+
+#[tokio::test]
+#[ignore = "requires network access"]
+async fn optimism_sepolia_preset() {
+    let provider = provider("OPTIMISM_SEPOLIA_RPC_URL", "https://sepolia.optimism.io");
+    check_preset(EvmNetwork::OptimismSepolia, provider).await;
+}
+
+#[tokio::test]
+#[ignore = "requires network access"]
+async fn base_sepolia_preset() {
+    let provider = provider("BASE_SEPOLIA_RPC_URL", "https://sepolia.base.org");
+    check_preset(EvmNetwork::BaseSepolia, provider).await;
+}
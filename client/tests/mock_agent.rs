@@ -0,0 +1,161 @@
+//! Exercises escrow flows against [`MockAgent`].
+//!
+//! ```sh
+//! cargo test -p zescrow-client --features test-utils --test mock_agent
+//! ```
+
+#![cfg(feature = "test-utils")]
+
+use futures::StreamExt;
+use zescrow_client::mock::{MockAgent, MockOperation};
+use zescrow_client::{Agent, ClientError, Operation, Recipient, UpdateKind, ZescrowClient};
+use zescrow_core::interface::ChainConfig;
+use zescrow_core::{Asset, BigNumber, Chain, EscrowParams, ExecutionState, Party};
+
+fn params(finish_after: Option<u64>, cancel_after: Option<u64>) -> EscrowParams {
+    EscrowParams {
+        chain_config: ChainConfig {
+            chain: Chain::Ethereum,
+            rpc_url: "http://localhost:8545".into(),
+            fallback_rpc_urls: Vec::new(),
+            sender_private_id: "0x01".into(),
+            agent_id: "0x0000000000000000000000000000000000000001".into(),
+            pubsub_url: None,
+            network: None,
+            smart_account: None,
+            cosmos: None,
+            substrate: None,
+            cardano: None,
+            tron: None,
+            zksync: None,
+            hedera: None,
+            custom_chain: None,
+        },
+        asset: Asset::native(BigNumber::from(1_000u64)),
+        sender: Party::new("0xdeadbeef").unwrap(),
+        recipient: Party::new("0xcafebabe").unwrap(),
+        finish_after,
+        cancel_after,
+        has_conditions: false,
+        memo: None,
+        rent_destination: None,
+        recipient_contact: None,
+    }
+}
+
+#[tokio::test]
+async fn finish_after_timelock() {
+    let mock = MockAgent::new(Chain::Ethereum);
+    let client = ZescrowClient::from_agent(mock.clone());
+    let params = params(Some(10), Some(20));
+
+    let metadata = client.create_escrow(&params).await.unwrap();
+    assert_eq!(metadata.escrow_id, Some(0));
+    assert!(client.finish_escrow(&metadata).await.is_err());
+
+    mock.set_height(10);
+    assert!(client
+        .get_escrow_state(&metadata)
+        .await
+        .unwrap()
+        .can_finish());
+    client.finish_escrow(&metadata).await.unwrap();
+
+    assert_eq!(mock.escrow(0).unwrap().state, ExecutionState::Released);
+    assert_eq!(
+        mock.balance(&params.recipient.to_string()),
+        BigNumber::from(1_000u64)
+    );
+    assert!(client.get_escrow_state(&metadata).await.unwrap().settled);
+    assert!(client.cancel_escrow(&metadata).await.is_err());
+}
+
+#[tokio::test]
+async fn cancel_after_expiry() {
+    let mock = MockAgent::new(Chain::Solana);
+    let client = ZescrowClient::from_agent(mock.clone());
+    let params = params(None, Some(5));
+
+    let metadata = client.create_escrow(&params).await.unwrap();
+    let simulation = client.simulate(Operation::Cancel(&metadata)).await.unwrap();
+    assert!(!simulation.succeeded());
+
+    mock.advance(5);
+    client.cancel_escrow(&metadata).await.unwrap();
+    assert_eq!(
+        mock.balance(&params.sender.to_string()),
+        BigNumber::from(1_000u64)
+    );
+    assert_eq!(mock.transactions().len(), 2);
+}
+
+#[tokio::test]
+async fn injected_failure_leaves_ledger_unchanged() {
+    let mock = MockAgent::new(Chain::Ethereum);
+    let client = ZescrowClient::from_agent(mock.clone());
+    let params = params(Some(0), None);
+
+    mock.fail_next(MockOperation::Create, ClientError::tx_dropped("injected"));
+    assert!(matches!(
+        client.create_escrow(&params).await,
+        Err(ClientError::TransactionDropped(_))
+    ));
+    assert!(mock.escrows().is_empty());
+
+    client.create_escrow(&params).await.unwrap();
+    assert_eq!(client.list_escrows().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn memo_rejected_where_not_stored() {
+    let mut params = params(Some(0), None);
+    params.memo = Some("INV-42".into());
+
+    let ethereum = ZescrowClient::from_agent(MockAgent::new(Chain::Ethereum));
+    assert!(matches!(
+        ethereum.create_escrow(&params).await,
+        Err(ClientError::InvalidChainOperation(_))
+    ));
+
+    params.chain_config.chain = Chain::Solana;
+    let solana = ZescrowClient::from_agent(MockAgent::new(Chain::Solana));
+    let metadata = solana.create_escrow(&params).await.unwrap();
+    assert_eq!(metadata.params.memo.as_deref(), Some("INV-42"));
+}
+
+#[tokio::test]
+async fn watch_reports_transitions() {
+    let mock = MockAgent::new(Chain::Ethereum);
+    let client = ZescrowClient::from_agent(mock.clone());
+    let mut updates = client.watch_escrows(None).await.unwrap();
+
+    let metadata = client.create_escrow(&params(Some(0), None)).await.unwrap();
+    client.finish_escrow(&metadata).await.unwrap();
+
+    let created = updates.next().await.unwrap().unwrap();
+    assert_eq!(created.kind, UpdateKind::Created);
+    let finished = updates.next().await.unwrap().unwrap();
+    assert_eq!(finished.kind, UpdateKind::Finished);
+    assert_eq!(finished.escrow, "0");
+}
+
+#[tokio::test]
+async fn registered_agent_for_custom_chain() {
+    let mock = MockAgent::new(Chain::Ethereum);
+    let mut config = params(Some(0), None).chain_config;
+    config.custom_chain = Some("Mockchain".into());
+
+    assert!(ZescrowClient::builder(&config).build().await.is_err());
+
+    let agent = mock.clone();
+    let client = ZescrowClient::builder(&config)
+        .register_agent("mockchain", move |_: ChainConfig, _: Option<Recipient>| {
+            let agent = agent.clone();
+            async move { Ok(Box::new(agent) as Box<dyn Agent>) }
+        })
+        .build()
+        .await
+        .unwrap();
+    client.create_escrow(&params(Some(0), None)).await.unwrap();
+    assert_eq!(mock.escrows().len(), 1);
+}
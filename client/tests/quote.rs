@@ -0,0 +1,136 @@
+//! Exercises lifecycle quotes across two [`MockAgent`] chains.
+//!
+//! ```sh
+//! cargo test -p zescrow-client --features test-utils --test quote
+//! ```
+
+#![cfg(feature = "test-utils")]
+
+use serde_json::json;
+use zescrow_client::mock::MockAgent;
+use zescrow_client::{quote, ClientError, Cost, LifecycleEstimate, PriceFeeds, ZescrowClient};
+use zescrow_core::interface::ChainConfig;
+use zescrow_core::{Asset, BigNumber, Chain, EscrowParams, Party};
+
+fn params(chain: Chain) -> EscrowParams {
+    EscrowParams {
+        chain_config: ChainConfig {
+            chain,
+            rpc_url: "http://localhost:8545".into(),
+            fallback_rpc_urls: Vec::new(),
+            sender_private_id: "0x01".into(),
+            agent_id: "0x0000000000000000000000000000000000000001".into(),
+            pubsub_url: None,
+            network: None,
+            smart_account: None,
+            cosmos: None,
+            substrate: None,
+            cardano: None,
+            tron: None,
+            zksync: None,
+            hedera: None,
+            custom_chain: None,
+        },
+        asset: Asset::native(BigNumber::from(1_000u64)),
+        sender: Party::new("0xdeadbeef").unwrap(),
+        recipient: Party::new("0xcafebabe").unwrap(),
+        finish_after: None,
+        cancel_after: Some(100),
+        has_conditions: true,
+        memo: None,
+        rent_destination: None,
+        recipient_contact: None,
+    }
+}
+
+fn cost(operation: &'static str, fee: u64) -> Cost {
+    Cost {
+        operation,
+        units: Some(1),
+        fee: Some(BigNumber::from(fee)),
+        deposit: None,
+        simulated: false,
+    }
+}
+
+/// A mock chain whose lifecycle costs `create`, `finish`, and `cancel` in
+/// its fee unit.
+fn chain(chain: Chain, create: u64, finish: u64, cancel: u64) -> ZescrowClient {
+    let mock = MockAgent::new(chain);
+    mock.set_estimate(LifecycleEstimate {
+        create: cost("create", create),
+        finish: cost("finish", finish),
+        cancel: cost("cancel", cancel),
+    });
+    ZescrowClient::from_agent(mock)
+}
+
+fn feeds() -> PriceFeeds {
+    serde_json::from_value(json!({
+        "currency": "usd",
+        "tokens": {
+            "ethereum": { "source": "fixed", "price": 3000.0 },
+            "solana": { "source": "fixed", "price": 150.0 },
+            "devnet": { "source": "fixed", "price": 0.0, "decimals": 9 }
+        }
+    }))
+    .unwrap()
+}
+
+fn assert_close(actual: Option<f64>, expected: f64) {
+    let actual = actual.unwrap();
+    assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+}
+
+#[tokio::test]
+async fn recommends_cheapest_chain() {
+    let feeds = feeds();
+    let ethereum = chain(
+        Chain::Ethereum,
+        2 * 10u64.pow(14),
+        3 * 10u64.pow(14),
+        10u64.pow(14),
+    );
+    let solana = chain(Chain::Solana, 10_000, 5_000, 5_000);
+
+    let quotes = vec![
+        quote::quote("ethereum", &ethereum, &params(Chain::Ethereum), &feeds)
+            .await
+            .unwrap(),
+        quote::quote("solana", &solana, &params(Chain::Solana), &feeds)
+            .await
+            .unwrap(),
+    ];
+    // Totals add the costlier settlement: finishing on Ethereum.
+    assert_close(quotes[0].create, 0.6);
+    assert_close(quotes[0].total, 1.5);
+    assert_close(quotes[1].cancel, 0.00075);
+    assert_close(quotes[1].total, 0.00225);
+    assert!(quotes.iter().all(|quote| quote.viable));
+    assert_eq!(quote::cheapest(&quotes).unwrap().label, "solana");
+
+    // Labels take precedence over chain names.
+    let devnet = quote::quote("devnet", &solana, &params(Chain::Solana), &feeds)
+        .await
+        .unwrap();
+    assert_close(devnet.total, 0.0);
+}
+
+#[tokio::test]
+async fn unknown_costs_are_not_viable() {
+    let feeds = feeds();
+    // Without a set estimate, the mock leaves settlement costs unknown.
+    let client = ZescrowClient::from_agent(MockAgent::new(Chain::Ethereum));
+    let unknown = quote::quote("ethereum", &client, &params(Chain::Ethereum), &feeds)
+        .await
+        .unwrap();
+    assert!(unknown.total.is_none());
+    assert!(!unknown.viable);
+    assert!(quote::cheapest(&[unknown]).is_none());
+
+    let cosmos = chain(Chain::Cosmos, 1, 1, 1);
+    assert!(matches!(
+        quote::quote("osmosis", &cosmos, &params(Chain::Cosmos), &feeds).await,
+        Err(ClientError::Quote(_))
+    ));
+}
@@ -0,0 +1,140 @@
+//! Exercises reorg-aware escrow states against a [`MockAgent`] chain.
+//!
+//! ```sh
+//! cargo test -p zescrow-client --features test-utils --test reorg
+//! ```
+
+#![cfg(feature = "test-utils")]
+
+use zescrow_client::mock::MockAgent;
+use zescrow_client::{reorg, Reconciliation, ZescrowClient};
+use zescrow_core::interface::ChainConfig;
+use zescrow_core::{Asset, BigNumber, Chain, EscrowMetadata, EscrowParams, ExecutionState, Party};
+
+/// Blocks below the latest that mock blocks become final at.
+const FINALITY_DEPTH: u64 = 5;
+
+fn params() -> EscrowParams {
+    EscrowParams {
+        chain_config: ChainConfig {
+            chain: Chain::Ethereum,
+            rpc_url: "http://localhost:8545".into(),
+            fallback_rpc_urls: Vec::new(),
+            sender_private_id: "0x01".into(),
+            agent_id: "0x0000000000000000000000000000000000000001".into(),
+            pubsub_url: None,
+            network: None,
+            smart_account: None,
+            cosmos: None,
+            substrate: None,
+            cardano: None,
+            tron: None,
+            zksync: None,
+            hedera: None,
+            custom_chain: None,
+        },
+        asset: Asset::native(BigNumber::from(1_000u64)),
+        sender: Party::new("0xdeadbeef").unwrap(),
+        recipient: Party::new("0xcafebabe").unwrap(),
+        finish_after: None,
+        cancel_after: None,
+        has_conditions: false,
+        memo: None,
+        rent_destination: None,
+        recipient_contact: None,
+    }
+}
+
+/// A mock chain at height 10 whose blocks are final `FINALITY_DEPTH`
+/// blocks deep, and an escrow created and observed on it.
+async fn created() -> (MockAgent, ZescrowClient, EscrowMetadata) {
+    let mock = MockAgent::new(Chain::Ethereum);
+    mock.set_height(10);
+    mock.set_finality_depth(FINALITY_DEPTH);
+    let client = ZescrowClient::from_agent(mock.clone());
+    let mut metadata = client.create_escrow(&params()).await.unwrap();
+    reorg::observe(&client, &mut metadata, ExecutionState::Funded)
+        .await
+        .unwrap();
+    (mock, client, metadata)
+}
+
+#[tokio::test]
+async fn promotes_once_final() {
+    let (mock, client, mut metadata) = created().await;
+    assert_eq!(metadata.state, ExecutionState::PendingCreate);
+    assert_eq!(metadata.observed.unwrap().height, 10);
+
+    mock.advance(FINALITY_DEPTH - 1);
+    let reconciliation = reorg::reconcile(&client, &mut metadata).await.unwrap();
+    assert_eq!(reconciliation, Reconciliation::Pending);
+    assert_eq!(metadata.state, ExecutionState::PendingCreate);
+
+    mock.advance(1);
+    let reconciliation = reorg::reconcile(&client, &mut metadata).await.unwrap();
+    assert_eq!(reconciliation, Reconciliation::Final);
+    assert_eq!(metadata.state, ExecutionState::Funded);
+    assert!(metadata.observed.is_none());
+}
+
+#[tokio::test]
+async fn demotes_orphaned_create() {
+    let (mock, client, mut metadata) = created().await;
+    mock.reorg(1);
+    assert!(mock.escrow(0).is_none());
+
+    let reconciliation = reorg::reconcile(&client, &mut metadata).await.unwrap();
+    assert_eq!(reconciliation, Reconciliation::Demoted);
+    assert_eq!(metadata.state, ExecutionState::Initialized);
+    assert!(metadata.observed.is_none());
+}
+
+#[tokio::test]
+async fn demotes_orphaned_finish() {
+    let (mock, client, mut metadata) = created().await;
+    mock.advance(FINALITY_DEPTH);
+    reorg::reconcile(&client, &mut metadata).await.unwrap();
+
+    client.finish_escrow(&metadata).await.unwrap();
+    reorg::observe(&client, &mut metadata, ExecutionState::Released)
+        .await
+        .unwrap();
+    assert_eq!(metadata.state, ExecutionState::PendingFinish);
+
+    mock.reorg(1);
+    assert_eq!(mock.escrow(0).unwrap().state, ExecutionState::Funded);
+    let reconciliation = reorg::reconcile(&client, &mut metadata).await.unwrap();
+    assert_eq!(reconciliation, Reconciliation::Demoted);
+    assert_eq!(metadata.state, ExecutionState::Funded);
+    assert_eq!(
+        mock.balance(&params().recipient.to_string()),
+        BigNumber::from(0u64)
+    );
+}
+
+#[tokio::test]
+async fn reobserves_when_only_later_blocks_are_replaced() {
+    let mock = MockAgent::new(Chain::Ethereum);
+    mock.set_height(10);
+    mock.set_finality_depth(FINALITY_DEPTH);
+    let client = ZescrowClient::from_agent(mock.clone());
+    let mut metadata = client.create_escrow(&params()).await.unwrap();
+    // Observed two blocks after the one including the creation.
+    mock.advance(2);
+    reorg::observe(&client, &mut metadata, ExecutionState::Funded)
+        .await
+        .unwrap();
+    let observed = metadata.observed.unwrap();
+    assert_eq!(observed.height, 12);
+
+    mock.reorg(1);
+    let reconciliation = reorg::reconcile(&client, &mut metadata).await.unwrap();
+    assert_eq!(reconciliation, Reconciliation::Reobserved);
+    assert_eq!(metadata.state, ExecutionState::PendingCreate);
+    assert_ne!(metadata.observed.unwrap().hash, observed.hash);
+
+    mock.advance(FINALITY_DEPTH);
+    let reconciliation = reorg::reconcile(&client, &mut metadata).await.unwrap();
+    assert_eq!(reconciliation, Reconciliation::Final);
+    assert_eq!(metadata.state, ExecutionState::Funded);
+}
@@ -0,0 +1,135 @@
+//! Exercises atomic swaps between two [`MockAgent`] chains.
+//!
+//! ```sh
+//! cargo test -p zescrow-client --features test-utils --test swap
+//! ```
+
+#![cfg(feature = "test-utils")]
+
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use sha2::{Digest, Sha256};
+use zescrow_client::mock::MockAgent;
+use zescrow_client::{ClientError, Swap, SwapPhase, ZescrowClient};
+use zescrow_core::interface::ChainConfig;
+use zescrow_core::{Asset, BigNumber, Chain, EscrowParams, ExecutionState, Party};
+
+const PREIMAGE: &[u8] = b"swap secret";
+const MARGIN: Duration = Duration::from_secs(3600);
+
+fn params(chain: Chain, cancel_after: Option<u64>) -> EscrowParams {
+    EscrowParams {
+        chain_config: ChainConfig {
+            chain,
+            rpc_url: "http://localhost:8545".into(),
+            fallback_rpc_urls: Vec::new(),
+            sender_private_id: "0x01".into(),
+            agent_id: "0x0000000000000000000000000000000000000001".into(),
+            pubsub_url: None,
+            network: None,
+            smart_account: None,
+            cosmos: None,
+            substrate: None,
+            cardano: None,
+            tron: None,
+            zksync: None,
+            hedera: None,
+            custom_chain: None,
+        },
+        asset: Asset::native(BigNumber::from(1_000u64)),
+        sender: Party::new("0xdeadbeef").unwrap(),
+        recipient: Party::new("0xcafebabe").unwrap(),
+        finish_after: None,
+        cancel_after,
+        has_conditions: false,
+        memo: None,
+        rent_destination: None,
+        recipient_contact: None,
+    }
+}
+
+/// A swap of an Ethereum escrow cancellable after 1000 blocks (12000s)
+/// against a Solana escrow, with the mock ledgers behind each.
+fn swap(counterparty_cancel_after: Option<u64>) -> (Swap, MockAgent, MockAgent) {
+    let swap = Swap::new(
+        Sha256::digest(PREIMAGE).into(),
+        params(Chain::Ethereum, Some(1_000)),
+        params(Chain::Solana, counterparty_cancel_after),
+        MARGIN,
+    )
+    .unwrap();
+    (
+        swap,
+        MockAgent::new(Chain::Ethereum),
+        MockAgent::new(Chain::Solana),
+    )
+}
+
+#[tokio::test]
+async fn settles_both_legs() {
+    let (mut swap, initiator_chain, counterparty_chain) = swap(None);
+    let initiator = ZescrowClient::from_agent(initiator_chain.clone());
+    let counterparty = ZescrowClient::from_agent(counterparty_chain.clone());
+
+    assert!(swap.lock_counterparty(&counterparty).await.is_err());
+    swap.lock_initiator(&initiator).await.unwrap();
+    swap.lock_counterparty(&counterparty).await.unwrap();
+    assert_eq!(swap.phase, SwapPhase::Locked);
+    // 12000s less the margin, in 0.4s slots.
+    let cancel_after = swap.counterparty.params.cancel_after.unwrap();
+    assert!((20_990..=21_000).contains(&cancel_after));
+    assert!(counterparty_chain.escrow(0).unwrap().params.has_conditions);
+
+    assert!(matches!(
+        swap.claim(&counterparty, b"wrong".to_vec()).await,
+        Err(ClientError::Swap(_))
+    ));
+    assert!(swap.revealed().is_none());
+
+    swap.settle(&counterparty, &initiator, PREIMAGE.to_vec())
+        .await
+        .unwrap();
+    assert_eq!(swap.phase, SwapPhase::Completed);
+    assert!(swap.revealed().unwrap().verify().is_ok());
+    for chain in [&initiator_chain, &counterparty_chain] {
+        assert_eq!(chain.escrow(0).unwrap().state, ExecutionState::Released);
+    }
+}
+
+#[tokio::test]
+async fn refuses_counterparty_cancelling_too_late() {
+    let (mut swap, initiator_chain, counterparty_chain) = swap(Some(30_000));
+    let initiator = ZescrowClient::from_agent(initiator_chain);
+    let counterparty = ZescrowClient::from_agent(counterparty_chain.clone());
+
+    swap.lock_initiator(&initiator).await.unwrap();
+    assert!(matches!(
+        swap.lock_counterparty(&counterparty).await,
+        Err(ClientError::Swap(_))
+    ));
+    assert_eq!(swap.phase, SwapPhase::InitiatorLocked);
+    assert!(counterparty_chain.escrows().is_empty());
+}
+
+#[tokio::test]
+async fn refuses_claim_within_margin() {
+    let (mut swap, initiator_chain, counterparty_chain) = swap(None);
+    let initiator = ZescrowClient::from_agent(initiator_chain);
+    let counterparty = ZescrowClient::from_agent(counterparty_chain.clone());
+
+    swap.lock_initiator(&initiator).await.unwrap();
+    swap.lock_counterparty(&counterparty).await.unwrap();
+    // The initiator leg is about to become cancellable.
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap();
+    swap.initiator.cancel_at = Some(now.as_secs() + 60);
+
+    assert!(matches!(
+        swap.claim(&counterparty, PREIMAGE.to_vec()).await,
+        Err(ClientError::Swap(_))
+    ));
+    assert_eq!(swap.phase, SwapPhase::Locked);
+    assert_eq!(
+        counterparty_chain.escrow(0).unwrap().state,
+        ExecutionState::Funded
+    );
+}
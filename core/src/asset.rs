@@ -55,6 +55,8 @@ pub enum AssetKind {
     MultiToken,
     /// Liquidity pool share (proportional ownership).
     LpShare,
+    /// Staking position (e.g., a Solana stake account) whose authorities are escrowed.
+    Stake,
 }
 
 impl Asset {
@@ -123,6 +125,21 @@ impl Asset {
         }
     }
 
+    /// Create a staking position asset.
+    ///
+    /// `amount` is informational (e.g., the stake account's lamports); the
+    /// escrow transfers control of the position identified by `stake_account`.
+    pub fn stake(stake_account: ID, amount: BigNumber) -> Self {
+        Self {
+            kind: AssetKind::Stake,
+            id: Some(stake_account),
+            agent_id: None,
+            amount,
+            decimals: None,
+            total_supply: None,
+        }
+    }
+
     /// Ensure asset parameters are semantically valid.
     ///
     /// - **Native**: `amount` must be > 0.
@@ -130,6 +147,7 @@ impl Asset {
     /// - **MultiToken**: `amount` must be > 0, `contract` must be valid `ID`, `token_id` cannot be empty.
     /// - **Nft**: `contract` must be valid `ID`, `token_id` cannot be empty.
     /// - **PoolShare**: `share` must be > 0, `total_supply` must be > 0, and `share` <= `total_supply`.
+    /// - **Stake**: `amount` must be > 0, stake account `id` must be valid `ID`.
     pub fn validate(&self) -> Result<()> {
         self.validate_non_zero_amount()
             .and_then(|_| self.validate_by_kind())
@@ -149,6 +167,7 @@ impl Asset {
             AssetKind::Token => self.validate_agent_id(),
             AssetKind::Nft | AssetKind::MultiToken => self.validate_agent_and_token_id(),
             AssetKind::LpShare => self.validate_pool_share(),
+            AssetKind::Stake => self.validate_asset_id(),
         }
     }
 
@@ -162,23 +181,19 @@ impl Asset {
 
     /// Validates both agent ID and token ID are present and valid.
     fn validate_agent_and_token_id(&self) -> Result<()> {
-        self.validate_agent_id().and_then(|_| {
-            self.id
-                .as_ref()
-                .ok_or_else(|| AssetError::MissingId.into())
-                .and_then(ID::validate)
-        })
+        self.validate_agent_id()
+            .and_then(|_| self.validate_asset_id())
     }
 
     /// Validates pool share constraints: pool ID, total supply, and share ratio.
     fn validate_pool_share(&self) -> Result<()> {
-        self.validate_pool_id()
+        self.validate_asset_id()
             .and_then(|_| self.validate_total_supply())
             .and_then(|total| self.validate_share_ratio(total))
     }
 
-    /// Validates the pool ID is present and valid.
-    fn validate_pool_id(&self) -> Result<()> {
+    /// Validates the asset ID (token, pool, or stake account) is present and valid.
+    fn validate_asset_id(&self) -> Result<()> {
         self.id
             .as_ref()
             .ok_or_else(|| AssetError::MissingId.into())
@@ -397,6 +412,20 @@ mod tests {
         assert!(too_many.validate().is_err());
     }
 
+    #[test]
+    fn stake() {
+        let stake = Asset::stake(ID::from(vec![1, 2, 3]), to_bignum(5_000_000_000));
+        assert!(stake.validate().is_ok());
+
+        // empty stake account
+        let empty_account = Asset::stake(ID::from(Vec::new()), to_bignum(1));
+        assert!(empty_account.validate().is_err());
+
+        // zero amount
+        let zero_stake = Asset::stake(ID::from(vec![1, 2, 3]), to_bignum(0));
+        assert!(zero_stake.validate().is_err());
+    }
+
     #[test]
     fn bincode_roundtrip_native() {
         let original = Asset::native(to_bignum(1_000_000_000));
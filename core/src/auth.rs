@@ -0,0 +1,114 @@
+//! API-key authentication of the client's escrow API and the prover's
+//! proving service.
+//!
+//! Keys are presented as `Authorization: Bearer <key>` or
+//! `X-API-Key: <key>`, and checked against [`ApiKeys`].
+
+use std::fmt;
+
+use sha2::{Digest, Sha256};
+use subtle::{Choice, ConstantTimeEq};
+
+/// Header carrying an API key, as an alternative to a bearer token.
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// API keys accepted by a service, held as SHA-256 digests.
+#[derive(Clone, Default)]
+pub struct ApiKeys(Vec<[u8; 32]>);
+
+impl ApiKeys {
+    /// Accepts `keys`, or the comma-separated keys in the environment
+    /// variable `var` if `keys` is empty.
+    pub fn from_args_or_env(keys: &[String], var: &str) -> Self {
+        if keys.is_empty() {
+            Self::from_iter(
+                std::env::var(var)
+                    .unwrap_or_default()
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|key| !key.is_empty()),
+            )
+        } else {
+            Self::from_iter(keys)
+        }
+    }
+
+    /// Returns `true` if no key is accepted.
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Returns `true` if `key` is one of the accepted keys.
+    ///
+    /// The digest of `key` is compared with every accepted digest in
+    /// constant time, so the response time reveals neither which key
+    /// matched nor how many leading bytes of a guess were right.
+    pub fn accepts(&self, key: &str) -> bool {
+        let digest = sha256(key);
+        self.0
+            .iter()
+            .fold(Choice::from(0), |accepted, known| {
+                accepted | known.ct_eq(&digest)
+            })
+            .into()
+    }
+}
+
+impl<K: AsRef<str>> FromIterator<K> for ApiKeys {
+    fn from_iter<I: IntoIterator<Item = K>>(keys: I) -> Self {
+        Self(keys.into_iter().map(|key| sha256(key.as_ref())).collect())
+    }
+}
+
+impl fmt::Debug for ApiKeys {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ApiKeys")
+            .field("len", &self.0.len())
+            .finish_non_exhaustive()
+    }
+}
+
+/// Returns the key presented in the values of a request's `Authorization`
+/// and [`API_KEY_HEADER`] headers, preferring a bearer token.
+pub fn presented_key<'a>(
+    authorization: Option<&'a str>,
+    api_key: Option<&'a str>,
+) -> Option<&'a str> {
+    authorization
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .or(api_key)
+}
+
+/// Returns the SHA-256 digest of an API key.
+fn sha256(key: &str) -> [u8; 32] {
+    Sha256::digest(key.as_bytes()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_configured_keys_only() {
+        let keys = ApiKeys::from_iter(["first", "second"]);
+        assert!(keys.accepts("first"));
+        assert!(keys.accepts("second"));
+        assert!(!keys.accepts("third"));
+        assert!(!keys.accepts(""));
+        assert!(!ApiKeys::default().accepts("first"));
+    }
+
+    #[test]
+    fn prefers_bearer_token() {
+        assert_eq!(presented_key(Some("Bearer a"), Some("b")), Some("a"));
+        assert_eq!(presented_key(Some("Basic a"), Some("b")), Some("b"));
+        assert_eq!(presented_key(None, Some("b")), Some("b"));
+        assert_eq!(presented_key(Some("Basic a"), None), None);
+    }
+
+    #[test]
+    fn debug_hides_digests() {
+        let keys = ApiKeys::from_iter(["first"]);
+        assert_eq!(format!("{keys:?}"), "ApiKeys { len: 1, .. }");
+    }
+}
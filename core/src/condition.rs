@@ -7,14 +7,24 @@
 //! - **Ed25519**: EdDSA signature verification
 //! - **Secp256k1**: ECDSA signature verification
 //! - **Threshold**: N-of-M multi-condition logic
+//! - **CrossChain**: State of another chain, attested by a light-client
+//!   committee
+//! - **Bridge**: Message from another chain, signed by Wormhole guardians or
+//!   LayerZero DVNs
 
+use bincode::config::standard;
 use bincode::{Decode, Encode};
 #[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::error::ConditionError;
 use crate::Result;
 
+/// Message from another chain, delivered by Wormhole or LayerZero.
+pub mod bridge;
+/// Claim about another chain, attested by a quorum of a committee.
+pub mod cross_chain;
 /// Ed25519 signature over an arbitrary message.
 pub mod ed25519;
 /// XRPL-style hashlock: SHA-256(preimage) == hash.
@@ -24,6 +34,8 @@ pub mod secp256k1;
 /// Threshold condition: at least `threshold` subconditions must hold.
 pub mod threshold;
 
+use bridge::{Bridge, BridgeMessage, Verifiers};
+use cross_chain::{Attestation, CrossChain, RemoteClaim};
 use ed25519::Ed25519;
 use hashlock::Hashlock;
 use secp256k1::Secp256k1;
@@ -45,6 +57,10 @@ pub enum Condition {
     Secp256k1(Secp256k1),
     /// Threshold condition: at least `threshold` subconditions must hold.
     Threshold(Threshold),
+    /// Claim about another chain, attested by a quorum of a committee.
+    CrossChain(CrossChain),
+    /// Message from another chain, delivered by Wormhole or LayerZero.
+    Bridge(Bridge),
 }
 
 impl Condition {
@@ -57,6 +73,9 @@ impl Condition {
     /// - **Ed25519**: Public key parsing or signature verification fails.
     /// - **Secp256k1**: Public key parsing or signature verification fails.
     /// - **Threshold**: Fewer than `threshold` subconditions were satisfied.
+    /// - **CrossChain**: Fewer than `quorum` attestors signed the observation.
+    /// - **Bridge**: The message is not the expected one, or not signed by
+    ///   the bridge's verifiers.
     #[inline]
     pub fn verify(&self) -> Result<()> {
         match self {
@@ -64,10 +83,134 @@ impl Condition {
             Self::Ed25519(ed25519) => ed25519.verify().map_err(ConditionError::Ed25519)?,
             Self::Secp256k1(secp256k1) => secp256k1.verify().map_err(ConditionError::Secp256k1)?,
             Self::Threshold(threshold) => threshold.verify().map_err(ConditionError::Threshold)?,
+            Self::CrossChain(cross_chain) => {
+                cross_chain.verify().map_err(ConditionError::CrossChain)?
+            }
+            Self::Bridge(bridge) => bridge.verify().map_err(ConditionError::Bridge)?,
         }
         Ok(())
     }
 
+    /// Verifies this condition like [`verify`](Self::verify), but reports
+    /// the outcome of every subcondition of a threshold, for diagnosing
+    /// which part of a fulfillment is wrong.
+    pub fn verification(&self) -> Verification {
+        let leaf = |kind, result: std::result::Result<(), ConditionError>| Verification {
+            kind,
+            error: result.err().map(|e| e.to_string()),
+            subconditions: Vec::new(),
+        };
+        match self {
+            Self::Hashlock(hashlock) => leaf(
+                "hashlock",
+                hashlock.verify().map_err(ConditionError::Hashlock),
+            ),
+            Self::Ed25519(ed25519) => {
+                leaf("ed25519", ed25519.verify().map_err(ConditionError::Ed25519))
+            }
+            Self::Secp256k1(secp256k1) => leaf(
+                "secp256k1",
+                secp256k1.verify().map_err(ConditionError::Secp256k1),
+            ),
+            Self::CrossChain(cross_chain) => leaf(
+                "crosschain",
+                cross_chain.verify().map_err(ConditionError::CrossChain),
+            ),
+            Self::Bridge(bridge) => leaf("bridge", bridge.verify().map_err(ConditionError::Bridge)),
+            Self::Threshold(threshold) => {
+                let subconditions: Vec<Verification> = threshold
+                    .subconditions
+                    .iter()
+                    .map(Self::verification)
+                    .collect();
+                let satisfied = subconditions.iter().filter(|v| v.passed()).count();
+                let error =
+                    (threshold.threshold > 0 && satisfied < threshold.threshold).then(|| {
+                        ConditionError::Threshold(threshold::Error::ThresholdNotMet {
+                            required: threshold.threshold,
+                            satisfied,
+                        })
+                        .to_string()
+                    });
+                Verification {
+                    kind: "threshold",
+                    error,
+                    subconditions,
+                }
+            }
+        }
+    }
+
+    /// Returns a SHA-256 digest identifying this condition by its public
+    /// parameters: hashes, public keys, messages, thresholds, remote claims,
+    /// and bridge emitters, but not the preimages, signatures, attestations,
+    /// and bridge messages fulfilling it.
+    ///
+    /// The guest commits this rather than the condition itself, so a proof
+    /// reveals nothing of the condition to whoever sees the journal, while
+    /// parties who know the condition can recompute it.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let bytes = match self {
+            Self::Hashlock(hashlock) => encode(("hashlock", hashlock.hash)),
+            Self::Ed25519(ed25519) => encode(("ed25519", ed25519.public_key, &ed25519.message)),
+            Self::Secp256k1(secp256k1) => {
+                encode(("secp256k1", &secp256k1.public_key, &secp256k1.message))
+            }
+            Self::Threshold(threshold) => {
+                let subconditions: Vec<[u8; 32]> = threshold
+                    .subconditions
+                    .iter()
+                    .map(Self::fingerprint)
+                    .collect();
+                encode(("threshold", threshold.threshold as u64, subconditions))
+            }
+            Self::CrossChain(cross_chain) => encode((
+                "crosschain",
+                &cross_chain.chain,
+                &cross_chain.claim,
+                &cross_chain.attestors,
+                cross_chain.quorum as u64,
+            )),
+            Self::Bridge(bridge) => encode((
+                "bridge",
+                &bridge.verifiers,
+                bridge.source_chain,
+                bridge.emitter,
+                bridge.payload_hash,
+            )),
+        };
+        Sha256::digest(bytes).into()
+    }
+
+    /// Returns this condition without its fulfillment: preimages,
+    /// signatures, attestations, and bridge messages are emptied, so it can be logged or
+    /// displayed.
+    pub fn redacted(&self) -> Self {
+        match self {
+            Self::Hashlock(hashlock) => Self::hashlock(hashlock.hash, Vec::new()),
+            Self::Ed25519(ed25519) => {
+                Self::ed25519(ed25519.public_key, ed25519.message.clone(), Vec::new())
+            }
+            Self::Secp256k1(secp256k1) => Self::secp256k1(
+                secp256k1.public_key.clone(),
+                secp256k1.message.clone(),
+                Vec::new(),
+            ),
+            Self::Threshold(threshold) => Self::threshold(
+                threshold.threshold,
+                threshold.subconditions.iter().map(Self::redacted).collect(),
+            ),
+            Self::CrossChain(cross_chain) => Self::CrossChain(CrossChain {
+                attestation: Attestation::default(),
+                ..cross_chain.clone()
+            }),
+            Self::Bridge(bridge) => Self::Bridge(Bridge {
+                message: BridgeMessage::default(),
+                ..bridge.clone()
+            }),
+        }
+    }
+
     /// Construct a hashlock (preimage) condition.
     pub fn hashlock(hash: [u8; 32], preimage: Vec<u8>) -> Self {
         Self::Hashlock(Hashlock { hash, preimage })
@@ -98,22 +241,155 @@ impl Condition {
             subconditions,
         })
     }
+
+    /// Construct a cross-chain condition on `claim` holding on `chain` (a
+    /// CAIP-2 ID), attested by `quorum` of `attestors`, without an
+    /// attestation yet.
+    pub fn cross_chain(
+        chain: impl Into<String>,
+        claim: RemoteClaim,
+        attestors: Vec<[u8; 32]>,
+        quorum: usize,
+    ) -> Self {
+        Self::CrossChain(CrossChain {
+            chain: chain.into(),
+            claim,
+            attestors,
+            quorum,
+            attestation: Attestation::default(),
+        })
+    }
+
+    /// Construct a bridge condition on a message from `emitter` on
+    /// `source_chain` whose payload hashes to `payload_hash`, signed by
+    /// `verifiers`, without the message yet.
+    pub fn bridge(
+        verifiers: Verifiers,
+        source_chain: u32,
+        emitter: [u8; 32],
+        payload_hash: [u8; 32],
+    ) -> Self {
+        Self::Bridge(Bridge {
+            verifiers,
+            source_chain,
+            emitter,
+            payload_hash,
+            message: BridgeMessage::default(),
+        })
+    }
+}
+
+/// Outcome of verifying a condition and, for thresholds, each of its
+/// subconditions.
+#[cfg_attr(feature = "json", derive(Serialize))]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Verification {
+    /// Condition type, e.g. `hashlock`.
+    pub kind: &'static str,
+    /// Why verification failed, or `None` if it passed.
+    pub error: Option<String>,
+    /// Outcomes of the subconditions, in order (thresholds only).
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Vec::is_empty"))]
+    pub subconditions: Vec<Verification>,
+}
+
+impl Verification {
+    /// Whether the condition holds.
+    pub fn passed(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Returns the subcondition indices leading from this condition to
+    /// each satisfied one that is not a threshold, in order, e.g. to check
+    /// that an arbiter's approval is among them.
+    ///
+    /// Every subcondition is verified, so this includes those a threshold
+    /// already met did not need.
+    pub fn satisfied_paths(&self) -> Vec<Vec<u32>> {
+        if self.kind != "threshold" {
+            return if self.passed() {
+                vec![Vec::new()]
+            } else {
+                Vec::new()
+            };
+        }
+        let mut paths = Vec::new();
+        for (i, subcondition) in (0u32..).zip(&self.subconditions) {
+            for mut path in subcondition.satisfied_paths() {
+                path.insert(0, i);
+                paths.push(path);
+            }
+        }
+        paths
+    }
+
+    /// Returns the subcondition indices leading from this condition to the
+    /// first failing one that is not a threshold, or `None` if it passed.
+    ///
+    /// The path is empty if this condition itself fails and has no failing
+    /// subconditions.
+    pub fn failing_path(&self) -> Option<Vec<usize>> {
+        if self.passed() {
+            return None;
+        }
+        let path = self
+            .subconditions
+            .iter()
+            .enumerate()
+            .find_map(|(i, subcondition)| {
+                subcondition.failing_path().map(|mut path| {
+                    path.insert(0, i);
+                    path
+                })
+            });
+        Some(path.unwrap_or_default())
+    }
+
+    fn fmt_indented(&self, f: &mut std::fmt::Formatter<'_>, depth: usize) -> std::fmt::Result {
+        let outcome = if self.passed() { "PASS" } else { "FAIL" };
+        write!(
+            f,
+            "{:indent$}{outcome} {}",
+            "",
+            self.kind,
+            indent = depth * 2
+        )?;
+        if let Some(error) = &self.error {
+            write!(f, ": {error}")?;
+        }
+        for subcondition in &self.subconditions {
+            writeln!(f)?;
+            subcondition.fmt_indented(f, depth + 1)?;
+        }
+        Ok(())
+    }
+}
+
+impl std::fmt::Display for Verification {
+    /// Renders the outcome as an indented tree, one condition per line.
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_indented(f, 0)
+    }
 }
 
 #[cfg(feature = "json")]
 impl std::fmt::Display for Condition {
-    /// Serialize the condition to compact JSON for logging or write formats.
+    /// Serialize the condition to compact JSON for logging, without its
+    /// fulfillment (see [`Condition::redacted`]).
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let json = serde_json::to_string(self).map_err(|_| std::fmt::Error)?;
+        let json = serde_json::to_string(&self.redacted()).map_err(|_| std::fmt::Error)?;
         write!(f, "{json}")
     }
 }
 
+/// Encodes the public parameters of a condition for its fingerprint.
+fn encode(parameters: impl Encode) -> Vec<u8> {
+    bincode::encode_to_vec(parameters, standard()).expect("encoding to a Vec cannot fail")
+}
+
 #[cfg(test)]
 mod tests {
 
-    use sha2::{Digest, Sha256};
-
     use super::*;
 
     #[test]
@@ -224,6 +500,313 @@ mod tests {
         assert!(outer2.verify().is_err());
     }
 
+    #[test]
+    fn threshold_failure_counts_every_subcondition() {
+        // Verification stops at the threshold, and reordering by cost does
+        // not change the outcome.
+        let hash = Sha256::digest(b"zkEscrow").into();
+        let correct = Condition::hashlock(hash, b"zkEscrow".to_vec());
+        let wrong_signature = Condition::ed25519([0; 32], b"zkEscrow".to_vec(), vec![0; 64]);
+        let cond = Condition::threshold(1, vec![wrong_signature.clone(), correct.clone()]);
+        assert!(cond.verify().is_ok());
+
+        let cond = Condition::threshold(3, vec![wrong_signature, correct.clone(), correct]);
+        let err = cond.verify().unwrap_err().to_string();
+        assert!(
+            err.contains("needed at least 3 passes, but only 2 succeeded"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn cross_chain_quorum() {
+        use crate::ExecutionState;
+        use cross_chain::{Error, Observation};
+        use ed25519_dalek::ed25519::signature::rand_core::OsRng;
+        use ed25519_dalek::SigningKey;
+
+        let keys: Vec<SigningKey> = (0..3).map(|_| SigningKey::generate(&mut OsRng)).collect();
+        let attestors = keys.iter().map(|k| k.verifying_key().to_bytes()).collect();
+        let claim = RemoteClaim::Escrow {
+            instance: [7; 32],
+            state: ExecutionState::Released,
+        };
+        let unattested = Condition::cross_chain("eip155:1", claim.clone(), attestors, 2);
+        let observation = Observation {
+            chain: "eip155:1".into(),
+            claim,
+            height: 100,
+            block_hash: [1; 32],
+        };
+
+        let mut cond = unattested.clone();
+        let Condition::CrossChain(cross_chain) = &mut cond else {
+            unreachable!()
+        };
+        cross_chain.attest(&observation, &keys[0]).unwrap();
+        // The same attestor counts once.
+        cross_chain.attest(&observation, &keys[0]).unwrap();
+        assert!(cond.verify().is_err());
+
+        let Condition::CrossChain(cross_chain) = &mut cond else {
+            unreachable!()
+        };
+        let reorged = Observation {
+            block_hash: [2; 32],
+            ..observation.clone()
+        };
+        assert!(matches!(
+            cross_chain.attest(&reorged, &keys[1]),
+            Err(Error::BlockMismatch { .. })
+        ));
+        assert!(matches!(
+            cross_chain.attest(&observation, &SigningKey::generate(&mut OsRng)),
+            Err(Error::UnknownAttestor)
+        ));
+        cross_chain.attest(&observation, &keys[1]).unwrap();
+        assert!(cond.verify().is_ok());
+        assert_eq!(cond.verification().kind, "crosschain");
+
+        // The attestation is the fulfillment.
+        assert_eq!(cond.fingerprint(), unattested.fingerprint());
+        assert_eq!(cond.redacted(), unattested);
+
+        // Tampering with the attested block invalidates the signatures.
+        let Condition::CrossChain(cross_chain) = &mut cond else {
+            unreachable!()
+        };
+        cross_chain.attestation.height += 1;
+        assert!(cond.verify().is_err());
+    }
+
+    #[test]
+    fn bridge_wormhole_vaa() {
+        use crate::error::EscrowError;
+        use bridge::{address, Error, Vaa};
+        use k256::ecdsa::SigningKey;
+        use k256::elliptic_curve::rand_core::OsRng;
+
+        let keys: Vec<SigningKey> = (0..4).map(|_| SigningKey::random(&mut OsRng)).collect();
+        let guardians = keys.iter().map(|k| address(k.verifying_key())).collect();
+        let verifiers = Verifiers::Wormhole {
+            guardian_set_index: 4,
+            guardians,
+        };
+        let payload = b"delivered".to_vec();
+        let emitter = [9; 32];
+        let unfulfilled = Condition::bridge(verifiers, 2, emitter, Sha256::digest(&payload).into());
+        let mut vaa = Vaa {
+            guardian_set_index: 4,
+            signatures: Vec::new(),
+            timestamp: 1_700_000_000,
+            nonce: 0,
+            emitter_chain: 2,
+            emitter_address: emitter,
+            sequence: 42,
+            consistency_level: 1,
+            payload,
+        };
+        let with_vaa = |vaa: &Vaa| {
+            let mut cond = unfulfilled.clone();
+            let Condition::Bridge(bridge) = &mut cond else {
+                unreachable!()
+            };
+            bridge.message = BridgeMessage::vaa(vaa.to_bytes());
+            cond
+        };
+
+        // A quorum of four guardians is three.
+        for guardian in [2, 0] {
+            vaa.sign(guardian, &keys[usize::from(guardian)]);
+        }
+        assert!(matches!(
+            with_vaa(&vaa).verify(),
+            Err(EscrowError::Condition(ConditionError::Bridge(
+                Error::QuorumNotMet {
+                    required: 3,
+                    signed: 2
+                }
+            )))
+        ));
+        vaa.sign(3, &keys[3]);
+        assert_eq!(Vaa::parse(&vaa.to_bytes()).unwrap(), vaa);
+        let cond = with_vaa(&vaa);
+        assert!(cond.verify().is_ok());
+        assert_eq!(cond.verification().kind, "bridge");
+        assert_eq!(cond.fingerprint(), unfulfilled.fingerprint());
+        assert_eq!(cond.redacted(), unfulfilled);
+
+        // Signatures must be in guardian order, and by the named guardian.
+        let mut unordered = vaa.clone();
+        unordered.signatures.swap(0, 1);
+        assert!(matches!(
+            with_vaa(&unordered).verify(),
+            Err(EscrowError::Condition(ConditionError::Bridge(
+                Error::UnorderedSignatures
+            )))
+        ));
+        let mut forged = vaa.clone();
+        forged.signatures[0].guardian = 1;
+        assert!(matches!(
+            with_vaa(&forged).verify(),
+            Err(EscrowError::Condition(ConditionError::Bridge(
+                Error::InvalidSignature(1)
+            )))
+        ));
+
+        // The message must come from the emitter, with the payload.
+        let mut other = vaa.clone();
+        other.emitter_chain = 4;
+        assert!(matches!(
+            with_vaa(&other).verify(),
+            Err(EscrowError::Condition(ConditionError::Bridge(
+                Error::EmitterMismatch
+            )))
+        ));
+        let mut other = vaa;
+        other.payload = b"refunded".to_vec();
+        assert!(matches!(
+            with_vaa(&other).verify(),
+            Err(EscrowError::Condition(ConditionError::Bridge(
+                Error::PayloadMismatch
+            )))
+        ));
+    }
+
+    #[test]
+    fn bridge_layerzero_packet() {
+        use bridge::{address, Error, Packet};
+        use k256::ecdsa::SigningKey;
+        use k256::elliptic_curve::rand_core::OsRng;
+
+        let keys: Vec<SigningKey> = (0..3).map(|_| SigningKey::random(&mut OsRng)).collect();
+        let dvns = keys.iter().map(|k| address(k.verifying_key())).collect();
+        let packet = Packet {
+            nonce: 1,
+            source_eid: 30101,
+            sender: [1; 32],
+            destination_eid: 30168,
+            receiver: [2; 32],
+            guid: [3; 32],
+            message: b"delivered".to_vec(),
+        };
+        let verifiers = Verifiers::LayerZero {
+            destination: 30168,
+            receiver: [2; 32],
+            dvns,
+            threshold: 2,
+        };
+        let payload_hash = Sha256::digest(&packet.message).into();
+        let mut cond = Condition::bridge(verifiers, 30101, [1; 32], payload_hash);
+        let Condition::Bridge(bridge) = &mut cond else {
+            unreachable!()
+        };
+        assert_eq!(Packet::parse(&packet.to_bytes()).unwrap(), packet);
+
+        bridge
+            .add_dvn_signature(&packet, packet.sign(&keys[1]))
+            .unwrap();
+        // The same DVN counts once.
+        bridge
+            .add_dvn_signature(&packet, packet.sign(&keys[1]))
+            .unwrap();
+        assert!(matches!(
+            bridge.verify(),
+            Err(Error::QuorumNotMet {
+                required: 2,
+                signed: 1
+            })
+        ));
+        let stranger = SigningKey::random(&mut OsRng);
+        assert!(matches!(
+            bridge.add_dvn_signature(&packet, packet.sign(&stranger)),
+            Err(Error::UnknownSigner)
+        ));
+        let other = Packet {
+            nonce: 2,
+            ..packet.clone()
+        };
+        assert!(matches!(
+            bridge.add_dvn_signature(&other, other.sign(&keys[0])),
+            Err(Error::PacketMismatch)
+        ));
+        bridge
+            .add_dvn_signature(&packet, packet.sign(&keys[2]))
+            .unwrap();
+        assert!(cond.verify().is_ok());
+
+        // The packet must be delivered to the receiver.
+        let Condition::Bridge(bridge) = &mut cond else {
+            unreachable!()
+        };
+        bridge.message.data = Packet {
+            receiver: [4; 32],
+            ..packet
+        }
+        .to_bytes();
+        assert!(matches!(bridge.verify(), Err(Error::DestinationMismatch)));
+    }
+
+    #[test]
+    fn verification_tree() {
+        let preimage = b"zkEscrow".to_vec();
+        let hash = Sha256::digest(&preimage).into();
+        let correct = Condition::hashlock(hash, preimage);
+        let wrong = Condition::hashlock(hash, b"wrong-preimage".to_vec());
+
+        let cond = Condition::threshold(2, vec![correct.clone(), wrong.clone()]);
+        let verification = cond.verification();
+        assert!(!verification.passed());
+        assert_eq!(verification.kind, "threshold");
+        assert!(verification.subconditions[0].passed());
+        assert!(!verification.subconditions[1].passed());
+        assert_eq!(verification.passed(), cond.verify().is_ok());
+        assert_eq!(verification.failing_path(), Some(vec![1]));
+        assert_eq!(verification.satisfied_paths(), vec![vec![0]]);
+
+        let nested = Condition::threshold(2, vec![correct.clone(), cond]);
+        assert_eq!(nested.verification().failing_path(), Some(vec![1, 1]));
+        assert_eq!(
+            nested.verification().satisfied_paths(),
+            vec![vec![0], vec![1, 0]]
+        );
+        assert_eq!(wrong.verification().failing_path(), Some(vec![]));
+
+        let cond = Condition::threshold(1, vec![wrong, correct]);
+        assert_eq!(cond.verification().failing_path(), None);
+        assert!(cond.verification().passed());
+        assert_eq!(
+            cond.verification().to_string(),
+            format!(
+                "PASS threshold\n  FAIL hashlock: {}\n  PASS hashlock",
+                cond.verification().subconditions[0].error.as_ref().unwrap()
+            )
+        );
+    }
+
+    #[test]
+    fn fingerprint_ignores_fulfillment() {
+        let preimage = b"zkEscrow".to_vec();
+        let hash = Sha256::digest(&preimage).into();
+        let fulfilled = Condition::threshold(1, vec![Condition::hashlock(hash, preimage)]);
+        let unfulfilled = Condition::threshold(1, vec![Condition::hashlock(hash, Vec::new())]);
+        assert_eq!(fulfilled.fingerprint(), unfulfilled.fingerprint());
+        assert_eq!(fulfilled.redacted(), unfulfilled);
+
+        let other = Condition::threshold(2, vec![Condition::hashlock(hash, Vec::new())]);
+        assert_ne!(fulfilled.fingerprint(), other.fingerprint());
+    }
+
+    #[test]
+    fn debug_redacts_fulfillment() {
+        let preimage = b"top-secret".to_vec();
+        let hash = Sha256::digest(&preimage).into();
+        let cond = Condition::hashlock(hash, preimage);
+        let debug = format!("{cond:?}");
+        assert!(!debug.contains("top-secret"));
+        assert!(debug.contains("<redacted>"));
+    }
+
     #[cfg(feature = "json")]
     #[test]
     fn json_roundtrip_hashlock() {
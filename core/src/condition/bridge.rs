@@ -0,0 +1,647 @@
+use bincode::{Decode, Encode};
+#[cfg(feature = "json")]
+use hex::serde as hex_serde;
+use k256::ecdsa::{RecoveryId, Signature, SigningKey, VerifyingKey};
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sha3::Keccak256;
+
+#[cfg(feature = "json")]
+use crate::serde::hex_keys_serde;
+
+/// Version of the VAAs Wormhole guardians sign.
+const VAA_VERSION: u8 = 1;
+
+/// Version of the LayerZero V2 packets DVNs verify.
+const PACKET_VERSION: u8 = 1;
+
+/// Length of an encoded LayerZero packet header.
+const PACKET_HEADER_LEN: usize = 81;
+
+/// Bridge condition: a message from a contract on another chain, delivered
+/// by [Wormhole] or [LayerZero].
+///
+/// The condition holds when `message` is a message sent by `emitter` on
+/// `source_chain` whose payload hashes to `payload_hash` with SHA-256, and
+/// is signed by the bridge's verifiers: a quorum (two thirds plus one) of a
+/// Wormhole guardian set, which signs VAAs, or `threshold` of the DVNs
+/// (decentralized verifier networks) of a LayerZero application. A contract
+/// on the other chain can thus release an escrow by sending a message, e.g.
+/// once a delivery was paid for there.
+///
+/// On Solana, escrows can also be finished on-chain by a VAA that
+/// Wormhole's core bridge verified and posted.
+///
+/// [Wormhole]: https://wormhole.com/docs/protocol/infrastructure/vaas/
+/// [LayerZero]: https://docs.layerzero.network/v2/concepts/protocol/packet
+///
+/// # Example
+///
+/// ```ignore
+/// use zescrow_core::condition::bridge::{BridgeMessage, Verifiers};
+/// use zescrow_core::Condition;
+///
+/// let verifiers = Verifiers::Wormhole { guardian_set_index: 4, guardians };
+/// let payload_hash = Sha256::digest(b"delivered").into();
+/// let mut condition = Condition::bridge(verifiers, 2, emitter, payload_hash);
+/// // Once the guardians signed the message:
+/// if let Condition::Bridge(bridge) = &mut condition {
+///     bridge.message = BridgeMessage::vaa(vaa_bytes);
+/// }
+/// ```
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct Bridge {
+    /// Bridge carrying the message, and the verifiers that must sign it.
+    pub verifiers: Verifiers,
+
+    /// Chain the message is sent from: its Wormhole chain ID (e.g. 2 for
+    /// Ethereum) or LayerZero endpoint ID (e.g. 30101).
+    pub source_chain: u32,
+
+    /// Address of the contract sending the message, left-padded to 32
+    /// bytes.
+    #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
+    pub emitter: [u8; 32],
+
+    /// SHA-256 digest of the message payload (LayerZero: the application
+    /// message, without the GUID).
+    #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
+    pub payload_hash: [u8; 32],
+
+    /// The signed message: the fulfillment.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub message: BridgeMessage,
+}
+
+/// A bridge and the verifiers whose signatures make its messages valid.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "lowercase"))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub enum Verifiers {
+    /// A VAA signed by a quorum of a Wormhole guardian set.
+    Wormhole {
+        /// Index of the guardian set.
+        guardian_set_index: u32,
+        /// Ethereum addresses of the guardians, in guardian set order.
+        #[cfg_attr(feature = "json", serde(with = "hex_keys_serde"))]
+        guardians: Vec<[u8; 20]>,
+    },
+    /// A LayerZero V2 packet signed by `threshold` of an application's
+    /// DVNs.
+    LayerZero {
+        /// Endpoint ID of the chain the packet is delivered to.
+        destination: u32,
+        /// Address of the application receiving the packet, left-padded to
+        /// 32 bytes.
+        #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
+        receiver: [u8; 32],
+        /// Ethereum addresses of the DVN signers.
+        #[cfg_attr(feature = "json", serde(with = "hex_keys_serde"))]
+        dvns: Vec<[u8; 20]>,
+        /// Minimum number of distinct DVNs that must sign.
+        threshold: usize,
+    },
+}
+
+/// A bridge message and, for LayerZero, its DVN signatures.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Clone, Default, Encode, Decode, PartialEq, Eq)]
+pub struct BridgeMessage {
+    /// The VAA, or the encoded LayerZero packet.
+    #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
+    pub data: Vec<u8>,
+
+    /// DVN signatures of the packet (LayerZero only: VAAs carry their
+    /// guardians' signatures).
+    #[cfg_attr(feature = "json", serde(default))]
+    pub signatures: Vec<DvnSignature>,
+}
+
+// The message is the fulfillment; keep it out of logs.
+impl std::fmt::Debug for BridgeMessage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BridgeMessage")
+            .field("data", &"<redacted>")
+            .field("signatures", &self.signatures.len())
+            .finish()
+    }
+}
+
+impl BridgeMessage {
+    /// Wraps a VAA.
+    pub fn vaa(vaa: Vec<u8>) -> Self {
+        Self {
+            data: vaa,
+            signatures: Vec::new(),
+        }
+    }
+}
+
+/// A DVN's signature of a LayerZero [`Packet`].
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct DvnSignature {
+    /// Index of the DVN in [`Verifiers::LayerZero`]'s `dvns`.
+    pub dvn: u32,
+
+    /// Recoverable secp256k1 signature (`r || s || v`) of the packet's
+    /// [digest](Packet::digest).
+    #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
+    pub signature: Vec<u8>,
+}
+
+impl Bridge {
+    /// Adds the signature of the DVN that signed `packet` with `signature`
+    /// to the message, replacing any earlier one, after checking that
+    /// `packet` is the message's. The first signature sets the message to
+    /// `packet`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the condition is not a LayerZero one, `packet`
+    /// is another one, or the signer is not one of the DVNs.
+    pub fn add_dvn_signature(&mut self, packet: &Packet, signature: Vec<u8>) -> Result<(), Error> {
+        let Verifiers::LayerZero { dvns, .. } = &self.verifiers else {
+            return Err(Error::NotLayerZero);
+        };
+        if !self.message.data.is_empty() && Packet::parse(&self.message.data)? != *packet {
+            return Err(Error::PacketMismatch);
+        }
+        let signer = recover_address(&packet.digest(), &signature);
+        let dvn = dvns
+            .iter()
+            .position(|dvn| Some(*dvn) == signer)
+            .ok_or(Error::UnknownSigner)? as u32;
+        if self.message.data.is_empty() {
+            self.message.data = packet.to_bytes();
+        }
+        let signatures = &mut self.message.signatures;
+        signatures.retain(|s| s.dvn != dvn);
+        signatures.push(DvnSignature { dvn, signature });
+        Ok(())
+    }
+
+    /// Verifies that the message was sent by the emitter on the source
+    /// chain with the expected payload, and signed by the verifiers.
+    pub fn verify(&self) -> Result<(), Error> {
+        match &self.verifiers {
+            Verifiers::Wormhole {
+                guardian_set_index,
+                guardians,
+            } => {
+                let vaa = Vaa::parse(&self.message.data)?;
+                if u32::from(vaa.emitter_chain) != self.source_chain
+                    || vaa.emitter_address != self.emitter
+                {
+                    return Err(Error::EmitterMismatch);
+                }
+                self.check_payload(&vaa.payload)?;
+                vaa.verify(*guardian_set_index, guardians)
+            }
+            Verifiers::LayerZero {
+                destination,
+                receiver,
+                dvns,
+                threshold,
+            } => {
+                let packet = Packet::parse(&self.message.data)?;
+                if packet.source_eid != self.source_chain || packet.sender != self.emitter {
+                    return Err(Error::EmitterMismatch);
+                }
+                if packet.destination_eid != *destination || packet.receiver != *receiver {
+                    return Err(Error::DestinationMismatch);
+                }
+                self.check_payload(&packet.message)?;
+                packet.verify(dvns, *threshold, &self.message.signatures)
+            }
+        }
+    }
+
+    fn check_payload(&self, payload: &[u8]) -> Result<(), Error> {
+        if <[u8; 32]>::from(Sha256::digest(payload)) != self.payload_hash {
+            return Err(Error::PayloadMismatch);
+        }
+        Ok(())
+    }
+}
+
+/// A Wormhole VAA (verified action approval): a message observed by the
+/// guardians, with their signatures.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Vaa {
+    /// Index of the guardian set that signed the VAA.
+    pub guardian_set_index: u32,
+    /// Guardian signatures, in increasing guardian order.
+    pub signatures: Vec<GuardianSignature>,
+    /// Unix time of the block the message was sent in.
+    pub timestamp: u32,
+    /// Nonce chosen by the emitter.
+    pub nonce: u32,
+    /// Wormhole chain ID of the emitter's chain.
+    pub emitter_chain: u16,
+    /// Address of the emitter, left-padded to 32 bytes.
+    pub emitter_address: [u8; 32],
+    /// Sequence number of the message among the emitter's.
+    pub sequence: u64,
+    /// Finality the guardians waited for before signing.
+    pub consistency_level: u8,
+    /// The message payload.
+    pub payload: Vec<u8>,
+}
+
+/// A guardian's signature of a [`Vaa`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GuardianSignature {
+    /// Index of the guardian in the guardian set.
+    pub guardian: u8,
+    /// Recoverable secp256k1 signature (`r || s || v`) of the VAA's
+    /// [digest](Vaa::digest).
+    pub signature: [u8; 65],
+}
+
+impl Vaa {
+    /// Parses a VAA from its wire format.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is truncated or not a version 1 VAA.
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader(bytes);
+        let version = reader.u8()?;
+        if version != VAA_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        let guardian_set_index = reader.u32()?;
+        let signatures = (0..reader.u8()?)
+            .map(|_| {
+                Ok(GuardianSignature {
+                    guardian: reader.u8()?,
+                    signature: reader.array()?,
+                })
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Self {
+            guardian_set_index,
+            signatures,
+            timestamp: reader.u32()?,
+            nonce: reader.u32()?,
+            emitter_chain: u16::from_be_bytes(reader.array()?),
+            emitter_address: reader.array()?,
+            sequence: reader.u64()?,
+            consistency_level: reader.u8()?,
+            payload: reader.0.to_vec(),
+        })
+    }
+
+    /// Returns the signed body of the VAA: everything after the signatures.
+    pub fn body(&self) -> Vec<u8> {
+        let mut body = Vec::with_capacity(51 + self.payload.len());
+        body.extend_from_slice(&self.timestamp.to_be_bytes());
+        body.extend_from_slice(&self.nonce.to_be_bytes());
+        body.extend_from_slice(&self.emitter_chain.to_be_bytes());
+        body.extend_from_slice(&self.emitter_address);
+        body.extend_from_slice(&self.sequence.to_be_bytes());
+        body.push(self.consistency_level);
+        body.extend_from_slice(&self.payload);
+        body
+    }
+
+    /// Returns the Keccak-256 digest of the body, which e.g. Wormhole's
+    /// Solana core bridge derives posted VAA accounts from.
+    pub fn body_hash(&self) -> [u8; 32] {
+        Keccak256::digest(self.body()).into()
+    }
+
+    /// Returns the digest guardians sign: the Keccak-256 digest of the
+    /// [body hash](Self::body_hash).
+    pub fn digest(&self) -> [u8; 32] {
+        Keccak256::digest(self.body_hash()).into()
+    }
+
+    /// Encodes the VAA in its wire format.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = vec![VAA_VERSION];
+        bytes.extend_from_slice(&self.guardian_set_index.to_be_bytes());
+        bytes.push(self.signatures.len() as u8);
+        for GuardianSignature {
+            guardian,
+            signature,
+        } in &self.signatures
+        {
+            bytes.push(*guardian);
+            bytes.extend_from_slice(signature);
+        }
+        bytes.extend_from_slice(&self.body());
+        bytes
+    }
+
+    /// Signs the VAA as guardian `guardian` holding `key`, keeping the
+    /// signatures in guardian order and replacing any earlier one.
+    pub fn sign(&mut self, guardian: u8, key: &SigningKey) {
+        let signature = sign_digest(key, &self.digest());
+        self.signatures.retain(|s| s.guardian != guardian);
+        let index = self.signatures.partition_point(|s| s.guardian < guardian);
+        self.signatures.insert(
+            index,
+            GuardianSignature {
+                guardian,
+                signature,
+            },
+        );
+    }
+
+    /// Verifies the VAA as Wormhole's core bridge does: it must be signed
+    /// by guardian set `guardian_set_index`, with signatures in strictly
+    /// increasing guardian order that are all valid, by a quorum of two
+    /// thirds plus one of `guardians`.
+    pub fn verify(&self, guardian_set_index: u32, guardians: &[[u8; 20]]) -> Result<(), Error> {
+        if self.guardian_set_index != guardian_set_index {
+            return Err(Error::GuardianSetMismatch {
+                expected: guardian_set_index,
+                actual: self.guardian_set_index,
+            });
+        }
+        let digest = self.digest();
+        let mut last = None;
+        for GuardianSignature {
+            guardian,
+            signature,
+        } in &self.signatures
+        {
+            if last.is_some_and(|last| *guardian <= last) {
+                return Err(Error::UnorderedSignatures);
+            }
+            last = Some(*guardian);
+            let address = guardians
+                .get(usize::from(*guardian))
+                .ok_or(Error::UnknownGuardian(*guardian))?;
+            if recover_address(&digest, signature) != Some(*address) {
+                return Err(Error::InvalidSignature(*guardian));
+            }
+        }
+        let required = guardians.len() * 2 / 3 + 1;
+        if self.signatures.len() < required {
+            return Err(Error::QuorumNotMet {
+                required,
+                signed: self.signatures.len(),
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A LayerZero V2 packet: a message from a sender application on one
+/// endpoint to a receiver on another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Packet {
+    /// Nonce of the packet on its pathway.
+    pub nonce: u64,
+    /// Endpoint ID of the sending chain.
+    pub source_eid: u32,
+    /// Address of the sender, left-padded to 32 bytes.
+    pub sender: [u8; 32],
+    /// Endpoint ID of the receiving chain.
+    pub destination_eid: u32,
+    /// Address of the receiver, left-padded to 32 bytes.
+    pub receiver: [u8; 32],
+    /// Globally unique ID of the packet.
+    pub guid: [u8; 32],
+    /// The application message.
+    pub message: Vec<u8>,
+}
+
+impl Packet {
+    /// Parses an encoded packet.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is truncated or not a version 1 packet.
+    pub fn parse(bytes: &[u8]) -> Result<Self, Error> {
+        let mut reader = Reader(bytes);
+        let version = reader.u8()?;
+        if version != PACKET_VERSION {
+            return Err(Error::UnsupportedVersion(version));
+        }
+        Ok(Self {
+            nonce: reader.u64()?,
+            source_eid: reader.u32()?,
+            sender: reader.array()?,
+            destination_eid: reader.u32()?,
+            receiver: reader.array()?,
+            guid: reader.array()?,
+            message: reader.0.to_vec(),
+        })
+    }
+
+    /// Returns the packet header: everything before the GUID.
+    pub fn header(&self) -> [u8; PACKET_HEADER_LEN] {
+        let mut header = [0; PACKET_HEADER_LEN];
+        header[0] = PACKET_VERSION;
+        header[1..9].copy_from_slice(&self.nonce.to_be_bytes());
+        header[9..13].copy_from_slice(&self.source_eid.to_be_bytes());
+        header[13..45].copy_from_slice(&self.sender);
+        header[45..49].copy_from_slice(&self.destination_eid.to_be_bytes());
+        header[49..81].copy_from_slice(&self.receiver);
+        header
+    }
+
+    /// Returns the payload hash DVNs verify: the Keccak-256 digest of the
+    /// GUID and the message.
+    pub fn payload_hash(&self) -> [u8; 32] {
+        let mut hasher = Keccak256::new();
+        hasher.update(self.guid);
+        hasher.update(&self.message);
+        hasher.finalize().into()
+    }
+
+    /// Returns the digest DVN signers sign: the Keccak-256 digest of the
+    /// header and the [payload hash](Self::payload_hash).
+    pub fn digest(&self) -> [u8; 32] {
+        let mut hasher = Keccak256::new();
+        hasher.update(self.header());
+        hasher.update(self.payload_hash());
+        hasher.finalize().into()
+    }
+
+    /// Encodes the packet.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = self.header().to_vec();
+        bytes.extend_from_slice(&self.guid);
+        bytes.extend_from_slice(&self.message);
+        bytes
+    }
+
+    /// Signs the packet as the DVN signer holding `key`.
+    pub fn sign(&self, key: &SigningKey) -> Vec<u8> {
+        sign_digest(key, &self.digest()).to_vec()
+    }
+
+    /// Verifies that at least `threshold` distinct `dvns` signed the
+    /// packet among `signatures`.
+    pub fn verify(
+        &self,
+        dvns: &[[u8; 20]],
+        threshold: usize,
+        signatures: &[DvnSignature],
+    ) -> Result<(), Error> {
+        if threshold == 0 || threshold > dvns.len() {
+            return Err(Error::InvalidThreshold {
+                threshold,
+                dvns: dvns.len(),
+            });
+        }
+        let digest = self.digest();
+        let mut signed = vec![false; dvns.len()];
+        for DvnSignature { dvn, signature } in signatures {
+            let index = *dvn as usize;
+            if signed.get(index) != Some(&false) {
+                continue;
+            }
+            if recover_address(&digest, signature) == Some(dvns[index]) {
+                signed[index] = true;
+            }
+        }
+        let signed = signed.iter().filter(|signed| **signed).count();
+        if signed < threshold {
+            return Err(Error::QuorumNotMet {
+                required: threshold,
+                signed,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Returns the Ethereum address of `key`: the last 20 bytes of the
+/// Keccak-256 digest of its uncompressed point.
+pub fn address(key: &VerifyingKey) -> [u8; 20] {
+    let point = key.to_encoded_point(false);
+    let digest = Keccak256::digest(&point.as_bytes()[1..]);
+    digest[12..].try_into().expect("digests are 32 bytes")
+}
+
+/// Recovers the address that signed `digest` with a recoverable signature
+/// (`r || s || v`, with `v` either 0 or 1, or 27 or 28).
+fn recover_address(digest: &[u8; 32], signature: &[u8]) -> Option<[u8; 20]> {
+    let (signature, v) = match signature {
+        [signature @ .., v] if signature.len() == 64 => (signature, *v),
+        _ => return None,
+    };
+    let signature = Signature::from_slice(signature).ok()?;
+    let recovery_id = RecoveryId::from_byte(if v >= 27 { v - 27 } else { v })?;
+    VerifyingKey::recover_from_prehash(digest, &signature, recovery_id)
+        .ok()
+        .map(|key| address(&key))
+}
+
+/// Signs `digest` with `key` as a recoverable signature.
+fn sign_digest(key: &SigningKey, digest: &[u8; 32]) -> [u8; 65] {
+    let (signature, recovery_id) = key
+        .sign_prehash_recoverable(digest)
+        .expect("32-byte digests can be signed");
+    let mut bytes = [0; 65];
+    bytes[..64].copy_from_slice(&signature.to_bytes());
+    bytes[64] = recovery_id.to_byte();
+    bytes
+}
+
+/// Reads big-endian fields off the front of a message.
+struct Reader<'a>(&'a [u8]);
+
+impl Reader<'_> {
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], Error> {
+        let (field, rest) = self.0.split_first_chunk().ok_or(Error::Truncated)?;
+        self.0 = rest;
+        Ok(*field)
+    }
+
+    fn u8(&mut self) -> Result<u8, Error> {
+        self.array().map(u8::from_be_bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, Error> {
+        self.array().map(u32::from_be_bytes)
+    }
+
+    fn u64(&mut self) -> Result<u64, Error> {
+        self.array().map(u64::from_be_bytes)
+    }
+}
+
+/// Errors from bridge message verification.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The message ends before all its fields.
+    #[error("message is truncated")]
+    Truncated,
+
+    /// The message is of a version that is not supported.
+    #[error("unsupported message version {0}")]
+    UnsupportedVersion(u8),
+
+    /// The VAA was signed by another guardian set.
+    #[error("VAA is signed by guardian set {actual}, not {expected}")]
+    GuardianSetMismatch {
+        /// Guardian set of the condition.
+        expected: u32,
+        /// Guardian set that signed the VAA.
+        actual: u32,
+    },
+
+    /// The VAA's signatures are not in strictly increasing guardian order.
+    #[error("VAA signatures are not in guardian order")]
+    UnorderedSignatures,
+
+    /// A VAA signature names a guardian outside the guardian set.
+    #[error("guardian {0} is not in the guardian set")]
+    UnknownGuardian(u8),
+
+    /// A VAA signature is not its guardian's.
+    #[error("signature of guardian {0} is invalid")]
+    InvalidSignature(u8),
+
+    /// The DVN threshold is zero or exceeds the number of DVNs.
+    #[error("threshold of {threshold} is invalid for {dvns} DVNs")]
+    InvalidThreshold {
+        /// Required number of DVNs.
+        threshold: usize,
+        /// Number of DVNs.
+        dvns: usize,
+    },
+
+    /// Fewer than the required number of verifiers signed the message.
+    #[error("needed signatures of {required} verifiers, but only {signed} signed")]
+    QuorumNotMet {
+        /// Required number of verifiers.
+        required: usize,
+        /// Number of distinct verifiers with valid signatures.
+        signed: usize,
+    },
+
+    /// The message was sent by another emitter or from another chain.
+    #[error("message is not from the condition's emitter")]
+    EmitterMismatch,
+
+    /// The packet is delivered to another receiver or chain.
+    #[error("packet is not for the condition's receiver")]
+    DestinationMismatch,
+
+    /// The message payload does not hash to the condition's payload hash.
+    #[error("message payload does not match the payload hash")]
+    PayloadMismatch,
+
+    /// A DVN signature was added to a condition that is not a LayerZero
+    /// one.
+    #[error("condition is not a LayerZero one")]
+    NotLayerZero,
+
+    /// A DVN signature of another packet than the message was added.
+    #[error("packet is not the condition's message")]
+    PacketMismatch,
+
+    /// The signer is not one of the DVNs.
+    #[error("signer is not one of the DVNs")]
+    UnknownSigner,
+}
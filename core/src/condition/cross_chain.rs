@@ -0,0 +1,292 @@
+use bincode::config::standard;
+use bincode::{Decode, Encode};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+#[cfg(feature = "json")]
+use hex::serde as hex_serde;
+#[cfg(feature = "json")]
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+#[cfg(feature = "json")]
+use crate::serde::hex_keys_serde;
+use crate::ExecutionState;
+
+/// Domain separator of the digests attestors sign.
+const DOMAIN: &str = "zescrow/cross-chain/v1";
+
+/// Cross-chain condition: a claim about another chain, attested by a
+/// quorum of a light-client committee.
+///
+/// The committee (e.g. relayers following the other chain's consensus)
+/// observes the claim at a final block of that chain, and each member signs
+/// the [`Observation`] with its Ed25519 key. The condition holds when at
+/// least `quorum` distinct `attestors` signed the observation of `claim` on
+/// `chain` recorded in `attestation`, so e.g. the release of an escrow on
+/// Solana can be proven to the escrow contract on Ethereum.
+///
+/// # Example
+///
+/// ```ignore
+/// use zescrow_core::condition::cross_chain::{Observation, RemoteClaim};
+/// use zescrow_core::Condition;
+///
+/// let claim = RemoteClaim::Escrow { instance, state: ExecutionState::Released };
+/// let mut condition = Condition::cross_chain("eip155:1", claim, attestors, 2);
+/// // Each attestor observes the claim and signs it:
+/// let observation = agent.observe_escrow(&remote_metadata).await?;
+/// if let Condition::CrossChain(cross_chain) = &mut condition {
+///     cross_chain.attest(&observation, &signing_key)?;
+/// }
+/// ```
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct CrossChain {
+    /// CAIP-2 ID of the chain the claim is about, e.g. `eip155:1` or
+    /// `solana:5eykt4UsFv8P8NJdTREpY1vzqKqZKvdp`.
+    pub chain: String,
+
+    /// What must hold on `chain`.
+    pub claim: RemoteClaim,
+
+    /// Ed25519 public keys of the committee members.
+    #[cfg_attr(feature = "json", serde(with = "hex_keys_serde"))]
+    pub attestors: Vec<[u8; 32]>,
+
+    /// Minimum number of distinct attestors that must sign.
+    pub quorum: usize,
+
+    /// The attested observation of `claim`: the fulfillment.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub attestation: Attestation,
+}
+
+/// A claim about the state of another chain.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "lowercase"))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub enum RemoteClaim {
+    /// The escrow that proofs are bound to as `instance` (see
+    /// [`EscrowBinding::instance`](crate::interface::EscrowBinding)) has
+    /// reached `state`, e.g. [`ExecutionState::Released`].
+    Escrow {
+        /// Instance of the escrow.
+        #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
+        instance: [u8; 32],
+        /// State the escrow reached.
+        state: ExecutionState,
+    },
+    /// The account (Solana: its address) or storage slot (EVM: contract
+    /// address and slot) at `key` holds data hashing to `value_hash` with
+    /// SHA-256, e.g. a token account holding a delivered asset.
+    State {
+        /// Account address, or contract address followed by the slot.
+        #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
+        key: Vec<u8>,
+        /// SHA-256 digest of the account data or slot value.
+        #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
+        value_hash: [u8; 32],
+    },
+}
+
+/// A claim observed to hold on a chain at a block: the statement attestors
+/// sign.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct Observation {
+    /// CAIP-2 ID of the chain.
+    pub chain: String,
+    /// The claim observed.
+    pub claim: RemoteClaim,
+    /// Block number (EVM) or slot (Solana) the claim was observed at.
+    pub height: u64,
+    /// Hash of the block.
+    #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
+    pub block_hash: [u8; 32],
+}
+
+impl Observation {
+    /// Returns the digest attestors sign: SHA-256 of a domain separator and
+    /// the encoded observation.
+    pub fn digest(&self) -> [u8; 32] {
+        let bytes = bincode::encode_to_vec((DOMAIN, self), standard())
+            .expect("encoding to a Vec cannot fail");
+        Sha256::digest(bytes).into()
+    }
+
+    /// Signs the observation with an attestor's `key`.
+    pub fn sign(&self, key: &SigningKey) -> Vec<u8> {
+        key.sign(&self.digest()).to_bytes().to_vec()
+    }
+}
+
+/// Block a claim was observed at, and the attestors' signatures of the
+/// observation.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Default, Encode, Decode, PartialEq, Eq)]
+pub struct Attestation {
+    /// Block number (EVM) or slot (Solana) the claim was observed at.
+    pub height: u64,
+
+    /// Hash of the block.
+    #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
+    pub block_hash: [u8; 32],
+
+    /// Signatures of the observation, by attestor.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub signatures: Vec<AttestorSignature>,
+}
+
+/// An attestor's signature of an [`Observation`].
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Clone, Encode, Decode, PartialEq, Eq)]
+pub struct AttestorSignature {
+    /// Index of the attestor in [`CrossChain::attestors`].
+    pub attestor: u32,
+
+    /// Ed25519 signature of the observation's digest.
+    #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
+    pub signature: Vec<u8>,
+}
+
+// Signatures are the fulfillment; keep them out of logs.
+impl std::fmt::Debug for AttestorSignature {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AttestorSignature")
+            .field("attestor", &self.attestor)
+            .field("signature", &"<redacted>")
+            .finish()
+    }
+}
+
+impl CrossChain {
+    /// Returns the observation the attestors signed.
+    pub fn observation(&self) -> Observation {
+        Observation {
+            chain: self.chain.clone(),
+            claim: self.claim.clone(),
+            height: self.attestation.height,
+            block_hash: self.attestation.block_hash,
+        }
+    }
+
+    /// Adds the signature of the attestor holding `key` to the attestation,
+    /// replacing any earlier one, after checking that `observation` is of
+    /// this condition's claim and at the same block as the signatures
+    /// already collected.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the observation is of another claim or block, or
+    /// `key` is not an attestor's.
+    pub fn attest(&mut self, observation: &Observation, key: &SigningKey) -> Result<(), Error> {
+        if observation.chain != self.chain || observation.claim != self.claim {
+            return Err(Error::ClaimMismatch);
+        }
+        let attestation = &mut self.attestation;
+        if !attestation.signatures.is_empty()
+            && (attestation.height, attestation.block_hash)
+                != (observation.height, observation.block_hash)
+        {
+            return Err(Error::BlockMismatch {
+                attested: attestation.height,
+                observed: observation.height,
+            });
+        }
+        let public_key = key.verifying_key().to_bytes();
+        let attestor = self
+            .attestors
+            .iter()
+            .position(|attestor| *attestor == public_key)
+            .ok_or(Error::UnknownAttestor)? as u32;
+
+        attestation.height = observation.height;
+        attestation.block_hash = observation.block_hash;
+        attestation.signatures.retain(|s| s.attestor != attestor);
+        attestation.signatures.push(AttestorSignature {
+            attestor,
+            signature: observation.sign(key),
+        });
+        Ok(())
+    }
+
+    /// Verifies that at least `quorum` distinct attestors signed the
+    /// observation of the claim.
+    pub fn verify(&self) -> Result<(), Error> {
+        if self.quorum == 0 || self.quorum > self.attestors.len() {
+            return Err(Error::InvalidQuorum {
+                quorum: self.quorum,
+                attestors: self.attestors.len(),
+            });
+        }
+        let digest = self.observation().digest();
+        let mut signed = vec![false; self.attestors.len()];
+        for AttestorSignature {
+            attestor,
+            signature,
+        } in &self.attestation.signatures
+        {
+            let index = *attestor as usize;
+            if signed.get(index) != Some(&false) {
+                continue;
+            }
+            if verify_signature(&self.attestors[index], &digest, signature) {
+                signed[index] = true;
+                if signed.iter().filter(|signed| **signed).count() >= self.quorum {
+                    return Ok(());
+                }
+            }
+        }
+        Err(Error::QuorumNotMet {
+            required: self.quorum,
+            attested: signed.iter().filter(|signed| **signed).count(),
+        })
+    }
+}
+
+/// Whether `signature` is `public_key`'s Ed25519 signature of `digest`.
+fn verify_signature(public_key: &[u8; 32], digest: &[u8; 32], signature: &[u8]) -> bool {
+    let Ok(public_key) = VerifyingKey::from_bytes(public_key) else {
+        return false;
+    };
+    Signature::from_slice(signature).is_ok_and(|sig| public_key.verify(digest, &sig).is_ok())
+}
+
+/// Errors from cross-chain attestation verification.
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// The quorum is zero or exceeds the number of attestors.
+    #[error("quorum of {quorum} is invalid for {attestors} attestors")]
+    InvalidQuorum {
+        /// Required number of attestors.
+        quorum: usize,
+        /// Number of attestors.
+        attestors: usize,
+    },
+
+    /// Fewer than `quorum` distinct attestors signed the observation.
+    #[error("needed signatures of {required} attestors, but only {attested} signed")]
+    QuorumNotMet {
+        /// Required number of attestors.
+        required: usize,
+        /// Number of distinct attestors with valid signatures.
+        attested: usize,
+    },
+
+    /// An observation of another chain or claim was attested.
+    #[error("observation is not of the condition's claim")]
+    ClaimMismatch,
+
+    /// An observation at another block than the collected signatures was
+    /// attested.
+    #[error("observation at block {observed} differs from the one attested at {attested}")]
+    BlockMismatch {
+        /// Block of the collected signatures.
+        attested: u64,
+        /// Block of the new observation.
+        observed: u64,
+    },
+
+    /// The signing key is not one of the attestors'.
+    #[error("key is not one of the attestors'")]
+    UnknownAttestor,
+}
@@ -25,7 +25,7 @@ use serde::{Deserialize, Serialize};
 /// assert!(condition.verify().is_ok());
 /// ```
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+#[derive(Clone, Encode, Decode, PartialEq, Eq)]
 pub struct Ed25519 {
     /// Public key bytes
     #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
@@ -40,6 +40,17 @@ pub struct Ed25519 {
     pub message: Vec<u8>,
 }
 
+// The signature is the fulfillment; keep it out of logs.
+impl std::fmt::Debug for Ed25519 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Ed25519")
+            .field("public_key", &hex::encode(self.public_key))
+            .field("signature", &"<redacted>")
+            .field("message", &hex::encode(&self.message))
+            .finish()
+    }
+}
+
 impl Ed25519 {
     /// Verify that `signature` is a valid Ed25519 signature of `message` under `public_key`.
     pub fn verify(&self) -> Result<(), Error> {
@@ -26,7 +26,7 @@ use crate::serde::utf8_serde;
 /// assert!(condition.verify().is_ok());
 /// ```
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+#[derive(Clone, Encode, Decode, PartialEq, Eq)]
 pub struct Hashlock {
     /// The expected SHA-256 digest of the preimage.
     #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
@@ -37,6 +37,16 @@ pub struct Hashlock {
     pub preimage: Vec<u8>,
 }
 
+// The preimage is the secret being revealed; keep it out of logs.
+impl std::fmt::Debug for Hashlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Hashlock")
+            .field("hash", &hex::encode(self.hash))
+            .field("preimage", &"<redacted>")
+            .finish()
+    }
+}
+
 impl Hashlock {
     /// Verifies that `SHA-256(preimage) == hash` using constant-time comparison.
     ///
@@ -11,7 +11,7 @@ use serde::{Deserialize, Serialize};
 /// Verifies that `signature` is a valid ECDSA signature of `message`
 /// under `public_key`.
 #[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
-#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+#[derive(Clone, Encode, Decode, PartialEq, Eq)]
 pub struct Secp256k1 {
     /// Compressed SEC1-encoded public key bytes.
     #[cfg_attr(feature = "json", serde(with = "hex_serde"))]
@@ -26,6 +26,17 @@ pub struct Secp256k1 {
     pub message: Vec<u8>,
 }
 
+// The signature is the fulfillment; keep it out of logs.
+impl std::fmt::Debug for Secp256k1 {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Secp256k1")
+            .field("public_key", &hex::encode(&self.public_key))
+            .field("signature", &"<redacted>")
+            .field("message", &hex::encode(&self.message))
+            .finish()
+    }
+}
+
 impl Secp256k1 {
     /// Verify that `signature` is a valid Secp256k1 signature of `message` under `public_key`.
     pub fn verify(&self) -> Result<(), Error> {
@@ -33,24 +33,46 @@ impl Threshold {
             .unwrap_or_else(|| self.verify_threshold())
     }
 
-    /// Counts satisfied subconditions and checks against threshold.
+    /// Verifies subconditions, cheapest first, until `threshold` of them
+    /// are satisfied, so signatures beyond the threshold are not verified
+    /// in the zkVM.
     fn verify_threshold(&self) -> Result<(), Error> {
-        let satisfied = self.count_satisfied();
+        let mut satisfied = 0;
+        for condition in self.cheapest_first() {
+            if condition.verify().is_ok() {
+                satisfied += 1;
+                if satisfied >= self.threshold {
+                    return Ok(());
+                }
+            }
+        }
+        Err(Error::ThresholdNotMet {
+            required: self.threshold,
+            satisfied,
+        })
+    }
 
-        (satisfied >= self.threshold)
-            .then_some(())
-            .ok_or(Error::ThresholdNotMet {
-                required: self.threshold,
-                satisfied,
-            })
+    /// Subconditions ordered by their typical cost to verify in the zkVM.
+    fn cheapest_first(&self) -> Vec<&Condition> {
+        let mut subconditions: Vec<_> = self.subconditions.iter().collect();
+        subconditions.sort_by_key(|condition| cost(condition));
+        subconditions
     }
+}
 
-    /// Counts the number of subconditions that verify successfully.
-    fn count_satisfied(&self) -> usize {
-        self.subconditions
-            .iter()
-            .filter_map(|c| c.verify().ok())
-            .count()
+/// Relative cost of verifying `condition` in the zkVM: hashing is a single
+/// accelerated SHA-256, secp256k1 verification runs on accelerated
+/// big-integer arithmetic, ed25519 also hashes with unaccelerated SHA-512,
+/// cross-chain attestations take a quorum of ed25519 signatures, bridge
+/// messages a quorum of secp256k1 signatures, and nested thresholds may hold
+/// any number of signatures.
+fn cost(condition: &Condition) -> u8 {
+    match condition {
+        Condition::Hashlock(_) => 0,
+        Condition::Secp256k1(_) => 1,
+        Condition::Ed25519(_) => 2,
+        Condition::CrossChain(_) | Condition::Bridge(_) => 3,
+        Condition::Threshold(_) => 4,
     }
 }
 
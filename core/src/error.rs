@@ -47,6 +47,19 @@ pub enum EscrowError {
     /// The specified blockchain network is not supported.
     #[error("unsupported chain specified")]
     UnsupportedChain,
+
+    /// The escrow has conditions but none were supplied.
+    #[error("escrow has conditions but no condition was supplied")]
+    MissingCondition,
+
+    /// The escrow memo exceeds the maximum allowed length.
+    #[error("memo length {len} exceeds maximum of {max} bytes")]
+    MemoTooLong {
+        /// Memo length in bytes
+        len: usize,
+        /// Max allowed
+        max: usize,
+    },
 }
 
 /// Errors related to cryptographic condition verification.
@@ -68,6 +81,14 @@ pub enum ConditionError {
     /// Threshold error
     #[error("threshold check failed: {0}")]
     Threshold(#[from] crate::condition::threshold::Error),
+
+    /// Cross-chain error
+    #[error("cross-chain attestation failed: {0}")]
+    CrossChain(#[from] crate::condition::cross_chain::Error),
+
+    /// Bridge message error
+    #[error("bridge message failed: {0}")]
+    Bridge(#[from] crate::condition::bridge::Error),
 }
 
 /// Errors related to identity parsing and validation.
@@ -99,7 +120,20 @@ pub enum IdentityError {
     #[error("Base64 decoding error: {0}")]
     Base64(#[from] base64::DecodeError),
 
-    /// The input string did not match any supported identity format (hex, Base58, Base64).
+    /// Error decoding a Bech32-encoded identity.
+    #[error("Bech32 decoding error: {0}")]
+    Bech32(#[from] bech32::DecodeError),
+
+    /// Error decoding an SS58-encoded identity.
+    #[error("SS58 decoding error: {0}")]
+    Ss58(&'static str),
+
+    /// A Hedera entity ID was not of the form `shard.realm.num`.
+    #[error("invalid Hedera entity ID")]
+    Hedera,
+
+    /// The input string did not match any supported identity format (hex,
+    /// Hedera, Bech32, SS58, Base58, Base64).
     #[error("unsupported identity format")]
     UnsupportedFormat,
 }
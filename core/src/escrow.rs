@@ -7,11 +7,11 @@
 use bincode::{Decode, Encode};
 #[cfg(feature = "json")]
 use {
-    crate::interface::{EscrowMetadata, ESCROW_CONDITIONS_PATH},
     serde::{Deserialize, Serialize},
     serde_json,
 };
 
+use crate::interface::EscrowMetadata;
 use crate::{Asset, Condition, EscrowError, ExecutionState, Party, Result};
 
 /// Full escrow context, representing the locked asset, participants, and settlement rules.
@@ -71,9 +71,11 @@ impl Escrow {
             })
     }
 
-    /// Verifies that the escrow is in the `Funded` state.
+    /// Verifies that the escrow is funded, possibly by a creation that is
+    /// not final yet.
     fn validate_state(&self) -> Result<()> {
-        (self.state == ExecutionState::Funded)
+        self.state
+            .is_funded()
             .then_some(())
             .ok_or(EscrowError::InvalidState)
     }
@@ -85,27 +87,32 @@ impl Escrow {
             .and_then(|_| self.recipient.verify_identity())
     }
 
+    /// Returns the [fingerprint](Condition::fingerprint) of the escrow's
+    /// condition, or zeros if it has none.
+    pub fn condition_fingerprint(&self) -> [u8; 32] {
+        self.condition
+            .as_ref()
+            .map_or([0; 32], Condition::fingerprint)
+    }
+
     /// Verifies cryptographic conditions if present.
     fn verify_conditions(&self) -> Result<()> {
         self.condition.as_ref().map_or(Ok(()), |cond| cond.verify())
     }
 
-    /// Constructs an `Escrow` from on-chain metadata and, if required,
-    /// loads the cryptographic condition from a JSON file.
+    /// Constructs an `Escrow` from on-chain metadata and the cryptographic
+    /// condition it was created with.
     ///
-    /// If `metadata.has_conditions` is `true`, reads the file at
-    /// `ESCROW_CONDITIONS_PATH` and parses it as a `Condition`.
+    /// `condition` is ignored unless `metadata.params.has_conditions` is
+    /// `true`.
     ///
     /// # Errors
     ///
-    /// - I/O error when reading the condition file.
-    /// - JSON parsing error when decoding the condition.
-    #[cfg(feature = "json")]
-    pub fn from_metadata(metadata: EscrowMetadata) -> Result<Self> {
+    /// Returns [`EscrowError::MissingCondition`] if the escrow has
+    /// conditions but `condition` is `None`.
+    pub fn from_metadata(metadata: EscrowMetadata, condition: Option<Condition>) -> Result<Self> {
         let condition = if metadata.params.has_conditions {
-            let content = std::fs::read_to_string(ESCROW_CONDITIONS_PATH)?;
-            let cond: Condition = serde_json::from_str(&content)?;
-            Some(cond)
+            Some(condition.ok_or(EscrowError::MissingCondition)?)
         } else {
             None
         };
@@ -122,9 +129,14 @@ impl Escrow {
 
 #[cfg(feature = "json")]
 impl std::fmt::Display for Escrow {
-    /// Compact JSON representation of the `Escrow` for logging.
+    /// Compact JSON representation of the `Escrow` for logging, without the
+    /// condition's fulfillment.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let json = serde_json::to_string(self).map_err(|_| std::fmt::Error)?;
+        let redacted = Self {
+            condition: self.condition.as_ref().map(Condition::redacted),
+            ..self.clone()
+        };
+        let json = serde_json::to_string(&redacted).map_err(|_| std::fmt::Error)?;
         write!(f, "{json}")
     }
 }
@@ -134,7 +146,8 @@ mod tests {
     use sha2::{Digest as _, Sha256};
 
     use super::*;
-    use crate::{BigNumber, ID};
+    use crate::interface::{ChainConfig, EscrowParams};
+    use crate::{BigNumber, Chain, ID};
 
     fn valid_sender() -> Party {
         Party::new("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045").unwrap()
@@ -229,4 +242,62 @@ mod tests {
         let escrow = Escrow::new(valid_sender(), valid_recipient(), valid_asset(), None);
         assert_eq!(escrow.state, ExecutionState::Initialized);
     }
+
+    fn metadata(has_conditions: bool) -> EscrowMetadata {
+        EscrowMetadata {
+            params: EscrowParams {
+                chain_config: ChainConfig {
+                    chain: Chain::Ethereum,
+                    rpc_url: "http://localhost:8545".into(),
+                    fallback_rpc_urls: Vec::new(),
+                    sender_private_id: "0x01".into(),
+                    agent_id: "0x02".into(),
+                    pubsub_url: None,
+                    network: None,
+                    smart_account: None,
+                    cosmos: None,
+                    substrate: None,
+                    cardano: None,
+                    tron: None,
+                    zksync: None,
+                    hedera: None,
+                    custom_chain: None,
+                },
+                asset: valid_asset(),
+                sender: valid_sender(),
+                recipient: valid_recipient(),
+                finish_after: None,
+                cancel_after: None,
+                has_conditions,
+                memo: None,
+                rent_destination: None,
+                recipient_contact: None,
+            },
+            state: ExecutionState::Funded,
+            escrow_id: Some(1),
+            uid: None,
+            chain_metadata: None,
+            guest: None,
+            observed: None,
+        }
+    }
+
+    #[test]
+    fn from_metadata_with_condition() {
+        let escrow = Escrow::from_metadata(metadata(true), Some(valid_condition())).unwrap();
+        assert!(escrow.condition.is_some());
+        assert_eq!(escrow.state, ExecutionState::Funded);
+    }
+
+    #[test]
+    fn from_metadata_missing_condition() {
+        let err = Escrow::from_metadata(metadata(true), None).unwrap_err();
+        assert!(matches!(err, EscrowError::MissingCondition));
+    }
+
+    #[test]
+    fn from_metadata_ignores_unused_condition() {
+        let escrow = Escrow::from_metadata(metadata(false), Some(valid_condition())).unwrap();
+        assert!(escrow.condition.is_none());
+    }
 }
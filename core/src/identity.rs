@@ -2,17 +2,23 @@
 //!
 //! Supports multiple encoding formats:
 //! - Hexadecimal (with optional `0x` prefix)
-//! - Base58 (used by Solana)
+//! - Base58 (used by Solana, and in base58check form by Tron, e.g. `T...`)
+//! - Bech32 (used by Cosmos chains, e.g. `osmo1...`)
+//! - SS58 (used by Substrate chains, e.g. `5Grw...` or `1FRM...`)
+//! - Hedera entity IDs (`shard.realm.num`, e.g. `0.0.1234`)
 //! - Base64 (standard encoding)
 //! - Raw bytes
 //!
 //! Automatic format detection is performed during parsing.
+//!
+//! Escrows themselves are identified across chains by an [`EscrowId`].
 
 use std::str::FromStr;
 
 use base64::prelude::*;
 use base64::Engine;
 use bincode::{Decode, Encode};
+use blake2::{Blake2b512, Digest};
 #[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
 
@@ -23,6 +29,24 @@ use crate::{EscrowError, Result};
 /// Prevents arbitrarily‐long Base58/hex/base64 blobs.
 const MAX_ID_LEN: usize = 256;
 
+/// Context prepended to an SS58 payload when hashing its checksum.
+const SS58_CONTEXT: &[u8] = b"SS58PRE";
+
+/// Length of the checksum of SS58 addresses of 32- and 33-byte accounts.
+const SS58_CHECKSUM_LEN: usize = 2;
+
+/// Leading byte of Tron account addresses.
+const TRON_PREFIX: u8 = 0x41;
+
+/// Length of the checksum appended to base58check payloads.
+const BASE58CHECK_CHECKSUM_LEN: usize = 4;
+
+/// Length of the EVM address of a Hedera entity.
+const HEDERA_ADDRESS_LEN: usize = 20;
+
+/// Domain separator of the digests escrow IDs are.
+const ESCROW_ID_DOMAIN: &[u8] = b"zescrow/escrow-id/v1";
+
 /// A participant in the escrow protocol, wrapping a chain-agnostic `ID`.
 ///
 /// A `Party` represents an on-chain account or public-key identity.  
@@ -49,6 +73,13 @@ pub enum ID {
     /// Raw bytes.
     #[cfg_attr(feature = "json", serde(with = "serde_bytes"))]
     Bytes(Vec<u8>),
+    /// Bech32-encoded string with its human-readable prefix.
+    Bech32(String),
+    /// SS58-encoded account with its network prefix.
+    Ss58(String),
+    /// Hedera entity ID (`shard.realm.num`) of an account, contract, or
+    /// HTS token.
+    Hedera(String),
 }
 
 impl Party {
@@ -97,6 +128,13 @@ impl FromStr for Party {
     }
 }
 
+impl From<ID> for Party {
+    /// Wraps an identity whose encoding is already known.
+    fn from(identity: ID) -> Self {
+        Self { identity }
+    }
+}
+
 impl std::fmt::Display for Party {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(f, "{}", self.identity)
@@ -108,6 +146,9 @@ impl ID {
     const BASE58: &'static str = "base58";
     const BASE64: &'static str = "base64";
     const BYTES: &'static str = "bytes";
+    const BECH32: &'static str = "bech32";
+    const SS58: &'static str = "ss58";
+    const HEDERA: &'static str = "hedera";
 
     /// Verifies that self can be decoded into raw bytes, and that it's not empty.
     ///
@@ -129,6 +170,10 @@ impl ID {
     /// - **Base58**: decodes the Base58 string into bytes.
     /// - **Base64**: decodes the Base64 string into bytes.
     /// - **Bytes**: clones and returns the inner `Vec<u8>`.
+    /// - **Bech32**: decodes the data part, without the prefix, into bytes.
+    /// - **Ss58**: decodes the account, without the network prefix and
+    ///   checksum, into bytes.
+    /// - **Hedera**: encodes the entity's 20-byte long-zero EVM address.
     ///
     /// # Errors
     ///
@@ -142,10 +187,88 @@ impl ID {
             Self::Base58(s) => bs58::decode(s).into_vec().map_err(IdentityError::Base58),
             Self::Base64(s) => BASE64_STANDARD.decode(s).map_err(IdentityError::Base64),
             Self::Bytes(b) => Ok(b.clone()),
+            Self::Bech32(s) => bech32::decode(s)
+                .map(|(_, bytes)| bytes)
+                .map_err(IdentityError::Bech32),
+            Self::Ss58(s) => Self::decode_ss58(s).map(|(_, account)| account),
+            Self::Hedera(s) => parse_hedera(s)
+                .map(|(shard, realm, num)| hedera_address(shard, realm, num).to_vec())
+                .ok_or(IdentityError::Hedera),
         }?;
         Ok(decoded)
     }
 
+    /// Encodes `account` as the SS58 address of the network with `prefix`
+    /// (e.g. 0 for Polkadot, 42 for generic Substrate chains).
+    ///
+    /// Prefixes are 14 bits; higher bits are ignored.
+    pub fn ss58(account: &[u8], prefix: u16) -> Self {
+        let prefix = prefix & 0b0011_1111_1111_1111;
+        let mut payload = match prefix {
+            0..=63 => vec![prefix as u8],
+            _ => vec![
+                ((prefix & 0b1111_1100) >> 2) as u8 | 0b0100_0000,
+                (prefix >> 8) as u8 | ((prefix & 0b11) << 6) as u8,
+            ],
+        };
+        payload.extend_from_slice(account);
+        let checksum = ss58_checksum(&payload);
+        payload.extend_from_slice(&checksum[..SS58_CHECKSUM_LEN]);
+        Self::Ss58(bs58::encode(payload).into_string())
+    }
+
+    /// Returns the network prefix of an SS58 identity, or `None` for other
+    /// encodings or a malformed address.
+    pub fn ss58_prefix(&self) -> Option<u16> {
+        match self {
+            Self::Ss58(s) => Self::decode_ss58(s).ok().map(|(prefix, _)| prefix),
+            _ => None,
+        }
+    }
+
+    /// Encodes the 20-byte EVM `account` as the base58check address of a
+    /// Tron account (`T...`).
+    pub fn tron(account: [u8; 20]) -> Self {
+        let mut payload = vec![TRON_PREFIX];
+        payload.extend_from_slice(&account);
+        let checksum = base58check_checksum(&payload);
+        payload.extend_from_slice(&checksum[..BASE58CHECK_CHECKSUM_LEN]);
+        Self::Base58(bs58::encode(payload).into_string())
+    }
+
+    /// Returns the 20-byte EVM account of a Tron address, as the TVM sees
+    /// it, or `None` for other identities or a malformed address.
+    pub fn tron_account(&self) -> Option<[u8; 20]> {
+        let Self::Base58(s) = self else {
+            return None;
+        };
+        let decoded = bs58::decode(s).into_vec().ok()?;
+        let (payload, checksum) = decoded.split_at_checked(21)?;
+        let (&prefix, account) = payload.split_first()?;
+        (prefix == TRON_PREFIX
+            && checksum == &base58check_checksum(payload)[..BASE58CHECK_CHECKSUM_LEN])
+            .then(|| account.try_into().ok())
+            .flatten()
+    }
+
+    /// Returns the Hedera entity `shard.realm.num`.
+    pub fn hedera(shard: u32, realm: u64, num: u64) -> Self {
+        Self::Hedera(format!("{shard}.{realm}.{num}"))
+    }
+
+    /// Returns the long-zero EVM address of a Hedera entity, which the
+    /// network accepts for any account, contract, or token, or `None` for
+    /// other identities or a malformed entity ID.
+    ///
+    /// Accounts with an ECDSA key usually call contracts from their EVM
+    /// address alias instead, which the mirror node resolves.
+    pub fn hedera_address(&self) -> Option<[u8; 20]> {
+        let Self::Hedera(s) = self else {
+            return None;
+        };
+        parse_hedera(s).map(|(shard, realm, num)| hedera_address(shard, realm, num))
+    }
+
     /// Returns the hex string representation of the identity.
     ///
     /// # Errors
@@ -175,6 +298,9 @@ impl ID {
             Self::Base58(_) => Self::BASE58,
             Self::Base64(_) => Self::BASE64,
             Self::Bytes(_) => Self::BYTES,
+            Self::Bech32(_) => Self::BECH32,
+            Self::Ss58(_) => Self::SS58,
+            Self::Hedera(_) => Self::HEDERA,
         }
     }
 }
@@ -186,12 +312,18 @@ impl std::fmt::Display for ID {
     /// - **Base58**: canonical Base58 string.
     /// - **Base64**: standard Base64 string.
     /// - **Bytes**: standard Base64 string of bytes.
+    /// - **Bech32**: lowercase Bech32 string.
+    /// - **Ss58**: SS58 string.
+    /// - **Hedera**: `shard.realm.num` entity ID.
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             Self::Hex(s) => write!(f, "{s}"),
             Self::Base58(s) => write!(f, "{s}"),
             Self::Base64(s) => write!(f, "{s}"),
             Self::Bytes(b) => write!(f, "{}", BASE64_STANDARD.encode(b)),
+            Self::Bech32(s) => write!(f, "{s}"),
+            Self::Ss58(s) => write!(f, "{s}"),
+            Self::Hedera(s) => write!(f, "{s}"),
         }
     }
 }
@@ -205,6 +337,9 @@ impl FromStr for ID {
         Self::ensure_non_empty(raw)?;
 
         Self::try_decode_hex(raw)
+            .or_else(|| Self::try_decode_hedera(raw))
+            .or_else(|| Self::try_decode_bech32(raw))
+            .or_else(|| Self::try_decode_ss58(raw))
             .or_else(|| Self::try_decode_base58(raw))
             .or_else(|| Self::try_decode_base64(raw))
             .ok_or_else(|| IdentityError::UnsupportedFormat.into())
@@ -246,6 +381,52 @@ impl ID {
             .map(|bytes| Self::Hex(hex::encode(bytes)))
     }
 
+    /// Attempts to parse a Hedera entity ID into an `ID::Hedera`.
+    fn try_decode_hedera(s: &str) -> Option<Self> {
+        parse_hedera(s).map(|(shard, realm, num)| Self::hedera(shard, realm, num))
+    }
+
+    /// Attempts to decode a Bech32 string into an `ID::Bech32`.
+    ///
+    /// Tried before Base58, whose alphabet covers most Bech32 addresses.
+    fn try_decode_bech32(s: &str) -> Option<Self> {
+        bech32::decode(s)
+            .ok()
+            .map(|_| Self::Bech32(s.to_ascii_lowercase()))
+    }
+
+    /// Attempts to decode an SS58 address into an `ID::Ss58`.
+    ///
+    /// Tried before Base58, which SS58 addresses are, with a network prefix
+    /// and checksum the decoding verifies.
+    fn try_decode_ss58(s: &str) -> Option<Self> {
+        Self::decode_ss58(s).ok().map(|_| Self::Ss58(s.to_owned()))
+    }
+
+    /// Decodes an SS58 address of a 32- or 33-byte account into its network
+    /// prefix and account, verifying the checksum.
+    fn decode_ss58(s: &str) -> std::result::Result<(u16, Vec<u8>), IdentityError> {
+        let data = bs58::decode(s).into_vec()?;
+        let (prefix_len, prefix) = match data.first() {
+            Some(&byte @ 0..=63) => (1, u16::from(byte)),
+            Some(&byte @ 64..=127) if data.len() > 1 => {
+                let lower = (byte << 2) | (data[1] >> 6);
+                let upper = data[1] & 0b0011_1111;
+                (2, u16::from(lower) | (u16::from(upper) << 8))
+            }
+            _ => return Err(IdentityError::Ss58("invalid network prefix")),
+        };
+        let account_len = data.len().saturating_sub(prefix_len + SS58_CHECKSUM_LEN);
+        if !matches!(account_len, 32 | 33) {
+            return Err(IdentityError::Ss58("invalid account length"));
+        }
+        let (payload, checksum) = data.split_at(prefix_len + account_len);
+        if ss58_checksum(payload)[..SS58_CHECKSUM_LEN] != *checksum {
+            return Err(IdentityError::Ss58("invalid checksum"));
+        }
+        Ok((prefix, payload[prefix_len..].to_vec()))
+    }
+
     /// Attempts to decode a Base58 string into an `ID::Base58`.
     fn try_decode_base58(s: &str) -> Option<Self> {
         bs58::decode(s)
@@ -263,6 +444,46 @@ impl ID {
     }
 }
 
+/// Hashes an SS58 payload (network prefix and account) for its checksum.
+fn ss58_checksum(payload: &[u8]) -> [u8; 64] {
+    Blake2b512::new()
+        .chain_update(SS58_CONTEXT)
+        .chain_update(payload)
+        .finalize()
+        .into()
+}
+
+/// Parses a Hedera entity ID `shard.realm.num` into its parts.
+fn parse_hedera(s: &str) -> Option<(u32, u64, u64)> {
+    let mut parts = s.split('.');
+    let mut part = || {
+        parts
+            .next()
+            .filter(|part| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit()))
+    };
+    let entity = (
+        part()?.parse().ok()?,
+        part()?.parse().ok()?,
+        part()?.parse().ok()?,
+    );
+    parts.next().is_none().then_some(entity)
+}
+
+/// Encodes a Hedera entity as its long-zero EVM address: the shard, realm,
+/// and entity number as 4, 8, and 8 big-endian bytes.
+fn hedera_address(shard: u32, realm: u64, num: u64) -> [u8; HEDERA_ADDRESS_LEN] {
+    let mut address = [0; HEDERA_ADDRESS_LEN];
+    address[..4].copy_from_slice(&shard.to_be_bytes());
+    address[4..12].copy_from_slice(&realm.to_be_bytes());
+    address[12..].copy_from_slice(&num.to_be_bytes());
+    address
+}
+
+/// Hashes a base58check payload twice with SHA-256 for its checksum.
+fn base58check_checksum(payload: &[u8]) -> [u8; 32] {
+    sha2::Sha256::digest(sha2::Sha256::digest(payload)).into()
+}
+
 impl From<Vec<u8>> for ID {
     fn from(bytes: Vec<u8>) -> Self {
         ID::Bytes(bytes)
@@ -275,11 +496,142 @@ impl From<&[u8]> for ID {
     }
 }
 
+/// Chain-agnostic identifier of an escrow, computed identically by this
+/// crate, the escrow programs and contracts (which emit it when an escrow
+/// is created), and the client, so references to an escrow across systems
+/// need not depend on chain-specific addresses.
+///
+/// It is the SHA-256 digest of the domain separator `zescrow/escrow-id/v1`
+/// followed by the SHA-256 digests of the escrow's
+/// - chain: its CAIP-2 ID, e.g. `eip155:1` or `cosmos:osmosis-1`,
+/// - program or contract: its raw address,
+/// - sender and recipient: their raw addresses,
+/// - nonce: what makes the escrow unique among the program's escrows
+///   between the parties, e.g. its escrow ID as a [word](Self::word),
+/// - asset: its denomination or token address, empty for the chain's
+///   native coin (except on Cosmos, where escrows hold a denomination),
+///
+/// and then its amount, as a 32-byte big-endian word.
+///
+/// Raw addresses are 20 bytes on EVM chains (including Tron's TVM and
+/// Hedera), Bech32 data on Cosmos and Cardano, and 32-byte accounts on
+/// Solana and Substrate. Programs that cannot read their chain's CAIP-2 ID
+/// (on Solana and ink! chains) take its digest as an argument when an
+/// escrow is created; their nonce is the creation slot on Solana.
+///
+/// # Examples
+///
+/// ```
+/// # use zescrow_core::EscrowId;
+///
+/// let id = EscrowId::new(
+///     "eip155:1",
+///     &[0x11; 20],
+///     &[0x22; 20],
+///     &[0x33; 20],
+///     &EscrowId::word(1u64),
+///     &[],
+///     1000,
+/// );
+/// assert_eq!(id.to_string().parse::<EscrowId>().unwrap(), id);
+/// ```
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(transparent))]
+#[derive(Clone, Copy, Encode, Decode, PartialEq, Eq, Hash)]
+pub struct EscrowId(#[cfg_attr(feature = "json", serde(with = "hex::serde"))] pub [u8; 32]);
+
+impl EscrowId {
+    /// Computes the ID of an escrow from its parts, as described on
+    /// [`EscrowId`].
+    pub fn new(
+        chain: &str,
+        contract: &[u8],
+        sender: &[u8],
+        recipient: &[u8],
+        nonce: &[u8],
+        asset: &[u8],
+        amount: u128,
+    ) -> Self {
+        let mut hasher = sha2::Sha256::new();
+        hasher.update(ESCROW_ID_DOMAIN);
+        for part in [chain.as_bytes(), contract, sender, recipient, nonce, asset] {
+            hasher.update(sha2::Sha256::digest(part));
+        }
+        hasher.update(Self::word(amount));
+        Self(hasher.finalize().into())
+    }
+
+    /// Encodes `value` (e.g. an escrow ID or slot used as a nonce) as the
+    /// 32-byte big-endian word contracts hash it as.
+    pub fn word(value: impl Into<u128>) -> [u8; 32] {
+        let mut word = [0; 32];
+        word[16..].copy_from_slice(&value.into().to_be_bytes());
+        word
+    }
+}
+
+impl std::fmt::Display for EscrowId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+impl std::fmt::Debug for EscrowId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "EscrowId({self})")
+    }
+}
+
+impl FromStr for EscrowId {
+    type Err = EscrowError;
+
+    /// Parses an ID from hex, with or without a `0x` prefix.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let mut id = [0; 32];
+        hex::decode_to_slice(s.strip_prefix("0x").unwrap_or(s), &mut id)
+            .map_err(IdentityError::Hex)?;
+        Ok(Self(id))
+    }
+}
+
 #[cfg(test)]
 mod tests {
 
     use super::*;
 
+    #[test]
+    fn escrow_id_vectors() {
+        // Shared with the escrow programs' tests.
+        let ethereum = EscrowId::new(
+            "eip155:1",
+            &[0x11; 20],
+            &[0x22; 20],
+            &[0x33; 20],
+            &EscrowId::word(1u64),
+            &[],
+            1000,
+        );
+        assert_eq!(
+            ethereum.to_string(),
+            "78ee10cb82fb3c22509bb72ce059b7ff8df6e5d5e4dc93d9acb25415bf1ad784"
+        );
+        let cosmos = EscrowId::new(
+            "cosmos:osmosis-1",
+            &[0x11; 32],
+            &[0x22; 32],
+            &[0x33; 32],
+            &EscrowId::word(1u64),
+            b"uosmo",
+            1000,
+        );
+        assert_eq!(
+            cosmos.to_string(),
+            "d0ecd93ee3ba65f1115f334119ba577dd1cd10e1e2b66909d08b9f5e0605106b"
+        );
+        assert_eq!(format!("0x{cosmos}").parse::<EscrowId>().unwrap(), cosmos);
+        assert!("abcd".parse::<EscrowId>().is_err());
+    }
+
     #[test]
     fn hex_identity() {
         let id_str = "deadbeef";
@@ -290,6 +642,14 @@ mod tests {
         assert_eq!(id.encoding(), "hex");
     }
 
+    #[test]
+    fn party_from_id_keeps_encoding() {
+        let party = Party::from(ID::Base58("abc".into()));
+        assert_eq!(party.to_string(), "abc");
+        assert_eq!(party, Party::from(ID::Base58("abc".into())));
+        assert_ne!(party, Party::from(ID::Hex("abc".into())));
+    }
+
     #[test]
     fn hex_with_prefix() {
         let id_str = "0XDEADBEEF";
@@ -307,6 +667,79 @@ mod tests {
         assert_eq!(id.encoding(), "base58");
     }
 
+    #[test]
+    fn bech32_identity() {
+        let address = "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu";
+        let id = ID::from_str(address).unwrap();
+        assert_eq!(id, ID::Bech32(address.into()));
+        assert_eq!(id.to_bytes().unwrap(), (1..=20).collect::<Vec<u8>>());
+        assert_eq!(id.to_string(), address);
+        assert_eq!(id.encoding(), "bech32");
+        assert_eq!(
+            ID::from_str(&address.to_uppercase()).unwrap(),
+            ID::Bech32(address.into())
+        );
+    }
+
+    #[test]
+    fn tron_identity() {
+        let account: [u8; 20] = hex::decode("5cbdd86a2fa8dc4bddd8a8f69dba48572eec07fb")
+            .unwrap()
+            .try_into()
+            .unwrap();
+        let address = "TJRabPrwbZy45sbavfcjinPJC18kjpRTv8";
+        assert_eq!(ID::tron(account), ID::Base58(address.into()));
+        assert_eq!(ID::from_str(address).unwrap().tron_account(), Some(account));
+
+        let mut corrupted = address.to_owned();
+        corrupted.replace_range(33.., "9");
+        assert_eq!(ID::Base58(corrupted).tron_account(), None);
+        assert_eq!(ID::Hex(hex::encode(account)).tron_account(), None);
+    }
+
+    #[test]
+    fn hedera_identity() {
+        let id = ID::from_str("0.0.1234").unwrap();
+        assert_eq!(id, ID::hedera(0, 0, 1234));
+        assert_eq!(id.to_string(), "0.0.1234");
+        assert_eq!(id.encoding(), "hedera");
+        assert_eq!(
+            id.to_hex().unwrap(),
+            "00000000000000000000000000000000000004d2"
+        );
+        assert_eq!(
+            id.hedera_address().map(hex::encode),
+            Some(id.to_hex().unwrap())
+        );
+
+        assert!(ID::from_str("0.0").is_err());
+        assert!(ID::Hedera("0.0.x".into()).to_bytes().is_err());
+        assert_eq!(ID::Hex("04d2".into()).hedera_address(), None);
+    }
+
+    #[test]
+    fn ss58_identity() {
+        let alice = hex::decode("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d")
+            .unwrap();
+        let address = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY";
+        let id = ID::from_str(address).unwrap();
+        assert_eq!(id, ID::Ss58(address.into()));
+        assert_eq!(id.to_bytes().unwrap(), alice);
+        assert_eq!(id.ss58_prefix(), Some(42));
+        assert_eq!(id.encoding(), "ss58");
+        assert_eq!(ID::ss58(&alice, 42), id);
+        assert_eq!(
+            ID::ss58(&alice, 0).to_string(),
+            "15oF4uVJwmo4TdGW7VfQxNLavjCXviqxT9S1MgbjMNHr6Sp5"
+        );
+        assert_eq!(ID::ss58(&alice, 1284).ss58_prefix(), Some(1284));
+
+        // Without a valid checksum, the address is plain Base58.
+        let corrupted = "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQZ";
+        assert_eq!(ID::from_str(corrupted).unwrap().encoding(), "base58");
+        assert!(ID::Ss58(corrupted.into()).to_bytes().is_err());
+    }
+
     #[test]
     fn base64_identity() {
         let raw = vec![1, 2, 3, 4];
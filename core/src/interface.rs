@@ -9,17 +9,22 @@ use std::borrow::Cow;
 #[cfg(feature = "json")]
 use std::fs::File;
 #[cfg(feature = "json")]
-use std::path::Path;
+use std::path::{Path, PathBuf};
 
 #[cfg(feature = "json")]
 use anyhow::Context;
-use bincode::{Decode, Encode};
+use bincode::config::standard;
+use bincode::de::{BorrowDecoder, Decoder};
+use bincode::enc::Encoder;
+use bincode::error::{DecodeError, EncodeError};
+use bincode::{BorrowDecode, Decode, Encode};
 #[cfg(feature = "json")]
 use serde::de::DeserializeOwned;
 #[cfg(feature = "json")]
 use serde::{Deserialize, Serialize};
+use sha2::{Digest as _, Sha256};
 
-use crate::{Asset, EscrowError, Party};
+use crate::{Asset, EscrowError, EscrowId, Party, Result, ID};
 
 /// Default path to escrow parameters configuration.
 pub const ESCROW_PARAMS_PATH: &str =
@@ -37,6 +42,12 @@ pub const ESCROW_CONDITIONS_PATH: &str = concat!(
     "/../deploy/escrow_conditions.json"
 );
 
+/// Default path to proof data (output from prove command).
+pub const PROOF_DATA_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../deploy/proof_data.json");
+
+/// Maximum length in bytes of the optional escrow memo.
+pub const MAX_MEMO_LEN: usize = 64;
+
 /// Expands environment variable references in a string.
 ///
 /// Replaces all occurrences of `${VAR_NAME}` with the corresponding
@@ -164,6 +175,62 @@ pub enum ExecutionState {
     /// Conditions (if any) have been fulfilled;
     /// funds will be released to the recipient if the proof verifies on-chain.
     ConditionsMet,
+
+    /// Funds have been released to the recipient on-chain.
+    Released,
+
+    /// Funds have been refunded to the sender on-chain.
+    Refunded,
+
+    // Pending states go last, keeping the encoding of the others that
+    // journals commit to.
+    /// Escrow creation was included in a block that is not final yet, so a
+    /// reorg may still undo it; see [`EscrowMetadata::observed`].
+    PendingCreate,
+
+    /// Release to the recipient was included in a block that is not final
+    /// yet.
+    PendingFinish,
+
+    /// Refund to the sender was included in a block that is not final yet.
+    PendingCancel,
+}
+
+impl ExecutionState {
+    /// Returns the pending state recording a transaction that moved an
+    /// escrow to `self`, until its block is final, or `None` if no
+    /// transaction moves an escrow to `self`.
+    pub fn pending(self) -> Option<Self> {
+        match self {
+            Self::Funded => Some(Self::PendingCreate),
+            Self::Released => Some(Self::PendingFinish),
+            Self::Refunded => Some(Self::PendingCancel),
+            _ => None,
+        }
+    }
+
+    /// For a pending state, returns the state the escrow reaches once the
+    /// block is final, and the one it falls back to if the block is
+    /// orphaned.
+    pub fn outcomes(self) -> Option<(Self, Self)> {
+        match self {
+            Self::PendingCreate => Some((Self::Funded, Self::Initialized)),
+            Self::PendingFinish => Some((Self::Released, Self::Funded)),
+            Self::PendingCancel => Some((Self::Refunded, Self::Funded)),
+            _ => None,
+        }
+    }
+
+    /// Whether the state awaits the finality of its block.
+    pub fn is_pending(self) -> bool {
+        self.outcomes().is_some()
+    }
+
+    /// Whether funds are held in escrow, possibly by a creation that is not
+    /// final yet.
+    pub fn is_funded(self) -> bool {
+        matches!(self, Self::Funded | Self::PendingCreate)
+    }
 }
 
 /// Result of escrow execution in the `client`.
@@ -174,6 +241,312 @@ pub enum ExecutionResult {
     Ok(ExecutionState),
     /// Unsuccessful escrow execution, with the error message.
     Err(String),
+    /// Happy path, with the [paths](crate::Verification::satisfied_paths)
+    /// of the satisfied subconditions, for the host to apply policies to or
+    /// audit. Committed only when the host asks for it, as the Ethereum
+    /// escrow contract only accepts [`Ok`](Self::Ok) journals.
+    Satisfied {
+        /// State the escrow reached.
+        state: ExecutionState,
+        /// Subcondition indices leading to each satisfied condition that
+        /// is not a threshold.
+        paths: Vec<Vec<u32>>,
+    },
+}
+
+impl ExecutionResult {
+    /// Returns the state the escrow reached, or `None` if execution
+    /// failed.
+    pub fn state(&self) -> Option<ExecutionState> {
+        match self {
+            Self::Ok(state) | Self::Satisfied { state, .. } => Some(*state),
+            Self::Err(_) => None,
+        }
+    }
+
+    /// Returns the paths of the satisfied subconditions, if committed.
+    pub fn satisfied(&self) -> Option<&[Vec<u32>]> {
+        match self {
+            Self::Satisfied { paths, .. } => Some(paths),
+            _ => None,
+        }
+    }
+}
+
+/// Escrow instance a proof is bound to, supplied by the host and committed
+/// to the journal alongside the execution result, so that a receipt for one
+/// escrow cannot finish another, nor be reused once stale.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct EscrowBinding {
+    /// [`EscrowMetadata::fingerprint`] of the escrow.
+    pub fingerprint: [u8; 32],
+    /// On-chain identifier of the escrow: SHA-256 of the contract address
+    /// and escrow ID on Ethereum, which the escrow contract recomputes, or
+    /// on Cosmos and Substrate, or the escrow PDA (the program ID if not
+    /// recorded) on Solana.
+    pub instance: [u8; 32],
+    /// Window in which the proof may be used.
+    pub freshness: Freshness,
+    /// Recent chain state attested by the host, or [`ChainState::NONE`].
+    pub chain_state: ChainState,
+}
+
+impl EscrowBinding {
+    /// Whether both bindings identify the same escrow, whatever their
+    /// freshness.
+    pub fn same_escrow(&self, other: &Self) -> bool {
+        self.fingerprint == other.fingerprint && self.instance == other.instance
+    }
+}
+
+/// Freshness of a proof, supplied by the host: an application-defined
+/// nonce (e.g., a recent blockhash) and the block or slot after which the
+/// proof is stale.
+///
+/// Verifiers reject stale proofs, so a proof cannot be reused indefinitely
+/// after the conditions it attests to no longer hold.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Freshness {
+    /// Application-defined nonce.
+    pub nonce: [u8; 32],
+    /// Last block (Ethereum) or slot (Solana) the proof may be used at.
+    pub expires_at: u64,
+}
+
+impl Freshness {
+    /// Length of the encoded freshness: the nonce and the big-endian
+    /// expiry.
+    pub const ENCODED_LEN: usize = 32 + 8;
+
+    /// Freshness of proofs that never expire.
+    pub const NEVER: Self = Self {
+        nonce: [0; 32],
+        expires_at: u64::MAX,
+    };
+
+    /// Freshness of proofs usable up to and including `expires_at`.
+    pub fn until(expires_at: u64) -> Self {
+        Self {
+            expires_at,
+            ..Self::NEVER
+        }
+    }
+
+    /// Whether the proof is stale at block or slot `height`.
+    pub fn is_expired(&self, height: u64) -> bool {
+        height > self.expires_at
+    }
+
+    /// Encodes the freshness as committed to the journal, and as prefixed
+    /// to seals for the Ethereum escrow contract.
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut bytes = [0; Self::ENCODED_LEN];
+        bytes[..32].copy_from_slice(&self.nonce);
+        bytes[32..].copy_from_slice(&self.expires_at.to_be_bytes());
+        bytes
+    }
+
+    /// Splits encoded freshness off the front of `bytes`, returning it and
+    /// the remaining bytes, or `None` if `bytes` is too short.
+    pub fn split_prefix(bytes: &[u8]) -> Option<(Self, &[u8])> {
+        let (nonce, rest) = bytes.split_first_chunk::<32>()?;
+        let (expires_at, rest) = rest.split_first_chunk::<8>()?;
+        let freshness = Self {
+            nonce: *nonce,
+            expires_at: u64::from_be_bytes(*expires_at),
+        };
+        Some((freshness, rest))
+    }
+}
+
+impl Default for Freshness {
+    fn default() -> Self {
+        Self::NEVER
+    }
+}
+
+// Encoded with a fixed-width expiry, rather than bincode's varint, so the
+// escrow contract can rebuild the journal.
+impl Encode for Freshness {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> std::result::Result<(), EncodeError> {
+        self.to_bytes().encode(encoder)
+    }
+}
+
+impl<Context> Decode<Context> for Freshness {
+    fn decode<D: Decoder>(decoder: &mut D) -> std::result::Result<Self, DecodeError> {
+        let bytes = <[u8; Self::ENCODED_LEN]>::decode(decoder)?;
+        Ok(Self::split_prefix(&bytes)
+            .expect("encoded freshness is complete")
+            .0)
+    }
+}
+
+impl<'de, Context> BorrowDecode<'de, Context> for Freshness {
+    fn borrow_decode<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+    ) -> std::result::Result<Self, DecodeError> {
+        Self::decode(decoder)
+    }
+}
+
+/// Recent chain state fetched and checked by the host: a block (Ethereum)
+/// or slot (Solana) and its hash.
+///
+/// Committed to the journal as an attested input, so time-based and
+/// chain-state conditions can be proven against it; verifiers check that
+/// the block or slot is recent and has this hash (the Ethereum escrow
+/// contract with `blockhash`, which only covers the last 256 blocks).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChainState {
+    /// Block number (Ethereum) or slot (Solana).
+    pub height: u64,
+    /// Hash of the block, or the bank hash of the slot.
+    pub hash: [u8; 32],
+}
+
+impl ChainState {
+    /// Length of the encoded chain state: the big-endian height and the
+    /// hash.
+    pub const ENCODED_LEN: usize = 8 + 32;
+
+    /// No attested chain state.
+    pub const NONE: Self = Self {
+        height: 0,
+        hash: [0; 32],
+    };
+
+    /// Whether no chain state is attested.
+    pub fn is_none(&self) -> bool {
+        *self == Self::NONE
+    }
+
+    /// Encodes the chain state as committed to the journal.
+    pub fn to_bytes(&self) -> [u8; Self::ENCODED_LEN] {
+        let mut bytes = [0; Self::ENCODED_LEN];
+        bytes[..8].copy_from_slice(&self.height.to_be_bytes());
+        bytes[8..].copy_from_slice(&self.hash);
+        bytes
+    }
+
+    /// Splits an encoded chain state off the front of `bytes`, returning it
+    /// and the remaining bytes, or `None` if `bytes` is too short.
+    pub fn split_prefix(bytes: &[u8]) -> Option<(Self, &[u8])> {
+        let (height, rest) = bytes.split_first_chunk::<8>()?;
+        let (hash, rest) = rest.split_first_chunk::<32>()?;
+        let state = Self {
+            height: u64::from_be_bytes(*height),
+            hash: *hash,
+        };
+        Some((state, rest))
+    }
+}
+
+impl Default for ChainState {
+    fn default() -> Self {
+        Self::NONE
+    }
+}
+
+// Encoded with a fixed-width height, like `Freshness`, so the escrow
+// contract can rebuild the journal.
+impl Encode for ChainState {
+    fn encode<E: Encoder>(&self, encoder: &mut E) -> std::result::Result<(), EncodeError> {
+        self.to_bytes().encode(encoder)
+    }
+}
+
+impl<Context> Decode<Context> for ChainState {
+    fn decode<D: Decoder>(decoder: &mut D) -> std::result::Result<Self, DecodeError> {
+        let bytes = <[u8; Self::ENCODED_LEN]>::decode(decoder)?;
+        Ok(Self::split_prefix(&bytes)
+            .expect("encoded chain state is complete")
+            .0)
+    }
+}
+
+impl<'de, Context> BorrowDecode<'de, Context> for ChainState {
+    fn borrow_decode<D: BorrowDecoder<'de>>(
+        decoder: &mut D,
+    ) -> std::result::Result<Self, DecodeError> {
+        Self::decode(decoder)
+    }
+}
+
+/// Journal committed by the guest program.
+///
+/// Only the outcome, the binding, and the condition's
+/// [fingerprint](crate::Condition::fingerprint) are committed; the
+/// condition's keys, messages, and fulfillment stay private.
+///
+/// Fixed-size arrays are encoded without a length prefix, so a
+/// `ConditionsMet` journal is `0x0002 || fingerprint || instance || nonce
+/// || expires_at || state_height || state_hash || condition`, the expiry
+/// and chain state height as 8 big-endian bytes.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct Journal {
+    /// Outcome of executing the escrow.
+    pub result: ExecutionResult,
+    /// Escrow instance the execution is for.
+    pub binding: EscrowBinding,
+    /// Fingerprint of the condition verified, or zeros if none.
+    pub condition: [u8; 32],
+}
+
+impl Journal {
+    /// Length of the prefix of Ethereum seals: the journal's freshness,
+    /// chain state, and condition fingerprint, which the escrow contract
+    /// needs to rebuild the journal.
+    pub const SEAL_PREFIX_LEN: usize = Freshness::ENCODED_LEN + ChainState::ENCODED_LEN + 32;
+
+    /// The journal proving that `condition` (a condition fingerprint) of
+    /// the escrow `binding` identifies is fulfilled.
+    pub fn conditions_met(binding: EscrowBinding, condition: [u8; 32]) -> Self {
+        Self {
+            result: ExecutionResult::Ok(ExecutionState::ConditionsMet),
+            binding,
+            condition,
+        }
+    }
+
+    /// Encodes the journal's freshness, chain state, and condition
+    /// fingerprint, as prefixed to seals for the Ethereum escrow contract.
+    pub fn seal_prefix(&self) -> [u8; Self::SEAL_PREFIX_LEN] {
+        let mut prefix = [0; Self::SEAL_PREFIX_LEN];
+        let (freshness, rest) = prefix.split_at_mut(Freshness::ENCODED_LEN);
+        let (chain_state, condition) = rest.split_at_mut(ChainState::ENCODED_LEN);
+        freshness.copy_from_slice(&self.binding.freshness.to_bytes());
+        chain_state.copy_from_slice(&self.binding.chain_state.to_bytes());
+        condition.copy_from_slice(&self.condition);
+        prefix
+    }
+
+    /// Rebuilds the `ConditionsMet` journal for the escrow `binding`
+    /// identifies from the [seal prefix](Self::seal_prefix) of `seal`,
+    /// returning it and the remaining bytes, or `None` if `seal` is too
+    /// short.
+    pub fn from_seal_prefix(seal: &[u8], mut binding: EscrowBinding) -> Option<(Self, &[u8])> {
+        let (freshness, rest) = Freshness::split_prefix(seal)?;
+        let (chain_state, rest) = ChainState::split_prefix(rest)?;
+        let (condition, rest) = rest.split_first_chunk::<32>()?;
+        binding.freshness = freshness;
+        binding.chain_state = chain_state;
+        Some((Self::conditions_met(binding, *condition), rest))
+    }
+
+    /// Encodes the journal as the guest commits it.
+    pub fn encode(&self) -> Vec<u8> {
+        bincode::encode_to_vec(self, standard()).expect("encoding to a Vec cannot fail")
+    }
+
+    /// Decodes a journal committed by the guest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `bytes` is not an encoded journal.
+    pub fn decode(bytes: &[u8]) -> std::result::Result<Self, bincode::error::DecodeError> {
+        bincode::decode_from_slice(bytes, standard()).map(|(journal, _)| journal)
+    }
 }
 
 /// Metadata returned from on-chain escrow creation.
@@ -186,6 +559,267 @@ pub struct EscrowMetadata {
     pub state: ExecutionState,
     /// Unique identifier for the created escrow.
     pub escrow_id: Option<u64>,
+    /// Chain-agnostic identifier of the escrow, as emitted by the program
+    /// or contract that created it.
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub uid: Option<EscrowId>,
+    /// Chain-specific record of the escrow's creation, if captured.
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub chain_metadata: Option<ChainMetadata>,
+    /// Guest program the escrow's proofs are expected from, pinned when it
+    /// was created by a client built with the prover.
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub guest: Option<GuestPin>,
+    /// Block the transaction behind a [pending](ExecutionState::is_pending)
+    /// state was observed in, checked against the chain until final.
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub observed: Option<ObservedBlock>,
+}
+
+/// A block (or slot) as observed by the client, to tell whether it was
+/// later orphaned by a reorg.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+pub struct ObservedBlock {
+    /// Block number (Ethereum), slot (Solana, Cardano).
+    pub height: u64,
+    /// Hash of the block.
+    #[cfg_attr(feature = "json", serde(with = "hex::serde"))]
+    pub hash: [u8; 32],
+}
+
+/// Guest program pinned in [`EscrowMetadata`] at escrow creation, so
+/// proving or submitting with another one, e.g. after an upgrade changes
+/// the guest, is refused rather than failing on-chain.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct GuestPin {
+    /// Image ID of the guest program (`ZESCROW_GUEST_ID`), as hex.
+    pub image_id: String,
+    /// Version of the prover crate that built the guest.
+    pub version: String,
+}
+
+impl GuestPin {
+    /// Whether `image_id` (hex, with or without a `0x` prefix) is the
+    /// pinned image ID.
+    pub fn matches(&self, image_id: &str) -> bool {
+        self.image_id
+            .trim_start_matches("0x")
+            .eq_ignore_ascii_case(image_id.trim_start_matches("0x"))
+    }
+}
+
+impl EscrowMetadata {
+    /// Returns a SHA-256 digest identifying this escrow instance: its chain,
+    /// program or contract, on-chain ID (or Solana PDA), parties, asset,
+    /// timelocks, and memo.
+    ///
+    /// Local settings (RPC endpoints, keys) and the execution state do not
+    /// contribute, so every party computes the same fingerprint.
+    pub fn fingerprint(&self) -> [u8; 32] {
+        let params = &self.params;
+        // Ethereum and Bech32 addresses are case-insensitive; base58,
+        // base58check, and SS58 are not.
+        let agent_id = match params.chain_config.chain {
+            Chain::Ethereum | Chain::Cosmos | Chain::Cardano | Chain::Hedera => {
+                params.chain_config.agent_id.to_ascii_lowercase()
+            }
+            Chain::Solana | Chain::Substrate | Chain::Tron => params.chain_config.agent_id.clone(),
+        };
+        let pda = match &self.chain_metadata {
+            Some(ChainMetadata::Solana { pda, .. }) => Some(pda.as_str()),
+            // A Cardano escrow is the UTxO locked by its creating transaction,
+            // which the client locks a single escrow in.
+            Some(ChainMetadata::Cardano { tx_hash, .. }) => Some(tx_hash.as_str()),
+            Some(ChainMetadata::Cosmos { .. } | ChainMetadata::Substrate { .. }) | None => None,
+        };
+        let identity = (
+            &params.chain_config.chain,
+            agent_id,
+            &self.escrow_id,
+            pda,
+            &params.sender,
+            &params.recipient,
+            &params.asset,
+            &params.finish_after,
+            &params.cancel_after,
+            &params.has_conditions,
+            &params.memo,
+        );
+        let bytes =
+            bincode::encode_to_vec(identity, standard()).expect("encoding to a Vec cannot fail");
+        Sha256::digest(bytes).into()
+    }
+
+    /// Returns the binding proofs of this escrow commit to, with proofs
+    /// that never expire; set [`EscrowBinding::freshness`] to limit reuse.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`EscrowError::InvalidChainOp`] if an Ethereum, Cosmos,
+    /// Substrate, Tron, or Hedera escrow has no escrow ID, a Cardano escrow
+    /// has no UTxO reference, or the contract, PDA, program, or script address is
+    /// malformed.
+    pub fn binding(&self) -> Result<EscrowBinding> {
+        let chain_config = &self.params.chain_config;
+        let instance = match chain_config.chain {
+            Chain::Ethereum | Chain::Tron | Chain::Hedera => {
+                let id = self.escrow_id.ok_or_else(|| {
+                    EscrowError::InvalidChainOp("escrow ID is required to bind a proof".into())
+                })?;
+                let contract = match chain_config.chain {
+                    // The TVM drops the `0x41` prefix of Tron addresses.
+                    Chain::Tron => ID::Base58(chain_config.agent_id.clone())
+                        .tron_account()
+                        .map(Vec::from),
+                    _ => hex::decode(chain_config.agent_id.trim_start_matches("0x")).ok(),
+                }
+                .filter(|contract| contract.len() == 20)
+                .ok_or_else(|| {
+                    EscrowError::InvalidChainOp(format!(
+                        "invalid escrow contract address: {}",
+                        chain_config.agent_id
+                    ))
+                })?;
+                // `abi.encodePacked(address(this), escrowId)`
+                let mut packed = contract;
+                packed.extend_from_slice(&[0; 24]);
+                packed.extend_from_slice(&id.to_be_bytes());
+                Sha256::digest(packed).into()
+            }
+            Chain::Cosmos => {
+                let id = self.escrow_id.ok_or_else(|| {
+                    EscrowError::InvalidChainOp("escrow ID is required to bind a proof".into())
+                })?;
+                let (_, contract) = bech32::decode(&chain_config.agent_id).map_err(|_| {
+                    EscrowError::InvalidChainOp(format!(
+                        "invalid escrow contract address: {}",
+                        chain_config.agent_id
+                    ))
+                })?;
+                let mut packed = contract;
+                packed.extend_from_slice(&id.to_be_bytes());
+                Sha256::digest(packed).into()
+            }
+            Chain::Substrate => {
+                let id = self.escrow_id.ok_or_else(|| {
+                    EscrowError::InvalidChainOp("escrow ID is required to bind a proof".into())
+                })?;
+                let mut packed =
+                    ID::Ss58(chain_config.agent_id.clone())
+                        .to_bytes()
+                        .map_err(|_| {
+                            EscrowError::InvalidChainOp(format!(
+                                "invalid escrow contract address: {}",
+                                chain_config.agent_id
+                            ))
+                        })?;
+                packed.extend_from_slice(&id.to_be_bytes());
+                Sha256::digest(packed).into()
+            }
+            Chain::Cardano => {
+                let Some(ChainMetadata::Cardano {
+                    tx_hash,
+                    output_index,
+                    ..
+                }) = &self.chain_metadata
+                else {
+                    return Err(EscrowError::InvalidChainOp(
+                        "escrow UTxO is required to bind a proof".into(),
+                    ));
+                };
+                let (_, script) = bech32::decode(&chain_config.agent_id).map_err(|_| {
+                    EscrowError::InvalidChainOp(format!(
+                        "invalid escrow script address: {}",
+                        chain_config.agent_id
+                    ))
+                })?;
+                let tx_hash = hex::decode(tx_hash)
+                    .ok()
+                    .filter(|hash| hash.len() == 32)
+                    .ok_or_else(|| {
+                        EscrowError::InvalidChainOp(format!("invalid transaction hash: {tx_hash}"))
+                    })?;
+                let mut packed = script;
+                packed.extend_from_slice(&tx_hash);
+                packed.extend_from_slice(&output_index.to_be_bytes());
+                Sha256::digest(packed).into()
+            }
+            Chain::Solana => {
+                let address = match &self.chain_metadata {
+                    Some(ChainMetadata::Solana { pda, .. }) => pda,
+                    _ => &chain_config.agent_id,
+                };
+                bs58::decode(address)
+                    .into_vec()
+                    .ok()
+                    .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                    .ok_or_else(|| {
+                        EscrowError::InvalidChainOp(format!("invalid Solana address: {address}"))
+                    })?
+            }
+        };
+        Ok(EscrowBinding {
+            fingerprint: self.fingerprint(),
+            instance,
+            freshness: Freshness::NEVER,
+            chain_state: ChainState::NONE,
+        })
+    }
+}
+
+/// Chain-specific record of an escrow's creation, captured from the
+/// creating transaction.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(tag = "chain", rename_all = "lowercase"))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub enum ChainMetadata {
+    /// Solana escrow account, from the program's `EscrowEvent`.
+    Solana {
+        /// Base58 address of the escrow (or stake escrow) PDA.
+        pda: String,
+        /// Bump seed of the PDA.
+        bump: u8,
+        /// Base58 signature of the creating transaction.
+        signature: String,
+        /// Slot the creating transaction landed in.
+        slot: u64,
+    },
+    /// CosmWasm escrow, from the contract's `wasm` event.
+    Cosmos {
+        /// Hex hash of the creating transaction.
+        tx_hash: String,
+        /// Block height the creating transaction was included in.
+        height: u64,
+    },
+    /// ink! escrow, from the contract's `EscrowUpdated` event.
+    Substrate {
+        /// Hex hash of the creating extrinsic.
+        tx_hash: String,
+        /// Number of the finalized block the extrinsic was included in.
+        block_number: u64,
+    },
+    /// Plutus-script escrow, from the UTxO locked by the creating
+    /// transaction.
+    Cardano {
+        /// Hex hash of the creating transaction.
+        tx_hash: String,
+        /// Index of the escrow output in the creating transaction.
+        output_index: u32,
+    },
 }
 
 /// Parameters required to create an escrow on-chain.
@@ -214,6 +848,52 @@ pub struct EscrowParams {
 
     /// Denotes whether this escrow is subject to cryptographic conditions.
     pub has_conditions: bool,
+
+    /// Optional memo (e.g., invoice ID, order reference, or URI hash) used to
+    /// correlate the escrow with off-chain records. At most [`MAX_MEMO_LEN`] bytes.
+    /// Stored on Solana, Cosmos, and Cardano; other chains reject escrows
+    /// with a memo.
+    pub memo: Option<String>,
+
+    /// Optional party credited with the escrow account's rent when it is
+    /// closed (sender, recipient, or a third party). If `None`, rent goes to
+    /// whoever closes the escrow. Only honoured on Solana.
+    pub rent_destination: Option<Party>,
+
+    /// Address book name the recipient was given as, e.g. `alice` for
+    /// `"@alice"`, kept for reference next to the resolved address.
+    #[cfg_attr(
+        feature = "json",
+        serde(default, skip_serializing_if = "Option::is_none")
+    )]
+    pub recipient_contact: Option<String>,
+}
+
+impl EscrowParams {
+    /// Returns the memo as a zero-padded, fixed-size byte array suitable for
+    /// on-chain storage, or `None` if no memo was specified.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EscrowError::MemoTooLong` if the memo exceeds [`MAX_MEMO_LEN`] bytes.
+    pub fn memo_bytes(&self) -> Result<Option<[u8; MAX_MEMO_LEN]>> {
+        self.memo
+            .as_deref()
+            .map(|memo| {
+                let bytes = memo.as_bytes();
+                (bytes.len() <= MAX_MEMO_LEN)
+                    .then(|| {
+                        let mut padded = [0u8; MAX_MEMO_LEN];
+                        padded[..bytes.len()].copy_from_slice(bytes);
+                        padded
+                    })
+                    .ok_or(EscrowError::MemoTooLong {
+                        len: bytes.len(),
+                        max: MAX_MEMO_LEN,
+                    })
+            })
+            .transpose()
+    }
 }
 
 /// Chain-specific network configuration for creating or querying escrows.
@@ -224,13 +904,327 @@ pub struct ChainConfig {
     pub chain: Chain,
     /// JSON-RPC endpoint URL.
     pub rpc_url: String,
+    /// Further JSON-RPC endpoints of the same chain, tried in order when
+    /// `rpc_url` keeps failing with transient errors.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub fallback_rpc_urls: Vec<String>,
     /// Sender's private key and/or keypair path.
     ///
-    /// For Ethereum, a wallet import format (WIF) or hex is expected.
+    /// For Ethereum, a wallet import format (WIF) or hex is expected, or a
+    /// KMS key reference (`aws-kms:<key-id>`, `gcp-kms:<key-version-name>`).
     /// For Solana, a path to a keypair file (e.g., `~/.config/solana/id.json`).
+    /// For Cosmos, a hex-encoded secp256k1 private key.
+    /// For Substrate, a hex-encoded sr25519 seed or a secret URI (e.g. a
+    /// mnemonic, or `//Alice` on development chains).
+    /// For Cardano, a hex-encoded or Bech32 (`ed25519_sk1...`) ed25519
+    /// signing key.
+    /// For Tron, a hex-encoded secp256k1 private key or KMS key reference,
+    /// as on Ethereum.
+    /// For Hedera, the hex-encoded ECDSA (secp256k1) private key of an
+    /// account with an EVM address alias, or a KMS key reference.
     pub sender_private_id: String,
-    /// On-chain escrow program ID (Solana) or smart contract address (Ethereum).
+    /// On-chain escrow program ID (Solana), smart contract address
+    /// (Ethereum and Hedera, Bech32 on Cosmos, SS58 on Substrate, or
+    /// base58check on Tron), or Bech32 validator script address (Cardano).
     pub agent_id: String,
+    /// Optional WebSocket (`ws://`, `wss://`) URL or IPC socket path used for
+    /// push event subscriptions. On Solana it defaults to the WebSocket
+    /// counterpart of `rpc_url`.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub pubsub_url: Option<String>,
+    /// Optional EVM network preset (Ethereum only), an Avalanche subnet as
+    /// `{"subnet": {"chain_id": ...}}`, or any other EVM chain as
+    /// `{"custom": {"chain_id": ..., "block_time_secs": ...}}`. Enables L2
+    /// gas handling, sets the timelock block time and default confirmation
+    /// depth, and checks the endpoint's chain ID against the preset.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub network: Option<EvmNetwork>,
+    /// Optional ERC-4337 smart account settings (Ethereum only).
+    #[cfg_attr(feature = "json", serde(default))]
+    pub smart_account: Option<SmartAccountConfig>,
+    /// Network settings of a Cosmos chain (Cosmos only, where they are
+    /// required).
+    #[cfg_attr(feature = "json", serde(default))]
+    pub cosmos: Option<CosmosConfig>,
+    /// Optional signing settings of a Substrate chain (Substrate only).
+    #[cfg_attr(feature = "json", serde(default))]
+    pub substrate: Option<SubstrateConfig>,
+    /// Network settings of a Cardano chain (Cardano only, where they are
+    /// required).
+    #[cfg_attr(feature = "json", serde(default))]
+    pub cardano: Option<CardanoConfig>,
+    /// Optional fee settings of a Tron network (Tron only).
+    #[cfg_attr(feature = "json", serde(default))]
+    pub tron: Option<TronConfig>,
+    /// Optional zkSync paymaster settings (Ethereum on zkSync Era only).
+    #[cfg_attr(feature = "json", serde(default))]
+    pub zksync: Option<ZksyncConfig>,
+    /// Mirror node settings of a Hedera network (Hedera only, where they
+    /// are required).
+    #[cfg_attr(feature = "json", serde(default))]
+    pub hedera: Option<HederaConfig>,
+    /// Optional identifier of a chain supported by an agent registered with
+    /// the client, used instead of the built-in agent for `chain`. `chain`
+    /// still selects the key and address formats.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub custom_chain: Option<String>,
+}
+
+/// ERC-4337 smart account settings.
+///
+/// Escrow calls made by a party with a smart account are wrapped in
+/// UserOperations, signed by that party's key as the account owner, and
+/// submitted through a bundler.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct SmartAccountConfig {
+    /// Bundler JSON-RPC endpoint URL.
+    pub bundler_url: String,
+    /// EntryPoint v0.7 address; defaults to the canonical deployment.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub entry_point: Option<String>,
+    /// Smart account acting as the escrow sender, if any.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub sender_account: Option<String>,
+    /// Smart account acting as the escrow recipient, if any.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub recipient_account: Option<String>,
+    /// Optional ERC-7677 paymaster service URL sponsoring gas, so that
+    /// account owners need no ETH (e.g., for gasless recipient claims).
+    #[cfg_attr(feature = "json", serde(default))]
+    pub paymaster_url: Option<String>,
+}
+
+/// Settings of a Cosmos SDK chain running the CosmWasm escrow contract,
+/// e.g. Osmosis or Neutron. `rpc_url` is the chain's LCD (REST) endpoint.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq)]
+pub struct CosmosConfig {
+    /// Chain ID transactions are signed for, e.g. `osmosis-1`.
+    pub chain_id: String,
+    /// Denomination fees are paid in, and native escrows are funded with,
+    /// e.g. `uosmo`.
+    pub denom: String,
+    /// Bech32 prefix of account addresses, e.g. `osmo`.
+    pub prefix: String,
+    /// Fee per unit of gas, in `denom`.
+    pub gas_price: f64,
+    /// Multiplier applied to simulated gas to set the gas limit.
+    #[cfg_attr(
+        feature = "json",
+        serde(default = "CosmosConfig::default_gas_adjustment")
+    )]
+    pub gas_adjustment: f64,
+    /// Account on another IBC chain whose settlement packets may finish the
+    /// escrows created with this configuration, as their recipients can.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub ibc_settler: Option<IbcSettler>,
+}
+
+/// Account on the counterparty chain of an IBC channel, sending settlement
+/// packets through the escrow contract there, whose packets may finish an
+/// escrow on a Cosmos chain.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct IbcSettler {
+    /// Channel on the escrow's chain the packets arrive on, e.g.
+    /// `channel-7`, which must connect to a trusted escrow contract.
+    pub channel: String,
+    /// Address of the settler on the counterparty chain.
+    pub sender: String,
+}
+
+impl CosmosConfig {
+    /// Gas adjustment used unless configured, as the Cosmos SDK CLI
+    /// suggests.
+    pub const DEFAULT_GAS_ADJUSTMENT: f64 = 1.3;
+
+    #[cfg(feature = "json")]
+    fn default_gas_adjustment() -> f64 {
+        Self::DEFAULT_GAS_ADJUSTMENT
+    }
+}
+
+/// Settings of a Substrate chain running the ink! escrow contract on
+/// `pallet-contracts`. `rpc_url` is a node's WebSocket endpoint, and
+/// account addresses use the SS58 prefix of the contract address.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct SubstrateConfig {
+    /// Number of blocks a signed extrinsic stays valid for after the
+    /// latest finalized block, rounded up to a power of two by the chain.
+    #[cfg_attr(
+        feature = "json",
+        serde(default = "SubstrateConfig::default_mortality")
+    )]
+    pub mortality: u64,
+}
+
+impl SubstrateConfig {
+    /// Mortality used unless configured, as Polkadot wallets default to.
+    pub const DEFAULT_MORTALITY: u64 = 64;
+
+    #[cfg(feature = "json")]
+    fn default_mortality() -> u64 {
+        Self::DEFAULT_MORTALITY
+    }
+}
+
+impl Default for SubstrateConfig {
+    fn default() -> Self {
+        Self {
+            mortality: Self::DEFAULT_MORTALITY,
+        }
+    }
+}
+
+/// Settings of a Cardano network running the Plutus escrow validator.
+/// `rpc_url` is a Blockfrost-compatible API endpoint, and `agent_id` the
+/// validator's script address.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct CardanoConfig {
+    /// Network transactions are built for, which also sets how slots map
+    /// to the POSIX times the validator checks timelocks against.
+    pub network: CardanoNetwork,
+    /// UTxO holding the validator as a reference script, as
+    /// `<tx hash>#<output index>`.
+    pub reference_script: String,
+    /// Optional Blockfrost project ID, sent with every API request.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub project_id: Option<String>,
+}
+
+/// Settings of a Tron network running the Solidity escrow contract.
+/// `rpc_url` is a full node's HTTP API endpoint, e.g.
+/// `https://api.trongrid.io`, which also serves Ethereum JSON-RPC under
+/// `/jsonrpc`.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct TronConfig {
+    /// Most TRX, in sun, a transaction may burn for the energy its contract
+    /// call uses.
+    #[cfg_attr(feature = "json", serde(default = "TronConfig::default_fee_limit"))]
+    pub fee_limit: u64,
+    /// Optional TronGrid API key, sent with every request.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub api_key: Option<String>,
+}
+
+impl TronConfig {
+    /// Fee limit used unless configured: 100 TRX.
+    pub const DEFAULT_FEE_LIMIT: u64 = 100_000_000;
+
+    #[cfg(feature = "json")]
+    fn default_fee_limit() -> u64 {
+        Self::DEFAULT_FEE_LIMIT
+    }
+}
+
+impl Default for TronConfig {
+    fn default() -> Self {
+        Self {
+            fee_limit: Self::DEFAULT_FEE_LIMIT,
+            api_key: None,
+        }
+    }
+}
+
+/// Settings of a Hedera network running the Solidity escrow contract on
+/// its smart contract service. `rpc_url` is a JSON-RPC relay endpoint, e.g.
+/// `https://testnet.hashio.io/api`, and `agent_id` the contract's EVM
+/// address.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct HederaConfig {
+    /// Mirror node REST API base URL, e.g.
+    /// `https://testnet.mirrornode.hedera.com`, used to resolve account IDs
+    /// (`0.0.x`) to the EVM addresses their transactions come from.
+    pub mirror_url: String,
+}
+
+/// zkSync Era paymaster settings.
+///
+/// zkSync accounts support paymasters natively, so sponsored escrow calls
+/// are sent as EIP-712 transactions naming `paymaster`, which pays their
+/// fees through its general flow; the signing key needs no ETH.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Clone, Encode, Decode, PartialEq, Eq)]
+pub struct ZksyncConfig {
+    /// Address of the paymaster contract sponsoring fees.
+    pub paymaster: String,
+    /// Which escrow calls the paymaster sponsors.
+    #[cfg_attr(feature = "json", serde(default))]
+    pub sponsorship: SponsorshipPolicy,
+}
+
+/// Escrow calls a paymaster sponsors.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "lowercase"))]
+#[derive(Debug, Copy, Clone, Default, Encode, Decode, PartialEq, Eq)]
+pub enum SponsorshipPolicy {
+    /// Only recipients' `finishEscrow` calls, so recipients can claim
+    /// without holding ETH.
+    #[default]
+    Finish,
+    /// Every escrow call, from either party.
+    All,
+}
+
+/// Cardano networks, with their network IDs and slot timings.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "lowercase"))]
+#[derive(Debug, Copy, Clone, Encode, Decode, PartialEq, Eq)]
+pub enum CardanoNetwork {
+    /// Cardano mainnet.
+    Mainnet,
+    /// Pre-production testnet.
+    Preprod,
+    /// Preview testnet.
+    Preview,
+}
+
+impl CardanoNetwork {
+    /// Network ID of addresses and transactions: 1 on mainnet, 0 on
+    /// testnets.
+    pub fn network_id(self) -> u8 {
+        match self {
+            Self::Mainnet => 1,
+            Self::Preprod | Self::Preview => 0,
+        }
+    }
+
+    /// CAIP-2 ID of the network: `cip34:` followed by its network ID and
+    /// protocol magic.
+    pub fn caip2_id(self) -> &'static str {
+        match self {
+            Self::Mainnet => "cip34:1-764824073",
+            Self::Preprod => "cip34:0-1",
+            Self::Preview => "cip34:0-2",
+        }
+    }
+
+    /// POSIX time in milliseconds of the first Shelley slot, and that slot.
+    /// Slots last one second from then on.
+    fn shelley_start(self) -> (u64, u64) {
+        match self {
+            Self::Mainnet => (1_596_059_091_000, 4_492_800),
+            Self::Preprod => (1_655_769_600_000, 86_400),
+            Self::Preview => (1_666_656_000_000, 0),
+        }
+    }
+
+    /// POSIX time in milliseconds at which `slot` starts.
+    pub fn slot_to_posix_ms(self, slot: u64) -> u64 {
+        let (start, start_slot) = self.shelley_start();
+        start + slot.saturating_sub(start_slot) * 1_000
+    }
+
+    /// Slot containing the POSIX time `ms`, in milliseconds.
+    pub fn posix_ms_to_slot(self, ms: u64) -> u64 {
+        let (start, start_slot) = self.shelley_start();
+        start_slot + ms.saturating_sub(start) / 1_000
+    }
 }
 
 /// Supported blockchain networks.
@@ -242,6 +1236,17 @@ pub enum Chain {
     Ethereum,
     /// Solana
     Solana,
+    /// Cosmos SDK chains with CosmWasm, e.g. Osmosis and Neutron.
+    Cosmos,
+    /// Substrate chains with ink! contracts, e.g. Astar and Aleph Zero.
+    Substrate,
+    /// Cardano, with a Plutus escrow validator.
+    Cardano,
+    /// Tron, running the Solidity escrow contract on the TVM.
+    Tron,
+    /// Hedera, running the Solidity escrow contract on its smart contract
+    /// service.
+    Hedera,
 }
 
 impl AsRef<str> for Chain {
@@ -249,6 +1254,11 @@ impl AsRef<str> for Chain {
         match self {
             Chain::Ethereum => "ethereum",
             Chain::Solana => "solana",
+            Chain::Cosmos => "cosmos",
+            Chain::Substrate => "substrate",
+            Chain::Cardano => "cardano",
+            Chain::Tron => "tron",
+            Chain::Hedera => "hedera",
         }
     }
 }
@@ -265,49 +1275,1279 @@ impl std::str::FromStr for Chain {
         match s.to_lowercase().as_str() {
             "ethereum" | "eth" => Ok(Self::Ethereum),
             "solana" | "sol" => Ok(Self::Solana),
+            "cosmos" | "cosmwasm" => Ok(Self::Cosmos),
+            "substrate" | "polkadot" | "dot" | "ink" => Ok(Self::Substrate),
+            "cardano" | "ada" => Ok(Self::Cardano),
+            "tron" | "trx" => Ok(Self::Tron),
+            "hedera" | "hbar" => Ok(Self::Hedera),
             _ => Err(EscrowError::UnsupportedChain),
         }
     }
 }
 
-#[cfg(all(test, feature = "json"))]
-mod tests {
-    use std::str::FromStr;
+/// EVM rollup families whose gas and block semantics differ from Ethereum L1.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Rollup {
+    /// Arbitrum One and Arbitrum Sepolia.
+    Arbitrum,
+    /// OP Stack chains (Optimism, Base).
+    OpStack,
+    /// zkSync Era and zkSync Sepolia.
+    ZkSync,
+}
 
-    use super::*;
+/// Known EVM networks with preset chain IDs and timelock semantics.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "json", serde(rename_all = "kebab-case"))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Encode, Decode)]
+pub enum EvmNetwork {
+    /// Ethereum mainnet.
+    Mainnet,
+    /// Ethereum Sepolia testnet.
+    Sepolia,
+    /// Arbitrum One.
+    Arbitrum,
+    /// Arbitrum Sepolia testnet.
+    ArbitrumSepolia,
+    /// OP Mainnet.
+    Optimism,
+    /// OP Sepolia testnet.
+    OptimismSepolia,
+    /// Base mainnet.
+    Base,
+    /// Base Sepolia testnet.
+    BaseSepolia,
+    /// zkSync Era mainnet.
+    ZksyncEra,
+    /// zkSync Sepolia testnet.
+    ZksyncSepolia,
+    /// Avalanche C-Chain.
+    Avalanche,
+    /// Avalanche Fuji testnet C-Chain.
+    AvalancheFuji,
+    /// EVM chain of an Avalanche subnet, identified by its chain ID.
+    Subnet(AvalancheSubnet),
+    /// Any other EVM chain, such as Polygon PoS or BNB Smart Chain,
+    /// described entirely by its configuration.
+    Custom(CustomEvmNetwork),
+}
 
-    #[test]
-    fn chain_from_str_ethereum() {
-        assert!(matches!(Chain::from_str("ethereum"), Ok(Chain::Ethereum)));
-        assert!(matches!(Chain::from_str("ETHEREUM"), Ok(Chain::Ethereum)));
-        assert!(matches!(Chain::from_str("eth"), Ok(Chain::Ethereum)));
-        assert!(matches!(Chain::from_str("ETH"), Ok(Chain::Ethereum)));
+/// An Avalanche subnet (Avalanche L1) running the EVM, such as a Subnet-EVM
+/// chain, with the block timing and finality its operator configured.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct AvalancheSubnet {
+    /// EIP-155 chain ID of the subnet's EVM chain.
+    pub chain_id: u64,
+    /// Target seconds between blocks, as set by the subnet's fee config.
+    #[cfg_attr(
+        feature = "json",
+        serde(default = "AvalancheSubnet::default_block_time_secs")
+    )]
+    pub block_time_secs: u64,
+    /// Blocks a transaction must be buried under before it is treated as
+    /// final. Snowman consensus finalizes blocks once accepted, so one
+    /// suffices unless the subnet's validator set is not trusted to
+    /// finalize honestly.
+    #[cfg_attr(
+        feature = "json",
+        serde(default = "AvalancheSubnet::default_confirmations")
+    )]
+    pub confirmations: u64,
+}
+
+impl AvalancheSubnet {
+    /// Creates subnet settings for `chain_id` with the default block time
+    /// and confirmation depth.
+    pub fn new(chain_id: u64) -> Self {
+        Self {
+            chain_id,
+            block_time_secs: AVALANCHE_BLOCK_TIME_SECS,
+            confirmations: 1,
+        }
     }
 
-    #[test]
-    fn chain_from_str_solana() {
-        assert!(matches!(Chain::from_str("solana"), Ok(Chain::Solana)));
-        assert!(matches!(Chain::from_str("SOLANA"), Ok(Chain::Solana)));
-        assert!(matches!(Chain::from_str("sol"), Ok(Chain::Solana)));
-        assert!(matches!(Chain::from_str("SOL"), Ok(Chain::Solana)));
+    #[cfg(feature = "json")]
+    fn default_block_time_secs() -> u64 {
+        AVALANCHE_BLOCK_TIME_SECS
     }
 
-    #[test]
-    fn chain_from_str_unsupported() {
-        assert!(matches!(
-            Chain::from_str("bitcoin"),
-            Err(EscrowError::UnsupportedChain)
-        ));
-        assert!(matches!(
-            Chain::from_str(""),
-            Err(EscrowError::UnsupportedChain)
-        ));
+    #[cfg(feature = "json")]
+    fn default_confirmations() -> u64 {
+        1
     }
+}
 
-    #[test]
-    fn chain_as_ref() {
-        assert_eq!(Chain::Ethereum.as_ref(), "ethereum");
-        assert_eq!(Chain::Solana.as_ref(), "solana");
+/// An EVM chain without a preset, onboarded through configuration alone.
+///
+/// The chain is driven like Ethereum L1: gas is paid on the chain itself,
+/// and timelocks count its own blocks.
+#[cfg_attr(feature = "json", derive(Serialize, Deserialize))]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Encode, Decode)]
+pub struct CustomEvmNetwork {
+    /// EIP-155 chain ID, checked against the RPC endpoint's.
+    pub chain_id: u64,
+    /// Average seconds between increments of `block.number`, used to
+    /// convert timelocks to and from durations.
+    pub block_time_secs: u64,
+    /// Blocks a transaction must be buried under before it is treated as
+    /// final, e.g. more on chains with probabilistic finality.
+    #[cfg_attr(
+        feature = "json",
+        serde(default = "CustomEvmNetwork::default_confirmations")
+    )]
+    pub confirmations: u64,
+}
+
+impl CustomEvmNetwork {
+    /// Creates settings for `chain_id` with `block_time_secs` blocks and
+    /// the default confirmation depth.
+    pub fn new(chain_id: u64, block_time_secs: u64) -> Self {
+        Self {
+            chain_id,
+            block_time_secs,
+            confirmations: 1,
+        }
+    }
+
+    #[cfg(feature = "json")]
+    fn default_confirmations() -> u64 {
+        1
+    }
+}
+
+/// Target seconds between blocks of the Avalanche C-Chain and Subnet-EVM
+/// chains unless reconfigured.
+const AVALANCHE_BLOCK_TIME_SECS: u64 = 2;
+
+impl EvmNetwork {
+    /// Networks selectable by name.
+    pub const PRESETS: [Self; 12] = [
+        Self::Mainnet,
+        Self::Sepolia,
+        Self::Arbitrum,
+        Self::ArbitrumSepolia,
+        Self::Optimism,
+        Self::OptimismSepolia,
+        Self::Base,
+        Self::BaseSepolia,
+        Self::ZksyncEra,
+        Self::ZksyncSepolia,
+        Self::Avalanche,
+        Self::AvalancheFuji,
+    ];
+
+    /// EIP-155 chain ID of the network.
+    pub fn chain_id(&self) -> u64 {
+        match self {
+            Self::Mainnet => 1,
+            Self::Sepolia => 11_155_111,
+            Self::Arbitrum => 42_161,
+            Self::ArbitrumSepolia => 421_614,
+            Self::Optimism => 10,
+            Self::OptimismSepolia => 11_155_420,
+            Self::Base => 8_453,
+            Self::BaseSepolia => 84_532,
+            Self::ZksyncEra => 324,
+            Self::ZksyncSepolia => 300,
+            Self::Avalanche => 43_114,
+            Self::AvalancheFuji => 43_113,
+            Self::Subnet(subnet) => subnet.chain_id,
+            Self::Custom(custom) => custom.chain_id,
+        }
+    }
+
+    /// Rollup family of the network, or `None` for Ethereum L1, Avalanche
+    /// chains, and custom chains.
+    pub fn rollup(&self) -> Option<Rollup> {
+        match self {
+            Self::Mainnet
+            | Self::Sepolia
+            | Self::Avalanche
+            | Self::AvalancheFuji
+            | Self::Subnet(_)
+            | Self::Custom(_) => None,
+            Self::Arbitrum | Self::ArbitrumSepolia => Some(Rollup::Arbitrum),
+            Self::Optimism | Self::OptimismSepolia | Self::Base | Self::BaseSepolia => {
+                Some(Rollup::OpStack)
+            }
+            Self::ZksyncEra | Self::ZksyncSepolia => Some(Rollup::ZkSync),
+        }
+    }
+
+    /// Average seconds between increments of `block.number` as seen by
+    /// contracts, which is the clock `finish_after` and `cancel_after` use.
+    ///
+    /// On Arbitrum `block.number` returns an approximate *L1* block number,
+    /// so timelocks advance at L1 pace (~12s) rather than per L2 block. OP
+    /// Stack chains expose their own 2s L2 blocks, zkSync its ~1s L2
+    /// blocks, and Avalanche chains their own blocks, about 2s apart unless
+    /// a subnet configures otherwise. Custom chains use their configured
+    /// block time.
+    pub fn timelock_block_time_secs(&self) -> u64 {
+        match self {
+            Self::Avalanche | Self::AvalancheFuji => AVALANCHE_BLOCK_TIME_SECS,
+            Self::Subnet(subnet) => subnet.block_time_secs.max(1),
+            Self::Custom(custom) => custom.block_time_secs.max(1),
+            _ => match self.rollup() {
+                None | Some(Rollup::Arbitrum) => 12,
+                Some(Rollup::OpStack) => 2,
+                Some(Rollup::ZkSync) => 1,
+            },
+        }
+    }
+
+    /// Blocks a transaction must be buried under before it is treated as
+    /// final unless configured otherwise.
+    ///
+    /// Ethereum and rollup transactions dropped by a shallow reorg are
+    /// resent, so one block suffices there too. Avalanche's Snowman
+    /// consensus never reverts an accepted block, so the C-Chain needs no
+    /// more than inclusion; subnets and custom chains use their configured
+    /// depth.
+    pub fn default_confirmations(&self) -> u64 {
+        match self {
+            Self::Subnet(subnet) => subnet.confirmations.max(1),
+            Self::Custom(custom) => custom.confirmations.max(1),
+            _ => 1,
+        }
+    }
+
+    /// Checks that the network's settings are usable: custom chains need a
+    /// non-zero chain ID, block time, and confirmation depth, and must not
+    /// reuse the chain ID of a preset, whose rollup gas and timelock
+    /// handling they would miss.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EscrowError::InvalidChainOp` describing the first invalid
+    /// setting.
+    pub fn validate(&self) -> Result<()> {
+        let Self::Custom(custom) = self else {
+            return Ok(());
+        };
+        let invalid = |msg: String| Err(EscrowError::InvalidChainOp(msg));
+        if custom.chain_id == 0 {
+            return invalid("custom EVM network: chain_id must be non-zero".into());
+        }
+        if custom.block_time_secs == 0 {
+            return invalid("custom EVM network: block_time_secs must be non-zero".into());
+        }
+        if custom.confirmations == 0 {
+            return invalid("custom EVM network: confirmations must be non-zero".into());
+        }
+        if let Some(preset) = Self::PRESETS
+            .iter()
+            .find(|preset| preset.chain_id() == custom.chain_id)
+        {
+            return invalid(format!(
+                "custom EVM network: chain ID {} is the `{}` preset; set `network` to it instead",
+                custom.chain_id,
+                preset.as_ref()
+            ));
+        }
+        Ok(())
+    }
+
+    /// Number of timelock blocks spanning at least `secs` seconds.
+    pub fn timelock_blocks_for(&self, secs: u64) -> u64 {
+        secs.div_ceil(self.timelock_block_time_secs())
+    }
+}
+
+impl AsRef<str> for EvmNetwork {
+    fn as_ref(&self) -> &str {
+        match self {
+            Self::Mainnet => "mainnet",
+            Self::Sepolia => "sepolia",
+            Self::Arbitrum => "arbitrum",
+            Self::ArbitrumSepolia => "arbitrum-sepolia",
+            Self::Optimism => "optimism",
+            Self::OptimismSepolia => "optimism-sepolia",
+            Self::Base => "base",
+            Self::BaseSepolia => "base-sepolia",
+            Self::ZksyncEra => "zksync-era",
+            Self::ZksyncSepolia => "zksync-sepolia",
+            Self::Avalanche => "avalanche",
+            Self::AvalancheFuji => "avalanche-fuji",
+            Self::Subnet(_) => "avalanche-subnet",
+            Self::Custom(_) => "custom",
+        }
+    }
+}
+
+impl std::str::FromStr for EvmNetwork {
+    type Err = EscrowError;
+
+    /// Parses a network preset name (case-insensitive). Subnets and custom
+    /// chains have no name and are configured with their chain ID instead.
+    ///
+    /// # Errors
+    ///
+    /// Returns `EscrowError::UnsupportedChain` on unrecognized input.
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "mainnet" | "ethereum" => Ok(Self::Mainnet),
+            "sepolia" => Ok(Self::Sepolia),
+            "arbitrum" | "arbitrum-one" => Ok(Self::Arbitrum),
+            "arbitrum-sepolia" => Ok(Self::ArbitrumSepolia),
+            "optimism" | "op" => Ok(Self::Optimism),
+            "optimism-sepolia" | "op-sepolia" => Ok(Self::OptimismSepolia),
+            "base" => Ok(Self::Base),
+            "base-sepolia" => Ok(Self::BaseSepolia),
+            "zksync-era" | "zksync" => Ok(Self::ZksyncEra),
+            "zksync-sepolia" => Ok(Self::ZksyncSepolia),
+            "avalanche" | "avalanche-c" | "avax" => Ok(Self::Avalanche),
+            "avalanche-fuji" | "fuji" => Ok(Self::AvalancheFuji),
+            _ => Err(EscrowError::UnsupportedChain),
+        }
+    }
+}
+
+/// Proof that an escrow's conditions are fulfilled, as written by the
+/// prover and read when finishing the escrow on either chain.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProofArtifact {
+    /// Chain of the escrow proven.
+    pub chain: Chain,
+    /// Contract-assigned escrow ID (Ethereum, Cosmos, and Substrate).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escrow_id: Option<u64>,
+    /// Sender of the escrow proven.
+    pub sender: String,
+    /// Recipient of the escrow proven.
+    pub recipient: String,
+    /// Hex-encoded [`EscrowMetadata::fingerprint`] of the escrow proven.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub escrow_fingerprint: Option<String>,
+    /// Hex-encoded seal to submit when finishing (Ethereum only). Solana
+    /// escrows are proven off-chain, so the proof only attests that the
+    /// conditions were checked.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proof_seal: Option<String>,
+    /// Path of the full receipt, if written alongside.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub receipt: Option<PathBuf>,
+    /// Hex-encoded image ID of the guest program proven.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image_id: Option<String>,
+    /// Hex-encoded journal committed by the guest.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub journal: Option<String>,
+    /// Hex-encoded SHA-256 digest of the journal, which Groth16 verifiers
+    /// check the proof against.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub journal_digest: Option<String>,
+    /// Groth16 proof points, for verifiers that take them individually.
+    #[serde(flatten, default, skip_serializing_if = "Option::is_none")]
+    pub groth16: Option<Groth16Points>,
+    /// Version of the prover, and the guest program it embeds, that
+    /// generated the proof.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub guest_version: Option<String>,
+    /// When the proof was generated, in seconds since the Unix epoch.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<u64>,
+    /// Who generated the proof, signed with their identity key (see
+    /// [`ProofArtifact::sign`]).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provenance: Option<Provenance>,
+}
+
+/// Prover that generated a [`ProofArtifact`], when, and on what host,
+/// signed with the prover's Ed25519 identity key so that, in multi-party
+/// workflows, the recipient can show who produced a proof.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Provenance {
+    /// Hex-encoded Ed25519 public key identifying the prover.
+    pub signer: String,
+    /// When the artifact was signed, in seconds since the Unix epoch.
+    pub signed_at: u64,
+    /// Host the proof was generated on, e.g. its name, OS, and
+    /// architecture.
+    pub host: String,
+    /// Hex-encoded Ed25519 signature over the artifact and the fields
+    /// above.
+    pub signature: String,
+}
+
+/// Hex-encoded proof points of a Groth16 proof, each coordinate a 32-byte
+/// big-endian field element.
+#[cfg(feature = "json")]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Groth16Points {
+    /// The `A` point, in G1.
+    pub pi_a: [String; 2],
+    /// The `B` point, in G2.
+    pub pi_b: [[String; 2]; 2],
+    /// The `C` point, in G1.
+    pub pi_c: [String; 2],
+}
+
+#[cfg(feature = "json")]
+impl ProofArtifact {
+    /// Records the proof of the escrow in `metadata`, generated now, with
+    /// its Ethereum `seal` if any.
+    pub fn new(metadata: &EscrowMetadata, seal: Option<&[u8]>) -> Self {
+        let created_at = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0, |elapsed| elapsed.as_secs());
+        Self {
+            chain: metadata.params.chain_config.chain,
+            escrow_id: metadata.escrow_id,
+            sender: metadata.params.sender.to_string(),
+            recipient: metadata.params.recipient.to_string(),
+            escrow_fingerprint: Some(hex::encode(metadata.fingerprint())),
+            proof_seal: seal.map(|seal| format!("0x{}", hex::encode(seal))),
+            receipt: None,
+            image_id: None,
+            journal: None,
+            journal_digest: None,
+            groth16: None,
+            guest_version: None,
+            created_at: Some(created_at),
+            provenance: None,
+        }
+    }
+
+    /// Signs the artifact with the Ed25519 identity key whose secret is
+    /// `key`, recording the prover's [`Provenance`] as of now, on `host`.
+    ///
+    /// The signature covers every field but the local receipt path, so
+    /// the artifact must be complete before it is signed.
+    pub fn sign(&mut self, key: &[u8; 32], host: impl Into<String>) {
+        use ed25519_dalek::{Signer, SigningKey};
+
+        let key = SigningKey::from_bytes(key);
+        let mut provenance = Provenance {
+            signer: hex::encode(key.verifying_key().as_bytes()),
+            signed_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+            host: host.into(),
+            signature: String::new(),
+        };
+        let signature = key.sign(&self.provenance_digest(&provenance));
+        provenance.signature = hex::encode(signature.to_bytes());
+        self.provenance = Some(provenance);
+    }
+
+    /// Verifies the [`Provenance`] signature, returning the provenance, or
+    /// `None` if the artifact is not signed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signer or signature is malformed, or the
+    /// signature does not match the artifact, e.g. because it was altered
+    /// after signing.
+    pub fn verify_provenance(&self) -> anyhow::Result<Option<&Provenance>> {
+        use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+        let Some(provenance) = &self.provenance else {
+            return Ok(None);
+        };
+        let signer: [u8; 32] = hex::decode(provenance.signer.trim_start_matches("0x"))
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .context("provenance signer is not a hex Ed25519 public key")?;
+        let signature: [u8; 64] = hex::decode(provenance.signature.trim_start_matches("0x"))
+            .ok()
+            .and_then(|bytes| bytes.try_into().ok())
+            .context("provenance signature is not a hex Ed25519 signature")?;
+        VerifyingKey::from_bytes(&signer)
+            .context("invalid provenance signer")?
+            .verify(
+                &self.provenance_digest(provenance),
+                &Signature::from_bytes(&signature),
+            )
+            .context("provenance signature does not match the proof data")?;
+        Ok(Some(provenance))
+    }
+
+    /// Returns the SHA-256 digest the [`Provenance`] signature covers: the
+    /// artifact's fields but the receipt path, and the provenance's but
+    /// the signature.
+    fn provenance_digest(&self, provenance: &Provenance) -> [u8; 32] {
+        let groth16 = self
+            .groth16
+            .as_ref()
+            .and_then(|points| serde_json::to_string(points).ok());
+        let fields = [
+            Some(self.chain.as_ref().to_string()),
+            self.escrow_id.map(|id| id.to_string()),
+            Some(self.sender.clone()),
+            Some(self.recipient.clone()),
+            self.escrow_fingerprint.clone(),
+            self.proof_seal.clone(),
+            self.image_id.clone(),
+            self.journal.clone(),
+            self.journal_digest.clone(),
+            groth16,
+            self.guest_version.clone(),
+            self.created_at.map(|at| at.to_string()),
+            Some(provenance.signer.clone()),
+            Some(provenance.signed_at.to_string()),
+            Some(provenance.host.clone()),
+        ];
+        let encoded =
+            bincode::encode_to_vec(fields, standard()).expect("encoding to a Vec cannot fail");
+        Sha256::digest([PROVENANCE_DOMAIN, &encoded].concat()).into()
+    }
+
+    /// Reads a proof artifact from `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        load_escrow_data(path)
+    }
+
+    /// Writes the proof artifact to `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        save_escrow_data(path, self)
+    }
+
+    /// Whether the proof is for the escrow in `metadata`: by fingerprint,
+    /// or for artifacts without one, by chain, escrow ID, and parties.
+    pub fn is_for(&self, metadata: &EscrowMetadata) -> bool {
+        let params = &metadata.params;
+        match &self.escrow_fingerprint {
+            Some(fingerprint) => {
+                fingerprint.trim_start_matches("0x") == hex::encode(metadata.fingerprint())
+            }
+            None => {
+                self.chain.as_ref() == params.chain_config.chain.as_ref()
+                    && self.escrow_id == metadata.escrow_id
+                    && self.sender == params.sender.to_string()
+                    && self.recipient == params.recipient.to_string()
+            }
+        }
+    }
+
+    /// Returns the decoded seal, after checking that the proof is for the
+    /// escrow in `metadata`, and if the journal is recorded, that it is
+    /// bound to that escrow.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the proof is for another escrow, or the seal or
+    /// journal is malformed.
+    pub fn seal_for(&self, metadata: &EscrowMetadata) -> anyhow::Result<Option<Vec<u8>>> {
+        if !self.is_for(metadata) {
+            anyhow::bail!("proof data is for another escrow");
+        }
+        if let Some(journal) = self.journal_bytes()? {
+            let journal = Journal::decode(&journal).context("invalid journal")?;
+            if !journal.binding.same_escrow(&metadata.binding()?) {
+                anyhow::bail!("proof journal is bound to another escrow");
+            }
+        }
+        self.seal()
+    }
+
+    /// Returns the decoded seal, if any.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the seal is not valid hex.
+    pub fn seal(&self) -> anyhow::Result<Option<Vec<u8>>> {
+        decode_hex(self.proof_seal.as_deref()).context("invalid proof_seal")
+    }
+
+    /// Returns the decoded journal, if recorded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal is not valid hex.
+    pub fn journal_bytes(&self) -> anyhow::Result<Option<Vec<u8>>> {
+        decode_hex(self.journal.as_deref()).context("invalid journal")
+    }
+
+    /// Returns the freshness committed to the journal, if recorded.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the journal is malformed.
+    pub fn freshness(&self) -> anyhow::Result<Option<Freshness>> {
+        self.journal_bytes()?
+            .map(|journal| {
+                Journal::decode(&journal)
+                    .map(|journal| journal.binding.freshness)
+                    .context("invalid journal")
+            })
+            .transpose()
+    }
+}
+
+/// Domain separator of [`Provenance`] signatures, so they cannot be
+/// replayed as signatures over other messages.
+#[cfg(feature = "json")]
+const PROVENANCE_DOMAIN: &[u8] = b"zescrow-proof-artifact-provenance-v1";
+
+/// Decodes optional hex, with or without a `0x` prefix.
+#[cfg(feature = "json")]
+fn decode_hex(hex: Option<&str>) -> std::result::Result<Option<Vec<u8>>, hex::FromHexError> {
+    hex.map(|hex| hex::decode(hex.trim_start_matches("0x")))
+        .transpose()
+}
+
+#[cfg(all(test, feature = "json"))]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn chain_from_str_ethereum() {
+        assert!(matches!(Chain::from_str("ethereum"), Ok(Chain::Ethereum)));
+        assert!(matches!(Chain::from_str("ETHEREUM"), Ok(Chain::Ethereum)));
+        assert!(matches!(Chain::from_str("eth"), Ok(Chain::Ethereum)));
+        assert!(matches!(Chain::from_str("ETH"), Ok(Chain::Ethereum)));
+    }
+
+    #[test]
+    fn chain_from_str_solana() {
+        assert!(matches!(Chain::from_str("solana"), Ok(Chain::Solana)));
+        assert!(matches!(Chain::from_str("SOLANA"), Ok(Chain::Solana)));
+        assert!(matches!(Chain::from_str("sol"), Ok(Chain::Solana)));
+        assert!(matches!(Chain::from_str("SOL"), Ok(Chain::Solana)));
+    }
+
+    #[test]
+    fn chain_from_str_substrate() {
+        assert!(matches!(Chain::from_str("substrate"), Ok(Chain::Substrate)));
+        assert!(matches!(Chain::from_str("Polkadot"), Ok(Chain::Substrate)));
+        assert!(matches!(Chain::from_str("ink"), Ok(Chain::Substrate)));
+    }
+
+    #[test]
+    fn chain_from_str_cardano() {
+        assert!(matches!(Chain::from_str("cardano"), Ok(Chain::Cardano)));
+        assert!(matches!(Chain::from_str("ADA"), Ok(Chain::Cardano)));
+    }
+
+    #[test]
+    fn chain_from_str_tron() {
+        assert!(matches!(Chain::from_str("tron"), Ok(Chain::Tron)));
+        assert!(matches!(Chain::from_str("TRX"), Ok(Chain::Tron)));
+    }
+
+    #[test]
+    fn chain_from_str_hedera() {
+        assert!(matches!(Chain::from_str("hedera"), Ok(Chain::Hedera)));
+        assert!(matches!(Chain::from_str("HBAR"), Ok(Chain::Hedera)));
+    }
+
+    #[test]
+    fn chain_from_str_unsupported() {
+        assert!(matches!(
+            Chain::from_str("bitcoin"),
+            Err(EscrowError::UnsupportedChain)
+        ));
+        assert!(matches!(
+            Chain::from_str(""),
+            Err(EscrowError::UnsupportedChain)
+        ));
+    }
+
+    #[test]
+    fn chain_as_ref() {
+        assert_eq!(Chain::Ethereum.as_ref(), "ethereum");
+        assert_eq!(Chain::Solana.as_ref(), "solana");
+        assert_eq!(Chain::Cosmos.as_ref(), "cosmos");
+        assert_eq!(Chain::Substrate.as_ref(), "substrate");
+        assert_eq!(Chain::Cardano.as_ref(), "cardano");
+        assert_eq!(Chain::Tron.as_ref(), "tron");
+        assert_eq!(Chain::Hedera.as_ref(), "hedera");
+    }
+
+    #[test]
+    fn evm_network_from_str_round_trips() {
+        for network in EvmNetwork::PRESETS {
+            assert_eq!(EvmNetwork::from_str(network.as_ref()).unwrap(), network);
+        }
+        assert!(matches!(
+            EvmNetwork::from_str("polygon"),
+            Err(EscrowError::UnsupportedChain)
+        ));
+    }
+
+    #[test]
+    fn evm_network_timelock_semantics() {
+        // Arbitrum contracts see L1 block numbers.
+        assert_eq!(EvmNetwork::Arbitrum.rollup(), Some(Rollup::Arbitrum));
+        assert_eq!(EvmNetwork::Arbitrum.timelock_block_time_secs(), 12);
+        assert_eq!(EvmNetwork::BaseSepolia.rollup(), Some(Rollup::OpStack));
+        assert_eq!(EvmNetwork::BaseSepolia.timelock_block_time_secs(), 2);
+        assert_eq!(EvmNetwork::Sepolia.rollup(), None);
+
+        assert_eq!(EvmNetwork::Mainnet.timelock_blocks_for(3_600), 300);
+        assert_eq!(EvmNetwork::Base.timelock_blocks_for(3_600), 1_800);
+        assert_eq!(EvmNetwork::Optimism.timelock_blocks_for(1), 1);
+    }
+
+    #[test]
+    fn avalanche_presets() {
+        assert_eq!(EvmNetwork::Avalanche.chain_id(), 43_114);
+        assert_eq!(EvmNetwork::AvalancheFuji.rollup(), None);
+        assert_eq!(EvmNetwork::Avalanche.timelock_blocks_for(3_600), 1_800);
+        assert_eq!(EvmNetwork::Avalanche.default_confirmations(), 1);
+
+        let config: ChainConfig = serde_json::from_str(
+            r#"{"chain":"ethereum","rpc_url":"http://localhost:9650/ext/bc/dfk/rpc","sender_private_id":"0x01","agent_id":"0x02","network":{"subnet":{"chain_id":53935,"confirmations":3}}}"#,
+        )
+        .unwrap();
+        let network = config.network.unwrap();
+        assert_eq!(
+            network,
+            EvmNetwork::Subnet(AvalancheSubnet {
+                confirmations: 3,
+                ..AvalancheSubnet::new(53_935)
+            })
+        );
+        assert_eq!(network.chain_id(), 53_935);
+        assert_eq!(network.timelock_block_time_secs(), 2);
+        assert_eq!(network.default_confirmations(), 3);
+    }
+
+    #[test]
+    fn zksync_paymaster_config() {
+        assert_eq!(EvmNetwork::ZksyncEra.chain_id(), 324);
+        assert_eq!(EvmNetwork::ZksyncSepolia.rollup(), Some(Rollup::ZkSync));
+        assert_eq!(EvmNetwork::ZksyncEra.timelock_blocks_for(3_600), 3_600);
+
+        let config: ChainConfig = serde_json::from_str(
+            r#"{"chain":"ethereum","rpc_url":"https://mainnet.era.zksync.io","sender_private_id":"0x01","agent_id":"0x02","network":"zksync-era","zksync":{"paymaster":"0x03"}}"#,
+        )
+        .unwrap();
+        let zksync = config.zksync.unwrap();
+        assert_eq!(zksync.paymaster, "0x03");
+        assert_eq!(zksync.sponsorship, SponsorshipPolicy::Finish);
+
+        let zksync: ZksyncConfig =
+            serde_json::from_str(r#"{"paymaster":"0x03","sponsorship":"all"}"#).unwrap();
+        assert_eq!(zksync.sponsorship, SponsorshipPolicy::All);
+    }
+
+    #[test]
+    fn custom_evm_network() {
+        let config: ChainConfig = serde_json::from_str(
+            r#"{"chain":"ethereum","rpc_url":"https://polygon-rpc.com","sender_private_id":"0x01","agent_id":"0x02","network":{"custom":{"chain_id":137,"block_time_secs":2}}}"#,
+        )
+        .unwrap();
+        let network = config.network.unwrap();
+        assert_eq!(network, EvmNetwork::Custom(CustomEvmNetwork::new(137, 2)));
+        assert_eq!(network.chain_id(), 137);
+        assert_eq!(network.rollup(), None);
+        assert_eq!(network.timelock_blocks_for(3_600), 1_800);
+        assert_eq!(network.default_confirmations(), 1);
+        assert!(network.validate().is_ok());
+
+        let invalid = [
+            CustomEvmNetwork::new(0, 2),
+            CustomEvmNetwork::new(56, 0),
+            CustomEvmNetwork {
+                confirmations: 0,
+                ..CustomEvmNetwork::new(56, 3)
+            },
+            // Base's chain ID, which its preset covers.
+            CustomEvmNetwork::new(8_453, 2),
+        ];
+        for custom in invalid {
+            assert!(matches!(
+                EvmNetwork::Custom(custom).validate(),
+                Err(EscrowError::InvalidChainOp(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn chain_config_network_defaults_to_none() {
+        let config: ChainConfig = serde_json::from_str(
+            r#"{"chain":"ethereum","rpc_url":"http://localhost:8545","sender_private_id":"0x01","agent_id":"0x02"}"#,
+        )
+        .unwrap();
+        assert!(config.network.is_none());
+
+        let config: ChainConfig = serde_json::from_str(
+            r#"{"chain":"ethereum","rpc_url":"http://localhost:8545","sender_private_id":"0x01","agent_id":"0x02","network":"arbitrum-sepolia"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.network, Some(EvmNetwork::ArbitrumSepolia));
+    }
+
+    #[test]
+    fn chain_config_fallback_rpc_urls() {
+        let config: ChainConfig = serde_json::from_str(
+            r#"{"chain":"solana","rpc_url":"http://localhost:8899","sender_private_id":"id.json","agent_id":"p"}"#,
+        )
+        .unwrap();
+        assert!(config.fallback_rpc_urls.is_empty());
+
+        let config: ChainConfig = serde_json::from_str(
+            r#"{"chain":"solana","rpc_url":"http://localhost:8899","fallback_rpc_urls":["http://localhost:8900"],"sender_private_id":"id.json","agent_id":"p"}"#,
+        )
+        .unwrap();
+        assert_eq!(config.fallback_rpc_urls, ["http://localhost:8900"]);
+    }
+
+    #[test]
+    fn chain_config_smart_account() {
+        let config: ChainConfig = serde_json::from_str(
+            r#"{"chain":"ethereum","rpc_url":"http://localhost:8545","sender_private_id":"0x01","agent_id":"0x02","smart_account":{"bundler_url":"http://localhost:4337","recipient_account":"0x03"}}"#,
+        )
+        .unwrap();
+        let account = config.smart_account.unwrap();
+        assert_eq!(account.bundler_url, "http://localhost:4337");
+        assert_eq!(account.recipient_account.as_deref(), Some("0x03"));
+        assert!(account.sender_account.is_none());
+        assert!(account.entry_point.is_none());
+        assert!(account.paymaster_url.is_none());
+    }
+
+    fn params_with_memo(memo: Option<&str>) -> EscrowParams {
+        EscrowParams {
+            chain_config: ChainConfig {
+                chain: Chain::Solana,
+                rpc_url: "http://localhost:8899".into(),
+                fallback_rpc_urls: Vec::new(),
+                sender_private_id: "id.json".into(),
+                agent_id: "J4SfUoLAAsvmAWMQGa8dJHw8vsSvRfUUMXGTxcmSeS8s".into(),
+                pubsub_url: None,
+                network: None,
+                smart_account: None,
+                cosmos: None,
+                substrate: None,
+                cardano: None,
+                tron: None,
+                zksync: None,
+                hedera: None,
+                custom_chain: None,
+            },
+            asset: Asset::native(crate::BigNumber::from(1u64)),
+            sender: Party::new("0xdeadbeef").unwrap(),
+            recipient: Party::new("0xcafebabe").unwrap(),
+            finish_after: Some(10),
+            cancel_after: None,
+            has_conditions: false,
+            memo: memo.map(String::from),
+            rent_destination: None,
+            recipient_contact: None,
+        }
+    }
+
+    fn metadata() -> EscrowMetadata {
+        EscrowMetadata {
+            params: params_with_memo(Some("INV-42")),
+            state: ExecutionState::Funded,
+            escrow_id: Some(7),
+            uid: None,
+            chain_metadata: None,
+            guest: None,
+            observed: None,
+        }
+    }
+
+    #[test]
+    fn guest_pin_matches_hex_image_ids() {
+        let pin = GuestPin {
+            image_id: "0xABcd01".into(),
+            version: "0.1.0".into(),
+        };
+        assert!(pin.matches("abcd01"));
+        assert!(pin.matches("0xabCD01"));
+        assert!(!pin.matches("abcd02"));
+
+        // Metadata written before pinning still loads.
+        let mut metadata = metadata();
+        let json = serde_json::to_value(&metadata).unwrap();
+        assert!(json.get("guest").is_none());
+        metadata.guest = Some(pin.clone());
+        let json = serde_json::to_string(&metadata).unwrap();
+        let parsed: EscrowMetadata = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.guest, Some(pin));
+    }
+
+    #[test]
+    fn fingerprint_ignores_local_settings() {
+        let metadata = metadata();
+        let mut local = metadata.clone();
+        local.params.chain_config.rpc_url = "https://api.devnet.solana.com".into();
+        local.params.chain_config.sender_private_id = "other.json".into();
+        local.state = ExecutionState::ConditionsMet;
+        assert_eq!(metadata.fingerprint(), local.fingerprint());
+    }
+
+    #[test]
+    fn fingerprint_distinguishes_escrows() {
+        let metadata = metadata();
+        let mut other = metadata.clone();
+        other.escrow_id = Some(8);
+        assert_ne!(metadata.fingerprint(), other.fingerprint());
+
+        let mut other = metadata.clone();
+        other.params.recipient = Party::new("0xfeedface").unwrap();
+        assert_ne!(metadata.fingerprint(), other.fingerprint());
+    }
+
+    #[test]
+    fn proof_artifact_binds_escrow() {
+        let metadata = metadata();
+        let artifact = ProofArtifact::new(&metadata, Some(&[0xab, 0xcd]));
+        assert_eq!(
+            artifact.seal_for(&metadata).unwrap(),
+            Some(vec![0xab, 0xcd])
+        );
+
+        let mut other = metadata.clone();
+        other.escrow_id = Some(8);
+        assert!(artifact.seal_for(&other).is_err());
+    }
+
+    #[test]
+    fn proof_artifact_without_fingerprint_matches_parties() {
+        let metadata = metadata();
+        let artifact: ProofArtifact = serde_json::from_str(
+            r#"{"chain":"solana","escrow_id":7,"sender":"deadbeef","recipient":"cafebabe"}"#,
+        )
+        .unwrap();
+        assert!(artifact.is_for(&metadata));
+        assert_eq!(artifact.seal_for(&metadata).unwrap(), None);
+        assert!(artifact.groth16.is_none());
+    }
+
+    #[test]
+    fn proof_artifact_round_trips_groth16_points() {
+        let point = || ["0x01".to_string(), "0x02".to_string()];
+        let artifact = ProofArtifact {
+            groth16: Some(Groth16Points {
+                pi_a: point(),
+                pi_b: [point(), point()],
+                pi_c: point(),
+            }),
+            ..ProofArtifact::new(&metadata(), None)
+        };
+        let json = serde_json::to_value(&artifact).unwrap();
+        assert_eq!(json["pi_a"][1], "0x02");
+        let parsed: ProofArtifact = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed.groth16, artifact.groth16);
+    }
+
+    #[test]
+    fn conditions_met_journal_encoding() {
+        // The Ethereum escrow contract recomputes the digest of this journal.
+        let binding = EscrowBinding {
+            fingerprint: [0x11; 32],
+            instance: [0x22; 32],
+            freshness: Freshness {
+                nonce: [0x33; 32],
+                expires_at: 0x0102,
+            },
+            chain_state: ChainState {
+                height: 0x0304,
+                hash: [0x44; 32],
+            },
+        };
+        let journal = Journal::conditions_met(binding, [0x55; 32]);
+        let encoded = journal.encode();
+        assert_eq!(encoded[..2], [0x00, 0x02]);
+        assert_eq!(encoded[2..34], [0x11; 32]);
+        assert_eq!(encoded[34..66], [0x22; 32]);
+        assert_eq!(encoded[66..98], [0x33; 32]);
+        assert_eq!(encoded[98..106], [0, 0, 0, 0, 0, 0, 0x01, 0x02]);
+        assert_eq!(encoded[106..114], [0, 0, 0, 0, 0, 0, 0x03, 0x04]);
+        assert_eq!(encoded[114..146], [0x44; 32]);
+        assert_eq!(encoded[146..], [0x55; 32]);
+        let decoded = Journal::decode(&encoded).unwrap();
+        assert_eq!(decoded.binding, binding);
+        assert_eq!(decoded.condition, [0x55; 32]);
+
+        // Seals carry the journal's tail after the escrow identity.
+        let prefix = journal.seal_prefix();
+        assert_eq!(prefix[..], encoded[66..]);
+        let mut unbound = binding;
+        unbound.freshness = Freshness::NEVER;
+        unbound.chain_state = ChainState::NONE;
+        let (rebuilt, rest) = Journal::from_seal_prefix(&prefix, unbound).unwrap();
+        assert_eq!(rebuilt.encode(), encoded);
+        assert!(rest.is_empty());
+        assert!(Journal::from_seal_prefix(&prefix[..111], unbound).is_none());
+    }
+
+    #[test]
+    fn satisfied_journal_round_trips() {
+        let binding = EscrowBinding {
+            fingerprint: [0x11; 32],
+            instance: [0x22; 32],
+            freshness: Freshness::NEVER,
+            chain_state: ChainState::NONE,
+        };
+        let mut journal = Journal::conditions_met(binding, [0x55; 32]);
+        assert_eq!(journal.result.state(), Some(ExecutionState::ConditionsMet));
+        assert_eq!(journal.result.satisfied(), None);
+
+        journal.result = ExecutionResult::Satisfied {
+            state: ExecutionState::ConditionsMet,
+            paths: vec![vec![0], vec![2, 1]],
+        };
+        let decoded = Journal::decode(&journal.encode()).unwrap();
+        assert_eq!(decoded.result.state(), Some(ExecutionState::ConditionsMet));
+        assert_eq!(decoded.result.satisfied(), Some(&[vec![0], vec![2, 1]][..]));
+        assert_eq!(ExecutionResult::Err("unmet".into()).state(), None);
+    }
+
+    #[test]
+    fn pending_states_resolve() {
+        for state in [
+            ExecutionState::Funded,
+            ExecutionState::Released,
+            ExecutionState::Refunded,
+        ] {
+            let pending = state.pending().unwrap();
+            assert!(pending.is_pending());
+            assert_eq!(pending.outcomes().unwrap().0, state);
+        }
+        assert_eq!(
+            ExecutionState::PendingFinish.outcomes(),
+            Some((ExecutionState::Released, ExecutionState::Funded))
+        );
+        assert_eq!(ExecutionState::ConditionsMet.pending(), None);
+        assert!(!ExecutionState::Funded.is_pending());
+        assert!(ExecutionState::PendingCreate.is_funded());
+    }
+
+    #[test]
+    fn ethereum_binding_identifies_contract_escrow() {
+        let mut metadata = metadata();
+        metadata.params.chain_config.chain = Chain::Ethereum;
+        metadata.params.chain_config.agent_id = "0x5FbDB2315678afecb367f032d93F642f64180aa3".into();
+        let binding = metadata.binding().unwrap();
+        assert_eq!(binding.fingerprint, metadata.fingerprint());
+
+        let mut packed = hex::decode("5FbDB2315678afecb367f032d93F642f64180aa3").unwrap();
+        packed.extend_from_slice(&[0; 24]);
+        packed.extend_from_slice(&7u64.to_be_bytes());
+        assert_eq!(binding.instance, <[u8; 32]>::from(Sha256::digest(packed)));
+
+        let mut other = metadata.clone();
+        other.escrow_id = Some(8);
+        assert_ne!(other.binding().unwrap().instance, binding.instance);
+
+        metadata.escrow_id = None;
+        assert!(metadata.binding().is_err());
+    }
+
+    #[test]
+    fn cosmos_binding_identifies_contract_escrow() {
+        let mut metadata = metadata();
+        metadata.params.chain_config.chain = Chain::Cosmos;
+        metadata.params.chain_config.agent_id =
+            "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu".into();
+        let binding = metadata.binding().unwrap();
+
+        let mut packed: Vec<u8> = (1..=20).collect();
+        packed.extend_from_slice(&7u64.to_be_bytes());
+        assert_eq!(binding.instance, <[u8; 32]>::from(Sha256::digest(packed)));
+
+        // Bech32 addresses are case-insensitive.
+        let mut upper = metadata.clone();
+        upper.params.chain_config.agent_id = upper.params.chain_config.agent_id.to_uppercase();
+        assert_eq!(upper.binding().unwrap(), binding);
+
+        metadata.escrow_id = None;
+        assert!(metadata.binding().is_err());
+    }
+
+    #[test]
+    fn substrate_binding_identifies_contract_escrow() {
+        let mut metadata = metadata();
+        metadata.params.chain_config.chain = Chain::Substrate;
+        metadata.params.chain_config.agent_id =
+            "5GrwvaEF5zXb26Fz9rcQpDWS57CtERHpNehXCPcNoHGKutQY".into();
+        let binding = metadata.binding().unwrap();
+
+        let mut packed =
+            hex::decode("d43593c715fdd31c61141abd04a99fd6822c8558854ccde39a5684e7a56da27d")
+                .unwrap();
+        packed.extend_from_slice(&7u64.to_be_bytes());
+        assert_eq!(binding.instance, <[u8; 32]>::from(Sha256::digest(packed)));
+
+        metadata.escrow_id = None;
+        assert!(metadata.binding().is_err());
+    }
+
+    #[test]
+    fn tron_binding_matches_tvm_contract_address() {
+        let mut metadata = metadata();
+        metadata.params.chain_config.chain = Chain::Tron;
+        metadata.params.chain_config.agent_id = "TJRabPrwbZy45sbavfcjinPJC18kjpRTv8".into();
+        let binding = metadata.binding().unwrap();
+
+        let mut packed = hex::decode("5cbdd86a2fa8dc4bddd8a8f69dba48572eec07fb").unwrap();
+        packed.extend_from_slice(&[0; 24]);
+        packed.extend_from_slice(&7u64.to_be_bytes());
+        assert_eq!(binding.instance, <[u8; 32]>::from(Sha256::digest(packed)));
+
+        metadata.params.chain_config.agent_id = "0x5cbdd86a2fa8dc4bddd8a8f69dba48572eec07fb".into();
+        assert!(metadata.binding().is_err());
+    }
+
+    #[test]
+    fn hedera_binding_uses_contract_evm_address() {
+        let mut metadata = metadata();
+        metadata.params.chain_config.chain = Chain::Ethereum;
+        metadata.params.chain_config.agent_id = "0x5FbDB2315678afecb367f032d93F642f64180aa3".into();
+        let ethereum = metadata.binding().unwrap();
+        metadata.params.chain_config.chain = Chain::Hedera;
+        assert_eq!(metadata.binding().unwrap().instance, ethereum.instance);
+
+        metadata.params.chain_config.agent_id = "0.0.1234".into();
+        assert!(metadata.binding().is_err());
+    }
+
+    #[test]
+    fn cardano_binding_identifies_escrow_utxo() {
+        let mut metadata = metadata();
+        metadata.params.chain_config.chain = Chain::Cardano;
+        metadata.params.chain_config.agent_id =
+            "cosmos1qypqxpq9qcrsszg2pvxq6rs0zqg3yyc5lzv7xu".into();
+        assert!(metadata.binding().is_err());
+
+        metadata.chain_metadata = Some(ChainMetadata::Cardano {
+            tx_hash: "ab".repeat(32),
+            output_index: 1,
+        });
+        let binding = metadata.binding().unwrap();
+
+        let mut packed: Vec<u8> = (1..=20).collect();
+        packed.extend_from_slice(&[0xab; 32]);
+        packed.extend_from_slice(&1u32.to_be_bytes());
+        assert_eq!(binding.instance, <[u8; 32]>::from(Sha256::digest(packed)));
+    }
+
+    #[test]
+    fn cardano_slots_round_trip_posix_time() {
+        let network = CardanoNetwork::Preprod;
+        assert_eq!(network.slot_to_posix_ms(86_400), 1_655_769_600_000);
+        assert_eq!(network.posix_ms_to_slot(1_655_769_601_999), 86_401);
+        assert_eq!(
+            network.posix_ms_to_slot(network.slot_to_posix_ms(5_000_000)),
+            5_000_000
+        );
+    }
+
+    #[test]
+    fn chain_config_cosmos() {
+        let config: ChainConfig = serde_json::from_str(
+            r#"{"chain":"cosmos","rpc_url":"http://localhost:1317","sender_private_id":"0x01","agent_id":"osmo1","cosmos":{"chain_id":"osmosis-1","denom":"uosmo","prefix":"osmo","gas_price":0.025}}"#,
+        )
+        .unwrap();
+        let cosmos = config.cosmos.unwrap();
+        assert_eq!(cosmos.chain_id, "osmosis-1");
+        assert_eq!(cosmos.gas_adjustment, CosmosConfig::DEFAULT_GAS_ADJUSTMENT);
+    }
+
+    #[test]
+    fn proof_artifact_provenance_detects_tampering() {
+        let mut artifact = ProofArtifact::new(&metadata(), Some(&[0xab; 4]));
+        assert!(artifact.verify_provenance().unwrap().is_none());
+
+        let key = [7; 32];
+        artifact.sign(&key, "prover-1 (linux/x86_64)");
+        let json = serde_json::to_string(&artifact).unwrap();
+        let parsed: ProofArtifact = serde_json::from_str(&json).unwrap();
+        let provenance = parsed.verify_provenance().unwrap().unwrap();
+        assert_eq!(provenance.host, "prover-1 (linux/x86_64)");
+        assert_eq!(
+            provenance.signer,
+            hex::encode(
+                ed25519_dalek::SigningKey::from_bytes(&key)
+                    .verifying_key()
+                    .as_bytes()
+            )
+        );
+
+        // The receipt path is local and may be rewritten.
+        let mut moved = parsed.clone();
+        moved.receipt = Some("elsewhere/receipt.bin".into());
+        assert!(moved.verify_provenance().is_ok());
+
+        let mut tampered = parsed.clone();
+        tampered.proof_seal = Some("0xabababac".into());
+        assert!(tampered.verify_provenance().is_err());
+        let mut tampered = parsed;
+        tampered.provenance.as_mut().unwrap().host = "other".into();
+        assert!(tampered.verify_provenance().is_err());
+    }
+
+    #[test]
+    fn proof_artifact_rejects_journal_for_another_escrow() {
+        let metadata = metadata();
+        let mut binding = metadata.binding().unwrap();
+        let mut artifact = ProofArtifact {
+            journal: Some(hex::encode(
+                Journal::conditions_met(binding, [0; 32]).encode(),
+            )),
+            ..ProofArtifact::new(&metadata, None)
+        };
+        assert!(artifact.seal_for(&metadata).is_ok());
+
+        binding.freshness = Freshness::until(100);
+        artifact.journal = Some(hex::encode(
+            Journal::conditions_met(binding, [0; 32]).encode(),
+        ));
+        assert!(artifact.seal_for(&metadata).is_ok());
+        assert_eq!(artifact.freshness().unwrap(), Some(Freshness::until(100)));
+
+        binding.instance = [0; 32];
+        artifact.journal = Some(hex::encode(
+            Journal::conditions_met(binding, [0; 32]).encode(),
+        ));
+        assert!(artifact.seal_for(&metadata).is_err());
+    }
+
+    #[test]
+    fn freshness_prefix_round_trips() {
+        let freshness = Freshness {
+            nonce: [7; 32],
+            expires_at: 42,
+        };
+        let seal = [freshness.to_bytes().as_slice(), &[0xaa, 0xbb]].concat();
+        let (parsed, rest) = Freshness::split_prefix(&seal).unwrap();
+        assert_eq!(parsed, freshness);
+        assert_eq!(rest, [0xaa, 0xbb]);
+        assert!(Freshness::split_prefix(&seal[..39]).is_none());
+
+        assert!(!freshness.is_expired(42));
+        assert!(freshness.is_expired(43));
+        assert!(!Freshness::NEVER.is_expired(u64::MAX));
+    }
+
+    #[test]
+    fn memo_bytes_padded() {
+        let params = params_with_memo(Some("INV-42"));
+        let bytes = params.memo_bytes().unwrap().unwrap();
+        assert_eq!(&bytes[..6], b"INV-42");
+        assert!(bytes[6..].iter().all(|b| *b == 0));
+
+        assert!(params_with_memo(None).memo_bytes().unwrap().is_none());
+    }
+
+    #[test]
+    fn memo_bytes_too_long() {
+        let long = "x".repeat(MAX_MEMO_LEN + 1);
+        let err = params_with_memo(Some(&long)).memo_bytes().unwrap_err();
+        assert!(matches!(
+            err,
+            EscrowError::MemoTooLong { len, max } if len == MAX_MEMO_LEN + 1 && max == MAX_MEMO_LEN
+        ));
     }
 
     #[test]
@@ -4,6 +4,7 @@
 #![doc = include_str!("../README.md")]
 
 pub mod asset;
+pub mod auth;
 pub mod bignum;
 pub mod condition;
 pub mod error;
@@ -18,11 +19,16 @@ pub mod serde;
 
 pub use asset::{Asset, AssetKind};
 pub use bignum::BigNumber;
-pub use condition::Condition;
+pub use condition::{Condition, Verification};
 pub use error::EscrowError;
 pub use escrow::Escrow;
-pub use identity::{Party, ID};
-pub use interface::{Chain, ChainConfig, EscrowMetadata, EscrowParams, ExecutionState};
+pub use identity::{EscrowId, Party, ID};
+pub use interface::{
+    AvalancheSubnet, CardanoConfig, CardanoNetwork, Chain, ChainConfig, ChainMetadata,
+    CosmosConfig, CustomEvmNetwork, EscrowMetadata, EscrowParams, EvmNetwork, ExecutionState,
+    HederaConfig, IbcSettler, ObservedBlock, Rollup, SmartAccountConfig, SponsorshipPolicy,
+    SubstrateConfig, TronConfig, ZksyncConfig,
+};
 
 /// `Result` type for all core operations.
 pub type Result<T> = std::result::Result<T, EscrowError>;
@@ -52,12 +52,43 @@ pub mod utf8_serde {
     }
 }
 
+/// Serde helper to (de)serialize `Vec<[u8; N]>`, e.g. public keys or
+/// addresses, as hex strings.
+#[cfg(feature = "json")]
+pub mod hex_keys_serde {
+    use serde::{de, Deserialize, Deserializer, Serializer};
+
+    /// Serialize each key as a hex string.
+    pub fn serialize<S, const N: usize>(keys: &[[u8; N]], serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_seq(keys.iter().map(hex::encode))
+    }
+
+    /// Deserialize hex strings into keys.
+    pub fn deserialize<'de, D, const N: usize>(deserializer: D) -> Result<Vec<[u8; N]>, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        Vec::<String>::deserialize(deserializer)?
+            .iter()
+            .map(|s| {
+                let mut key = [0; N];
+                hex::decode_to_slice(s, &mut key)
+                    .map_err(|e| de::Error::custom(format!("invalid key {s}: {e}")))?;
+                Ok(key)
+            })
+            .collect()
+    }
+}
+
 #[cfg(all(test, feature = "json"))]
 mod tests {
     use num_bigint::BigUint;
     use serde::{Deserialize, Serialize};
 
-    use super::{biguint_serde, utf8_serde};
+    use super::{biguint_serde, hex_keys_serde, utf8_serde};
 
     #[derive(Serialize, Deserialize, PartialEq, Debug)]
     struct BigUintWrapper(#[serde(with = "biguint_serde")] BigUint);
@@ -96,4 +127,17 @@ mod tests {
         let bad = Utf8Wrapper(vec![0xff, 0xfe]);
         assert!(serde_json::to_string(&bad).is_err());
     }
+
+    #[derive(Serialize, Deserialize, PartialEq, Debug)]
+    struct KeysWrapper(#[serde(with = "hex_keys_serde")] Vec<[u8; 32]>);
+
+    #[test]
+    fn hex_keys_roundtrip() {
+        let wrapped = KeysWrapper(vec![[0xab; 32]]);
+        let ser = serde_json::to_string(&wrapped).unwrap();
+        assert_eq!(ser, format!("[\"{}\"]", "ab".repeat(32)));
+        let de: KeysWrapper = serde_json::from_str(&ser).unwrap();
+        assert_eq!(de, wrapped);
+        assert!(serde_json::from_str::<KeysWrapper>("[\"abcd\"]").is_err());
+    }
 }
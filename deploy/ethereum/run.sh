@@ -5,12 +5,13 @@
 # Deploys the Zescrow Escrow contract to Ethereum (local or Sepolia).
 #
 # Usage:
-#   ./deploy/ethereum/run.sh [--network local|sepolia]
+#   ./deploy/ethereum/run.sh [--network local|sepolia|arbitrum-sepolia|optimism-sepolia|base-sepolia]
 #
 # Examples:
 #   ./deploy/ethereum/run.sh                   # Defaults to local
 #   ./deploy/ethereum/run.sh --network local   # Local Hardhat node
 #   ./deploy/ethereum/run.sh --network sepolia # Sepolia testnet
+#   ./deploy/ethereum/run.sh --network base-sepolia # Base Sepolia testnet
 #
 # Prerequisites:
 #   - Node.js and npm installed
@@ -35,7 +36,7 @@ while [[ $# -gt 0 ]]; do
             ;;
         *)
             echo "Unknown option: $1"
-            echo "Usage: $0 [--network local|sepolia]"
+            echo "Usage: $0 [--network local|sepolia|arbitrum-sepolia|optimism-sepolia|base-sepolia]"
             exit 1
             ;;
     esac
@@ -58,8 +59,28 @@ case $NETWORK in
             exit 1
         fi
         ;;
+    arbitrum-sepolia|optimism-sepolia|base-sepolia)
+        case $NETWORK in
+            arbitrum-sepolia)
+                RPC_URL="${ETHEREUM_RPC_URL:-https://sepolia-rollup.arbitrum.io/rpc}"
+                HARDHAT_NETWORK="arbitrumSepolia"
+                ;;
+            optimism-sepolia)
+                RPC_URL="${ETHEREUM_RPC_URL:-https://sepolia.optimism.io}"
+                HARDHAT_NETWORK="optimismSepolia"
+                ;;
+            base-sepolia)
+                RPC_URL="${ETHEREUM_RPC_URL:-https://sepolia.base.org}"
+                HARDHAT_NETWORK="baseSepolia"
+                ;;
+        esac
+        if [[ -z "${ETHEREUM_SENDER_PRIVATE_KEY:-}" ]]; then
+            echo "Error: ETHEREUM_SENDER_PRIVATE_KEY environment variable is required for $NETWORK"
+            exit 1
+        fi
+        ;;
     *)
-        echo "Error: Invalid network '$NETWORK'. Use 'local' or 'sepolia'."
+        echo "Error: Invalid network '$NETWORK'. Use 'local', 'sepolia', 'arbitrum-sepolia', 'optimism-sepolia', or 'base-sepolia'."
         exit 1
         ;;
 esac
@@ -2,21 +2,61 @@
 
 use bincode::config::standard;
 use risc0_zkvm::guest::env;
-use zescrow_core::interface::ExecutionResult;
-use zescrow_core::Escrow;
+use zescrow_core::interface::{EscrowBinding, ExecutionResult, Journal};
+use zescrow_core::{Condition, Escrow, EscrowMetadata};
 
 /// Expects from the host:
-/// - `Escrow` object decoded from bytes containing escrow transaction details.
+/// - `EscrowMetadata` of the escrow, without RPC endpoints and keys, and
+///   the `Condition` fulfilling it, whose fulfillment may hold committee
+///   attestations of another chain's state or bridge messages from it,
+///   verified here like any other fulfillment.
+/// - `EscrowBinding` identifying the escrow instance and the proof's
+///   freshness, committed to the journal so the receipt cannot finish
+///   another escrow, nor be reused once stale, and any recent chain state
+///   the host attests, committed for verifiers to check. Its fingerprint
+///   must be that of the escrow proven.
+/// - Whether to commit the paths of the satisfied subconditions (see
+///   `ExecutionResult::Satisfied`).
 fn main() {
     let bytes: Vec<u8> = env::read_frame();
-    let (mut escrow, _): (Escrow, _) =
+    let ((metadata, condition), _): ((EscrowMetadata, Option<Condition>), _) =
         bincode::decode_from_slice(&bytes, standard()).expect("failed to decode from slice");
+    let bytes: Vec<u8> = env::read_frame();
+    let (binding, _): (EscrowBinding, _) =
+        bincode::decode_from_slice(&bytes, standard()).expect("failed to decode from slice");
+    let bytes: Vec<u8> = env::read_frame();
+    let (report_satisfied, _): (bool, _) =
+        bincode::decode_from_slice(&bytes, standard()).expect("failed to decode from slice");
+
+    // The fingerprint is recomputed here, so the journal only binds the
+    // escrow whose condition was verified.
+    assert_eq!(
+        binding.fingerprint,
+        metadata.fingerprint(),
+        "binding is for another escrow"
+    );
+    let mut escrow =
+        Escrow::from_metadata(metadata, condition).expect("escrow has conditions but none given");
 
+    // Commit only a fingerprint of the condition, keeping its keys,
+    // messages, and fulfillment private.
+    let condition = escrow.condition_fingerprint();
     let result = escrow
         .execute()
-        .map(ExecutionResult::Ok)
+        .map(|state| match &escrow.condition {
+            // Every subcondition is verified again to report all that hold.
+            Some(condition) if report_satisfied => ExecutionResult::Satisfied {
+                state,
+                paths: condition.verification().satisfied_paths(),
+            },
+            _ => ExecutionResult::Ok(state),
+        })
         .unwrap_or_else(|e| ExecutionResult::Err(e.to_string()));
 
-    let result = bincode::encode_to_vec(&result, standard()).expect("failed to encode to vec");
-    env::commit_slice(&result);
+    let journal = Journal {
+        result,
+        binding,
+        condition,
+    };
+    env::commit_slice(&journal.encode());
 }
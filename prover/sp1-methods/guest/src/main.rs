@@ -0,0 +1,62 @@
+//! The SP1 guest, reading the same input and committing the same journal
+//! as the RISC Zero guest.
+
+#![no_main]
+sp1_zkvm::entrypoint!(main);
+
+use bincode::config::standard;
+use zescrow_core::interface::{EscrowBinding, ExecutionResult, Journal};
+use zescrow_core::{Condition, Escrow, EscrowMetadata};
+
+/// Expects from the host:
+/// - `EscrowMetadata` of the escrow, without RPC endpoints and keys, and
+///   the `Condition` fulfilling it, including any cross-chain attestations
+///   and bridge messages it holds.
+/// - `EscrowBinding` identifying the escrow instance and the proof's
+///   freshness, and any attested chain state, committed to the journal.
+///   Its fingerprint must be that of the escrow proven.
+/// - Whether to commit the paths of the satisfied subconditions (see
+///   `ExecutionResult::Satisfied`).
+fn main() {
+    let bytes = sp1_zkvm::io::read_vec();
+    let ((metadata, condition), _): ((EscrowMetadata, Option<Condition>), _) =
+        bincode::decode_from_slice(&bytes, standard()).expect("failed to decode from slice");
+    let bytes = sp1_zkvm::io::read_vec();
+    let (binding, _): (EscrowBinding, _) =
+        bincode::decode_from_slice(&bytes, standard()).expect("failed to decode from slice");
+    let bytes = sp1_zkvm::io::read_vec();
+    let (report_satisfied, _): (bool, _) =
+        bincode::decode_from_slice(&bytes, standard()).expect("failed to decode from slice");
+
+    // The fingerprint is recomputed here, so the journal only binds the
+    // escrow whose condition was verified.
+    assert_eq!(
+        binding.fingerprint,
+        metadata.fingerprint(),
+        "binding is for another escrow"
+    );
+    let mut escrow =
+        Escrow::from_metadata(metadata, condition).expect("escrow has conditions but none given");
+
+    // Commit only a fingerprint of the condition, keeping its keys,
+    // messages, and fulfillment private.
+    let condition = escrow.condition_fingerprint();
+    let result = escrow
+        .execute()
+        .map(|state| match &escrow.condition {
+            // Every subcondition is verified again to report all that hold.
+            Some(condition) if report_satisfied => ExecutionResult::Satisfied {
+                state,
+                paths: condition.verification().satisfied_paths(),
+            },
+            _ => ExecutionResult::Ok(state),
+        })
+        .unwrap_or_else(|e| ExecutionResult::Err(e.to_string()));
+
+    let journal = Journal {
+        result,
+        binding,
+        condition,
+    };
+    sp1_zkvm::io::commit_slice(&journal.encode());
+}
@@ -0,0 +1,4 @@
+//! The SP1 guest program, built for the SP1 zkVM.
+
+/// ELF of the SP1 guest program.
+pub const ZESCROW_SP1_GUEST_ELF: &[u8] = include_bytes!(env!("SP1_ELF_zescrow_sp1_guest"));
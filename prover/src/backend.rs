@@ -0,0 +1,136 @@
+//! Proving stacks behind one interface.
+//!
+//! Every backend runs a guest program reading the same frames, the
+//! bincode-encoded [`EscrowMetadata`] and [`Condition`] of the escrow and
+//! its [`EscrowBinding`], and committing the same
+//! [`Journal`], so verifiers of the journal need not know which stack
+//! proved it.
+
+use anyhow::Context;
+use bincode::config::standard;
+use zescrow_core::interface::{EscrowBinding, Journal};
+use zescrow_core::{Condition, EscrowMetadata};
+
+use crate::{
+    decode_journal, guest_image_id, prove_escrow_receipt_with, verify_receipt, ProverBackend,
+    ProverError, Receipt,
+};
+
+/// A zkVM proving that escrow conditions are fulfilled.
+pub trait ZkBackend {
+    /// Name of the proving stack, recorded with its proofs.
+    fn name(&self) -> &'static str;
+
+    /// Identifier of the guest program proofs are verified against (the
+    /// image ID or verifying key hash), as hex.
+    fn program_id(&self) -> String;
+
+    /// Proves that `condition` fulfills the escrow in `metadata`, bound to
+    /// the escrow instance `binding` identifies.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if proving fails, the proof does not verify, or
+    /// the escrow conditions are not met.
+    fn prove(
+        &self,
+        metadata: &EscrowMetadata,
+        condition: Option<&Condition>,
+        binding: EscrowBinding,
+    ) -> anyhow::Result<ZkProof>;
+
+    /// Verifies `proof` and returns the journal it commits to.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProverError::VerifyReceipt`] if `proof` was produced by
+    /// another backend or does not verify.
+    fn verify(&self, proof: &ZkProof) -> anyhow::Result<Journal>;
+}
+
+/// Proof produced by a [`ZkBackend`].
+#[derive(Debug, Clone)]
+pub struct ZkProof {
+    /// [`ZkBackend::name`] of the backend that produced it.
+    pub backend: &'static str,
+    /// Encoded [`Journal`].
+    pub journal: Vec<u8>,
+    /// Backend-specific encoding of the proof.
+    pub proof: Vec<u8>,
+}
+
+impl ZkProof {
+    /// Checks that the proof was produced by `backend`.
+    pub(crate) fn check_backend(&self, backend: &dyn ZkBackend) -> Result<(), ProverError> {
+        if self.backend != backend.name() {
+            return Err(ProverError::VerifyReceipt(format!(
+                "proof was produced by {}, not {}",
+                self.backend,
+                backend.name()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// The RISC Zero zkVM, proving locally or on Bonsai. Its proofs are
+/// bincode-encoded [`Receipt`]s.
+#[derive(Debug, Clone, Default)]
+pub struct RiscZeroBackend {
+    /// Where proofs are generated.
+    pub config: ProverBackend,
+    /// Compress proofs to Groth16, e.g. for on-chain verification.
+    pub groth16: bool,
+}
+
+impl RiscZeroBackend {
+    /// Creates a backend proving on the prover `config` selects.
+    pub fn new(config: ProverBackend) -> Self {
+        Self {
+            config,
+            groth16: false,
+        }
+    }
+
+    /// Compresses proofs to Groth16.
+    pub fn groth16(mut self, groth16: bool) -> Self {
+        self.groth16 = groth16;
+        self
+    }
+}
+
+impl ZkBackend for RiscZeroBackend {
+    fn name(&self) -> &'static str {
+        "risc0"
+    }
+
+    fn program_id(&self) -> String {
+        guest_image_id()
+    }
+
+    fn prove(
+        &self,
+        metadata: &EscrowMetadata,
+        condition: Option<&Condition>,
+        binding: EscrowBinding,
+    ) -> anyhow::Result<ZkProof> {
+        let receipt =
+            prove_escrow_receipt_with(metadata, condition, binding, self.groth16, &self.config)?;
+        let proof = bincode::serde::encode_to_vec(&receipt, standard())
+            .with_context(|| "failed to encode receipt")?;
+        Ok(ZkProof {
+            backend: self.name(),
+            journal: receipt.journal.bytes,
+            proof,
+        })
+    }
+
+    fn verify(&self, proof: &ZkProof) -> anyhow::Result<Journal> {
+        proof.check_backend(self)?;
+        let (receipt, _): (Receipt, _) =
+            bincode::serde::decode_from_slice(&proof.proof, standard())
+                .with_context(|| "failed to decode receipt")?;
+        verify_receipt(&receipt)?;
+        decode_journal(&receipt.journal.bytes)
+    }
+}
@@ -0,0 +1,419 @@
+//! `zescrow-bench`: measures proving time, cycles, and peak memory across a
+//! matrix of condition types and tree sizes, and prints a JSON report.
+//!
+//! Each case runs in a child process, so that its peak memory is measured
+//! on its own.
+
+use std::path::PathBuf;
+use std::process::Command;
+use std::time::Instant;
+
+use anyhow::{bail, Context};
+use clap::{Parser, Subcommand, ValueEnum};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use zescrow_core::interface::{ChainConfig, ChainState, EscrowBinding, Freshness};
+use zescrow_core::{
+    Asset, BigNumber, Chain, Condition, EscrowMetadata, EscrowParams, ExecutionState, Party,
+};
+use zescrow_prover::{
+    count_cycles, guest_image_id, prove_escrow_receipt_with, Accelerator, ProverBackend,
+    ProvingOptions, GUEST_VERSION,
+};
+
+#[derive(Parser)]
+#[command(author, version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Benchmark every combination of condition type and tree size
+    Run {
+        /// Condition types to benchmark [default: all]
+        #[arg(long, value_delimiter = ',')]
+        conditions: Vec<Kind>,
+
+        /// Tree sizes: a size of N proves a threshold of N out of N
+        /// conditions of the type, and 1 a single condition
+        #[arg(long, value_delimiter = ',', default_values_t = [1, 4, 16])]
+        sizes: Vec<usize>,
+
+        /// Write the report to this file instead of stdout
+        #[arg(long)]
+        out: Option<PathBuf>,
+
+        #[command(flatten)]
+        case: CaseArgs,
+    },
+    /// Benchmark a single case, reporting it as JSON (run by `run`)
+    #[command(hide = true)]
+    Case {
+        #[arg(long)]
+        condition: Kind,
+
+        #[arg(long)]
+        size: usize,
+
+        #[command(flatten)]
+        case: CaseArgs,
+    },
+}
+
+/// How each case is measured.
+#[derive(clap::Args, Clone, Copy)]
+struct CaseArgs {
+    /// Only execute the guest and count cycles, without proving
+    #[arg(long)]
+    execute_only: bool,
+
+    /// Compress proofs to Groth16, as for Ethereum (needs Docker or an x86
+    /// host with the Groth16 prover)
+    #[arg(long, conflicts_with = "execute_only")]
+    groth16: bool,
+
+    /// Segment size, as a power of two of cycles [default: 20]
+    #[arg(long, value_name = "PO2")]
+    segment_po2: Option<u32>,
+
+    /// Worker threads [default: one per core]
+    #[arg(long, value_name = "N")]
+    threads: Option<usize>,
+}
+
+impl CaseArgs {
+    fn to_args(self) -> Vec<String> {
+        let mut args = Vec::new();
+        if self.execute_only {
+            args.push("--execute-only".into());
+        }
+        if self.groth16 {
+            args.push("--groth16".into());
+        }
+        if let Some(po2) = self.segment_po2 {
+            args.extend(["--segment-po2".into(), po2.to_string()]);
+        }
+        if let Some(threads) = self.threads {
+            args.extend(["--threads".into(), threads.to_string()]);
+        }
+        args
+    }
+}
+
+/// Condition type of a case.
+#[derive(ValueEnum, Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+enum Kind {
+    Hashlock,
+    Ed25519,
+    Secp256k1,
+    CrossChain,
+    Bridge,
+}
+
+/// Benchmark report.
+#[derive(Debug, Serialize)]
+struct Report {
+    guest_version: &'static str,
+    image_id: String,
+    mode: &'static str,
+    /// Hardware this build proves on.
+    accelerator: String,
+    segment_po2: Option<u32>,
+    threads: Option<usize>,
+    cases: Vec<CaseReport>,
+}
+
+/// Measurements of one case.
+#[derive(Debug, Serialize, Deserialize)]
+struct CaseReport {
+    condition: Kind,
+    size: usize,
+    /// Cycles proven, with segments padded to a power of two.
+    cycles: Option<u64>,
+    /// Time to execute the guest and count cycles.
+    execute_ms: Option<u128>,
+    /// Time to prove, including Groth16 compression if requested.
+    prove_ms: Option<u128>,
+    /// Peak resident memory of the process (Linux only).
+    peak_memory_bytes: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::filter::EnvFilter::from_default_env())
+        .with_writer(std::io::stderr)
+        .init();
+
+    match Cli::parse().command {
+        Commands::Run {
+            conditions,
+            sizes,
+            out,
+            case,
+        } => {
+            let conditions = if conditions.is_empty() {
+                Kind::value_variants().to_vec()
+            } else {
+                conditions
+            };
+            let mut cases = Vec::new();
+            for condition in conditions {
+                for &size in &sizes {
+                    eprintln!("Benchmarking {condition:?} x {size}");
+                    cases.push(run_case(condition, size, case)?);
+                }
+            }
+            let report = Report {
+                guest_version: GUEST_VERSION,
+                image_id: guest_image_id(),
+                mode: match (case.execute_only, case.groth16) {
+                    (true, _) => "execute",
+                    (false, true) => "groth16",
+                    (false, false) => "prove",
+                },
+                accelerator: Accelerator::built().to_string(),
+                segment_po2: case.segment_po2,
+                threads: case.threads,
+                cases,
+            };
+            let json = serde_json::to_string_pretty(&report)?;
+            match out {
+                Some(path) => std::fs::write(&path, json)
+                    .with_context(|| format!("failed to write {}", path.display()))?,
+                None => println!("{json}"),
+            }
+            Ok(())
+        }
+        Commands::Case {
+            condition,
+            size,
+            case,
+        } => {
+            let report = measure(condition, size, case);
+            println!("{}", serde_json::to_string(&report)?);
+            Ok(())
+        }
+    }
+}
+
+/// Benchmarks a case in a child process.
+fn run_case(condition: Kind, size: usize, case: CaseArgs) -> anyhow::Result<CaseReport> {
+    let condition_arg = condition
+        .to_possible_value()
+        .expect("condition kinds are not skipped");
+    let output = Command::new(std::env::current_exe()?)
+        .args(["case", "--condition", condition_arg.get_name()])
+        .args(["--size", &size.to_string()])
+        .args(case.to_args())
+        .output()
+        .with_context(|| "failed to start the benchmark case")?;
+    if !output.status.success() {
+        bail!(
+            "benchmark case {condition:?} x {size} failed: {}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    serde_json::from_slice(&output.stdout).with_context(|| "failed to decode the case report")
+}
+
+/// Benchmarks a case in this process.
+fn measure(condition: Kind, size: usize, case: CaseArgs) -> CaseReport {
+    let mut report = CaseReport {
+        condition,
+        size,
+        cycles: None,
+        execute_ms: None,
+        prove_ms: None,
+        peak_memory_bytes: None,
+        error: None,
+    };
+    let metadata = metadata();
+    let condition = build_condition(condition, size);
+    let binding = EscrowBinding {
+        fingerprint: metadata.fingerprint(),
+        instance: [0; 32],
+        freshness: Freshness::NEVER,
+        chain_state: ChainState::NONE,
+    };
+
+    let start = Instant::now();
+    match count_cycles(&metadata, Some(&condition), binding) {
+        Ok(cycles) => {
+            report.cycles = Some(cycles);
+            report.execute_ms = Some(start.elapsed().as_millis());
+        }
+        Err(e) => report.error = Some(format!("{e:#}")),
+    }
+    if report.error.is_none() && !case.execute_only {
+        let config = ProverBackend::Local(ProvingOptions {
+            segment_po2: case.segment_po2,
+            threads: case.threads,
+            ..ProvingOptions::default()
+        });
+        let start = Instant::now();
+        match prove_escrow_receipt_with(&metadata, Some(&condition), binding, case.groth16, &config)
+        {
+            Ok(_) => report.prove_ms = Some(start.elapsed().as_millis()),
+            Err(e) => report.error = Some(format!("{e:#}")),
+        }
+    }
+    report.peak_memory_bytes = peak_memory();
+    report
+}
+
+/// Returns the metadata of a funded escrow with conditions.
+fn metadata() -> EscrowMetadata {
+    let party = |address| Party::new(address).expect("valid address");
+    EscrowMetadata {
+        params: EscrowParams {
+            chain_config: ChainConfig {
+                chain: Chain::Ethereum,
+                rpc_url: String::new(),
+                fallback_rpc_urls: Vec::new(),
+                sender_private_id: String::new(),
+                agent_id: "0x0000000000000000000000000000000000000001".into(),
+                pubsub_url: None,
+                network: None,
+                smart_account: None,
+                cosmos: None,
+                substrate: None,
+                cardano: None,
+                tron: None,
+                zksync: None,
+                hedera: None,
+                custom_chain: None,
+            },
+            asset: Asset::native(BigNumber::from(1_000u64)),
+            sender: party("0xd8dA6BF26964aF9D7eEd9e03E53415D37aA96045"),
+            recipient: party("0xEA674fdDe714fd979de3EdF0F56AA9716B898ec8"),
+            finish_after: None,
+            cancel_after: None,
+            has_conditions: true,
+            memo: None,
+            rent_destination: None,
+            recipient_contact: None,
+        },
+        state: ExecutionState::Funded,
+        escrow_id: Some(1),
+        uid: None,
+        chain_metadata: None,
+        guest: None,
+        observed: None,
+    }
+}
+
+/// Returns a fulfilled condition of type `kind`, or a threshold of `size`
+/// out of `size` of them.
+fn build_condition(kind: Kind, size: usize) -> Condition {
+    let mut leaves = (0..size.max(1)).map(|i| leaf(kind, i as u8));
+    if size <= 1 {
+        return leaves.next().expect("one leaf");
+    }
+    Condition::threshold(size, leaves.collect())
+}
+
+/// Returns the `i`th fulfilled condition of type `kind`, from fixed keys.
+fn leaf(kind: Kind, i: u8) -> Condition {
+    let message = format!("zescrow-bench-{i}").into_bytes();
+    match kind {
+        Kind::Hashlock => Condition::hashlock(Sha256::digest(&message).into(), message),
+        Kind::Ed25519 => {
+            use ed25519_dalek::{Signer, SigningKey};
+
+            let key = SigningKey::from_bytes(&[i.wrapping_add(1); 32]);
+            let signature = key.sign(&message).to_bytes().to_vec();
+            Condition::ed25519(key.verifying_key().to_bytes(), message, signature)
+        }
+        Kind::Secp256k1 => {
+            use k256::ecdsa::signature::Signer;
+            use k256::ecdsa::{Signature, SigningKey};
+
+            let key = SigningKey::from_bytes(&[i.wrapping_add(1); 32].into())
+                .expect("valid secp256k1 key");
+            let signature: Signature = key.sign(&message);
+            let public_key = key.verifying_key().to_encoded_point(true);
+            Condition::secp256k1(
+                public_key.as_bytes().to_vec(),
+                message,
+                signature.to_der().as_bytes().to_vec(),
+            )
+        }
+        Kind::CrossChain => {
+            use ed25519_dalek::SigningKey;
+            use zescrow_core::condition::cross_chain::{Observation, RemoteClaim};
+
+            let keys: Vec<SigningKey> = (0..3)
+                .map(|k| SigningKey::from_bytes(&[i.wrapping_mul(3).wrapping_add(k + 1); 32]))
+                .collect();
+            let claim = RemoteClaim::Escrow {
+                instance: Sha256::digest(&message).into(),
+                state: ExecutionState::Released,
+            };
+            let observation = Observation {
+                chain: "eip155:1".into(),
+                claim: claim.clone(),
+                height: 1,
+                block_hash: [i; 32],
+            };
+            let attestors = keys.iter().map(|k| k.verifying_key().to_bytes()).collect();
+            let mut condition = Condition::cross_chain("eip155:1", claim, attestors, 2);
+            if let Condition::CrossChain(cross_chain) = &mut condition {
+                for key in &keys[..2] {
+                    cross_chain.attest(&observation, key).expect("attestor key");
+                }
+            }
+            condition
+        }
+        Kind::Bridge => {
+            use k256::ecdsa::SigningKey;
+            use zescrow_core::condition::bridge::{address, BridgeMessage, Vaa, Verifiers};
+
+            // A VAA signed by a quorum (13) of a guardian set as large as
+            // Wormhole's mainnet one (19).
+            let keys: Vec<SigningKey> = (0..19u8)
+                .map(|k| {
+                    SigningKey::from_bytes(&[i.wrapping_mul(19).wrapping_add(k + 1); 32].into())
+                        .expect("valid secp256k1 key")
+                })
+                .collect();
+            let mut vaa = Vaa {
+                guardian_set_index: 4,
+                signatures: Vec::new(),
+                timestamp: 1,
+                nonce: 0,
+                emitter_chain: 2,
+                emitter_address: [i; 32],
+                sequence: u64::from(i),
+                consistency_level: 1,
+                payload: message,
+            };
+            for (guardian, key) in (0u8..).zip(&keys[..13]) {
+                vaa.sign(guardian, key);
+            }
+            let verifiers = Verifiers::Wormhole {
+                guardian_set_index: 4,
+                guardians: keys.iter().map(|k| address(k.verifying_key())).collect(),
+            };
+            let payload_hash = Sha256::digest(&vaa.payload).into();
+            let mut condition = Condition::bridge(verifiers, 2, [i; 32], payload_hash);
+            if let Condition::Bridge(bridge) = &mut condition {
+                bridge.message = BridgeMessage::vaa(vaa.to_bytes());
+            }
+            condition
+        }
+    }
+}
+
+/// Returns the peak resident memory of this process, from `VmHWM` in
+/// `/proc/self/status`.
+fn peak_memory() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with("VmHWM:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
@@ -12,30 +12,81 @@
 //! # Usage
 //!
 //! ```ignore
-//! use zescrow_prover::run;
+//! use zescrow_prover::{run, ProverConfig};
 //!
-//! // Reads escrow metadata from deploy/escrow_metadata.json
-//! // and generates a proof if conditions are met
-//! run()?;
+//! // Generates a proof if the escrow's conditions are met
+//! let config = ProverConfig::new(metadata)
+//!     .conditions(condition)
+//!     .output("proof_data.json");
+//! let proof = run(&config)?;
 //! ```
 //!
 //! # Workflow
 //!
-//! 1. Load escrow metadata from JSON file
-//! 2. Encode escrow context for the guest program
-//! 3. Execute the zkVM to generate a proof
-//! 4. Verify the receipt against the guest program ID
-//! 5. Decode and validate the execution result
+//! 1. Encode the escrow's metadata and condition, and the escrow instance
+//!    it is bound to, for the guest program
+//! 2. Execute the zkVM to generate a proof, the guest constructing the
+//!    escrow context and checking that the binding fingerprints it
+//! 3. Verify the receipt against the guest program ID
+//! 4. Decode and validate the execution result and binding
+//!
+//! Proofs are generated locally, or with [`ProverBackend::Remote`] on the
+//! Bonsai proving service.
+//!
+//! The functions above prove with RISC Zero. [`ZkBackend`] abstracts the
+//! proving stack, with [`RiscZeroBackend`] and, with the `sp1` feature,
+//! `Sp1Backend`, sharing the escrow encoding and journal schema.
+
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 
 use anyhow::Context;
 use bincode::config::standard;
-use risc0_zkvm::{default_prover, ExecutorEnv, Receipt};
+use flate2::read::GzDecoder;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+pub use risc0_zkvm::sha::Digest;
+use risc0_zkvm::sha::{Digestible, Impl, Sha256};
+pub use risc0_zkvm::Receipt;
+use risc0_zkvm::{
+    default_executor, ExecutorEnv, Groth16Receipt, Groth16ReceiptVerifierParameters, InnerReceipt,
+    LocalProver, Prover, ProverOpts, ReceiptClaim, VerifierContext,
+};
+use serde::de::DeserializeOwned;
 use thiserror::Error;
 use tracing::{info, info_span};
-use zescrow_core::interface::{load_escrow_data, ExecutionResult, ESCROW_METADATA_PATH};
-use zescrow_core::{Escrow, EscrowMetadata, ExecutionState};
+use zescrow_core::interface::{
+    load_escrow_data, EscrowBinding, ExecutionResult, Journal, ProofArtifact,
+};
+use zescrow_core::{Chain, Condition, Escrow, EscrowMetadata, ExecutionState};
 use zescrow_methods::{ZESCROW_GUEST_ELF, ZESCROW_GUEST_ID};
 
+pub use crate::backend::{RiscZeroBackend, ZkBackend, ZkProof};
+pub use crate::remote::RemoteConfig;
+#[cfg(feature = "sp1")]
+pub use crate::sp1::Sp1Backend;
+
+/// Version of this crate, and of the guest program it embeds, recorded in
+/// proof artifacts.
+pub const GUEST_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Leading bytes of gzip streams, by which [`load_receipt`] tells
+/// compressed receipts from ones written uncompressed.
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+
+/// Selector of Ethereum seals made for fake (dev-mode) receipts, which only
+/// the `DevModeVerifier` test contract accepts.
+#[cfg(feature = "dev-mode")]
+pub const DEV_MODE_SELECTOR: [u8; 4] = [0; 4];
+
+mod backend;
+mod remote;
+#[cfg(feature = "server")]
+pub mod server;
+#[cfg(feature = "sp1")]
+mod sp1;
+
 /// Errors that can occur during proof generation and verification.
 #[derive(Debug, Error)]
 #[non_exhaustive]
@@ -44,6 +95,15 @@ pub enum ProverError {
     #[error("receipt verification failed: {0}")]
     VerifyReceipt(String),
 
+    /// A receipt or journal could not be decoded.
+    #[error("decode failed: {0}")]
+    Decode(String),
+
+    /// Executing or proving the guest program failed, e.g. it panicked on
+    /// its input or the prover ran out of memory.
+    #[error("proving failed: {0}")]
+    Proving(String),
+
     /// Escrow execution produced an unexpected state.
     #[error("unexpected escrow state: expected ConditionsMet, got {0:?}")]
     UnexpectedState(ExecutionState),
@@ -51,70 +111,1107 @@ pub enum ProverError {
     /// Escrow condition verification failed inside the zkVM.
     #[error("condition verification failed: {0}")]
     ConditionFailed(String),
+
+    /// Journal is bound to another escrow than the one proven.
+    #[error("journal is bound to another escrow")]
+    BindingMismatch,
+
+    /// Proof was recorded for another guest program than the pinned one.
+    #[error("proof is for image ID {actual}, expected {expected}")]
+    ImageIdMismatch {
+        /// Pinned image ID, as hex.
+        expected: String,
+        /// Image ID recorded with the proof, as hex.
+        actual: String,
+    },
+
+    /// Receipt cannot be verified on-chain.
+    #[error("receipt is not a Groth16 receipt")]
+    NotGroth16,
+
+    /// Groth16 seal is not made of the expected proof points.
+    #[error("malformed Groth16 seal: expected {expected} bytes, got {actual}")]
+    MalformedSeal {
+        /// Expected seal length.
+        expected: usize,
+        /// Actual seal length.
+        actual: usize,
+    },
+
+    /// Dev mode was requested from a build without the `dev-mode` feature.
+    #[error("dev mode requires building with the `dev-mode` feature")]
+    DevModeDisabled,
+
+    /// The requested accelerator is not built in or not present.
+    #[error("cannot prove on {requested}: {reason}")]
+    AcceleratorUnavailable {
+        /// Accelerator requested.
+        requested: Accelerator,
+        /// Why it cannot be used.
+        reason: String,
+    },
+
+    /// Remote proving service request failed.
+    #[error("remote proving failed: {0}")]
+    Remote(String),
+
+    /// Remote proving service did not produce a proof in time.
+    #[error("remote proving timed out after {0:?}")]
+    RemoteTimeout(Duration),
+}
+
+/// Where proofs are generated.
+#[derive(Debug, Clone)]
+pub enum ProverBackend {
+    /// Prove on this machine, with the given options.
+    Local(ProvingOptions),
+    /// Prove on the Bonsai proving service.
+    Remote(RemoteConfig),
+}
+
+impl Default for ProverBackend {
+    fn default() -> Self {
+        Self::Local(ProvingOptions::default())
+    }
+}
+
+/// What [`run`] proves, where, and where it writes the proof, so the
+/// prover can be embedded without going through the client's files.
+#[derive(Clone)]
+pub struct ProverConfig {
+    /// Metadata of the escrow to prove.
+    pub metadata_source: Source<EscrowMetadata>,
+    /// Fulfillment of the escrow's conditions, if it has any.
+    pub conditions_source: Option<Source<Condition>>,
+    /// Path to write the proof data to, with the receipt written alongside
+    /// it (with a `receipt` extension). Nothing is written if `None`.
+    pub output: Option<PathBuf>,
+    /// Where proofs are generated, and how, when proving locally.
+    pub backend: ProverBackend,
+    /// Compress the proof to Groth16 even for Solana escrows; always done
+    /// for Ethereum escrows, whose contract verifies the seal.
+    pub groth16: bool,
+    /// Secret Ed25519 identity key to sign the proof data with, recording
+    /// who generated it and when (see [`ProofArtifact::sign`]).
+    pub signing_key: Option<[u8; 32]>,
+}
+
+impl std::fmt::Debug for ProverConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProverConfig")
+            .field("metadata_source", &self.metadata_source)
+            .field("conditions_source", &self.conditions_source)
+            .field("output", &self.output)
+            .field("backend", &self.backend)
+            .field("groth16", &self.groth16)
+            .field("signing_key", &self.signing_key.map(|_| "<redacted>"))
+            .finish()
+    }
+}
+
+impl ProverConfig {
+    /// Proves the escrow with metadata from `metadata_source` and no
+    /// fulfillment, locally with default options, writing nothing.
+    pub fn new(metadata_source: impl Into<Source<EscrowMetadata>>) -> Self {
+        Self {
+            metadata_source: metadata_source.into(),
+            conditions_source: None,
+            output: None,
+            backend: ProverBackend::default(),
+            groth16: false,
+            signing_key: None,
+        }
+    }
+
+    /// Proves the escrow with the fulfillment from `conditions_source`.
+    pub fn conditions(mut self, conditions_source: impl Into<Source<Condition>>) -> Self {
+        self.conditions_source = Some(conditions_source.into());
+        self
+    }
+
+    /// Writes the proof data to `path`.
+    pub fn output(mut self, path: impl Into<PathBuf>) -> Self {
+        self.output = Some(path.into());
+        self
+    }
+
+    /// Proves on `backend`.
+    pub fn backend(mut self, backend: ProverBackend) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Compresses the proof to Groth16.
+    pub fn groth16(mut self, groth16: bool) -> Self {
+        self.groth16 = groth16;
+        self
+    }
+
+    /// Signs the proof data with the secret Ed25519 identity key `key`.
+    pub fn sign_with(mut self, key: [u8; 32]) -> Self {
+        self.signing_key = Some(key);
+        self
+    }
+}
+
+/// Describes the host proofs are generated on, for the provenance of
+/// signed proof data: its name, if known, OS, and architecture.
+pub fn host_info() -> String {
+    let platform = format!("{}/{}", std::env::consts::OS, std::env::consts::ARCH);
+    match std::env::var("HOSTNAME").or_else(|_| std::env::var("COMPUTERNAME")) {
+        Ok(name) if !name.is_empty() => format!("{name} ({platform})"),
+        _ => platform,
+    }
+}
+
+/// Where [`ProverConfig`] takes an input from: a JSON file, as the client
+/// writes them, or a value already in memory.
+#[derive(Debug, Clone)]
+pub enum Source<T> {
+    /// JSON file, with `${VAR}` references expanded (see
+    /// [`load_escrow_data`]).
+    Path(PathBuf),
+    /// Value in memory.
+    Value(T),
+}
+
+impl<T: Clone + DeserializeOwned> Source<T> {
+    /// Returns the value, reading it from its file if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file cannot be read or parsed.
+    pub fn load(&self) -> anyhow::Result<T> {
+        match self {
+            Self::Path(path) => {
+                load_escrow_data(path).with_context(|| format!("failed to load {}", path.display()))
+            }
+            Self::Value(value) => Ok(value.clone()),
+        }
+    }
+}
+
+impl<T> From<PathBuf> for Source<T> {
+    fn from(path: PathBuf) -> Self {
+        Self::Path(path)
+    }
+}
+
+impl<T> From<&Path> for Source<T> {
+    fn from(path: &Path) -> Self {
+        Self::Path(path.to_path_buf())
+    }
+}
+
+impl From<EscrowMetadata> for Source<EscrowMetadata> {
+    fn from(metadata: EscrowMetadata) -> Self {
+        Self::Value(metadata)
+    }
+}
+
+impl From<Condition> for Source<Condition> {
+    fn from(condition: Condition) -> Self {
+        Self::Value(condition)
+    }
+}
+
+/// Tuning of local proving, trading memory for speed.
+#[derive(Debug, Clone, Default)]
+pub struct ProvingOptions {
+    /// Segment size, as a power of two of cycles. Smaller segments need
+    /// less memory but more of them are proven. Defaults to RISC Zero's
+    /// (2^20 cycles, about 10 GB of memory per segment proven).
+    pub segment_po2: Option<u32>,
+    /// Number of worker threads. Defaults to one per core.
+    pub threads: Option<usize>,
+    /// Largest keccak precompile batch, as a power of two of cycles, for
+    /// guests using accelerated keccak. Defaults to RISC Zero's.
+    pub keccak_po2: Option<u32>,
+    /// Skip proving and produce fake receipts, which only verify with
+    /// `dev_mode` too. For tests only, and rejected with
+    /// [`ProverError::DevModeDisabled`] unless built with the `dev-mode`
+    /// feature.
+    pub dev_mode: bool,
+    /// Accelerator to require. Defaults to the one this build proves on
+    /// (see [`Accelerator::built`]).
+    pub accelerator: Option<Accelerator>,
+    /// Kind of receipt to produce when not compressing to Groth16 for an
+    /// on-chain verifier. Defaults to a composite receipt.
+    pub receipt_kind: ReceiptKind,
+    /// Commit the paths of the satisfied subconditions to the journal
+    /// (see [`ExecutionResult::Satisfied`]), verifying every subcondition.
+    /// Such proofs are not accepted by the Ethereum escrow contract.
+    pub report_satisfied: bool,
+}
+
+/// Kind of receipt local proofs are produced as, trading proving time for
+/// receipt size.
+///
+/// Remote proofs are always succinct, or Groth16 when compressed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ReceiptKind {
+    /// One proof per segment; fastest to produce, but megabytes per
+    /// segment for large condition trees.
+    #[default]
+    Composite,
+    /// Segments aggregated into a single proof of a few hundred kilobytes.
+    Succinct,
+    /// Succinct proof compressed to a few hundred bytes, verifiable
+    /// on-chain. Needs Docker (or an x86 host with the RISC Zero Groth16
+    /// prover installed).
+    Groth16,
+}
+
+impl ReceiptKind {
+    /// Returns the RISC Zero prover options producing this kind.
+    fn prover_opts(self) -> ProverOpts {
+        match self {
+            Self::Composite => ProverOpts::composite(),
+            Self::Succinct => ProverOpts::succinct(),
+            Self::Groth16 => ProverOpts::groth16(),
+        }
+    }
+}
+
+impl std::fmt::Display for ReceiptKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Composite => "composite",
+            Self::Succinct => "succinct",
+            Self::Groth16 => "groth16",
+        })
+    }
+}
+
+impl std::str::FromStr for ReceiptKind {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "composite" => Ok(Self::Composite),
+            "succinct" => Ok(Self::Succinct),
+            "groth16" => Ok(Self::Groth16),
+            _ => anyhow::bail!(
+                "unknown receipt kind {s:?}; expected composite, succinct, or groth16"
+            ),
+        }
+    }
+}
+
+/// Hardware local proofs are generated on.
+///
+/// RISC Zero selects its GPU backend at build time, so a build proves on
+/// the GPU its `cuda` or `metal` feature enables, or on the CPU without
+/// either; [`select_accelerator`] checks at runtime that the hardware is
+/// present.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Accelerator {
+    /// CPU proving.
+    Cpu,
+    /// NVIDIA GPU, with the `cuda` feature.
+    Cuda,
+    /// Apple GPU, with the `metal` feature.
+    Metal,
+}
+
+impl Accelerator {
+    /// The accelerator this build proves on.
+    pub const fn built() -> Self {
+        if cfg!(feature = "cuda") {
+            Self::Cuda
+        } else if cfg!(feature = "metal") {
+            Self::Metal
+        } else {
+            Self::Cpu
+        }
+    }
+
+    /// Whether this machine appears to have the accelerator's hardware.
+    ///
+    /// The check is light: a CUDA device node (or Windows) not hidden by
+    /// `CUDA_VISIBLE_DEVICES`, or macOS for Metal.
+    pub fn is_present(self) -> bool {
+        match self {
+            Self::Cpu => true,
+            Self::Cuda => {
+                let hidden = std::env::var("CUDA_VISIBLE_DEVICES")
+                    .is_ok_and(|devices| devices.trim().is_empty() || devices.trim() == "-1");
+                !hidden && (cfg!(windows) || Path::new("/dev/nvidiactl").exists())
+            }
+            Self::Metal => cfg!(target_os = "macos"),
+        }
+    }
+}
+
+impl std::fmt::Display for Accelerator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Self::Cpu => "cpu",
+            Self::Cuda => "cuda",
+            Self::Metal => "metal",
+        })
+    }
+}
+
+impl std::str::FromStr for Accelerator {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "cpu" => Ok(Self::Cpu),
+            "cuda" => Ok(Self::Cuda),
+            "metal" => Ok(Self::Metal),
+            _ => anyhow::bail!("unknown accelerator {s:?}; expected cpu, cuda, or metal"),
+        }
+    }
+}
+
+/// Returns the accelerator local proving runs on, after checking that it
+/// is `requested` (if given) and its hardware is present.
+///
+/// # Errors
+///
+/// Returns [`ProverError::AcceleratorUnavailable`] if this build proves on
+/// another accelerator than `requested`, or the hardware is missing.
+pub fn select_accelerator(requested: Option<Accelerator>) -> Result<Accelerator, ProverError> {
+    let built = Accelerator::built();
+    let requested = requested.unwrap_or(built);
+    if requested != built {
+        let reason = match requested {
+            Accelerator::Cpu => format!("this build always proves on {built}"),
+            gpu => format!("rebuild with the `{gpu}` feature"),
+        };
+        return Err(ProverError::AcceleratorUnavailable { requested, reason });
+    }
+    if !built.is_present() {
+        return Err(ProverError::AcceleratorUnavailable {
+            requested,
+            reason: "no device found; rebuild without the GPU feature to prove on the CPU".into(),
+        });
+    }
+    Ok(built)
 }
 
 /// Executes the zero-knowledge proof workflow for an escrow transaction.
 ///
 /// This function:
-/// 1. Reads escrow metadata from [`ESCROW_METADATA_PATH`]
-/// 2. Constructs an [`Escrow`] from the metadata
-/// 3. Executes the zkVM guest program to verify conditions
+/// 1. Loads the escrow metadata and fulfillment from `config`'s sources
+/// 2. Constructs an [`Escrow`] from them
+/// 3. Executes the zkVM guest program to verify conditions, on `config`'s
+///    backend
 /// 4. Verifies the generated proof
+/// 5. Signs the proof data with `config.signing_key`, if set
+/// 6. Writes the proof data and receipt to `config.output`, if set
 ///
 /// # Returns
 ///
-/// Returns `Ok(())` if all escrow conditions are fulfilled and verified.
+/// The proof data, with the Ethereum seal for Ethereum escrows.
 ///
 /// # Errors
 ///
 /// Returns an error if:
-/// - Metadata file cannot be read or parsed
-/// - Escrow construction fails
+/// - An input cannot be loaded
+/// - Escrow construction fails, e.g. the escrow has conditions but no
+///   conditions source is given
 /// - Proof generation fails
 /// - Receipt verification fails
 /// - Escrow conditions are not met
-pub fn run() -> anyhow::Result<()> {
+/// - The output cannot be written
+pub fn run(config: &ProverConfig) -> anyhow::Result<ProofArtifact> {
+    let _span = info_span!("zk_prover").entered();
+
+    let metadata = config.metadata_source.load()?;
+    let condition = config
+        .conditions_source
+        .as_ref()
+        .map(Source::load)
+        .transpose()?;
+    let binding = metadata.binding()?;
+    let ethereum = matches!(metadata.params.chain_config.chain, Chain::Ethereum);
+    let receipt = prove_escrow_receipt_with(
+        &metadata,
+        condition.as_ref(),
+        binding,
+        config.groth16 || ethereum,
+        &config.backend,
+    )?;
+    let seal = ethereum.then(|| ethereum_seal(&receipt)).transpose()?;
+
+    let journal = &receipt.journal.bytes;
+    let mut artifact = ProofArtifact {
+        image_id: Some(guest_image_id()),
+        journal: Some(format!("0x{}", hex::encode(journal))),
+        journal_digest: Some(format!("0x{}", journal_digest(journal))),
+        guest_version: Some(GUEST_VERSION.into()),
+        ..ProofArtifact::new(&metadata, seal.as_deref())
+    };
+    if let Some(key) = &config.signing_key {
+        artifact.sign(key, host_info());
+    }
+    if let Some(output) = &config.output {
+        let receipt_path = output.with_extension("receipt");
+        save_receipt(&receipt_path, &receipt)?;
+        artifact.receipt = Some(receipt_path);
+        artifact.save(output)?;
+        info!(output = %output.display(), "Proof data written");
+    }
+    Ok(artifact)
+}
+
+/// Proves `metadata` with `condition` like [`run`], producing a Groth16
+/// receipt, and returns its seal encoded for the Ethereum escrow contract.
+///
+/// Groth16 compression requires Docker (or an x86 host with the RISC Zero
+/// Groth16 prover installed) unless proving remotely with
+/// [`ProverBackend::Remote`].
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`run`], or if the receipt cannot
+/// be compressed to Groth16.
+pub fn run_groth16(
+    metadata: EscrowMetadata,
+    condition: Option<Condition>,
+) -> anyhow::Result<Vec<u8>> {
     let _span = info_span!("zk_prover").entered();
 
-    let escrow = load_escrow_from_metadata()?;
-    let receipt = generate_proof(&escrow)?;
-    verify_receipt(&receipt)?;
-    validate_execution_result(&receipt)
+    let binding = metadata.binding()?;
+    prove_escrow_groth16(&metadata, condition.as_ref(), binding)
+}
+
+/// Proves that `condition` fulfills the escrow in `metadata`, like [`run`]
+/// but for inputs that are already loaded, bound to the escrow instance
+/// `binding` identifies (see [`EscrowMetadata::binding`]).
+///
+/// # Errors
+///
+/// Returns an error if the escrow has conditions but `condition` is
+/// `None`, `binding` is for another escrow, proof generation or
+/// verification fails, or the escrow conditions are not met.
+pub fn prove_escrow(
+    metadata: &EscrowMetadata,
+    condition: Option<&Condition>,
+    binding: EscrowBinding,
+) -> anyhow::Result<()> {
+    prove_escrow_receipt(metadata, condition, binding, false).map(|_| ())
 }
 
-/// Loads escrow data from the metadata file.
-fn load_escrow_from_metadata() -> anyhow::Result<Escrow> {
-    info!(path = ESCROW_METADATA_PATH, "Loading escrow metadata");
+/// Proves that `condition` fulfills the escrow in `metadata`, like
+/// [`run_groth16`] but for inputs that are already loaded, and returns the
+/// seal encoded for the Ethereum escrow contract.
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`prove_escrow`], or if the
+/// receipt cannot be compressed to Groth16.
+pub fn prove_escrow_groth16(
+    metadata: &EscrowMetadata,
+    condition: Option<&Condition>,
+    binding: EscrowBinding,
+) -> anyhow::Result<Vec<u8>> {
+    ethereum_seal(&prove_escrow_receipt(metadata, condition, binding, true)?)
+}
 
-    load_escrow_data::<_, EscrowMetadata>(ESCROW_METADATA_PATH).and_then(|metadata| {
-        Escrow::from_metadata(metadata).with_context(|| "failed to construct Escrow from metadata")
-    })
+/// Proves that `condition` fulfills the escrow in `metadata`, like
+/// [`prove_escrow`] (or [`prove_escrow_groth16`] if `groth16`), and returns
+/// the verified receipt, e.g. to archive or [inspect](inspect_receipt) it.
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`prove_escrow`], or if the
+/// receipt cannot be compressed to Groth16.
+pub fn prove_escrow_receipt(
+    metadata: &EscrowMetadata,
+    condition: Option<&Condition>,
+    binding: EscrowBinding,
+    groth16: bool,
+) -> anyhow::Result<Receipt> {
+    prove_escrow_receipt_with(
+        metadata,
+        condition,
+        binding,
+        groth16,
+        &ProverBackend::default(),
+    )
 }
 
-/// Generates a zero-knowledge proof for the escrow.
+/// Proves that `condition` fulfills the escrow in `metadata` like
+/// [`prove_escrow_receipt`], on the prover `config` selects.
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`prove_escrow_receipt`], or if
+/// the remote proving service fails or times out.
+pub fn prove_escrow_receipt_with(
+    metadata: &EscrowMetadata,
+    condition: Option<&Condition>,
+    binding: EscrowBinding,
+    groth16: bool,
+    config: &ProverBackend,
+) -> anyhow::Result<Receipt> {
+    let (receipt, dev_mode) = match config {
+        ProverBackend::Local(options)
+            if groth16 || options.receipt_kind == ReceiptKind::Groth16 =>
+        {
+            (
+                generate_groth16_proof(metadata, condition, binding, options)?,
+                options.dev_mode,
+            )
+        }
+        ProverBackend::Local(options) => (
+            generate_proof(metadata, condition, binding, options)?,
+            options.dev_mode,
+        ),
+        ProverBackend::Remote(remote) => {
+            info!("Starting remote proof generation");
+            let escrow = encode_escrow(metadata, condition)?;
+            let input = guest_input(escrow, binding, remote.report_satisfied)?;
+            (remote::prove(remote, input, groth16)?, false)
+        }
+    };
+    verify_receipt_in(
+        &receipt,
+        &VerifierContext::default().with_dev_mode(dev_mode),
+    )?;
+    validate_execution_result(&receipt, binding)?;
+    Ok(receipt)
+}
+
+/// Executes the guest program for the escrow in `metadata` without
+/// proving, and returns the number of cycles a proof would cover, e.g. to
+/// estimate proving time.
+///
+/// Execution takes milliseconds to seconds where proving takes minutes.
+///
+/// # Errors
+///
+/// Returns an error if execution fails or the escrow conditions are not
+/// met.
+pub fn count_cycles(
+    metadata: &EscrowMetadata,
+    condition: Option<&Condition>,
+    binding: EscrowBinding,
+) -> anyhow::Result<u64> {
+    let escrow = encode_escrow(metadata, condition)?;
+    let session = default_executor()
+        .execute(
+            executor_env(escrow, binding, &ProvingOptions::default())?,
+            ZESCROW_GUEST_ELF,
+        )
+        .map_err(|e| ProverError::Proving(format!("guest execution failed: {e:#}")))?;
+    validate_journal(&session.journal.bytes, binding)?;
+    // Segments are proven padded to a power of two.
+    Ok(session
+        .segments
+        .iter()
+        .map(|segment| 1u64 << segment.po2)
+        .sum())
+}
+
+/// Generates a zero-knowledge proof for the escrow, of the
+/// [kind](ProvingOptions::receipt_kind) `options` select.
 ///
 /// # Arguments
 ///
-/// * `escrow` - The escrow to generate a proof for
+/// * `metadata` - Metadata of the escrow to generate a proof for
+/// * `condition` - Fulfillment of the escrow's conditions, if it has any
+/// * `binding` - The escrow instance the proof is bound to
+/// * `options` - Tuning of the local prover
 ///
 /// # Returns
 ///
 /// The RISC Zero receipt containing the proof.
-pub fn generate_proof(escrow: &Escrow) -> anyhow::Result<Receipt> {
-    let escrow_bytes =
-        bincode::encode_to_vec(escrow, standard()).with_context(|| "failed to encode escrow")?;
+pub fn generate_proof(
+    metadata: &EscrowMetadata,
+    condition: Option<&Condition>,
+    binding: EscrowBinding,
+    options: &ProvingOptions,
+) -> anyhow::Result<Receipt> {
+    let escrow = encode_escrow(metadata, condition)?;
+    prove(escrow, binding, options.receipt_kind.prover_opts(), options)
+}
+
+/// Generates a zero-knowledge proof for the escrow, compressed to a Groth16
+/// receipt that can be verified on-chain.
+///
+/// # Arguments
+///
+/// * `metadata` - Metadata of the escrow to generate a proof for
+/// * `condition` - Fulfillment of the escrow's conditions, if it has any
+/// * `binding` - The escrow instance the proof is bound to
+/// * `options` - Tuning of the local prover
+///
+/// # Returns
+///
+/// The RISC Zero receipt containing the Groth16 proof.
+pub fn generate_groth16_proof(
+    metadata: &EscrowMetadata,
+    condition: Option<&Condition>,
+    binding: EscrowBinding,
+    options: &ProvingOptions,
+) -> anyhow::Result<Receipt> {
+    let escrow = encode_escrow(metadata, condition)?;
+    prove(escrow, binding, ReceiptKind::Groth16.prover_opts(), options)
+}
+
+/// Encodes a Groth16 receipt's seal for the Ethereum escrow contract.
+///
+/// The seal is prefixed with the freshness and condition fingerprint
+/// committed to the journal (see [`Journal::seal_prefix`]), which the
+/// contract needs to rebuild the journal and rejects once expired, then
+/// with the first four bytes of the verifier parameters
+/// digest, by which the RISC Zero verifier router selects a verifier.
+///
+/// With the `dev-mode` feature, fake receipts are encoded with
+/// [`DEV_MODE_SELECTOR`] followed by the SHA-256 digest of the image ID and
+/// journal digest, for the `DevModeVerifier` test contract.
+///
+/// # Errors
+///
+/// Returns [`ProverError::NotGroth16`] if `receipt` is not a Groth16
+/// receipt, or an error if its journal cannot be decoded.
+pub fn ethereum_seal(receipt: &Receipt) -> anyhow::Result<Vec<u8>> {
+    let prefix = decode_journal(&receipt.journal.bytes)?.seal_prefix();
+    #[cfg(feature = "dev-mode")]
+    if let InnerReceipt::Fake(_) = &receipt.inner {
+        let digest = dev_mode_digest(ZESCROW_GUEST_ID.into(), &receipt.journal.bytes);
+        return Ok([&prefix[..], &DEV_MODE_SELECTOR[..], digest.as_bytes()].concat());
+    }
+    let groth16 = receipt
+        .inner
+        .groth16()
+        .map_err(|_| ProverError::NotGroth16)?;
+    let selector = &groth16.verifier_parameters.as_bytes()[..4];
+    Ok([&prefix[..], selector, groth16.seal.as_slice()].concat())
+}
+
+/// Proof points of a Groth16 seal, each coordinate a 32-byte big-endian
+/// field element, in the order the RISC Zero Groth16 verifiers (the
+/// Ethereum verifier router and Solana verifier router) take them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Groth16Proof {
+    /// The `A` point, in G1.
+    pub pi_a: [[u8; 32]; 2],
+    /// The `B` point, in G2.
+    pub pi_b: [[[u8; 32]; 2]; 2],
+    /// The `C` point, in G1.
+    pub pi_c: [[u8; 32]; 2],
+}
+
+impl Groth16Proof {
+    /// Length of a raw Groth16 seal: eight field elements.
+    pub const SEAL_LEN: usize = 8 * 32;
+
+    /// Splits a raw Groth16 seal, as held by a Groth16 receipt (without the
+    /// selector [`ethereum_seal`] prepends), into its proof points.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProverError::MalformedSeal`] if `seal` is not
+    /// [`SEAL_LEN`](Self::SEAL_LEN) bytes long.
+    pub fn from_seal(seal: &[u8]) -> Result<Self, ProverError> {
+        if seal.len() != Self::SEAL_LEN {
+            return Err(ProverError::MalformedSeal {
+                expected: Self::SEAL_LEN,
+                actual: seal.len(),
+            });
+        }
+        let mut elements = seal.chunks_exact(32).map(|chunk| {
+            let mut element = [0; 32];
+            element.copy_from_slice(chunk);
+            element
+        });
+        let mut next = || elements.next().expect("seal holds eight elements");
+        Ok(Self {
+            pi_a: [next(), next()],
+            pi_b: [[next(), next()], [next(), next()]],
+            pi_c: [next(), next()],
+        })
+    }
+}
+
+/// Returns the proof points of a Groth16 receipt, to verify it with a
+/// Groth16 verifier that takes them individually.
+///
+/// # Errors
+///
+/// Returns [`ProverError::NotGroth16`] if `receipt` is not a Groth16 receipt.
+pub fn groth16_proof(receipt: &Receipt) -> anyhow::Result<Groth16Proof> {
+    let groth16 = receipt
+        .inner
+        .groth16()
+        .map_err(|_| ProverError::NotGroth16)?;
+    Ok(Groth16Proof::from_seal(&groth16.seal)?)
+}
+
+/// Returns the SHA-256 digest of `journal`, which on-chain verifiers check
+/// the receipt claim against, as hex.
+pub fn journal_digest(journal: &[u8]) -> String {
+    Impl::hash_bytes(journal).to_string()
+}
+
+/// Returns the image ID of the guest program receipts are verified
+/// against, as hex.
+pub fn guest_image_id() -> String {
+    Digest::from(ZESCROW_GUEST_ID).to_string()
+}
+
+/// What a receipt proves, for triaging proof issues.
+#[derive(Debug)]
+pub struct ReceiptInspection {
+    /// Image ID of the program the receipt was produced by, as hex, if the
+    /// receipt claim is not pruned.
+    pub image_id: Option<String>,
+    /// Receipt kind: `composite`, `succinct`, `groth16`, or `fake`.
+    pub kind: &'static str,
+    /// Execution result and escrow binding committed to the journal.
+    pub journal: anyhow::Result<Journal>,
+    /// Outcome of verifying the receipt against [`guest_image_id`].
+    pub verification: anyhow::Result<()>,
+}
+
+/// Decodes and verifies `receipt` without requiring it to prove fulfilled
+/// conditions.
+pub fn inspect_receipt(receipt: &Receipt) -> ReceiptInspection {
+    let image_id = receipt.claim().ok().and_then(|claim| {
+        claim
+            .as_value()
+            .map(|claim| claim.pre.digest().to_string())
+            .ok()
+    });
+    let kind = match &receipt.inner {
+        InnerReceipt::Composite(_) => "composite",
+        InnerReceipt::Succinct(_) => "succinct",
+        InnerReceipt::Groth16(_) => "groth16",
+        InnerReceipt::Fake(_) => "fake",
+        _ => "unknown",
+    };
+    ReceiptInspection {
+        image_id,
+        kind,
+        journal: decode_journal(&receipt.journal.bytes),
+        verification: verify_receipt(receipt),
+    }
+}
+
+/// Verifies a Groth16 `seal` encoded for the Ethereum escrow contract (see
+/// [`ethereum_seal`]) over `journal`, against [`guest_image_id`].
+///
+/// # Errors
+///
+/// Returns [`ProverError::VerifyReceipt`] if the seal does not verify, was
+/// produced for another verifier or journal (see [`Journal::seal_prefix`]),
+/// or is not an encoded Groth16 seal.
+pub fn verify_ethereum_seal(seal: &[u8], journal: &[u8]) -> anyhow::Result<()> {
+    verify_ethereum_seal_for(seal, journal, ZESCROW_GUEST_ID.into())
+}
+
+fn verify_ethereum_seal_for(seal: &[u8], journal: &[u8], image_id: Digest) -> anyhow::Result<()> {
+    let parameters = Groth16ReceiptVerifierParameters::default().digest();
+    let too_short = || ProverError::VerifyReceipt("seal is too short".into());
+    let (prefix, seal) = seal
+        .split_at_checked(Journal::SEAL_PREFIX_LEN)
+        .ok_or_else(too_short)?;
+    if prefix != decode_journal(journal)?.seal_prefix() {
+        return Err(ProverError::VerifyReceipt(
+            "seal freshness, chain state, or condition does not match the journal".into(),
+        )
+        .into());
+    }
+    let (selector, seal) = seal.split_at_checked(4).ok_or_else(too_short)?;
+    #[cfg(feature = "dev-mode")]
+    if selector == DEV_MODE_SELECTOR {
+        if seal != dev_mode_digest(image_id, journal).as_bytes() {
+            return Err(ProverError::VerifyReceipt(
+                "dev-mode seal does not match the journal".into(),
+            )
+            .into());
+        }
+        return Ok(());
+    }
+    if selector != &parameters.as_bytes()[..4] {
+        return Err(ProverError::VerifyReceipt(
+            "seal was produced for another Groth16 verifier version".into(),
+        )
+        .into());
+    }
+    verify_groth16_seal(seal, journal, image_id)
+}
+
+/// Verifies a raw Groth16 `seal` over `journal` against `image_id`.
+fn verify_groth16_seal(seal: &[u8], journal: &[u8], image_id: Digest) -> anyhow::Result<()> {
+    let parameters = Groth16ReceiptVerifierParameters::default().digest();
+    let claim = ReceiptClaim::ok(image_id, journal.to_vec());
+    let receipt = Receipt::new(
+        InnerReceipt::Groth16(Groth16Receipt::new(seal.to_vec(), claim.into(), parameters)),
+        journal.to_vec(),
+    );
+    verify_receipt_against(&receipt, &VerifierContext::default(), image_id)
+}
 
-    let env = ExecutorEnv::builder()
-        .write_frame(&escrow_bytes)
+/// A proof verified by [`verify_artifact`].
+#[derive(Debug)]
+pub struct VerifiedArtifact {
+    /// Image ID the proof was verified against, as hex.
+    pub image_id: String,
+    /// What was verified: a `receipt`, an `ethereum seal`, or `groth16
+    /// points`.
+    pub kind: &'static str,
+    /// Execution result and escrow binding committed to the journal.
+    pub journal: Journal,
+}
+
+/// Verifies a proof someone else produced, without trusting their machine:
+/// a proof data file (see [`ProofArtifact`]) or a receipt file (see
+/// [`save_receipt`]) at `path`, against the image ID of the guest program
+/// this crate embeds, and decodes its journal.
+///
+/// Whether the journal reports fulfilled conditions, or is bound to a
+/// given escrow, is left to the caller.
+///
+/// # Errors
+///
+/// Returns [`ProverError::ImageIdMismatch`] if proof data was recorded for
+/// another image ID, [`ProverError::VerifyReceipt`] if the proof does not
+/// verify, or an error if the file cannot be read or holds nothing to
+/// verify.
+pub fn verify_artifact(path: impl AsRef<Path>) -> anyhow::Result<VerifiedArtifact> {
+    verify_artifact_against(path, ZESCROW_GUEST_ID.into())
+}
+
+/// Verifies a proof like [`verify_artifact`], against a pinned `image_id`
+/// instead of this crate's guest program, e.g. the one an escrow contract
+/// was deployed with.
+///
+/// # Errors
+///
+/// Returns an error in the same cases as [`verify_artifact`].
+pub fn verify_artifact_against(
+    path: impl AsRef<Path>,
+    image_id: Digest,
+) -> anyhow::Result<VerifiedArtifact> {
+    let path = path.as_ref();
+    let bytes =
+        std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let verified = |kind, journal: &[u8]| -> anyhow::Result<VerifiedArtifact> {
+        Ok(VerifiedArtifact {
+            image_id: image_id.to_string(),
+            kind,
+            journal: decode_journal(journal)?,
+        })
+    };
+    if !bytes.trim_ascii_start().starts_with(b"{") {
+        let receipt = load_receipt(path)?;
+        verify_receipt_against(&receipt, &VerifierContext::default(), image_id)?;
+        return verified("receipt", &receipt.journal.bytes);
+    }
+
+    let artifact = ProofArtifact::load(path)?;
+    if let Some(recorded) = &artifact.image_id {
+        let recorded = recorded.trim_start_matches("0x");
+        if !recorded.eq_ignore_ascii_case(&image_id.to_string()) {
+            return Err(ProverError::ImageIdMismatch {
+                expected: image_id.to_string(),
+                actual: recorded.into(),
+            }
+            .into());
+        }
+    }
+    let journal = artifact.journal_bytes()?;
+    match (artifact.seal()?, &artifact.groth16, journal) {
+        (Some(seal), _, Some(journal)) => {
+            verify_ethereum_seal_for(&seal, &journal, image_id)?;
+            verified("ethereum seal", &journal)
+        }
+        (None, Some(points), Some(journal)) => {
+            let decode = |element: &String| hex::decode(element.trim_start_matches("0x"));
+            let seal = points
+                .pi_a
+                .iter()
+                .chain(points.pi_b.iter().flatten())
+                .chain(&points.pi_c)
+                .map(decode)
+                .collect::<Result<Vec<_>, _>>()
+                .with_context(|| "invalid Groth16 proof points")?
+                .concat();
+            verify_groth16_seal(&seal, &journal, image_id)?;
+            verified("groth16 points", &journal)
+        }
+        _ => match &artifact.receipt {
+            Some(receipt) => {
+                let receipt = load_receipt(receipt)?;
+                verify_receipt_against(&receipt, &VerifierContext::default(), image_id)?;
+                verified("receipt", &receipt.journal.bytes)
+            }
+            None => anyhow::bail!(
+                "{} holds no seal, proof points, or receipt to verify",
+                path.display()
+            ),
+        },
+    }
+}
+
+/// Returns what a dev-mode Ethereum seal commits to: the SHA-256 digest of
+/// the image ID followed by the journal digest.
+#[cfg(feature = "dev-mode")]
+fn dev_mode_digest(image_id: Digest, journal: &[u8]) -> Digest {
+    let preimage = [image_id.as_bytes(), Impl::hash_bytes(journal).as_bytes()].concat();
+    *Impl::hash_bytes(&preimage)
+}
+
+/// Writes `receipt` to `path`, gzip-compressed.
+///
+/// Composite receipts of large condition trees are tens of megabytes, and
+/// their seals compress well.
+///
+/// # Errors
+///
+/// Returns an error if the receipt cannot be encoded or written.
+pub fn save_receipt(path: impl AsRef<Path>, receipt: &Receipt) -> anyhow::Result<()> {
+    let path = path.as_ref();
+    let file = std::fs::File::create(path)
+        .with_context(|| format!("failed to create {}", path.display()))?;
+    let mut encoder = GzEncoder::new(std::io::BufWriter::new(file), Compression::default());
+    bincode::serde::encode_into_std_write(receipt, &mut encoder, standard())
+        .with_context(|| "failed to encode receipt")?;
+    encoder
+        .finish()
+        .and_then(|mut writer| writer.flush())
+        .with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Reads a receipt written by [`save_receipt`], compressed or not.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or is not a receipt.
+pub fn load_receipt(path: impl AsRef<Path>) -> anyhow::Result<Receipt> {
+    let mut bytes = std::fs::read(path.as_ref())
+        .with_context(|| format!("failed to read {}", path.as_ref().display()))?;
+    if bytes.starts_with(&GZIP_MAGIC) {
+        let mut decompressed = Vec::new();
+        GzDecoder::new(bytes.as_slice())
+            .read_to_end(&mut decompressed)
+            .with_context(|| "failed to decompress receipt")?;
+        bytes = decompressed;
+    }
+    let (receipt, _) = bincode::serde::decode_from_slice(&bytes, standard())
+        .map_err(|e| ProverError::Decode(format!("receipt: {e}")))?;
+    Ok(receipt)
+}
+
+/// Builds the guest's environment, holding the encoded escrow (see
+/// [`encode_escrow`]) and binding.
+fn executor_env(
+    escrow: Vec<u8>,
+    binding: EscrowBinding,
+    options: &ProvingOptions,
+) -> anyhow::Result<ExecutorEnv<'static>> {
+    let mut builder = ExecutorEnv::builder();
+    builder.write_frame(&escrow);
+    builder.write_frame(&encode_binding(binding)?);
+    builder.write_frame(&encode_report_satisfied(options.report_satisfied)?);
+    if let Some(po2) = options.segment_po2 {
+        builder.segment_limit_po2(po2);
+    }
+    if let Some(po2) = options.keccak_po2 {
+        builder.keccak_max_po2(po2)?;
+    }
+    builder
         .build()
-        .with_context(|| "failed to build executor environment")?;
+        .with_context(|| "failed to build executor environment")
+}
+
+/// Returns the guest's raw input: the encoded escrow, binding, and whether
+/// to report the satisfied subconditions as length-prefixed frames, as
+/// [`executor_env`] writes them.
+fn guest_input(
+    escrow: Vec<u8>,
+    binding: EscrowBinding,
+    report_satisfied: bool,
+) -> anyhow::Result<Vec<u8>> {
+    let mut input = Vec::new();
+    for frame in [
+        escrow,
+        encode_binding(binding)?,
+        encode_report_satisfied(report_satisfied)?,
+    ] {
+        let len = u32::try_from(frame.len()).with_context(|| "escrow is too large")?;
+        input.extend_from_slice(&len.to_le_bytes());
+        input.extend_from_slice(&frame);
+    }
+    Ok(input)
+}
+
+/// Encodes the escrow in `metadata` and its `condition` for the guest,
+/// which constructs the escrow context and fingerprints the escrow itself.
+///
+/// RPC endpoints and keys are left out: they do not contribute to the
+/// fingerprint, and the input may be sent to a remote prover.
+fn encode_escrow(
+    metadata: &EscrowMetadata,
+    condition: Option<&Condition>,
+) -> anyhow::Result<Vec<u8>> {
+    // Fail here rather than with a guest panic.
+    Escrow::from_metadata(metadata.clone(), condition.cloned())
+        .with_context(|| "failed to construct Escrow from metadata")?;
+    let mut metadata = metadata.clone();
+    let chain_config = &mut metadata.params.chain_config;
+    chain_config.rpc_url.clear();
+    chain_config.fallback_rpc_urls.clear();
+    chain_config.sender_private_id.clear();
+    chain_config.pubsub_url = None;
+    bincode::encode_to_vec((metadata, condition), standard())
+        .with_context(|| "failed to encode escrow")
+}
+
+fn encode_binding(binding: EscrowBinding) -> anyhow::Result<Vec<u8>> {
+    bincode::encode_to_vec(binding, standard()).with_context(|| "failed to encode binding")
+}
+
+fn encode_report_satisfied(report_satisfied: bool) -> anyhow::Result<Vec<u8>> {
+    bincode::encode_to_vec(report_satisfied, standard())
+        .with_context(|| "failed to encode guest options")
+}
+
+fn prove(
+    escrow: Vec<u8>,
+    binding: EscrowBinding,
+    opts: ProverOpts,
+    options: &ProvingOptions,
+) -> anyhow::Result<Receipt> {
+    if options.dev_mode && !cfg!(feature = "dev-mode") {
+        return Err(ProverError::DevModeDisabled.into());
+    }
+    // Fake receipts are not proven on any hardware.
+    let accelerator = if options.dev_mode {
+        None
+    } else {
+        Some(select_accelerator(options.accelerator)?)
+    };
+    let env = executor_env(escrow, binding, options)?;
+    let opts = opts.with_dev_mode(options.dev_mode);
 
-    info!("Starting zkVM proof generation");
+    info!(
+        accelerator = accelerator.map(tracing::field::display),
+        segment_po2 = options.segment_po2,
+        threads = options.threads,
+        dev_mode = options.dev_mode,
+        "Starting zkVM proof generation"
+    );
     let start = std::time::Instant::now();
 
-    let prove_info = default_prover()
-        .prove(env, ZESCROW_GUEST_ELF)
-        .with_context(|| "proof generation failed")?;
+    let prover = LocalProver::new("zescrow");
+    let prove = || prover.prove_with_opts(env, ZESCROW_GUEST_ELF, &opts);
+    let prove_info = match options.threads {
+        Some(threads) => rayon::ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .build()
+            .with_context(|| "failed to start prover threads")?
+            .install(prove),
+        None => prove(),
+    }
+    .map_err(|e| ProverError::Proving(format!("{e:#}")))?;
 
     let elapsed = start.elapsed();
     info!(
@@ -136,26 +1233,60 @@ pub fn generate_proof(escrow: &Escrow) -> anyhow::Result<Receipt> {
 ///
 /// Returns [`ProverError::VerifyReceipt`] if verification fails.
 pub fn verify_receipt(receipt: &Receipt) -> anyhow::Result<()> {
+    verify_receipt_in(receipt, &VerifierContext::default())
+}
+
+fn verify_receipt_in(receipt: &Receipt, ctx: &VerifierContext) -> anyhow::Result<()> {
+    verify_receipt_against(receipt, ctx, ZESCROW_GUEST_ID.into())
+}
+
+fn verify_receipt_against(
+    receipt: &Receipt,
+    ctx: &VerifierContext,
+    image_id: Digest,
+) -> anyhow::Result<()> {
     info!("Verifying receipt");
     receipt
-        .verify(ZESCROW_GUEST_ID)
+        .verify_with_context(ctx, image_id)
         .map_err(|e| ProverError::VerifyReceipt(e.to_string()))?;
     info!("Receipt verified successfully");
     Ok(())
 }
 
 /// Decodes and validates the execution result from the receipt journal.
-fn validate_execution_result(receipt: &Receipt) -> anyhow::Result<()> {
-    let (result, _): (ExecutionResult, _) =
-        bincode::decode_from_slice(&receipt.journal.bytes, standard())
-            .with_context(|| "failed to decode execution result from journal")?;
+fn validate_execution_result(receipt: &Receipt, binding: EscrowBinding) -> anyhow::Result<()> {
+    validate_journal(&receipt.journal.bytes, binding)
+}
+
+/// Decodes the execution result and escrow binding committed to `journal`.
+///
+/// # Errors
+///
+/// Returns [`ProverError::Decode`] if `journal` does not hold a
+/// [`Journal`].
+pub fn decode_journal(journal: &[u8]) -> anyhow::Result<Journal> {
+    Journal::decode(journal).map_err(|e| ProverError::Decode(format!("journal: {e}")).into())
+}
 
-    match result {
-        ExecutionResult::Ok(ExecutionState::ConditionsMet) => {
+/// Decodes and validates the execution result committed to `journal`, and
+/// that it is bound to `binding`.
+fn validate_journal(journal: &[u8], binding: EscrowBinding) -> anyhow::Result<()> {
+    let journal = decode_journal(journal)?;
+    if journal.binding != binding {
+        return Err(ProverError::BindingMismatch.into());
+    }
+    match journal.result {
+        ExecutionResult::Ok(ExecutionState::ConditionsMet)
+        | ExecutionResult::Satisfied {
+            state: ExecutionState::ConditionsMet,
+            ..
+        } => {
             info!("Escrow conditions fulfilled");
             Ok(())
         }
-        ExecutionResult::Ok(state) => Err(ProverError::UnexpectedState(state).into()),
+        ExecutionResult::Ok(state) | ExecutionResult::Satisfied { state, .. } => {
+            Err(ProverError::UnexpectedState(state).into())
+        }
         ExecutionResult::Err(err) => Err(ProverError::ConditionFailed(err).into()),
     }
 }
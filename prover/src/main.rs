@@ -0,0 +1,89 @@
+//! `zescrow-prover`: runs the Zescrow prover as a service.
+
+use std::net::SocketAddr;
+
+use clap::{Parser, Subcommand};
+use zescrow_prover::server::{self, ServerConfig};
+use zescrow_prover::{Accelerator, ProverBackend, ProvingOptions};
+
+#[derive(Parser)]
+#[command(author, version, about)]
+struct Cli {
+    #[command(subcommand)]
+    command: Commands,
+}
+
+#[derive(Subcommand)]
+enum Commands {
+    /// Serve an HTTP API queueing proof jobs for a pool of workers, so
+    /// several clients can share this machine's prover
+    Serve {
+        /// Address to listen on
+        #[arg(long, env = "ZESCROW_PROVER_LISTEN", default_value = "127.0.0.1:3100")]
+        listen: SocketAddr,
+
+        /// Jobs proven at once; each needs the memory of a local proof
+        #[arg(long, env = "ZESCROW_PROVER_WORKERS", default_value_t = 1)]
+        workers: usize,
+
+        /// Jobs waiting for a worker before new ones are rejected
+        #[arg(long, env = "ZESCROW_PROVER_QUEUE", default_value_t = 16)]
+        queue_capacity: usize,
+
+        /// Finished jobs kept for polling
+        #[arg(long, default_value_t = 256)]
+        retained_jobs: usize,
+
+        /// Accepted API key (repeatable) [default: `ZESCROW_PROVER_API_KEYS`]
+        #[arg(long = "api-key", value_name = "KEY")]
+        api_keys: Vec<String>,
+
+        /// Segment size, as a power of two of cycles [default: 20]
+        #[arg(long, value_name = "PO2", value_parser = clap::value_parser!(u32).range(13..=24))]
+        segment_po2: Option<u32>,
+
+        /// Worker threads per job [default: one per core]
+        #[arg(long, value_name = "N")]
+        threads: Option<usize>,
+
+        /// Hardware to require: cpu, cuda, or metal [default: the build's]
+        #[arg(long, env = "ZESCROW_PROVER_ACCELERATOR")]
+        accelerator: Option<Accelerator>,
+    },
+}
+
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::filter::EnvFilter::from_default_env())
+        .init();
+
+    match Cli::parse().command {
+        Commands::Serve {
+            listen,
+            workers,
+            queue_capacity,
+            retained_jobs,
+            api_keys,
+            segment_po2,
+            threads,
+            accelerator,
+        } => {
+            let prover = ProverBackend::Local(ProvingOptions {
+                segment_po2,
+                threads,
+                accelerator,
+                ..ProvingOptions::default()
+            });
+            server::run(ServerConfig {
+                listen,
+                workers,
+                queue_capacity,
+                retained_jobs,
+                api_keys,
+                prover,
+            })
+            .await
+        }
+    }
+}
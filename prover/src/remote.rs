@@ -0,0 +1,201 @@
+//! Remote proving on Bonsai.
+//!
+//! The guest program and its input are uploaded to Bonsai, which executes
+//! and proves it (and compresses the proof to Groth16 on request) while the
+//! host polls for the outcome and downloads the receipt.
+
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use bincode::config::legacy;
+use bonsai_sdk::blocking::Client;
+use risc0_zkvm::Receipt;
+use tracing::{info, warn};
+use zescrow_methods::ZESCROW_GUEST_ELF;
+
+use crate::{guest_image_id, ProverError};
+
+/// Environment variable holding the Bonsai API URL.
+pub const API_URL_VAR: &str = "BONSAI_API_URL";
+
+/// Environment variable holding the Bonsai API key.
+pub const API_KEY_VAR: &str = "BONSAI_API_KEY";
+
+/// Connection and polling settings of the Bonsai proving service.
+#[derive(Clone)]
+pub struct RemoteConfig {
+    /// Bonsai API URL.
+    pub url: String,
+    /// Bonsai API key.
+    pub api_key: String,
+    /// Time between status polls.
+    pub poll_interval: Duration,
+    /// Time to wait for a proof (including Groth16 compression) before
+    /// giving up.
+    pub timeout: Duration,
+    /// Commit the paths of the satisfied subconditions to the journal, as
+    /// with [`ProvingOptions::report_satisfied`](crate::ProvingOptions::report_satisfied).
+    pub report_satisfied: bool,
+}
+
+impl RemoteConfig {
+    /// Default time between status polls.
+    pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+    /// Default time to wait for a proof.
+    pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(30 * 60);
+
+    /// Creates a configuration for the Bonsai API at `url`, authenticated
+    /// with `api_key`, with the default polling settings.
+    pub fn new(url: impl Into<String>, api_key: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            api_key: api_key.into(),
+            poll_interval: Self::DEFAULT_POLL_INTERVAL,
+            timeout: Self::DEFAULT_TIMEOUT,
+            report_satisfied: false,
+        }
+    }
+
+    /// Reads the API URL and key from `BONSAI_API_URL` and
+    /// `BONSAI_API_KEY`.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`ProverError::Remote`] if either variable is unset.
+    pub fn from_env() -> Result<Self, ProverError> {
+        let var = |name| {
+            std::env::var(name).map_err(|_| ProverError::Remote(format!("{name} is not set")))
+        };
+        Ok(Self::new(var(API_URL_VAR)?, var(API_KEY_VAR)?))
+    }
+
+    /// Sets the time to wait for a proof.
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Sets the time between status polls.
+    pub fn poll_interval(mut self, interval: Duration) -> Self {
+        self.poll_interval = interval;
+        self
+    }
+
+    /// Commits the paths of the satisfied subconditions to the journal.
+    pub fn report_satisfied(mut self, report_satisfied: bool) -> Self {
+        self.report_satisfied = report_satisfied;
+        self
+    }
+}
+
+impl fmt::Debug for RemoteConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RemoteConfig")
+            .field("url", &self.url)
+            .field("api_key", &"<redacted>")
+            .field("poll_interval", &self.poll_interval)
+            .field("timeout", &self.timeout)
+            .field("report_satisfied", &self.report_satisfied)
+            .finish()
+    }
+}
+
+/// Proves the guest program on `input` on Bonsai, compressing the proof to
+/// Groth16 if `groth16`, and downloads the receipt.
+pub(crate) fn prove(
+    config: &RemoteConfig,
+    input: Vec<u8>,
+    groth16: bool,
+) -> anyhow::Result<Receipt> {
+    let client = Client::from_parts(
+        config.url.clone(),
+        config.api_key.clone(),
+        risc0_zkvm::VERSION,
+    )
+    .map_err(remote)?;
+    let deadline = Instant::now() + config.timeout;
+
+    let image_id = guest_image_id();
+    client
+        .upload_img(&image_id, ZESCROW_GUEST_ELF.to_vec())
+        .map_err(remote)?;
+    let input_id = client.upload_input(input).map_err(remote)?;
+    let session = client
+        .create_session(image_id, input_id, Vec::new(), false)
+        .map_err(remote)?;
+    info!(session = %session.uuid, "Submitted proof request to Bonsai");
+
+    let receipt_url = loop {
+        let status = session.status(&client).map_err(remote)?;
+        match status.status.as_str() {
+            "RUNNING" => {}
+            "SUCCEEDED" => {
+                break status.receipt_url.ok_or_else(|| {
+                    ProverError::Remote("session succeeded without a receipt".into())
+                })?
+            }
+            other => {
+                return Err(ProverError::Remote(format!(
+                    "session {other}: {}",
+                    status.error_msg.unwrap_or_default()
+                ))
+                .into())
+            }
+        }
+        if let Err(e) = wait(config, deadline) {
+            if let Err(stop) = session.stop(&client) {
+                warn!(error = %stop, session = %session.uuid, "Could not stop Bonsai session");
+            }
+            return Err(e.into());
+        }
+    };
+    if !groth16 {
+        return download(&client, &receipt_url);
+    }
+
+    let snark = client.create_snark(session.uuid).map_err(remote)?;
+    info!(snark = %snark.uuid, "Requested Groth16 compression from Bonsai");
+    loop {
+        let status = snark.status(&client).map_err(remote)?;
+        match status.status.as_str() {
+            "RUNNING" => {}
+            "SUCCEEDED" => {
+                let url = status.output.ok_or_else(|| {
+                    ProverError::Remote("compression succeeded without a receipt".into())
+                })?;
+                return download(&client, &url);
+            }
+            other => {
+                return Err(ProverError::Remote(format!(
+                    "compression {other}: {}",
+                    status.error_msg.unwrap_or_default()
+                ))
+                .into())
+            }
+        }
+        wait(config, deadline)?;
+    }
+}
+
+/// Sleeps for one poll interval, or fails if that would pass `deadline`.
+fn wait(config: &RemoteConfig, deadline: Instant) -> Result<(), ProverError> {
+    if Instant::now() + config.poll_interval > deadline {
+        return Err(ProverError::RemoteTimeout(config.timeout));
+    }
+    std::thread::sleep(config.poll_interval);
+    Ok(())
+}
+
+/// Downloads and decodes the receipt at `url`.
+fn download(client: &Client, url: &str) -> anyhow::Result<Receipt> {
+    let bytes = client.download(url).map_err(remote)?;
+    info!(bytes = bytes.len(), "Downloaded receipt from Bonsai");
+    let (receipt, _) = bincode::serde::decode_from_slice(&bytes, legacy())
+        .map_err(|e| ProverError::Decode(format!("receipt from Bonsai: {e}")))?;
+    Ok(receipt)
+}
+
+fn remote(e: impl fmt::Display) -> ProverError {
+    ProverError::Remote(e.to_string())
+}
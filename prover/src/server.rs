@@ -0,0 +1,396 @@
+//! Proving service: an HTTP API queueing proof jobs for a bounded pool of
+//! workers, so several clients can share one proving machine.
+//!
+//! Every route except `GET /health` requires one of the configured API keys,
+//! sent as `Authorization: Bearer <key>` or `X-API-Key: <key>`. Errors are
+//! `{"error": "..."}`.
+//!
+//! | Route                         | Body                                   | Response                  |
+//! | ----------------------------- | -------------------------------------- | ------------------------- |
+//! | `POST /v1/jobs`               | `{"metadata", "condition", "groth16"?, "expires_at"?}` | `{"id"}` (202) |
+//! | `GET /v1/jobs/{id}`           |                                        | job status                |
+//! | `GET /v1/jobs/{id}/receipt`   |                                        | receipt (bincode)         |
+//!
+//! Jobs are rejected with 503 while the queue is full. Finished jobs are
+//! kept in memory, up to a configured number, until the service restarts.
+
+use std::collections::{HashMap, VecDeque};
+use std::net::SocketAddr;
+use std::sync::mpsc::{self, Receiver, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Context;
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use bincode::config::standard;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+use zescrow_core::auth::{self, ApiKeys, API_KEY_HEADER};
+use zescrow_core::interface::Freshness;
+use zescrow_core::{Chain, Condition, Escrow, EscrowMetadata};
+
+use crate::{ethereum_seal, prove_escrow_receipt_with, select_accelerator, ProverBackend, Receipt};
+
+/// Environment variable holding comma-separated API keys.
+pub const API_KEYS_VAR: &str = "ZESCROW_PROVER_API_KEYS";
+
+/// Settings of the proving service.
+#[derive(Debug, Clone)]
+pub struct ServerConfig {
+    /// Address to listen on.
+    pub listen: SocketAddr,
+    /// Jobs proven at once.
+    pub workers: usize,
+    /// Jobs waiting for a worker before new ones are rejected.
+    pub queue_capacity: usize,
+    /// Finished jobs kept for polling; the oldest are dropped first.
+    pub retained_jobs: usize,
+    /// Accepted API keys; falls back to [`API_KEYS_VAR`] if empty.
+    pub api_keys: Vec<String>,
+    /// Where workers prove.
+    pub prover: ProverBackend,
+}
+
+/// Body of the job submission route.
+#[derive(Debug, Deserialize)]
+struct JobRequest {
+    metadata: EscrowMetadata,
+    /// Condition with its fulfillment.
+    condition: Condition,
+    /// Compress the proof to Groth16; always done for Ethereum escrows.
+    #[serde(default)]
+    groth16: bool,
+    /// Last block or slot the proof may be used at [default: never].
+    #[serde(default)]
+    expires_at: Option<u64>,
+}
+
+/// Stage of a job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+enum JobStatus {
+    Queued,
+    Running,
+    Succeeded,
+    Failed,
+}
+
+/// A job and, once finished, its outcome.
+struct Job {
+    status: JobStatus,
+    receipt: Option<Receipt>,
+    /// Seal for the Ethereum escrow contract, for Ethereum escrows.
+    seal: Option<Vec<u8>>,
+    error: Option<String>,
+}
+
+/// Job status, as returned by `GET /v1/jobs/{id}`.
+#[derive(Debug, Serialize)]
+struct JobView {
+    id: String,
+    status: JobStatus,
+    /// Hex-encoded journal, once succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    journal: Option<String>,
+    /// Hex-encoded seal for the Ethereum escrow contract, once succeeded.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    seal: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+}
+
+/// Work handed to the worker pool.
+struct Task {
+    id: u64,
+    metadata: EscrowMetadata,
+    condition: Condition,
+    freshness: Freshness,
+    groth16: bool,
+}
+
+/// Jobs by ID, and the finished ones oldest first.
+#[derive(Default)]
+struct Jobs {
+    next_id: u64,
+    jobs: HashMap<u64, Job>,
+    finished: VecDeque<u64>,
+}
+
+/// Shared service state.
+struct Service {
+    jobs: Mutex<Jobs>,
+    queue: SyncSender<Task>,
+    retained_jobs: usize,
+    /// Accepted API keys.
+    api_keys: ApiKeys,
+}
+
+impl Service {
+    fn update(&self, id: u64, update: impl FnOnce(&mut Job)) {
+        let mut jobs = self.jobs.lock().expect("jobs lock poisoned");
+        if let Some(job) = jobs.jobs.get_mut(&id) {
+            update(job);
+        }
+    }
+
+    /// Records the outcome of job `id`, dropping the oldest finished jobs
+    /// beyond the retention limit.
+    fn finish(&self, id: u64, outcome: anyhow::Result<(Receipt, Option<Vec<u8>>)>) {
+        let mut jobs = self.jobs.lock().expect("jobs lock poisoned");
+        if let Some(job) = jobs.jobs.get_mut(&id) {
+            match outcome {
+                Ok((receipt, seal)) => {
+                    job.status = JobStatus::Succeeded;
+                    job.receipt = Some(receipt);
+                    job.seal = seal;
+                }
+                Err(e) => {
+                    job.status = JobStatus::Failed;
+                    job.error = Some(format!("{e:#}"));
+                }
+            }
+        }
+        jobs.finished.push_back(id);
+        while jobs.finished.len() > self.retained_jobs {
+            if let Some(oldest) = jobs.finished.pop_front() {
+                jobs.jobs.remove(&oldest);
+            }
+        }
+    }
+}
+
+/// Error response, rendered as `{"error": "..."}`.
+#[derive(Debug)]
+struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        if self.status.is_server_error() {
+            warn!(status = %self.status, error = %self.message, "Request failed");
+        }
+        let body = Json(serde_json::json!({ "error": self.message }));
+        (self.status, body).into_response()
+    }
+}
+
+type ApiResult = Result<Response, ApiError>;
+
+/// Serves the proving API until interrupted.
+///
+/// # Errors
+///
+/// Returns an error if no API key is configured, the worker pool is empty,
+/// or the address cannot be bound.
+pub async fn run(config: ServerConfig) -> anyhow::Result<()> {
+    let api_keys = ApiKeys::from_args_or_env(&config.api_keys, API_KEYS_VAR);
+    if api_keys.is_empty() {
+        anyhow::bail!("no API key configured; pass --api-key or set {API_KEYS_VAR}");
+    }
+    if config.workers == 0 {
+        anyhow::bail!("at least one worker is required");
+    }
+    // Fail at startup, not on every job, if the accelerator is missing.
+    let accelerator = match &config.prover {
+        ProverBackend::Local(options) if !options.dev_mode => {
+            Some(select_accelerator(options.accelerator)?)
+        }
+        _ => None,
+    };
+
+    let (queue, tasks) = mpsc::sync_channel(config.queue_capacity);
+    let service = Arc::new(Service {
+        jobs: Mutex::default(),
+        queue,
+        retained_jobs: config.retained_jobs,
+        api_keys,
+    });
+    let tasks = Arc::new(Mutex::new(tasks));
+    for worker in 0..config.workers {
+        let (service, tasks, prover) = (service.clone(), tasks.clone(), config.prover.clone());
+        std::thread::Builder::new()
+            .name(format!("prover-worker-{worker}"))
+            .spawn(move || work(&service, &tasks, &prover))
+            .with_context(|| "failed to start prover workers")?;
+    }
+
+    let app = Router::new()
+        .route("/v1/jobs", post(submit))
+        .route("/v1/jobs/{id}", get(status))
+        .route("/v1/jobs/{id}/receipt", get(receipt))
+        .route_layer(middleware::from_fn_with_state(
+            service.clone(),
+            require_api_key,
+        ))
+        .route("/health", get(|| async { "ok" }))
+        .with_state(service);
+
+    let listener = tokio::net::TcpListener::bind(config.listen)
+        .await
+        .with_context(|| format!("binding {}", config.listen))?;
+    info!(
+        listen = %config.listen,
+        workers = config.workers,
+        queue_capacity = config.queue_capacity,
+        accelerator = accelerator.map(tracing::field::display),
+        "Serving the proving API"
+    );
+    axum::serve(listener, app)
+        .with_graceful_shutdown(async {
+            tokio::signal::ctrl_c().await.ok();
+        })
+        .await?;
+    info!("Proving API stopped");
+    Ok(())
+}
+
+/// Proves queued tasks one at a time until the service stops.
+fn work(service: &Service, tasks: &Mutex<Receiver<Task>>, prover: &ProverBackend) {
+    loop {
+        // Holding the lock only while waiting lets the other workers take
+        // the next task as soon as this one starts proving.
+        let task = match tasks.lock().expect("task queue lock poisoned").recv() {
+            Ok(task) => task,
+            Err(_) => return,
+        };
+        service.update(task.id, |job| job.status = JobStatus::Running);
+        info!(job = task.id, "Proving job");
+        let outcome = prove_task(&task, prover);
+        if let Err(e) = &outcome {
+            warn!(job = task.id, error = %e, "Proving job failed");
+        }
+        service.finish(task.id, outcome);
+    }
+}
+
+fn prove_task(task: &Task, prover: &ProverBackend) -> anyhow::Result<(Receipt, Option<Vec<u8>>)> {
+    let mut binding = task.metadata.binding()?;
+    binding.freshness = task.freshness;
+    let ethereum = matches!(task.metadata.params.chain_config.chain, Chain::Ethereum);
+    let receipt = prove_escrow_receipt_with(
+        &task.metadata,
+        Some(&task.condition),
+        binding,
+        task.groth16 || ethereum,
+        prover,
+    )?;
+    let seal = ethereum.then(|| ethereum_seal(&receipt)).transpose()?;
+    Ok((receipt, seal))
+}
+
+/// Rejects requests without a configured API key.
+async fn require_api_key(
+    State(service): State<Arc<Service>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let headers = request.headers();
+    let key = auth::presented_key(
+        headers
+            .get(header::AUTHORIZATION)
+            .and_then(|value| value.to_str().ok()),
+        headers
+            .get(API_KEY_HEADER)
+            .and_then(|value| value.to_str().ok()),
+    );
+    match key {
+        Some(key) if service.api_keys.accepts(key) => next.run(request).await,
+        _ => ApiError::new(StatusCode::UNAUTHORIZED, "missing or invalid API key").into_response(),
+    }
+}
+
+async fn submit(State(service): State<Arc<Service>>, Json(request): Json<JobRequest>) -> ApiResult {
+    Escrow::from_metadata(request.metadata.clone(), Some(request.condition.clone()))
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e.to_string()))?;
+    request
+        .metadata
+        .binding()
+        .map_err(|e| ApiError::new(StatusCode::BAD_REQUEST, e.to_string()))?;
+    let freshness = request
+        .expires_at
+        .map_or(Freshness::NEVER, Freshness::until);
+
+    let mut jobs = service.jobs.lock().expect("jobs lock poisoned");
+    let id = jobs.next_id;
+    let task = Task {
+        id,
+        metadata: request.metadata,
+        condition: request.condition,
+        freshness,
+        groth16: request.groth16,
+    };
+    match service.queue.try_send(task) {
+        Ok(()) => {}
+        Err(TrySendError::Full(_)) => {
+            return Err(ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "the proving queue is full; retry later",
+            ))
+        }
+        Err(TrySendError::Disconnected(_)) => {
+            return Err(ApiError::new(
+                StatusCode::SERVICE_UNAVAILABLE,
+                "the proving workers have stopped",
+            ))
+        }
+    }
+    jobs.next_id += 1;
+    jobs.jobs.insert(
+        id,
+        Job {
+            status: JobStatus::Queued,
+            receipt: None,
+            seal: None,
+            error: None,
+        },
+    );
+    info!(job = id, "Queued proving job");
+    let body = Json(serde_json::json!({ "id": id.to_string() }));
+    Ok((StatusCode::ACCEPTED, body).into_response())
+}
+
+async fn status(State(service): State<Arc<Service>>, Path(id): Path<String>) -> ApiResult {
+    let jobs = service.jobs.lock().expect("jobs lock poisoned");
+    let job = find(&jobs, &id)?;
+    let hex = |bytes: &[u8]| format!("0x{}", hex::encode(bytes));
+    Ok(Json(JobView {
+        status: job.status,
+        journal: job.receipt.as_ref().map(|r| hex(&r.journal.bytes)),
+        seal: job.seal.as_deref().map(hex),
+        error: job.error.clone(),
+        id,
+    })
+    .into_response())
+}
+
+async fn receipt(State(service): State<Arc<Service>>, Path(id): Path<String>) -> ApiResult {
+    let jobs = service.jobs.lock().expect("jobs lock poisoned");
+    let receipt = find(&jobs, &id)?.receipt.as_ref().ok_or_else(|| {
+        ApiError::new(StatusCode::CONFLICT, format!("job {id} has no receipt yet"))
+    })?;
+    let bytes = bincode::serde::encode_to_vec(receipt, standard())
+        .map_err(|e| ApiError::new(StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(([(header::CONTENT_TYPE, "application/octet-stream")], bytes).into_response())
+}
+
+fn find<'a>(jobs: &'a Jobs, id: &str) -> Result<&'a Job, ApiError> {
+    id.parse()
+        .ok()
+        .and_then(|id| jobs.jobs.get(&id))
+        .ok_or_else(|| ApiError::new(StatusCode::NOT_FOUND, format!("no job {id}")))
+}
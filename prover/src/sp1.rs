@@ -0,0 +1,113 @@
+//! SP1 backend.
+//!
+//! The SP1 prover is selected from the environment (`SP1_PROVER`: `cpu`,
+//! `cuda`, or `network`), like the SP1 SDK does. Its proofs are
+//! bincode-encoded `SP1ProofWithPublicValues`.
+
+use anyhow::Context;
+use bincode::config::standard;
+use sp1_sdk::{
+    EnvProver, HashableKey, ProverClient, SP1ProofWithPublicValues, SP1ProvingKey, SP1Stdin,
+    SP1VerifyingKey,
+};
+use tracing::info;
+use zescrow_core::interface::{EscrowBinding, Journal};
+use zescrow_core::{Condition, EscrowMetadata};
+use zescrow_sp1_methods::ZESCROW_SP1_GUEST_ELF;
+
+use crate::backend::{ZkBackend, ZkProof};
+use crate::{
+    decode_journal, encode_binding, encode_escrow, encode_report_satisfied, validate_journal,
+    ProverError,
+};
+
+/// The SP1 zkVM.
+pub struct Sp1Backend {
+    client: EnvProver,
+    proving_key: SP1ProvingKey,
+    verifying_key: SP1VerifyingKey,
+    groth16: bool,
+    report_satisfied: bool,
+}
+
+impl Sp1Backend {
+    /// Sets up the SP1 prover the environment selects for the guest
+    /// program.
+    pub fn from_env() -> Self {
+        let client = ProverClient::from_env();
+        let (proving_key, verifying_key) = client.setup(ZESCROW_SP1_GUEST_ELF);
+        Self {
+            client,
+            proving_key,
+            verifying_key,
+            groth16: false,
+            report_satisfied: false,
+        }
+    }
+
+    /// Wraps proofs in Groth16, e.g. for on-chain verification.
+    pub fn groth16(mut self, groth16: bool) -> Self {
+        self.groth16 = groth16;
+        self
+    }
+
+    /// Commits the paths of the satisfied subconditions to the journal.
+    pub fn report_satisfied(mut self, report_satisfied: bool) -> Self {
+        self.report_satisfied = report_satisfied;
+        self
+    }
+}
+
+impl ZkBackend for Sp1Backend {
+    fn name(&self) -> &'static str {
+        "sp1"
+    }
+
+    fn program_id(&self) -> String {
+        self.verifying_key.bytes32()
+    }
+
+    fn prove(
+        &self,
+        metadata: &EscrowMetadata,
+        condition: Option<&Condition>,
+        binding: EscrowBinding,
+    ) -> anyhow::Result<ZkProof> {
+        let mut stdin = SP1Stdin::new();
+        stdin.write_vec(encode_escrow(metadata, condition)?);
+        stdin.write_vec(encode_binding(binding)?);
+        stdin.write_vec(encode_report_satisfied(self.report_satisfied)?);
+
+        info!(groth16 = self.groth16, "Starting SP1 proof generation");
+        let prove = self.client.prove(&self.proving_key, &stdin);
+        let proof = if self.groth16 {
+            prove.groth16().run()
+        } else {
+            prove.compressed().run()
+        }
+        .with_context(|| "SP1 proof generation failed")?;
+        self.client
+            .verify(&proof, &self.verifying_key)
+            .map_err(|e| ProverError::VerifyReceipt(e.to_string()))?;
+
+        let journal = proof.public_values.to_vec();
+        validate_journal(&journal, binding)?;
+        Ok(ZkProof {
+            backend: self.name(),
+            journal,
+            proof: bincode::serde::encode_to_vec(&proof, standard())
+                .with_context(|| "failed to encode SP1 proof")?,
+        })
+    }
+
+    fn verify(&self, proof: &ZkProof) -> anyhow::Result<Journal> {
+        proof.check_backend(self)?;
+        let (proof, _): (SP1ProofWithPublicValues, _) =
+            bincode::serde::decode_from_slice(&proof.proof, standard())
+                .with_context(|| "failed to decode SP1 proof")?;
+        self.client
+            .verify(&proof, &self.verifying_key)
+            .map_err(|e| ProverError::VerifyReceipt(e.to_string()))?;
+        decode_journal(proof.public_values.as_slice())
+    }
+}